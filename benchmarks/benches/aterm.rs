@@ -0,0 +1,61 @@
+use std::hint::black_box;
+
+use criterion::Criterion;
+use criterion::criterion_group;
+use criterion::criterion_main;
+
+use merc_aterm::ATerm;
+use merc_aterm::Symbol;
+use merc_aterm::Term;
+use merc_aterm::storage::THREAD_TERM_POOL;
+
+/// Creates the term f_depth, where f_0 = c and f_i = f(f_{i-1}, f_{i-1}).
+fn create_nested_function(depth: usize) -> ATerm {
+    let f_symbol = Symbol::new("f", 2);
+    let c_symbol = Symbol::new("c", 0);
+
+    let mut f_term = ATerm::constant(&c_symbol);
+    for _ in 0..depth {
+        f_term.replace(ATerm::with_args(&f_symbol, &[f_term.copy(), f_term.copy()]));
+    }
+
+    f_term
+}
+
+/// Creates the terms f(c_i, c_i) for all i < size, which are garbage immediately after creation.
+fn create_garbage(size: usize) {
+    let f_symbol = Symbol::new("f", 2);
+
+    for i in 0..size {
+        let c_term = ATerm::constant(&Symbol::new(format!("c{i}"), 0));
+        black_box(ATerm::with_args(&f_symbol, &[c_term.copy(), c_term.copy()]));
+    }
+}
+
+/// Measures the creation of terms, with and without automatic garbage collection.
+pub fn criterion_benchmark_aterm(c: &mut Criterion) {
+    const SIZE: usize = 100_000;
+
+    for garbage_collection in [false, true] {
+        THREAD_TERM_POOL.with_borrow(|tp| tp.automatic_garbage_collection(garbage_collection));
+
+        c.bench_function(&format!("aterm creation gc={garbage_collection}"), |bencher| {
+            bencher.iter(|| {
+                black_box(create_nested_function(SIZE));
+            })
+        });
+
+        c.bench_function(&format!("aterm garbage gc={garbage_collection}"), |bencher| {
+            bencher.iter(|| {
+                create_garbage(SIZE);
+            })
+        });
+    }
+}
+
+criterion_group!(
+    name = benches;
+    config = Criterion::default().sample_size(10);
+    targets = criterion_benchmark_aterm
+);
+criterion_main!(benches);
@@ -0,0 +1,66 @@
+use std::fs::File;
+use std::hint::black_box;
+
+use criterion::Criterion;
+use criterion::criterion_group;
+use criterion::criterion_main;
+
+use merc_benchmarks::BenchmarkSuite;
+use merc_benchmarks::input_name;
+use merc_lts::LabelledTransitionSystem;
+use merc_lts::random_lts;
+use merc_lts::read_aut;
+use merc_reduction::Equivalence;
+use merc_reduction::reduce_lts;
+use merc_utilities::Timing;
+
+/// Measures the reduction of the given LTS modulo strong and branching bisimulation.
+fn bench_reduction(c: &mut Criterion, name: &str, lts: &LabelledTransitionSystem<String>) {
+    for equivalence in [Equivalence::StrongBisim, Equivalence::BranchingBisim] {
+        c.bench_function(&format!("{equivalence:?} {name}"), |bencher| {
+            bencher.iter(|| {
+                let mut timing = Timing::default();
+                black_box(reduce_lts(lts.clone(), equivalence, &mut timing).unwrap());
+            })
+        });
+    }
+}
+
+/// Measures the reduction of the given LTS modulo weak bisimulation, comparing
+/// the on-demand marking of [merc_reduction::weak_bisimulation] with the
+/// saturated signatures of [merc_reduction::weak_bisim_sigref_naive].
+fn bench_weak_reduction(c: &mut Criterion, name: &str, lts: &LabelledTransitionSystem<String>) {
+    for equivalence in [Equivalence::WeakBisim, Equivalence::WeakBisimSigref] {
+        c.bench_function(&format!("{equivalence:?} {name}"), |bencher| {
+            bencher.iter(|| {
+                let mut timing = Timing::default();
+                black_box(reduce_lts(lts.clone(), equivalence, &mut timing).unwrap());
+            })
+        });
+    }
+}
+
+/// Measures the bisimulation reductions on a random LTS and on the models of
+/// [BenchmarkSuite::Vlts].
+pub fn criterion_benchmark_reduction(c: &mut Criterion) {
+    let mut rng = rand::rng();
+    let lts = random_lts(&mut rng, 10_000, 10, 5);
+    bench_reduction(c, "random 10000", &lts);
+
+    // With few labels a large fraction of the transitions is hidden, for which
+    // saturating the weak transitions is expensive.
+    let lts = random_lts(&mut rng, 1_000, 2, 5);
+    bench_weak_reduction(c, "tau-dense random 1000", &lts);
+
+    for path in BenchmarkSuite::Vlts.inputs() {
+        let lts = read_aut(File::open(&path).unwrap(), Vec::new()).unwrap();
+        bench_reduction(c, &input_name(&path), &lts);
+    }
+}
+
+criterion_group!(
+    name = benches;
+    config = Criterion::default().sample_size(10);
+    targets = criterion_benchmark_reduction
+);
+criterion_main!(benches);
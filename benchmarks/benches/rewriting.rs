@@ -0,0 +1,70 @@
+use std::hint::black_box;
+
+use criterion::BatchSize;
+use criterion::Criterion;
+use criterion::criterion_group;
+use criterion::criterion_main;
+
+use merc_benchmarks::BenchmarkSuite;
+use merc_benchmarks::input_name;
+use merc_data::DataExpression;
+use merc_data::to_untyped_data_expression;
+use merc_rec_tests::load_rec_from_file;
+use merc_sabre::InnermostRewriter;
+use merc_sabre::NaiveRewriter;
+use merc_sabre::RewriteEngine;
+use merc_sabre::RewriteSpecification;
+use merc_sabre::SabreRewriter;
+
+/// Rewrites all the terms to normal form using the given rewriter.
+fn rewrite_all(rewriter: &mut impl RewriteEngine, terms: &[DataExpression]) {
+    for term in terms {
+        black_box(rewriter.rewrite(term));
+    }
+}
+
+/// Measures the naive, innermost and Sabre rewriters on the REC specifications
+/// of [BenchmarkSuite::Rec], where the innermost rewriter is also measured with
+/// a normal form cache.
+pub fn criterion_benchmark_rewriting(c: &mut Criterion) {
+    for path in BenchmarkSuite::Rec.inputs() {
+        let name = input_name(&path);
+        let (syntax_spec, syntax_terms) = load_rec_from_file(path).unwrap();
+        let spec: RewriteSpecification = syntax_spec.to_rewrite_spec();
+        let terms: Vec<DataExpression> = syntax_terms
+            .into_iter()
+            .map(|term| to_untyped_data_expression(term, None))
+            .collect();
+
+        c.bench_function(&format!("naive {name}"), |bencher| {
+            let mut rewriter = NaiveRewriter::new(&spec);
+            bencher.iter(|| rewrite_all(&mut rewriter, &terms))
+        });
+
+        c.bench_function(&format!("innermost {name}"), |bencher| {
+            let mut rewriter = InnermostRewriter::new(&spec);
+            bencher.iter(|| rewrite_all(&mut rewriter, &terms))
+        });
+
+        // A new rewriter is used for every iteration, since the cache would otherwise contain every normal form.
+        c.bench_function(&format!("innermost cached {name}"), |bencher| {
+            bencher.iter_batched(
+                || InnermostRewriter::new(&spec).with_cache(100_000),
+                |mut rewriter| rewrite_all(&mut rewriter, &terms),
+                BatchSize::LargeInput,
+            )
+        });
+
+        c.bench_function(&format!("sabre {name}"), |bencher| {
+            let mut rewriter = SabreRewriter::new(&spec);
+            bencher.iter(|| rewrite_all(&mut rewriter, &terms))
+        });
+    }
+}
+
+criterion_group!(
+    name = benches;
+    config = Criterion::default().sample_size(10);
+    targets = criterion_benchmark_rewriting
+);
+criterion_main!(benches);
@@ -0,0 +1,44 @@
+use std::fs::File;
+use std::hint::black_box;
+
+use criterion::Criterion;
+use criterion::criterion_group;
+use criterion::criterion_main;
+
+use merc_benchmarks::BenchmarkSuite;
+use merc_benchmarks::input_name;
+use merc_vpg::random_parity_game;
+use merc_vpg::read_pg;
+use merc_vpg::solve_zielonka;
+
+/// Measures Zielonka's algorithm on random parity games and on the games of
+/// [BenchmarkSuite::ParityGames].
+pub fn criterion_benchmark_solving(c: &mut Criterion) {
+    let mut rng = rand::rng();
+    for num_of_vertices in [1_000, 10_000, 100_000] {
+        let game = random_parity_game(&mut rng, true, num_of_vertices, 10, 5);
+
+        c.bench_function(&format!("zielonka random {num_of_vertices}"), |bencher| {
+            bencher.iter(|| {
+                black_box(solve_zielonka(&game).unwrap());
+            })
+        });
+    }
+
+    for path in BenchmarkSuite::ParityGames.inputs() {
+        let game = read_pg(File::open(&path).unwrap()).unwrap();
+
+        c.bench_function(&format!("zielonka {}", input_name(&path)), |bencher| {
+            bencher.iter(|| {
+                black_box(solve_zielonka(&game).unwrap());
+            })
+        });
+    }
+}
+
+criterion_group!(
+    name = benches;
+    config = Criterion::default().sample_size(10);
+    targets = criterion_benchmark_solving
+);
+criterion_main!(benches);
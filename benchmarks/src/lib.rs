@@ -0,0 +1,117 @@
+//!
+//! The benchmark suite of merc, which combines the criterion benchmarks for
+//! term creation, rewriting, reduction and solving. This crate locates the
+//! benchmark inputs in a consistent way, see [BenchmarkSuite].
+//!
+//! The inputs that are not part of the repository can be downloaded and checked
+//! by `cargo xtask benchmark-inputs`.
+//!
+
+#![forbid(unsafe_code)]
+
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+
+/// The REC specifications in `examples/REC/rec` that are used when no other
+/// directory is given, chosen such that all rewriters finish within a second.
+pub const REC_BENCHMARKS: [&str; 10] = [
+    "benchexpr10",
+    "benchsym10",
+    "bubblesort20",
+    "factorial5",
+    "fibonacci05",
+    "hanoi4",
+    "mergesort10",
+    "quicksort10",
+    "sieve100",
+    "tak18",
+];
+
+/// A set of benchmark inputs. The inputs are read from the directory given by
+/// the environment variable of the suite, or otherwise from its default
+/// directory.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BenchmarkSuite {
+    /// REC specifications for the rewriters.
+    Rec,
+    /// `.aut` files of the VLTS benchmark suite for the reductions.
+    Vlts,
+    /// `.pg` files of parity games for the solvers.
+    ParityGames,
+}
+
+impl BenchmarkSuite {
+    /// Returns the environment variable that overrides the directory of the inputs.
+    pub fn variable(&self) -> &'static str {
+        match self {
+            BenchmarkSuite::Rec => "MERC_REC_BENCHMARKS",
+            BenchmarkSuite::Vlts => "MERC_VLTS_BENCHMARKS",
+            BenchmarkSuite::ParityGames => "MERC_PG_BENCHMARKS",
+        }
+    }
+
+    /// Returns the extension of the input files.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            BenchmarkSuite::Rec => "rec",
+            BenchmarkSuite::Vlts => "aut",
+            BenchmarkSuite::ParityGames => "pg",
+        }
+    }
+
+    /// Returns the directory that is used when the environment variable is not set.
+    pub fn default_directory(&self) -> PathBuf {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("..");
+        match self {
+            BenchmarkSuite::Rec => root.join("examples").join("REC").join("rec"),
+            BenchmarkSuite::Vlts => root.join("target").join("benchmarks").join("vlts"),
+            BenchmarkSuite::ParityGames => root.join("target").join("benchmarks").join("pg"),
+        }
+    }
+
+    /// Returns the directory that contains the inputs.
+    pub fn directory(&self) -> PathBuf {
+        env::var(self.variable())
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| self.default_directory())
+    }
+
+    /// Returns the input files of this suite sorted by name, which is empty
+    /// when the directory does not exist.
+    ///
+    /// # Details
+    ///
+    /// The default directory of the REC suite contains the whole REC benchmark
+    /// set, including libraries and long running specifications, so only the
+    /// specifications in [REC_BENCHMARKS] are used from there.
+    pub fn inputs(&self) -> Vec<PathBuf> {
+        if *self == BenchmarkSuite::Rec && env::var(self.variable()).is_err() {
+            let directory = self.default_directory();
+            return REC_BENCHMARKS
+                .iter()
+                .map(|name| directory.join(format!("{name}.rec")))
+                .collect();
+        }
+
+        let Ok(entries) = fs::read_dir(self.directory()) else {
+            return Vec::new();
+        };
+
+        let mut paths: Vec<_> = entries
+            .map(|entry| entry.expect("Failed to read directory entry").path())
+            .filter(|path| path.extension().is_some_and(|extension| extension == self.extension()))
+            .collect();
+        paths.sort();
+        paths
+    }
+}
+
+/// Returns the name of a benchmark input, which is its file name without the extension.
+pub fn input_name(path: &Path) -> String {
+    path.file_stem()
+        .expect("Benchmark inputs are files")
+        .to_string_lossy()
+        .to_string()
+}
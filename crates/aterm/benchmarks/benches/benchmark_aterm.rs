@@ -77,6 +77,28 @@ fn create_nested_function<const ARITY: usize>(function_name: &str, leaf_name: &s
     f_term
 }
 
+/// Same as [create_nested_function], but creates all terms using a single [merc_aterm::storage::TermFactory].
+fn create_nested_function_factory<const ARITY: usize>(function_name: &str, leaf_name: &str, depth: usize) -> ATerm {
+    debug_assert!(depth > 0, "Depth must be greater than 0");
+
+    let f_symbol = Symbol::new(function_name, 2);
+    let c_symbol = Symbol::new(leaf_name, 0);
+
+    THREAD_TERM_POOL.with_borrow(|tp| {
+        let factory = tp.factory();
+
+        let c_term = factory.create_constant(&c_symbol);
+        let mut f_term = factory.create_term(&f_symbol, &from_fn::<_, ARITY, _>(|_| c_term.copy()));
+
+        for _ in 0..depth {
+            let next = factory.create_term(&f_symbol, &from_fn::<_, ARITY, _>(|_| f_term.copy()));
+            f_term = next;
+        }
+
+        f_term
+    })
+}
+
 // In these three benchmarks all threads operate on a shared term.
 fn benchmark_shared_creation(c: &mut Criterion) {
     const SIZE: usize = 400000;
@@ -94,6 +116,34 @@ fn benchmark_shared_creation(c: &mut Criterion) {
     }
 }
 
+fn benchmark_factory_creation(c: &mut Criterion) {
+    const SIZE: usize = 400000;
+
+    THREAD_TERM_POOL.with_borrow(|tp| tp.automatic_garbage_collection(false));
+
+    for num_threads in THREADS {
+        c.bench_function(&format!("shared_factory_creation_{}", num_threads), |b| {
+            b.iter(|| {
+                benchmark_threads(num_threads, |_id| {
+                    black_box(create_nested_function_factory::<2>("f", "c", SIZE));
+                });
+            });
+        });
+
+        c.bench_function(&format!("unique_factory_creation_{}", num_threads), |b| {
+            b.iter(|| {
+                benchmark_threads(num_threads, move |id| {
+                    black_box(create_nested_function_factory::<2>(
+                        "f",
+                        &format!("c{}", id),
+                        SIZE / num_threads,
+                    ));
+                });
+            });
+        });
+    }
+}
+
 /// Local function to count the number of subterms in a term.
 fn inspect<'a>(term: &'a ATermRef<'a>, iterations: usize) -> u64 {
     let mut queue: VecDeque<ATermRef<'a>> = VecDeque::new();
@@ -241,6 +291,7 @@ criterion_group!(
     config = Criterion::default().sample_size(10);
     targets = benchmark_shared_creation,
         benchmark_unique_creation,
+        benchmark_factory_creation,
         benchmark_shared_inspect,
         benchmark_unique_inspect,
         benchmark_shared_lookup,
@@ -1,5 +1,4 @@
 use std::borrow::Borrow;
-use std::cell::UnsafeCell;
 use std::cmp::Ordering;
 use std::collections::VecDeque;
 use std::fmt;
@@ -28,8 +27,8 @@ use crate::is_list_term;
 use crate::storage::GlobalTermPool;
 use crate::storage::Marker;
 use crate::storage::SharedTerm;
-use crate::storage::SharedTermProtection;
 use crate::storage::THREAD_TERM_POOL;
+use crate::storage::ThreadProtection;
 
 /// The ATerm trait represents a first-order term in the ATerm library.
 /// It provides methods to manipulate and access the term's properties.
@@ -422,7 +421,7 @@ pub struct ATermSend {
     root: ProtectionIndex,
 
     /// A shared reference to the protection set that this term was created in.
-    protection_set: Arc<UnsafeCell<SharedTermProtection>>,
+    protection_set: Arc<ThreadProtection>,
 }
 
 unsafe impl Send for ATermSend {}
@@ -448,11 +447,17 @@ impl ATermSend {
 impl Drop for ATermSend {
     fn drop(&mut self) {
         THREAD_TERM_POOL.with_borrow(|tp| {
-            let _guard = tp.term_pool().read_recursive().expect("Lock poisoned!");
-
-            unsafe { &mut *self.protection_set.get() }
-                .protection_set
-                .unprotect(self.root);
+            if Arc::ptr_eq(tp.get_protection_set(), &self.protection_set) {
+                let _guard = tp.term_pool().read_recursive().expect("Lock poisoned!");
+
+                // SAFETY: This thread owns the protection set and holds a read lock on the global term pool.
+                unsafe { &mut *self.protection_set.get() }
+                    .protection_set
+                    .unprotect(self.root);
+            } else {
+                // Modifying the protection set of another thread would race with its owner.
+                self.protection_set.defer_unprotect(self.root);
+            }
         });
     }
 }
@@ -347,16 +347,14 @@ impl<R: Read> BinaryATermReader<R> {
 
         // Read the binary aterm format header
         if stream.read_bits(8)? != 0 || stream.read_bits(16)? != BAF_MAGIC as u64 {
-            return Err(Error::new(ErrorKind::InvalidData, "Missing BAF_MAGIC control sequence").into());
+            return Err(MercError::format("Missing BAF_MAGIC control sequence"));
         }
 
         let version = stream.read_bits(16)?;
         if version != BAF_VERSION as u64 {
-            return Err(Error::new(
-                ErrorKind::InvalidData,
-                format!("BAF version ({version}) incompatible with expected version ({BAF_VERSION})"),
-            )
-            .into());
+            return Err(MercError::format(format!(
+                "BAF version ({version}) incompatible with expected version ({BAF_VERSION})"
+            )));
         }
 
         // The term with function symbol index 0 indicates the end of the stream
@@ -396,9 +394,10 @@ impl<R: Read> BinaryATermReader<R> {
     /// Returns the current bit width needed to encode a term index.
     ///
     /// In debug builds, this asserts that the cached width equals the
-    /// computed width based on the current number of terms.
+    /// computed width based on the current number of terms, where the width
+    /// is one before any term has been read.
     fn term_index_width(&self) -> u8 {
-        let expected = bits_for_value(self.terms.read().len());
+        let expected = bits_for_value(self.terms.read().len().max(1));
         debug_assert_eq!(
             self.term_index_width, expected,
             "term_index_width does not match bits_for_value",
@@ -449,10 +448,12 @@ impl<R: Read> ATermRead for BinaryATermReader<R> {
                     }
 
                     let symbols = self.function_symbols.read();
-                    let symbol = symbols.get(symbol_index).ok_or(format!(
-                        "Read invalid function symbol index {symbol_index}, length {}",
-                        symbols.len()
-                    ))?;
+                    let symbol = symbols.get(symbol_index).ok_or_else(|| {
+                        MercError::format(format!(
+                            "Read invalid function symbol index {symbol_index}, length {}",
+                            symbols.len()
+                        ))
+                    })?;
 
                     if is_int_symbol(symbol) {
                         let value = self.stream.read_integer()?.try_into()?;
@@ -467,16 +468,17 @@ impl<R: Read> ATermRead for BinaryATermReader<R> {
                         // When the arity is zero, no bits are read for the arguments.
                         let num_of_bits = if symbol.arity() > 0 { self.term_index_width() } else { 0 };
                         let mut write_terms = self.terms.write();
-                        for _ in 0..symbol.arity() {}
 
                         let term = ATerm::try_with_iter(
                             symbol,
                             (0..symbol.arity()).map(|_| {
                                 let arg_index = self.stream.read_bits(num_of_bits)? as usize;
-                                let arg = write_terms.get(arg_index).ok_or(format!(
-                                    "Read invalid aterm index {arg_index}, length {}",
-                                    write_terms.len()
-                                ))?;
+                                let arg = write_terms.get(arg_index).ok_or_else(|| {
+                                    MercError::format(format!(
+                                        "Read invalid aterm index {arg_index}, length {}",
+                                        write_terms.len()
+                                    ))
+                                })?;
                                 debug_trace!("Read arg: {arg}");
                                 Ok(arg)
                             }),
@@ -508,7 +510,7 @@ impl<R: Read> ATermRead for BinaryATermReader<R> {
 
         let number_of_elements: ATermInt = self
             .read_aterm()?
-            .ok_or("Missing number of elements for iterator")?
+            .ok_or_else(|| MercError::format("Missing number of elements for iterator"))?
             .into();
         Ok(ATermReadIter {
             reader: self,
@@ -567,6 +569,7 @@ impl<'a, R: Read> ExactSizeIterator for ATermReadIter<'a, R> {
 #[cfg(test)]
 mod tests {
     use merc_utilities::random_test;
+    use rand::Rng;
 
     use crate::random_term;
 
@@ -628,4 +631,31 @@ mod tests {
             }
         });
     }
+
+    #[test]
+    #[cfg_attr(miri, ignore)] // Miri is too slow
+    fn test_corrupted_binary_stream() {
+        random_test(100, |rng| {
+            let input: Vec<_> = (0..20)
+                .map(|_| random_term(rng, &[("f".into(), 2), ("g".into(), 1)], &["a".into(), "b".into()], 1))
+                .collect();
+
+            let mut stream: Vec<u8> = Vec::new();
+
+            let mut output_stream = BinaryATermWriter::new(&mut stream).unwrap();
+            for term in &input {
+                output_stream.write_aterm(term).unwrap();
+            }
+            ATermWrite::flush(&mut output_stream).expect("Flushing the output to the stream");
+            drop(output_stream); // Explicitly drop to release the mutable borrow
+
+            // Corrupt a byte after the header and truncate the stream, which must result in an error instead of a panic.
+            let position = rng.random_range(5..stream.len());
+            stream[position] = rng.random();
+            stream.truncate(rng.random_range(position..=stream.len()));
+
+            let mut input_stream = BinaryATermReader::new(&stream[..]).unwrap();
+            while let Ok(Some(_term)) = input_stream.read_aterm() {}
+        });
+    }
 }
@@ -4,8 +4,11 @@ use std::fmt;
 
 use merc_utilities::MercError;
 use merc_utilities::debug_trace;
+use rustc_hash::FxHashMap;
 
 use crate::ATerm;
+use crate::ATermIndex;
+use crate::ATermRef;
 use crate::Symbol;
 use crate::Term;
 use crate::storage::ThreadTermPool;
@@ -49,6 +52,53 @@ where
         .unwrap()
 }
 
+/// Computes a result for the given term bottom-up, where `function(t, results)`
+/// computes the result for the subterm `t` from the `results` of its arguments.
+///
+/// # Details
+///
+/// Since terms are maximally shared, the results are memoized per
+/// [ATermIndex], so every distinct subterm is folded only once and repeated
+/// subterms reuse the cached result. Similar to [TermBuilder::evaluate] the
+/// traversal does not use recursion to avoid stack overflows on deep terms.
+pub fn fold_term<'a, 'b, T, F>(term: &'b impl Term<'a, 'b>, mut function: F) -> Result<T, MercError>
+where
+    T: Clone,
+    F: FnMut(&ATermRef<'a>, &[T]) -> Result<T, MercError>,
+{
+    let mut results: FxHashMap<ATermIndex, T> = FxHashMap::default();
+    let mut stack: Vec<(ATermRef<'a>, bool)> = vec![(term.copy(), false)];
+
+    // The results of the arguments, reused for every subterm.
+    let mut arguments: Vec<T> = Vec::new();
+
+    while let Some((current, expanded)) = stack.pop() {
+        if results.contains_key(current.shared()) {
+            continue;
+        }
+
+        if !expanded {
+            // Fold the arguments before the term itself.
+            stack.push((current.copy(), true));
+            for arg in current.arguments() {
+                if !results.contains_key(arg.shared()) {
+                    stack.push((arg, false));
+                }
+            }
+        } else {
+            arguments.clear();
+            arguments.extend(current.arguments().map(|arg| results[arg.shared()].clone()));
+
+            let result = function(&current, &arguments)?;
+            results.insert(current.shared().copy(), result);
+        }
+    }
+
+    Ok(results
+        .remove(term.shared())
+        .expect("The result for the input term has been computed"))
+}
+
 impl<I: fmt::Debug, C: fmt::Debug> TermBuilder<I, C> {
     pub fn new() -> TermBuilder<I, C> {
         TermBuilder {
@@ -206,3 +256,26 @@ impl<I: fmt::Debug, C: fmt::Debug> fmt::Debug for Config<I, C> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fold_term() {
+        let _ = merc_utilities::test_logger();
+
+        let term = ATerm::from_string("f(g(a), g(a), h(g(a), b))").unwrap();
+
+        // Compute the size of the term tree, where the shared subterm g(a) is only folded once.
+        let mut calls = 0;
+        let size = fold_term(&term, |_t, args: &[usize]| {
+            calls += 1;
+            Ok(1 + args.iter().sum::<usize>())
+        })
+        .unwrap();
+
+        assert_eq!(size, 9);
+        assert_eq!(calls, 5, "Every distinct subterm should be folded exactly once");
+    }
+}
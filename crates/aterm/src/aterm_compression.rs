@@ -0,0 +1,162 @@
+#![forbid(unsafe_code)]
+
+//! Transparent (zstd) compression for binary aterm streams.
+//!
+//! A compressed binary aterm stream is a regular binary aterm stream wrapped
+//! in a single zstd frame, such that the files can also be inspected and
+//! decompressed with the standard `zstd` command line tool. The first byte of
+//! an uncompressed binary aterm stream is always zero, whereas a zstd frame
+//! starts with a non-zero magic number, which serves as the header flag to
+//! distinguish both cases.
+
+use std::io;
+use std::io::BufRead;
+use std::io::Read;
+#[cfg(feature = "zstd")]
+use std::io::Write;
+
+use merc_utilities::MercError;
+
+/// The magic number at the start of every zstd frame, in little endian.
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+/// The default compression level used by [`compressed_writer`], chosen to favour speed.
+#[cfg(feature = "zstd")]
+pub const DEFAULT_COMPRESSION_LEVEL: i32 = 3;
+
+/// Returns true iff the given bytes start with the zstd frame magic number.
+pub fn is_zstd_compressed(bytes: &[u8]) -> bool {
+    bytes.starts_with(&ZSTD_MAGIC)
+}
+
+/// A reader that transparently decompresses zstd compressed input, and passes
+/// through uncompressed input unchanged.
+pub enum DecompressingReader<R: BufRead> {
+    Plain(R),
+    #[cfg(feature = "zstd")]
+    Zstd(Box<zstd::stream::read::Decoder<'static, R>>),
+}
+
+impl<R: BufRead> DecompressingReader<R> {
+    /// Inspects the start of the given reader to determine whether the input is compressed.
+    pub fn new(mut reader: R) -> Result<Self, MercError> {
+        // The frame magic is small enough to always be part of the first buffer, unless the input is shorter.
+        let compressed = is_zstd_compressed(reader.fill_buf()?);
+
+        if compressed {
+            #[cfg(feature = "zstd")]
+            return Ok(DecompressingReader::Zstd(Box::new(
+                zstd::stream::read::Decoder::with_buffer(reader)?,
+            )));
+
+            #[cfg(not(feature = "zstd"))]
+            return Err(MercError::unsupported(
+                "The input is zstd compressed, but the toolset was compiled without the 'zstd' feature",
+            ));
+        }
+
+        Ok(DecompressingReader::Plain(reader))
+    }
+
+    /// Returns true iff the underlying input is compressed.
+    pub fn is_compressed(&self) -> bool {
+        match self {
+            DecompressingReader::Plain(_) => false,
+            #[cfg(feature = "zstd")]
+            DecompressingReader::Zstd(_) => true,
+        }
+    }
+}
+
+impl<R: BufRead> Read for DecompressingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            DecompressingReader::Plain(reader) => reader.read(buf),
+            #[cfg(feature = "zstd")]
+            DecompressingReader::Zstd(reader) => reader.read(buf),
+        }
+    }
+}
+
+/// Returns a writer that compresses everything written to it into a single
+/// zstd frame. The frame must be completed by calling
+/// [`zstd::stream::write::Encoder::finish`], which reports any errors that
+/// occur while writing the end of the frame.
+#[cfg(feature = "zstd")]
+pub fn compressed_writer<W: Write>(
+    writer: W,
+    level: i32,
+) -> Result<zstd::stream::write::Encoder<'static, W>, MercError> {
+    Ok(zstd::stream::write::Encoder::new(writer, level)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use merc_utilities::random_test;
+
+    use crate::ATermRead;
+    use crate::ATermWrite;
+    use crate::BinaryATermReader;
+    use crate::BinaryATermWriter;
+    use crate::random_term;
+
+    use super::*;
+
+    #[test]
+    #[cfg_attr(miri, ignore)] // Miri is too slow
+    fn test_uncompressed_passthrough() {
+        random_test(10, |rng| {
+            let input: Vec<_> = (0..20)
+                .map(|_| random_term(rng, &[("f".into(), 2), ("g".into(), 1)], &["a".into(), "b".into()], 1))
+                .collect();
+
+            let mut stream: Vec<u8> = Vec::new();
+            {
+                let mut writer = BinaryATermWriter::new(&mut stream).unwrap();
+                for term in &input {
+                    writer.write_aterm(term).unwrap();
+                }
+                ATermWrite::flush(&mut writer).unwrap();
+            }
+
+            let reader = DecompressingReader::new(&stream[..]).unwrap();
+            assert!(!reader.is_compressed());
+
+            let mut reader = BinaryATermReader::new(reader).unwrap();
+            for term in &input {
+                assert_eq!(*term, reader.read_aterm().unwrap().unwrap());
+            }
+        });
+    }
+
+    #[test]
+    #[cfg(feature = "zstd")]
+    #[cfg_attr(miri, ignore)] // Miri does not support the zstd C library
+    fn test_random_compressed_stream() {
+        random_test(10, |rng| {
+            let input: Vec<_> = (0..20)
+                .map(|_| random_term(rng, &[("f".into(), 2), ("g".into(), 1)], &["a".into(), "b".into()], 1))
+                .collect();
+
+            let mut stream: Vec<u8> = Vec::new();
+            let mut encoder = compressed_writer(&mut stream, DEFAULT_COMPRESSION_LEVEL).unwrap();
+            {
+                let mut writer = BinaryATermWriter::new(&mut encoder).unwrap();
+                for term in &input {
+                    writer.write_aterm(term).unwrap();
+                }
+                ATermWrite::flush(&mut writer).unwrap();
+            }
+            encoder.finish().unwrap();
+
+            assert!(is_zstd_compressed(&stream));
+            let reader = DecompressingReader::new(&stream[..]).unwrap();
+            assert!(reader.is_compressed());
+
+            let mut reader = BinaryATermReader::new(reader).unwrap();
+            for term in &input {
+                assert_eq!(*term, reader.read_aterm().unwrap().unwrap());
+            }
+        });
+    }
+}
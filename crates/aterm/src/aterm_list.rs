@@ -3,6 +3,7 @@
 //!
 #![forbid(unsafe_code)]
 
+use std::cell::OnceCell;
 use std::fmt;
 use std::marker::PhantomData;
 
@@ -36,9 +37,11 @@ pub fn is_empty_list_term<'a, 'b>(t: &'b impl Term<'a, 'b>) -> bool {
 /// Internally, uses two standard function symbols `cons` and `[]` to represent
 /// lists. The `cons` function symbol has arity 2, where the first argument is
 /// the head of the list and the second argument is the tail of the list. The
-/// `[]` function symbol has arity 0 and represents the empty list.
+/// `[]` function symbol has arity 0 and represents the empty list. The length
+/// of the list is computed at most once, and is cached afterwards.
 pub struct ATermList<T> {
     term: ATerm,
+    length: OnceCell<usize>,
     _marker: PhantomData<T>,
 }
 
@@ -53,6 +56,11 @@ impl<T: From<ATerm>> ATermList<T> {
     pub fn to_vec(&self) -> Vec<T> {
         self.iter().collect()
     }
+
+    /// Returns the element at the given index, or None if the index is out of bounds.
+    pub fn get(&self, index: usize) -> Option<T> {
+        self.elements().nth(index).map(|element| element.protect().into())
+    }
 }
 
 impl<T> ATermList<T> {
@@ -61,11 +69,8 @@ impl<T> ATermList<T> {
     where
         T: Into<ATerm>,
     {
-        let mut list = Self::empty();
-        for item in iter.rev() {
-            list = list.cons(item);
-        }
-        list
+        let elements: Vec<ATerm> = iter.map(|item| item.into()).collect();
+        Self::from_elements(&elements, Self::empty())
     }
 
     /// Constructs a new list from an iterator that is consumed.
@@ -73,11 +78,10 @@ impl<T> ATermList<T> {
     where
         T: Into<ATerm>,
     {
-        let mut list = Self::empty();
-        for item in iter.rev() {
-            list = list.cons(item?);
-        }
-        Ok(list)
+        let elements = iter
+            .map(|item| item.map(|item| item.into()))
+            .collect::<Result<Vec<ATerm>, _>>()?;
+        Ok(Self::from_elements(&elements, Self::empty()))
     }
 
     /// Constructs a new list with the given item as the head and the current list as the tail.
@@ -85,20 +89,20 @@ impl<T> ATermList<T> {
     where
         T: Into<ATerm>,
     {
-        ATermList {
-            term: THREAD_TERM_POOL.with_borrow(|tp| {
+        Self::with_length(
+            THREAD_TERM_POOL.with_borrow(|tp| {
                 ATerm::with_args(tp.list_symbol(), &[item.into().copy(), self.term.copy()]).protect()
             }),
-            _marker: PhantomData,
-        }
+            self.length.get().map(|length| length + 1),
+        )
     }
 
     /// Constructs the empty list.
     pub fn empty() -> Self {
-        ATermList {
-            term: THREAD_TERM_POOL.with_borrow(|tp| ATerm::constant(tp.empty_list_symbol())),
-            _marker: PhantomData,
-        }
+        Self::with_length(
+            THREAD_TERM_POOL.with_borrow(|tp| ATerm::constant(tp.empty_list_symbol())),
+            Some(0),
+        )
     }
 
     /// Returns true iff the list is empty.
@@ -106,15 +110,84 @@ impl<T> ATermList<T> {
         is_empty_list_term(&self.term)
     }
 
+    /// Returns the number of elements in the list, which takes linear time the first time it is called.
+    pub fn len(&self) -> usize {
+        *self.length.get_or_init(|| self.elements().count())
+    }
+
     /// Obtain the tail, i.e. the remainder, of the list.
     pub fn tail(&self) -> ATermList<T> {
-        self.term.arg(1).into()
+        Self::with_length(self.term.arg(1).protect(), self.length.get().map(|length| length - 1))
+    }
+
+    /// Returns the list with the elements in reverse order.
+    pub fn reverse(&self) -> Self {
+        THREAD_TERM_POOL.with_borrow(|tp| {
+            let factory = tp.factory();
+
+            // Reversing a list is exactly prepending its elements from left to right.
+            let mut result = factory.create_constant(tp.empty_list_symbol());
+            for element in self.elements() {
+                let next = factory.create_term(tp.list_symbol(), &[element, result.copy()]);
+                result = next;
+            }
+
+            Self::with_length(result, self.length.get().copied())
+        })
+    }
+
+    /// Returns the concatenation of this list followed by the given list.
+    pub fn concat(&self, other: &ATermList<T>) -> Self {
+        let elements: Vec<ATerm> = self.elements().map(|element| element.protect()).collect();
+        Self::from_elements(&elements, other.clone())
     }
 
     /// Returns an iterator over all elements in the list.
     pub fn iter(&self) -> ATermListIter<T> {
         ATermListIter { current: self.clone() }
     }
+
+    /// Returns the list with the given elements followed by the elements of
+    /// `tail`. The list is constructed right-to-left using a single [crate::storage::TermFactory].
+    fn from_elements(elements: &[ATerm], tail: ATermList<T>) -> Self {
+        let length = tail.length.get().map(|length| length + elements.len());
+
+        THREAD_TERM_POOL.with_borrow(|tp| {
+            let factory = tp.factory();
+
+            let mut result = tail.term;
+            for element in elements.iter().rev() {
+                let next = factory.create_term(tp.list_symbol(), &[element.copy(), result.copy()]);
+                result = next;
+            }
+
+            Self::with_length(result, length)
+        })
+    }
+
+    /// Returns an iterator over the (unprotected) elements of the list.
+    fn elements(&self) -> impl Iterator<Item = ATermRef<'_>> {
+        let mut current = self.term.copy();
+
+        std::iter::from_fn(move || {
+            if is_empty_list_term(&current) {
+                None
+            } else {
+                let head = current.arg(0);
+                current = current.arg(1);
+                Some(head)
+            }
+        })
+    }
+
+    /// Creates a list from the given term with an optionally known length.
+    fn with_length(term: ATerm, length: Option<usize>) -> Self {
+        ATermList {
+            term,
+            length: length.map(OnceCell::from).unwrap_or_default(),
+            _marker: PhantomData,
+        }
+    }
 }
 
 impl<'a, 'b, T> Term<'a, 'b> for ATermList<T>
@@ -140,6 +213,7 @@ impl<T> Clone for ATermList<T> {
     fn clone(&self) -> Self {
         ATermList {
             term: self.term.clone(),
+            length: self.length.clone(),
             _marker: PhantomData,
         }
     }
@@ -171,10 +245,7 @@ impl<T> From<ATerm> for ATermList<T> {
             is_list_term(&value) || is_empty_list_term(&value),
             "Can only convert an aterm_list"
         );
-        ATermList::<T> {
-            term: value,
-            _marker: PhantomData,
-        }
+        ATermList::with_length(value, None)
     }
 }
 
@@ -184,10 +255,14 @@ impl<'a, T> From<ATermRef<'a>> for ATermList<T> {
             is_list_term(&value) || is_empty_list_term(&value),
             "Can only convert an aterm_list"
         );
-        ATermList::<T> {
-            term: value.protect(),
-            _marker: PhantomData,
-        }
+        ATermList::with_length(value.protect(), None)
+    }
+}
+
+impl<T: Into<ATerm>> FromIterator<T> for ATermList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let elements: Vec<ATerm> = iter.into_iter().map(|item| item.into()).collect();
+        Self::from_elements(&elements, Self::empty())
     }
 }
 
@@ -234,4 +309,31 @@ mod tests {
         assert_eq!(list.tail().tail().head().value(), 3);
         assert!(list.tail().tail().tail().is_empty());
     }
+
+    #[test]
+    fn test_list_operations() {
+        use super::*;
+        use crate::ATermInt;
+
+        let list: ATermList<ATermInt> = (1..=3).map(ATermInt::new).collect();
+        assert_eq!(list.len(), 3);
+        assert_eq!(list.tail().len(), 2);
+        assert_eq!(list.get(1).map(|x| x.value()), Some(2));
+        assert!(list.get(3).is_none());
+
+        let reversed = list.reverse();
+        assert_eq!(reversed.iter().map(|x| x.value()).collect::<Vec<_>>(), vec![3, 2, 1]);
+
+        let concatenated = list.concat(&reversed);
+        assert_eq!(concatenated.len(), 6);
+        assert_eq!(
+            concatenated.iter().map(|x| x.value()).collect::<Vec<_>>(),
+            vec![1, 2, 3, 3, 2, 1]
+        );
+
+        // The length of a list obtained from a term is computed on demand.
+        let term: ATerm = concatenated.into();
+        assert_eq!(ATermList::<ATermInt>::from(term).len(), 6);
+        assert!(ATermList::<ATermInt>::empty().reverse().is_empty());
+    }
 }
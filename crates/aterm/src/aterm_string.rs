@@ -19,7 +19,6 @@ use crate::ATermStreamable;
 use crate::ATermWrite;
 use crate::Markable;
 use crate::Symb;
-use crate::Symbol;
 use crate::SymbolRef;
 use crate::Term;
 use crate::TermIterator;
@@ -42,17 +41,53 @@ mod inner {
     }
 
     impl ATermString {
+        /// Interns the given string in the term pool.
         #[merc_ignore]
         pub fn new(string: impl Into<String> + AsRef<str>) -> Self {
             THREAD_TERM_POOL.with_borrow(|tp| ATermString {
-                term: tp.create_constant(&Symbol::new(string, 0)),
+                term: tp.create_constant(&tp.create_symbol(string, 0)),
             })
         }
 
-        /// Get the value of the string
-        pub fn value(&self) -> &str {
+        /// Interns the given bytes in the term pool, which must be valid UTF-8.
+        #[merc_ignore]
+        pub fn from_utf8(bytes: &[u8]) -> Result<Self, MercError> {
+            let string = std::str::from_utf8(bytes)
+                .map_err(|error| MercError::format(format!("String term is not valid UTF-8: {error}")))?;
+            Ok(ATermString::new(string))
+        }
+
+        /// Interns all the given strings in the term pool, which is cheaper than
+        /// calling [ATermString::new] for every string since the thread-local
+        /// term pool is only accessed once.
+        #[merc_ignore]
+        pub fn from_strs<S: AsRef<str>>(strings: impl IntoIterator<Item = S>) -> Vec<Self> {
+            THREAD_TERM_POOL.with_borrow(|tp| {
+                strings
+                    .into_iter()
+                    .map(|string| ATermString {
+                        term: tp.create_constant(&tp.create_symbol(string.as_ref(), 0)),
+                    })
+                    .collect()
+            })
+        }
+
+        /// Returns the string, which borrows the interned name of the term and is
+        /// therefore not copied.
+        pub fn as_str(&self) -> &str {
             self.term.get_head_symbol().name()
         }
+
+        /// Get the value of the string, see [ATermString::as_str].
+        pub fn value(&self) -> &str {
+            self.as_str()
+        }
+    }
+
+    impl AsRef<str> for ATermString {
+        fn as_ref(&self) -> &str {
+            self.as_str()
+        }
     }
 
     #[merc_ignore]
@@ -93,7 +128,10 @@ impl ATermStreamable for String {
     where
         Self: Sized,
     {
-        let term: ATermString = reader.read_aterm()?.ok_or("Expected a string ATerm")?.into();
+        let term: ATermString = reader
+            .read_aterm()?
+            .ok_or_else(|| MercError::format("Expected a string ATerm"))?
+            .into();
         Ok(term.value().to_string())
     }
 }
@@ -108,6 +146,20 @@ mod tests {
 
         let s = ATermString::new("test");
         assert_eq!(s.value(), "test");
+        assert_eq!(s.as_str(), "test");
         assert_eq!(s.to_string(), "test");
     }
+
+    #[test]
+    fn test_string_interning() {
+        let _ = merc_utilities::test_logger();
+
+        let strings = ATermString::from_strs(["a", "b", "a"]);
+        assert_eq!(strings[0], ATermString::new("a"));
+        assert_eq!(strings[0], strings[2], "Equal strings are interned as the same term");
+        assert_ne!(strings[0], strings[1]);
+
+        assert_eq!(ATermString::from_utf8("ß".as_bytes()).unwrap().as_str(), "ß");
+        assert!(ATermString::from_utf8(&[0xff, 0xfe]).is_err());
+    }
 }
@@ -0,0 +1,151 @@
+#![forbid(unsafe_code)]
+
+use std::fs::File;
+use std::io::BufReader;
+use std::io::BufWriter;
+use std::io::Read;
+use std::io::Write;
+use std::path::Path;
+
+use merc_utilities::MercError;
+
+use crate::ATerm;
+use crate::ATermInt;
+use crate::ATermRead;
+use crate::ATermStreamable;
+use crate::ATermWrite;
+use crate::BinaryATermReader;
+use crate::BinaryATermWriter;
+use crate::is_int_term;
+
+/// A snapshot of the terms that are reachable from the state of a long running
+/// algorithm, together with a number of handles that describe the remaining
+/// state of that algorithm.
+///
+/// # Details
+///
+/// The terms are stored in the binary aterm format, which maximally shares
+/// subterms, and the handles are stored as integer terms. The meaning of the
+/// handles is up to the algorithm, typically they are indices into the terms
+/// or counters. Reading a checkpoint recreates the terms in the thread local
+/// term pool, after which the algorithm can be continued.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Checkpoint {
+    terms: Vec<ATerm>,
+    handles: Vec<usize>,
+}
+
+impl Checkpoint {
+    /// Creates a checkpoint of the given terms and handles.
+    pub fn new(terms: Vec<ATerm>, handles: Vec<usize>) -> Self {
+        Self { terms, handles }
+    }
+
+    /// Returns the terms stored in the checkpoint.
+    pub fn terms(&self) -> &[ATerm] {
+        &self.terms
+    }
+
+    /// Returns the handles stored in the checkpoint.
+    pub fn handles(&self) -> &[usize] {
+        &self.handles
+    }
+
+    /// Consumes the checkpoint and returns the terms and handles.
+    pub fn into_parts(self) -> (Vec<ATerm>, Vec<usize>) {
+        (self.terms, self.handles)
+    }
+
+    /// Writes the checkpoint in the binary aterm format to the given writer.
+    pub fn write_to(&self, writer: impl Write) -> Result<(), MercError> {
+        let mut stream = BinaryATermWriter::new(writer)?;
+        ATermStreamable::write(self, &mut stream)?;
+        ATermWrite::flush(&mut stream)
+    }
+
+    /// Reads a checkpoint in the binary aterm format from the given reader.
+    pub fn read_from(reader: impl Read) -> Result<Self, MercError> {
+        let mut stream = BinaryATermReader::new(reader)?;
+        <Self as ATermStreamable>::read(&mut stream)
+    }
+
+    /// Saves the checkpoint to the given path.
+    ///
+    /// The checkpoint is first written to a temporary file next to the given
+    /// path, which is then renamed. This ensures that a crash while saving
+    /// never destroys the previous checkpoint.
+    pub fn save(&self, path: &Path) -> Result<(), MercError> {
+        let mut temporary = path.as_os_str().to_owned();
+        temporary.push(".tmp");
+
+        let mut writer = BufWriter::new(File::create(&temporary)?);
+        self.write_to(&mut writer)?;
+        writer.into_inner().map_err(|err| err.into_error())?.sync_all()?;
+
+        std::fs::rename(&temporary, path)?;
+        Ok(())
+    }
+
+    /// Loads a checkpoint from the given path.
+    pub fn load(path: &Path) -> Result<Self, MercError> {
+        Self::read_from(BufReader::new(File::open(path)?))
+    }
+}
+
+impl ATermStreamable for Checkpoint {
+    fn write<W: ATermWrite>(&self, writer: &mut W) -> Result<(), MercError> {
+        writer.write_aterm_iter(self.terms.iter().cloned())?;
+        writer.write_aterm_iter(self.handles.iter().map(|handle| ATermInt::new(*handle).into()))
+    }
+
+    fn read<R: ATermRead>(reader: &mut R) -> Result<Self, MercError>
+    where
+        Self: Sized,
+    {
+        let terms = reader.read_aterm_iter()?.collect::<Result<Vec<ATerm>, MercError>>()?;
+
+        let mut handles = Vec::new();
+        for term in reader.read_aterm_iter()? {
+            let term = term?;
+            if !is_int_term(&term) {
+                return Err(MercError::format(format!("Expected an integer handle, found {term}")));
+            }
+
+            let handle: ATermInt = term.into();
+            handles.push(handle.value());
+        }
+
+        Ok(Self { terms, handles })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use merc_utilities::random_test;
+    use rand::Rng;
+
+    use crate::random_term;
+
+    use super::*;
+
+    #[test]
+    #[cfg_attr(miri, ignore)] // Miri is too slow
+    fn test_random_checkpoint() {
+        random_test(100, |rng| {
+            let terms: Vec<_> = (0..rng.random_range(0..20))
+                .map(|_| random_term(rng, &[("f".into(), 2), ("g".into(), 1)], &["a".into(), "b".into()], 3))
+                .collect();
+            let handles: Vec<usize> = (0..rng.random_range(0..20))
+                .map(|_| rng.random_range(0..1000))
+                .collect();
+
+            let checkpoint = Checkpoint::new(terms, handles);
+
+            let mut stream: Vec<u8> = Vec::new();
+            checkpoint.write_to(&mut stream).unwrap();
+
+            let result = Checkpoint::read_from(&stream[..]).unwrap();
+            assert_eq!(checkpoint, result, "The restored checkpoint must match the saved one");
+        });
+    }
+}
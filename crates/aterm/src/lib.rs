@@ -3,14 +3,21 @@
 mod aterm;
 mod aterm_binary_stream;
 mod aterm_builder;
+mod aterm_compression;
 mod aterm_int;
 mod aterm_list;
 mod aterm_string;
+mod checkpoint;
 mod markable;
 mod parse_term;
 mod protected;
 mod random_term;
+mod static_symbol;
+mod substitution;
 mod symbol;
+mod term_arena;
+mod term_map;
+mod term_pattern;
 mod transmutable;
 
 pub mod storage;
@@ -18,12 +25,19 @@ pub mod storage;
 pub use aterm::*;
 pub use aterm_binary_stream::*;
 pub use aterm_builder::*;
+pub use aterm_compression::*;
 pub use aterm_int::*;
 pub use aterm_list::*;
 pub use aterm_string::*;
+pub use checkpoint::*;
 pub use markable::*;
 pub use parse_term::*;
 pub use protected::*;
 pub use random_term::*;
+pub use static_symbol::*;
+pub use substitution::*;
 pub use symbol::*;
+pub use term_arena::*;
+pub use term_map::*;
+pub use term_pattern::*;
 pub use transmutable::*;
@@ -174,3 +174,21 @@ impl Markable for bool {
         0
     }
 }
+
+impl Markable for usize {
+    fn mark(&self, _marker: &mut Marker) {
+        // Nothing to mark
+    }
+
+    fn contains_term(&self, _term: &ATermRef<'_>) -> bool {
+        false
+    }
+
+    fn contains_symbol(&self, _symbol: &SymbolRef<'_>) -> bool {
+        false
+    }
+
+    fn len(&self) -> usize {
+        0
+    }
+}
@@ -1,7 +1,8 @@
 #![forbid(unsafe_code)]
 
+use merc_utilities::MercError;
 use rand::Rng;
-use rustc_hash::FxHashSet;
+use rustc_hash::FxHashMap;
 
 use crate::ATerm;
 use crate::Symbol;
@@ -16,12 +17,17 @@ pub fn random_term(rng: &mut impl Rng, symbols: &[(String, usize)], constants: &
 
     debug_assert!(!constants.is_empty(), "We need constants to be able to create a term");
 
-    let mut subterms = THREAD_TERM_POOL.with_borrow(|tp| {
-        FxHashSet::<ATerm>::from_iter(constants.iter().map(|name| {
-            let symbol = tp.create_symbol(name, 0);
-            let a: &[ATerm] = &[];
-            tp.create_term(&symbol, a).protect()
-        }))
+    // The subterms are kept in a deterministic order, since terms are hashed by
+    // their address, so that the result only depends on the seed.
+    let mut subterms: Vec<ATerm> = THREAD_TERM_POOL.with_borrow(|tp| {
+        constants
+            .iter()
+            .map(|name| {
+                let symbol = tp.create_symbol(name, 0);
+                let a: &[ATerm] = &[];
+                tp.create_term(&symbol, a).protect()
+            })
+            .collect()
     });
 
     let mut result = None;
@@ -37,10 +43,164 @@ pub fn random_term(rng: &mut impl Rng, symbols: &[(String, usize)], constants: &
         let term = ATerm::with_args(&symbol, &arguments).protect();
 
         // Make this term available as another subterm that can be used.
-        subterms.insert(term.clone());
+        if !subterms.contains(&term) {
+            subterms.push(term.clone());
+        }
 
         result = Some(term);
     }
 
     result.expect("At least one iteration was performed")
 }
+
+/// A function symbol of a sorted signature, given by its name, the sorts of
+/// its arguments and its result sort.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SortedSymbol {
+    pub name: String,
+    pub arguments: Vec<String>,
+    pub sort: String,
+}
+
+impl SortedSymbol {
+    /// Creates a new symbol `name: arguments -> sort`.
+    pub fn new(name: &str, arguments: &[&str], sort: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            arguments: arguments.iter().map(|argument| argument.to_string()).collect(),
+            sort: sort.to_string(),
+        }
+    }
+}
+
+/// Create a random well-typed term of the given sort over the given sorted
+/// signature. Similar to [random_term] this performs iterations number of
+/// constructions, where every argument is a previously constructed term of the
+/// required sort.
+///
+/// # Details
+///
+/// The symbols without arguments are the initial subterms. Afterwards only
+/// symbols of which all argument sorts have a term are applied. The
+/// construction only depends on the given random number generator, so the same
+/// seed yields the same term. Returns an error when no term of the given sort
+/// can be constructed.
+pub fn random_typed_term(
+    rng: &mut impl Rng,
+    signature: &[SortedSymbol],
+    sort: &str,
+    iterations: usize,
+) -> Result<ATerm, MercError> {
+    use rand::prelude::IteratorRandom;
+
+    // The subterms of every sort, in a deterministic order as in [random_term].
+    let mut subterms: FxHashMap<&str, Vec<ATerm>> = FxHashMap::default();
+    let mut result = None;
+
+    for symbol in signature.iter().filter(|symbol| symbol.arguments.is_empty()) {
+        let term = add_subterm(&mut subterms, symbol, &[]);
+        if symbol.sort == sort {
+            result = Some(term);
+        }
+    }
+
+    for _ in 0..iterations {
+        let applicable = signature.iter().filter(|symbol| {
+            !symbol.arguments.is_empty()
+                && symbol
+                    .arguments
+                    .iter()
+                    .all(|argument| subterms.contains_key(argument.as_str()))
+        });
+
+        let Some(symbol) = applicable.choose(rng) else {
+            // Only constants can be constructed.
+            break;
+        };
+
+        let arguments: Vec<ATerm> = symbol
+            .arguments
+            .iter()
+            .map(|argument| {
+                subterms[argument.as_str()]
+                    .iter()
+                    .choose(rng)
+                    .expect("Every sort in the map has a term")
+                    .clone()
+            })
+            .collect();
+
+        let term = add_subterm(&mut subterms, symbol, &arguments);
+        if symbol.sort == sort {
+            result = Some(term);
+        }
+    }
+
+    result.ok_or_else(|| MercError::from(format!("The signature has no term of sort {sort}")))
+}
+
+/// Adds the application of the symbol to the given arguments to the subterms of its sort, and returns it.
+fn add_subterm<'a>(
+    subterms: &mut FxHashMap<&'a str, Vec<ATerm>>,
+    symbol: &'a SortedSymbol,
+    arguments: &[ATerm],
+) -> ATerm {
+    let term = ATerm::with_args(&Symbol::new(&symbol.name, arguments.len()), arguments).protect();
+
+    let terms = subterms.entry(symbol.sort.as_str()).or_default();
+    if !terms.contains(&term) {
+        terms.push(term.clone());
+    }
+
+    term
+}
+
+#[cfg(test)]
+mod tests {
+    use merc_utilities::random_test;
+
+    use crate::Symb;
+    use crate::fold_term;
+
+    use super::*;
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_random_typed_term() {
+        let signature = [
+            SortedSymbol::new("zero", &[], "Nat"),
+            SortedSymbol::new("succ", &["Nat"], "Nat"),
+            SortedSymbol::new("plus", &["Nat", "Nat"], "Nat"),
+            SortedSymbol::new("true", &[], "Bool"),
+            SortedSymbol::new("is_zero", &["Nat"], "Bool"),
+            SortedSymbol::new("and", &["Bool", "Bool"], "Bool"),
+            SortedSymbol::new("if", &["Bool", "Nat", "Nat"], "Nat"),
+        ];
+
+        random_test(100, |rng| {
+            let term = random_typed_term(rng, &signature, "Nat", 20).unwrap();
+
+            // Determine the sort of every subterm, which fails when an argument has the wrong sort.
+            let sort = fold_term(&term, |t, arguments: &[String]| {
+                let symbol = signature
+                    .iter()
+                    .find(|symbol| symbol.name == t.get_head_symbol().name())
+                    .expect("Only symbols of the signature are used");
+
+                if symbol.arguments != arguments {
+                    return Err(MercError::from(format!("Term {t} is not well-typed")));
+                }
+
+                Ok(symbol.sort.clone())
+            })
+            .unwrap();
+
+            assert_eq!(sort, "Nat");
+        });
+
+        // There is no term of an unknown sort.
+        random_test(1, |rng| {
+            assert!(random_typed_term(rng, &signature, "Real", 20).is_err());
+        });
+    }
+}
@@ -0,0 +1,163 @@
+use std::ops::Deref;
+use std::sync::OnceLock;
+
+use crate::Symb;
+use crate::SymbolRef;
+use crate::storage::THREAD_TERM_POOL;
+
+/// The number of arities for which a [StaticSymbolFamily] caches its symbols.
+pub const STATIC_SYMBOL_FAMILY_ARITIES: usize = 32;
+
+/// Registers the function symbol with the given name and arity in the table of
+/// static symbols of the global term pool, and returns a reference to it.
+///
+/// Static symbols are never garbage collected, so the returned reference is
+/// valid for the remainder of the process. Registering a symbol again returns
+/// the same reference.
+pub fn register_static_symbol(name: &str, arity: usize) -> SymbolRef<'static> {
+    THREAD_TERM_POOL.with_borrow(|tp| {
+        tp.term_pool()
+            .read_recursive()
+            .expect("Lock poisoned!")
+            .register_static_symbol(name, arity)
+    })
+}
+
+/// A function symbol that is registered once per process, see
+/// [register_static_symbol], such that frequently used symbols can be obtained
+/// without hashing their name.
+///
+/// # Example
+///
+/// ```
+/// use merc_aterm::ATerm;
+/// use merc_aterm::StaticSymbol;
+/// use merc_aterm::Term;
+///
+/// static PAIR: StaticSymbol = StaticSymbol::new("pair", 2);
+///
+/// let a = ATerm::from_string("a").unwrap();
+/// let term = ATerm::with_args(PAIR.get(), &[a.clone(), a]);
+/// assert_eq!(term.get_head_symbol(), *PAIR.get());
+/// ```
+pub struct StaticSymbol {
+    name: &'static str,
+    arity: usize,
+    symbol: OnceLock<SymbolRef<'static>>,
+}
+
+impl StaticSymbol {
+    /// Creates a handle to the static symbol with the given name and arity,
+    /// which is registered on first use.
+    pub const fn new(name: &'static str, arity: usize) -> Self {
+        Self {
+            name,
+            arity,
+            symbol: OnceLock::new(),
+        }
+    }
+
+    /// Returns the symbol, registering it on first use.
+    pub fn get(&self) -> &SymbolRef<'static> {
+        self.symbol
+            .get_or_init(|| register_static_symbol(self.name, self.arity))
+    }
+}
+
+impl Deref for StaticSymbol {
+    type Target = SymbolRef<'static>;
+
+    fn deref(&self) -> &Self::Target {
+        self.get()
+    }
+}
+
+/// A family of static function symbols that share a name, but have different
+/// arities, such as the symbols of applications.
+///
+/// The symbols for arities below [STATIC_SYMBOL_FAMILY_ARITIES] are cached in
+/// the family, larger arities are looked up in the table of static symbols on
+/// every use.
+pub struct StaticSymbolFamily {
+    name: &'static str,
+    symbols: [OnceLock<SymbolRef<'static>>; STATIC_SYMBOL_FAMILY_ARITIES],
+}
+
+impl StaticSymbolFamily {
+    /// Creates a family of static symbols with the given name.
+    pub const fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            symbols: [const { OnceLock::new() }; STATIC_SYMBOL_FAMILY_ARITIES],
+        }
+    }
+
+    /// Returns the symbol of the family with the given arity, registering it on
+    /// first use.
+    pub fn get(&self, arity: usize) -> SymbolRef<'static> {
+        match self.symbols.get(arity) {
+            Some(symbol) => symbol.get_or_init(|| register_static_symbol(self.name, arity)).copy(),
+            None => register_static_symbol(self.name, arity),
+        }
+    }
+
+    /// Returns true iff the given symbol belongs to this family.
+    pub fn contains(&self, symbol: &SymbolRef<'_>) -> bool {
+        if symbol.arity() < STATIC_SYMBOL_FAMILY_ARITIES {
+            *symbol == self.get(symbol.arity())
+        } else {
+            symbol.name() == self.name
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ATerm;
+    use crate::Symbol;
+    use crate::Term;
+
+    use super::*;
+
+    static STATIC_F: StaticSymbol = StaticSymbol::new("static_f", 2);
+    static STATIC_APPL: StaticSymbolFamily = StaticSymbolFamily::new("static_appl");
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_static_symbol() {
+        let _ = merc_utilities::test_logger();
+
+        let symbol = Symbol::new("static_f", 2);
+        assert_eq!(*STATIC_F.get(), symbol.copy());
+
+        // The static symbol must survive garbage collection without being protected.
+        drop(symbol);
+        THREAD_TERM_POOL.with_borrow(|tp| {
+            tp.term_pool()
+                .write()
+                .expect("Lock poisoned!")
+                .trigger_garbage_collection()
+        });
+
+        let a = ATerm::from_string("a").unwrap();
+        let term = ATerm::with_args(STATIC_F.get(), &[a.clone(), a]);
+        assert_eq!(term.get_head_symbol().name(), "static_f");
+        assert_eq!(term.protect(), ATerm::from_string("static_f(a, a)").unwrap());
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_static_symbol_family() {
+        let _ = merc_utilities::test_logger();
+
+        for arity in [0, 1, 5, STATIC_SYMBOL_FAMILY_ARITIES, STATIC_SYMBOL_FAMILY_ARITIES + 3] {
+            let symbol = STATIC_APPL.get(arity);
+            assert_eq!(symbol.name(), "static_appl");
+            assert_eq!(symbol.arity(), arity);
+            assert_eq!(symbol, STATIC_APPL.get(arity));
+            assert!(STATIC_APPL.contains(&symbol));
+        }
+
+        assert!(!STATIC_APPL.contains(&STATIC_F.get().copy()));
+    }
+}
@@ -1,5 +1,6 @@
 #![allow(dead_code)]
 
+use std::hash::BuildHasher;
 use std::hash::Hash;
 
 use equivalent::Equivalent;
@@ -51,6 +52,21 @@ impl ATermStorage {
         self.int_terms.len() + self.terms.len()
     }
 
+    /// Returns the number of terms that can be stored without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.terms.capacity()
+    }
+
+    /// Returns an iterator over the stored terms.
+    pub fn iter(&self) -> impl Iterator<Item = &SharedTerm> {
+        self.terms.iter()
+    }
+
+    /// Returns the hash that is used to store the given term.
+    pub fn hash(&self, term: &SharedTerm) -> u64 {
+        self.terms.hasher().hash_one(term)
+    }
+
     pub fn retain<F>(&self, mut f: F)
     where
         F: FnMut(&StablePointer<SharedTerm>) -> bool,
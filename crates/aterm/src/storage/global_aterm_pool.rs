@@ -2,17 +2,24 @@ use std::cell::UnsafeCell;
 use std::collections::HashSet;
 use std::fmt;
 use std::sync::Arc;
+#[cfg(not(loom))]
 use std::sync::LazyLock;
 use std::sync::atomic::AtomicUsize;
-use std::time::Instant;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
 
 use log::debug;
+use log::info;
 
+use merc_collections::ProtectionIndex;
 use merc_collections::ProtectionSet;
 use merc_io::LargeFormatter;
 use merc_sharedmutex::GlobalBfSharedMutex;
 use merc_sharedmutex::RecursiveLockReadGuard;
+use merc_sharedmutex::sync::AtomicBool;
+use merc_sharedmutex::sync::Mutex;
 use merc_unsafety::StablePointer;
+use merc_utilities::Instant;
 use merc_utilities::debug_trace;
 
 use crate::ATermIndex;
@@ -24,17 +31,54 @@ use crate::SymbolIndex;
 use crate::SymbolRef;
 use crate::Term;
 use crate::storage::ATermStorage;
+use crate::storage::LoadFactorSample;
 use crate::storage::SharedTerm;
 use crate::storage::SharedTermLookup;
 use crate::storage::SymbolPool;
+use crate::storage::THREAD_TERM_POOL;
+use crate::storage::TermPoolDiagnostics;
+use crate::storage::diagnostics_from_environment;
 
 /// This is the global set of protection sets that are managed by the ThreadTermPool
+#[cfg(not(loom))]
 pub static GLOBAL_TERM_POOL: LazyLock<GlobalBfSharedMutex<GlobalTermPool>> =
     LazyLock::new(|| GlobalBfSharedMutex::new(GlobalTermPool::new()));
 
+#[cfg(loom)]
+loom::lazy_static! {
+    /// Under loom the global term pool is created anew for every execution of the model.
+    pub static ref GLOBAL_TERM_POOL: GlobalBfSharedMutex<GlobalTermPool> = GlobalBfSharedMutex::new(GlobalTermPool::new());
+}
+
+/// Prints the contention metrics of the global term pool lock, which are only
+/// available when the `merc_metrics` feature is enabled. Also prints the
+/// diagnostics of the term pool when these are enabled by the
+/// [TERM_POOL_DIAGNOSTICS_VARIABLE] environment variable.
+pub fn print_term_pool_metrics() {
+    if let Some(metrics) = GLOBAL_TERM_POOL.shared_mutex.metrics() {
+        info!("Global term pool lock: {metrics}");
+    }
+
+    if let Some(top) = diagnostics_from_environment() {
+        print_term_pool_diagnostics(top);
+    }
+}
+
+/// Prints the diagnostics of the hash-consing in the term pool, reporting the
+/// given number of most shared subterms, see [TermPoolDiagnostics].
+pub fn print_term_pool_diagnostics(top: usize) {
+    THREAD_TERM_POOL.with_borrow(|tp| {
+        let pool = tp.term_pool().read_recursive().expect("Lock poisoned!");
+        info!("{}", pool.diagnostics(top));
+    });
+}
+
 /// Enables aggressive garbage collection, which is used for testing.
 pub(crate) const AGGRESSIVE_GC: bool = false;
 
+/// A major collection is performed after this many minor collections.
+const MAJOR_COLLECTION_INTERVAL: usize = 8;
+
 /// A type alias for the global term pool guard
 pub(crate) type GlobalTermPoolGuard<'a> = RecursiveLockReadGuard<'a, GlobalTermPool>;
 
@@ -48,7 +92,7 @@ pub struct GlobalTermPool {
     /// The symbol pool for managing function symbols.
     symbol_pool: SymbolPool,
     /// The thread-specific protection sets.
-    thread_pools: Vec<Option<Arc<UnsafeCell<SharedTermProtection>>>>,
+    thread_pools: Vec<Option<Arc<ThreadProtection>>>,
 
     // Data structures used for garbage collection
     /// Used to avoid reallocations for the markings of all terms - uses pointers as keys
@@ -57,6 +101,12 @@ pub struct GlobalTermPool {
     marked_symbols: HashSet<SymbolIndex>,
     /// A stack used to mark terms recursively.
     stack: Vec<ATermIndex>,
+    /// Terms that survived a collection, these are only removed by major collections.
+    tenured: HashSet<ATermIndex>,
+    /// The number of tenured terms directly after the last major collection.
+    tenured_after_major: usize,
+    /// Statistics of the collections performed so far.
+    collection_metrics: CollectionMetrics,
 
     /// Deletion hooks called whenever a term with the given head symbol is deleted.
     deletion_hooks: Vec<(Symbol, DeletionHook)>,
@@ -64,6 +114,13 @@ pub struct GlobalTermPool {
     /// Indicates whether automatic garbage collection is enabled.
     garbage_collection: bool,
 
+    /// The number of most shared subterms reported in the diagnostics printed
+    /// after every collection, or `None` when these are disabled.
+    print_diagnostics: Option<usize>,
+
+    /// Symbols that are registered for the remainder of the process, see [crate::StaticSymbol].
+    static_symbols: Mutex<Vec<SymbolRef<'static>>>,
+
     /// Default terms
     int_symbol: SymbolRef<'static>,
     empty_list_symbol: SymbolRef<'static>,
@@ -88,8 +145,13 @@ impl GlobalTermPool {
             marked_terms: HashSet::new(),
             marked_symbols: HashSet::new(),
             stack: Vec::new(),
+            tenured: HashSet::new(),
+            tenured_after_major: 0,
+            collection_metrics: CollectionMetrics::default(),
             deletion_hooks: Vec::new(),
             garbage_collection: true,
+            print_diagnostics: diagnostics_from_environment(),
+            static_symbols: Mutex::new(Vec::new()),
             int_symbol,
             list_symbol,
             empty_list_symbol,
@@ -160,20 +222,24 @@ impl GlobalTermPool {
         protect(self.symbol_pool.create(name, arity))
     }
 
+    /// Registers a function symbol that is never garbage collected, and returns
+    /// a reference to it that is valid for the remainder of the process.
+    pub fn register_static_symbol(&self, name: &str, arity: usize) -> SymbolRef<'static> {
+        // SAFETY: The symbol is added to the static symbols, which are always marked, before a
+        // garbage collection can take place since that requires exclusive access to the pool.
+        let symbol = unsafe { SymbolRef::from_index(&self.symbol_pool.create(name, arity)) };
+
+        let mut static_symbols = self.static_symbols.lock().expect("Lock poisoned!");
+        if !static_symbols.contains(&symbol) {
+            static_symbols.push(symbol.copy());
+        }
+
+        symbol
+    }
+
     /// Registers a new thread term pool.
-    ///
-    /// # Safety
-    ///
-    /// Note that the returned `Arc<UnsafeCell<...>>` is not Send or Sync, so it
-    /// *must* be protected through other means.
-    #[allow(clippy::arc_with_non_send_sync)]
-    pub(crate) fn register_thread_term_pool(&mut self) -> Arc<UnsafeCell<SharedTermProtection>> {
-        let protection = Arc::new(UnsafeCell::new(SharedTermProtection {
-            protection_set: ProtectionSet::new(),
-            symbol_protection_set: ProtectionSet::new(),
-            container_protection_set: ProtectionSet::new(),
-            index: self.thread_pools.len(),
-        }));
+    pub(crate) fn register_thread_term_pool(&mut self) -> Arc<ThreadProtection> {
+        let protection = Arc::new(ThreadProtection::new(self.thread_pools.len()));
 
         debug!("Registered thread_local protection set(s) {}", self.thread_pools.len());
         self.thread_pools.push(Some(protection.clone()));
@@ -182,17 +248,39 @@ impl GlobalTermPool {
     }
 
     /// Deregisters a thread pool.
+    ///
+    /// # Details
+    ///
+    /// Terms that were sent to other threads by means of [crate::ATermSend]
+    /// remain protected by the protection set of this thread. In that case the
+    /// protection set is only removed during the first garbage collection after
+    /// all these terms have been dropped.
     pub(crate) fn deregister_thread_pool(&mut self, index: usize) {
-        debug!("Removed thread_local protection set(s) {index}");
         if let Some(entry) = self.thread_pools.get_mut(index) {
-            *entry = None;
+            // The global pool and the thread pool itself hold the remaining references.
+            if entry
+                .as_ref()
+                .is_some_and(|protection| Arc::strong_count(protection) <= 2)
+            {
+                debug!("Removed thread_local protection set(s) {index}");
+                *entry = None;
+            }
         }
     }
 
     /// Triggers garbage collection if necessary and returns an updated counter for the thread local pool.
     pub(crate) fn trigger_garbage_collection(&mut self) -> usize {
         self.collect_garbage();
+        self.garbage_collection_counter()
+    }
+
+    /// Returns true iff automatic garbage collection is enabled.
+    pub(crate) fn is_garbage_collection_enabled(&self) -> bool {
+        self.garbage_collection
+    }
 
+    /// Returns the counter for the thread local pool after a (possibly skipped) garbage collection.
+    pub(crate) fn garbage_collection_counter(&self) -> usize {
         if AGGRESSIVE_GC {
             return 1;
         }
@@ -224,33 +312,68 @@ impl GlobalTermPool {
     }
 
     /// Collects garbage terms.
+    ///
+    /// # Details
+    ///
+    /// The collection is generational. Terms are maximally shared and immutable,
+    /// so the arguments of a term are always created before the term itself. As
+    /// such, a tenured term can only refer to other tenured terms. A minor
+    /// collection therefore only marks the young terms that are reachable from
+    /// the roots, without descending into tenured terms, and only removes young
+    /// terms. All surviving terms are tenured afterwards. A major collection
+    /// marks all reachable terms, removes all unreachable terms and symbols, and
+    /// is performed periodically or when the number of tenured terms has doubled.
     fn collect_garbage(&mut self) {
         if !self.garbage_collection {
             // Garbage collection is disabled.
             return;
         }
 
+        let major = self.collection_metrics.minor_collections_since_major >= MAJOR_COLLECTION_INTERVAL
+            || self.tenured.len() >= 2 * self.tenured_after_major.max(1000);
+
         // Clear marking data structures
         self.marked_terms.clear();
         self.marked_symbols.clear();
         self.stack.clear();
 
+        if major {
+            // Every term is considered young during a major collection.
+            self.tenured.clear();
+        }
+
         // Mark the default symbols
         self.marked_symbols.insert(self.int_symbol.shared().copy());
         self.marked_symbols.insert(self.list_symbol.shared().copy());
         self.marked_symbols.insert(self.empty_list_symbol.shared().copy());
+        for symbol in self.static_symbols.lock().expect("Lock poisoned!").iter() {
+            self.marked_symbols.insert(symbol.shared().copy());
+        }
 
         let mut marker = Marker {
             marked_terms: &mut self.marked_terms,
             marked_symbols: &mut self.marked_symbols,
             stack: &mut self.stack,
+            tenured: &self.tenured,
         };
 
         let mark_time = Instant::now();
 
+        // Remove the protection sets of terminated threads that no longer protect any sent terms.
+        for (index, entry) in self.thread_pools.iter_mut().enumerate() {
+            if entry
+                .as_ref()
+                .is_some_and(|protection| Arc::strong_count(protection) == 1)
+            {
+                debug!("Removed thread_local protection set(s) {index}");
+                *entry = None;
+            }
+        }
+
         // Loop through all protection sets and mark the terms.
         for pool in self.thread_pools.iter().flatten() {
             // SAFETY: We have exclusive access to the global term pool, so no other thread can modify the protection sets.
+            unsafe { pool.release_deferred() };
             let pool = unsafe { &mut *pool.get() };
 
             for (_root, symbol) in pool.symbol_protection_set.iter() {
@@ -277,9 +400,9 @@ impl GlobalTermPool {
         let num_of_terms = self.len();
         let num_of_symbols = self.symbol_pool.len();
 
-        // Delete all terms that are not marked
+        // Delete all terms that are not marked, tenured terms are only deleted by major collections.
         self.terms.retain(|term| {
-            if !self.marked_terms.contains(term) {
+            if !self.marked_terms.contains(term) && !self.tenured.contains(term) {
                 debug_trace!("Dropping term: {:?}", term);
 
                 // Call the deletion hooks for the term
@@ -296,18 +419,34 @@ impl GlobalTermPool {
             true
         });
 
-        // We ensure that every removed symbol is not used anymore.
-        self.symbol_pool.retain(|symbol| {
-            if !self.marked_symbols.contains(symbol) {
-                debug_trace!("Dropping symbol: {:?}", symbol);
-                return false;
-            }
+        if major {
+            // The symbols of tenured terms are not marked during a minor collection, so symbols can only be removed here.
+            self.symbol_pool.retain(|symbol| {
+                if !self.marked_symbols.contains(symbol) {
+                    debug_trace!("Dropping symbol: {:?}", symbol);
+                    return false;
+                }
 
-            true
+                true
+            });
+        }
+
+        // All surviving terms are tenured.
+        self.tenured.extend(self.marked_terms.drain());
+        if major {
+            self.tenured_after_major = self.tenured.len();
+        }
+
+        self.collection_metrics
+            .record(major, mark_time_elapsed + collect_time.elapsed());
+        self.collection_metrics.load_factors.push(LoadFactorSample {
+            terms: self.len(),
+            capacity: self.terms.capacity(),
         });
 
         debug!(
-            "Garbage collection: marking took {}ms, collection took {}ms, {} terms and {} symbols removed",
+            "Garbage collection ({}): marking took {}ms, collection took {}ms, {} terms and {} symbols removed",
+            if major { "major" } else { "minor" },
             mark_time_elapsed.as_millis(),
             collect_time.elapsed().as_millis(),
             num_of_terms - self.len(),
@@ -322,6 +461,10 @@ impl GlobalTermPool {
             let pool = unsafe { &mut *pool.get() };
             debug!("{}", pool.metrics());
         }
+
+        if let Some(top) = self.print_diagnostics {
+            info!("{}", self.diagnostics(top));
+        }
     }
 
     /// Returns the metrics of the term pool, can be formatted and written to output.
@@ -329,6 +472,13 @@ impl GlobalTermPool {
         TermPoolMetrics(self)
     }
 
+    /// Returns the diagnostics of the hash-consing in the term pool, reporting
+    /// the given number of most shared subterms. This iterates over all terms,
+    /// so it should only be used for debugging.
+    pub fn diagnostics(&self, top: usize) -> TermPoolDiagnostics {
+        TermPoolDiagnostics::new(&self.terms, &self.collection_metrics.load_factors, top)
+    }
+
     /// Marks the given term as being reachable.
     ///
     /// # Safety
@@ -340,6 +490,7 @@ impl GlobalTermPool {
             marked_terms: &mut self.marked_terms,
             marked_symbols: &mut self.marked_symbols,
             stack: &mut self.stack,
+            tenured: &self.tenured,
         };
         term.mark(&mut marker);
     }
@@ -364,15 +515,50 @@ pub struct TermPoolMetrics<'a>(&'a GlobalTermPool);
 
 impl fmt::Display for TermPoolMetrics<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let collections = &self.0.collection_metrics;
+
         write!(
             f,
-            "There are {} terms, and {} symbols",
-            self.0.terms.len(),
-            self.0.symbol_pool.len()
+            "There are {} terms ({} tenured), and {} symbols. Performed {} minor and {} major collections, last pause {}ms, max pause {}ms",
+            LargeFormatter(self.0.terms.len()),
+            LargeFormatter(self.0.tenured.len()),
+            LargeFormatter(self.0.symbol_pool.len()),
+            collections.minor_collections,
+            collections.major_collections,
+            collections.last_pause.as_millis(),
+            collections.max_pause.as_millis(),
         )
     }
 }
 
+/// Statistics of the garbage collections of the [GlobalTermPool].
+#[derive(Default)]
+struct CollectionMetrics {
+    minor_collections: usize,
+    major_collections: usize,
+    minor_collections_since_major: usize,
+    last_pause: Duration,
+    max_pause: Duration,
+    /// The load factor of the term storage after every collection.
+    load_factors: Vec<LoadFactorSample>,
+}
+
+impl CollectionMetrics {
+    /// Records a collection that took the given time.
+    fn record(&mut self, major: bool, pause: Duration) {
+        if major {
+            self.major_collections += 1;
+            self.minor_collections_since_major = 0;
+        } else {
+            self.minor_collections += 1;
+            self.minor_collections_since_major += 1;
+        }
+
+        self.last_pause = pause;
+        self.max_pause = self.max_pause.max(pause);
+    }
+}
+
 pub struct SharedTermProtection {
     /// Protection set for terms
     pub protection_set: ProtectionSet<ATermIndex>,
@@ -391,6 +577,82 @@ impl SharedTermProtection {
     }
 }
 
+/// The protection sets of a single thread, shared with the global term pool
+/// for garbage collection.
+///
+/// # Details
+///
+/// The protection sets themselves are not synchronised, since they are only
+/// modified by the owning thread while it holds a (cheap, thread local) read
+/// lock on the global term pool, or during garbage collection when the global
+/// term pool is locked exclusively. Terms that were sent to another thread and
+/// dropped there are not removed immediately, instead their roots are pushed
+/// onto a deferred list that is released during the next garbage collection.
+/// This avoids synchronisation on the common protect and unprotect path.
+pub struct ThreadProtection {
+    /// The protection sets, see the details above for the access rules.
+    local: UnsafeCell<SharedTermProtection>,
+    /// Roots of terms that have been unprotected by other threads.
+    deferred: Mutex<Vec<ProtectionIndex>>,
+    /// Indicates whether `deferred` might be non-empty, avoids locking it for every garbage collection.
+    has_deferred: AtomicBool,
+}
+
+// SAFETY: Access to the unsynchronised protection sets is guarded by the global term pool, see [ThreadProtection].
+unsafe impl Send for ThreadProtection {}
+unsafe impl Sync for ThreadProtection {}
+
+impl ThreadProtection {
+    /// Creates empty protection sets for the thread with the given index.
+    fn new(index: usize) -> Self {
+        Self {
+            local: UnsafeCell::new(SharedTermProtection {
+                protection_set: ProtectionSet::new(),
+                symbol_protection_set: ProtectionSet::new(),
+                container_protection_set: ProtectionSet::new(),
+                index,
+            }),
+            deferred: Mutex::new(Vec::new()),
+            has_deferred: AtomicBool::new(false),
+        }
+    }
+
+    /// Returns a raw pointer to the protection sets, which may only be
+    /// dereferenced by the owning thread while it holds a read lock on the
+    /// global term pool, or while the global term pool is locked exclusively.
+    pub(crate) fn get(&self) -> *mut SharedTermProtection {
+        self.local.get()
+    }
+
+    /// Unprotects the term with the given root from a thread that does not own
+    /// this protection set. The root is released during the next garbage collection.
+    pub(crate) fn defer_unprotect(&self, root: ProtectionIndex) {
+        self.deferred.lock().expect("Lock poisoned!").push(root);
+        self.has_deferred.store(true, Ordering::Release);
+    }
+
+    /// Returns the number of roots that are waiting to be released.
+    pub fn number_of_deferred(&self) -> usize {
+        self.deferred.lock().expect("Lock poisoned!").len()
+    }
+
+    /// Releases all roots that have been unprotected by other threads.
+    ///
+    /// # Safety
+    ///
+    /// The caller must have exclusive access to the protection sets, see [ThreadProtection::get].
+    unsafe fn release_deferred(&self) {
+        if self.has_deferred.swap(false, Ordering::Acquire) {
+            let roots = std::mem::take(&mut *self.deferred.lock().expect("Lock poisoned!"));
+            let local = unsafe { &mut *self.get() };
+
+            for root in roots {
+                local.protection_set.unprotect(root);
+            }
+        }
+    }
+}
+
 /// A struct that can be used to print the performance of the protection sets.
 pub struct ProtectionMetrics<'a>(&'a SharedTermProtection);
 
@@ -428,12 +690,14 @@ pub struct Marker<'a> {
     marked_terms: &'a mut HashSet<ATermIndex>,
     marked_symbols: &'a mut HashSet<SymbolIndex>,
     stack: &'a mut Vec<ATermIndex>,
+    /// Tenured terms are not marked, since they (and their arguments) are kept alive regardless.
+    tenured: &'a HashSet<ATermIndex>,
 }
 
 impl Marker<'_> {
     // Marks the given term as being reachable.
     pub fn mark(&mut self, term: &ATermRef<'_>) {
-        if !self.marked_terms.contains(term.shared()) {
+        if !self.marked_terms.contains(term.shared()) && !self.tenured.contains(term.shared()) {
             self.stack.push(term.shared().copy());
 
             while let Some(term) = self.stack.pop() {
@@ -446,7 +710,7 @@ impl Marker<'_> {
                 // For some terms, such as ATermInt, we must ONLY consider the valid arguments (indicated by the arity)
                 for arg in term.arguments()[0..term.symbol().arity()].iter() {
                     // Skip if unnecessary, otherwise mark before pushing to stack since it can be shared.
-                    if !self.marked_terms.contains(arg.shared()) {
+                    if !self.marked_terms.contains(arg.shared()) && !self.tenured.contains(arg.shared()) {
                         self.marked_terms.insert(arg.shared().copy());
                         self.marked_symbols.insert(arg.get_head_symbol().shared().copy());
                         self.stack.push(arg.shared().copy());
@@ -468,7 +732,12 @@ mod tests {
 
     use merc_utilities::random_test;
 
+    use crate::ATerm;
+    use crate::Term;
     use crate::random_term;
+    use crate::storage::THREAD_TERM_POOL;
+
+    use super::*;
 
     #[test]
     #[cfg_attr(miri, ignore)]
@@ -488,4 +757,32 @@ mod tests {
             }
         });
     }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_generational_collection() {
+        let _ = merc_utilities::test_logger();
+
+        let term = ATerm::from_string("f(g(a),b)").unwrap();
+
+        for _ in 0..2 * MAJOR_COLLECTION_INTERVAL {
+            // Young garbage that should be collected by minor collections.
+            let _ = ATerm::from_string("h(g(c),d)").unwrap();
+
+            THREAD_TERM_POOL.with_borrow(|tp| {
+                let mut pool = tp.term_pool().write().expect("Lock poisoned!");
+                pool.trigger_garbage_collection();
+                assert!(pool.tenured.contains(term.shared()));
+            });
+        }
+
+        THREAD_TERM_POOL.with_borrow(|tp| {
+            let pool = tp.term_pool().write().expect("Lock poisoned!");
+            assert!(pool.collection_metrics.major_collections >= 1);
+            assert!(pool.collection_metrics.minor_collections >= MAJOR_COLLECTION_INTERVAL);
+        });
+
+        assert_eq!(term.get_head_symbol().name(), "f");
+        assert_eq!(term.arg(0).get_head_symbol().name(), "g");
+    }
 }
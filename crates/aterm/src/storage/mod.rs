@@ -21,6 +21,7 @@ mod gc_mutex;
 mod global_aterm_pool;
 mod shared_term;
 mod symbol_pool;
+mod term_pool_diagnostics;
 mod thread_aterm_pool;
 
 pub(crate) use aterm_storage::*;
@@ -28,4 +29,5 @@ pub use gc_mutex::*;
 pub use global_aterm_pool::*;
 pub use shared_term::*;
 pub use symbol_pool::*;
+pub use term_pool_diagnostics::*;
 pub use thread_aterm_pool::*;
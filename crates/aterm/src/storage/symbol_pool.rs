@@ -121,8 +121,9 @@ impl SymbolPool {
         // Check whether there is a registered prefix p such that name equal pn where n is a number.
         // In that case prevent that pn will be generated as a fresh function name.
         let start_of_index = name
-            .rfind(|c: char| !c.is_ascii_digit())
-            .map(|pos| pos + 1)
+            .char_indices()
+            .rfind(|(_, c)| !c.is_ascii_digit())
+            .map(|(pos, c)| pos + c.len_utf8())
             .unwrap_or(0);
 
         if start_of_index < name.len() {
@@ -0,0 +1,233 @@
+#![forbid(unsafe_code)]
+
+use std::collections::HashMap;
+use std::fmt;
+
+use itertools::Itertools;
+use merc_io::LargeFormatter;
+
+use crate::ATermIndex;
+use crate::Symb;
+use crate::Term;
+use crate::storage::ATermStorage;
+use crate::storage::SharedTerm;
+
+/// The name of the environment variable that enables the diagnostics of the
+/// term pool. Its value is the number of most shared subterms that are
+/// reported, which defaults to [DEFAULT_MOST_SHARED].
+pub const TERM_POOL_DIAGNOSTICS_VARIABLE: &str = "MERC_TERM_POOL_DIAGNOSTICS";
+
+/// The number of most shared subterms reported by default.
+pub const DEFAULT_MOST_SHARED: usize = 10;
+
+/// Buckets with at least this many terms are counted in the last entry of the
+/// occupancy histogram.
+const MAX_OCCUPANCY: usize = 8;
+
+/// The number of load factor samples that are printed.
+const PRINTED_LOAD_FACTORS: usize = 10;
+
+/// Subterms are printed up to this depth.
+const PRINTED_DEPTH: usize = 3;
+
+/// Returns the number of most shared subterms that should be reported when
+/// the diagnostics are enabled by [TERM_POOL_DIAGNOSTICS_VARIABLE].
+pub(crate) fn diagnostics_from_environment() -> Option<usize> {
+    std::env::var(TERM_POOL_DIAGNOSTICS_VARIABLE)
+        .ok()
+        .map(|value| value.parse().unwrap_or(DEFAULT_MOST_SHARED))
+}
+
+/// The number of terms and the capacity of the term pool at some point in time.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct LoadFactorSample {
+    pub(crate) terms: usize,
+    pub(crate) capacity: usize,
+}
+
+impl LoadFactorSample {
+    /// Returns the fraction of the capacity that is in use.
+    fn load_factor(&self) -> f64 {
+        self.terms as f64 / self.capacity.max(1) as f64
+    }
+}
+
+/// Diagnostics of the hash-consing in the global term pool, which can be used
+/// to detect pathological hashing of the stored terms.
+///
+/// # Details
+///
+/// The bucket occupancy is computed by distributing the hashes of all terms
+/// over the number of buckets of a single table with the capacity of the
+/// pool, which approximates the sharded table used in practice. Terms that end
+/// up in the same bucket form a collision chain, whereas terms with the same
+/// full hash can never be distinguished by the table.
+pub struct TermPoolDiagnostics {
+    /// The number of terms in the pool.
+    num_of_terms: usize,
+    /// The number of buckets that the hashes are distributed over.
+    num_of_buckets: usize,
+    /// The number of buckets containing the given number of terms, where the
+    /// last entry counts all buckets with at least that many terms.
+    occupancy: Vec<usize>,
+    /// The largest number of terms in a single bucket.
+    longest_chain: usize,
+    /// The number of terms that share their full hash with another term.
+    hash_collisions: usize,
+    /// The load factor after every garbage collection.
+    load_factors: Vec<LoadFactorSample>,
+    /// The printed subterms with the most parents, and the number of parents.
+    most_shared: Vec<(String, usize)>,
+}
+
+impl TermPoolDiagnostics {
+    /// Computes the diagnostics of the given terms, reporting the given number
+    /// of most shared subterms.
+    pub(crate) fn new(terms: &ATermStorage, load_factors: &[LoadFactorSample], top: usize) -> Self {
+        let num_of_buckets = terms.capacity().max(1).next_power_of_two();
+
+        let mut buckets = vec![0usize; num_of_buckets];
+        let mut hashes: HashMap<u64, usize> = HashMap::new();
+        let mut parents: HashMap<ATermIndex, usize> = HashMap::new();
+        let mut num_of_terms = 0;
+
+        for term in terms.iter() {
+            let hash = terms.hash(term);
+            buckets[hash as usize & (num_of_buckets - 1)] += 1;
+            *hashes.entry(hash).or_default() += 1;
+
+            for argument in term.arguments() {
+                *parents.entry(argument.shared().copy()).or_default() += 1;
+            }
+
+            num_of_terms += 1;
+        }
+
+        let mut occupancy = vec![0; MAX_OCCUPANCY + 1];
+        for size in &buckets {
+            occupancy[(*size).min(MAX_OCCUPANCY)] += 1;
+        }
+
+        let most_shared = parents
+            .into_iter()
+            .sorted_unstable_by(|(_, left), (_, right)| right.cmp(left))
+            .take(top)
+            .map(|(term, count)| (TermPrinter(&term, PRINTED_DEPTH).to_string(), count))
+            .collect();
+
+        Self {
+            num_of_terms,
+            num_of_buckets,
+            occupancy,
+            longest_chain: buckets.iter().copied().max().unwrap_or(0),
+            hash_collisions: hashes.values().filter(|count| **count > 1).sum(),
+            load_factors: load_factors.to_vec(),
+            most_shared,
+        }
+    }
+}
+
+impl fmt::Display for TermPoolDiagnostics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Term pool diagnostics: {} terms in {} buckets, load factor {:.2}",
+            LargeFormatter(self.num_of_terms),
+            LargeFormatter(self.num_of_buckets),
+            self.num_of_terms as f64 / self.num_of_buckets as f64
+        )?;
+
+        write!(f, "Bucket occupancy:")?;
+        for (size, count) in self.occupancy.iter().enumerate() {
+            let plus = if size == MAX_OCCUPANCY { "+" } else { "" };
+            write!(f, " {size}{plus}: {}", LargeFormatter(*count))?;
+        }
+        writeln!(f, ", longest chain {}", self.longest_chain)?;
+
+        writeln!(
+            f,
+            "Hash collisions: {} terms share their full hash with another term",
+            LargeFormatter(self.hash_collisions)
+        )?;
+
+        if !self.load_factors.is_empty() {
+            let skipped = self.load_factors.len().saturating_sub(PRINTED_LOAD_FACTORS);
+            writeln!(
+                f,
+                "Load factor after the last {} collections: {}",
+                self.load_factors.len() - skipped,
+                self.load_factors[skipped..]
+                    .iter()
+                    .format_with(", ", |sample, f| f(&format_args!("{:.2}", sample.load_factor())))
+            )?;
+        }
+
+        write!(f, "Most shared subterms:")?;
+        for (term, count) in &self.most_shared {
+            write!(f, "\n  {} parents: {term}", LargeFormatter(*count))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Prints a term up to the given depth, without accessing the thread local
+/// term pool such that it can be used during garbage collection.
+struct TermPrinter<'a>(&'a SharedTerm, usize);
+
+impl fmt::Display for TermPrinter<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let TermPrinter(term, depth) = *self;
+
+        if let Some(value) = term.annotation() {
+            return write!(f, "{value}");
+        }
+
+        write!(f, "{}", term.symbol().name())?;
+        if !term.arguments().is_empty() {
+            if depth == 0 {
+                write!(f, "(..)")?;
+            } else {
+                write!(
+                    f,
+                    "({})",
+                    term.arguments()
+                        .iter()
+                        .format_with(", ", |argument, f| f(&TermPrinter(argument.shared(), depth - 1)))
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ATerm;
+    use crate::storage::THREAD_TERM_POOL;
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_term_pool_diagnostics() {
+        let _ = merc_utilities::test_logger();
+
+        let terms: Vec<ATerm> = (0..100)
+            .map(|i| ATerm::from_string(&format!("diagnostics_{i}(diagnostics_shared)")).unwrap())
+            .collect();
+
+        THREAD_TERM_POOL.with_borrow(|tp| {
+            let pool = tp.term_pool().read_recursive().expect("Lock poisoned!");
+            let diagnostics = pool.diagnostics(5);
+
+            assert_eq!(diagnostics.occupancy.iter().sum::<usize>(), diagnostics.num_of_buckets);
+            assert!(diagnostics.num_of_terms >= terms.len());
+            assert!(diagnostics.most_shared.len() <= 5);
+            assert!(
+                diagnostics.most_shared.windows(2).all(|pair| pair[0].1 >= pair[1].1),
+                "The subterms should be ordered by the number of parents"
+            );
+            assert!(diagnostics.most_shared[0].1 >= terms.len());
+        });
+    }
+}
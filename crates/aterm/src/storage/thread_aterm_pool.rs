@@ -1,6 +1,5 @@
 use std::cell::Cell;
 use std::cell::RefCell;
-use std::cell::UnsafeCell;
 use std::ops::Deref;
 use std::ops::DerefMut;
 use std::sync::Arc;
@@ -29,17 +28,45 @@ use crate::storage::AGGRESSIVE_GC;
 use crate::storage::GlobalTermPool;
 use crate::storage::SharedTerm;
 use crate::storage::SharedTermProtection;
+use crate::storage::ThreadProtection;
 use crate::storage::global_aterm_pool::GLOBAL_TERM_POOL;
 
+#[cfg(not(loom))]
 thread_local! {
     /// Thread-specific term pool that manages protection sets.
     pub static THREAD_TERM_POOL: RefCell<ThreadTermPool> = RefCell::new(ThreadTermPool::new());
 }
 
+#[cfg(loom)]
+loom::thread_local! {
+    static LOOM_THREAD_TERM_POOL: RefCell<ThreadTermPool> = RefCell::new(ThreadTermPool::new());
+}
+
+/// Thread-specific term pool that manages protection sets, under loom it is
+/// created anew for every thread of the model.
+#[cfg(loom)]
+pub static THREAD_TERM_POOL: LoomThreadTermPool = LoomThreadTermPool;
+
+/// Provides the subset of the [std::thread::LocalKey] interface that is used
+/// for the thread-specific term pool, since loom thread locals do not offer it.
+#[cfg(loom)]
+pub struct LoomThreadTermPool;
+
+#[cfg(loom)]
+impl LoomThreadTermPool {
+    /// Acquires a reference to the thread-specific term pool.
+    pub fn with_borrow<F, R>(&'static self, f: F) -> R
+    where
+        F: FnOnce(&ThreadTermPool) -> R,
+    {
+        LOOM_THREAD_TERM_POOL.with(|tp| f(&tp.borrow()))
+    }
+}
+
 /// Per-thread term pool managing local protection sets.
 pub struct ThreadTermPool {
     /// A reference to the protection set of this thread pool.
-    protection_set: Arc<UnsafeCell<SharedTermProtection>>,
+    protection_set: Arc<ThreadProtection>,
 
     /// The number of times termms have been created before garbage collection is triggered.
     garbage_collection_counter: Cell<usize>,
@@ -92,7 +119,7 @@ impl ThreadTermPool {
         let (index, inserted) = guard.create_term_array(symbol, &empty_args);
 
         if inserted {
-            self.trigger_garbage_collection();
+            self.trigger_garbage_collection(1);
         }
 
         self.protect_guard(guard, &unsafe { ATermRef::from_index(&index) })
@@ -118,7 +145,7 @@ impl ThreadTermPool {
         let (index, inserted) = guard.create_term_array(symbol, &arguments);
 
         if inserted {
-            self.trigger_garbage_collection();
+            self.trigger_garbage_collection(1);
         }
 
         unsafe {
@@ -137,7 +164,7 @@ impl ThreadTermPool {
         let (index, inserted) = guard.create_int(value);
 
         if inserted {
-            self.trigger_garbage_collection();
+            self.trigger_garbage_collection(1);
         }
 
         self.protect_guard(guard, &unsafe { ATermRef::from_index(&index) })
@@ -162,7 +189,7 @@ impl ThreadTermPool {
         let (index, inserted) = guard.create_term_array(symbol, &arguments);
 
         if inserted {
-            self.trigger_garbage_collection();
+            self.trigger_garbage_collection(1);
         }
 
         self.protect_guard(guard, &unsafe { ATermRef::from_index(&index) })
@@ -191,7 +218,7 @@ impl ThreadTermPool {
         let (index, inserted) = guard.create_term_array(symbol, &arguments);
 
         if inserted {
-            self.trigger_garbage_collection();
+            self.trigger_garbage_collection(1);
         }
 
         Ok(self.protect_guard(guard, &unsafe { ATermRef::from_index(&index) }))
@@ -224,7 +251,7 @@ impl ThreadTermPool {
         let (index, inserted) = guard.create_term_array(symbol, &arguments);
 
         if inserted {
-            self.trigger_garbage_collection();
+            self.trigger_garbage_collection(1);
         }
 
         self.protect_guard(guard, &unsafe { ATermRef::from_index(&index) })
@@ -259,7 +286,21 @@ impl ThreadTermPool {
     }
 
     /// Protect the term by adding its index to the protection set
-    pub fn protect_guard(&self, _guard: RecursiveLockReadGuard<'_, GlobalTermPool>, term: &ATermRef<'_>) -> ATerm {
+    pub fn protect_guard(&self, guard: RecursiveLockReadGuard<'_, GlobalTermPool>, term: &ATermRef<'_>) -> ATerm {
+        self.protect_locked(&guard, term)
+    }
+
+    /// Returns a [TermFactory] that creates terms while holding the lock on the global term pool.
+    pub fn factory(&self) -> TermFactory<'_> {
+        TermFactory {
+            pool: self,
+            guard: Some(self.term_pool.read_recursive().expect("Lock poisoned!")),
+            insertions: Cell::new(0),
+        }
+    }
+
+    /// Protect the term by adding its index to the protection set, for which the global term pool must be locked.
+    fn protect_locked(&self, _guard: &RecursiveLockReadGuard<'_, GlobalTermPool>, term: &ATermRef<'_>) -> ATerm {
         // Protect the term by adding its index to the protection set
         // SAFETY: If the global term pool is locked, so we can safely access the protection set.
         let root = unsafe { &mut *self.protection_set.get() }
@@ -365,7 +406,7 @@ impl ThreadTermPool {
     }
 
     /// Returns access to the shared protection set.
-    pub(crate) fn get_protection_set(&self) -> &Arc<UnsafeCell<SharedTermProtection>> {
+    pub(crate) fn get_protection_set(&self) -> &Arc<ThreadProtection> {
         &self.protection_set
     }
 
@@ -388,19 +429,24 @@ impl ThreadTermPool {
             .replace(root, term);
     }
 
-    /// This triggers the global garbage collection based on heuristics.
-    fn trigger_garbage_collection(&self) {
+    /// This triggers the global garbage collection based on heuristics, given the number of newly inserted terms.
+    fn trigger_garbage_collection(&self, insertions: usize) {
         // If the term was newly inserted, decrease the garbage collection counter and trigger garbage collection if necessary
         let mut value = self.garbage_collection_counter.get();
-        value = value.saturating_sub(1);
-
-        if value == 0 && !self.term_pool.is_locked() {
-            // Trigger garbage collection and acquire a new counter value.
-            value = self
-                .term_pool
-                .write()
-                .expect("Lock poisoned!")
-                .trigger_garbage_collection();
+        value = value.saturating_sub(insertions);
+
+        // A collection inside a read or write section, for example in a deletion hook, is postponed until the
+        // lock is released, since the read lock cannot be upgraded and the terms in a write section are in use.
+        if value == 0 && !self.term_pool.is_held() {
+            // Only upgrade to exclusive access when a collection is actually performed, other readers can
+            // continue in the meantime and no writer can interleave between the check and the upgrade.
+            let guard = self.term_pool.upgradable_read().expect("Lock poisoned!");
+            value = if guard.is_garbage_collection_enabled() {
+                // Trigger garbage collection and acquire a new counter value.
+                guard.upgrade().trigger_garbage_collection()
+            } else {
+                guard.garbage_collection_counter()
+            };
         }
 
         self.garbage_collection_counter.set(value);
@@ -436,6 +482,98 @@ impl Drop for ThreadTermPool {
     }
 }
 
+/// Creates terms while holding the (recursive) read lock on the global term
+/// pool, such that the lock is only acquired once for many terms. Obtained
+/// using [ThreadTermPool::factory].
+///
+/// # Details
+///
+/// Garbage collection cannot happen while the lock is held, so it is only
+/// (potentially) triggered when the factory is dropped. A factory should
+/// therefore be used for batches of terms and not be kept alive indefinitely.
+pub struct TermFactory<'a> {
+    pool: &'a ThreadTermPool,
+
+    /// Only None during drop.
+    guard: Option<RecursiveLockReadGuard<'a, GlobalTermPool>>,
+
+    /// The number of terms that were newly inserted into the global term pool.
+    insertions: Cell<usize>,
+}
+
+impl TermFactory<'_> {
+    /// Creates a term without arguments.
+    pub fn create_constant(&self, symbol: &SymbolRef<'_>) -> ATerm {
+        assert!(symbol.arity() == 0, "A constant should not have arity > 0");
+
+        let empty_args: [ATermRef<'_>; 0] = [];
+        self.create_term(symbol, &empty_args)
+    }
+
+    /// Creates a term with the given arguments.
+    pub fn create_term<'a, 'b>(&self, symbol: &'b impl Symb<'a, 'b>, args: &'b [impl Term<'a, 'b>]) -> ATerm {
+        self.create_term_iter(symbol, args)
+    }
+
+    /// Creates a term with the given arguments given by the iterator.
+    pub fn create_term_iter<'a, 'b, 'c, 'd, I, T>(&self, symbol: &'b impl Symb<'a, 'b>, args: I) -> ATerm
+    where
+        I: IntoIterator<Item = T>,
+        T: Term<'c, 'd>,
+    {
+        let mut arguments = self.pool.tmp_arguments.borrow_mut();
+        arguments.clear();
+        for arg in args {
+            unsafe {
+                arguments.push(ATermRef::from_index(arg.shared()));
+            }
+        }
+
+        let guard = self.guard();
+        let (index, inserted) = guard.create_term_array(symbol, &arguments);
+        self.count(inserted);
+
+        self.pool
+            .protect_locked(guard, &unsafe { ATermRef::from_index(&index) })
+    }
+
+    /// Creates a term storing a single integer value.
+    pub fn create_int(&self, value: usize) -> ATerm {
+        let guard = self.guard();
+        let (index, inserted) = guard.create_int(value);
+        self.count(inserted);
+
+        self.pool
+            .protect_locked(guard, &unsafe { ATermRef::from_index(&index) })
+    }
+
+    /// Returns the number of terms created by this factory that were not yet in the global term pool.
+    pub fn insertions(&self) -> usize {
+        self.insertions.get()
+    }
+
+    fn guard(&self) -> &RecursiveLockReadGuard<'_, GlobalTermPool> {
+        self.guard.as_ref().expect("The guard is only taken on drop")
+    }
+
+    fn count(&self, inserted: bool) {
+        if inserted {
+            self.insertions.set(self.insertions.get() + 1);
+        }
+    }
+}
+
+impl Drop for TermFactory<'_> {
+    fn drop(&mut self) {
+        // Release the lock first, since garbage collection requires exclusive access.
+        drop(self.guard.take());
+
+        if self.insertions.get() > 0 {
+            self.pool.trigger_garbage_collection(self.insertions.get());
+        }
+    }
+}
+
 struct ProtectionSetGuard<'a> {
     _guard: RecursiveLockReadGuard<'a, GlobalTermPool>,
     object: &'a mut SharedTermProtection,
@@ -469,6 +607,7 @@ mod tests {
     use crate::Term;
 
     use super::*;
+    use crate::ATermSend;
     use std::thread;
 
     #[test]
@@ -500,6 +639,57 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_deferred_unprotect() {
+        let _ = merc_utilities::test_logger();
+
+        let term = ATerm::from_string("f(g(a),b)").unwrap();
+        let root = term.root();
+        let term = ATermSend::from(term);
+
+        // Dropping the term on another thread must not modify the protection set of this thread.
+        thread::scope(|scope| {
+            scope.spawn(move || drop(term));
+        });
+
+        THREAD_TERM_POOL.with_borrow(|tp| {
+            assert!(tp.lock_protection_set().protection_set.contains_root(root));
+            assert_eq!(tp.get_protection_set().number_of_deferred(), 1);
+
+            tp.term_pool()
+                .write()
+                .expect("Lock poisoned!")
+                .trigger_garbage_collection();
+
+            assert!(!tp.lock_protection_set().protection_set.contains_root(root));
+            assert_eq!(tp.get_protection_set().number_of_deferred(), 0);
+        });
+    }
+
+    #[test]
+    fn test_term_factory() {
+        let _ = merc_utilities::test_logger();
+
+        let f = Symbol::new("f", 2);
+        let a = Symbol::new("a", 0);
+
+        let t = THREAD_TERM_POOL.with_borrow(|tp| {
+            let factory = tp.factory();
+
+            let mut term = factory.create_constant(&a);
+            for _ in 0..100 {
+                let value = factory.create_int(42);
+                let next = factory.create_term(&f, &[term.copy(), value.copy()]);
+                term = next;
+            }
+
+            term
+        });
+
+        assert_eq!(t.get_head_symbol(), f.copy());
+        assert_eq!(t.arg(1).annotation(), Some(42));
+    }
+
     #[test]
     fn test_parsing() {
         let _ = merc_utilities::test_logger();
@@ -535,3 +725,76 @@ mod tests {
         assert!(t.arg(1).get_head_symbol().name() == "b");
     }
 }
+
+#[cfg(all(test, loom))]
+mod loom_tests {
+    use crate::ATerm;
+    use crate::ATermSend;
+    use crate::Symbol;
+    use crate::Term;
+
+    use super::*;
+
+    /// Creates, protects and drops terms on two threads, where one of them triggers garbage collection.
+    #[test]
+    fn test_loom_create_protect_collect() {
+        loom::model(|| {
+            let threads: Vec<_> = (0..2)
+                .map(|index| {
+                    loom::thread::spawn(move || {
+                        let symbol = Symbol::new("f", 1);
+                        let argument = ATerm::constant(&Symbol::new(format!("a{index}"), 0));
+                        let protected = ATerm::with_args(&symbol, &[argument.clone()]).protect();
+
+                        if index == 0 {
+                            THREAD_TERM_POOL.with_borrow(|tp| {
+                                tp.term_pool()
+                                    .upgradable_read()
+                                    .expect("Lock poisoned!")
+                                    .upgrade()
+                                    .trigger_garbage_collection();
+                            });
+                        }
+
+                        // The protected term must survive the garbage collection.
+                        assert_eq!(protected.arg(0), argument.copy());
+                    })
+                })
+                .collect();
+
+            for thread in threads {
+                thread.join().unwrap();
+            }
+        });
+    }
+
+    /// Drops a term on another thread while the owning thread triggers garbage collection.
+    #[test]
+    fn test_loom_deferred_unprotect() {
+        loom::model(|| {
+            let term = ATerm::constant(&Symbol::new("a", 0));
+            let root = term.root();
+            let term = ATermSend::from(term);
+
+            let thread = loom::thread::spawn(move || drop(term));
+
+            THREAD_TERM_POOL.with_borrow(|tp| {
+                tp.term_pool()
+                    .write()
+                    .expect("Lock poisoned!")
+                    .trigger_garbage_collection();
+            });
+
+            thread.join().unwrap();
+
+            THREAD_TERM_POOL.with_borrow(|tp| {
+                tp.term_pool()
+                    .write()
+                    .expect("Lock poisoned!")
+                    .trigger_garbage_collection();
+
+                assert!(!tp.lock_protection_set().protection_set.contains_root(root));
+            });
+        });
+    }
+}
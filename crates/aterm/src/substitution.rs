@@ -0,0 +1,133 @@
+#![forbid(unsafe_code)]
+
+use std::collections::HashMap;
+use std::hash::BuildHasher;
+
+use rustc_hash::FxHashMap;
+
+use crate::ATerm;
+use crate::ATermRef;
+use crate::Term;
+use crate::storage::THREAD_TERM_POOL;
+
+/// Replaces every subterm `t` of the given term for which `function(t)`
+/// returns `Some(u)` by `u`, without recursing into `u`.
+///
+/// # Details
+///
+/// Since terms are maximally shared, every distinct subterm is visited only
+/// once and the results are memoized, so the complexity is linear in the
+/// number of distinct subterms rather than in the size of the term tree.
+/// Subterms that are unchanged are reused instead of being recreated. The
+/// traversal does not use recursion to avoid stack overflows on deep terms.
+pub fn replace_subterms<'a, 'b, F>(term: &'b impl Term<'a, 'b>, mut function: F) -> ATerm
+where
+    F: FnMut(&ATermRef<'a>) -> Option<ATerm>,
+{
+    let mut results: FxHashMap<ATermRef<'a>, ATerm> = FxHashMap::default();
+    let mut stack: Vec<(ATermRef<'a>, bool)> = vec![(term.copy(), false)];
+
+    THREAD_TERM_POOL.with_borrow(|tp| {
+        let factory = tp.factory();
+
+        while let Some((current, expanded)) = stack.pop() {
+            if results.contains_key(&current) {
+                continue;
+            }
+
+            if !expanded {
+                if let Some(replacement) = function(&current) {
+                    results.insert(current, replacement);
+                } else {
+                    stack.push((current.copy(), true));
+                    for arg in current.arguments() {
+                        if !results.contains_key(&arg) {
+                            stack.push((arg, false));
+                        }
+                    }
+                }
+            } else {
+                let changed = current.arguments().any(|arg| results[&arg].copy() != arg);
+
+                let result = if changed {
+                    factory.create_term_iter(
+                        &current.get_head_symbol(),
+                        current.arguments().map(|arg| results[&arg].copy()),
+                    )
+                } else {
+                    current.protect()
+                };
+
+                results.insert(current, result);
+            }
+        }
+    });
+
+    results
+        .remove(&term.copy())
+        .expect("The result for the input term has been computed")
+}
+
+/// Applies the substitution `sigma`, which maps (typically) variables to
+/// terms, to the given term. See [replace_subterms] for details.
+///
+/// # Details
+///
+/// Terms do not contain binders, so the substitution is always capture free.
+/// The terms in the image of `sigma` are not substituted themselves.
+pub fn apply_substitution<'a, 'b, S: BuildHasher>(
+    term: &'b impl Term<'a, 'b>,
+    sigma: &HashMap<ATerm, ATerm, S>,
+) -> ATerm {
+    if sigma.is_empty() {
+        return term.protect();
+    }
+
+    replace_subterms(term, |subterm| sigma.get(subterm).cloned())
+}
+
+#[cfg(test)]
+mod tests {
+    use merc_utilities::random_test;
+
+    use crate::random_term;
+
+    use super::*;
+
+    #[test]
+    fn test_apply_substitution() {
+        let _ = merc_utilities::test_logger();
+
+        let term = ATerm::from_string("f(x, g(x), h(y, z))").unwrap();
+
+        let mut sigma: FxHashMap<ATerm, ATerm> = FxHashMap::default();
+        sigma.insert(ATerm::from_string("x").unwrap(), ATerm::from_string("g(x)").unwrap());
+        sigma.insert(ATerm::from_string("y").unwrap(), ATerm::from_string("a").unwrap());
+
+        assert_eq!(
+            apply_substitution(&term, &sigma),
+            ATerm::from_string("f(g(x), g(g(x)), h(a, z))").unwrap()
+        );
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_random_identity_substitution() {
+        random_test(100, |rng| {
+            let term = random_term(rng, &[("f".into(), 2), ("g".into(), 1)], &["a".into(), "b".into()], 10);
+
+            // Replacing nothing does not change the term, and every distinct subterm is visited once.
+            let mut visited = Vec::new();
+            let result = replace_subterms(&term, |subterm| {
+                visited.push(subterm.protect());
+                None
+            });
+
+            assert_eq!(result, term);
+            let number_of_visits = visited.len();
+            visited.sort();
+            visited.dedup();
+            assert_eq!(visited.len(), number_of_visits);
+        });
+    }
+}
@@ -0,0 +1,235 @@
+#![forbid(unsafe_code)]
+
+use std::fmt;
+
+use crate::ATerm;
+use crate::Symb;
+use crate::Symbol;
+use crate::SymbolRef;
+use crate::Term;
+use crate::storage::THREAD_TERM_POOL;
+use crate::storage::TermFactory;
+
+/// An index of a term in a [TermArena].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ArenaIndex(usize);
+
+/// A scoped arena for intermediate terms that are not maximally shared.
+///
+/// # Details
+///
+/// Creating a term in the arena only appends it to a vector, so it does not
+/// require hashing, locking the global term pool or garbage collection. This is
+/// useful for algorithms such as rewriting that create many short-lived
+/// intermediate terms. Only the final result is interned into the global term
+/// pool by [TermArena::intern]. Existing (shared) terms can be embedded using
+/// [TermArena::shared], and all terms are kept alive until the arena is cleared
+/// or dropped.
+#[derive(Default)]
+pub struct TermArena {
+    nodes: Vec<Node>,
+
+    /// The arguments of all applications, every application refers to a consecutive range.
+    arguments: Vec<ArenaIndex>,
+
+    /// Stack and intermediate results used by intern, to avoid reallocations.
+    stack: Vec<(ArenaIndex, bool)>,
+    results: Vec<Option<ATerm>>,
+}
+
+enum Node {
+    /// A maximally shared term from the global term pool.
+    Shared(ATerm),
+    /// A function symbol applied to the arguments in the given range of `arguments`.
+    Application { symbol: Symbol, first: usize },
+}
+
+impl TermArena {
+    /// Creates a new empty arena.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of terms in the arena.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns true iff the arena contains no terms.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Removes all terms from the arena, invalidating all previously returned indices.
+    pub fn clear(&mut self) {
+        self.nodes.clear();
+        self.arguments.clear();
+    }
+
+    /// Embeds the given shared term into the arena.
+    pub fn shared<'a, 'b>(&mut self, term: &'b impl Term<'a, 'b>) -> ArenaIndex {
+        self.nodes.push(Node::Shared(term.protect()));
+        ArenaIndex(self.nodes.len() - 1)
+    }
+
+    /// Creates the application of the given symbol to the given arguments in the arena.
+    pub fn create(&mut self, symbol: &SymbolRef<'_>, args: &[ArenaIndex]) -> ArenaIndex {
+        debug_assert_eq!(
+            symbol.arity(),
+            args.len(),
+            "The number of arguments does not match the arity of {symbol}"
+        );
+        debug_assert!(
+            args.iter().all(|arg| arg.0 < self.nodes.len()),
+            "The arguments must be created before the term itself"
+        );
+
+        let first = self.arguments.len();
+        self.arguments.extend_from_slice(args);
+        self.nodes.push(Node::Application {
+            symbol: symbol.protect(),
+            first,
+        });
+
+        ArenaIndex(self.nodes.len() - 1)
+    }
+
+    /// Returns the head symbol of the given term.
+    pub fn head_symbol(&self, index: ArenaIndex) -> SymbolRef<'_> {
+        match &self.nodes[index.0] {
+            Node::Shared(term) => term.get_head_symbol(),
+            Node::Application { symbol, .. } => symbol.copy(),
+        }
+    }
+
+    /// Returns the arguments of the given term if it is created in the arena,
+    /// or None for shared terms. Use [TermArena::shared_term] to inspect shared terms.
+    pub fn arguments(&self, index: ArenaIndex) -> Option<&[ArenaIndex]> {
+        match &self.nodes[index.0] {
+            Node::Shared(_) => None,
+            Node::Application { symbol, first } => Some(&self.arguments[*first..*first + symbol.arity()]),
+        }
+    }
+
+    /// Returns the shared term if the given term was embedded using [TermArena::shared].
+    pub fn shared_term(&self, index: ArenaIndex) -> Option<&ATerm> {
+        match &self.nodes[index.0] {
+            Node::Shared(term) => Some(term),
+            Node::Application { .. } => None,
+        }
+    }
+
+    /// Interns the given term, and all its subterms, into the global term pool.
+    ///
+    /// # Details
+    ///
+    /// Subterms that occur multiple times in the arena, because their index is
+    /// used more than once, are only interned once. The interning does not use
+    /// recursion to avoid stack overflows on deep terms, and all terms are
+    /// created using a single [TermFactory].
+    pub fn intern(&mut self, index: ArenaIndex) -> ATerm {
+        self.results.clear();
+        self.results.resize(self.nodes.len(), None);
+
+        self.stack.clear();
+        self.stack.push((index, false));
+
+        THREAD_TERM_POOL.with_borrow(|tp| self.intern_with(&tp.factory()));
+
+        let result = self.results[index.0].take().expect("The term has been interned");
+        self.results.clear();
+        result
+    }
+
+    /// Interns the terms on the stack using the given factory, see [TermArena::intern].
+    fn intern_with(&mut self, factory: &TermFactory<'_>) {
+        while let Some((current, expanded)) = self.stack.pop() {
+            if self.results[current.0].is_some() {
+                continue;
+            }
+
+            match &self.nodes[current.0] {
+                Node::Shared(term) => {
+                    self.results[current.0] = Some(term.clone());
+                }
+                Node::Application { symbol, first } => {
+                    let args = &self.arguments[*first..*first + symbol.arity()];
+
+                    if expanded {
+                        let results = &self.results;
+                        let term = factory.create_term_iter(
+                            symbol,
+                            args.iter()
+                                .map(|arg| results[arg.0].as_ref().expect("Arguments are interned first").copy()),
+                        );
+                        self.results[current.0] = Some(term);
+                    } else {
+                        self.stack.push((current, true));
+                        for arg in args {
+                            if self.results[arg.0].is_none() {
+                                self.stack.push((*arg, false));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Debug for TermArena {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "TermArena with {} terms", self.nodes.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use merc_utilities::random_test;
+
+    use crate::ATermRef;
+    use crate::random_term;
+
+    use super::*;
+
+    /// Copies the given term into the arena, one application at a time.
+    fn copy_into(arena: &mut TermArena, term: &ATermRef<'_>) -> ArenaIndex {
+        if term.arguments().is_empty() {
+            return arena.shared(term);
+        }
+
+        let args: Vec<ArenaIndex> = term.arguments().map(|arg| copy_into(arena, &arg)).collect();
+        arena.create(&term.get_head_symbol(), &args)
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_random_intern() {
+        random_test(100, |rng| {
+            let term = random_term(rng, &[("f".into(), 2), ("g".into(), 1)], &["a".into(), "b".into()], 10);
+
+            let mut arena = TermArena::new();
+            let index = copy_into(&mut arena, &term.copy());
+
+            assert_eq!(arena.head_symbol(index), term.get_head_symbol());
+            assert_eq!(arena.intern(index), term);
+        });
+    }
+
+    #[test]
+    fn test_intern_shared_subterm() {
+        let _ = merc_utilities::test_logger();
+
+        let mut arena = TermArena::new();
+        let a = arena.shared(&ATerm::from_string("a").unwrap());
+        let g = arena.create(&Symbol::new("g", 1).copy(), &[a]);
+        let f = arena.create(&Symbol::new("f", 2).copy(), &[g, g]);
+
+        assert_eq!(arena.arguments(f), Some(&[g, g][..]));
+        assert!(arena.shared_term(a).is_some());
+        assert_eq!(arena.intern(f), ATerm::from_string("f(g(a),g(a))").unwrap());
+
+        arena.clear();
+        assert!(arena.is_empty());
+    }
+}
@@ -0,0 +1,257 @@
+use std::mem::transmute;
+
+use rustc_hash::FxHashMap;
+use rustc_hash::FxHashSet;
+
+use crate::ATermIndex;
+use crate::ATermRef;
+use crate::Markable;
+use crate::SymbolRef;
+use crate::Term;
+use crate::Transmutable;
+use crate::storage::Marker;
+
+/// A map from terms to values that is keyed by the identity of the term, i.e.,
+/// its [ATermIndex], which makes lookups cheap since terms are maximally shared.
+///
+/// # Details
+///
+/// The keys are not protected individually, instead the map should be stored in
+/// a [crate::Protected] container, which marks every key and value during
+/// garbage collection. This keeps the entries valid for as long as they are in
+/// the map, for example in a cache of normal forms. Values that contain terms
+/// must be protected by the write guard before they are inserted.
+pub struct TermMap<V> {
+    map: FxHashMap<ATermIndex, V>,
+}
+
+impl<V> TermMap<V> {
+    /// Creates an empty map.
+    pub fn new() -> Self {
+        Self {
+            map: FxHashMap::default(),
+        }
+    }
+
+    /// Inserts the value for the given term, and returns the previous value if it was present.
+    pub fn insert<'a, 'b>(&mut self, term: &'b impl Term<'a, 'b>, value: V) -> Option<V> {
+        self.map.insert(term.shared().copy(), value)
+    }
+
+    /// Returns the value of the given term, if present.
+    pub fn get<'a, 'b>(&self, term: &'b impl Term<'a, 'b>) -> Option<&V> {
+        self.map.get(term.shared())
+    }
+
+    /// Returns a mutable reference to the value of the given term, if present.
+    pub fn get_mut<'a, 'b>(&mut self, term: &'b impl Term<'a, 'b>) -> Option<&mut V> {
+        self.map.get_mut(term.shared())
+    }
+
+    /// Returns true iff the map contains the given term.
+    pub fn contains_key<'a, 'b>(&self, term: &'b impl Term<'a, 'b>) -> bool {
+        self.map.contains_key(term.shared())
+    }
+
+    /// Removes the given term from the map, and returns its value if it was present.
+    pub fn remove<'a, 'b>(&mut self, term: &'b impl Term<'a, 'b>) -> Option<V> {
+        self.map.remove(term.shared())
+    }
+
+    /// Removes all entries, which allows their terms to be garbage collected.
+    pub fn clear(&mut self) {
+        self.map.clear()
+    }
+
+    /// Only keeps the entries for which the given predicate returns true.
+    pub fn retain(&mut self, mut predicate: impl FnMut(ATermRef<'_>, &mut V) -> bool) {
+        // The keys are valid during the call, since they are marked by the map.
+        self.map
+            .retain(|index, value| predicate(unsafe { ATermRef::from_index(index) }, value))
+    }
+
+    /// Returns an iterator over the terms and their values.
+    pub fn iter(&self) -> impl Iterator<Item = (ATermRef<'_>, &V)> {
+        // The keys are valid for the lifetime of the map, since they are marked by it.
+        self.map
+            .iter()
+            .map(|(index, value)| (unsafe { ATermRef::from_index(index) }, value))
+    }
+
+    /// Returns the number of entries in the map.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns true iff the map is empty.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+}
+
+impl<V> Default for TermMap<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V: Markable> Markable for TermMap<V> {
+    fn mark(&self, marker: &mut Marker) {
+        for (term, value) in self.iter() {
+            marker.mark(&term);
+            value.mark(marker);
+        }
+    }
+
+    fn contains_term(&self, term: &ATermRef<'_>) -> bool {
+        self.map.contains_key(term.shared()) || self.map.values().any(|value| value.contains_term(term))
+    }
+
+    fn contains_symbol(&self, symbol: &SymbolRef<'_>) -> bool {
+        self.iter()
+            .any(|(term, value)| term.get_head_symbol() == *symbol || value.contains_symbol(symbol))
+    }
+
+    fn len(&self) -> usize {
+        self.map.len()
+    }
+}
+
+impl<V: Transmutable> Transmutable for TermMap<V> {
+    type Target<'a>
+        = TermMap<V::Target<'a>>
+    where
+        V: 'a;
+
+    fn transmute_lifetime<'a>(&self) -> &'a Self::Target<'a> {
+        unsafe { transmute::<&Self, &'a TermMap<V::Target<'a>>>(self) }
+    }
+
+    fn transmute_lifetime_mut<'a>(&mut self) -> &'a mut Self::Target<'a> {
+        unsafe { transmute::<&mut Self, &'a mut TermMap<V::Target<'a>>>(self) }
+    }
+}
+
+/// A set of terms that is keyed by the identity of the term, see [TermMap] for
+/// the garbage collection semantics.
+#[derive(Default)]
+pub struct TermSet {
+    set: FxHashSet<ATermIndex>,
+}
+
+impl TermSet {
+    /// Creates an empty set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inserts the given term, and returns true iff it was not yet present.
+    pub fn insert<'a, 'b>(&mut self, term: &'b impl Term<'a, 'b>) -> bool {
+        self.set.insert(term.shared().copy())
+    }
+
+    /// Returns true iff the set contains the given term.
+    pub fn contains<'a, 'b>(&self, term: &'b impl Term<'a, 'b>) -> bool {
+        self.set.contains(term.shared())
+    }
+
+    /// Removes the given term, and returns true iff it was present.
+    pub fn remove<'a, 'b>(&mut self, term: &'b impl Term<'a, 'b>) -> bool {
+        self.set.remove(term.shared())
+    }
+
+    /// Removes all terms, which allows them to be garbage collected.
+    pub fn clear(&mut self) {
+        self.set.clear()
+    }
+
+    /// Returns an iterator over the terms in the set.
+    pub fn iter(&self) -> impl Iterator<Item = ATermRef<'_>> {
+        // The terms are valid for the lifetime of the set, since they are marked by it.
+        self.set.iter().map(|index| unsafe { ATermRef::from_index(index) })
+    }
+
+    /// Returns the number of terms in the set.
+    pub fn len(&self) -> usize {
+        self.set.len()
+    }
+
+    /// Returns true iff the set is empty.
+    pub fn is_empty(&self) -> bool {
+        self.set.is_empty()
+    }
+}
+
+impl Markable for TermSet {
+    fn mark(&self, marker: &mut Marker) {
+        for term in self.iter() {
+            marker.mark(&term);
+        }
+    }
+
+    fn contains_term(&self, term: &ATermRef<'_>) -> bool {
+        self.set.contains(term.shared())
+    }
+
+    fn contains_symbol(&self, symbol: &SymbolRef<'_>) -> bool {
+        self.iter().any(|term| term.get_head_symbol() == *symbol)
+    }
+
+    fn len(&self) -> usize {
+        self.set.len()
+    }
+}
+
+impl Transmutable for TermSet {
+    type Target<'a> = TermSet;
+
+    fn transmute_lifetime<'a>(&self) -> &'a Self::Target<'a> {
+        unsafe { transmute::<&Self, &'a TermSet>(self) }
+    }
+
+    fn transmute_lifetime_mut<'a>(&mut self) -> &'a mut Self::Target<'a> {
+        unsafe { transmute::<&mut Self, &'a mut TermSet>(self) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ATerm;
+    use crate::Protected;
+    use crate::storage::THREAD_TERM_POOL;
+
+    use super::*;
+
+    #[test]
+    fn test_term_map_garbage_collection() {
+        let _ = merc_utilities::test_logger();
+
+        let mut cache: Protected<TermMap<ATermRef<'static>>> = Protected::new(TermMap::new());
+        let mut visited: Protected<TermSet> = Protected::new(TermSet::new());
+
+        for i in 0..100 {
+            let term = ATerm::from_string(&format!("f(g({i}), a)")).unwrap();
+            let normal_form = ATerm::from_string(&format!("h({i})")).unwrap();
+
+            let mut write = cache.write();
+            let value = write.protect(&normal_form);
+            write.insert(&term, value);
+            drop(write);
+
+            visited.write().insert(&term);
+        }
+
+        // Create many terms to trigger garbage collection, after which the entries must still be valid.
+        for i in 0..10_000 {
+            let _ = THREAD_TERM_POOL.with_borrow(|tp| tp.create_int(i));
+        }
+
+        let read = cache.read();
+        assert_eq!(read.len(), 100);
+        for i in 0..100 {
+            let term = ATerm::from_string(&format!("f(g({i}), a)")).unwrap();
+            assert_eq!(read.get(&term).unwrap().to_string(), format!("h({i})"));
+            assert!(visited.read().contains(&term));
+        }
+    }
+}
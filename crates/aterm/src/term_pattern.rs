@@ -0,0 +1,231 @@
+#![forbid(unsafe_code)]
+
+use std::fmt;
+use std::ops::Index;
+
+use crate::ATermRef;
+use crate::Symb;
+use crate::Term;
+
+/// A pattern that can be matched against terms, see [Pattern::matches].
+///
+/// # Details
+///
+/// Patterns are typically constructed using the [crate::term_pattern] macro,
+/// for example `term_pattern!(f(_, ?x, g(?x)))` matches every term with head
+/// symbol `f` of arity three whose last argument is `g` applied to its second
+/// argument. A variable that occurs multiple times must be bound to the same
+/// term everywhere.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Pattern {
+    /// Matches every term.
+    Wildcard,
+    /// Matches every term, and binds it to the variable with the given name.
+    Variable(String),
+    /// Matches terms with the given head symbol name, whose arity is the
+    /// number of arguments, and whose arguments match the argument patterns.
+    Application(String, Vec<Pattern>),
+}
+
+impl Pattern {
+    /// Returns a pattern matching the constant with the given name.
+    pub fn constant(name: impl Into<String>) -> Pattern {
+        Pattern::Application(name.into(), Vec::new())
+    }
+
+    /// Returns a pattern matching the given name applied to terms matching the given arguments.
+    pub fn application(name: impl Into<String>, args: Vec<Pattern>) -> Pattern {
+        Pattern::Application(name.into(), args)
+    }
+
+    /// Returns a pattern binding any term to the variable with the given name.
+    pub fn variable(name: impl Into<String>) -> Pattern {
+        Pattern::Variable(name.into())
+    }
+
+    /// Returns the variable bindings when the given term matches this pattern, and None otherwise.
+    pub fn matches<'a, 'b>(&self, term: &'b impl Term<'a, 'b>) -> Option<Bindings<'_, 'a>> {
+        let mut bindings = Bindings { entries: Vec::new() };
+
+        if self.match_term(&term.copy(), &mut bindings) {
+            Some(bindings)
+        } else {
+            None
+        }
+    }
+
+    /// Returns true iff the given term matches this pattern.
+    pub fn is_match<'a, 'b>(&self, term: &'b impl Term<'a, 'b>) -> bool {
+        self.matches(term).is_some()
+    }
+
+    fn match_term<'p, 'a>(&'p self, term: &ATermRef<'a>, bindings: &mut Bindings<'p, 'a>) -> bool {
+        match self {
+            Pattern::Wildcard => true,
+            Pattern::Variable(name) => match bindings.get(name) {
+                Some(bound) => bound == term,
+                None => {
+                    bindings.entries.push((name.as_str(), term.copy()));
+                    true
+                }
+            },
+            Pattern::Application(name, args) => {
+                let symbol = term.get_head_symbol();
+                if symbol.arity() != args.len() || symbol.name() != name.as_str() {
+                    return false;
+                }
+
+                args.iter()
+                    .zip(term.arguments())
+                    .all(|(pattern, arg)| pattern.match_term(&arg, bindings))
+            }
+        }
+    }
+}
+
+impl fmt::Display for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Pattern::Wildcard => write!(f, "_"),
+            Pattern::Variable(name) => write!(f, "?{name}"),
+            Pattern::Application(name, args) => {
+                write!(f, "{name}")?;
+                if !args.is_empty() {
+                    write!(f, "(")?;
+                    for (index, arg) in args.iter().enumerate() {
+                        if index > 0 {
+                            write!(f, ", ")?;
+                        }
+                        write!(f, "{arg}")?;
+                    }
+                    write!(f, ")")?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
+/// The terms bound to the variables of a [Pattern] by a successful match.
+#[derive(Debug)]
+pub struct Bindings<'p, 'a> {
+    entries: Vec<(&'p str, ATermRef<'a>)>,
+}
+
+impl<'a> Bindings<'_, 'a> {
+    /// Returns the term bound to the given variable, if any.
+    pub fn get(&self, name: &str) -> Option<&ATermRef<'a>> {
+        self.entries
+            .iter()
+            .find_map(|(variable, term)| (*variable == name).then_some(term))
+    }
+
+    /// Returns the number of bound variables.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true iff no variables were bound.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl<'a> Index<&str> for Bindings<'_, 'a> {
+    type Output = ATermRef<'a>;
+
+    fn index(&self, name: &str) -> &Self::Output {
+        self.get(name)
+            .unwrap_or_else(|| panic!("Variable {name} is not bound by the pattern"))
+    }
+}
+
+/// Constructs a [Pattern] from a term-like syntax.
+///
+/// # Details
+///
+/// The syntax is `_` for a wildcard, `?x` for the variable `x`, and `f` or
+/// `f(p1, ..., pn)` for an application of the function symbol `f`. Names that
+/// are not valid identifiers can be given as string literals, for example
+/// `"<aterm_int>"`.
+///
+/// ```
+/// use merc_aterm::ATerm;
+/// use merc_aterm::Symb;
+/// use merc_aterm::Term;
+/// use merc_aterm::term_pattern;
+///
+/// let term = ATerm::from_string("f(a, g(b))").unwrap();
+/// let pattern = term_pattern!(f(_, g(?x)));
+/// let bindings = pattern.matches(&term).unwrap();
+/// assert_eq!(bindings["x"].get_head_symbol().name(), "b");
+/// ```
+#[macro_export]
+macro_rules! term_pattern {
+    (_) => {
+        $crate::Pattern::Wildcard
+    };
+    (? $variable:ident) => {
+        $crate::Pattern::variable(stringify!($variable))
+    };
+    ($name:ident ( $($args:tt)* )) => {
+        $crate::Pattern::application(stringify!($name), $crate::term_pattern_args!(@acc [] [] $($args)*))
+    };
+    ($name:literal ( $($args:tt)* )) => {
+        $crate::Pattern::application($name, $crate::term_pattern_args!(@acc [] [] $($args)*))
+    };
+    ($name:ident) => {
+        $crate::Pattern::constant(stringify!($name))
+    };
+    ($name:literal) => {
+        $crate::Pattern::constant($name)
+    };
+}
+
+/// Splits the arguments of a [term_pattern] on commas, and converts every argument into a pattern.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! term_pattern_args {
+    (@acc [$($done:expr),*] [$($current:tt)+] , $($rest:tt)*) => {
+        $crate::term_pattern_args!(@acc [$($done,)* $crate::term_pattern!($($current)+)] [] $($rest)*)
+    };
+    (@acc [$($done:expr),*] [$($current:tt)*] $next:tt $($rest:tt)*) => {
+        $crate::term_pattern_args!(@acc [$($done),*] [$($current)* $next] $($rest)*)
+    };
+    (@acc [$($done:expr),*] [$($current:tt)+]) => {
+        vec![$($done,)* $crate::term_pattern!($($current)+)]
+    };
+    (@acc [$($done:expr),*] []) => {
+        vec![$($done),*]
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ATerm;
+
+    use super::*;
+
+    #[test]
+    fn test_term_pattern() {
+        let _ = merc_utilities::test_logger();
+
+        let term = ATerm::from_string("f(a, g(b), g(a))").unwrap();
+
+        let pattern = term_pattern!(f(?x, g(_), g(?x)));
+        let bindings = pattern.matches(&term).unwrap();
+        assert_eq!(bindings.len(), 1);
+        assert_eq!(bindings["x"].get_head_symbol().name(), "a");
+
+        // The variable must be bound to the same term in both positions.
+        assert!(!term_pattern!(f(?x, g(?x), _)).is_match(&term));
+
+        // The arity must match.
+        assert!(!term_pattern!(f(_, _)).is_match(&term));
+        assert!(term_pattern!(f(a, _, _)).is_match(&term));
+        assert!(term_pattern!(_).is_match(&term));
+
+        assert_eq!(term_pattern!(f(?x, g(_), "b")).to_string(), "f(?x, g(_), b)");
+    }
+}
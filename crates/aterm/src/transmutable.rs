@@ -130,3 +130,15 @@ impl Transmutable for bool {
         unsafe { transmute::<&mut Self, &'a mut bool>(self) }
     }
 }
+
+impl Transmutable for usize {
+    type Target<'a> = usize;
+
+    fn transmute_lifetime<'a>(&self) -> &'a Self::Target<'a> {
+        unsafe { transmute::<&Self, &'a usize>(self) }
+    }
+
+    fn transmute_lifetime_mut<'a>(&mut self) -> &'a mut Self::Target<'a> {
+        unsafe { transmute::<&mut Self, &'a mut usize>(self) }
+    }
+}
@@ -5,6 +5,7 @@ use bitvec::bitvec;
 use bitvec::order::Lsb0;
 use delegate::delegate;
 use log::trace;
+use rayon::prelude::*;
 
 use merc_io::BytesFormatter;
 use merc_utilities::TagIndex;
@@ -32,11 +33,18 @@ macro_rules! bytevec {
 /// represent the largest number added so far. Note that the number of bytes
 /// used per entry is only increased over time as larger entries are added.
 ///
+/// Entries that require at most two or four bits, see
+/// [CompressedEntry::bits_required], are packed together into a single byte.
+/// This is useful for transition labels, which are typically dominated by a
+/// handful of labels.
+///
 /// TODO: The `drop()` function of `T` is never called.
 #[derive(Default, PartialEq, Eq, Clone)]
 pub struct ByteCompressedVec<T> {
     data: Vec<u8>,
-    bytes_per_entry: usize,
+    /// The number of bits used per entry, which is either 0, 2, 4 or a multiple of 8.
+    bits_per_entry: usize,
+    len: usize,
     _marker: PhantomData<T>,
 }
 
@@ -44,7 +52,8 @@ impl<T: CompressedEntry> ByteCompressedVec<T> {
     pub fn new() -> ByteCompressedVec<T> {
         ByteCompressedVec {
             data: Vec::new(),
-            bytes_per_entry: 0,
+            bits_per_entry: 0,
+            len: 0,
             _marker: PhantomData,
         }
     }
@@ -53,7 +62,8 @@ impl<T: CompressedEntry> ByteCompressedVec<T> {
     pub fn with_capacity(capacity: usize, bytes_per_entry: usize) -> ByteCompressedVec<T> {
         ByteCompressedVec {
             data: Vec::with_capacity(capacity * bytes_per_entry),
-            bytes_per_entry,
+            bits_per_entry: bytes_per_entry * u8::BITS as usize,
+            len: 0,
             _marker: PhantomData,
         }
     }
@@ -65,25 +75,63 @@ impl<T: CompressedEntry> ByteCompressedVec<T> {
     where
         I: ExactSizeIterator<Item = T> + Clone,
     {
-        let bytes_per_entry = iter
+        let bits_per_entry = iter
             .clone()
-            .fold(0, |max_bytes, entry| max_bytes.max(entry.bytes_required()));
+            .fold(0, |max_bits, entry| max_bits.max(entry.bits_required()));
 
-        let mut vec = ByteCompressedVec::with_capacity(iter.len(), bytes_per_entry);
+        let mut vec = ByteCompressedVec {
+            data: Vec::with_capacity(num_of_bytes(iter.len(), bits_per_entry)),
+            bits_per_entry,
+            len: 0,
+            _marker: PhantomData,
+        };
         for entry in iter {
             vec.push(entry);
         }
         vec
     }
 
+    /// Constructs the vector from the given slice, where the entries are
+    /// converted and packed in parallel.
+    pub fn from_slice_par(entries: &[T]) -> ByteCompressedVec<T>
+    where
+        T: Sync,
+    {
+        let bits_per_entry = entries.par_iter().map(|entry| entry.bits_required()).max().unwrap_or(0);
+
+        let mut data = vec![0; num_of_bytes(entries.len(), bits_per_entry)];
+        if bits_per_entry > 0 && bits_per_entry < u8::BITS as usize {
+            // Every byte is written by exactly one thread, and contains multiple entries.
+            let entries_per_byte = u8::BITS as usize / bits_per_entry;
+            data.par_iter_mut().enumerate().for_each(|(byte_index, byte)| {
+                let start = byte_index * entries_per_byte;
+                let end = (start + entries_per_byte).min(entries.len());
+                for (offset, entry) in entries[start..end].iter().enumerate() {
+                    *byte |= entry_to_byte(entry) << (offset * bits_per_entry);
+                }
+            });
+        } else if bits_per_entry > 0 {
+            data.par_chunks_mut(bits_per_entry / u8::BITS as usize)
+                .zip(entries.par_iter())
+                .for_each(|(bytes, entry)| entry.to_bytes(bytes));
+        }
+
+        ByteCompressedVec {
+            data,
+            bits_per_entry,
+            len: entries.len(),
+            _marker: PhantomData,
+        }
+    }
+
     /// Adds a new entry to the vector.
     pub fn push(&mut self, entry: T) {
-        self.resize_entries(entry.bytes_required());
+        self.resize_entries(entry.bits_required());
 
         // Add the new entry to the end of the vector.
-        let old_len = self.data.len();
-        self.data.resize(old_len + self.bytes_per_entry, 0);
-        entry.to_bytes(&mut self.data[old_len..]);
+        self.len += 1;
+        self.data.resize(num_of_bytes(self.len, self.bits_per_entry), 0);
+        write_entry(&mut self.data, self.bits_per_entry, self.len - 1, &entry);
     }
 
     /// Removes the last element from the vector and returns it, or None if it is empty.
@@ -93,35 +141,26 @@ impl<T: CompressedEntry> ByteCompressedVec<T> {
         } else {
             let index = self.len() - 1;
             let entry = self.index(index);
-            self.data.truncate(index * self.bytes_per_entry);
+            self.truncate(index);
             Some(entry)
         }
     }
 
     /// Returns the entry at the given index.
     pub fn index(&self, index: usize) -> T {
-        let start = index * self.bytes_per_entry;
-        let end = start + self.bytes_per_entry;
-        T::from_bytes(&self.data[start..end])
+        debug_assert!(index < self.len, "Index {index} out of bounds {}", self.len);
+        read_entry(&self.data, self.bits_per_entry, index)
     }
 
     /// Sets the entry at the given index.
     pub fn set(&mut self, index: usize, entry: T) {
-        self.resize_entries(entry.bytes_required());
-
-        let start = index * self.bytes_per_entry;
-        let end = start + self.bytes_per_entry;
-        entry.to_bytes(&mut self.data[start..end]);
+        self.resize_entries(entry.bits_required());
+        write_entry(&mut self.data, self.bits_per_entry, index, &entry);
     }
 
     /// Returns the number of elements in the vector.
     pub fn len(&self) -> usize {
-        if self.bytes_per_entry == 0 {
-            0
-        } else {
-            debug_assert!(self.data.len() % self.bytes_per_entry == 0);
-            self.data.len() / self.bytes_per_entry
-        }
+        self.len
     }
 
     /// Returns true if the vector is empty.
@@ -132,8 +171,10 @@ impl<T: CompressedEntry> ByteCompressedVec<T> {
     /// Returns metrics about memory usage of this compressed vector
     pub fn metrics(&self) -> CompressedVecMetrics {
         let element_count = self.len();
-        let actual_memory =
-            self.data.len() + std::mem::size_of_val(&self.bytes_per_entry) + std::mem::size_of::<PhantomData<T>>();
+        let actual_memory = self.data.len()
+            + std::mem::size_of_val(&self.bits_per_entry)
+            + std::mem::size_of_val(&self.len)
+            + std::mem::size_of::<PhantomData<T>>();
         let worst_case_memory = element_count * std::mem::size_of::<T>();
 
         CompressedVecMetrics {
@@ -287,7 +328,12 @@ impl<T: CompressedEntry> ByteCompressedVec<T> {
     where
         P: Fn(usize) -> usize,
     {
-        let mut result = ByteCompressedVec::with_capacity(self.data.capacity(), self.bytes_per_entry);
+        let mut result = ByteCompressedVec {
+            data: Vec::with_capacity(self.data.len()),
+            bits_per_entry: self.bits_per_entry,
+            len: 0,
+            _marker: PhantomData,
+        };
         for index in 0..self.len() {
             result.push(self.index(indices(index)));
         }
@@ -297,17 +343,10 @@ impl<T: CompressedEntry> ByteCompressedVec<T> {
     /// Swaps the entries at the given indices.
     pub fn swap(&mut self, index1: usize, index2: usize) {
         if index1 != index2 {
-            let start1 = index1 * self.bytes_per_entry;
-            let start2 = index2 * self.bytes_per_entry;
-
-            // Create a temporary buffer for one entry
-            let temp = T::from_bytes(&self.data[start1..start1 + self.bytes_per_entry]);
-
-            // Copy entry2 to entry1's position
-            self.data.copy_within(start2..start2 + self.bytes_per_entry, start1);
-
-            // Copy temp to entry2's position
-            temp.to_bytes(&mut self.data[start2..start2 + self.bytes_per_entry]);
+            let entry1 = self.index(index1);
+            let entry2 = self.index(index2);
+            write_entry(&mut self.data, self.bits_per_entry, index1, &entry2);
+            write_entry(&mut self.data, self.bits_per_entry, index2, &entry1);
         }
     }
 
@@ -319,50 +358,121 @@ impl<T: CompressedEntry> ByteCompressedVec<T> {
         let current_len = self.len();
         if new_len > current_len {
             // Preallocate the required space.
-            self.data.reserve(new_len * self.bytes_per_entry);
+            self.data.reserve(num_of_bytes(new_len, self.bits_per_entry));
             for _ in current_len..new_len {
                 self.push(f());
             }
         } else if new_len < current_len {
             if new_len == 0 {
                 self.data.clear();
-                self.bytes_per_entry = 0;
+                self.bits_per_entry = 0;
+                self.len = 0;
             } else {
-                // It could be that the bytes per entry is now less, but that we never reduce.
-                self.data.truncate(new_len * self.bytes_per_entry);
+                // It could be that the bits per entry is now less, but that we never reduce.
+                self.truncate(new_len);
             }
         }
     }
 
     /// Reserves capacity for at least additional more entries to be inserted with the given bytes per entry.
     pub fn reserve(&mut self, additional: usize, bytes_per_entry: usize) {
-        self.resize_entries(bytes_per_entry);
-        self.data.reserve(additional * self.bytes_per_entry);
+        self.resize_entries(bytes_per_entry * u8::BITS as usize);
+        self.data.reserve(num_of_bytes(additional, self.bits_per_entry));
+    }
+
+    /// Shortens the vector to the given length, which must be at most the current length.
+    fn truncate(&mut self, new_len: usize) {
+        // Clear the remaining entries in a partially used byte, such that equal vectors have equal data.
+        if self.bits_per_entry < u8::BITS as usize {
+            for index in new_len
+                ..self
+                    .len
+                    .min(num_of_bytes(new_len, self.bits_per_entry) * 8 / self.bits_per_entry)
+            {
+                clear_sub_byte_entry(&mut self.data, self.bits_per_entry, index);
+            }
+        }
+
+        self.len = new_len;
+        self.data.truncate(num_of_bytes(new_len, self.bits_per_entry));
     }
 
-    /// Resizes all entries in the vector to the given length.
-    fn resize_entries(&mut self, new_bytes_required: usize) {
-        if new_bytes_required > self.bytes_per_entry {
-            let mut new_data: Vec<u8> = vec![0; self.len() * new_bytes_required];
+    /// Resizes all entries in the vector to the given number of bits.
+    fn resize_entries(&mut self, new_bits_required: usize) {
+        if new_bits_required > self.bits_per_entry {
+            let mut new_data: Vec<u8> = vec![0; num_of_bytes(self.len, new_bits_required)];
 
-            if self.bytes_per_entry > 0 {
-                // Resize all the existing elements because the new entry requires more bytes.
+            if self.bits_per_entry > 0 {
+                // Resize all the existing elements because the new entry requires more bits.
                 for (index, entry) in self.iter().enumerate() {
-                    let start = index * new_bytes_required;
-                    let end = start + new_bytes_required;
-                    entry.to_bytes(&mut new_data[start..end]);
+                    write_entry(&mut new_data, new_bits_required, index, &entry);
                 }
             }
 
-            self.bytes_per_entry = new_bytes_required;
+            self.bits_per_entry = new_bits_required;
             self.data = new_data;
         }
     }
 }
 
+/// Returns the number of bytes required to store the given number of entries.
+fn num_of_bytes(len: usize, bits_per_entry: usize) -> usize {
+    (len * bits_per_entry).div_ceil(u8::BITS as usize)
+}
+
+/// Returns the mask and shift of the entry at the given index, for sub-byte entries.
+fn sub_byte_position(bits_per_entry: usize, index: usize) -> (usize, u8, usize) {
+    let entries_per_byte = u8::BITS as usize / bits_per_entry;
+    let mask = (1u8 << bits_per_entry) - 1;
+    (
+        index / entries_per_byte,
+        mask,
+        (index % entries_per_byte) * bits_per_entry,
+    )
+}
+
+/// Returns the single byte representation of an entry that fits in a byte.
+fn entry_to_byte<T: CompressedEntry>(entry: &T) -> u8 {
+    let mut byte = [0u8];
+    entry.to_bytes(&mut byte);
+    byte[0]
+}
+
+/// Reads the entry at the given index from the packed data.
+fn read_entry<T: CompressedEntry>(data: &[u8], bits_per_entry: usize, index: usize) -> T {
+    if bits_per_entry < u8::BITS as usize {
+        let (byte_index, mask, shift) = sub_byte_position(bits_per_entry, index);
+        T::from_bytes(&[(data[byte_index] >> shift) & mask])
+    } else {
+        let bytes_per_entry = bits_per_entry / u8::BITS as usize;
+        let start = index * bytes_per_entry;
+        T::from_bytes(&data[start..start + bytes_per_entry])
+    }
+}
+
+/// Writes the entry at the given index into the packed data.
+fn write_entry<T: CompressedEntry>(data: &mut [u8], bits_per_entry: usize, index: usize, entry: &T) {
+    if bits_per_entry < u8::BITS as usize {
+        let (byte_index, mask, shift) = sub_byte_position(bits_per_entry, index);
+        data[byte_index] = (data[byte_index] & !(mask << shift)) | ((entry_to_byte(entry) & mask) << shift);
+    } else {
+        let bytes_per_entry = bits_per_entry / u8::BITS as usize;
+        let start = index * bytes_per_entry;
+        entry.to_bytes(&mut data[start..start + bytes_per_entry]);
+    }
+}
+
+/// Clears the sub-byte entry at the given index in the packed data.
+fn clear_sub_byte_entry(data: &mut [u8], bits_per_entry: usize, index: usize) {
+    let (byte_index, mask, shift) = sub_byte_position(bits_per_entry, index);
+    data[byte_index] &= !(mask << shift);
+}
+
 impl<T: CompressedEntry + Clone> ByteCompressedVec<T> {
     pub fn from_elem(entry: T, n: usize) -> ByteCompressedVec<T> {
-        let mut vec = ByteCompressedVec::with_capacity(n, entry.bytes_required());
+        let mut vec = ByteCompressedVec::new();
+        vec.resize_entries(entry.bits_required());
+        vec.data.reserve(num_of_bytes(n, vec.bits_per_entry));
         for _ in 0..n {
             vec.push(entry.clone());
         }
@@ -372,6 +482,7 @@ impl<T: CompressedEntry + Clone> ByteCompressedVec<T> {
 
 /// Metrics for tracking memory usage of a ByteCompressedVec
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CompressedVecMetrics {
     /// Actual memory used by the compressed vector (in bytes)
     pub actual_memory: usize,
@@ -435,6 +546,13 @@ pub trait CompressedEntry {
 
     // Returns the number of bytes required to store the current entry
     fn bytes_required(&self) -> usize;
+
+    /// Returns the number of bits required to store the current entry, which
+    /// is either 2, 4 or a multiple of 8. Entries that require fewer than 8
+    /// bits must be representable by [CompressedEntry::to_bytes] in a single byte.
+    fn bits_required(&self) -> usize {
+        self.bytes_required() * u8::BITS as usize
+    }
 }
 
 impl CompressedEntry for usize {
@@ -456,6 +574,16 @@ impl CompressedEntry for usize {
     fn bytes_required(&self) -> usize {
         ((self + 1).ilog2() / u8::BITS) as usize + 1
     }
+
+    fn bits_required(&self) -> usize {
+        if *self < 4 {
+            2
+        } else if *self < 16 {
+            4
+        } else {
+            self.bytes_required() * u8::BITS as usize
+        }
+    }
 }
 
 impl<T: CompressedEntry + fmt::Debug> fmt::Debug for ByteCompressedVec<T> {
@@ -470,6 +598,7 @@ impl<T: CompressedEntry + Copy, Tag> CompressedEntry for TagIndex<T, Tag> {
         to self.value() {
             fn to_bytes(&self, bytes: &mut [u8]);
             fn bytes_required(&self) -> usize;
+            fn bits_required(&self) -> usize;
         }
     }
 
@@ -547,6 +676,62 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_random_sub_byte_bytevector() {
+        random_test(100, |rng| {
+            let max_value = [4, 16][rng.random_range(0..2)];
+            let expected_vector: Vec<usize> = (0..rng.random_range(1..100))
+                .map(|_| rng.random_range(0..max_value))
+                .collect();
+
+            let mut vector = ByteCompressedVec::new();
+            for element in &expected_vector {
+                vector.push(*element);
+            }
+
+            assert!(vector.data.len() <= expected_vector.len().div_ceil(8 / vector.bits_per_entry));
+            assert!(vector.iter().eq(expected_vector.iter().cloned()));
+
+            // Growing the entries beyond a byte preserves the packed entries.
+            vector.push(1024);
+            assert!(
+                vector
+                    .iter()
+                    .take(expected_vector.len())
+                    .eq(expected_vector.iter().cloned())
+            );
+            assert_eq!(vector.pop(), Some(1024));
+        });
+    }
+
+    #[test]
+    fn test_sub_byte_pop() {
+        let mut vec = ByteCompressedVec::new();
+        vec.push(1);
+        vec.push(2);
+        vec.push(3);
+        assert_eq!(vec.pop(), Some(3));
+
+        // The popped entry is cleared, such that the vectors are equal.
+        let mut expected = ByteCompressedVec::new();
+        expected.push(1);
+        expected.push(2);
+        assert_eq!(vec, expected);
+    }
+
+    #[test]
+    fn test_random_from_slice_par() {
+        random_test(100, |rng| {
+            let max_value = [4, 16, 1024][rng.random_range(0..3)];
+            let elements: Vec<usize> = (0..rng.random_range(0..100))
+                .map(|_| rng.random_range(0..max_value))
+                .collect();
+
+            let vec = ByteCompressedVec::from_slice_par(&elements);
+            assert_eq!(vec, ByteCompressedVec::with_iter(elements.iter().cloned()));
+        });
+    }
+
     #[test]
     fn test_swap() {
         let mut vec = ByteCompressedVec::new();
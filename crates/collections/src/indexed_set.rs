@@ -43,6 +43,15 @@ impl fmt::Display for SetIndex {
 }
 
 /// A set that assigns a unique index to every entry. The returned index can be used to access the inserted entry.
+///
+/// # Details
+///
+/// Iteration is always in the order of the indices, and is therefore
+/// deterministic and independent of the hasher. Elements removed with
+/// [IndexedSet::remove] free their position for later insertions, whereas
+/// elements removed with [IndexedSet::remove_tombstone] leave a tombstone
+/// such that their position is never reused. As long as only the latter is
+/// used, iteration is in insertion order.
 pub struct IndexedSet<T, S = FxBuildHasher> {
     /// The table of elements, which can be either filled or empty.
     table: Vec<IndexSetEntry<T>>,
@@ -56,10 +65,13 @@ pub struct IndexedSet<T, S = FxBuildHasher> {
     hasher: S,
 }
 
-/// An entry in the indexed set, which can either be filled or empty.
+/// An entry in the indexed set, which can either be filled, empty or a tombstone.
 enum IndexSetEntry<T> {
     Filled(T),
+    /// An empty position that is part of the free list, pointing to the next free position.
     Empty(usize),
+    /// A removed position that is not reused for insertions.
+    Tombstone,
 }
 
 impl<T, S: BuildHasher + Default> IndexedSet<T, S> {
@@ -102,7 +114,7 @@ impl<T, S> IndexedSet<T, S> {
         if let Some(entry) = self.table.get(self.generation_counter.get_index(index.0)) {
             match entry {
                 IndexSetEntry::Filled(element) => Some(element),
-                IndexSetEntry::Empty(_) => None,
+                IndexSetEntry::Empty(_) | IndexSetEntry::Tombstone => None,
             }
         } else {
             None
@@ -114,6 +126,30 @@ impl<T, S> IndexedSet<T, S> {
         self.table.capacity()
     }
 
+    /// Releases the positions after the last element, and shrinks the
+    /// capacity of the set as much as possible. The indices of the remaining
+    /// elements are unchanged.
+    pub fn shrink_to_fit(&mut self) {
+        while matches!(
+            self.table.last(),
+            Some(IndexSetEntry::Empty(_) | IndexSetEntry::Tombstone)
+        ) {
+            self.table.pop();
+        }
+
+        // Rebuild the free list, since some of its positions might have been removed.
+        self.free = None;
+        for index in (0..self.table.len()).rev() {
+            if let IndexSetEntry::Empty(_) = self.table[index] {
+                self.table[index] = IndexSetEntry::Empty(self.free.unwrap_or(index));
+                self.free = Some(index);
+            }
+        }
+
+        self.table.shrink_to_fit();
+        self.index.shrink_to_fit();
+    }
+
     /// Returns an iterator over the elements in the set.
     pub fn iter(&self) -> Iter<'_, T, S> {
         Iter {
@@ -156,7 +192,9 @@ impl<T: Hash + Eq, S: BuildHasher> IndexedSet<T, S> {
             Some(first) => {
                 let next = match self.table[first] {
                     IndexSetEntry::Empty(x) => x,
-                    IndexSetEntry::Filled(_) => panic!("The free list contains a filled element"),
+                    IndexSetEntry::Filled(_) | IndexSetEntry::Tombstone => {
+                        panic!("The free list contains a non-empty element")
+                    }
                 };
 
                 if first == next {
@@ -198,7 +236,9 @@ impl<T: Hash + Eq, S: BuildHasher> IndexedSet<T, S> {
             Some(first) => {
                 let next = match self.table[first] {
                     IndexSetEntry::Empty(x) => x,
-                    IndexSetEntry::Filled(_) => panic!("The free list contains a filled element"),
+                    IndexSetEntry::Filled(_) | IndexSetEntry::Tombstone => {
+                        panic!("The free list contains a non-empty element")
+                    }
                 };
 
                 if first == next {
@@ -284,6 +324,20 @@ impl<T: Hash + Eq, S: BuildHasher> IndexedSet<T, S> {
         }
     }
 
+    /// Removes the given element from the set, leaving a tombstone such that
+    /// its position is not reused by later insertions.
+    pub fn remove_tombstone(&mut self, element: &T) -> bool {
+        let equivalent = IndexValueEquivalent::new(element, &self.hasher, &self.table);
+
+        if let Some(entry) = self.index.take(&equivalent) {
+            self.table[entry.index] = IndexSetEntry::Tombstone;
+            true
+        } else {
+            // The element was not found in the set.
+            false
+        }
+    }
+
     /// Returns true iff the set contains the given element.
     pub fn contains<Q>(&self, element: &Q) -> bool
     where
@@ -494,4 +548,50 @@ mod tests {
             }
         })
     }
+
+    #[test]
+    fn test_random_indexed_set_insertion_order() {
+        random_test(100, |rng| {
+            let mut set: IndexedSet<usize> = IndexedSet::default();
+            let mut expected = vec![];
+
+            for _ in 0..100 {
+                let value = rng.random_range(0..32) as usize;
+                if rng.random_bool(0.3) {
+                    set.remove_tombstone(&value);
+                    expected.retain(|element| *element != value);
+                } else if set.insert(value).1 {
+                    expected.push(value);
+                }
+            }
+
+            // Iteration is in insertion order, also after shrinking.
+            assert!(set.iter().map(|(_, value)| *value).eq(expected.iter().cloned()));
+            set.shrink_to_fit();
+            assert!(set.iter().map(|(_, value)| *value).eq(expected.iter().cloned()));
+
+            for value in &expected {
+                assert!(set.contains(value));
+            }
+        })
+    }
+
+    #[test]
+    fn test_indexed_set_shrink_to_fit() {
+        let mut set: IndexedSet<usize> = IndexedSet::default();
+        for value in 0..10 {
+            set.insert(value);
+        }
+
+        set.remove(&2);
+        set.remove(&8);
+        set.remove_tombstone(&9);
+        set.shrink_to_fit();
+        assert_eq!(set.len(), 8);
+
+        // The freed position is reused after shrinking.
+        assert_eq!(*set.insert(20).0, 2);
+        assert_eq!(*set.insert(21).0, 8);
+        assert_eq!(*set.insert(22).0, 8 + 1);
+    }
 }
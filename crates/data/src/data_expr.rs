@@ -0,0 +1,147 @@
+use ahash::AHashSet;
+
+use merc_syntax::DataExpr;
+use merc_utilities::MercError;
+
+use crate::DataApplication;
+use crate::DataExpression;
+use crate::DataFunctionSymbol;
+use crate::DataVariable;
+use crate::Enumerator;
+
+/// Converts a parsed mCRL2 data expression into an untyped data expression,
+/// where the identifiers in `variables` become data variables.
+///
+/// # Details
+///
+/// Operators are represented by function symbols with the name of the
+/// operator, such that for example `x + 1` becomes `+(x, 1)`, and numbers are
+/// represented by constants. List enumerations `[a, b]` become `|>(a, |>(b,
+/// []))`, and set enumerations `{a, b}` insert the elements into `{}` with the
+/// `@fset_insert` function of the [crate::standard_data_specification]. Binders,
+/// such as quantifiers and lambda abstractions, comprehensions and bag
+/// enumerations are not supported. Quantifiers over finite sorts can be
+/// expanded by [Enumerator::to_data_expression].
+pub fn to_data_expression(expr: &DataExpr, variables: &AHashSet<String>) -> Result<DataExpression, MercError> {
+    convert(expr, variables, None)
+}
+
+/// Converts the data expression as [to_data_expression], where the quantifiers
+/// are expanded by the enumerator when it is given.
+pub(crate) fn convert(
+    expr: &DataExpr,
+    variables: &AHashSet<String>,
+    enumerator: Option<&Enumerator>,
+) -> Result<DataExpression, MercError> {
+    match expr {
+        DataExpr::Id(identifier) => {
+            if variables.contains(identifier) {
+                Ok(DataVariable::new(identifier.as_str()).into())
+            } else {
+                Ok(DataFunctionSymbol::new(identifier.as_str()).into())
+            }
+        }
+        DataExpr::Number(number) => Ok(DataFunctionSymbol::new(number.as_str()).into()),
+        DataExpr::Bool(value) => Ok(DataFunctionSymbol::new(if *value { "true" } else { "false" }).into()),
+        DataExpr::Application { function, arguments } => match function.as_ref() {
+            DataExpr::Id(identifier) if !variables.contains(identifier) => apply(
+                DataFunctionSymbol::new(identifier.as_str()),
+                arguments.iter(),
+                variables,
+                enumerator,
+            ),
+            _ => Err(MercError::unsupported(format!(
+                "Higher-order application {expr} is not supported"
+            ))),
+        },
+        DataExpr::Unary { op, expr } => apply(
+            DataFunctionSymbol::new(op.to_string()),
+            [expr.as_ref()],
+            variables,
+            enumerator,
+        ),
+        DataExpr::Binary { op, lhs, rhs } => apply(
+            DataFunctionSymbol::new(op.to_string()),
+            [lhs.as_ref(), rhs.as_ref()],
+            variables,
+            enumerator,
+        ),
+        DataExpr::Quantifier {
+            op,
+            variables: bound,
+            body,
+        } => match enumerator {
+            Some(enumerator) => enumerator.expand_quantifier(op, bound, body, variables),
+            None => Err(MercError::unsupported(format!(
+                "Quantifier {expr} is not supported without an enumerator"
+            ))),
+        },
+        DataExpr::EmptyList => Ok(DataFunctionSymbol::new("[]").into()),
+        DataExpr::EmptySet => Ok(DataFunctionSymbol::new("{}").into()),
+        DataExpr::EmptyBag => Ok(DataFunctionSymbol::new("{:}").into()),
+        DataExpr::List(elements) => {
+            elements
+                .iter()
+                .rev()
+                .try_fold(DataExpression::from(DataFunctionSymbol::new("[]")), |list, element| {
+                    let element = convert(element, variables, enumerator)?;
+                    Ok(DataApplication::with_args(&DataFunctionSymbol::new("|>"), &[element, list]).into())
+                })
+        }
+        DataExpr::Set(elements) => {
+            elements
+                .iter()
+                .try_fold(DataExpression::from(DataFunctionSymbol::new("{}")), |set, element| {
+                    let element = convert(element, variables, enumerator)?;
+                    Ok(DataApplication::with_args(&DataFunctionSymbol::new("@fset_insert"), &[element, set]).into())
+                })
+        }
+        _ => Err(MercError::unsupported(format!(
+            "Data expression {expr} is not supported"
+        ))),
+    }
+}
+
+/// Returns the application of the given function symbol to the converted arguments.
+fn apply<'a>(
+    symbol: DataFunctionSymbol,
+    arguments: impl IntoIterator<Item = &'a DataExpr>,
+    variables: &AHashSet<String>,
+    enumerator: Option<&Enumerator>,
+) -> Result<DataExpression, MercError> {
+    let arguments = arguments
+        .into_iter()
+        .map(|argument| convert(argument, variables, enumerator))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(DataApplication::with_args(&symbol, &arguments).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_data_expression() {
+        let expr = DataExpr::parse("f(x, 1) + g").unwrap();
+        let variables = AHashSet::from_iter(["x".to_string()]);
+
+        let result = to_data_expression(&expr, &variables).unwrap();
+        assert_eq!(result.to_string(), "+(f(x, 1), g)");
+    }
+
+    #[test]
+    fn test_to_data_expression_containers() {
+        let expr = DataExpr::parse("[1, x] ++ []").unwrap();
+        let variables = AHashSet::from_iter(["x".to_string()]);
+
+        let result = to_data_expression(&expr, &variables).unwrap();
+        assert_eq!(result.to_string(), "++(|>(1, |>(x, [])), [])");
+    }
+
+    #[test]
+    fn test_to_data_expression_unsupported() {
+        let expr = DataExpr::parse("forall n: Nat . n == n").unwrap();
+        assert!(to_data_expression(&expr, &AHashSet::new()).is_err());
+    }
+}
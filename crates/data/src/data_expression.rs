@@ -1,5 +1,4 @@
 use std::fmt;
-use std::ops::Deref;
 
 use ahash::AHashSet;
 use delegate::delegate;
@@ -7,23 +6,24 @@ use delegate::delegate;
 use merc_aterm::ATerm;
 use merc_aterm::ATermArgs;
 use merc_aterm::ATermIndex;
+use merc_aterm::ATermList;
 use merc_aterm::ATermRef;
 use merc_aterm::ATermString;
 use merc_aterm::Markable;
 use merc_aterm::Symb;
 use merc_aterm::SymbolRef;
 use merc_aterm::Term;
-use merc_aterm::TermBuilder;
 use merc_aterm::TermIterator;
 use merc_aterm::Transmutable;
-use merc_aterm::Yield;
+use merc_aterm::fold_term;
 use merc_aterm::storage::Marker;
-use merc_aterm::storage::THREAD_TERM_POOL;
 use merc_macros::merc_derive_terms;
 use merc_macros::merc_ignore;
 use merc_macros::merc_term;
 
-use crate::DATA_SYMBOLS;
+use crate::DATA_APPLICATION_SYMBOLS;
+use crate::DATA_FUNCTION_SYMBOL;
+use crate::DATA_VARIABLE_SYMBOL;
 use crate::SortExpression;
 use crate::SortExpressionRef;
 use crate::is_data_application;
@@ -59,7 +59,7 @@ mod inner {
     ///     - set enumeration
     ///     - bag enumeration
     ///
-    #[merc_term(is_data_expression)]
+    #[merc_term(is_data_expression, list)]
     pub struct DataExpression {
         term: ATerm,
     }
@@ -160,16 +160,16 @@ mod inner {
     impl DataFunctionSymbol {
         #[merc_ignore]
         pub fn new(name: impl Into<String> + AsRef<str>) -> DataFunctionSymbol {
-            DATA_SYMBOLS.with_borrow(|ds| DataFunctionSymbol {
+            DataFunctionSymbol {
                 term: ATerm::with_args(
-                    ds.data_function_symbol.deref(),
+                    DATA_FUNCTION_SYMBOL.get(),
                     &[
                         Into::<ATerm>::into(ATermString::new(name)),
                         SortExpression::unknown_sort().into(),
                     ],
                 )
                 .protect(),
-            })
+            }
         }
 
         /// Returns the name of the function symbol
@@ -194,7 +194,7 @@ mod inner {
         }
     }
 
-    #[merc_term(is_data_variable)]
+    #[merc_term(is_data_variable, list)]
     pub struct DataVariable {
         term: ATerm,
     }
@@ -203,28 +203,24 @@ mod inner {
         /// Create a new untyped variable with the given name.
         #[merc_ignore]
         pub fn new(name: impl Into<ATermString>) -> DataVariable {
-            DATA_SYMBOLS.with_borrow(|ds| {
-                // TODO: Storing terms temporarily is not optimal.
-                let t = name.into();
-                let args: &[ATerm] = &[t.into(), SortExpression::unknown_sort().into()];
+            // TODO: Storing terms temporarily is not optimal.
+            let t = name.into();
+            let args: &[ATerm] = &[t.into(), SortExpression::unknown_sort().into()];
 
-                DataVariable {
-                    term: ATerm::with_args(ds.data_variable.deref(), args).protect(),
-                }
-            })
+            DataVariable {
+                term: ATerm::with_args(DATA_VARIABLE_SYMBOL.get(), args).protect(),
+            }
         }
 
         /// Create a variable with the given sort and name.
         pub fn with_sort(name: impl Into<ATermString>, sort: SortExpressionRef<'_>) -> DataVariable {
-            DATA_SYMBOLS.with_borrow(|ds| {
-                // TODO: Storing terms temporarily is not optimal.
-                let t = name.into();
-                let args: &[ATermRef<'_>] = &[t.copy().into(), sort.into()];
+            // TODO: Storing terms temporarily is not optimal.
+            let t = name.into();
+            let args: &[ATermRef<'_>] = &[t.copy().into(), sort.into()];
 
-                DataVariable {
-                    term: ATerm::with_args(ds.data_variable.deref(), args).protect(),
-                }
-            })
+            DataVariable {
+                term: ATerm::with_args(DATA_VARIABLE_SYMBOL.get(), args).protect(),
+            }
         }
 
         /// Returns the name of the variable.
@@ -254,14 +250,12 @@ mod inner {
         /// Create a new data application with the given head and arguments.
         #[merc_ignore]
         pub fn with_args<'a, 'b>(head: &'b impl Term<'a, 'b>, arguments: &'b [impl Term<'a, 'b>]) -> DataApplication {
-            DATA_SYMBOLS.with_borrow_mut(|ds| {
-                let symbol = ds.get_data_application_symbol(arguments.len() + 1).copy();
+            let symbol = DATA_APPLICATION_SYMBOLS.get(arguments.len() + 1);
 
-                let args = iter::once(head.copy()).chain(arguments.iter().map(|t| t.copy()));
-                let term = ATerm::with_iter(&symbol, args);
+            let args = iter::once(head.copy()).chain(arguments.iter().map(|t| t.copy()));
+            let term = ATerm::with_iter(&symbol, args);
 
-                DataApplication { term }
-            })
+            DataApplication { term }
         }
 
         /// Create a new data application with the given head and arguments.
@@ -277,13 +271,11 @@ mod inner {
             I: Iterator<Item = T>,
             T: Term<'c, 'd>,
         {
-            DATA_SYMBOLS.with_borrow_mut(|ds| {
-                let symbol = ds.get_data_application_symbol(arity + 1).copy();
+            let symbol = DATA_APPLICATION_SYMBOLS.get(arity + 1);
 
-                let term = ATerm::with_iter_head(&symbol, head, arguments);
+            let term = ATerm::with_iter_head(&symbol, head, arguments);
 
-                DataApplication { term }
-            })
+            DataApplication { term }
         }
 
         /// Returns the head symbol a data application
@@ -436,37 +428,22 @@ impl<'a> DataExpressionRef<'a> {
 
 /// Converts an [ATerm] to an untyped data expression.
 pub fn to_untyped_data_expression(t: ATerm, variables: Option<&AHashSet<String>>) -> DataExpression {
-    let mut builder = TermBuilder::<ATerm, ATerm>::new();
-    THREAD_TERM_POOL.with_borrow(|tp| {
-        builder
-            .evaluate(
-                tp,
-                t,
-                |_tp, args, t| {
-                    if variables.is_some_and(|v| v.contains(t.get_head_symbol().name())) {
-                        // Convert a constant variable, for example 'x', into an untyped variable.
-                        Ok(Yield::Term(DataVariable::new(t.get_head_symbol().name()).into()))
-                    } else if t.get_head_symbol().arity() == 0 {
-                        Ok(Yield::Term(DataFunctionSymbol::new(t.get_head_symbol().name()).into()))
-                    } else {
-                        // This is a function symbol applied to a number of arguments
-                        let head = DataFunctionSymbol::new(t.get_head_symbol().name());
-
-                        for arg in t.arguments() {
-                            args.push(arg.protect());
-                        }
-
-                        Ok(Yield::Construct(head.into()))
-                    }
-                },
-                |_tp, input, args| {
-                    let arity = args.clone().count();
-                    Ok(DataApplication::with_iter(&input, arity, args).into())
-                },
-            )
-            .unwrap()
-            .into()
+    fold_term(&t, |t, args: &[ATerm]| {
+        let symbol = t.get_head_symbol();
+
+        if variables.is_some_and(|v| v.contains(symbol.name())) {
+            // Convert a constant variable, for example 'x', into an untyped variable.
+            Ok(DataVariable::new(symbol.name()).into())
+        } else if args.is_empty() {
+            Ok(DataFunctionSymbol::new(symbol.name()).into())
+        } else {
+            // This is a function symbol applied to a number of arguments
+            let head = DataFunctionSymbol::new(symbol.name());
+            Ok(DataApplication::with_iter(&head, args.len(), args.iter()).into())
+        }
     })
+    .expect("Converting to an untyped data expression cannot fail")
+    .into()
 }
 
 #[cfg(test)]
@@ -498,6 +475,22 @@ mod tests {
         assert!(is_data_application(&term));
     }
 
+    #[test]
+    fn test_try_from_term() {
+        let a = DataFunctionSymbol::new("a");
+        let x = DataVariable::new("x");
+
+        assert!(DataVariable::try_from_term(x.clone().into()).is_ok());
+        assert!(DataExpression::try_from_term(x.clone().into()).is_ok());
+        assert!(DataVariable::try_from_term(a.clone().into()).is_err());
+        assert!(DataVariableRef::try_from_term(a.copy().into()).is_err());
+        assert!(DataExpression::try_from_term(ATerm::from_string("f(a)").unwrap()).is_err());
+
+        assert!(DataVariable::is(&x));
+        assert!(!DataVariable::is(&a));
+        assert!(DataFunctionSymbol::is(&a));
+    }
+
     #[test]
     fn test_data_arguments() {
         let a = DataFunctionSymbol::new("a");
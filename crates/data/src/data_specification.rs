@@ -1,13 +1,264 @@
+use ahash::AHashSet;
+
 use merc_aterm::ATerm;
 use merc_aterm::ATermRead;
 use merc_aterm::ATermStreamable;
 use merc_aterm::ATermWrite;
 use merc_aterm::Symbol;
+use merc_syntax::ConstructorDecl;
+use merc_syntax::IdDecl;
+use merc_syntax::Sort;
+use merc_syntax::UntypedDataSpecification;
 use merc_utilities::MercError;
 
-/// TODO: Not yet useful, but can be used to read the data specification from a binary stream.
-#[derive(Default)]
-pub struct DataSpecification {}
+use crate::DataApplication;
+use crate::DataExpression;
+use crate::DataFunctionSymbol;
+use crate::DataVariable;
+use crate::to_data_expression;
+
+/// A function symbol of a data specification together with its sort.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FunctionDeclaration {
+    pub symbol: DataFunctionSymbol,
+    pub sort: merc_syntax::SortExpression,
+}
+
+/// An equation `condition -> lhs = rhs` of a data specification, where the
+/// condition is a boolean data expression that holds when it is omitted.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DataEquation {
+    pub variables: Vec<DataVariable>,
+    pub condition: Option<DataExpression>,
+    pub lhs: DataExpression,
+    pub rhs: DataExpression,
+}
+
+/// A data specification, which owns the declared sorts, constructors, mappings
+/// and equations.
+///
+/// # Details
+///
+/// The data expressions of the equations are untyped, see
+/// [to_data_expression], since the sorts are irrelevant for rewriting. For a
+/// structured sort, such as `sort List = struct nil | cons(head: Nat, tail:
+/// List)`, the constructors are added to the constructors of the
+/// specification, and the projection and recognizer functions are added to the
+/// mappings together with their defining equations.
+///
+/// The data specification stored in binary mCRL2 files is not yet supported,
+/// and is read and written as an empty specification.
+#[derive(Clone, Debug, Default)]
+pub struct DataSpecification {
+    sorts: Vec<String>,
+    constructors: Vec<FunctionDeclaration>,
+    mappings: Vec<FunctionDeclaration>,
+    equations: Vec<DataEquation>,
+}
+
+impl DataSpecification {
+    /// Creates a data specification from the parsed syntax tree.
+    pub fn from_syntax(spec: &UntypedDataSpecification) -> Result<Self, MercError> {
+        let mut result = DataSpecification::default();
+
+        for sort in &spec.sort_declarations {
+            result.sorts.push(sort.identifier.clone());
+
+            if let Some(merc_syntax::SortExpression::Struct { inner }) = &sort.expr {
+                result.add_structured_sort(&sort.identifier, inner);
+            }
+        }
+
+        result
+            .constructors
+            .extend(spec.constructor_declarations.iter().map(FunctionDeclaration::from));
+        result
+            .mappings
+            .extend(spec.map_declarations.iter().map(FunctionDeclaration::from));
+
+        for section in &spec.equation_declarations {
+            let names = AHashSet::from_iter(section.variables.iter().map(|variable| variable.identifier.clone()));
+            let variables: Vec<DataVariable> = section
+                .variables
+                .iter()
+                .map(|variable| DataVariable::new(variable.identifier.as_str()))
+                .collect();
+
+            for equation in &section.equations {
+                result.equations.push(DataEquation {
+                    variables: variables.clone(),
+                    condition: equation
+                        .condition
+                        .as_ref()
+                        .map(|condition| to_data_expression(condition, &names))
+                        .transpose()?,
+                    lhs: to_data_expression(&equation.lhs, &names)?,
+                    rhs: to_data_expression(&equation.rhs, &names)?,
+                });
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Returns the names of the declared sorts.
+    pub fn sorts(&self) -> &[String] {
+        &self.sorts
+    }
+
+    /// Returns the constructors, including those of the structured sorts.
+    pub fn constructors(&self) -> &[FunctionDeclaration] {
+        &self.constructors
+    }
+
+    /// Returns the mappings, including the projection and recognizer functions of the structured sorts.
+    pub fn mappings(&self) -> &[FunctionDeclaration] {
+        &self.mappings
+    }
+
+    /// Returns the equations, including those of the structured sorts.
+    pub fn equations(&self) -> &[DataEquation] {
+        &self.equations
+    }
+
+    /// Adds a sort with the given name.
+    pub fn add_sort(&mut self, name: impl Into<String>) {
+        self.sorts.push(name.into());
+    }
+
+    /// Adds the given constructor.
+    pub fn add_constructor(&mut self, constructor: FunctionDeclaration) {
+        self.constructors.push(constructor);
+    }
+
+    /// Adds the given mapping.
+    pub fn add_mapping(&mut self, mapping: FunctionDeclaration) {
+        self.mappings.push(mapping);
+    }
+
+    /// Adds the given equation.
+    pub fn add_equation(&mut self, equation: DataEquation) {
+        self.equations.push(equation);
+    }
+
+    /// Adds the sorts, constructors, mappings and equations of the other
+    /// specification, for example to combine a specification with the
+    /// [crate::standard_data_specification].
+    pub fn merge(&mut self, other: DataSpecification) {
+        self.sorts.extend(other.sorts);
+        self.constructors.extend(other.constructors);
+        self.mappings.extend(other.mappings);
+        self.equations.extend(other.equations);
+    }
+
+    /// Adds the constructors, projections and recognizers of the structured sort with the given name.
+    fn add_structured_sort(&mut self, name: &str, constructors: &[ConstructorDecl]) {
+        let sort = merc_syntax::SortExpression::Reference(name.to_string());
+
+        for constructor in constructors {
+            let symbol = DataFunctionSymbol::new(constructor.name.as_str());
+            let domain = constructor
+                .args
+                .iter()
+                .map(|(_, sort)| sort.clone())
+                .reduce(|lhs, rhs| merc_syntax::SortExpression::Product {
+                    lhs: Box::new(lhs),
+                    rhs: Box::new(rhs),
+                });
+
+            self.constructors.push(FunctionDeclaration {
+                symbol,
+                sort: function_sort(domain, sort.clone()),
+            });
+
+            let (variables, pattern) = constructor_pattern(constructor);
+
+            for ((projection, projection_sort), variable) in constructor.args.iter().zip(&variables) {
+                if let Some(projection) = projection {
+                    let projection = DataFunctionSymbol::new(projection.as_str());
+                    self.mappings.push(FunctionDeclaration {
+                        symbol: projection.clone(),
+                        sort: function_sort(Some(sort.clone()), projection_sort.clone()),
+                    });
+                    self.equations.push(DataEquation {
+                        variables: variables.clone(),
+                        condition: None,
+                        lhs: DataApplication::with_args(&projection, std::slice::from_ref(&pattern)).into(),
+                        rhs: variable.clone().into(),
+                    });
+                }
+            }
+        }
+
+        // A recognizer holds for the terms of its own constructor, and not for the other constructors.
+        for recognizer in constructors {
+            let Some(name) = &recognizer.projection else {
+                continue;
+            };
+
+            let symbol = DataFunctionSymbol::new(name.as_str());
+            self.mappings.push(FunctionDeclaration {
+                symbol: symbol.clone(),
+                sort: function_sort(Some(sort.clone()), merc_syntax::SortExpression::Simple(Sort::Bool)),
+            });
+
+            for constructor in constructors {
+                let (variables, pattern) = constructor_pattern(constructor);
+
+                let value = if constructor.name == recognizer.name {
+                    "true"
+                } else {
+                    "false"
+                };
+                self.equations.push(DataEquation {
+                    variables,
+                    condition: None,
+                    lhs: DataApplication::with_args(&symbol, &[pattern]).into(),
+                    rhs: DataFunctionSymbol::new(value).into(),
+                });
+            }
+        }
+    }
+}
+
+impl From<&IdDecl> for FunctionDeclaration {
+    fn from(declaration: &IdDecl) -> Self {
+        FunctionDeclaration {
+            symbol: DataFunctionSymbol::new(declaration.identifier.as_str()),
+            sort: declaration.sort.clone(),
+        }
+    }
+}
+
+/// Returns the term `c(x0, ..., xn)` for the given constructor `c`, which is
+/// matched by the projections and recognizers, together with its variables.
+fn constructor_pattern(constructor: &ConstructorDecl) -> (Vec<DataVariable>, DataExpression) {
+    let symbol = DataFunctionSymbol::new(constructor.name.as_str());
+    let variables: Vec<DataVariable> = (0..constructor.args.len())
+        .map(|index| DataVariable::new(format!("@x{index}").as_str()))
+        .collect();
+
+    if variables.is_empty() {
+        (variables, symbol.into())
+    } else {
+        let pattern = DataApplication::with_args(&symbol, &variables).into();
+        (variables, pattern)
+    }
+}
+
+/// Returns the function sort `domain -> range`, or only the range for constants.
+fn function_sort(
+    domain: Option<merc_syntax::SortExpression>,
+    range: merc_syntax::SortExpression,
+) -> merc_syntax::SortExpression {
+    match domain {
+        Some(domain) => merc_syntax::SortExpression::Function {
+            domain: Box::new(domain),
+            range: Box::new(range),
+        },
+        None => range,
+    }
+}
 
 impl ATermStreamable for DataSpecification {
     fn write<W: ATermWrite>(&self, writer: &mut W) -> Result<(), MercError> {
@@ -31,6 +282,36 @@ impl ATermStreamable for DataSpecification {
         let _user_defined_equations: Result<Vec<ATerm>, MercError> = reader.read_aterm_iter()?.collect();
 
         // Ignore results for now.
-        Ok(DataSpecification {})
+        Ok(DataSpecification::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_data_specification_from_syntax() {
+        let spec = UntypedDataSpecification::parse(
+            "sort List = struct nil?is_nil | cons(head: Nat, tail: List)?is_cons;
+            map append: List # Nat -> List;
+            var l: List;
+                n, m: Nat;
+            eqn append(nil, n) = cons(n, nil);
+                append(cons(m, l), n) = cons(m, append(l, n));",
+        )
+        .unwrap();
+
+        let spec = DataSpecification::from_syntax(&spec).unwrap();
+        assert_eq!(spec.sorts(), ["List"]);
+        assert_eq!(spec.constructors().len(), 2);
+
+        // The projections head and tail, the recognizers is_nil and is_cons, and append.
+        assert_eq!(spec.mappings().len(), 5);
+        assert_eq!(spec.equations().len(), 8);
+
+        let append = spec.equations().last().unwrap();
+        assert_eq!(append.lhs.to_string(), "append(cons(m, l), n)");
+        assert_eq!(append.rhs.to_string(), "cons(m, append(l, n))");
     }
 }
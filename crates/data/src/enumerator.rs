@@ -0,0 +1,238 @@
+use ahash::AHashSet;
+
+use merc_syntax::DataExpr;
+use merc_syntax::Quantifier;
+use merc_syntax::Sort;
+use merc_syntax::SortExpression;
+use merc_syntax::VarDecl;
+use merc_utilities::MercError;
+
+use crate::DataApplication;
+use crate::DataExpression;
+use crate::DataFunctionSymbol;
+use crate::DataSpecification;
+use crate::DataVariable;
+use crate::data_expr::convert;
+use crate::is_data_application;
+
+/// Enumerates the values of the finite sorts of a data specification, which
+/// are used to eliminate quantifiers over these sorts.
+///
+/// # Details
+///
+/// A sort is finite when it is `Bool`, or a declared sort whose constructors
+/// only have arguments of finite sorts, such as the enumeration `sort Colour =
+/// struct red | green | blue`. Recursive sorts and the numbers are not finite.
+pub struct Enumerator<'a> {
+    spec: &'a DataSpecification,
+}
+
+impl<'a> Enumerator<'a> {
+    /// Creates an enumerator for the sorts of the given data specification.
+    pub fn new(spec: &'a DataSpecification) -> Self {
+        Self { spec }
+    }
+
+    /// Returns all values of the given sort, or `None` when the sort is not finite.
+    pub fn values(&self, sort: &SortExpression) -> Option<Vec<DataExpression>> {
+        self.values_rec(sort, &mut Vec::new())
+    }
+
+    /// Converts the data expression as [crate::to_data_expression], where the
+    /// quantifiers `forall x: S . e` and `exists x: S . e` over finite sorts are
+    /// replaced by the conjunction and disjunction of `e` for all values of `S`.
+    ///
+    /// Returns an unsupported error when a quantified variable has a sort that
+    /// is not finite.
+    pub fn to_data_expression(
+        &self,
+        expr: &DataExpr,
+        variables: &AHashSet<String>,
+    ) -> Result<DataExpression, MercError> {
+        convert(expr, variables, Some(self))
+    }
+
+    /// Returns the expansion of the quantifier with the given bound variables and body.
+    pub(crate) fn expand_quantifier(
+        &self,
+        quantifier: &Quantifier,
+        bound: &[VarDecl],
+        body: &DataExpr,
+        variables: &AHashSet<String>,
+    ) -> Result<DataExpression, MercError> {
+        let mut scope = variables.clone();
+        scope.extend(bound.iter().map(|variable| variable.identifier.clone()));
+
+        let mut instances = vec![convert(body, &scope, Some(self))?];
+        for declaration in bound {
+            let values = self.values(&declaration.sort).ok_or_else(|| {
+                MercError::unsupported(format!(
+                    "Cannot enumerate the quantified variable {}: {}, since its sort is not finite",
+                    declaration.identifier, declaration.sort
+                ))
+            })?;
+
+            let variable = DataVariable::new(declaration.identifier.as_str());
+            instances = instances
+                .iter()
+                .flat_map(|instance| values.iter().map(|value| substitute(instance, &variable, value)))
+                .collect();
+        }
+
+        let (operator, unit) = match quantifier {
+            Quantifier::Forall => ("&&", "true"),
+            Quantifier::Exists => ("||", "false"),
+        };
+
+        let operator = DataFunctionSymbol::new(operator);
+        Ok(instances
+            .into_iter()
+            .reduce(|lhs, rhs| DataApplication::with_args(&operator, &[lhs, rhs]).into())
+            .unwrap_or_else(|| DataFunctionSymbol::new(unit).into()))
+    }
+
+    /// Returns the values of the sort, where `visiting` contains the sorts that
+    /// are being enumerated to detect recursive sorts.
+    fn values_rec(&self, sort: &SortExpression, visiting: &mut Vec<SortExpression>) -> Option<Vec<DataExpression>> {
+        match sort {
+            SortExpression::Simple(Sort::Bool) => Some(vec![
+                DataFunctionSymbol::new("false").into(),
+                DataFunctionSymbol::new("true").into(),
+            ]),
+            SortExpression::Reference(_) => {
+                if visiting.contains(sort) {
+                    return None;
+                }
+
+                visiting.push(sort.clone());
+                let mut result = Vec::new();
+                let mut has_constructors = false;
+                for constructor in self.spec.constructors() {
+                    let (domain, range) = split_function_sort(&constructor.sort);
+                    if range != sort {
+                        continue;
+                    }
+
+                    has_constructors = true;
+                    let mut arguments: Vec<Vec<DataExpression>> = vec![Vec::new()];
+                    for argument_sort in domain {
+                        let values = self.values_rec(argument_sort, visiting)?;
+                        arguments = arguments
+                            .iter()
+                            .flat_map(|prefix| {
+                                values.iter().map(move |value| {
+                                    let mut arguments = prefix.clone();
+                                    arguments.push(value.clone());
+                                    arguments
+                                })
+                            })
+                            .collect();
+                    }
+
+                    for arguments in arguments {
+                        if arguments.is_empty() {
+                            result.push(constructor.symbol.clone().into());
+                        } else {
+                            result.push(DataApplication::with_args(&constructor.symbol, &arguments).into());
+                        }
+                    }
+                }
+                visiting.pop();
+
+                // A sort without constructors is not necessarily empty, for example when it is defined by mappings.
+                has_constructors.then_some(result)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Returns the argument sorts and the result sort of a function sort, where the
+/// domain is empty for constants.
+fn split_function_sort(sort: &SortExpression) -> (Vec<&SortExpression>, &SortExpression) {
+    match sort {
+        SortExpression::Function { domain, range } => {
+            let mut result = Vec::new();
+            flatten_product(domain, &mut result);
+            (result, range)
+        }
+        _ => (Vec::new(), sort),
+    }
+}
+
+/// Adds the sorts of the product `A # B # ...` to the result.
+fn flatten_product<'a>(sort: &'a SortExpression, result: &mut Vec<&'a SortExpression>) {
+    match sort {
+        SortExpression::Product { lhs, rhs } => {
+            flatten_product(lhs, result);
+            flatten_product(rhs, result);
+        }
+        _ => result.push(sort),
+    }
+}
+
+/// Returns the expression where every occurrence of the variable is replaced by the value.
+fn substitute(expr: &DataExpression, variable: &DataVariable, value: &DataExpression) -> DataExpression {
+    if **expr == **variable {
+        value.clone()
+    } else if is_data_application(expr) {
+        let arguments: Vec<DataExpression> = expr
+            .data_arguments()
+            .map(|argument| substitute(&argument.protect(), variable, value))
+            .collect();
+        DataApplication::with_args(&expr.data_function_symbol().protect(), &arguments).into()
+    } else {
+        expr.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use merc_syntax::UntypedDataSpecification;
+
+    use super::*;
+
+    #[test]
+    fn test_enumerator_values() {
+        let spec = UntypedDataSpecification::parse(
+            "sort Colour = struct red | green;
+                 Pair = struct pair(Colour, Bool);
+                 List = struct nil | cons(Bool, List);",
+        )
+        .unwrap();
+        let spec = DataSpecification::from_syntax(&spec).unwrap();
+        let enumerator = Enumerator::new(&spec);
+
+        let values = enumerator
+            .values(&SortExpression::Reference("Pair".to_string()))
+            .unwrap();
+        assert_eq!(values.len(), 4);
+        assert_eq!(values[0].to_string(), "pair(red, false)");
+
+        assert!(
+            enumerator
+                .values(&SortExpression::Reference("List".to_string()))
+                .is_none()
+        );
+        assert!(enumerator.values(&SortExpression::Simple(Sort::Nat)).is_none());
+    }
+
+    #[test]
+    fn test_enumerator_quantifiers() {
+        let spec = UntypedDataSpecification::parse("sort Colour = struct red | green;").unwrap();
+        let spec = DataSpecification::from_syntax(&spec).unwrap();
+        let enumerator = Enumerator::new(&spec);
+
+        let expr = DataExpr::parse("forall c: Colour . exists b: Bool . f(c, b, x)").unwrap();
+        let result = enumerator
+            .to_data_expression(&expr, &AHashSet::from_iter(["x".to_string()]))
+            .unwrap();
+        assert_eq!(
+            result.to_string(),
+            "&&(||(f(red, false, x), f(red, true, x)), ||(f(green, false, x), f(green, true, x)))"
+        );
+
+        let expr = DataExpr::parse("forall n: Nat . n == n").unwrap();
+        assert!(enumerator.to_data_expression(&expr, &AHashSet::new()).is_err());
+    }
+}
@@ -1,12 +1,20 @@
 #![doc = include_str!("../README.md")]
 #![forbid(unsafe_code)]
 
+mod data_expr;
 mod data_expression;
 mod data_specification;
 mod data_terms;
+mod enumerator;
+mod simplifier;
 mod sort_terms;
+mod standard_library;
 
+pub use data_expr::*;
 pub use data_expression::*;
 pub use data_specification::*;
 pub use data_terms::*;
+pub use enumerator::*;
+pub use simplifier::*;
 pub use sort_terms::*;
+pub use standard_library::*;
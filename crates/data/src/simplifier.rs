@@ -0,0 +1,292 @@
+use ahash::AHashSet;
+
+use crate::DataApplication;
+use crate::DataExpression;
+use crate::DataFunctionSymbol;
+use crate::DataSpecification;
+use crate::is_data_application;
+use crate::is_data_function_symbol;
+
+/// Simplifies data expressions without a rewrite system, for example to
+/// reduce conditions before they are translated into BDDs.
+///
+/// # Details
+///
+/// The simplifier applies constant folding of the boolean operators and of the
+/// arithmetic on numerals, resolves `if` with a constant condition or equal
+/// branches, and resolves equalities between syntactically equal terms and
+/// between constructor terms. An `if` in an argument of a comparison is lifted
+/// out, `x == if(c, a, b)` becomes `if(c, x == a, x == b)`, when this reduces
+/// both branches to a constant. The operators are identified by their names,
+/// as produced by [crate::to_data_expression]. The default simplifier only
+/// knows the constructors `true` and `false`.
+#[derive(Default)]
+pub struct Simplifier {
+    /// The names of the constructors, whose applications are values.
+    constructors: AHashSet<String>,
+}
+
+impl Simplifier {
+    /// Creates a simplifier that resolves equalities between the constructors of the given specification.
+    pub fn new(spec: &DataSpecification) -> Self {
+        let mut result = Self::default();
+        result.constructors.extend(
+            spec.constructors()
+                .iter()
+                .map(|constructor| constructor.symbol.name().to_string()),
+        );
+        result
+    }
+
+    /// Returns the simplified data expression.
+    pub fn simplify(&self, expr: &DataExpression) -> DataExpression {
+        if is_data_application(expr) {
+            let name = expr.data_function_symbol().name().to_string();
+            let arguments: Vec<DataExpression> = expr
+                .data_arguments()
+                .map(|argument| self.simplify(&argument.protect()))
+                .collect();
+
+            self.simplify_application(&name, arguments)
+        } else {
+            expr.clone()
+        }
+    }
+
+    /// Returns the simplification of `name(arguments)`, where the arguments are already simplified.
+    fn simplify_application(&self, name: &str, arguments: Vec<DataExpression>) -> DataExpression {
+        if let Some(result) = self.fold(name, &arguments) {
+            return result;
+        }
+
+        if is_comparison(name) {
+            return self
+                .lift_if(name, &arguments)
+                .unwrap_or_else(|| apply(name, &arguments));
+        }
+
+        apply(name, &arguments)
+    }
+
+    /// Returns the result of the operator when it can be determined from its arguments.
+    fn fold(&self, name: &str, arguments: &[DataExpression]) -> Option<DataExpression> {
+        match (name, arguments) {
+            ("!", [x]) => match boolean(x) {
+                Some(value) => Some(constant(if value { "false" } else { "true" })),
+                None if head(x).as_deref() == Some("!") => Some(x.data_arguments().next()?.protect()),
+                None => None,
+            },
+            ("&&", [x, y]) => match (boolean(x), boolean(y)) {
+                (Some(false), _) | (_, Some(false)) => Some(constant("false")),
+                (Some(true), _) => Some(y.clone()),
+                (_, Some(true)) => Some(x.clone()),
+                _ if x == y => Some(x.clone()),
+                _ => None,
+            },
+            ("||", [x, y]) => match (boolean(x), boolean(y)) {
+                (Some(true), _) | (_, Some(true)) => Some(constant("true")),
+                (Some(false), _) => Some(y.clone()),
+                (_, Some(false)) => Some(x.clone()),
+                _ if x == y => Some(x.clone()),
+                _ => None,
+            },
+            ("=>", [x, y]) => match (boolean(x), boolean(y)) {
+                (Some(false), _) | (_, Some(true)) => Some(constant("true")),
+                (Some(true), _) => Some(y.clone()),
+                (_, Some(false)) => Some(self.simplify_application("!", vec![x.clone()])),
+                _ if x == y => Some(constant("true")),
+                _ => None,
+            },
+            ("if", [c, x, y]) => match (boolean(c), boolean(x), boolean(y)) {
+                (Some(true), _, _) => Some(x.clone()),
+                (Some(false), _, _) => Some(y.clone()),
+                _ if x == y => Some(x.clone()),
+                (_, Some(true), Some(false)) => Some(c.clone()),
+                (_, Some(false), Some(true)) => Some(self.simplify_application("!", vec![c.clone()])),
+                _ => None,
+            },
+            ("==", [x, y]) => self.resolve_equality(x, y),
+            ("!=", [x, y]) => self
+                .resolve_equality(x, y)
+                .map(|equal| self.simplify_application("!", vec![equal])),
+            (_, [x, y]) => {
+                let (x, y) = (numeral(x)?, numeral(y)?);
+                match name {
+                    "+" => x.checked_add(y).map(numeral_expression),
+                    "*" => x.checked_mul(y).map(numeral_expression),
+                    "-" if x >= y => Some(numeral_expression(x - y)),
+                    "max" => Some(numeral_expression(x.max(y))),
+                    "min" => Some(numeral_expression(x.min(y))),
+                    "<" => Some(boolean_expression(x < y)),
+                    "<=" => Some(boolean_expression(x <= y)),
+                    ">" => Some(boolean_expression(x > y)),
+                    ">=" => Some(boolean_expression(x >= y)),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the value of `x == y` when it can be determined syntactically.
+    fn resolve_equality(&self, x: &DataExpression, y: &DataExpression) -> Option<DataExpression> {
+        if x == y {
+            return Some(constant("true"));
+        }
+
+        if numeral(x).is_some() && numeral(y).is_some() {
+            return Some(constant("false"));
+        }
+
+        let (x_head, y_head) = (self.constructor(x)?, self.constructor(y)?);
+        let x_arguments: Vec<DataExpression> = arguments(x);
+        let y_arguments: Vec<DataExpression> = arguments(y);
+        if x_head != y_head || x_arguments.len() != y_arguments.len() {
+            return Some(constant("false"));
+        }
+
+        // Equal constructors are equal when all their arguments are equal.
+        Some(
+            x_arguments
+                .into_iter()
+                .zip(y_arguments)
+                .fold(constant("true"), |result, (x, y)| {
+                    let equal = self.simplify_application("==", vec![x, y]);
+                    self.simplify_application("&&", vec![result, equal])
+                }),
+        )
+    }
+
+    /// Lifts the first `if` argument out of the comparison, when this simplifies both branches to a constant.
+    fn lift_if(&self, name: &str, arguments: &[DataExpression]) -> Option<DataExpression> {
+        let index = arguments
+            .iter()
+            .position(|argument| head(argument).as_deref() == Some("if"))?;
+        let mut branches = arguments[index].data_arguments().map(|argument| argument.protect());
+        let (condition, then, otherwise) = (branches.next()?, branches.next()?, branches.next()?);
+
+        let lifted = |branch: DataExpression| {
+            let mut arguments = arguments.to_vec();
+            arguments[index] = branch;
+            self.simplify_application(name, arguments)
+        };
+
+        let (then, otherwise) = (lifted(then), lifted(otherwise));
+        if boolean(&then).is_some() && boolean(&otherwise).is_some() {
+            Some(self.simplify_application("if", vec![condition, then, otherwise]))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the name of the constructor at the head of the expression, if it is a constructor term.
+    fn constructor(&self, expr: &DataExpression) -> Option<String> {
+        let name = head(expr)?;
+        (self.constructors.contains(&name) || name == "true" || name == "false").then_some(name)
+    }
+}
+
+/// Returns true iff the operator is a comparison, whose result is a boolean.
+fn is_comparison(name: &str) -> bool {
+    matches!(name, "==" | "!=" | "<" | "<=" | ">" | ">=")
+}
+
+/// Returns the name of the function symbol at the head of the expression, or `None` for variables.
+fn head(expr: &DataExpression) -> Option<String> {
+    if is_data_application(expr) || is_data_function_symbol(expr) {
+        Some(expr.data_function_symbol().name().to_string())
+    } else {
+        None
+    }
+}
+
+/// Returns the arguments of an application, or no arguments for a constant.
+fn arguments(expr: &DataExpression) -> Vec<DataExpression> {
+    if is_data_application(expr) {
+        expr.data_arguments().map(|argument| argument.protect()).collect()
+    } else {
+        Vec::new()
+    }
+}
+
+/// Returns the value of the constant `true` or `false`.
+fn boolean(expr: &DataExpression) -> Option<bool> {
+    if !is_data_function_symbol(expr) {
+        return None;
+    }
+
+    match head(expr)?.as_str() {
+        "true" => Some(true),
+        "false" => Some(false),
+        _ => None,
+    }
+}
+
+/// Returns the value of a numeral constant, such as `42`.
+fn numeral(expr: &DataExpression) -> Option<u64> {
+    if !is_data_function_symbol(expr) {
+        return None;
+    }
+
+    head(expr)?.parse().ok()
+}
+
+fn numeral_expression(value: u64) -> DataExpression {
+    constant(&value.to_string())
+}
+
+fn boolean_expression(value: bool) -> DataExpression {
+    constant(if value { "true" } else { "false" })
+}
+
+fn constant(name: &str) -> DataExpression {
+    DataFunctionSymbol::new(name).into()
+}
+
+fn apply(name: &str, arguments: &[DataExpression]) -> DataExpression {
+    DataApplication::with_args(&DataFunctionSymbol::new(name), arguments).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use merc_syntax::DataExpr;
+    use merc_syntax::UntypedDataSpecification;
+
+    use crate::to_data_expression;
+
+    use super::*;
+
+    fn simplify(simplifier: &Simplifier, text: &str) -> String {
+        let variables = AHashSet::from_iter(["x".to_string(), "y".to_string(), "b".to_string()]);
+        let expr = to_data_expression(&DataExpr::parse(text).unwrap(), &variables).unwrap();
+        simplifier.simplify(&expr).to_string()
+    }
+
+    #[test]
+    fn test_simplify_constant_folding() {
+        let simplifier = Simplifier::default();
+
+        assert_eq!(simplify(&simplifier, "b && (true || x)"), "b");
+        assert_eq!(simplify(&simplifier, "!(!b) => false"), "!(b)");
+        assert_eq!(simplify(&simplifier, "2 + 3 * 4 < 15"), "true");
+        assert_eq!(simplify(&simplifier, "if(b, x, x) == x"), "true");
+        assert_eq!(simplify(&simplifier, "f(x, 1 + 1)"), "f(x, 2)");
+    }
+
+    #[test]
+    fn test_simplify_equality_resolution() {
+        let spec = UntypedDataSpecification::parse("sort Colour = struct red | green | mix(Colour, Colour);").unwrap();
+        let simplifier = Simplifier::new(&DataSpecification::from_syntax(&spec).unwrap());
+
+        assert_eq!(simplify(&simplifier, "red == green"), "false");
+        assert_eq!(
+            simplify(&simplifier, "mix(red, x) != mix(red, green)"),
+            "!(==(x, green))"
+        );
+        assert_eq!(simplify(&simplifier, "if(b, red, green) == green"), "!(b)");
+        assert_eq!(
+            simplify(&simplifier, "if(b, red, x) == green"),
+            "==(if(b, red, x), green)"
+        );
+    }
+}
@@ -1,5 +1,4 @@
 use std::fmt;
-use std::ops::Deref;
 
 use delegate::delegate;
 
@@ -17,7 +16,7 @@ use merc_aterm::storage::Marker;
 use merc_macros::merc_derive_terms;
 use merc_macros::merc_term;
 
-use crate::DATA_SYMBOLS;
+use crate::SORT_ID_SYMBOL;
 use crate::is_sort_expression;
 
 // This module is only used internally to run the proc macro.
@@ -40,9 +39,9 @@ mod inner {
 
         /// Creates a sort expression with the unknown value.
         pub fn unknown_sort() -> SortExpression {
-            DATA_SYMBOLS.with_borrow(|ds| SortExpression {
-                term: ATerm::with_args(ds.sort_id_symbol.deref(), &[ATermString::new("@no_value@")]).protect(),
-            })
+            SortExpression {
+                term: ATerm::with_args(SORT_ID_SYMBOL.get(), &[ATermString::new("@no_value@")]).protect(),
+            }
         }
     }
 
@@ -0,0 +1,972 @@
+use merc_aterm::Term;
+use merc_syntax::ComplexSort;
+use merc_syntax::Sort;
+use merc_syntax::SortExpression;
+
+use crate::DataApplication;
+use crate::DataEquation;
+use crate::DataExpression;
+use crate::DataFunctionSymbol;
+use crate::DataSpecification;
+use crate::DataVariable;
+use crate::FunctionDeclaration;
+use crate::is_data_variable;
+
+/// Returns the data specification of the standard mCRL2 data types Bool, Pos,
+/// Nat, Int, List, Set and Bag, with equations that make them executable.
+///
+/// # Details
+///
+/// The numbers use the binary representation of mCRL2, where a positive number
+/// is either `@c1` or `@cDub(b, p)` for `2 * p + b`, a natural number is either
+/// `@c0` or `@cNat(p)` and an integer is either `@cInt(n)` or `@cNeg(p)`. The
+/// functions [pos_expression], [nat_expression] and [int_expression] construct
+/// these terms, since numerals such as `3` are not translated. Lists are built
+/// from `[]` and `|>`, and sets and bags are finite and represented by sorted
+/// lists `@fset_cons(x, s)` and `@fbag_cons(x, p, b)` without duplicates, such
+/// that equal sets have equal normal forms when `<` is a total order on the
+/// elements.
+///
+/// Every operator is defined by case distinction on the constructors of its
+/// first argument, such that the rules do not overlap even though the
+/// operators are overloaded for the different sorts. The equality, and for
+/// Bool and List also the orderings, are generated from the constructors.
+/// The function symbols are identified by their name, so the unary minus is
+/// called `negate` to distinguish it from the binary `-`.
+///
+/// Division, exponentiation, reals and the infinite sets and bags are not
+/// supported.
+pub fn standard_data_specification() -> DataSpecification {
+    let mut spec = DataSpecification::default();
+
+    add_generic(&mut spec);
+    add_bool(&mut spec);
+    add_pos(&mut spec);
+    add_nat(&mut spec);
+    add_int(&mut spec);
+    add_list(&mut spec);
+    add_set(&mut spec);
+    add_bag(&mut spec);
+
+    spec
+}
+
+/// Returns `true` or `false`.
+pub fn bool_expression(value: bool) -> DataExpression {
+    constant(if value { "true" } else { "false" })
+}
+
+/// Returns the representation of the given positive number.
+///
+/// # Panics
+///
+/// Panics when the value is zero.
+pub fn pos_expression(value: u64) -> DataExpression {
+    assert!(value > 0, "A positive number cannot be zero");
+
+    if value == 1 {
+        constant("@c1")
+    } else {
+        apply("@cDub", &[bool_expression(value % 2 == 1), pos_expression(value / 2)])
+    }
+}
+
+/// Returns the representation of the given natural number.
+pub fn nat_expression(value: u64) -> DataExpression {
+    if value == 0 {
+        constant("@c0")
+    } else {
+        apply("@cNat", &[pos_expression(value)])
+    }
+}
+
+/// Returns the representation of the given integer.
+pub fn int_expression(value: i64) -> DataExpression {
+    if value >= 0 {
+        apply("@cInt", &[nat_expression(value.unsigned_abs())])
+    } else {
+        apply("@cNeg", &[pos_expression(value.unsigned_abs())])
+    }
+}
+
+/// Adds the functions that are defined for all sorts in terms of the others.
+fn add_generic(spec: &mut DataSpecification) {
+    for sort in [
+        bool_sort(),
+        pos_sort(),
+        nat_sort(),
+        int_sort(),
+        list_sort(),
+        set_sort(),
+        bag_sort(),
+    ] {
+        mapping(spec, "if", &[bool_sort(), sort.clone(), sort.clone()], sort.clone());
+        for name in ["==", "!=", "<", "<=", ">", ">="] {
+            mapping(spec, name, &[sort.clone(), sort.clone()], bool_sort());
+        }
+        for name in ["max", "min"] {
+            mapping(spec, name, &[sort.clone(), sort.clone()], sort.clone());
+        }
+    }
+
+    let (x, y) = (var("x"), var("y"));
+    equation(spec, apply("if", &[constant("true"), x.clone(), y.clone()]), x.clone());
+    equation(spec, apply("if", &[constant("false"), x.clone(), y.clone()]), y.clone());
+    equation(
+        spec,
+        apply("!=", &[x.clone(), y.clone()]),
+        apply("!", &[apply("==", &[x.clone(), y.clone()])]),
+    );
+    equation(
+        spec,
+        apply(">", &[x.clone(), y.clone()]),
+        apply("<", &[y.clone(), x.clone()]),
+    );
+    equation(
+        spec,
+        apply(">=", &[x.clone(), y.clone()]),
+        apply("<=", &[y.clone(), x.clone()]),
+    );
+
+    let less_equal = apply("<=", &[x.clone(), y.clone()]);
+    equation(
+        spec,
+        apply("max", &[x.clone(), y.clone()]),
+        apply("if", &[less_equal.clone(), y.clone(), x.clone()]),
+    );
+    equation(
+        spec,
+        apply("min", &[x.clone(), y.clone()]),
+        apply("if", &[less_equal, x, y]),
+    );
+}
+
+fn add_bool(spec: &mut DataSpecification) {
+    add_constructor_sort(spec, "Bool", bool_sort(), &[("false", vec![]), ("true", vec![])], true);
+
+    mapping(spec, "!", &[bool_sort()], bool_sort());
+    for name in ["&&", "||", "=>"] {
+        mapping(spec, name, &[bool_sort(), bool_sort()], bool_sort());
+    }
+
+    let (t, f, b) = (constant("true"), constant("false"), var("b"));
+    equation(spec, apply("!", [t.clone()]), f.clone());
+    equation(spec, apply("!", [f.clone()]), t.clone());
+    equation(spec, apply("&&", &[t.clone(), b.clone()]), b.clone());
+    equation(spec, apply("&&", &[f.clone(), b.clone()]), f.clone());
+    equation(spec, apply("||", &[t.clone(), b.clone()]), t.clone());
+    equation(spec, apply("||", &[f.clone(), b.clone()]), b.clone());
+    equation(spec, apply("=>", &[t.clone(), b.clone()]), b.clone());
+    equation(spec, apply("=>", &[f, b]), t);
+}
+
+fn add_pos(spec: &mut DataSpecification) {
+    add_constructor_sort(
+        spec,
+        "Pos",
+        pos_sort(),
+        &[("@c1", vec![]), ("@cDub", vec![bool_sort(), pos_sort()])],
+        false,
+    );
+
+    mapping(spec, "succ", &[pos_sort()], pos_sort());
+    mapping(spec, "pred", &[pos_sort()], nat_sort());
+    for name in ["+", "*"] {
+        mapping(spec, name, &[pos_sort(), pos_sort()], pos_sort());
+    }
+    mapping(spec, "-", &[pos_sort(), pos_sort()], int_sort());
+    mapping(spec, "@addc", &[bool_sort(), pos_sort(), pos_sort()], pos_sort());
+    mapping(spec, "@dub", &[bool_sort(), nat_sort()], nat_sort());
+    mapping(spec, "@gtesubtb", &[bool_sort(), pos_sort(), pos_sort()], nat_sort());
+    mapping(spec, "@pos_minus", &[pos_sort(), pos_sort()], int_sort());
+    mapping(spec, "Pos2Nat", &[pos_sort()], nat_sort());
+    mapping(spec, "Pos2Int", &[pos_sort()], int_sort());
+
+    let (t, f) = (constant("true"), constant("false"));
+    let one = constant("@c1");
+    let (b, c, d, p, q) = (var("b"), var("c"), var("d"), var("p"), var("q"));
+    let dub = |bit: &DataExpression, p: &DataExpression| apply("@cDub", &[bit.clone(), p.clone()]);
+    let succ = |p: DataExpression| apply("succ", &[p]);
+    let bp = dub(&b, &p);
+
+    equation(spec, succ(one.clone()), dub(&f, &one));
+    equation(spec, succ(dub(&f, &p)), dub(&t, &p));
+    equation(spec, succ(dub(&t, &p)), dub(&f, &succ(p.clone())));
+
+    // Addition with a carry bit, which adds the numbers bit by bit.
+    equation(spec, apply("+", &[one.clone(), q.clone()]), succ(q.clone()));
+    equation(
+        spec,
+        apply("+", &[bp.clone(), q.clone()]),
+        apply("@addc", &[f.clone(), bp.clone(), q.clone()]),
+    );
+    equation(
+        spec,
+        apply("@addc", &[f.clone(), one.clone(), q.clone()]),
+        succ(q.clone()),
+    );
+    equation(
+        spec,
+        apply("@addc", &[t.clone(), one.clone(), q.clone()]),
+        succ(succ(q.clone())),
+    );
+    equation(
+        spec,
+        apply("@addc", &[f.clone(), bp.clone(), one.clone()]),
+        succ(bp.clone()),
+    );
+    equation(
+        spec,
+        apply("@addc", &[t.clone(), bp.clone(), one.clone()]),
+        succ(succ(bp.clone())),
+    );
+    let carry = apply(
+        "||",
+        &[
+            apply("&&", &[b.clone(), d.clone()]),
+            apply("&&", &[c.clone(), apply("||", &[b.clone(), d.clone()])]),
+        ],
+    );
+    equation(
+        spec,
+        apply("@addc", &[c.clone(), bp.clone(), dub(&d, &q)]),
+        dub(
+            &apply("==", &[apply("==", &[b.clone(), d.clone()]), c.clone()]),
+            &apply("@addc", &[carry, p.clone(), q.clone()]),
+        ),
+    );
+
+    equation(spec, apply("*", &[one.clone(), q.clone()]), q.clone());
+    let product = apply("*", &[p.clone(), q.clone()]);
+    equation(spec, apply("*", &[dub(&f, &p), q.clone()]), dub(&f, &product));
+    equation(
+        spec,
+        apply("*", &[dub(&t, &p), q.clone()]),
+        apply("+", &[dub(&f, &product), q.clone()]),
+    );
+
+    let cq = dub(&c, &q);
+    equation(spec, apply("<", &[one.clone(), one.clone()]), f.clone());
+    equation(spec, apply("<", &[one.clone(), cq.clone()]), t.clone());
+    equation(spec, apply("<", &[bp.clone(), one.clone()]), f.clone());
+    equation(
+        spec,
+        apply("<", &[bp.clone(), cq.clone()]),
+        apply(
+            "if",
+            &[
+                apply("&&", &[apply("!", [b.clone()]), c.clone()]),
+                apply("<=", &[p.clone(), q.clone()]),
+                apply("<", &[p.clone(), q.clone()]),
+            ],
+        ),
+    );
+    equation(spec, apply("<=", &[one.clone(), q.clone()]), t.clone());
+    equation(spec, apply("<=", &[bp.clone(), one.clone()]), f.clone());
+    equation(
+        spec,
+        apply("<=", &[bp.clone(), cq]),
+        apply(
+            "if",
+            &[
+                apply("&&", &[b.clone(), apply("!", [c.clone()])]),
+                apply("<", &[p.clone(), q.clone()]),
+                apply("<=", &[p.clone(), q.clone()]),
+            ],
+        ),
+    );
+
+    // The predecessor and subtraction are natural numbers, where `@dub(b, n)` is `2 * n + b`.
+    let zero = constant("@c0");
+    let cnat = |p: DataExpression| apply("@cNat", &[p]);
+    let pred = |p: DataExpression| apply("pred", &[p]);
+    let double = |bit: &DataExpression, n: DataExpression| apply("@dub", &[bit.clone(), n]);
+    equation(spec, pred(one.clone()), zero.clone());
+    equation(spec, pred(dub(&t, &p)), cnat(dub(&f, &p)));
+    equation(spec, pred(dub(&f, &p)), double(&t, pred(p.clone())));
+    equation(spec, double(&f, zero.clone()), zero.clone());
+    equation(spec, double(&t, zero.clone()), cnat(one.clone()));
+    equation(spec, double(&b, cnat(p.clone())), cnat(bp.clone()));
+
+    // The difference `p - q - borrow` for `p >= q + borrow`.
+    let subtract =
+        |borrow: &DataExpression, p: DataExpression, q: DataExpression| apply("@gtesubtb", &[borrow.clone(), p, q]);
+    let nat2pos = |n: DataExpression| apply("Nat2Pos", &[n]);
+    equation(spec, subtract(&f, p.clone(), one.clone()), pred(p.clone()));
+    equation(
+        spec,
+        subtract(&t, p.clone(), one.clone()),
+        pred(nat2pos(pred(p.clone()))),
+    );
+    for (left, right, borrow) in [(&f, &f, &b), (&t, &t, &b), (&t, &f, &f), (&f, &t, &t)] {
+        let bit = if left == right {
+            b.clone()
+        } else {
+            apply("!", [b.clone()])
+        };
+        equation(
+            spec,
+            subtract(&b, dub(left, &p), dub(right, &q)),
+            double(&bit, subtract(borrow, p.clone(), q.clone())),
+        );
+    }
+
+    let minus = apply(
+        "if",
+        &[
+            apply("<", &[p.clone(), q.clone()]),
+            apply("@cNeg", &[nat2pos(subtract(&f, q.clone(), p.clone()))]),
+            apply("@cInt", &[subtract(&f, p.clone(), q.clone())]),
+        ],
+    );
+    equation(spec, apply("@pos_minus", &[p.clone(), q.clone()]), minus);
+    equation(
+        spec,
+        apply("-", &[one.clone(), q.clone()]),
+        apply("@pos_minus", &[one.clone(), q.clone()]),
+    );
+    equation(
+        spec,
+        apply("-", &[bp.clone(), q.clone()]),
+        apply("@pos_minus", &[bp, q]),
+    );
+
+    equation(spec, apply("Pos2Nat", [p.clone()]), cnat(p.clone()));
+    equation(spec, apply("Pos2Int", [p.clone()]), apply("@cInt", &[cnat(p)]));
+}
+
+fn add_nat(spec: &mut DataSpecification) {
+    add_constructor_sort(
+        spec,
+        "Nat",
+        nat_sort(),
+        &[("@c0", vec![]), ("@cNat", vec![pos_sort()])],
+        false,
+    );
+
+    mapping(spec, "succ", &[nat_sort()], pos_sort());
+    for name in ["+", "*"] {
+        mapping(spec, name, &[nat_sort(), nat_sort()], nat_sort());
+    }
+    mapping(spec, "-", &[nat_sort(), nat_sort()], int_sort());
+    mapping(spec, "Nat2Pos", &[nat_sort()], pos_sort());
+    mapping(spec, "Nat2Int", &[nat_sort()], int_sort());
+
+    let (t, f) = (constant("true"), constant("false"));
+    let (zero, one) = (constant("@c0"), constant("@c1"));
+    let (n, p, q) = (var("n"), var("p"), var("q"));
+    let cnat = |p: &DataExpression| apply("@cNat", [p.clone()]);
+    let cint = |n: DataExpression| apply("@cInt", &[n]);
+
+    equation(spec, apply("succ", [zero.clone()]), one);
+    equation(spec, apply("succ", &[cnat(&p)]), apply("succ", [p.clone()]));
+
+    equation(spec, apply("+", &[zero.clone(), n.clone()]), n.clone());
+    equation(spec, apply("+", &[cnat(&p), zero.clone()]), cnat(&p));
+    equation(
+        spec,
+        apply("+", &[cnat(&p), cnat(&q)]),
+        cnat(&apply("+", &[p.clone(), q.clone()])),
+    );
+    equation(spec, apply("*", &[zero.clone(), n.clone()]), zero.clone());
+    equation(spec, apply("*", &[cnat(&p), zero.clone()]), zero.clone());
+    equation(
+        spec,
+        apply("*", &[cnat(&p), cnat(&q)]),
+        cnat(&apply("*", &[p.clone(), q.clone()])),
+    );
+
+    equation(spec, apply("<", &[zero.clone(), zero.clone()]), f.clone());
+    equation(spec, apply("<", &[zero.clone(), cnat(&q)]), t.clone());
+    equation(spec, apply("<", &[cnat(&p), zero.clone()]), f.clone());
+    equation(
+        spec,
+        apply("<", &[cnat(&p), cnat(&q)]),
+        apply("<", &[p.clone(), q.clone()]),
+    );
+    equation(spec, apply("<=", &[zero.clone(), n.clone()]), t);
+    equation(spec, apply("<=", &[cnat(&p), zero.clone()]), f);
+    equation(
+        spec,
+        apply("<=", &[cnat(&p), cnat(&q)]),
+        apply("<=", &[p.clone(), q.clone()]),
+    );
+
+    equation(spec, apply("-", &[zero.clone(), zero.clone()]), cint(zero.clone()));
+    equation(spec, apply("-", &[zero.clone(), cnat(&q)]), apply("@cNeg", [q.clone()]));
+    equation(spec, apply("-", &[cnat(&p), zero.clone()]), cint(cnat(&p)));
+    equation(spec, apply("-", &[cnat(&p), cnat(&q)]), apply("-", &[p.clone(), q]));
+
+    equation(spec, apply("Nat2Pos", &[cnat(&p)]), p);
+    equation(spec, apply("Nat2Int", [n.clone()]), cint(n));
+}
+
+fn add_int(spec: &mut DataSpecification) {
+    add_constructor_sort(
+        spec,
+        "Int",
+        int_sort(),
+        &[("@cInt", vec![nat_sort()]), ("@cNeg", vec![pos_sort()])],
+        false,
+    );
+
+    mapping(spec, "negate", &[int_sort()], int_sort());
+    for name in ["+", "-", "*"] {
+        mapping(spec, name, &[int_sort(), int_sort()], int_sort());
+    }
+    mapping(spec, "abs", &[int_sort()], nat_sort());
+    mapping(spec, "Int2Nat", &[int_sort()], nat_sort());
+    mapping(spec, "Int2Pos", &[int_sort()], pos_sort());
+
+    let (t, f) = (constant("true"), constant("false"));
+    let zero = constant("@c0");
+    let (m, n, p, q, y) = (var("m"), var("n"), var("p"), var("q"), var("y"));
+    let cnat = |p: &DataExpression| apply("@cNat", [p.clone()]);
+    let cint = |n: &DataExpression| apply("@cInt", [n.clone()]);
+    let cneg = |p: &DataExpression| apply("@cNeg", [p.clone()]);
+    let negate = |x: DataExpression| apply("negate", &[x]);
+
+    equation(spec, negate(cint(&zero)), cint(&zero));
+    equation(spec, negate(cint(&cnat(&p))), cneg(&p));
+    equation(spec, negate(cneg(&p)), cint(&cnat(&p)));
+
+    equation(
+        spec,
+        apply("+", &[cint(&m), cint(&n)]),
+        cint(&apply("+", &[m.clone(), n.clone()])),
+    );
+    equation(
+        spec,
+        apply("+", &[cint(&m), cneg(&q)]),
+        apply("-", &[m.clone(), cnat(&q)]),
+    );
+    equation(
+        spec,
+        apply("+", &[cneg(&p), cint(&n)]),
+        apply("-", &[n.clone(), cnat(&p)]),
+    );
+    equation(
+        spec,
+        apply("+", &[cneg(&p), cneg(&q)]),
+        cneg(&apply("+", &[p.clone(), q.clone()])),
+    );
+
+    equation(
+        spec,
+        apply("-", &[cint(&m), y.clone()]),
+        apply("+", &[cint(&m), negate(y.clone())]),
+    );
+    equation(
+        spec,
+        apply("-", &[cneg(&p), y.clone()]),
+        apply("+", &[cneg(&p), negate(y)]),
+    );
+
+    equation(
+        spec,
+        apply("*", &[cint(&m), cint(&n)]),
+        cint(&apply("*", &[m.clone(), n.clone()])),
+    );
+    equation(
+        spec,
+        apply("*", &[cint(&m), cneg(&q)]),
+        negate(cint(&apply("*", &[m.clone(), cnat(&q)]))),
+    );
+    equation(
+        spec,
+        apply("*", &[cneg(&p), cint(&n)]),
+        negate(cint(&apply("*", &[cnat(&p), n.clone()]))),
+    );
+    equation(
+        spec,
+        apply("*", &[cneg(&p), cneg(&q)]),
+        cint(&cnat(&apply("*", &[p.clone(), q.clone()]))),
+    );
+
+    for name in ["<", "<="] {
+        equation(
+            spec,
+            apply(name, &[cint(&m), cint(&n)]),
+            apply(name, &[m.clone(), n.clone()]),
+        );
+        equation(spec, apply(name, &[cint(&m), cneg(&q)]), f.clone());
+        equation(spec, apply(name, &[cneg(&p), cint(&n)]), t.clone());
+        equation(
+            spec,
+            apply(name, &[cneg(&p), cneg(&q)]),
+            apply(name, &[q.clone(), p.clone()]),
+        );
+    }
+
+    equation(spec, apply("abs", &[cint(&n)]), n.clone());
+    equation(spec, apply("abs", &[cneg(&p)]), cnat(&p));
+    equation(spec, apply("Int2Nat", &[cint(&n)]), n);
+    equation(spec, apply("Int2Pos", &[cint(&cnat(&p))]), p);
+}
+
+fn add_list(spec: &mut DataSpecification) {
+    add_constructor_sort(
+        spec,
+        "List",
+        list_sort(),
+        &[("[]", vec![]), ("|>", vec![element_sort(), list_sort()])],
+        true,
+    );
+
+    mapping(spec, "<|", &[list_sort(), element_sort()], list_sort());
+    mapping(spec, "++", &[list_sort(), list_sort()], list_sort());
+    mapping(spec, "in", &[element_sort(), list_sort()], bool_sort());
+    mapping(spec, "#", &[list_sort()], nat_sort());
+    mapping(spec, ".", &[list_sort(), nat_sort()], element_sort());
+    for name in ["head", "rhead"] {
+        mapping(spec, name, &[list_sort()], element_sort());
+    }
+    for name in ["tail", "rtail"] {
+        mapping(spec, name, &[list_sort()], list_sort());
+    }
+
+    let empty = constant("[]");
+    let (x, y, l, m, p) = (var("x"), var("y"), var("l"), var("m"), var("p"));
+    let cons = |x: &DataExpression, l: DataExpression| apply("|>", &[x.clone(), l]);
+    let xl = cons(&x, l.clone());
+    let yl = cons(&y, l.clone());
+    let xyl = cons(&x, yl.clone());
+
+    equation(spec, apply("in", &[x.clone(), empty.clone()]), constant("false"));
+    equation(
+        spec,
+        apply("in", &[x.clone(), yl.clone()]),
+        apply(
+            "||",
+            &[
+                apply("==", &[x.clone(), y.clone()]),
+                apply("in", &[x.clone(), l.clone()]),
+            ],
+        ),
+    );
+    equation(spec, apply("#", [empty.clone()]), constant("@c0"));
+    equation(
+        spec,
+        apply("#", [xl.clone()]),
+        apply("@cNat", &[apply("succ", &[apply("#", [l.clone()])])]),
+    );
+
+    equation(spec, apply("<|", &[empty.clone(), x.clone()]), cons(&x, empty.clone()));
+    equation(
+        spec,
+        apply("<|", &[yl.clone(), x.clone()]),
+        cons(&y, apply("<|", &[l.clone(), x.clone()])),
+    );
+    equation(spec, apply("++", &[empty.clone(), m.clone()]), m.clone());
+    equation(
+        spec,
+        apply("++", &[xl.clone(), m.clone()]),
+        cons(&x, apply("++", &[l.clone(), m])),
+    );
+
+    equation(spec, apply("head", [xl.clone()]), x.clone());
+    equation(spec, apply("tail", [xl.clone()]), l.clone());
+    equation(spec, apply("rhead", &[cons(&x, empty.clone())]), x.clone());
+    equation(spec, apply("rhead", [xyl.clone()]), apply("rhead", [yl.clone()]));
+    equation(spec, apply("rtail", &[cons(&x, empty.clone())]), empty);
+    equation(spec, apply("rtail", &[xyl]), cons(&x, apply("rtail", &[yl])));
+
+    equation(spec, apply(".", &[xl.clone(), constant("@c0")]), x);
+    equation(
+        spec,
+        apply(".", &[xl, apply("@cNat", [p.clone()])]),
+        apply(".", &[l, apply("pred", &[p])]),
+    );
+}
+
+fn add_set(spec: &mut DataSpecification) {
+    add_constructor_sort(
+        spec,
+        "Set",
+        set_sort(),
+        &[("{}", vec![]), ("@fset_cons", vec![element_sort(), set_sort()])],
+        false,
+    );
+
+    mapping(spec, "@fset_insert", &[element_sort(), set_sort()], set_sort());
+    mapping(spec, "in", &[element_sort(), set_sort()], bool_sort());
+    for name in ["+", "*", "-"] {
+        mapping(spec, name, &[set_sort(), set_sort()], set_sort());
+    }
+    mapping(spec, "#", &[set_sort()], nat_sort());
+
+    let empty = constant("{}");
+    let (x, y, s, u) = (var("x"), var("y"), var("s"), var("u"));
+    let cons = |x: &DataExpression, s: DataExpression| apply("@fset_cons", &[x.clone(), s]);
+    let insert = |x: &DataExpression, s: DataExpression| apply("@fset_insert", &[x.clone(), s]);
+    let xs = cons(&x, s.clone());
+    let ys = cons(&y, s.clone());
+
+    // Inserts the element at its sorted position, unless it is already present.
+    equation(spec, insert(&x, empty.clone()), cons(&x, empty.clone()));
+    equation(
+        spec,
+        insert(&x, ys.clone()),
+        apply(
+            "if",
+            &[
+                apply("==", &[x.clone(), y.clone()]),
+                ys.clone(),
+                apply(
+                    "if",
+                    &[
+                        apply("<", &[x.clone(), y.clone()]),
+                        cons(&x, ys.clone()),
+                        cons(&y, insert(&x, s.clone())),
+                    ],
+                ),
+            ],
+        ),
+    );
+
+    equation(spec, apply("in", &[x.clone(), empty.clone()]), constant("false"));
+    equation(
+        spec,
+        apply("in", &[x.clone(), ys]),
+        apply(
+            "||",
+            &[
+                apply("==", &[x.clone(), y.clone()]),
+                apply("in", &[x.clone(), s.clone()]),
+            ],
+        ),
+    );
+
+    // Subset inclusion, where `<` is the strict subset.
+    equation(spec, apply("<=", &[empty.clone(), u.clone()]), constant("true"));
+    equation(
+        spec,
+        apply("<=", &[xs.clone(), u.clone()]),
+        apply(
+            "&&",
+            &[
+                apply("in", &[x.clone(), u.clone()]),
+                apply("<=", &[s.clone(), u.clone()]),
+            ],
+        ),
+    );
+    for set in [empty.clone(), xs.clone()] {
+        equation(
+            spec,
+            apply("<", &[set.clone(), u.clone()]),
+            apply(
+                "&&",
+                &[apply("<=", &[set.clone(), u.clone()]), apply("!=", &[set, u.clone()])],
+            ),
+        );
+    }
+
+    let contains = apply("in", &[x.clone(), u.clone()]);
+    equation(spec, apply("+", &[empty.clone(), u.clone()]), u.clone());
+    equation(
+        spec,
+        apply("+", &[xs.clone(), u.clone()]),
+        insert(&x, apply("+", &[s.clone(), u.clone()])),
+    );
+    equation(spec, apply("*", &[empty.clone(), u.clone()]), empty.clone());
+    let intersection = apply("*", &[s.clone(), u.clone()]);
+    equation(
+        spec,
+        apply("*", &[xs.clone(), u.clone()]),
+        apply(
+            "if",
+            &[contains.clone(), insert(&x, intersection.clone()), intersection],
+        ),
+    );
+    equation(spec, apply("-", &[empty.clone(), u.clone()]), empty.clone());
+    let difference = apply("-", &[s.clone(), u.clone()]);
+    equation(
+        spec,
+        apply("-", &[xs.clone(), u]),
+        apply("if", &[contains, difference.clone(), insert(&x, difference)]),
+    );
+
+    equation(spec, apply("#", &[empty]), constant("@c0"));
+    equation(
+        spec,
+        apply("#", &[xs]),
+        apply("@cNat", &[apply("succ", &[apply("#", &[s])])]),
+    );
+}
+
+fn add_bag(spec: &mut DataSpecification) {
+    add_constructor_sort(
+        spec,
+        "Bag",
+        bag_sort(),
+        &[
+            ("{:}", vec![]),
+            ("@fbag_cons", vec![element_sort(), pos_sort(), bag_sort()]),
+        ],
+        false,
+    );
+
+    mapping(
+        spec,
+        "@fbag_insert",
+        &[element_sort(), pos_sort(), bag_sort()],
+        bag_sort(),
+    );
+    mapping(spec, "count", &[element_sort(), bag_sort()], nat_sort());
+    mapping(spec, "in", &[element_sort(), bag_sort()], bool_sort());
+    mapping(spec, "+", &[bag_sort(), bag_sort()], bag_sort());
+
+    let empty = constant("{:}");
+    let (x, y, p, q, b, c) = (var("x"), var("y"), var("p"), var("q"), var("b"), var("c"));
+    let cons =
+        |x: &DataExpression, p: &DataExpression, b: DataExpression| apply("@fbag_cons", &[x.clone(), p.clone(), b]);
+    let insert =
+        |x: &DataExpression, p: &DataExpression, b: DataExpression| apply("@fbag_insert", &[x.clone(), p.clone(), b]);
+    let yqb = cons(&y, &q, b.clone());
+
+    // Inserts the element with its multiplicity at its sorted position, or adds the multiplicities.
+    equation(spec, insert(&x, &p, empty.clone()), cons(&x, &p, empty.clone()));
+    equation(
+        spec,
+        insert(&x, &p, yqb.clone()),
+        apply(
+            "if",
+            &[
+                apply("==", &[x.clone(), y.clone()]),
+                cons(&y, &apply("+", &[p.clone(), q.clone()]), b.clone()),
+                apply(
+                    "if",
+                    &[
+                        apply("<", &[x.clone(), y.clone()]),
+                        cons(&x, &p, yqb.clone()),
+                        cons(&y, &q, insert(&x, &p, b.clone())),
+                    ],
+                ),
+            ],
+        ),
+    );
+
+    let equal = apply("==", &[x.clone(), y.clone()]);
+    equation(spec, apply("count", &[x.clone(), empty.clone()]), constant("@c0"));
+    equation(
+        spec,
+        apply("count", &[x.clone(), yqb.clone()]),
+        apply(
+            "if",
+            &[
+                equal.clone(),
+                apply("@cNat", [q.clone()]),
+                apply("count", &[x.clone(), b.clone()]),
+            ],
+        ),
+    );
+    equation(spec, apply("in", &[x.clone(), empty.clone()]), constant("false"));
+    equation(
+        spec,
+        apply("in", &[x.clone(), yqb]),
+        apply("||", &[equal, apply("in", &[x.clone(), b.clone()])]),
+    );
+
+    equation(spec, apply("+", &[empty, c.clone()]), c.clone());
+    equation(
+        spec,
+        apply("+", &[cons(&x, &p, b.clone()), c.clone()]),
+        insert(&x, &p, apply("+", &[b, c])),
+    );
+}
+
+/// Adds the sort with the given constructors, and generates the equations of
+/// `==` that compare the constructors and their arguments. When `ordered` is
+/// set the equations of `<` and `<=` are generated as well, which order the
+/// terms by their constructor and then lexicographically by their arguments.
+fn add_constructor_sort(
+    spec: &mut DataSpecification,
+    name: &str,
+    sort: SortExpression,
+    constructors: &[(&str, Vec<SortExpression>)],
+    ordered: bool,
+) {
+    spec.add_sort(name);
+    for (constructor, arguments) in constructors {
+        spec.add_constructor(FunctionDeclaration {
+            symbol: DataFunctionSymbol::new(*constructor),
+            sort: function_sort(arguments, sort.clone()),
+        });
+    }
+
+    let pattern = |constructor: &str, arity: usize, prefix: &str| -> (DataExpression, Vec<DataExpression>) {
+        let arguments: Vec<DataExpression> = (0..arity).map(|index| var(&format!("{prefix}{index}"))).collect();
+        (apply(constructor, &arguments), arguments)
+    };
+
+    for (i, (left, left_arguments)) in constructors.iter().enumerate() {
+        for (j, (right, right_arguments)) in constructors.iter().enumerate() {
+            let (lhs, xs) = pattern(left, left_arguments.len(), "@x");
+            let (rhs, ys) = pattern(right, right_arguments.len(), "@y");
+
+            let equal = if i == j {
+                xs.iter().zip(&ys).rev().fold(constant("true"), |result, (x, y)| {
+                    conjunction(apply("==", &[x.clone(), y.clone()]), result)
+                })
+            } else {
+                constant("false")
+            };
+            equation(spec, apply("==", &[lhs.clone(), rhs.clone()]), equal);
+
+            if ordered {
+                for (operator, strict) in [("<", true), ("<=", false)] {
+                    let result = if i == j {
+                        // Lexicographic comparison: x0 < y0 || (x0 == y0 && ...).
+                        xs.iter()
+                            .zip(&ys)
+                            .rev()
+                            .fold(bool_expression(!strict), |result, (x, y)| {
+                                apply(
+                                    "||",
+                                    &[
+                                        apply("<", &[x.clone(), y.clone()]),
+                                        conjunction(apply("==", &[x.clone(), y.clone()]), result),
+                                    ],
+                                )
+                            })
+                    } else {
+                        bool_expression(i < j)
+                    };
+                    equation(spec, apply(operator, &[lhs.clone(), rhs.clone()]), result);
+                }
+            }
+        }
+    }
+}
+
+/// Returns `lhs && rhs`, where a trailing `true` is omitted.
+fn conjunction(lhs: DataExpression, rhs: DataExpression) -> DataExpression {
+    if rhs == constant("true") {
+        lhs
+    } else {
+        apply("&&", &[lhs, rhs])
+    }
+}
+
+/// Adds the mapping with the given name and sort.
+fn mapping(spec: &mut DataSpecification, name: &str, domain: &[SortExpression], range: SortExpression) {
+    spec.add_mapping(FunctionDeclaration {
+        symbol: DataFunctionSymbol::new(name),
+        sort: function_sort(domain, range),
+    });
+}
+
+/// Adds the unconditional equation `lhs = rhs`, whose variables are the variables occurring in the left-hand side.
+fn equation(spec: &mut DataSpecification, lhs: DataExpression, rhs: DataExpression) {
+    let mut variables: Vec<DataVariable> = Vec::new();
+    for term in lhs.iter() {
+        if is_data_variable(&term) {
+            let variable = DataVariable::from(term.protect());
+            if !variables.contains(&variable) {
+                variables.push(variable);
+            }
+        }
+    }
+
+    spec.add_equation(DataEquation {
+        variables,
+        condition: None,
+        lhs,
+        rhs,
+    });
+}
+
+/// Returns the variable with the given name.
+fn var(name: &str) -> DataExpression {
+    DataVariable::new(name).into()
+}
+
+/// Returns the constant with the given name.
+fn constant(name: &str) -> DataExpression {
+    DataFunctionSymbol::new(name).into()
+}
+
+/// Returns the application of the function symbol with the given name, or the constant when there are no arguments.
+fn apply(name: &str, arguments: impl AsRef<[DataExpression]>) -> DataExpression {
+    let arguments = arguments.as_ref();
+    if arguments.is_empty() {
+        constant(name)
+    } else {
+        DataApplication::with_args(&DataFunctionSymbol::new(name), arguments).into()
+    }
+}
+
+/// Returns the sort `domain -> range`, or only the range when the domain is empty.
+fn function_sort(domain: &[SortExpression], range: SortExpression) -> SortExpression {
+    match domain.iter().cloned().reduce(|lhs, rhs| SortExpression::Product {
+        lhs: Box::new(lhs),
+        rhs: Box::new(rhs),
+    }) {
+        Some(domain) => SortExpression::Function {
+            domain: Box::new(domain),
+            range: Box::new(range),
+        },
+        None => range,
+    }
+}
+
+fn bool_sort() -> SortExpression {
+    SortExpression::Simple(Sort::Bool)
+}
+
+fn pos_sort() -> SortExpression {
+    SortExpression::Simple(Sort::Pos)
+}
+
+fn nat_sort() -> SortExpression {
+    SortExpression::Simple(Sort::Nat)
+}
+
+fn int_sort() -> SortExpression {
+    SortExpression::Simple(Sort::Int)
+}
+
+/// The element sort of the containers, which are polymorphic since the equations are untyped.
+fn element_sort() -> SortExpression {
+    SortExpression::Reference("S".to_string())
+}
+
+fn list_sort() -> SortExpression {
+    SortExpression::Complex(ComplexSort::List, Box::new(element_sort()))
+}
+
+fn set_sort() -> SortExpression {
+    SortExpression::Complex(ComplexSort::FSet, Box::new(element_sort()))
+}
+
+fn bag_sort() -> SortExpression {
+    SortExpression::Complex(ComplexSort::FBag, Box::new(element_sort()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_number_expressions() {
+        assert_eq!(pos_expression(1).to_string(), "@c1");
+        assert_eq!(pos_expression(6).to_string(), "@cDub(false, @cDub(true, @c1))");
+        assert_eq!(nat_expression(0).to_string(), "@c0");
+        assert_eq!(int_expression(-2).to_string(), "@cNeg(@cDub(false, @c1))");
+    }
+
+    #[test]
+    fn test_standard_data_specification() {
+        let spec = standard_data_specification();
+        assert_eq!(spec.sorts(), ["Bool", "Pos", "Nat", "Int", "List", "Set", "Bag"]);
+
+        // The rules do not overlap, so every equation has a left-hand side that differs from the others.
+        for (index, equation) in spec.equations().iter().enumerate() {
+            assert!(
+                !spec.equations()[..index].iter().any(|other| other.lhs == equation.lhs),
+                "Duplicate equation for {}",
+                equation.lhs
+            );
+        }
+    }
+}
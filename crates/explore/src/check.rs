@@ -0,0 +1,217 @@
+#![forbid(unsafe_code)]
+
+use std::collections::VecDeque;
+use std::fmt;
+
+use itertools::Itertools;
+use log::info;
+
+use merc_collections::IndexedSet;
+use merc_collections::SetIndex;
+use merc_data::DataExpression;
+use merc_sabre::RewriteEngine;
+use merc_utilities::MercError;
+
+use crate::Constants;
+use crate::LinearProcess;
+use crate::initial_state;
+use crate::successors;
+
+/// A property that is checked on-the-fly by [check].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Property {
+    /// A state without outgoing transitions is reachable.
+    Deadlock,
+    /// A transition labelled by the given action is reachable. The action
+    /// matches a label when it is equal to the label, or to the name of its
+    /// head symbol, such that `a` matches both `a` and `a(zero)`.
+    Action(String),
+}
+
+impl Property {
+    /// Returns true iff the given transition label is the action of this property.
+    fn matches(&self, label: &str) -> bool {
+        match self {
+            Property::Deadlock => false,
+            Property::Action(action) => {
+                label == action || label.split_once('(').is_some_and(|(head, _)| head == action)
+            }
+        }
+    }
+}
+
+impl fmt::Display for Property {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Property::Deadlock => write!(f, "deadlock"),
+            Property::Action(action) => write!(f, "action {action}"),
+        }
+    }
+}
+
+/// A trace from the initial state to a state that witnesses a [Property].
+#[derive(Clone, Debug)]
+pub struct Counterexample {
+    /// The states along the trace, starting with the initial state.
+    pub states: Vec<Vec<DataExpression>>,
+    /// The labels of the transitions between consecutive states, which has one element less than `states`.
+    pub labels: Vec<String>,
+}
+
+impl fmt::Display for Counterexample {
+    /// Prints the labels of the trace, one per line.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.labels.iter().format("\n"))
+    }
+}
+
+/// Explores the state space of the given linear process in breadth-first order
+/// until a state witnessing the given property is found, see [crate::explore].
+///
+/// # Details
+///
+/// Returns `None` when the property does not hold in any reachable state, and
+/// otherwise a shortest trace towards the first witness, which is
+/// reconstructed from the breadth-first search parents of the states. For an
+/// action the trace ends with the transition labelled by that action.
+pub fn check<R: RewriteEngine>(
+    process: &LinearProcess,
+    rewriter: &mut R,
+    property: &Property,
+) -> Result<Option<Counterexample>, MercError> {
+    let constants = Constants::new()?;
+
+    let mut states: IndexedSet<Vec<DataExpression>> = IndexedSet::new();
+    let mut parents: Vec<Option<(SetIndex, String)>> = Vec::new();
+    let mut queue = VecDeque::new();
+
+    let initial_state = initial_state(process, rewriter);
+    let (initial_index, _) = states.insert(initial_state.clone());
+    parents.push(None);
+    queue.push_back((initial_index, initial_state));
+
+    while let Some((state_index, state)) = queue.pop_front() {
+        let transitions = successors(process, rewriter, &constants, &state)?;
+
+        if *property == Property::Deadlock && transitions.is_empty() {
+            info!("Found a deadlock after exploring {} states", states.len());
+            return Ok(Some(counterexample(&states, &parents, state_index)));
+        }
+
+        for (label, next_state) in transitions {
+            if property.matches(&label) {
+                info!("Found action {label} after exploring {} states", states.len());
+                let mut trace = counterexample(&states, &parents, state_index);
+                trace.labels.push(label);
+                trace.states.push(next_state);
+                return Ok(Some(trace));
+            }
+
+            let (next_index, inserted) = states.insert(next_state.clone());
+            if inserted {
+                parents.push(Some((state_index, label)));
+                queue.push_back((next_index, next_state));
+            }
+        }
+    }
+
+    info!("Explored {} states without finding {property}", states.len());
+    Ok(None)
+}
+
+/// Returns the trace from the initial state to the given state by following the parents.
+fn counterexample(
+    states: &IndexedSet<Vec<DataExpression>>,
+    parents: &[Option<(SetIndex, String)>],
+    mut state_index: SetIndex,
+) -> Counterexample {
+    let mut trace = Counterexample {
+        states: vec![states[state_index].clone()],
+        labels: Vec::new(),
+    };
+
+    while let Some((parent, label)) = &parents[*state_index] {
+        trace.states.push(states[*parent].clone());
+        trace.labels.push(label.clone());
+        state_index = *parent;
+    }
+
+    trace.states.reverse();
+    trace.labels.reverse();
+    trace
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utility::counter_rewriter;
+
+    use super::*;
+
+    #[test]
+    fn test_check_deadlock() {
+        let process = LinearProcess::from_string(
+            "proc P(x)
+            init P(zero)
+            sum lt(x, succ(succ(succ(zero)))) -> inc(x) . P(succ(x))",
+        )
+        .unwrap();
+
+        let trace = check(&process, &mut counter_rewriter(), &Property::Deadlock)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            trace.labels,
+            vec!["inc(zero)", "inc(succ(zero))", "inc(succ(succ(zero)))"]
+        );
+        assert_eq!(trace.states.len(), 4);
+    }
+
+    #[test]
+    fn test_check_no_deadlock() {
+        let process = LinearProcess::from_string(
+            "proc P(x)
+            init P(zero)
+            sum lt(x, succ(succ(succ(zero)))) -> inc(x) . P(succ(x))
+            sum tau . P(x)",
+        )
+        .unwrap();
+
+        assert!(
+            check(&process, &mut counter_rewriter(), &Property::Deadlock)
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_check_action() {
+        let process = LinearProcess::from_string(
+            "proc P(x)
+            init P(zero)
+            sum lt(x, succ(succ(succ(zero)))) -> inc(x) . P(succ(x))
+            sum tau . P(x)",
+        )
+        .unwrap();
+
+        let trace = check(
+            &process,
+            &mut counter_rewriter(),
+            &Property::Action("inc(succ(zero))".to_string()),
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(trace.labels, vec!["inc(zero)", "inc(succ(zero))"]);
+        assert_eq!(trace.states.len(), 3);
+
+        let trace = check(&process, &mut counter_rewriter(), &Property::Action("inc".to_string()))
+            .unwrap()
+            .unwrap();
+        assert_eq!(trace.labels, vec!["inc(zero)"]);
+
+        assert!(
+            check(&process, &mut counter_rewriter(), &Property::Action("dec".to_string()))
+                .unwrap()
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,392 @@
+#![forbid(unsafe_code)]
+
+use std::collections::HashMap;
+use std::collections::VecDeque;
+use std::path::Path;
+
+use log::debug;
+use log::info;
+
+use merc_aterm::ATerm;
+use merc_aterm::ATermString;
+use merc_aterm::Checkpoint;
+use merc_aterm::Term;
+use merc_aterm::apply_substitution;
+use merc_collections::IndexedSet;
+use merc_collections::SetIndex;
+use merc_data::DataExpression;
+use merc_lts::LabelledTransitionSystem;
+use merc_lts::LtsBuilderFast;
+use merc_lts::StateIndex;
+use merc_lts::TransitionLabel;
+use merc_sabre::RewriteEngine;
+use merc_utilities::MercError;
+
+use crate::LinearProcess;
+
+/// Generates the state space of the given linear process in breadth-first
+/// order and returns it as a labelled transition system.
+///
+/// # Details
+///
+/// A state is the vector of values of the process parameters. For every
+/// discovered state the parameters are substituted in the summands, after
+/// which the conditions, actions and next states are evaluated by the given
+/// rewriter. A summand is enabled when its condition rewrites to `true`, and it
+/// is an error when the condition rewrites to anything other than `true` or
+/// `false`. The action `tau` is the internal action, other actions are labelled
+/// by their normal form. The initial state has index zero.
+pub fn explore<R: RewriteEngine>(
+    process: &LinearProcess,
+    rewriter: &mut R,
+) -> Result<LabelledTransitionSystem<String>, MercError> {
+    explore_impl(process, rewriter, None)
+}
+
+/// The same as [explore], but periodically saves the progress to a
+/// [Checkpoint] at the given path such that a crashed run can be continued.
+///
+/// # Details
+///
+/// When the given path already contains a checkpoint the exploration continues
+/// from that checkpoint, which must have been created for the same process.
+/// Otherwise, the exploration starts from the initial state. A checkpoint is
+/// written every `interval` explored states, and once more when the
+/// exploration has finished.
+pub fn explore_with_checkpoint<R: RewriteEngine>(
+    process: &LinearProcess,
+    rewriter: &mut R,
+    path: &Path,
+    interval: usize,
+) -> Result<LabelledTransitionSystem<String>, MercError> {
+    explore_impl(process, rewriter, Some((path, interval.max(1))))
+}
+
+/// Explores the state space, see [explore], optionally storing checkpoints at
+/// the given path every given number of states.
+fn explore_impl<R: RewriteEngine>(
+    process: &LinearProcess,
+    rewriter: &mut R,
+    checkpoint: Option<(&Path, usize)>,
+) -> Result<LabelledTransitionSystem<String>, MercError> {
+    let constants = Constants::new()?;
+
+    let mut states: IndexedSet<Vec<DataExpression>> = IndexedSet::new();
+    let mut queue = VecDeque::new();
+    let mut builder = LtsBuilderFast::new(Vec::new(), Vec::new());
+
+    let initial_state = initial_state(process, rewriter);
+    match checkpoint {
+        Some((path, _)) if path.exists() => {
+            restore_exploration(process, &Checkpoint::load(path)?, &mut states, &mut queue, &mut builder)?;
+            info!(
+                "Continuing from a checkpoint with {} states, of which {} are unexplored",
+                states.len(),
+                queue.len()
+            );
+        }
+        _ => {
+            let (initial_index, _) = states.insert(initial_state.clone());
+            queue.push_back((initial_index, initial_state.clone()));
+        }
+    }
+
+    let mut explored = 0;
+    while let Some((state_index, state)) = queue.pop_front() {
+        for (label, next_state) in successors(process, rewriter, &constants, &state)? {
+            let (next_index, inserted) = states.insert(next_state.clone());
+            if inserted {
+                debug!("Discovered state {}", states.len() - 1);
+                queue.push_back((next_index, next_state));
+            }
+
+            builder.add_transition(StateIndex::new(*state_index), &label, StateIndex::new(*next_index));
+        }
+
+        explored += 1;
+        if let Some((path, interval)) = checkpoint {
+            if explored % interval == 0 || queue.is_empty() {
+                debug!("Writing a checkpoint with {} states", states.len());
+                checkpoint_exploration(process, &states, &queue, &builder).save(path)?;
+            }
+        }
+    }
+
+    info!(
+        "Explored {} states and {} transitions",
+        states.len(),
+        builder.num_of_transitions()
+    );
+
+    // The initial state is always the first discovered state, also when continuing from a checkpoint.
+    let initial_index = states
+        .index(&initial_state)
+        .ok_or("The checkpoint does not contain the initial state")?;
+    builder.require_num_of_states(states.len());
+    Ok(builder.finish(StateIndex::new(*initial_index), true))
+}
+
+/// Stores the progress of the exploration in a checkpoint.
+///
+/// The terms are the parameter values of all states in order of their index,
+/// followed by the labels. The handles are the number of parameters, states,
+/// labels and unexplored states, followed by the transitions as triples of
+/// indices. Since states are explored in order of their index the unexplored
+/// states are always the last discovered states.
+fn checkpoint_exploration(
+    process: &LinearProcess,
+    states: &IndexedSet<Vec<DataExpression>>,
+    queue: &VecDeque<(SetIndex, Vec<DataExpression>)>,
+    builder: &LtsBuilderFast<String>,
+) -> Checkpoint {
+    let terms = states
+        .iter()
+        .flat_map(|(_, state)| state.iter().map(|value| value.protect()))
+        .chain(ATermString::from_strs(builder.labels()).into_iter().map(Into::into))
+        .collect();
+
+    let mut handles = vec![
+        process.parameters().len(),
+        states.len(),
+        builder.labels().len(),
+        queue.len(),
+    ];
+    for (from, label, to) in builder.iter() {
+        handles.extend([from.value(), label.value(), to.value()]);
+    }
+
+    Checkpoint::new(terms, handles)
+}
+
+/// Restores the progress of the exploration from a checkpoint created by
+/// [checkpoint_exploration].
+fn restore_exploration(
+    process: &LinearProcess,
+    checkpoint: &Checkpoint,
+    states: &mut IndexedSet<Vec<DataExpression>>,
+    queue: &mut VecDeque<(SetIndex, Vec<DataExpression>)>,
+    builder: &mut LtsBuilderFast<String>,
+) -> Result<(), MercError> {
+    let [
+        num_of_parameters,
+        num_of_states,
+        num_of_labels,
+        num_of_unexplored,
+        transitions @ ..,
+    ] = checkpoint.handles()
+    else {
+        return Err("The checkpoint is missing the exploration header".into());
+    };
+
+    if *num_of_parameters != process.parameters().len() {
+        return Err(format!(
+            "The checkpoint has {num_of_parameters} parameters, but the process has {}",
+            process.parameters().len()
+        )
+        .into());
+    }
+
+    if checkpoint.terms().len() != num_of_parameters * num_of_states + num_of_labels
+        || num_of_unexplored > num_of_states
+        || transitions.len() % 3 != 0
+    {
+        return Err("The checkpoint does not describe a valid exploration".into());
+    }
+
+    let (values, labels) = checkpoint.terms().split_at(num_of_parameters * num_of_states);
+    for index in 0..*num_of_states {
+        let state: Vec<DataExpression> = values[index * num_of_parameters..(index + 1) * num_of_parameters]
+            .iter()
+            .map(|value| value.clone().into())
+            .collect();
+        let (state_index, _) = states.insert(state.clone());
+        if index >= num_of_states - num_of_unexplored {
+            queue.push_back((state_index, state));
+        }
+    }
+
+    let labels: Vec<String> = labels
+        .iter()
+        .map(|label| ATermString::from(label.clone()).as_str().to_string())
+        .collect();
+    for transition in transitions.chunks(3) {
+        let label = labels
+            .get(transition[1])
+            .ok_or("The checkpoint contains a transition with an unknown label")?;
+        builder.add_transition(StateIndex::new(transition[0]), label, StateIndex::new(transition[2]));
+    }
+
+    Ok(())
+}
+
+/// The terms that are compared against while evaluating summands.
+pub(crate) struct Constants {
+    pub(crate) true_term: DataExpression,
+    pub(crate) false_term: DataExpression,
+    pub(crate) tau_term: DataExpression,
+}
+
+impl Constants {
+    pub(crate) fn new() -> Result<Self, MercError> {
+        Ok(Self {
+            true_term: DataExpression::from_string("true")?,
+            false_term: DataExpression::from_string("false")?,
+            tau_term: DataExpression::from_string("tau")?,
+        })
+    }
+}
+
+/// Returns the normal form of the initial state of the given process.
+pub(crate) fn initial_state<R: RewriteEngine>(process: &LinearProcess, rewriter: &mut R) -> Vec<DataExpression> {
+    process
+        .initial_state()
+        .iter()
+        .map(|value| rewriter.rewrite(value))
+        .collect()
+}
+
+/// Returns the outgoing transitions of the given state as pairs of labels and
+/// next states, in the order of the summands.
+pub(crate) fn successors<R: RewriteEngine>(
+    process: &LinearProcess,
+    rewriter: &mut R,
+    constants: &Constants,
+    state: &[DataExpression],
+) -> Result<Vec<(String, Vec<DataExpression>)>, MercError> {
+    Ok(enabled_summands(process, rewriter, constants, state)?
+        .into_iter()
+        .map(|(_, label, next_state)| (label, next_state))
+        .collect())
+}
+
+/// Returns the outgoing transitions of the given state as triples of the index
+/// of the enabled summand, the label and the next state, see [successors].
+pub(crate) fn enabled_summands<R: RewriteEngine>(
+    process: &LinearProcess,
+    rewriter: &mut R,
+    constants: &Constants,
+    state: &[DataExpression],
+) -> Result<Vec<(usize, String, Vec<DataExpression>)>, MercError> {
+    let sigma: HashMap<ATerm, ATerm> = process
+        .parameters()
+        .iter()
+        .zip(state.iter())
+        .map(|(parameter, value)| (parameter.protect(), value.protect()))
+        .collect();
+
+    let mut result = Vec::new();
+    for (index, summand) in process.summands().iter().enumerate() {
+        if let Some(condition) = &summand.condition {
+            let value = rewriter.rewrite(&apply_substitution(condition, &sigma).into());
+            if value == constants.false_term {
+                continue;
+            } else if value != constants.true_term {
+                return Err(format!("Condition {condition} rewrites to {value}, which is not a boolean").into());
+            }
+        }
+
+        let action = rewriter.rewrite(&apply_substitution(&summand.action, &sigma).into());
+        let next_state: Vec<DataExpression> = summand
+            .next_state
+            .iter()
+            .map(|value| rewriter.rewrite(&apply_substitution(value, &sigma).into()))
+            .collect();
+
+        let label = if action == constants.tau_term {
+            String::tau_label()
+        } else {
+            action.to_string()
+        };
+        result.push((index, label, next_state));
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use merc_lts::LTS;
+    use merc_sabre::SabreRewriter;
+
+    use crate::test_utility::counter_rewriter;
+
+    use super::*;
+
+    /// Returns a rewriter and a process that counts from zero up to three.
+    fn counter() -> (SabreRewriter, LinearProcess) {
+        let process = LinearProcess::from_string(
+            "proc P(x)
+            init P(zero)
+            sum lt(x, succ(succ(succ(zero)))) -> inc(x) . P(succ(x))
+            sum tau . P(x)",
+        )
+        .unwrap();
+
+        (counter_rewriter(), process)
+    }
+
+    #[test]
+    fn test_explore_counter() {
+        let (mut rewriter, process) = counter();
+        let lts = explore(&process, &mut rewriter).unwrap();
+
+        // The counter has the values zero up to three, each with a tau loop.
+        assert_eq!(lts.num_of_states(), 4);
+        assert_eq!(lts.num_of_transitions(), 7);
+        assert_eq!(lts.initial_state_index(), StateIndex::new(0));
+        assert!(lts.labels().iter().any(|label| label == "inc(zero)"));
+        assert!(lts.labels().iter().all(|label| label != "tau"));
+    }
+
+    #[test]
+    fn test_explore_with_checkpoint() {
+        let (mut rewriter, process) = counter();
+        let expected = explore(&process, &mut rewriter).unwrap();
+
+        // Simulate a crash after exploring only the initial state.
+        let constants = Constants::new().unwrap();
+        let mut states = IndexedSet::new();
+        let mut queue = VecDeque::new();
+        let mut builder = LtsBuilderFast::new(Vec::new(), Vec::new());
+
+        let state = initial_state(&process, &mut rewriter);
+        let (initial_index, _) = states.insert(state.clone());
+        for (label, next_state) in successors(&process, &mut rewriter, &constants, &state).unwrap() {
+            let (next_index, inserted) = states.insert(next_state.clone());
+            if inserted {
+                queue.push_back((next_index, next_state));
+            }
+            builder.add_transition(StateIndex::new(*initial_index), &label, StateIndex::new(*next_index));
+        }
+
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("counter.checkpoint");
+        checkpoint_exploration(&process, &states, &queue, &builder)
+            .save(&path)
+            .unwrap();
+
+        let lts = explore_with_checkpoint(&process, &mut rewriter, &path, 1).unwrap();
+        assert_eq!(lts.num_of_states(), expected.num_of_states());
+        assert_eq!(lts.num_of_transitions(), expected.num_of_transitions());
+        assert_eq!(lts.initial_state_index(), expected.initial_state_index());
+        assert_eq!(lts.labels(), expected.labels());
+
+        // The final checkpoint contains the complete state space.
+        let lts = explore_with_checkpoint(&process, &mut rewriter, &path, 1).unwrap();
+        assert_eq!(lts.num_of_states(), expected.num_of_states());
+        assert_eq!(lts.num_of_transitions(), expected.num_of_transitions());
+    }
+
+    #[test]
+    fn test_explore_non_boolean_condition() {
+        let process = LinearProcess::from_string(
+            "proc P(x)
+            init P(zero)
+            sum x -> a . P(x)",
+        )
+        .unwrap();
+
+        let mut rewriter = SabreRewriter::new(&Default::default());
+        assert!(explore(&process, &mut rewriter).is_err());
+    }
+}
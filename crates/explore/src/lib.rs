@@ -0,0 +1,16 @@
+#![doc = include_str!("../README.md")]
+
+mod check;
+mod explore;
+mod linear_process;
+mod parallel_explore;
+mod partial_order;
+
+#[cfg(test)]
+mod test_utility;
+
+pub use check::*;
+pub use explore::*;
+pub use linear_process::*;
+pub use parallel_explore::*;
+pub use partial_order::*;
@@ -0,0 +1,275 @@
+#![forbid(unsafe_code)]
+
+use std::fmt;
+
+use ahash::AHashSet;
+use itertools::Itertools;
+
+use merc_aterm::ATerm;
+use merc_aterm::Symb;
+use merc_aterm::Term;
+use merc_data::DataExpression;
+use merc_data::DataVariable;
+use merc_data::to_untyped_data_expression;
+use merc_utilities::MercError;
+use merc_utilities::Span;
+
+/// A summand `condition -> action . P(next_state)` of a [LinearProcess].
+#[derive(Clone, Debug)]
+pub struct Summand {
+    /// The condition under which the summand is enabled, which is always enabled when absent.
+    pub condition: Option<DataExpression>,
+    /// The action that is performed, where the constant `tau` is the internal action.
+    pub action: DataExpression,
+    /// The values of the process parameters after performing the action.
+    pub next_state: Vec<DataExpression>,
+}
+
+/// A linear process `P(d_1, ..., d_n)` consisting of a number of summands,
+/// where the expressions of every summand can refer to the process parameters.
+///
+/// # Details
+///
+/// A linear process is given in a line based textual format, where empty lines
+/// and lines starting with `#` are ignored. The first lines declare the process
+/// with its parameters and the initial state, followed by one line for every
+/// summand. The condition of a summand is optional.
+///
+/// ```text
+/// proc P(x, y)
+/// init P(zero, zero)
+/// sum lt(x, three) -> inc(x) . P(succ(x), y)
+/// sum tau . P(x, y)
+/// ```
+///
+/// The data expressions are untyped and their function symbols must be
+/// defined by a rewrite specification to evaluate them, see [crate::explore].
+/// Sums over data variables are not supported.
+#[derive(Clone, Debug)]
+pub struct LinearProcess {
+    name: String,
+    parameters: Vec<DataVariable>,
+    initial_state: Vec<DataExpression>,
+    summands: Vec<Summand>,
+}
+
+impl LinearProcess {
+    /// Creates a new linear process, where the initial state and the next
+    /// state of every summand must assign a value to every parameter.
+    pub fn new(
+        name: String,
+        parameters: Vec<DataVariable>,
+        initial_state: Vec<DataExpression>,
+        summands: Vec<Summand>,
+    ) -> Result<Self, MercError> {
+        if initial_state.len() != parameters.len() {
+            return Err(format!(
+                "The initial state has {} values, but process {name} has {} parameters",
+                initial_state.len(),
+                parameters.len()
+            )
+            .into());
+        }
+
+        if let Some(summand) = summands
+            .iter()
+            .find(|summand| summand.next_state.len() != parameters.len())
+        {
+            return Err(format!(
+                "The next state of summand {} has {} values, but process {name} has {} parameters",
+                summand.action,
+                summand.next_state.len(),
+                parameters.len()
+            )
+            .into());
+        }
+
+        Ok(Self {
+            name,
+            parameters,
+            initial_state,
+            summands,
+        })
+    }
+
+    /// Parses a linear process from the textual format described in [LinearProcess].
+    pub fn from_string(text: &str) -> Result<Self, MercError> {
+        let mut declaration: Option<(String, Vec<String>)> = None;
+        let mut initial_state = None;
+        let mut summands = Vec::new();
+
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (keyword, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+            let rest = rest.trim();
+            let span = Some(Span::line(number + 1));
+
+            match keyword {
+                "proc" => {
+                    if declaration.is_some() {
+                        return Err(MercError::parse("The process is declared twice", span));
+                    }
+
+                    let term = ATerm::from_string(rest)?;
+                    let mut parameters = Vec::new();
+                    for argument in term.arguments() {
+                        if argument.get_head_symbol().arity() != 0 {
+                            return Err(MercError::parse(
+                                format!("Parameter {argument} is not a variable"),
+                                span,
+                            ));
+                        }
+                        parameters.push(argument.get_head_symbol().name().to_string());
+                    }
+
+                    declaration = Some((term.get_head_symbol().name().to_string(), parameters));
+                }
+                "init" => {
+                    let (name, _) = declaration
+                        .as_ref()
+                        .ok_or_else(|| MercError::parse("The process must be declared first", span))?;
+                    initial_state = Some(parse_process_instance(rest, name, &AHashSet::new())?);
+                }
+                "sum" => {
+                    let (name, parameters) = declaration
+                        .as_ref()
+                        .ok_or_else(|| MercError::parse("The process must be declared first", span))?;
+                    let variables = AHashSet::from_iter(parameters.iter().cloned());
+
+                    let (left, next_state) = rest
+                        .rsplit_once('.')
+                        .ok_or_else(|| MercError::parse("A summand must be of the shape c -> a . P(e)", span))?;
+
+                    let (condition, action) = match left.split_once("->") {
+                        Some((condition, action)) => (
+                            Some(DataExpression::from_string_untyped(condition.trim(), &variables)?),
+                            action,
+                        ),
+                        None => (None, left),
+                    };
+
+                    summands.push(Summand {
+                        condition,
+                        action: DataExpression::from_string_untyped(action.trim(), &variables)?,
+                        next_state: parse_process_instance(next_state, name, &variables)?,
+                    });
+                }
+                _ => {
+                    return Err(MercError::parse(format!("Unknown keyword {keyword}"), span));
+                }
+            }
+        }
+
+        let (name, parameters) = declaration.ok_or_else(|| MercError::parse("The process is not declared", None))?;
+        let initial_state =
+            initial_state.ok_or_else(|| MercError::parse("The initial state is not specified", None))?;
+
+        Self::new(
+            name,
+            parameters
+                .iter()
+                .map(|parameter| DataVariable::new(parameter.as_str()))
+                .collect(),
+            initial_state,
+            summands,
+        )
+    }
+
+    /// Returns the name of the process.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the process parameters.
+    pub fn parameters(&self) -> &[DataVariable] {
+        &self.parameters
+    }
+
+    /// Returns the values of the process parameters in the initial state.
+    pub fn initial_state(&self) -> &[DataExpression] {
+        &self.initial_state
+    }
+
+    /// Returns the summands of the process.
+    pub fn summands(&self) -> &[Summand] {
+        &self.summands
+    }
+}
+
+/// Parses `P(e_1, ..., e_n)` and returns the expressions `e_1, ..., e_n`.
+fn parse_process_instance(
+    text: &str,
+    name: &str,
+    variables: &AHashSet<String>,
+) -> Result<Vec<DataExpression>, MercError> {
+    let term = ATerm::from_string(text.trim())?;
+    if term.get_head_symbol().name() != name {
+        return Err(MercError::parse(
+            format!("Expected an instance of process {name}, but found {term}"),
+            None,
+        ));
+    }
+
+    Ok(term
+        .arguments()
+        .map(|argument| to_untyped_data_expression(argument.protect(), Some(variables)))
+        .collect())
+}
+
+impl fmt::Display for LinearProcess {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "proc {}({})", self.name, self.parameters.iter().format(", "))?;
+        writeln!(f, "init {}({})", self.name, self.initial_state.iter().format(", "))?;
+
+        for summand in &self.summands {
+            write!(f, "sum ")?;
+            if let Some(condition) = &summand.condition {
+                write!(f, "{condition} -> ")?;
+            }
+            writeln!(
+                f,
+                "{} . {}({})",
+                summand.action,
+                self.name,
+                summand.next_state.iter().format(", ")
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_linear_process() {
+        let process = LinearProcess::from_string(
+            "# A simple counter
+            proc P(x, y)
+            init P(zero, zero)
+            sum lt(x, three) -> inc(x) . P(succ(x), y)
+            sum tau . P(x, y)",
+        )
+        .unwrap();
+
+        assert_eq!(process.name(), "P");
+        assert_eq!(process.parameters().len(), 2);
+        assert_eq!(process.initial_state().len(), 2);
+        assert_eq!(process.summands().len(), 2);
+        assert!(process.summands()[0].condition.is_some());
+        assert!(process.summands()[1].condition.is_none());
+    }
+
+    #[test]
+    fn test_parse_linear_process_errors() {
+        assert!(LinearProcess::from_string("init P(zero)").is_err());
+        assert!(LinearProcess::from_string("proc P(x)\ninit P(zero, zero)").is_err());
+        assert!(LinearProcess::from_string("proc P(x)\ninit P(zero)\nsum a . Q(x)").is_err());
+    }
+}
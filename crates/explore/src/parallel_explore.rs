@@ -0,0 +1,453 @@
+#![forbid(unsafe_code)]
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::thread;
+
+use dashmap::DashMap;
+use dashmap::mapref::entry::Entry;
+use log::debug;
+use log::info;
+
+use merc_aterm::ATerm;
+use merc_aterm::ATermSend;
+use merc_aterm::Symbol;
+use merc_aterm::Term;
+use merc_data::DataExpression;
+use merc_data::DataVariable;
+use merc_lts::LabelledTransitionSystem;
+use merc_lts::LtsBuilderFast;
+use merc_lts::StateIndex;
+use merc_sabre::RewriteEngine;
+use merc_utilities::MercError;
+
+use crate::Constants;
+use crate::LinearProcess;
+use crate::Summand;
+use crate::initial_state;
+use crate::successors;
+
+/// Generates the state space of the given linear process using the given
+/// number of threads, see [crate::explore] for the semantics.
+///
+/// # Details
+///
+/// Terms are local to the thread that created them, so every worker creates
+/// its own rewriter using `new_rewriter` and its own copy of the process. A
+/// state is stored as a single term in the global term pool, which is
+/// maximally shared, such that the index of this term identifies the state in
+/// the shared visited set. Every worker owns a deque of states to explore, and
+/// when that is empty it steals states from the deques of the other workers.
+///
+/// The order in which states are discovered, and thus their numbering, depends
+/// on the scheduling of the workers. In deterministic mode the states are
+/// renumbered afterwards in breadth-first order, following the transitions in
+/// the order of the summands, which results in exactly the numbering of
+/// [crate::explore].
+pub fn explore_parallel<R, F>(
+    process: &LinearProcess,
+    num_of_threads: usize,
+    deterministic: bool,
+    new_rewriter: F,
+) -> Result<LabelledTransitionSystem<String>, MercError>
+where
+    R: RewriteEngine,
+    F: Fn() -> Result<R, MercError> + Sync,
+{
+    if num_of_threads == 0 {
+        return Err("At least one thread is required for exploration".into());
+    }
+
+    let shared_process = SharedProcess::new(process);
+    let state_symbol = Symbol::new("@state", process.parameters().len());
+
+    let states = StateSet::new();
+    let frontier = Frontier::new(num_of_threads);
+    let stop = AtomicBool::new(false);
+
+    {
+        let mut rewriter = new_rewriter()?;
+        let initial_term = state_term(&state_symbol, &initial_state(process, &mut rewriter));
+        let (initial_index, _) = states.insert(&initial_term);
+        frontier.push(0, (initial_index, ATermSend::from(initial_term)));
+    }
+
+    let results: Vec<Result<Vec<Successors>, MercError>> = thread::scope(|scope| {
+        let workers: Vec<_> = (0..num_of_threads)
+            .map(|worker| {
+                let shared_process = &shared_process;
+                let new_rewriter = &new_rewriter;
+                let states = &states;
+                let frontier = &frontier;
+                let stop = &stop;
+
+                scope.spawn(move || {
+                    let result = explore_worker(worker, shared_process, new_rewriter, states, frontier, stop);
+                    if result.is_err() {
+                        // Let the other workers finish early, since the state space is incomplete anyway.
+                        stop.store(true, Ordering::Relaxed);
+                    }
+                    result
+                })
+            })
+            .collect();
+
+        workers
+            .into_iter()
+            .map(|worker| worker.join().expect("Exploration worker panicked"))
+            .collect()
+    });
+
+    // Gather the outgoing transitions of every state, in the order of the summands.
+    let num_of_states = states.len();
+    let mut outgoing: Vec<Vec<(String, StateIndex)>> = vec![Vec::new(); num_of_states];
+    for result in results {
+        for (state_index, transitions) in result? {
+            outgoing[state_index.value()] = transitions;
+        }
+    }
+
+    let renumbering = if deterministic {
+        breadth_first_numbering(&outgoing)
+    } else {
+        (0..num_of_states).collect()
+    };
+
+    let mut order = vec![0; num_of_states];
+    for (state, &index) in renumbering.iter().enumerate() {
+        order[index] = state;
+    }
+
+    let mut builder = LtsBuilderFast::new(Vec::new(), Vec::new());
+    for state in order {
+        for (label, to) in &outgoing[state] {
+            builder.add_transition(
+                StateIndex::new(renumbering[state]),
+                label,
+                StateIndex::new(renumbering[to.value()]),
+            );
+        }
+    }
+
+    info!(
+        "Explored {num_of_states} states and {} transitions using {num_of_threads} threads",
+        builder.num_of_transitions()
+    );
+
+    builder.require_num_of_states(num_of_states);
+    Ok(builder.finish(StateIndex::new(0), true))
+}
+
+/// The outgoing transitions of a state, in the order of the summands.
+type Successors = (StateIndex, Vec<(String, StateIndex)>);
+
+/// Explores states from the frontier until all discovered states have been explored.
+fn explore_worker<R, F>(
+    worker: usize,
+    shared_process: &SharedProcess,
+    new_rewriter: &F,
+    states: &StateSet,
+    frontier: &Frontier,
+    stop: &AtomicBool,
+) -> Result<Vec<Successors>, MercError>
+where
+    R: RewriteEngine,
+    F: Fn() -> Result<R, MercError>,
+{
+    let mut rewriter = new_rewriter()?;
+    let process = shared_process.to_process();
+    let constants = Constants::new()?;
+    let state_symbol = Symbol::new("@state", process.parameters().len());
+
+    let mut result = Vec::new();
+    while let Some((state_index, state)) = frontier.pop(worker, stop) {
+        let state: Vec<DataExpression> = state.arguments().map(|value| value.protect().into()).collect();
+
+        let mut transitions = Vec::new();
+        for (label, next_state) in successors(&process, &mut rewriter, &constants, &state)? {
+            let next_term = state_term(&state_symbol, &next_state);
+            let (next_index, inserted) = states.insert(&next_term);
+            if inserted {
+                debug!("Worker {worker} discovered state {next_index}");
+                frontier.push(worker, (next_index, ATermSend::from(next_term)));
+            }
+
+            transitions.push((label, next_index));
+        }
+
+        result.push((state_index, transitions));
+        frontier.finish();
+    }
+
+    Ok(result)
+}
+
+/// Returns the term `@state(v_1, ..., v_n)` representing the given state.
+fn state_term(symbol: &Symbol, state: &[DataExpression]) -> ATerm {
+    ATerm::with_iter(symbol, state.iter().map(|value| value.copy()))
+}
+
+/// Returns the index of every state when the states are numbered in
+/// breadth-first order from state zero, following the given transitions in order.
+fn breadth_first_numbering(outgoing: &[Vec<(String, StateIndex)>]) -> Vec<usize> {
+    let mut renumbering = vec![usize::MAX; outgoing.len()];
+    let mut queue = VecDeque::new();
+
+    renumbering[0] = 0;
+    queue.push_back(0);
+    let mut num_of_states = 1;
+
+    while let Some(state) = queue.pop_front() {
+        for (_, to) in &outgoing[state] {
+            if renumbering[to.value()] == usize::MAX {
+                renumbering[to.value()] = num_of_states;
+                num_of_states += 1;
+                queue.push_back(to.value());
+            }
+        }
+    }
+
+    debug_assert_eq!(
+        num_of_states,
+        outgoing.len(),
+        "All states are reachable from the initial state"
+    );
+    renumbering
+}
+
+/// The set of discovered states, shared by all workers.
+///
+/// # Details
+///
+/// States are identified by the index of their term in the global term pool.
+/// This index is only stable while the term is protected, so the set keeps
+/// every state term protected until exploration has finished.
+struct StateSet {
+    states: DashMap<usize, (StateIndex, ATermSend)>,
+    num_of_states: AtomicUsize,
+}
+
+impl StateSet {
+    fn new() -> Self {
+        Self {
+            states: DashMap::new(),
+            num_of_states: AtomicUsize::new(0),
+        }
+    }
+
+    /// Inserts the given state term, and returns its index and whether it was newly inserted.
+    fn insert(&self, term: &ATerm) -> (StateIndex, bool) {
+        // Protect the term before acquiring the shard lock, since protecting
+        // can wait for garbage collection by a thread that requires this shard.
+        let protected = ATermSend::from(term.clone());
+
+        match self.states.entry(term.index()) {
+            Entry::Occupied(entry) => (entry.get().0, false),
+            Entry::Vacant(entry) => {
+                let index = StateIndex::new(self.num_of_states.fetch_add(1, Ordering::Relaxed));
+                entry.insert((index, protected));
+                (index, true)
+            }
+        }
+    }
+
+    /// Returns the number of discovered states.
+    fn len(&self) -> usize {
+        self.num_of_states.load(Ordering::Relaxed)
+    }
+}
+
+/// A work-stealing frontier with a deque for every worker.
+///
+/// # Details
+///
+/// Workers take states from the back of their own deque, and steal from the
+/// front of the deques of other workers when their own deque is empty.
+/// Exploration has finished when no state is pending, where a state is pending
+/// from the moment it is pushed until its successors have been pushed.
+struct Frontier {
+    queues: Vec<Mutex<VecDeque<(StateIndex, ATermSend)>>>,
+    pending: AtomicUsize,
+}
+
+impl Frontier {
+    fn new(num_of_workers: usize) -> Self {
+        Self {
+            queues: (0..num_of_workers).map(|_| Mutex::new(VecDeque::new())).collect(),
+            pending: AtomicUsize::new(0),
+        }
+    }
+
+    /// Adds a state to the deque of the given worker.
+    fn push(&self, worker: usize, state: (StateIndex, ATermSend)) {
+        self.pending.fetch_add(1, Ordering::SeqCst);
+        self.queues[worker].lock().expect("Lock poisoned!").push_back(state);
+    }
+
+    /// Returns the next state to explore for the given worker, or `None` when
+    /// exploration has finished or should stop.
+    fn pop(&self, worker: usize, stop: &AtomicBool) -> Option<(StateIndex, ATermSend)> {
+        loop {
+            if let Some(state) = self.queues[worker].lock().expect("Lock poisoned!").pop_back() {
+                return Some(state);
+            }
+
+            for offset in 1..self.queues.len() {
+                let victim = (worker + offset) % self.queues.len();
+                if let Some(state) = self.queues[victim].lock().expect("Lock poisoned!").pop_front() {
+                    return Some(state);
+                }
+            }
+
+            if self.pending.load(Ordering::SeqCst) == 0 || stop.load(Ordering::Relaxed) {
+                return None;
+            }
+
+            thread::yield_now();
+        }
+    }
+
+    /// Indicates that the successors of a popped state have been pushed.
+    fn finish(&self) {
+        self.pending.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// A linear process whose terms can be shared between threads.
+struct SharedProcess {
+    name: String,
+    parameters: Vec<ATermSend>,
+    initial_state: Vec<ATermSend>,
+    summands: Vec<(Option<ATermSend>, ATermSend, Vec<ATermSend>)>,
+}
+
+impl SharedProcess {
+    fn new(process: &LinearProcess) -> Self {
+        let share = |values: &[DataExpression]| -> Vec<ATermSend> {
+            values.iter().map(|value| ATermSend::from(value.protect())).collect()
+        };
+
+        Self {
+            name: process.name().to_string(),
+            parameters: process
+                .parameters()
+                .iter()
+                .map(|parameter| ATermSend::from(parameter.protect()))
+                .collect(),
+            initial_state: share(process.initial_state()),
+            summands: process
+                .summands()
+                .iter()
+                .map(|summand| {
+                    (
+                        summand
+                            .condition
+                            .as_ref()
+                            .map(|condition| ATermSend::from(condition.protect())),
+                        ATermSend::from(summand.action.protect()),
+                        share(&summand.next_state),
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    /// Returns a copy of the process whose terms are protected by the current thread.
+    fn to_process(&self) -> LinearProcess {
+        let local = |values: &[ATermSend]| -> Vec<DataExpression> {
+            values.iter().map(|value| value.protect().into()).collect()
+        };
+
+        LinearProcess::new(
+            self.name.clone(),
+            self.parameters
+                .iter()
+                .map(|parameter| DataVariable::from(parameter.protect()))
+                .collect(),
+            local(&self.initial_state),
+            self.summands
+                .iter()
+                .map(|(condition, action, next_state)| Summand {
+                    condition: condition.as_ref().map(|condition| condition.protect().into()),
+                    action: action.protect().into(),
+                    next_state: local(next_state),
+                })
+                .collect(),
+        )
+        .expect("The process was valid when it was shared")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use merc_lts::LTS;
+    use merc_sabre::SabreRewriter;
+
+    use crate::explore;
+    use crate::test_utility::counter_rewriter;
+
+    use super::*;
+
+    const PROCESS: &str = "proc P(x, y)
+        init P(zero, zero)
+        sum lt(x, succ(succ(succ(zero)))) -> incx(y) . P(succ(x), y)
+        sum lt(y, succ(succ(succ(zero)))) -> incy(x) . P(x, succ(y))
+        sum tau . P(y, x)";
+
+    /// Returns the transitions of the given state space, with the labels as strings.
+    fn transitions(lts: &LabelledTransitionSystem<String>) -> Vec<(StateIndex, String, StateIndex)> {
+        let mut result: Vec<_> = lts
+            .iter_states()
+            .flat_map(|state| {
+                lts.outgoing_transitions(state)
+                    .map(move |transition| (state, lts.labels()[transition.label.value()].clone(), transition.to))
+            })
+            .collect();
+        result.sort();
+        result
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_explore_parallel_deterministic() {
+        let process = LinearProcess::from_string(PROCESS).unwrap();
+        let expected = explore(&process, &mut counter_rewriter()).unwrap();
+
+        for num_of_threads in [1, 2, 4] {
+            let lts = explore_parallel(&process, num_of_threads, true, || Ok(counter_rewriter())).unwrap();
+
+            assert_eq!(lts.num_of_states(), expected.num_of_states());
+            assert_eq!(lts.num_of_transitions(), expected.num_of_transitions());
+            assert_eq!(transitions(&lts), transitions(&expected));
+        }
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_explore_parallel() {
+        let process = LinearProcess::from_string(PROCESS).unwrap();
+        let expected = explore(&process, &mut counter_rewriter()).unwrap();
+
+        let lts = explore_parallel(&process, 4, false, || Ok(counter_rewriter())).unwrap();
+
+        assert_eq!(lts.num_of_states(), expected.num_of_states());
+        assert_eq!(lts.num_of_transitions(), expected.num_of_transitions());
+        assert_eq!(lts.initial_state_index(), StateIndex::new(0));
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_explore_parallel_error() {
+        let process = LinearProcess::from_string(
+            "proc P(x)
+            init P(zero)
+            sum x -> a . P(x)",
+        )
+        .unwrap();
+
+        assert!(explore_parallel(&process, 2, false, || Ok(SabreRewriter::new(&Default::default()))).is_err());
+    }
+}
@@ -0,0 +1,449 @@
+#![forbid(unsafe_code)]
+
+use std::collections::VecDeque;
+
+use ahash::AHashMap;
+use itertools::Itertools;
+use log::debug;
+use log::info;
+
+use merc_aterm::Term;
+use merc_collections::IndexedSet;
+use merc_data::DataExpression;
+use merc_lts::LabelledTransitionSystem;
+use merc_lts::LtsBuilderFast;
+use merc_lts::StateIndex;
+use merc_sabre::RewriteEngine;
+use merc_utilities::MercError;
+
+use crate::Constants;
+use crate::LinearProcess;
+use crate::enabled_summands;
+use crate::initial_state;
+
+/// Describes which summands of a linear process can influence each other,
+/// which is used by [explore_reduced] to select the summands that are expanded.
+pub trait Independence {
+    /// Returns the summands that are dependent on the given summand. Two
+    /// summands are independent when, in every state where both are enabled,
+    /// executing one does not disable the other, does not change the label of
+    /// the other, and executing them in either order leads to the same state.
+    fn dependent(&self, summand: usize) -> &[usize];
+
+    /// Returns the summands that may enable the given summand in a state where it is disabled.
+    fn enablers(&self, summand: usize) -> &[usize];
+}
+
+/// The dependencies between the summands of a linear process that are derived
+/// from the process parameters that the summands read and write.
+///
+/// # Details
+///
+/// A summand writes a parameter when its next state assigns a value other
+/// than the parameter itself, and it reads the parameters that occur in its
+/// condition, action or next state. Two summands are dependent when one writes
+/// a parameter that the other reads or writes, and a summand may enable another
+/// summand when it writes a parameter that occurs in the condition of the other.
+pub struct SummandDependencies {
+    dependent: Vec<Vec<usize>>,
+    enablers: Vec<Vec<usize>>,
+}
+
+impl SummandDependencies {
+    /// Computes the dependencies between the summands of the given process.
+    pub fn new(process: &LinearProcess) -> Self {
+        let parameters: AHashMap<usize, usize> = process
+            .parameters()
+            .iter()
+            .enumerate()
+            .map(|(position, parameter)| (parameter.index(), position))
+            .collect();
+
+        // Returns the positions of the parameters that occur in the given expression.
+        let occurring = |expression: &DataExpression| -> Vec<usize> {
+            expression
+                .iter()
+                .filter_map(|term| parameters.get(&term.index()).copied())
+                .collect()
+        };
+
+        let mut reads = Vec::new();
+        let mut condition_reads = Vec::new();
+        let mut writes = Vec::new();
+        for summand in process.summands() {
+            let condition = summand.condition.as_ref().map(occurring).unwrap_or_default();
+
+            let mut write = Vec::new();
+            let mut read = condition.clone();
+            read.extend(occurring(&summand.action));
+            for (position, (value, parameter)) in summand.next_state.iter().zip(process.parameters()).enumerate() {
+                if value.index() != parameter.index() {
+                    write.push(position);
+                    read.extend(occurring(value));
+                }
+            }
+
+            read.sort_unstable();
+            read.dedup();
+            reads.push(read);
+            condition_reads.push(condition);
+            writes.push(write);
+        }
+
+        let intersects = |left: &[usize], right: &[usize]| left.iter().any(|position| right.contains(position));
+
+        let num_of_summands = process.summands().len();
+        let dependent = (0..num_of_summands)
+            .map(|i| {
+                (0..num_of_summands)
+                    .filter(|&j| {
+                        i != j
+                            && (intersects(&writes[i], &reads[j])
+                                || intersects(&writes[i], &writes[j])
+                                || intersects(&writes[j], &reads[i]))
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let enablers = (0..num_of_summands)
+            .map(|i| {
+                (0..num_of_summands)
+                    .filter(|&j| intersects(&writes[j], &condition_reads[i]))
+                    .collect()
+            })
+            .collect();
+
+        Self { dependent, enablers }
+    }
+}
+
+impl Independence for SummandDependencies {
+    fn dependent(&self, summand: usize) -> &[usize] {
+        &self.dependent[summand]
+    }
+
+    fn enablers(&self, summand: usize) -> &[usize] {
+        &self.enablers[summand]
+    }
+}
+
+/// The behaviour that is preserved by [explore_reduced].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+pub enum PartialOrderReduction {
+    /// Only preserves the reachable deadlocks.
+    Deadlocks,
+    /// Preserves the reachable deadlocks and the traces of visible actions,
+    /// by only reducing states where the expanded summands are internal and
+    /// do not close a cycle.
+    Traces,
+}
+
+/// Generates a reduced state space of the given linear process in
+/// breadth-first order, where in every state only a subset of the enabled
+/// summands is expanded, see [crate::explore].
+///
+/// # Details
+///
+/// The expanded summands form a stubborn set: starting from an enabled
+/// summand, the dependent summands of enabled summands and the enablers of
+/// disabled summands are added until the set is closed. The smallest such set
+/// over all enabled summands is chosen. For [PartialOrderReduction::Traces] the
+/// state is fully expanded when no set consists of internal summands only, or
+/// when one of the selected successors was already discovered, which ensures
+/// that no summand is postponed forever along a cycle.
+///
+/// When debug assertions are enabled, it is checked that every expanded
+/// summand commutes with the enabled summands that are not expanded, which
+/// reports an independence relation that is not sound for the process.
+pub fn explore_reduced<R: RewriteEngine, I: Independence>(
+    process: &LinearProcess,
+    rewriter: &mut R,
+    independence: &I,
+    reduction: PartialOrderReduction,
+) -> Result<LabelledTransitionSystem<String>, MercError> {
+    let constants = Constants::new()?;
+    let internal: Vec<bool> = process
+        .summands()
+        .iter()
+        .map(|summand| summand.action == constants.tau_term)
+        .collect();
+
+    let mut states: IndexedSet<Vec<DataExpression>> = IndexedSet::new();
+    let mut queue = VecDeque::new();
+    let mut builder = LtsBuilderFast::new(Vec::new(), Vec::new());
+    let mut num_of_reduced_states = 0;
+
+    let initial_state = initial_state(process, rewriter);
+    let (initial_index, _) = states.insert(initial_state.clone());
+    queue.push_back((initial_index, initial_state));
+
+    while let Some((state_index, state)) = queue.pop_front() {
+        let transitions = enabled_summands(process, rewriter, &constants, &state)?;
+
+        let mut enabled = vec![false; process.summands().len()];
+        for (summand, _, _) in &transitions {
+            enabled[*summand] = true;
+        }
+
+        let selected = stubborn_set(
+            independence,
+            &enabled,
+            (reduction == PartialOrderReduction::Traces).then_some(&internal[..]),
+        );
+
+        let (mut expanded, mut postponed): (Vec<_>, Vec<_>) = transitions
+            .into_iter()
+            .partition(|(summand, _, _)| selected.as_ref().is_none_or(|selected| selected[*summand]));
+
+        if reduction == PartialOrderReduction::Traces
+            && !postponed.is_empty()
+            && expanded.iter().any(|(_, _, next_state)| states.contains(next_state))
+        {
+            // Cycle proviso: a postponed summand could otherwise be ignored along a cycle.
+            expanded.append(&mut postponed);
+        }
+
+        if !postponed.is_empty() {
+            num_of_reduced_states += 1;
+            if cfg!(debug_assertions) {
+                check_commutation(process, rewriter, &constants, &expanded, &postponed)?;
+            }
+        }
+
+        for (_, label, next_state) in expanded {
+            let (next_index, inserted) = states.insert(next_state.clone());
+            if inserted {
+                debug!("Discovered state {}", states.len() - 1);
+                queue.push_back((next_index, next_state));
+            }
+
+            builder.add_transition(StateIndex::new(*state_index), &label, StateIndex::new(*next_index));
+        }
+    }
+
+    info!(
+        "Explored {} states and {} transitions, of which {num_of_reduced_states} states are not fully expanded",
+        states.len(),
+        builder.num_of_transitions()
+    );
+
+    builder.require_num_of_states(states.len());
+    Ok(builder.finish(StateIndex::new(*initial_index), true))
+}
+
+/// Returns the smallest stubborn set (marking the summands in the set) over
+/// all enabled summands, or `None` when no proper subset of the enabled
+/// summands is found. When `internal` is given, only sets in which all enabled
+/// summands are internal are considered.
+fn stubborn_set(independence: &impl Independence, enabled: &[bool], internal: Option<&[bool]>) -> Option<Vec<bool>> {
+    let num_of_enabled = enabled.iter().filter(|enabled| **enabled).count();
+
+    let mut best: Option<(usize, Vec<bool>)> = None;
+    for seed in (0..enabled.len()).filter(|&summand| enabled[summand]) {
+        if internal.is_some_and(|internal| !internal[seed]) {
+            continue;
+        }
+
+        let mut in_set = vec![false; enabled.len()];
+        let mut stack = vec![seed];
+        in_set[seed] = true;
+
+        while let Some(summand) = stack.pop() {
+            let related = if enabled[summand] {
+                independence.dependent(summand)
+            } else {
+                independence.enablers(summand)
+            };
+
+            for &other in related {
+                if !in_set[other] {
+                    in_set[other] = true;
+                    stack.push(other);
+                }
+            }
+        }
+
+        let expanded: Vec<usize> = (0..enabled.len())
+            .filter(|&summand| in_set[summand] && enabled[summand])
+            .collect();
+        if internal.is_some_and(|internal| expanded.iter().any(|&summand| !internal[summand])) {
+            continue;
+        }
+
+        if best.as_ref().is_none_or(|(size, _)| expanded.len() < *size) {
+            let done = expanded.len() == 1;
+            best = Some((expanded.len(), in_set));
+            if done {
+                break;
+            }
+        }
+    }
+
+    best.filter(|(size, _)| *size < num_of_enabled)
+        .map(|(_, in_set)| in_set)
+}
+
+/// Checks that every expanded summand commutes with every postponed summand,
+/// i.e., executing them in either order yields the same labels and state.
+fn check_commutation<R: RewriteEngine>(
+    process: &LinearProcess,
+    rewriter: &mut R,
+    constants: &Constants,
+    expanded: &[(usize, String, Vec<DataExpression>)],
+    postponed: &[(usize, String, Vec<DataExpression>)],
+) -> Result<(), MercError> {
+    for (summand, label, next_state) in expanded {
+        let after_expanded = enabled_summands(process, rewriter, constants, next_state)?;
+
+        for (other, other_label, other_next_state) in postponed {
+            let after_postponed = enabled_summands(process, rewriter, constants, other_next_state)?;
+
+            let left = after_expanded.iter().find(|(index, _, _)| index == other);
+            let right = after_postponed.iter().find(|(index, _, _)| index == summand);
+            let commutes = match (left, right) {
+                (Some((_, left_label, left_state)), Some((_, right_label, right_state))) => {
+                    left_label == other_label && right_label == label && left_state == right_state
+                }
+                _ => false,
+            };
+
+            if !commutes {
+                return Err(format!(
+                    "Summands {summand} and {other} are assumed to be independent, but do not commute in state {}",
+                    next_state.iter().format(", ")
+                )
+                .into());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use merc_lts::LTS;
+
+    use crate::explore;
+    use crate::test_utility::counter_rewriter;
+
+    use super::*;
+
+    /// Two counters from zero to three that are incremented independently.
+    fn counters(first_action: &str) -> LinearProcess {
+        LinearProcess::from_string(&format!(
+            "proc P(x, y)
+            init P(zero, zero)
+            sum lt(x, succ(succ(succ(zero)))) -> {first_action} . P(succ(x), y)
+            sum lt(y, succ(succ(succ(zero)))) -> b . P(x, succ(y))"
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_summand_dependencies() {
+        let process = LinearProcess::from_string(
+            "proc P(x, y)
+            init P(zero, zero)
+            sum lt(x, y) -> a . P(succ(x), y)
+            sum b . P(x, succ(y))
+            sum c(x) . P(x, y)",
+        )
+        .unwrap();
+
+        let dependencies = SummandDependencies::new(&process);
+        assert_eq!(dependencies.dependent(0), &[1, 2]);
+        assert_eq!(dependencies.dependent(1), &[0]);
+        assert_eq!(dependencies.dependent(2), &[0]);
+        assert_eq!(dependencies.enablers(0), &[0, 1]);
+        assert!(dependencies.enablers(1).is_empty());
+    }
+
+    #[test]
+    fn test_explore_reduced_deadlocks() {
+        let process = counters("a");
+        let independence = SummandDependencies::new(&process);
+
+        let full = explore(&process, &mut counter_rewriter()).unwrap();
+        let reduced = explore_reduced(
+            &process,
+            &mut counter_rewriter(),
+            &independence,
+            PartialOrderReduction::Deadlocks,
+        )
+        .unwrap();
+
+        // Only the interleaving in which x is incremented first is explored.
+        assert_eq!(full.num_of_states(), 16);
+        assert_eq!(reduced.num_of_states(), 7);
+
+        let deadlocks = |lts: &LabelledTransitionSystem<String>| {
+            lts.iter_states()
+                .filter(|state| lts.outgoing_transitions(*state).next().is_none())
+                .count()
+        };
+        assert_eq!(deadlocks(&reduced), deadlocks(&full));
+    }
+
+    #[test]
+    fn test_explore_reduced_traces() {
+        let independence = SummandDependencies::new(&counters("a"));
+
+        // Both summands are visible, so no state can be reduced.
+        let reduced = explore_reduced(
+            &counters("a"),
+            &mut counter_rewriter(),
+            &independence,
+            PartialOrderReduction::Traces,
+        )
+        .unwrap();
+        assert_eq!(reduced.num_of_states(), 16);
+
+        // The internal summand is executed first.
+        let reduced = explore_reduced(
+            &counters("tau"),
+            &mut counter_rewriter(),
+            &independence,
+            PartialOrderReduction::Traces,
+        )
+        .unwrap();
+        assert_eq!(reduced.num_of_states(), 7);
+    }
+
+    /// An independence relation in which all summands are independent.
+    struct Unsound;
+
+    impl Independence for Unsound {
+        fn dependent(&self, _summand: usize) -> &[usize] {
+            &[]
+        }
+
+        fn enablers(&self, _summand: usize) -> &[usize] {
+            &[]
+        }
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    fn test_explore_reduced_unsound() {
+        let process = LinearProcess::from_string(
+            "proc P(x)
+            init P(zero)
+            sum lt(x, succ(zero)) -> a . P(succ(x))
+            sum b . P(zero)",
+        )
+        .unwrap();
+
+        assert!(
+            explore_reduced(
+                &process,
+                &mut counter_rewriter(),
+                &Unsound,
+                PartialOrderReduction::Deadlocks
+            )
+            .is_err()
+        );
+    }
+}
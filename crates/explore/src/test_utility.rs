@@ -0,0 +1,9 @@
+use merc_rec_tests::load_rec_from_strings;
+use merc_sabre::SabreRewriter;
+
+/// Returns a rewriter for the natural numbers with a strict order of `examples/explore/counter.rec`.
+pub fn counter_rewriter() -> SabreRewriter {
+    let (spec, _) = load_rec_from_strings(&[include_str!("../../../examples/explore/counter.rec")])
+        .expect("The counter specification should be valid");
+    SabreRewriter::new(&spec.to_rewrite_spec())
+}
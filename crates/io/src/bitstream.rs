@@ -11,8 +11,12 @@ use log::error;
 
 use merc_number::read_u64_variablelength;
 use merc_number::write_u64_variablelength;
+use merc_number::zigzag_decode;
+use merc_number::zigzag_encode;
 use merc_utilities::MercError;
 
+use crate::capacity_hint;
+
 /// Trait for writing bit-level data.
 pub trait BitStreamWrite {
     /// Writes the least significant bits from a u64 value.
@@ -27,6 +31,12 @@ pub trait BitStreamWrite {
     /// Writes a u64 value using variable-width encoding.
     fn write_integer(&mut self, value: u64) -> Result<(), MercError>;
 
+    /// Writes an i64 value using zigzag variable-width encoding, such that values
+    /// with a small magnitude, for example negative deltas, have a short encoding.
+    fn write_signed_integer(&mut self, value: i64) -> Result<(), MercError> {
+        self.write_integer(zigzag_encode(value))
+    }
+
     /// Flushes any remaining bits to the underlying writer.
     fn flush(&mut self) -> Result<(), MercError>;
 }
@@ -44,6 +54,11 @@ pub trait BitStreamRead {
 
     /// Reads a variable-width encoded integer.
     fn read_integer(&mut self) -> Result<u64, MercError>;
+
+    /// Reads a zigzag variable-width encoded integer written by [`BitStreamWrite::write_signed_integer`].
+    fn read_signed_integer(&mut self) -> Result<i64, MercError> {
+        Ok(zigzag_decode(self.read_integer()?))
+    }
 }
 
 /// Writer for bit-level output operations using an underlying writer.
@@ -118,8 +133,11 @@ impl<R: Read> BitStreamRead for BitStreamReader<R> {
     fn read_string(&mut self) -> Result<String, MercError> {
         let length = self.read_integer()?;
         self.text_buffer.clear();
-        self.text_buffer
-            .reserve(length.try_into().expect("String size exceeds usize!"));
+        self.text_buffer.reserve(capacity_hint(
+            length
+                .try_into()
+                .map_err(|_| MercError::format(format!("String length {length} exceeds usize")))?,
+        ));
 
         for _ in 0..length {
             let byte = self.reader.read::<8, u8>()?;
@@ -148,6 +166,7 @@ mod tests {
     enum Instruction {
         String(String),
         Integer(u64),
+        SignedInteger(i64),
         /// (value, num_of_bits), where num_of_bits must be at most 64.
         Bits(u64, u8),
     }
@@ -166,7 +185,7 @@ mod tests {
     fn test_arbitrary_bitstream() {
         random_test(100, |rng| {
             let instructions: Vec<Instruction> = (0..100)
-                .map(|_| match rng.random_range(0..2) {
+                .map(|_| match rng.random_range(0..4) {
                     0 => {
                         let string = rng.sample_iter(&Alphanumeric).take(7).map(char::from).collect();
                         Instruction::String(string)
@@ -176,7 +195,8 @@ mod tests {
                         let value: u64 = rng.random();
                         Instruction::Bits(value, required_bits(value))
                     }
-                    _ => unreachable!("The range is from 0 to 2"),
+                    3 => Instruction::SignedInteger(rng.random()),
+                    _ => unreachable!("The range is from 0 to 3"),
                 })
                 .collect();
 
@@ -193,6 +213,9 @@ mod tests {
                         Instruction::Integer(value) => {
                             writer.write_integer(*value).expect("Failed to write into stream")
                         }
+                        Instruction::SignedInteger(value) => writer
+                            .write_signed_integer(*value)
+                            .expect("Failed to write into stream"),
                         Instruction::Bits(value, number_of_bits) => writer
                             .write_bits(*value, *number_of_bits)
                             .expect("Failed to write into stream"),
@@ -221,6 +244,13 @@ mod tests {
                             "Failed to read back the integer"
                         )
                     }
+                    Instruction::SignedInteger(value) => {
+                        debug_assert_eq!(
+                            reader.read_signed_integer().expect("Failed to read from stream"),
+                            *value,
+                            "Failed to read back the signed integer"
+                        )
+                    }
                     Instruction::Bits(value, number_of_bits) => {
                         debug_assert_eq!(
                             reader.read_bits(*number_of_bits).expect("Failed to read from stream"),
@@ -0,0 +1,11 @@
+/// The largest number of elements that is reserved up front for a size read
+/// from an input file.
+pub const MAX_PREALLOCATION: usize = 1 << 20;
+
+/// Returns the capacity to reserve for the given number of elements, where the
+/// number is taken from the header of an (untrusted) input. A malformed header
+/// can state an arbitrary size, so larger collections grow while reading
+/// instead of being allocated at once.
+pub fn capacity_hint(num_of_elements: usize) -> usize {
+    num_of_elements.min(MAX_PREALLOCATION)
+}
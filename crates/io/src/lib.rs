@@ -2,13 +2,17 @@
 #![forbid(unsafe_code)]
 
 mod bitstream;
+mod capacity;
 mod dumpfiles;
 mod format;
 mod line_iterator;
 mod progress;
+mod render_svg;
 
 pub use bitstream::*;
+pub use capacity::*;
 pub use dumpfiles::*;
 pub use format::*;
 pub use line_iterator::*;
 pub use progress::*;
+pub use render_svg::*;
@@ -1,13 +1,44 @@
+use std::io;
 use std::io::BufRead;
 use std::io::BufReader;
 use std::io::Read;
+use std::mem::take;
+
+use merc_utilities::MercError;
+use merc_utilities::Span;
 use streaming_iterator::StreamingIterator;
 
+/// The maximum number of bytes of a single line that the readers of textual formats accept.
+pub const MAX_LINE_LENGTH: usize = 1 << 24;
+
 /// A lending iterator over the lines of a type implementing Read.
+///
+/// # Details
+///
+/// Lines are terminated by `\n` or `\r\n`, and the terminator is not part of
+/// the line. The input is read in chunks into a single buffer that is reused
+/// for every line. When a maximum line length is set with
+/// [LineIterator::with_max_line_length], the remainder of a longer line is
+/// skipped without being stored, see [LineIterator::is_truncated].
+///
+/// The iterator keeps track of the line number and byte offset of the current
+/// line, such that readers can report accurate error locations. A read error
+/// or invalid UTF-8 ends the iteration, after which the error can be obtained
+/// with [LineIterator::take_error].
 pub struct LineIterator<T: Read> {
     reader: BufReader<T>,
     buffer: String,
     end: bool,
+    /// The maximum number of bytes that is stored for a single line.
+    max_line_length: Option<usize>,
+    /// True iff the current line was longer than the maximum line length.
+    truncated: bool,
+    /// The line number of the current line, starting at one.
+    line_number: usize,
+    /// The byte offset of the start of the current line and the next line.
+    offset: usize,
+    next_offset: usize,
+    error: Option<io::Error>,
 }
 
 impl<T: Read> LineIterator<T> {
@@ -16,6 +47,99 @@ impl<T: Read> LineIterator<T> {
             reader: BufReader::new(reader),
             buffer: String::new(),
             end: false,
+            max_line_length: None,
+            truncated: false,
+            line_number: 0,
+            offset: 0,
+            next_offset: 0,
+            error: None,
+        }
+    }
+
+    /// Only stores the first `max_line_length` bytes of every line, rounded
+    /// down to a character boundary, and skips the remainder.
+    pub fn with_max_line_length(mut self, max_line_length: usize) -> Self {
+        self.max_line_length = Some(max_line_length);
+        self
+    }
+
+    /// Returns the line number of the current line, starting at one.
+    pub fn line_number(&self) -> usize {
+        self.line_number
+    }
+
+    /// Returns the byte offset of the start of the current line in the input.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Returns the span of the current line, for error reporting.
+    pub fn span(&self) -> Span {
+        Span::line(self.line_number)
+    }
+
+    /// Returns true iff the current line exceeds the maximum line length, in
+    /// which case only its prefix is returned.
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+
+    /// Returns a parse error for the current line iff it exceeds the maximum line length.
+    pub fn check_length(&self) -> Result<(), MercError> {
+        if self.truncated {
+            return Err(MercError::parse(
+                format!(
+                    "The line exceeds the maximum length of {} bytes",
+                    self.max_line_length.unwrap_or_default()
+                ),
+                Some(self.span()),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Returns the error that ended the iteration, if any.
+    pub fn take_error(&mut self) -> Option<io::Error> {
+        self.error.take()
+    }
+
+    /// Reads the next line into the given bytes, and returns false iff the end of the input was reached before it.
+    fn read_line(&mut self, bytes: &mut Vec<u8>) -> io::Result<bool> {
+        let mut read_any = false;
+
+        loop {
+            let available = match self.reader.fill_buf() {
+                Ok(available) => available,
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+                Err(error) => return Err(error),
+            };
+
+            if available.is_empty() {
+                return Ok(read_any);
+            }
+            read_any = true;
+
+            let (length, consumed, found_newline) = match available.iter().position(|byte| *byte == b'\n') {
+                Some(position) => (position, position + 1, true),
+                None => (available.len(), available.len(), false),
+            };
+
+            // One additional byte is stored to determine whether the line without a carriage return fits.
+            let remaining = self.max_line_length.map_or(length, |max_line_length| {
+                max_line_length.saturating_add(1).saturating_sub(bytes.len())
+            });
+            if remaining < length {
+                self.truncated = true;
+            }
+            bytes.extend_from_slice(&available[..length.min(remaining)]);
+
+            self.reader.consume(consumed);
+            self.next_offset += consumed;
+
+            if found_newline {
+                return Ok(true);
+            }
         }
     }
 }
@@ -24,18 +148,58 @@ impl<T: Read> StreamingIterator for LineIterator<T> {
     type Item = String;
 
     fn advance(&mut self) {
-        self.buffer.clear();
-        match self.reader.read_line(&mut self.buffer) {
-            Ok(n) if n > 0 => {
-                if self.buffer.ends_with('\n') {
-                    self.buffer.pop();
-                    if self.buffer.ends_with('\r') {
-                        self.buffer.pop();
-                    }
-                }
+        if self.end {
+            return;
+        }
+
+        // Reuse the allocation of the previous line.
+        let mut bytes = take(&mut self.buffer).into_bytes();
+        bytes.clear();
+        self.truncated = false;
+        self.offset = self.next_offset;
+
+        match self.read_line(&mut bytes) {
+            Ok(true) => {}
+            Ok(false) => {
+                self.end = true;
+                return;
+            }
+            Err(error) => {
+                self.error = Some(error);
+                self.end = true;
+                return;
+            }
+        }
+
+        self.line_number += 1;
+        if bytes.ends_with(b"\r") && !self.truncated {
+            bytes.pop();
+        }
+
+        if let Some(max_line_length) = self
+            .max_line_length
+            .filter(|max_line_length| bytes.len() > *max_line_length)
+        {
+            bytes.truncate(max_line_length);
+            self.truncated = true;
+        }
+
+        match String::from_utf8(bytes) {
+            Ok(line) => self.buffer = line,
+            Err(error) if self.truncated && error.utf8_error().error_len().is_none() => {
+                // The line has been truncated in the middle of a character.
+                let valid = error.utf8_error().valid_up_to();
+                let mut bytes = error.into_bytes();
+                bytes.truncate(valid);
+                self.buffer = String::from_utf8(bytes).expect("The prefix is valid UTF-8");
+            }
+            Err(error) => {
+                self.error = Some(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("Line {} is not valid UTF-8: {error}", self.line_number),
+                ));
+                self.end = true;
             }
-            Ok(_) => self.end = true,
-            Err(_) => self.end = true,
         }
     }
 
@@ -105,4 +269,73 @@ mod tests {
 
         assert_eq!(lines, vec!["line1", "line2", "line3"]);
     }
+
+    #[test]
+    fn test_line_iterator_positions() {
+        let data = "first\r\n\nthird line\n";
+        let mut line_iterator = LineIterator::new(Cursor::new(data));
+
+        let mut positions = Vec::new();
+        while let Some(line) = line_iterator.next() {
+            positions.push((line.clone(), line_iterator.line_number(), line_iterator.offset()));
+        }
+
+        assert_eq!(
+            positions,
+            vec![
+                (String::from("first"), 1, 0),
+                (String::new(), 2, 7),
+                (String::from("third line"), 3, 8)
+            ]
+        );
+        assert!(line_iterator.take_error().is_none());
+    }
+
+    /// A reader that returns at most three bytes at a time, such that lines are read in many chunks.
+    struct Chunked<'a>(&'a [u8]);
+
+    impl Read for Chunked<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let length = buf.len().min(self.0.len()).min(3);
+            buf[..length].copy_from_slice(&self.0[..length]);
+            self.0 = &self.0[length..];
+            Ok(length)
+        }
+    }
+
+    #[test]
+    fn test_line_iterator_long_lines() {
+        let long_line = "x".repeat(100_000);
+        let data = format!("short\r\n{long_line}\r\nabcd\r\néé\n");
+
+        let mut line_iterator = LineIterator::new(Chunked(data.as_bytes())).with_max_line_length(4);
+
+        let mut lines = Vec::new();
+        while let Some(line) = line_iterator.next() {
+            lines.push((line.clone(), line_iterator.is_truncated()));
+        }
+
+        assert_eq!(
+            lines,
+            vec![
+                (String::from("shor"), true),
+                (String::from("xxxx"), true),
+                (String::from("abcd"), false),
+                (String::from("éé"), false)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_line_iterator_invalid_utf8() {
+        let data: &[u8] = b"valid\n\xff\n";
+        let mut line_iterator = LineIterator::new(Cursor::new(data));
+
+        assert_eq!(line_iterator.next().map(String::as_str), Some("valid"));
+        assert!(line_iterator.next().is_none());
+        assert_eq!(
+            line_iterator.take_error().map(|error| error.kind()),
+            Some(io::ErrorKind::InvalidData)
+        );
+    }
 }
@@ -3,11 +3,23 @@
 //! take a fixed number of steps. In particular, avoids writing too many
 //! progress indications.
 //!
+//! The readers and reduction algorithms report their progress through the
+//! [`Progress`] type to a globally installed [`ProgressSink`], such that tools
+//! can decide how progress is shown.
+//!
 
+use std::cell::Cell;
 use std::cell::RefCell;
 use std::marker::PhantomData;
+use std::sync::OnceLock;
 use std::time::Duration;
-use std::time::Instant;
+
+use log::info;
+
+use merc_utilities::Instant;
+use merc_utilities::MercError;
+
+use crate::LargeFormatter;
 
 /// A time-based progress tracker that prints messages at regular intervals.
 pub struct TimeProgress<T> {
@@ -42,3 +54,183 @@ impl<T> TimeProgress<T> {
         }
     }
 }
+
+/// A single progress update of a long running procedure.
+#[derive(Clone, Copy, Debug)]
+pub struct ProgressUpdate<'a> {
+    /// A short description of the procedure, for example "Reading LTS".
+    pub task: &'a str,
+    /// The unit of the amount, for example "transitions".
+    pub unit: &'a str,
+    /// The amount of work that has been done so far.
+    pub current: usize,
+    /// The total amount of work, when it is known in advance.
+    pub total: Option<usize>,
+}
+
+/// A sink that receives the progress updates of all long running procedures,
+/// such as the readers and the reduction algorithms. Tools can install their
+/// own sink with [`set_progress_sink`], by default [`LogProgressSink`] is used.
+pub trait ProgressSink: Send + Sync {
+    /// Called periodically with the current progress of a procedure.
+    fn update(&self, update: &ProgressUpdate<'_>);
+
+    /// Called once when the procedure has finished.
+    fn finish(&self, update: &ProgressUpdate<'_>) {
+        let _ = update;
+    }
+}
+
+/// Prints the progress updates as log messages with the `info` level.
+pub struct LogProgressSink;
+
+impl ProgressSink for LogProgressSink {
+    fn update(&self, update: &ProgressUpdate<'_>) {
+        match update.total {
+            Some(total) if total > 0 => info!(
+                "{}: {} {} ({}%)...",
+                update.task,
+                LargeFormatter(update.current),
+                update.unit,
+                update.current * 100 / total
+            ),
+            _ => info!("{}: {} {}...", update.task, LargeFormatter(update.current), update.unit),
+        }
+    }
+}
+
+/// Ignores all progress updates.
+pub struct SilentProgressSink;
+
+impl ProgressSink for SilentProgressSink {
+    fn update(&self, _update: &ProgressUpdate<'_>) {}
+}
+
+/// The globally installed progress sink.
+static PROGRESS_SINK: OnceLock<Box<dyn ProgressSink>> = OnceLock::new();
+
+/// Installs the progress sink that receives all progress updates, can only be
+/// called once and must be called before any progress has been reported.
+pub fn set_progress_sink(sink: Box<dyn ProgressSink>) -> Result<(), MercError> {
+    PROGRESS_SINK
+        .set(sink)
+        .map_err(|_| "The progress sink has already been installed".into())
+}
+
+/// Returns the installed progress sink.
+pub fn progress_sink() -> &'static dyn ProgressSink {
+    PROGRESS_SINK.get_or_init(|| Box::new(LogProgressSink)).as_ref()
+}
+
+/// Reports the progress of a procedure to the installed [`ProgressSink`], at
+/// most once per interval to avoid flooding the sink.
+pub struct Progress {
+    task: &'static str,
+    unit: &'static str,
+    total: Option<usize>,
+    interval: Duration,
+    last_update: Cell<Instant>,
+    /// The sink that receives the updates, or `None` for the installed [`ProgressSink`].
+    sink: Option<&'static dyn ProgressSink>,
+}
+
+impl Progress {
+    /// Creates a new progress report for the given task, which is updated at most once per second.
+    pub fn new(task: &'static str, unit: &'static str, total: Option<usize>) -> Self {
+        Self {
+            task,
+            unit,
+            total,
+            interval: Duration::from_secs(1),
+            last_update: Cell::new(Instant::now()),
+            sink: None,
+        }
+    }
+
+    /// Changes the minimum interval between two updates.
+    pub fn with_interval(mut self, interval_seconds: u64) -> Self {
+        self.interval = Duration::from_secs(interval_seconds);
+        self
+    }
+
+    /// Reports the progress to the given sink instead of the installed one.
+    pub fn with_sink(mut self, sink: &'static dyn ProgressSink) -> Self {
+        self.sink = Some(sink);
+        self
+    }
+
+    /// Reports the current amount of work done, but only when the interval has passed since the last update.
+    pub fn print(&self, current: usize) {
+        let now = Instant::now();
+        if now.duration_since(self.last_update.get()) >= self.interval {
+            self.sink().update(&self.update(current));
+            self.last_update.set(now);
+        }
+    }
+
+    /// Reports that the procedure has finished with the given amount of work done.
+    pub fn finish(&self, current: usize) {
+        self.sink().finish(&self.update(current));
+    }
+
+    /// Returns the sink that receives the updates.
+    fn sink(&self) -> &'static dyn ProgressSink {
+        self.sink.unwrap_or_else(progress_sink)
+    }
+
+    /// Returns the update for the given amount of work done.
+    fn update(&self, current: usize) -> ProgressUpdate<'static> {
+        ProgressUpdate {
+            task: self.task,
+            unit: self.unit,
+            current,
+            total: self.total,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::atomic::Ordering;
+
+    use super::*;
+
+    /// Counts the number of updates and finished procedures.
+    struct CountingSink {
+        updates: AtomicUsize,
+        finished: AtomicUsize,
+    }
+
+    impl ProgressSink for CountingSink {
+        fn update(&self, update: &ProgressUpdate<'_>) {
+            assert!(update.current < update.total.unwrap());
+            self.updates.fetch_add(1, Ordering::Relaxed);
+        }
+
+        fn finish(&self, update: &ProgressUpdate<'_>) {
+            assert_eq!(update.current, update.total.unwrap());
+            self.finished.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    #[test]
+    fn test_progress_sink() {
+        static SINK: CountingSink = CountingSink {
+            updates: AtomicUsize::new(0),
+            finished: AtomicUsize::new(0),
+        };
+
+        // Every update is reported since the interval is zero.
+        let progress = Progress::new("Testing", "steps", Some(100))
+            .with_interval(0)
+            .with_sink(&SINK);
+        for i in 0..100 {
+            progress.print(i);
+        }
+        progress.finish(100);
+
+        assert_eq!(SINK.updates.load(Ordering::Relaxed), 100);
+        assert_eq!(SINK.finished.load(Ordering::Relaxed), 1);
+    }
+}
@@ -0,0 +1,439 @@
+#![forbid(unsafe_code)]
+
+//! A small built-in layered layout to render (small) graphs as SVG, such that
+//! visualising does not depend on Graphviz being installed.
+
+use std::io::Write;
+
+use merc_utilities::MercError;
+
+/// The default number of nodes above which [RenderGraph::write_svg] refuses to
+/// render a graph, since the layout is not intended for large graphs.
+pub const DEFAULT_SVG_MAX_NODES: usize = 500;
+
+/// The horizontal distance between the layers.
+const LAYER_SPACING: f64 = 120.0;
+
+/// The vertical distance between the nodes in a layer.
+const NODE_SPACING: f64 = 70.0;
+
+/// The space around the drawing.
+const MARGIN: f64 = 50.0;
+
+/// The radius of the nodes.
+const NODE_RADIUS: f64 = 18.0;
+
+/// The number of barycenter sweeps used to reduce the edge crossings.
+const ORDERING_SWEEPS: usize = 8;
+
+/// The shape used to draw a node.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NodeShape {
+    Circle,
+    Square,
+    Diamond,
+}
+
+/// A node of a [RenderGraph].
+#[derive(Clone, Debug)]
+pub struct RenderNode {
+    /// The text inside the node.
+    pub label: String,
+
+    /// An optional text that is shown next to the node.
+    pub caption: Option<String>,
+
+    pub shape: NodeShape,
+}
+
+/// A directed edge of a [RenderGraph].
+#[derive(Clone, Debug)]
+pub struct RenderEdge {
+    pub from: usize,
+    pub to: usize,
+    pub label: Option<String>,
+}
+
+/// A graph that can be rendered as SVG, which is independent of whether it
+/// represents for example an LTS or a parity game.
+#[derive(Clone, Debug, Default)]
+pub struct RenderGraph {
+    nodes: Vec<RenderNode>,
+    edges: Vec<RenderEdge>,
+    initial: Option<usize>,
+}
+
+/// The positions of the nodes of a [RenderGraph] computed by [RenderGraph::layout].
+#[derive(Clone, Debug)]
+pub struct Layout {
+    /// The center of every node.
+    pub positions: Vec<(f64, f64)>,
+
+    /// The layer of every node, where the layers are placed from left to right.
+    pub layers: Vec<usize>,
+
+    pub width: f64,
+    pub height: f64,
+}
+
+impl RenderGraph {
+    /// Creates an empty graph.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a node and returns its index.
+    pub fn add_node(&mut self, label: impl Into<String>, caption: Option<String>, shape: NodeShape) -> usize {
+        self.nodes.push(RenderNode {
+            label: label.into(),
+            caption,
+            shape,
+        });
+        self.nodes.len() - 1
+    }
+
+    /// Adds an edge between the nodes with the given indices.
+    pub fn add_edge(&mut self, from: usize, to: usize, label: Option<String>) {
+        debug_assert!(
+            from < self.nodes.len() && to < self.nodes.len(),
+            "Edge ({from}, {to}) refers to an unknown node"
+        );
+        self.edges.push(RenderEdge { from, to, label });
+    }
+
+    /// Marks the given node as the initial node, which is drawn with an incoming arrow.
+    pub fn set_initial(&mut self, node: usize) {
+        self.initial = Some(node);
+    }
+
+    /// Returns the number of nodes.
+    pub fn num_of_nodes(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Computes a layered (Sugiyama-style) layout of the graph.
+    ///
+    /// # Details
+    ///
+    /// First the cycles are broken by reversing the back edges of a depth
+    /// first search, starting from the initial node. Then every node is
+    /// assigned to the layer of the longest path towards it, and the order of
+    /// the nodes within the layers is improved by a number of barycenter
+    /// sweeps. Unlike Graphviz no dummy nodes are introduced for the edges
+    /// spanning multiple layers, since these are drawn as curves instead.
+    pub fn layout(&self) -> Layout {
+        let num_of_nodes = self.nodes.len();
+
+        let mut successors = vec![Vec::new(); num_of_nodes];
+        for edge in &self.edges {
+            if edge.from != edge.to {
+                successors[edge.from].push(edge.to);
+            }
+        }
+
+        // Depth first search that reverses the back edges, and computes the post order.
+        let mut dag_successors = vec![Vec::new(); num_of_nodes];
+        let mut dag_predecessors = vec![Vec::new(); num_of_nodes];
+        let mut post_order = Vec::with_capacity(num_of_nodes);
+        let mut visited = vec![false; num_of_nodes];
+        let mut on_stack = vec![false; num_of_nodes];
+        let mut stack: Vec<(usize, usize)> = Vec::new();
+
+        for root in self.initial.into_iter().chain(0..num_of_nodes) {
+            if visited[root] {
+                continue;
+            }
+
+            visited[root] = true;
+            on_stack[root] = true;
+            stack.push((root, 0));
+
+            while let Some(&(node, position)) = stack.last() {
+                if let Some(&to) = successors[node].get(position) {
+                    stack.last_mut().expect("The stack is not empty").1 += 1;
+
+                    let (from, to) = if on_stack[to] { (to, node) } else { (node, to) };
+                    dag_successors[from].push(to);
+                    dag_predecessors[to].push(from);
+
+                    if !visited[to] {
+                        visited[to] = true;
+                        on_stack[to] = true;
+                        stack.push((to, 0));
+                    }
+                } else {
+                    stack.pop();
+                    on_stack[node] = false;
+                    post_order.push(node);
+                }
+            }
+        }
+
+        // The reverse post order is a topological order of the acyclic graph.
+        let mut layers = vec![0; num_of_nodes];
+        for &node in post_order.iter().rev() {
+            for &to in &dag_successors[node] {
+                layers[to] = layers[to].max(layers[node] + 1);
+            }
+        }
+
+        let num_of_layers = layers.iter().max().map_or(0, |layer| layer + 1);
+        let mut ordering: Vec<Vec<usize>> = vec![Vec::new(); num_of_layers];
+        for &node in post_order.iter().rev() {
+            ordering[layers[node]].push(node);
+        }
+
+        let mut positions = vec![0.0; num_of_nodes];
+        for layer in &ordering {
+            for (index, &node) in layer.iter().enumerate() {
+                positions[node] = index as f64;
+            }
+        }
+
+        // Alternate between sweeping right, ordering by the predecessors, and left, ordering by the successors.
+        for sweep in 0..ORDERING_SWEEPS {
+            let (neighbours, layer_indices): (_, Vec<usize>) = if sweep % 2 == 0 {
+                (&dag_predecessors, (1..num_of_layers).collect())
+            } else {
+                (&dag_successors, (0..num_of_layers.saturating_sub(1)).rev().collect())
+            };
+
+            for layer in layer_indices {
+                let barycenter = |node: usize| -> f64 {
+                    let neighbours: &Vec<usize> = &neighbours[node];
+                    if neighbours.is_empty() {
+                        positions[node]
+                    } else {
+                        neighbours.iter().map(|&neighbour| positions[neighbour]).sum::<f64>() / neighbours.len() as f64
+                    }
+                };
+
+                let mut keyed: Vec<(f64, usize)> =
+                    ordering[layer].iter().map(|&node| (barycenter(node), node)).collect();
+                keyed.sort_by(|(left, _), (right, _)| left.total_cmp(right));
+                ordering[layer] = keyed.into_iter().map(|(_, node)| node).collect();
+
+                for (index, &node) in ordering[layer].iter().enumerate() {
+                    positions[node] = index as f64;
+                }
+            }
+        }
+
+        // Center every layer vertically with respect to the largest layer.
+        let max_layer_size = ordering.iter().map(Vec::len).max().unwrap_or(0);
+        let coordinates = (0..num_of_nodes)
+            .map(|node| {
+                let offset = (max_layer_size - ordering[layers[node]].len()) as f64 / 2.0;
+                (
+                    MARGIN + layers[node] as f64 * LAYER_SPACING,
+                    MARGIN + (positions[node] + offset) * NODE_SPACING,
+                )
+            })
+            .collect();
+
+        Layout {
+            positions: coordinates,
+            layers,
+            width: 2.0 * MARGIN + num_of_layers.saturating_sub(1) as f64 * LAYER_SPACING,
+            height: 2.0 * MARGIN + max_layer_size.saturating_sub(1) as f64 * NODE_SPACING,
+        }
+    }
+
+    /// Writes the graph as a standalone SVG image using [RenderGraph::layout].
+    ///
+    /// Returns an error when the graph has more than `max_nodes` nodes.
+    pub fn write_svg(&self, writer: &mut impl Write, max_nodes: usize) -> Result<(), MercError> {
+        if self.nodes.len() > max_nodes {
+            return Err(MercError::unsupported(format!(
+                "Cannot render a graph with {} nodes, the limit is {max_nodes}",
+                self.nodes.len()
+            )));
+        }
+
+        let layout = self.layout();
+
+        writeln!(
+            writer,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w:.0}\" height=\"{h:.0}\" viewBox=\"0 0 {w:.0} {h:.0}\" font-family=\"DejaVu Sans, sans-serif\" font-size=\"12\">",
+            w = layout.width,
+            h = layout.height
+        )?;
+        writeln!(
+            writer,
+            "  <defs><marker id=\"arrow\" viewBox=\"0 0 10 10\" refX=\"10\" refY=\"5\" markerWidth=\"7\" markerHeight=\"7\" orient=\"auto\"><path d=\"M 0 0 L 10 5 L 0 10 z\" fill=\"#444444\"/></marker></defs>"
+        )?;
+
+        for edge in &self.edges {
+            self.write_edge(writer, &layout, edge)?;
+        }
+
+        if let Some(initial) = self.initial {
+            let (x, y) = layout.positions[initial];
+            writeln!(
+                writer,
+                "  <line x1=\"{:.1}\" y1=\"{y:.1}\" x2=\"{:.1}\" y2=\"{y:.1}\" stroke=\"#444444\" marker-end=\"url(#arrow)\"/>",
+                x - NODE_RADIUS - 25.0,
+                x - NODE_RADIUS
+            )?;
+        }
+
+        for (node, &(x, y)) in self.nodes.iter().zip(&layout.positions) {
+            match node.shape {
+                NodeShape::Circle => writeln!(
+                    writer,
+                    "  <circle cx=\"{x:.1}\" cy=\"{y:.1}\" r=\"{NODE_RADIUS}\" fill=\"white\" stroke=\"black\"/>"
+                )?,
+                NodeShape::Square => writeln!(
+                    writer,
+                    "  <rect x=\"{:.1}\" y=\"{:.1}\" width=\"{size:.1}\" height=\"{size:.1}\" fill=\"white\" stroke=\"black\"/>",
+                    x - NODE_RADIUS * 0.8,
+                    y - NODE_RADIUS * 0.8,
+                    size = NODE_RADIUS * 1.6
+                )?,
+                NodeShape::Diamond => writeln!(
+                    writer,
+                    "  <polygon points=\"{x:.1},{:.1} {:.1},{y:.1} {x:.1},{:.1} {:.1},{y:.1}\" fill=\"white\" stroke=\"black\"/>",
+                    y - NODE_RADIUS,
+                    x + NODE_RADIUS,
+                    y + NODE_RADIUS,
+                    x - NODE_RADIUS
+                )?,
+            }
+
+            writeln!(
+                writer,
+                "  <text x=\"{x:.1}\" y=\"{y:.1}\" text-anchor=\"middle\" dominant-baseline=\"central\">{}</text>",
+                escape(&node.label)
+            )?;
+
+            if let Some(caption) = &node.caption {
+                writeln!(
+                    writer,
+                    "  <text x=\"{:.1}\" y=\"{:.1}\" font-size=\"9\">{}</text>",
+                    x + NODE_RADIUS,
+                    y + NODE_RADIUS + 6.0,
+                    escape(caption)
+                )?;
+            }
+        }
+
+        writeln!(writer, "</svg>")?;
+        Ok(())
+    }
+
+    /// Writes a single edge, where the edges between adjacent layers are drawn
+    /// as straight lines and the other edges as curves.
+    fn write_edge(&self, writer: &mut impl Write, layout: &Layout, edge: &RenderEdge) -> Result<(), MercError> {
+        let (x1, y1) = layout.positions[edge.from];
+        let (x2, y2) = layout.positions[edge.to];
+
+        let (path, label_x, label_y) = if edge.from == edge.to {
+            // A self-loop is drawn above the node.
+            (
+                format!(
+                    "M {:.1} {:.1} C {:.1} {:.1} {:.1} {:.1} {:.1} {:.1}",
+                    x1 - 7.0,
+                    y1 - NODE_RADIUS + 2.0,
+                    x1 - 25.0,
+                    y1 - 60.0,
+                    x1 + 25.0,
+                    y1 - 60.0,
+                    x1 + 7.0,
+                    y1 - NODE_RADIUS + 2.0
+                ),
+                x1,
+                y1 - 50.0,
+            )
+        } else {
+            let straight = layout.layers[edge.to] == layout.layers[edge.from] + 1;
+            let bend = if straight { 0.0 } else { 35.0 };
+
+            // The control point lies on the normal of the line between the nodes.
+            let length = ((x2 - x1).powi(2) + (y2 - y1).powi(2)).sqrt();
+            let (normal_x, normal_y) = ((y1 - y2) / length, (x2 - x1) / length);
+            let (cx, cy) = ((x1 + x2) / 2.0 + normal_x * bend, (y1 + y2) / 2.0 + normal_y * bend);
+
+            // Start and end at the border of the nodes, in the direction of the control point.
+            let towards = |x: f64, y: f64| {
+                let length = ((cx - x).powi(2) + (cy - y).powi(2)).sqrt();
+                (x + (cx - x) / length * NODE_RADIUS, y + (cy - y) / length * NODE_RADIUS)
+            };
+            let (sx, sy) = towards(x1, y1);
+            let (ex, ey) = towards(x2, y2);
+
+            (
+                format!("M {sx:.1} {sy:.1} Q {cx:.1} {cy:.1} {ex:.1} {ey:.1}"),
+                0.25 * sx + 0.5 * cx + 0.25 * ex,
+                0.25 * sy + 0.5 * cy + 0.25 * ey - 4.0,
+            )
+        };
+
+        writeln!(
+            writer,
+            "  <path d=\"{path}\" fill=\"none\" stroke=\"#444444\" stroke-width=\"1.2\" marker-end=\"url(#arrow)\"/>"
+        )?;
+
+        if let Some(label) = &edge.label {
+            writeln!(
+                writer,
+                "  <text x=\"{label_x:.1}\" y=\"{label_y:.1}\" text-anchor=\"middle\">{}</text>",
+                escape(label)
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Escapes the characters that have a special meaning in XML.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layout_layers() {
+        // The back edge 2 -> 0 is reversed, such that the nodes are placed in three layers.
+        let mut graph = RenderGraph::new();
+        for index in 0..4 {
+            graph.add_node(index.to_string(), None, NodeShape::Circle);
+        }
+        graph.set_initial(0);
+        graph.add_edge(0, 1, Some("a".to_string()));
+        graph.add_edge(1, 2, Some("b".to_string()));
+        graph.add_edge(2, 0, Some("c".to_string()));
+        graph.add_edge(0, 3, None);
+        graph.add_edge(3, 3, None);
+
+        let layout = graph.layout();
+        assert_eq!(layout.layers, vec![0, 1, 2, 1]);
+        assert!(layout.positions[0].0 < layout.positions[1].0);
+        assert_ne!(layout.positions[1], layout.positions[3]);
+    }
+
+    #[test]
+    fn test_write_svg() {
+        let mut graph = RenderGraph::new();
+        let s0 = graph.add_node("0", Some("v0".to_string()), NodeShape::Diamond);
+        let s1 = graph.add_node("1", None, NodeShape::Square);
+        graph.set_initial(s0);
+        graph.add_edge(s0, s1, Some("a<b>".to_string()));
+
+        let mut output = Vec::new();
+        graph.write_svg(&mut output, DEFAULT_SVG_MAX_NODES).unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.starts_with("<svg"));
+        assert!(output.contains("a&lt;b&gt;"));
+        // The two node labels, the caption and the edge label.
+        assert_eq!(output.matches("<text").count(), 4);
+
+        assert!(graph.write_svg(&mut Vec::new(), 1).is_err());
+    }
+}
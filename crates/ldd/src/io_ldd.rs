@@ -104,12 +104,14 @@ impl<R: BitStreamRead> BinaryLddReader<R> {
         // Read and verify the header of the binary LDD format.
         let magic = reader.read_bits(16)?;
         if magic != BLF_MAGIC {
-            return Err("Invalid magic number in binary LDD stream".into());
+            return Err(MercError::format("Invalid magic number in binary LDD stream"));
         }
 
         let version = reader.read_bits(16)?;
         if version != BLF_VERSION {
-            return Err(format!("The BLF version ({version}) of the input file is incompatible with the version ({BLF_VERSION}) of this tool. The input file must be regenerated.").into());
+            return Err(MercError::format(format!(
+                "The BLF version ({version}) of the input file is incompatible with the version ({BLF_VERSION}) of this tool. The input file must be regenerated."
+            )));
         }
 
         // Add the true and false constants
@@ -131,7 +133,9 @@ impl<R: BitStreamRead> BinaryLddReader<R> {
                 return Ok(self
                     .nodes
                     .get(index)
-                    .ok_or(format!("Read invalid ldd index {index}, length {}", self.nodes.len()))?
+                    .ok_or_else(|| {
+                        MercError::format(format!("Read invalid ldd index {index}, length {}", self.nodes.len()))
+                    })?
                     .clone());
             }
 
@@ -140,14 +144,18 @@ impl<R: BitStreamRead> BinaryLddReader<R> {
             let right_index = self.reader.read_bits(self.ldd_index_width(true))? as usize;
             let ldd = storage.insert(
                 value as u32,
-                self.nodes.get(down_index).ok_or(format!(
-                    "Read invalid down ldd index {down_index}, length {}",
-                    self.nodes.len()
-                ))?,
-                self.nodes.get(right_index).ok_or(format!(
-                    "Read invalid right ldd index {right_index}, length {}",
-                    self.nodes.len()
-                ))?,
+                self.nodes.get(down_index).ok_or_else(|| {
+                    MercError::format(format!(
+                        "Read invalid down ldd index {down_index}, length {}",
+                        self.nodes.len()
+                    ))
+                })?,
+                self.nodes.get(right_index).ok_or_else(|| {
+                    MercError::format(format!(
+                        "Read invalid right ldd index {right_index}, length {}",
+                        self.nodes.len()
+                    ))
+                })?,
             );
             self.nodes.push(ldd);
         }
@@ -5,6 +5,8 @@ mod io_ldd;
 mod io_sylvan;
 pub mod iterators;
 mod operations;
+mod reorder;
+mod statistics;
 mod storage;
 mod test_utility;
 
@@ -12,5 +14,7 @@ pub use format::*;
 pub use io_ldd::*;
 pub use io_sylvan::*;
 pub use operations::*;
+pub use reorder::*;
+pub use statistics::*;
 pub use storage::*;
 pub use test_utility::*;
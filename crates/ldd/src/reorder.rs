@@ -0,0 +1,276 @@
+use std::collections::HashMap;
+
+use crate::DataRef;
+use crate::Ldd;
+use crate::LddRef;
+use crate::Storage;
+use crate::Value;
+use crate::compute_meta;
+use crate::height;
+use crate::relational_product;
+use crate::union;
+
+/// Returns the set of vectors where the values at the given level and the
+/// next level have been swapped, i.e., { <x_0, ..., x_(i+1), x_i, ..., x_n> |
+/// <x_0, ..., x_i, x_(i+1), ..., x_n> in set } for i = level.
+///
+/// Requires that the vectors in the set have at least level + 2 elements.
+pub fn swap_levels(storage: &mut Storage, set: &LddRef, level: usize) -> Ldd {
+    // Since all vectors have the same length, every node occurs at a single
+    // level and the results can be cached by node.
+    #[allow(clippy::mutable_key_type)]
+    let mut cache: HashMap<Ldd, Ldd> = HashMap::new();
+    swap_levels_rec(storage, set, level, &mut cache)
+}
+
+/// Returns the set of vectors where the levels have been permuted by the given
+/// order, i.e., { <x_(order_0), ..., x_(order_n)> | <x_0, ..., x_n> in set }.
+///
+/// # Details
+///
+/// The order must be a permutation of 0..n, where n is the height of the set.
+/// The permutation is applied by a sequence of [swap_levels], so reorderings
+/// that only move levels over short distances are the cheapest.
+pub fn reorder(storage: &mut Storage, set: &LddRef, order: &[usize]) -> Ldd {
+    debug_assert!(
+        set == storage.empty_set() || height(storage, set) == order.len(),
+        "The order must define the position of every level of the set"
+    );
+    debug_assert!(
+        (0..order.len()).all(|level| order.contains(&level)),
+        "The order {order:?} must be a permutation"
+    );
+
+    // The original level that is currently stored at every position.
+    let mut current: Vec<usize> = (0..order.len()).collect();
+    let mut result = storage.protect(set);
+
+    for (position, level) in order.iter().enumerate() {
+        let index = current
+            .iter()
+            .position(|x| x == level)
+            .expect("Every level occurs in the permutation");
+
+        // Move the level upwards until it is at the desired position.
+        for i in (position..index).rev() {
+            result = swap_levels(storage, &result, i);
+            current.swap(i, i + 1);
+        }
+    }
+
+    result
+}
+
+/// Computes the relation and meta information suitable for
+/// [relational_product] from a relation whose read and write parameters are
+/// given in an arbitrary order.
+///
+/// # Details
+///
+/// The vectors of the given relation are <x'_0, ..., x'_k, y'_0, ..., y'_l>,
+/// where x'_j is the value read from index read_proj_j and y'_j is the value
+/// written to index write_proj_j. The indices in the projections must be
+/// unique, but do not have to be sorted. The resulting relation interleaves the
+/// read and write values ordered by their index, as described by [compute_meta].
+pub fn compute_relation(
+    storage: &mut Storage,
+    relation: &LddRef,
+    read_proj: &[Value],
+    write_proj: &[Value],
+) -> (Ldd, Ldd) {
+    let order = relation_order(read_proj, write_proj);
+    let relation = reorder(storage, relation, &order);
+    let meta = compute_meta(storage, read_proj, write_proj);
+
+    (relation, meta)
+}
+
+/// Computes the set of vectors reachable in one step from the given set for a
+/// relation whose read and write parameters are given in an arbitrary order,
+/// see [compute_relation].
+///
+/// The relation is reordered on every call, so when the same relation is used
+/// repeatedly it is better to call [compute_relation] once and then use
+/// [relational_product].
+pub fn relational_product_with_mapping(
+    storage: &mut Storage,
+    set: &LddRef,
+    relation: &LddRef,
+    read_proj: &[Value],
+    write_proj: &[Value],
+) -> Ldd {
+    let (relation, meta) = compute_relation(storage, relation, read_proj, write_proj);
+    relational_product(storage, set, &relation, &meta)
+}
+
+/// Returns the order that maps the levels <x'_0, ..., x'_k, y'_0, ..., y'_l> of
+/// a relation to the interleaved levels expected by [relational_product].
+fn relation_order(read_proj: &[Value], write_proj: &[Value]) -> Vec<usize> {
+    debug_assert!(
+        read_proj.iter().enumerate().all(|(i, x)| !read_proj[..i].contains(x)),
+        "The read projection {read_proj:?} must not contain duplicates"
+    );
+    debug_assert!(
+        write_proj.iter().enumerate().all(|(i, x)| !write_proj[..i].contains(x)),
+        "The write projection {write_proj:?} must not contain duplicates"
+    );
+
+    let mut indices: Vec<Value> = read_proj.iter().chain(write_proj.iter()).copied().collect();
+    indices.sort();
+    indices.dedup();
+
+    let mut order = Vec::with_capacity(read_proj.len() + write_proj.len());
+    for index in indices {
+        if let Some(position) = read_proj.iter().position(|x| *x == index) {
+            order.push(position);
+        }
+
+        if let Some(position) = write_proj.iter().position(|x| *x == index) {
+            order.push(read_proj.len() + position);
+        }
+    }
+
+    order
+}
+
+/// The recursive implementation of [swap_levels].
+#[allow(clippy::mutable_key_type)]
+fn swap_levels_rec(storage: &mut Storage, set: &LddRef, level: usize, cache: &mut HashMap<Ldd, Ldd>) -> Ldd {
+    if set == storage.empty_set() {
+        return storage.empty_set().clone();
+    }
+
+    debug_assert_ne!(
+        set,
+        storage.empty_vector(),
+        "The set must have at least level + 2 levels"
+    );
+
+    if let Some(result) = cache.get(set) {
+        return result.clone();
+    }
+
+    let result = if level == 0 {
+        // Combine every pair of values x and y in { <x, y, ...> } into { <y, x, ...> }.
+        let mut result = storage.empty_set().clone();
+        let mut current = storage.protect(set);
+        while current != *storage.empty_set() {
+            let DataRef(x, down, right) = storage.get_ref(&current);
+            debug_assert_ne!(down, *storage.empty_vector(), "The set must have at least two levels");
+
+            let mut inner = storage.protect(&down);
+            while inner != *storage.empty_set() {
+                let DataRef(y, inner_down, inner_right) = storage.get_ref(&inner);
+
+                let empty_set = storage.empty_set().clone();
+                let swapped = storage.insert(x, &inner_down, &empty_set);
+                let swapped = storage.insert(y, &swapped, &empty_set);
+                result = union(storage, &result, &swapped);
+
+                inner = storage.protect(&inner_right);
+            }
+
+            current = storage.protect(&right);
+        }
+
+        result
+    } else {
+        let DataRef(value, down, right) = storage.get_ref(set);
+
+        let down_result = swap_levels_rec(storage, &down, level - 1, cache);
+        let right_result = swap_levels_rec(storage, &right, level, cache);
+        storage.insert(value, &down_result, &right_result)
+    };
+
+    cache.insert(storage.protect(set), result.clone());
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use rand::seq::SliceRandom;
+
+    use merc_utilities::random_test;
+
+    use crate::from_iter;
+    use crate::random_sorted_vector;
+    use crate::random_vector_set;
+
+    use super::*;
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_random_reorder() {
+        random_test(100, |rng| {
+            let mut storage = Storage::new();
+
+            let set = random_vector_set(rng, 32, 6, 4);
+            let mut order: Vec<usize> = (0..6).collect();
+            order.shuffle(rng);
+
+            let ldd = from_iter(&mut storage, set.iter());
+            let result = reorder(&mut storage, &ldd, &order);
+
+            let expected_result: HashSet<Vec<Value>> = set
+                .iter()
+                .map(|vector| order.iter().map(|level| vector[*level]).collect())
+                .collect();
+            let expected = from_iter(&mut storage, expected_result.iter());
+
+            assert_eq!(
+                result, expected,
+                "The reordered set does not match the permuted vectors"
+            );
+        });
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_random_relational_product_with_mapping() {
+        random_test(100, |rng| {
+            let mut storage = Storage::new();
+
+            let set = random_vector_set(rng, 32, 8, 4);
+
+            // The read and write parameters are not sorted, and may overlap.
+            let mut read_proj = random_sorted_vector(rng, 3, 8);
+            let mut write_proj = random_sorted_vector(rng, 2, 8);
+            read_proj.shuffle(rng);
+            write_proj.shuffle(rng);
+
+            let relation = random_vector_set(rng, 32, read_proj.len() + write_proj.len(), 4);
+
+            let ldd = from_iter(&mut storage, set.iter());
+            let rel = from_iter(&mut storage, relation.iter());
+            let result = relational_product_with_mapping(&mut storage, &ldd, &rel, &read_proj, &write_proj);
+
+            // Compute { x[write_proj := y'] | project(x, read_proj) = x' and (x', y') in R and x in S }.
+            let mut expected_result: HashSet<Vec<Value>> = HashSet::new();
+            for x in &set {
+                for vector in &relation {
+                    let (x_prime, y_prime) = vector.split_at(read_proj.len());
+
+                    if read_proj
+                        .iter()
+                        .zip(x_prime)
+                        .all(|(index, value)| x[*index as usize] == *value)
+                    {
+                        let mut value = x.clone();
+                        for (index, y) in write_proj.iter().zip(y_prime) {
+                            value[*index as usize] = *y;
+                        }
+                        expected_result.insert(value);
+                    }
+                }
+            }
+            let expected = from_iter(&mut storage, expected_result.iter());
+
+            assert_eq!(
+                result, expected,
+                "The relational product does not match the explicit result"
+            );
+        });
+    }
+}
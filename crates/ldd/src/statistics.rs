@@ -0,0 +1,85 @@
+use std::collections::HashSet;
+
+use crate::DataRef;
+use crate::LddRef;
+use crate::Storage;
+
+/// Returns the number of unique nodes of the given LDD per level, where level
+/// zero contains the nodes of the first element of the vectors. The constants
+/// 'true' and 'false' are not counted.
+///
+/// # Details
+///
+/// All nodes that are reachable by following the right edges are on the same
+/// level, since they represent the alternatives for the same element of the
+/// vectors, whereas the down edges lead to the next level.
+pub fn nodes_per_level(storage: &Storage, ldd: &LddRef) -> Vec<usize> {
+    let mut result = Vec::new();
+    let mut visited = HashSet::new();
+    let mut stack = vec![(ldd.index(), 0)];
+
+    while let Some((index, level)) = stack.pop() {
+        let node = LddRef::new(index);
+        if node == *storage.empty_set() || node == *storage.empty_vector() || !visited.insert(index) {
+            continue;
+        }
+
+        if result.len() <= level {
+            result.resize(level + 1, 0);
+        }
+        result[level] += 1;
+
+        let DataRef(_, down, right) = storage.get_ref(&node);
+        stack.push((down.index(), level + 1));
+        stack.push((right.index(), level));
+    }
+
+    result
+}
+
+/// Returns the number of unique nodes of the given LDD, excluding the
+/// constants 'true' and 'false'.
+pub fn node_count(storage: &Storage, ldd: &LddRef) -> usize {
+    nodes_per_level(storage, ldd).iter().sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use merc_utilities::random_test;
+
+    use crate::from_iter;
+    use crate::height;
+    use crate::random_vector_set;
+    use crate::singleton;
+
+    use super::*;
+
+    #[test]
+    fn test_nodes_per_level() {
+        let mut storage = Storage::new();
+
+        // The vectors <0 1>, <0 2> and <1 2> share the node for <2> on the second level.
+        let set = from_iter(&mut storage, [vec![0, 1], vec![0, 2], vec![1, 2]].iter());
+        assert_eq!(nodes_per_level(&storage, &set), vec![2, 2]);
+        assert_eq!(node_count(&storage, &set), 4);
+
+        let vector = singleton(&mut storage, &[1, 2, 3]);
+        assert_eq!(nodes_per_level(&storage, &vector), vec![1, 1, 1]);
+        assert_eq!(node_count(&storage, storage.empty_set()), 0);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_random_nodes_per_level() {
+        random_test(100, |rng| {
+            let mut storage = Storage::new();
+
+            let vectors = random_vector_set(rng, 32, 10, 10);
+            let set = from_iter(&mut storage, vectors.iter());
+
+            let levels = nodes_per_level(&storage, &set);
+            assert_eq!(levels.len(), height(&storage, &set));
+            assert!(node_count(&storage, &set) <= storage.num_of_nodes() - 2);
+        });
+    }
+}
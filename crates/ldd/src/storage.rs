@@ -5,6 +5,7 @@ use std::rc::Rc;
 
 use ldd::LddIndex;
 use ldd::SharedProtectionSet;
+use log::info;
 use merc_collections::IndexedSet;
 use merc_collections::ProtectionSet;
 
@@ -12,10 +13,15 @@ use crate::operations::height;
 
 mod cache;
 mod ldd;
+mod metrics;
 
 pub use self::cache::*;
 pub use self::ldd::Ldd;
 pub use self::ldd::LddRef;
+pub use self::metrics::*;
+
+/// The default minimum number of created nodes between two garbage collections.
+pub const DEFAULT_GARBAGE_COLLECTION_THRESHOLD: usize = 10000;
 
 pub type Value = u32;
 
@@ -80,9 +86,15 @@ pub struct Storage {
     nodes: IndexedSet<Node>,
     cache: OperationCache,
 
-    count_until_collection: u64,     // Count down until the next garbage collection.
+    count_until_collection: usize,   // Count down until the next garbage collection.
     enable_garbage_collection: bool, // Whether to enable automatic garbage collection based on heuristics.
     enable_performance_metrics: bool,
+
+    /// The minimum number of created nodes between two garbage collections.
+    collection_threshold: usize,
+    /// The number of created nodes until the next collection relative to the number of remaining nodes.
+    collection_growth: f64,
+    metrics: StorageMetrics,
     empty_set: Ldd,
     empty_vector: Ldd,
 }
@@ -106,14 +118,23 @@ impl Storage {
             nodes,
             cache: OperationCache::new(Rc::clone(&shared)),
 
-            count_until_collection: 10000,
+            count_until_collection: DEFAULT_GARBAGE_COLLECTION_THRESHOLD,
             enable_garbage_collection: true,
             enable_performance_metrics: false,
+            collection_threshold: DEFAULT_GARBAGE_COLLECTION_THRESHOLD,
+            collection_growth: 1.0,
+            metrics: StorageMetrics::default(),
             empty_set: Ldd::new(&shared, empty_set),
             empty_vector: Ldd::new(&shared, empty_vector),
         }
     }
 
+    /// Returns the number of nodes in the node table, including the nodes that
+    /// have not been garbage collected yet and the constants 'true' and 'false'.
+    pub fn num_of_nodes(&self) -> usize {
+        self.nodes.len()
+    }
+
     /// Provides access to the underlying operation cache.
     pub fn operation_cache(&mut self) -> &mut OperationCache {
         &mut self.cache
@@ -138,15 +159,13 @@ impl Storage {
             debug_assert!(value < self.value(right), "value should be less than right node value.");
         }
 
-        if self.count_until_collection == 0 {
-            if self.enable_garbage_collection {
-                self.garbage_collect();
-            }
-            self.count_until_collection = self.nodes.len() as u64;
+        let (index, inserted) = self.nodes.insert(Node::new(value, down.index(), right.index()));
+        if inserted {
+            self.count_until_collection = self.count_until_collection.saturating_sub(1);
+            self.metrics.nodes_created += 1;
+            self.metrics.peak_nodes = self.metrics.peak_nodes.max(self.nodes.len());
         }
 
-        let (index, _inserted) = self.nodes.insert(Node::new(value, down.index(), right.index()));
-
         Ldd::new(&self.protection_set, index)
     }
 
@@ -155,7 +174,32 @@ impl Storage {
         Ldd::new(&self.protection_set, ldd.index())
     }
 
+    /// Cleans up all LDDs that are unreachable from the root LDDs when enough
+    /// nodes have been created since the previous collection, see
+    /// [Storage::set_garbage_collection_threshold], and garbage collection is
+    /// enabled.
+    ///
+    /// # Details
+    ///
+    /// This must only be called when all LDDs that are still used are
+    /// protected, for example between two top-level operations. The operations
+    /// never collect themselves, since their intermediate results are not
+    /// protected.
+    pub fn maybe_garbage_collect(&mut self) {
+        if self.count_until_collection == 0 {
+            if self.enable_garbage_collection {
+                self.garbage_collect();
+            } else {
+                self.count_until_collection = self.next_collection_count();
+            }
+        }
+    }
+
     /// Cleans up all LDDs that are unreachable from the root LDDs.
+    ///
+    /// Like [Storage::maybe_garbage_collect] this must only be called when all
+    /// LDDs that are still used are protected, for example after a large
+    /// intermediate result has been dropped.
     pub fn garbage_collect(&mut self) {
         // Clear the cache since it contains unprotected LDDs, and keep track of size before clearing.
         let size_of_cache = self.cache.len();
@@ -194,6 +238,10 @@ impl Storage {
             );
         }
 
+        self.metrics.collections += 1;
+        self.metrics.nodes_collected += number_of_collections;
+        self.count_until_collection = self.next_collection_count();
+
         if self.enable_performance_metrics {
             println!(
                 "Collected {number_of_collections} elements and {} elements remaining",
@@ -203,7 +251,7 @@ impl Storage {
         }
     }
 
-    /// Enables automatic garbage collection, which is enabled by default.
+    /// Enables the garbage collections of [Storage::maybe_garbage_collect], which are enabled by default.
     pub fn enable_garbage_collection(&mut self, enabled: bool) {
         self.enable_garbage_collection = enabled;
     }
@@ -212,6 +260,42 @@ impl Storage {
         self.enable_performance_metrics = enabled;
     }
 
+    /// Sets the minimum number of nodes that are created between two automatic
+    /// garbage collections, which defaults to [DEFAULT_GARBAGE_COLLECTION_THRESHOLD].
+    pub fn set_garbage_collection_threshold(&mut self, threshold: usize) {
+        self.collection_threshold = threshold;
+        self.count_until_collection = self.count_until_collection.min(threshold);
+    }
+
+    /// Sets the number of nodes that are created until the next automatic
+    /// garbage collection relative to the number of nodes that remain after a
+    /// collection, which defaults to one. A larger factor collects less often,
+    /// at the cost of a larger node table.
+    pub fn set_garbage_collection_growth(&mut self, factor: f64) {
+        debug_assert!(factor >= 0.0, "The growth factor must be non-negative");
+        self.collection_growth = factor;
+    }
+
+    /// Returns the metrics of this storage.
+    pub fn metrics(&self) -> StorageMetrics {
+        StorageMetrics {
+            cache_hits: self.cache.hits(),
+            cache_misses: self.cache.misses(),
+            ..self.metrics.clone()
+        }
+    }
+
+    /// Prints the metrics of this storage, see [StorageMetrics].
+    pub fn print_metrics(&self) {
+        info!("{}", self.metrics());
+    }
+
+    /// Returns the number of nodes that can be created until the next automatic garbage collection.
+    fn next_collection_count(&self) -> usize {
+        let growth = (self.nodes.len() as f64 * self.collection_growth) as usize;
+        growth.max(self.collection_threshold)
+    }
+
     /// The 'false' LDD.
     pub fn empty_set(&self) -> &Ldd {
         &self.empty_set
@@ -316,6 +400,7 @@ mod tests {
 
     use super::*;
     use crate::operations::singleton;
+    use crate::operations::union;
     use crate::test_utility::*;
 
     use merc_utilities::random_test;
@@ -358,4 +443,32 @@ mod tests {
             storage.garbage_collect();
         });
     }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_random_garbage_collection_threshold() {
+        random_test(20, |rng| {
+            let mut storage = Storage::new();
+            storage.set_garbage_collection_threshold(16);
+
+            let a = from_iter(&mut storage, random_vector_set(rng, 200, 10, 5).iter());
+            storage.maybe_garbage_collect();
+            let b = from_iter(&mut storage, random_vector_set(rng, 200, 10, 5).iter());
+            storage.maybe_garbage_collect();
+            let _result = union(&mut storage, &a, &b);
+            storage.maybe_garbage_collect();
+
+            let metrics = storage.metrics();
+            assert!(
+                metrics.collections > 0,
+                "A small threshold should trigger automatic collections"
+            );
+
+            // Every node, except for the constants, has been created and not yet collected.
+            let live_nodes = storage.nodes.iter().count();
+            assert_eq!(metrics.nodes_created - metrics.nodes_collected, live_nodes - 2);
+            assert!(metrics.peak_nodes >= live_nodes);
+            assert!(metrics.cache_hits + metrics.cache_misses > 0);
+        });
+    }
 }
@@ -22,6 +22,11 @@ pub struct OperationCache {
     caches1: Vec<Cache<LddIndex, usize>>,
     caches2: Vec<Cache<(LddIndex, LddIndex), LddIndex>>,
     caches3: Vec<Cache<(LddIndex, LddIndex, LddIndex), LddIndex>>,
+
+    /// The number of results found in the caches.
+    hits: usize,
+    /// The number of results that were not found in the caches.
+    misses: usize,
 }
 
 impl OperationCache {
@@ -31,6 +36,8 @@ impl OperationCache {
             caches1: vec![Cache::new()],
             caches2: vec![Cache::new(); 3],
             caches3: vec![Cache::new()],
+            hits: 0,
+            misses: 0,
         }
     }
 
@@ -75,6 +82,16 @@ impl OperationCache {
         self.len() == 0
     }
 
+    /// Returns the number of results that were found in the caches.
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+
+    /// Returns the number of results that were not found in the caches.
+    pub fn misses(&self) -> usize {
+        self.misses
+    }
+
     /// Puts a limit on the operation cache size. This will ensure that
     /// self.len() <= n if self.limit(n) has been set.
     pub fn limit(&mut self, size: usize) {
@@ -225,8 +242,11 @@ where
 {
     let key = a.index();
     if let Some(result) = storage.operation_cache().get_cache1(&operator).get(&key) {
-        *result
+        let result = *result;
+        storage.operation_cache().hits += 1;
+        result
     } else {
+        storage.operation_cache().misses += 1;
         let result = f(storage, a);
         storage.operation_cache().get_cache1(&operator).insert(key, result);
         result
@@ -241,8 +261,10 @@ where
     let key = (a.index(), b.index());
     if let Some(result) = storage.operation_cache().get_cache2(&operator).get(&key) {
         let result = *result; // Necessary to decouple borrow from storage and the call to create.
+        storage.operation_cache().hits += 1;
         storage.operation_cache().create(result)
     } else {
+        storage.operation_cache().misses += 1;
         let result = f(storage, a, b);
         storage
             .operation_cache()
@@ -281,8 +303,10 @@ where
     let key = (a.index(), b.index(), c.index());
     if let Some(result) = storage.operation_cache().get_cache3(&operator).get(&key) {
         let result = *result; // Necessary to decouple borrow from storage and the call to create.
+        storage.operation_cache().hits += 1;
         storage.operation_cache().create(result)
     } else {
+        storage.operation_cache().misses += 1;
         let result = f(storage, a, b, c);
         storage
             .operation_cache()
@@ -0,0 +1,44 @@
+use std::fmt;
+
+use merc_io::LargeFormatter;
+
+/// Metrics of a [crate::Storage], which can be used to tune its garbage
+/// collection, see [crate::Storage::set_garbage_collection_threshold].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StorageMetrics {
+    /// The number of nodes that have been created.
+    pub nodes_created: usize,
+    /// The number of nodes that have been garbage collected.
+    pub nodes_collected: usize,
+    /// The number of garbage collections.
+    pub collections: usize,
+    /// The largest number of nodes in the node table.
+    pub peak_nodes: usize,
+    /// The number of results found in the operation cache.
+    pub cache_hits: usize,
+    /// The number of results that had to be computed.
+    pub cache_misses: usize,
+}
+
+impl StorageMetrics {
+    /// Returns the fraction of the lookups in the operation cache that were hits.
+    pub fn cache_hit_rate(&self) -> f64 {
+        self.cache_hits as f64 / (self.cache_hits + self.cache_misses).max(1) as f64
+    }
+}
+
+impl fmt::Display for StorageMetrics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "LDD storage: {} nodes created, {} collected in {} collections, at most {} nodes, operation cache hit rate {:.1}% ({} hits, {} misses)",
+            LargeFormatter(self.nodes_created),
+            LargeFormatter(self.nodes_collected),
+            self.collections,
+            LargeFormatter(self.peak_nodes),
+            self.cache_hit_rate() * 100.0,
+            LargeFormatter(self.cache_hits),
+            LargeFormatter(self.cache_misses),
+        )
+    }
+}
@@ -0,0 +1,508 @@
+use std::collections::HashMap;
+
+use ahash::AHashSet;
+use log::debug;
+
+use merc_data::DataApplication;
+use merc_data::DataExpression;
+use merc_data::DataFunctionSymbol;
+use merc_data::DataSpecification;
+use merc_data::DataVariable;
+use merc_data::Enumerator;
+use merc_data::to_data_expression;
+use merc_explore::LinearProcess;
+use merc_explore::Summand;
+use merc_syntax::DataExprBinaryOp;
+use merc_syntax::DataExprUnaryOp;
+use merc_syntax::ProcDecl;
+use merc_syntax::ProcExprBinaryOp;
+use merc_syntax::ProcessExpr;
+use merc_syntax::UntypedProcessSpecification;
+use merc_utilities::MercError;
+
+/// The value of a process parameter that is not used by the current process.
+const UNDEFINED: &str = "@undefined";
+
+/// Converts the given process specification into a linear process.
+///
+/// # Details
+///
+/// Only regular processes are supported, which are processes built from
+/// actions, `tau`, `delta`, choice, conditions and sequential composition,
+/// where a process instance may only occur at the end of a sequential
+/// composition. Parallel composition, the communication operators, sums over
+/// data variables and time are not supported.
+///
+/// The parameters of the linear process are the union of the parameters of all
+/// process declarations, where parameters with the same name are shared, and a
+/// program counter when the process has more than one control state. The
+/// control states are the processes and the remainders of sequential
+/// compositions, and the program counter is a number starting at one, as in
+/// the mCRL2 toolset. Whenever a process is instantiated the parameters that do
+/// not belong to it are set to the constant `@undefined`.
+///
+/// The data expressions of the result are untyped and the operators of mCRL2
+/// are represented by function symbols with their names, see
+/// [merc_data::to_data_expression]. The conditions use `&&`, `!` and `==` to
+/// combine guards and compare the program counter. Quantifiers in conditions
+/// are expanded when they range over finite sorts, see [merc_data::Enumerator].
+pub fn linearise(spec: &UntypedProcessSpecification) -> Result<LinearProcess, MercError> {
+    let init = spec.init.as_ref().ok_or("The specification has no initial process")?;
+
+    let mut parameters: Vec<String> = Vec::new();
+    for declaration in &spec.process_declarations {
+        for parameter in &declaration.params {
+            if !parameters.contains(&parameter.identifier) {
+                parameters.push(parameter.identifier.clone());
+            }
+        }
+    }
+
+    let mut lineariser = Lineariser {
+        spec,
+        data_specification: DataSpecification::from_syntax(&spec.data_specification)?,
+        variables: AHashSet::from_iter(parameters.iter().cloned()),
+        control_states: Vec::new(),
+        process_states: HashMap::new(),
+        sequence_states: HashMap::new(),
+        terminated: None,
+        pending: Vec::new(),
+    };
+
+    let (initial_control_state, initial_values) = lineariser.continuation(&[init])?;
+    let name = match init {
+        ProcessExpr::Action(name, _) | ProcessExpr::Id(name, _) if lineariser.declaration(name).is_some() => {
+            name.clone()
+        }
+        _ => "P".to_string(),
+    };
+
+    while let Some((state, sequence)) = lineariser.pending.pop() {
+        lineariser.summands(&sequence, state, Vec::new())?;
+    }
+
+    let num_of_control_states = lineariser.control_states.len();
+    debug!("Linearised the specification into {num_of_control_states} control states");
+
+    // The program counter is only necessary when there are multiple control states.
+    let program_counter = if num_of_control_states > 1 {
+        let mut name = "s".to_string();
+        while parameters.contains(&name) {
+            name.push('\'');
+        }
+        Some(name)
+    } else {
+        None
+    };
+
+    let next_state = |control_state: usize, values: &Option<HashMap<String, DataExpression>>| {
+        let mut result = Vec::new();
+        if program_counter.is_some() {
+            result.push(control_state_value(control_state));
+        }
+
+        for parameter in &parameters {
+            let value = match values {
+                Some(values) => values
+                    .get(parameter)
+                    .cloned()
+                    .unwrap_or_else(|| DataFunctionSymbol::new(UNDEFINED).into()),
+                None => DataVariable::new(parameter.as_str()).into(),
+            };
+            result.push(value);
+        }
+        result
+    };
+
+    let mut summands = Vec::new();
+    for (control_state, raw_summands) in lineariser.control_states.iter().enumerate() {
+        for summand in raw_summands {
+            let mut guards = Vec::new();
+            if let Some(program_counter) = &program_counter {
+                guards.push(operator(
+                    DataExprBinaryOp::Equal.to_string(),
+                    &[
+                        DataVariable::new(program_counter.as_str()).into(),
+                        control_state_value(control_state),
+                    ],
+                ));
+            }
+            guards.extend(summand.guards.iter().cloned());
+
+            summands.push(Summand {
+                condition: guards
+                    .into_iter()
+                    .reduce(|lhs, rhs| operator(DataExprBinaryOp::Conj.to_string(), &[lhs, rhs])),
+                action: summand.action.clone(),
+                next_state: next_state(summand.target, &summand.values),
+            });
+        }
+    }
+
+    LinearProcess::new(
+        name,
+        program_counter
+            .iter()
+            .chain(parameters.iter())
+            .map(|parameter| DataVariable::new(parameter.as_str()))
+            .collect(),
+        next_state(initial_control_state, &Some(initial_values.unwrap_or_default())),
+        summands,
+    )
+}
+
+/// A summand `guards -> action . target(values)` of a control state.
+struct RawSummand {
+    guards: Vec<DataExpression>,
+    action: DataExpression,
+    target: usize,
+    /// The values of the parameters in the target, or `None` when all parameters keep their value.
+    values: Option<HashMap<String, DataExpression>>,
+}
+
+struct Lineariser<'a> {
+    spec: &'a UntypedProcessSpecification,
+
+    /// The data specification, which determines the finite sorts for quantifier elimination.
+    data_specification: DataSpecification,
+
+    /// The names of all process parameters.
+    variables: AHashSet<String>,
+
+    /// The summands of every control state.
+    control_states: Vec<Vec<RawSummand>>,
+
+    /// The control state of every process declaration.
+    process_states: HashMap<&'a str, usize>,
+
+    /// The control states of the remainders of sequential compositions, identified by the addresses of their elements.
+    sequence_states: HashMap<Vec<usize>, usize>,
+
+    /// The control state after successful termination, which has no summands.
+    terminated: Option<usize>,
+
+    /// Control states whose summands have not yet been computed.
+    pending: Vec<(usize, Vec<&'a ProcessExpr>)>,
+}
+
+impl<'a> Lineariser<'a> {
+    /// Computes the summands of the sequential composition of the given
+    /// expressions in the given control state, under the given guards.
+    fn summands(
+        &mut self,
+        sequence: &[&'a ProcessExpr],
+        state: usize,
+        guards: Vec<DataExpression>,
+    ) -> Result<(), MercError> {
+        let (&head, rest) = sequence.split_first().expect("A sequence is never empty");
+
+        let with_head = |head: &'a ProcessExpr| -> Vec<&'a ProcessExpr> {
+            let mut result = vec![head];
+            result.extend_from_slice(rest);
+            result
+        };
+
+        match head {
+            ProcessExpr::Delta => Ok(()),
+            ProcessExpr::Binary {
+                op: ProcExprBinaryOp::Choice,
+                lhs,
+                rhs,
+            } => {
+                self.summands(&with_head(lhs.as_ref()), state, guards.clone())?;
+                self.summands(&with_head(rhs.as_ref()), state, guards)
+            }
+            ProcessExpr::Binary {
+                op: ProcExprBinaryOp::Sequence,
+                lhs,
+                rhs,
+            } => {
+                let mut result = vec![lhs.as_ref(), rhs.as_ref()];
+                result.extend_from_slice(rest);
+                self.summands(&result, state, guards)
+            }
+            ProcessExpr::Condition { condition, then, else_ } => {
+                let condition =
+                    Enumerator::new(&self.data_specification).to_data_expression(condition, &self.variables)?;
+
+                let mut then_guards = guards.clone();
+                then_guards.push(condition.clone());
+                self.summands(&with_head(then.as_ref()), state, then_guards)?;
+
+                if let Some(else_) = else_ {
+                    let mut else_guards = guards;
+                    else_guards.push(operator(DataExprUnaryOp::Negation.to_string(), &[condition]));
+                    self.summands(&with_head(else_.as_ref()), state, else_guards)?;
+                }
+
+                Ok(())
+            }
+            ProcessExpr::Tau => self.add_summand(state, guards, DataFunctionSymbol::new("tau").into(), rest),
+            ProcessExpr::Action(name, arguments) if self.declaration(name).is_none() => {
+                if !self
+                    .spec
+                    .action_declarations
+                    .iter()
+                    .any(|declaration| &declaration.identifier == name)
+                {
+                    return Err(format!("Action or process {name} is not declared").into());
+                }
+
+                let arguments = arguments
+                    .iter()
+                    .map(|argument| to_data_expression(argument, &self.variables))
+                    .collect::<Result<Vec<_>, _>>()?;
+                self.add_summand(state, guards, operator(name.clone(), &arguments), rest)
+            }
+            ProcessExpr::Action(name, _) | ProcessExpr::Id(name, _) => {
+                if rest.is_empty() {
+                    Err(MercError::unsupported(format!(
+                        "The recursion on process {name} is not guarded by an action"
+                    )))
+                } else {
+                    Err(MercError::unsupported(format!(
+                        "Process {name} is followed by a sequential composition, which is not regular"
+                    )))
+                }
+            }
+            ProcessExpr::Sum { .. } => Err(MercError::unsupported(format!(
+                "Sums over data variables in {head} are not supported"
+            ))),
+            _ => Err(MercError::unsupported(format!(
+                "The operator in {head} is not supported"
+            ))),
+        }
+    }
+
+    /// Adds a summand that performs the given action and continues with the given expressions.
+    fn add_summand(
+        &mut self,
+        state: usize,
+        guards: Vec<DataExpression>,
+        action: DataExpression,
+        rest: &[&'a ProcessExpr],
+    ) -> Result<(), MercError> {
+        let (target, values) = if rest.is_empty() {
+            (self.terminated_state(), None)
+        } else {
+            self.continuation(rest)?
+        };
+
+        self.control_states[state].push(RawSummand {
+            guards,
+            action,
+            target,
+            values,
+        });
+        Ok(())
+    }
+
+    /// Returns the control state in which the given sequential composition is
+    /// executed, and the values of the parameters in that state when it is a
+    /// process instance. Otherwise all parameters keep their value.
+    fn continuation(
+        &mut self,
+        sequence: &[&'a ProcessExpr],
+    ) -> Result<(usize, Option<HashMap<String, DataExpression>>), MercError> {
+        if let [instance] = sequence {
+            match *instance {
+                ProcessExpr::Action(name, arguments) => {
+                    if let Some(declaration) = self.declaration(name) {
+                        if declaration.params.len() != arguments.len() {
+                            return Err(format!(
+                                "Process {name} has {} parameters, but is instantiated with {} arguments",
+                                declaration.params.len(),
+                                arguments.len()
+                            )
+                            .into());
+                        }
+
+                        let mut values = HashMap::new();
+                        for (parameter, argument) in declaration.params.iter().zip(arguments) {
+                            values.insert(
+                                parameter.identifier.clone(),
+                                to_data_expression(argument, &self.variables)?,
+                            );
+                        }
+
+                        return Ok((self.process_state(declaration), Some(values)));
+                    }
+                }
+                ProcessExpr::Id(name, assignments) => {
+                    let declaration = self
+                        .declaration(name)
+                        .ok_or_else(|| format!("Process {name} is not declared"))?;
+
+                    // Parameters that are not assigned keep their value.
+                    let mut values: HashMap<String, DataExpression> = declaration
+                        .params
+                        .iter()
+                        .map(|parameter| {
+                            (
+                                parameter.identifier.clone(),
+                                DataVariable::new(parameter.identifier.as_str()).into(),
+                            )
+                        })
+                        .collect();
+
+                    for assignment in assignments {
+                        if !values.contains_key(&assignment.identifier) {
+                            return Err(format!("Process {name} has no parameter {}", assignment.identifier).into());
+                        }
+                        values.insert(
+                            assignment.identifier.clone(),
+                            to_data_expression(&assignment.expr, &self.variables)?,
+                        );
+                    }
+
+                    return Ok((self.process_state(declaration), Some(values)));
+                }
+                _ => {}
+            }
+        }
+
+        let key: Vec<usize> = sequence
+            .iter()
+            .map(|expr| *expr as *const ProcessExpr as usize)
+            .collect();
+        if let Some(&state) = self.sequence_states.get(&key) {
+            return Ok((state, None));
+        }
+
+        let state = self.new_control_state(sequence.to_vec());
+        self.sequence_states.insert(key, state);
+        Ok((state, None))
+    }
+
+    /// Returns the declaration of the process with the given name.
+    fn declaration(&self, name: &str) -> Option<&'a ProcDecl> {
+        self.spec
+            .process_declarations
+            .iter()
+            .find(|declaration| declaration.identifier == name)
+    }
+
+    /// Returns the control state of the given process declaration.
+    fn process_state(&mut self, declaration: &'a ProcDecl) -> usize {
+        if let Some(&state) = self.process_states.get(declaration.identifier.as_str()) {
+            return state;
+        }
+
+        let state = self.new_control_state(vec![&declaration.body]);
+        self.process_states.insert(&declaration.identifier, state);
+        state
+    }
+
+    /// Returns the control state after successful termination.
+    fn terminated_state(&mut self) -> usize {
+        if let Some(state) = self.terminated {
+            return state;
+        }
+
+        let state = self.control_states.len();
+        self.control_states.push(Vec::new());
+        self.terminated = Some(state);
+        state
+    }
+
+    /// Creates a control state whose summands are given by the sequence.
+    fn new_control_state(&mut self, sequence: Vec<&'a ProcessExpr>) -> usize {
+        let state = self.control_states.len();
+        self.control_states.push(Vec::new());
+        self.pending.push((state, sequence));
+        state
+    }
+}
+
+/// Returns the value of the program counter for the given control state.
+fn control_state_value(control_state: usize) -> DataExpression {
+    DataFunctionSymbol::new((control_state + 1).to_string()).into()
+}
+
+/// Returns the application of the operator with the given name to the arguments.
+fn operator(name: String, arguments: &[DataExpression]) -> DataExpression {
+    if arguments.is_empty() {
+        DataFunctionSymbol::new(name).into()
+    } else {
+        DataApplication::with_args(&DataFunctionSymbol::new(name), arguments).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use merc_lts::LTS;
+    use merc_rec_tests::load_rec_from_strings;
+    use merc_sabre::SabreRewriter;
+
+    use merc_explore::explore;
+
+    use super::*;
+
+    #[test]
+    fn test_linearise_single_process() {
+        let spec = UntypedProcessSpecification::parse(
+            "act inc: Nat;
+            proc P(x: Nat) = lt(x, succ(succ(succ(zero)))) -> inc(x) . P(succ(x)) + tau . P(x);
+            init P(zero);",
+        )
+        .unwrap();
+
+        let process = linearise(&spec).unwrap();
+        assert_eq!(process.parameters().len(), 1);
+        assert_eq!(process.summands().len(), 2);
+
+        let (rec, _) = load_rec_from_strings(&[include_str!("../../../examples/explore/counter.rec")]).unwrap();
+
+        let mut rewriter = SabreRewriter::new(&rec.to_rewrite_spec());
+        let lts = explore(&process, &mut rewriter).unwrap();
+        assert_eq!(lts.num_of_states(), 4);
+        assert_eq!(lts.num_of_transitions(), 7);
+    }
+
+    #[test]
+    fn test_linearise_control_states() {
+        let spec = UntypedProcessSpecification::parse(
+            "act a, b, c;
+            proc P = a . b . Q(zero) + c;
+                 Q(n: Nat) = a . P;
+            init P;",
+        )
+        .unwrap();
+
+        let process = linearise(&spec).unwrap();
+
+        // The program counter and the parameter n.
+        assert_eq!(process.parameters().len(), 2);
+        // The control states P, b . Q, the terminated state and Q.
+        assert_eq!(process.summands().len(), 4);
+        assert!(process.summands().iter().all(|summand| summand.condition.is_some()));
+    }
+
+    #[test]
+    fn test_linearise_quantified_condition() {
+        let spec = UntypedProcessSpecification::parse(
+            "sort Colour = struct red | green;
+            act a;
+            proc P(c: Colour) = (exists d: Colour . d == c) -> a . P(c);
+            init P(red);",
+        )
+        .unwrap();
+
+        let process = linearise(&spec).unwrap();
+        let condition = process.summands()[0].condition.as_ref().unwrap();
+        assert_eq!(condition.to_string(), "||(==(red, c), ==(green, c))");
+    }
+
+    #[test]
+    fn test_linearise_unsupported() {
+        for text in [
+            "act a; proc P = a . P || a . P; init P;",
+            "act a; proc P = P + a; init P;",
+            "act a: Nat; proc P = sum n: Nat . a(n) . P; init P;",
+            "act a; init b;",
+        ] {
+            let spec = UntypedProcessSpecification::parse(text).unwrap();
+            assert!(linearise(&spec).is_err(), "Expected {text} to be rejected");
+        }
+    }
+}
@@ -0,0 +1,118 @@
+/* Generated by cbindgen from crates/lts-ffi, do not modify manually. */
+
+#ifndef MERC_LTS_FFI_H
+#define MERC_LTS_FFI_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * The equivalences that are supported by [lts_reduce]. Isomorphism is not
+ * included, since it can only be used to compare LTSs and not to reduce them.
+ */
+typedef enum lts_equivalence_t {
+  LTS_EQUIVALENCE_STRONG_BISIM = 0,
+  LTS_EQUIVALENCE_STRONG_BISIM_NAIVE = 1,
+  LTS_EQUIVALENCE_BRANCHING_BISIM = 2,
+  LTS_EQUIVALENCE_BRANCHING_BISIM_NAIVE = 3,
+  LTS_EQUIVALENCE_WEAK_BISIM = 4,
+  LTS_EQUIVALENCE_WEAK_BISIM_SIGREF = 5,
+  LTS_EQUIVALENCE_NONE = 6,
+  LTS_EQUIVALENCE_SIMULATION = 7,
+  LTS_EQUIVALENCE_READY_SIMULATION = 8,
+  LTS_EQUIVALENCE_COUPLED_SIMULATION = 9,
+} lts_equivalence_t;
+
+/**
+ * The result of every function that can fail, on failure the error message
+ * can be obtained using [lts_last_error].
+ */
+typedef enum lts_status_t {
+  LTS_STATUS_OK = 0,
+  LTS_STATUS_ERROR = 1,
+} lts_status_t;
+
+/**
+ * An opaque labelled transition system, must be freed using [lts_free].
+ */
+typedef struct lts_t lts_t;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Returns the error message of the last function that failed on the calling
+ * thread, or null if there is none. The message remains valid until the next
+ * failing call on the same thread.
+ */
+const char *lts_last_error(void);
+
+/**
+ * Reads an LTS in the Aldebaran (.aut) format from the file at the given path,
+ * and stores it in `result` on success.
+ *
+ * # Safety
+ *
+ * The `path` must be a valid null terminated string, and `result` must be a valid pointer.
+ */
+lts_status_t lts_read_aut(const char *path, lts_t **result);
+
+/**
+ * Reduces the given LTS modulo the given equivalence, and stores the reduced
+ * LTS in `result` on success. The input LTS is not modified.
+ *
+ * The `equivalence` must be one of the [LtsEquivalence] values, which is
+ * passed as an integer since an out of range enum value is undefined
+ * behaviour in Rust. Unknown values result in an error.
+ *
+ * # Safety
+ *
+ * The `lts` must be obtained from this library and not yet freed, and `result` must be a valid pointer.
+ */
+lts_status_t lts_reduce(const lts_t *lts, uint32_t equivalence, lts_t **result);
+
+/**
+ * Writes the given LTS in the Aldebaran (.aut) format to the file at the given path.
+ *
+ * # Safety
+ *
+ * The `lts` must be obtained from this library and not yet freed, and `path` must be a valid null terminated string.
+ */
+lts_status_t lts_write_aut(const lts_t *lts, const char *path);
+
+/**
+ * Returns the number of states of the given LTS.
+ *
+ * # Safety
+ *
+ * The `lts` must be obtained from this library and not yet freed.
+ */
+size_t lts_num_of_states(const lts_t *lts);
+
+/**
+ * Returns the number of transitions of the given LTS.
+ *
+ * # Safety
+ *
+ * The `lts` must be obtained from this library and not yet freed.
+ */
+size_t lts_num_of_transitions(const lts_t *lts);
+
+/**
+ * Frees the given LTS, does nothing when it is null.
+ *
+ * # Safety
+ *
+ * The `lts` must be obtained from this library and not yet freed.
+ */
+void lts_free(lts_t *lts);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* MERC_LTS_FFI_H */
@@ -0,0 +1,279 @@
+#![doc = include_str!("../README.md")]
+
+use std::cell::RefCell;
+use std::ffi::CStr;
+use std::ffi::CString;
+use std::ffi::c_char;
+use std::fs::File;
+use std::panic::AssertUnwindSafe;
+use std::panic::catch_unwind;
+use std::ptr;
+
+use merc_lts::LTS;
+use merc_lts::LabelledTransitionSystem;
+use merc_lts::read_aut;
+use merc_lts::write_aut;
+use merc_reduction::Equivalence;
+use merc_reduction::reduce_lts;
+use merc_utilities::MercError;
+use merc_utilities::Timing;
+
+/// An opaque labelled transition system, must be freed using [lts_free].
+pub struct Lts {
+    lts: LabelledTransitionSystem<String>,
+}
+
+/// The result of every function that can fail, on failure the error message
+/// can be obtained using [lts_last_error].
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LtsStatus {
+    Ok = 0,
+    Error = 1,
+}
+
+/// The equivalences that are supported by [lts_reduce]. Isomorphism is not
+/// included, since it can only be used to compare LTSs and not to reduce them.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LtsEquivalence {
+    StrongBisim = 0,
+    StrongBisimNaive = 1,
+    BranchingBisim = 2,
+    BranchingBisimNaive = 3,
+    WeakBisim = 4,
+    WeakBisimSigref = 5,
+    None = 6,
+    Simulation = 7,
+    ReadySimulation = 8,
+    CoupledSimulation = 9,
+}
+
+impl TryFrom<u32> for LtsEquivalence {
+    type Error = MercError;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(LtsEquivalence::StrongBisim),
+            1 => Ok(LtsEquivalence::StrongBisimNaive),
+            2 => Ok(LtsEquivalence::BranchingBisim),
+            3 => Ok(LtsEquivalence::BranchingBisimNaive),
+            4 => Ok(LtsEquivalence::WeakBisim),
+            5 => Ok(LtsEquivalence::WeakBisimSigref),
+            6 => Ok(LtsEquivalence::None),
+            7 => Ok(LtsEquivalence::Simulation),
+            8 => Ok(LtsEquivalence::ReadySimulation),
+            9 => Ok(LtsEquivalence::CoupledSimulation),
+            _ => Err(format!("Unknown equivalence {value}").into()),
+        }
+    }
+}
+
+impl From<LtsEquivalence> for Equivalence {
+    fn from(equivalence: LtsEquivalence) -> Self {
+        match equivalence {
+            LtsEquivalence::StrongBisim => Equivalence::StrongBisim,
+            LtsEquivalence::StrongBisimNaive => Equivalence::StrongBisimNaive,
+            LtsEquivalence::BranchingBisim => Equivalence::BranchingBisim,
+            LtsEquivalence::BranchingBisimNaive => Equivalence::BranchingBisimNaive,
+            LtsEquivalence::WeakBisim => Equivalence::WeakBisim,
+            LtsEquivalence::WeakBisimSigref => Equivalence::WeakBisimSigref,
+            LtsEquivalence::None => Equivalence::None,
+            LtsEquivalence::Simulation => Equivalence::Simulation,
+            LtsEquivalence::ReadySimulation => Equivalence::ReadySimulation,
+            LtsEquivalence::CoupledSimulation => Equivalence::CoupledSimulation,
+        }
+    }
+}
+
+thread_local! {
+    /// The error message of the last failed call on this thread.
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Returns the error message of the last function that failed on the calling
+/// thread, or null if there is none. The message remains valid until the next
+/// failing call on the same thread.
+#[unsafe(no_mangle)]
+pub extern "C" fn lts_last_error() -> *const c_char {
+    LAST_ERROR.with_borrow(|error| error.as_ref().map_or(ptr::null(), |message| message.as_ptr()))
+}
+
+/// Reads an LTS in the Aldebaran (.aut) format from the file at the given path,
+/// and stores it in `result` on success.
+///
+/// # Safety
+///
+/// The `path` must be a valid null terminated string, and `result` must be a valid pointer.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn lts_read_aut(path: *const c_char, result: *mut *mut Lts) -> LtsStatus {
+    guard(|| {
+        // SAFETY: The caller guarantees that path is a valid null terminated string.
+        let path = unsafe { CStr::from_ptr(path) }.to_str()?;
+        let lts = read_aut(File::open(path)?, Vec::new())?;
+
+        // SAFETY: The caller guarantees that result is a valid pointer.
+        unsafe { *result = Box::into_raw(Box::new(Lts { lts })) };
+        Ok(())
+    })
+}
+
+/// Reduces the given LTS modulo the given equivalence, and stores the reduced
+/// LTS in `result` on success. The input LTS is not modified.
+///
+/// The `equivalence` must be one of the [LtsEquivalence] values, which is
+/// passed as an integer since an out of range enum value is undefined
+/// behaviour in Rust. Unknown values result in an error.
+///
+/// # Safety
+///
+/// The `lts` must be obtained from this library and not yet freed, and `result` must be a valid pointer.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn lts_reduce(lts: *const Lts, equivalence: u32, result: *mut *mut Lts) -> LtsStatus {
+    guard(|| {
+        let equivalence = LtsEquivalence::try_from(equivalence)?;
+        // SAFETY: The caller guarantees that lts is a valid pointer.
+        let lts = unsafe { lts.as_ref() }.ok_or("The given LTS is null")?;
+        let reduced = reduce_lts(lts.lts.clone(), equivalence.into(), &mut Timing::new())?;
+
+        // SAFETY: The caller guarantees that result is a valid pointer.
+        unsafe { *result = Box::into_raw(Box::new(Lts { lts: reduced })) };
+        Ok(())
+    })
+}
+
+/// Writes the given LTS in the Aldebaran (.aut) format to the file at the given path.
+///
+/// # Safety
+///
+/// The `lts` must be obtained from this library and not yet freed, and `path` must be a valid null terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn lts_write_aut(lts: *const Lts, path: *const c_char) -> LtsStatus {
+    guard(|| {
+        // SAFETY: The caller guarantees that lts is a valid pointer.
+        let lts = unsafe { lts.as_ref() }.ok_or("The given LTS is null")?;
+        // SAFETY: The caller guarantees that path is a valid null terminated string.
+        let path = unsafe { CStr::from_ptr(path) }.to_str()?;
+
+        write_aut(&mut File::create(path)?, &lts.lts)
+    })
+}
+
+/// Returns the number of states of the given LTS.
+///
+/// # Safety
+///
+/// The `lts` must be obtained from this library and not yet freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn lts_num_of_states(lts: *const Lts) -> usize {
+    // SAFETY: The caller guarantees that lts is a valid pointer.
+    unsafe { &*lts }.lts.num_of_states()
+}
+
+/// Returns the number of transitions of the given LTS.
+///
+/// # Safety
+///
+/// The `lts` must be obtained from this library and not yet freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn lts_num_of_transitions(lts: *const Lts) -> usize {
+    // SAFETY: The caller guarantees that lts is a valid pointer.
+    unsafe { &*lts }.lts.num_of_transitions()
+}
+
+/// Frees the given LTS, does nothing when it is null.
+///
+/// # Safety
+///
+/// The `lts` must be obtained from this library and not yet freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn lts_free(lts: *mut Lts) {
+    if !lts.is_null() {
+        // SAFETY: The caller guarantees that lts was obtained from Box::into_raw and not yet freed.
+        drop(unsafe { Box::from_raw(lts) });
+    }
+}
+
+/// Executes the given function, and converts errors and panics into a status
+/// code and the last error message, since neither may cross the FFI boundary.
+fn guard(function: impl FnOnce() -> Result<(), MercError>) -> LtsStatus {
+    let error = match catch_unwind(AssertUnwindSafe(function)) {
+        Ok(Ok(())) => return LtsStatus::Ok,
+        Ok(Err(error)) => error.to_string(),
+        Err(_) => "An internal error occurred".to_string(),
+    };
+
+    let message = CString::new(error.trim_end().replace('\0', "")).expect("All null characters are removed");
+    LAST_ERROR.with_borrow_mut(|last| *last = Some(message));
+    LtsStatus::Error
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::*;
+
+    #[test]
+    fn test_reduce_aut() {
+        let directory = tempfile::tempdir().unwrap();
+        let input = directory.path().join("input.aut");
+        let output = directory.path().join("output.aut");
+        writeln!(
+            File::create(&input).unwrap(),
+            "des (0, 2, 2)\n(0, \"a\", 1)\n(1, \"a\", 0)"
+        )
+        .unwrap();
+
+        let input = CString::new(input.to_str().unwrap()).unwrap();
+        let output = CString::new(output.to_str().unwrap()).unwrap();
+
+        unsafe {
+            let mut lts = ptr::null_mut();
+            assert_eq!(lts_read_aut(input.as_ptr(), &mut lts), LtsStatus::Ok);
+
+            let mut reduced = ptr::null_mut();
+            assert_eq!(
+                lts_reduce(lts, LtsEquivalence::StrongBisim as u32, &mut reduced),
+                LtsStatus::Ok
+            );
+            assert_eq!(lts_num_of_states(reduced), 1);
+            assert_eq!(lts_write_aut(reduced, output.as_ptr()), LtsStatus::Ok);
+
+            lts_free(lts);
+            lts_free(reduced);
+        }
+    }
+
+    #[test]
+    fn test_read_error() {
+        let path = CString::new("does/not/exist.aut").unwrap();
+
+        unsafe {
+            let mut lts = ptr::null_mut();
+            assert_eq!(lts_read_aut(path.as_ptr(), &mut lts), LtsStatus::Error);
+            assert!(lts.is_null());
+            assert!(!lts_last_error().is_null());
+        }
+    }
+
+    #[test]
+    fn test_reduce_unknown_equivalence() {
+        let directory = tempfile::tempdir().unwrap();
+        let input = directory.path().join("input.aut");
+        writeln!(File::create(&input).unwrap(), "des (0, 1, 2)\n(0, \"a\", 1)").unwrap();
+        let input = CString::new(input.to_str().unwrap()).unwrap();
+
+        unsafe {
+            let mut lts = ptr::null_mut();
+            assert_eq!(lts_read_aut(input.as_ptr(), &mut lts), LtsStatus::Ok);
+
+            let mut reduced = ptr::null_mut();
+            assert_eq!(lts_reduce(lts, 42, &mut reduced), LtsStatus::Error);
+            assert!(reduced.is_null());
+            assert!(!lts_last_error().is_null());
+
+            lts_free(lts);
+        }
+    }
+}
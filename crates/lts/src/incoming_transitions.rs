@@ -1,6 +1,9 @@
 #![forbid(unsafe_code)]
 
+use std::fmt;
+
 use merc_collections::ByteCompressedVec;
+use merc_collections::CompressedVecMetrics;
 use merc_collections::bytevec;
 
 use crate::LTS;
@@ -96,6 +99,32 @@ impl IncomingTransitions {
             .map(move |i| Transition::new(self.transition_labels.index(i), self.transition_from.index(i)))
             .take_while(|transition| transition.label == 0)
     }
+
+    /// Returns metrics about the memory usage of the incoming transitions.
+    pub fn metrics(&self) -> IncomingTransitionsMetrics {
+        IncomingTransitionsMetrics {
+            state_metrics: self.state2incoming.metrics(),
+            transition_labels_metrics: self.transition_labels.metrics(),
+            transition_from_metrics: self.transition_from.metrics(),
+        }
+    }
+}
+
+/// Metrics for the incoming transitions of a labelled transition system.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IncomingTransitionsMetrics {
+    pub state_metrics: CompressedVecMetrics,
+    pub transition_labels_metrics: CompressedVecMetrics,
+    pub transition_from_metrics: CompressedVecMetrics,
+}
+
+impl fmt::Display for IncomingTransitionsMetrics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Incoming states {}", self.state_metrics)?;
+        writeln!(f, "Incoming transition labels {}", self.transition_labels_metrics)?;
+        write!(f, "Incoming transition from {}", self.transition_from_metrics)
+    }
 }
 
 #[cfg(test)]
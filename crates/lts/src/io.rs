@@ -1,18 +1,29 @@
 #![forbid(unsafe_code)]
 
+use std::collections::HashMap;
 use std::ffi::OsStr;
 use std::fs::File;
 use std::path::Path;
 
+use merc_io::DEFAULT_SVG_MAX_NODES;
+use merc_unsafety::FileReader;
 use merc_utilities::MercError;
 use merc_utilities::Timing;
 
 use crate::LTS;
 use crate::LabelledTransitionSystem;
 use crate::MultiAction;
+use crate::TransitionLabel;
+use crate::canonicalize_lts_pair;
 use crate::read_aut;
 use crate::read_bcg;
 use crate::read_lts;
+use crate::write_aut;
+use crate::write_bcg;
+use crate::write_dot;
+use crate::write_fsm;
+use crate::write_lts;
+use crate::write_svg;
 
 /// Convenience macro to call `GenericLts::apply` with the same function for both variants.
 /// Useful with generic functions that can be monomorphized for both label types.
@@ -20,6 +31,7 @@ use crate::read_lts;
 /// Examples:
 /// - apply_lts!(lts, my_fn)
 /// - apply_lts!(lts, |lts| do_something(lts))
+#[deprecated(note = "convert the LTS into an `LtsVariant`, which implements the `LTS` trait, instead")]
 #[macro_export]
 macro_rules! apply_lts {
     ($lts:expr, $arguments:expr, $f:path) => {
@@ -36,6 +48,7 @@ macro_rules! apply_lts {
 /// Examples:
 /// - apply_lts_pair!(lhs, rhs, args, my_fn)
 /// - apply_lts_pair!(lhs, rhs, args, |a, b, args| do_something(a, b, args))
+#[deprecated(note = "convert the LTSs into an `LtsVariant`, which implements the `LTS` trait, instead")]
 #[macro_export]
 macro_rules! apply_lts_pair {
     ($lhs:expr, $rhs:expr, $arguments:expr, $f:path) => {
@@ -56,6 +69,12 @@ pub enum LtsFormat {
     Lts,
     /// The CADP BCG format (requires 'cadp' feature)
     Bcg,
+    /// The mCRL2 FSM format, only supported for writing
+    Fsm,
+    /// The Graphviz DOT format, only supported for writing
+    Dot,
+    /// An SVG image using the built-in layout, only supported for writing small LTSs
+    Svg,
 }
 
 /// Guesses the LTS file format from the file extension.
@@ -64,12 +83,26 @@ pub fn guess_lts_format_from_extension(path: &Path, format: Option<LtsFormat>) -
         return Some(format);
     }
 
+    // Only the `.lts` format is transparently decompressed, so compressed files such as `abp.aut.zst` are rejected.
+    if path.extension() == Some(OsStr::new("zst")) {
+        return path
+            .file_stem()
+            .filter(|stem| Path::new(stem).extension() == Some(OsStr::new("lts")))
+            .map(|_| LtsFormat::Lts);
+    }
+
     if path.extension() == Some(OsStr::new("aut")) {
         Some(LtsFormat::Aut)
     } else if path.extension() == Some(OsStr::new("lts")) {
         Some(LtsFormat::Lts)
     } else if path.extension() == Some(OsStr::new("bcg")) {
         Some(LtsFormat::Bcg)
+    } else if path.extension() == Some(OsStr::new("fsm")) {
+        Some(LtsFormat::Fsm)
+    } else if path.extension() == Some(OsStr::new("dot")) {
+        Some(LtsFormat::Dot)
+    } else if path.extension() == Some(OsStr::new("svg")) {
+        Some(LtsFormat::Svg)
     } else {
         None
     }
@@ -89,16 +122,20 @@ pub enum GenericLts {
 
 impl GenericLts {
     /// Applies the given function to both LTSs when they are the same variant.
-    /// Returns an error if the variants do not match.
+    /// Returns an error if the variants do not match. The string labels of
+    /// both LTSs are canonicalised and share their label indices, see
+    /// [canonicalize_lts_pair].
     pub fn apply_pair<T, FAut, FLts, R>(self, other: GenericLts, arguments: T, apply_aut: FAut, apply_lts: FLts) -> R
     where
         FAut: FnOnce(LabelledTransitionSystem<String>, LabelledTransitionSystem<String>, T) -> R,
         FLts: FnOnce(LabelledTransitionSystem<MultiAction>, LabelledTransitionSystem<MultiAction>, T) -> R,
     {
         match (self, other) {
-            (GenericLts::Aut(a), GenericLts::Aut(b)) => apply_aut(a, b, arguments),
+            (GenericLts::Aut(a), GenericLts::Aut(b)) | (GenericLts::Bcg(a), GenericLts::Bcg(b)) => {
+                let (a, b) = canonicalize_lts_pair(&a, &b);
+                apply_aut(a, b, arguments)
+            }
             (GenericLts::Lts(a), GenericLts::Lts(b)) => apply_lts(a, b, arguments),
-            (GenericLts::Bcg(a), GenericLts::Bcg(b)) => apply_aut(a, b, arguments),
             _ => unreachable!("Mismatched GenericLts variants in apply_pair; this indicates a programming error"),
         }
     }
@@ -136,6 +173,43 @@ impl GenericLts {
             GenericLts::Bcg(lts) => lts.num_of_transitions(),
         }
     }
+
+    /// Converts the LTS into one with string labels, as used by the `.aut` and
+    /// `.bcg` formats. The internal action is represented by `i`.
+    pub fn into_string_lts(self) -> LabelledTransitionSystem<String> {
+        match self {
+            GenericLts::Aut(lts) | GenericLts::Bcg(lts) => lts,
+            GenericLts::Lts(lts) => lts.relabel(|label| {
+                if label.is_tau_label() && label.time().is_none() {
+                    String::tau_label()
+                } else {
+                    label.to_string()
+                }
+            }),
+        }
+    }
+
+    /// Converts the LTS into one with multi-action labels, as used by the
+    /// `.lts` format. Fails when a label cannot be parsed as a multi-action.
+    pub fn into_multi_action_lts(self) -> Result<LabelledTransitionSystem<MultiAction>, MercError> {
+        match self {
+            GenericLts::Lts(lts) => Ok(lts),
+            GenericLts::Aut(lts) | GenericLts::Bcg(lts) => {
+                // Parse all labels first, since relabelling cannot fail.
+                let mut multi_actions = HashMap::new();
+                for label in lts.labels() {
+                    let multi_action = if label.is_tau_label() {
+                        MultiAction::tau_label()
+                    } else {
+                        MultiAction::from_string(label)?
+                    };
+                    multi_actions.insert(label.clone(), multi_action);
+                }
+
+                Ok(lts.relabel(|label| multi_actions[&label].clone()))
+            }
+        }
+    }
 }
 
 /// Reads an explicit labelled transition system from the given path and format.
@@ -149,16 +223,101 @@ pub fn read_explicit_lts(
 
     let result = match format {
         LtsFormat::Aut => {
-            let file = File::open(path)?;
-            GenericLts::Aut(read_aut(&file, hidden_labels)?)
+            let file = FileReader::open(path)?;
+            GenericLts::Aut(read_aut(file, hidden_labels)?)
         }
         LtsFormat::Lts => {
-            let file = File::open(path)?;
-            GenericLts::Lts(read_lts(&file, hidden_labels)?)
+            // Large binary files are read through a memory map when the platform supports it.
+            let file = FileReader::open(path)?;
+            GenericLts::Lts(read_lts(file, hidden_labels)?)
         }
         LtsFormat::Bcg => GenericLts::Bcg(read_bcg(path, hidden_labels)?),
+        LtsFormat::Fsm | LtsFormat::Dot | LtsFormat::Svg => {
+            return Err(MercError::unsupported(format!(
+                "Reading LTSs in the {format:?} format is not supported."
+            )));
+        }
     };
 
     time_read.finish();
     Ok(result)
 }
+
+/// Writes the given labelled transition system to the given path in the given
+/// format, converting the labels when the LTS was read in another format.
+///
+/// # Details
+///
+/// The `.lts` format is written zstd compressed when the path has the `.zst`
+/// extension, which requires the `zstd` feature. Note that only the states,
+/// transitions and labels are preserved when converting between formats,
+/// since the `.aut` and `.bcg` formats cannot store state labels.
+pub fn write_explicit_lts(
+    path: &Path,
+    format: LtsFormat,
+    lts: GenericLts,
+    timing: &mut Timing,
+) -> Result<(), MercError> {
+    let mut time_write = timing.start("write_explicit_lts");
+
+    match format {
+        LtsFormat::Aut => write_aut(&mut File::create(path)?, &lts.into_string_lts())?,
+        LtsFormat::Lts => {
+            let lts = lts.into_multi_action_lts()?;
+            let mut file = File::create(path)?;
+
+            if path.extension() == Some(OsStr::new("zst")) {
+                #[cfg(feature = "zstd")]
+                crate::write_lts_compressed(&mut file, &lts)?;
+                #[cfg(not(feature = "zstd"))]
+                return Err(MercError::unsupported(
+                    "Writing compressed .lts files requires the 'zstd' feature.",
+                ));
+            } else {
+                write_lts(&mut file, &lts)?;
+            }
+        }
+        LtsFormat::Bcg => write_bcg(&lts.into_string_lts(), path)?,
+        LtsFormat::Fsm => write_fsm(&mut File::create(path)?, &lts.into_string_lts())?,
+        LtsFormat::Dot => write_dot(&mut File::create(path)?, &lts.into_string_lts())?,
+        LtsFormat::Svg => write_svg(&mut File::create(path)?, &lts.into_string_lts(), DEFAULT_SVG_MAX_NODES)?,
+    }
+
+    time_write.finish();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use merc_utilities::random_test;
+
+    use crate::check_equivalent;
+    use crate::random_lts;
+
+    use super::*;
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_random_label_conversion() {
+        random_test(100, |rng| {
+            let lts = random_lts(rng, 10, 3, 3);
+
+            let multi_action_lts = GenericLts::Aut(lts.clone()).into_multi_action_lts().unwrap();
+            let result = GenericLts::Lts(multi_action_lts).into_string_lts();
+
+            check_equivalent(&lts, &result);
+        });
+    }
+
+    #[test]
+    fn test_guess_compressed_lts_format() {
+        assert_eq!(
+            guess_lts_format_from_extension(Path::new("abp.lts.zst"), None),
+            Some(LtsFormat::Lts)
+        );
+
+        // Only the .lts format can be read compressed.
+        assert_eq!(guess_lts_format_from_extension(Path::new("abp.aut.zst"), None), None);
+        assert_eq!(guess_lts_format_from_extension(Path::new("abp.zst"), None), None);
+    }
+}
@@ -5,14 +5,16 @@ use std::io::Read;
 use std::io::Write;
 
 use log::info;
-use merc_io::LargeFormatter;
 use regex::Regex;
 use streaming_iterator::StreamingIterator;
 use thiserror::Error;
 
 use merc_io::LineIterator;
-use merc_io::TimeProgress;
+use merc_io::MAX_LINE_LENGTH;
+use merc_io::Progress;
+use merc_io::capacity_hint;
 use merc_utilities::MercError;
+use merc_utilities::Span;
 use merc_utilities::debug_trace;
 
 use crate::LTS;
@@ -28,6 +30,9 @@ pub enum IOError {
 
     #[error("Invalid transition {0}")]
     InvalidTransition(String),
+
+    #[error("Probabilistic .aut files are not supported, found the probabilistic state {0}")]
+    ProbabilisticState(String),
 }
 
 /// Loads a labelled transition system in the [Aldebaran
@@ -41,49 +46,126 @@ pub enum IOError {
 /// And one line for every transition either one of these cases:
 ///  `(<from>: Nat, "<label>": Str, <to>: Nat)`
 ///  `(<from>: Nat, <label>: Str, <to>: Nat)`
+///
+/// Empty lines and comments, which are lines whose first non-whitespace
+/// character is `%`, are skipped. Use [read_aut_with_comments] to retain the comments. The
+/// probabilistic extension of the format, where the initial state and the
+/// target of a transition can be a distribution such as `1 1/2 2`, is rejected
+/// with the line of the first probabilistic state.
 pub fn read_aut(reader: impl Read, hidden_labels: Vec<String>) -> Result<LabelledTransitionSystem<String>, MercError> {
+    read_aut_impl(reader, hidden_labels, None)
+}
+
+/// Loads a labelled transition system in the Aldebaran format like
+/// [read_aut], and returns the comments of the file in the order in which they
+/// occur, without the leading `%`.
+pub fn read_aut_with_comments(
+    reader: impl Read,
+    hidden_labels: Vec<String>,
+) -> Result<(LabelledTransitionSystem<String>, Vec<String>), MercError> {
+    let mut comments = Vec::new();
+    let lts = read_aut_impl(reader, hidden_labels, Some(&mut comments))?;
+    Ok((lts, comments))
+}
+
+/// Reads the .aut format, see [read_aut], and adds the comments to `comments` when given.
+fn read_aut_impl(
+    reader: impl Read,
+    hidden_labels: Vec<String>,
+    mut comments: Option<&mut Vec<String>>,
+) -> Result<LabelledTransitionSystem<String>, MercError> {
     info!("Reading LTS in .aut format...");
 
-    let mut lines = LineIterator::new(reader);
-    lines.advance();
-    let header = lines
-        .get()
-        .ok_or(IOError::InvalidHeader("The first line should be the header"))?;
+    let mut lines = LineIterator::new(reader).with_max_line_length(MAX_LINE_LENGTH);
+
+    // Returns the next line that is not empty or a comment, together with its
+    // line number. Lines that are too long are always returned, such that they are reported.
+    let mut next_line = |lines: &mut LineIterator<_>| -> Option<usize> {
+        loop {
+            lines.advance();
+            let line = lines.get()?;
+            if lines.is_truncated() {
+                return Some(lines.line_number());
+            }
+
+            let trimmed = line.trim_start();
+            if let Some(comment) = trimmed.strip_prefix('%') {
+                if let Some(comments) = comments.as_mut() {
+                    comments.push(comment.to_string());
+                }
+            } else if !trimmed.is_empty() {
+                return Some(lines.line_number());
+            }
+        }
+    };
+
+    let Some(header_line) = next_line(&mut lines) else {
+        return Err(match lines.take_error() {
+            Some(error) => error.into(),
+            None => MercError::parse(IOError::InvalidHeader("The first line should be the header"), None),
+        });
+    };
+    let header = lines.get().expect("The header line has been read");
+    let header_span = Span::line(header_line);
+    lines.check_length()?;
 
     // Regex for des (<initial>: Nat, <num_of_states>: Nat, <num_of_transitions>: Nat)
-    let header_regex = Regex::new(r#"des\s*\(\s*([0-9]*)\s*,\s*([0-9]*)\s*,\s*([0-9]*)\s*\)\s*"#)
+    let header_regex = Regex::new(r#"des\s*\(\s*([^,]*?)\s*,\s*([0-9]*)\s*,\s*([0-9]*)\s*\)\s*"#)
         .expect("Regex compilation should not fail");
 
     let (_, [initial_txt, num_of_transitions_txt, num_of_states_txt]) = header_regex
         .captures(header)
-        .ok_or(IOError::InvalidHeader(
-            "does not match des (<init>, <num_of_transitions>, <num_of_states>)",
-        ))?
+        .ok_or_else(|| {
+            MercError::parse(
+                IOError::InvalidHeader("does not match des (<init>, <num_of_transitions>, <num_of_states>)"),
+                Some(header_span),
+            )
+        })?
         .extract();
 
-    let initial_state = StateIndex::new(initial_txt.parse()?);
-    let num_of_transitions: usize = num_of_transitions_txt.parse()?;
-    let num_of_states: usize = num_of_states_txt.parse()?;
-
-    let mut builder = LtsBuilder::with_capacity(Vec::new(), hidden_labels, num_of_states, 16, num_of_transitions);
-    let progress = TimeProgress::new(
-        move |read: usize| {
-            info!(
-                "Read {} transitions {}%...",
-                LargeFormatter(read),
-                read * 100 / num_of_transitions
-            )
-        },
-        1,
+    let initial_state = read_state(initial_txt, header_span)?;
+    let header_error = |error: std::num::ParseIntError| MercError::from(error).with_span(header_span);
+    let num_of_transitions: usize = num_of_transitions_txt.parse().map_err(header_error)?;
+    let num_of_states: usize = num_of_states_txt.parse().map_err(header_error)?;
+
+    if initial_state.value() >= num_of_states {
+        return Err(MercError::parse(
+            format!("The initial state {initial_state} exceeds the number of states {num_of_states}"),
+            Some(header_span),
+        ));
+    }
+
+    let mut builder = LtsBuilder::with_capacity(
+        Vec::new(),
+        hidden_labels,
+        num_of_states,
+        16,
+        capacity_hint(num_of_transitions),
     );
+    builder.require_num_of_states(initial_state.value() + 1);
+    let progress = Progress::new("Reading LTS", "transitions", Some(num_of_transitions));
 
-    while let Some(line) = lines.next() {
-        let (from_txt, label_txt, to_txt) =
-            read_transition(line).ok_or_else(|| IOError::InvalidTransition(line.clone()))?;
+    while let Some(line_number) = next_line(&mut lines) {
+        lines.check_length()?;
+        let line = lines.get().expect("The transition line has been read");
+        let span = Span::line(line_number);
+        let (from_txt, label_txt, to_txt) = read_transition(line)
+            .ok_or_else(|| MercError::parse(IOError::InvalidTransition(line.clone()), Some(span)))?;
 
         // Parse the from and to states, with the given label.
-        let from = StateIndex::new(from_txt.parse()?);
-        let to = StateIndex::new(to_txt.parse()?);
+        let from = StateIndex::new(
+            from_txt
+                .parse()
+                .map_err(|error| MercError::from(error).with_span(span))?,
+        );
+        let to = read_state(to_txt, span)?;
+
+        if from.value() >= num_of_states || to.value() >= num_of_states {
+            return Err(MercError::parse(
+                format!("Transition {from} -> {to} exceeds the number of states {num_of_states}"),
+                Some(span),
+            ));
+        }
 
         debug_trace!("Read transition {from} --[{label_txt}]-> {to}");
 
@@ -92,16 +174,45 @@ pub fn read_aut(reader: impl Read, hidden_labels: Vec<String>) -> Result<Labelle
         progress.print(builder.num_of_transitions());
     }
 
+    if let Some(error) = lines.take_error() {
+        return Err(error.into());
+    }
+
+    progress.finish(builder.num_of_transitions());
     info!("Finished reading LTS");
 
     Ok(builder.finish(initial_state))
 }
 
+/// Parses a state, which fails with a dedicated error for the probabilistic
+/// states `<state> <probability> <state> ...` of the probabilistic extension.
+fn read_state(text: &str, span: Span) -> Result<StateIndex, MercError> {
+    if text.split_whitespace().count() > 1 {
+        return Err(MercError::parse(
+            IOError::ProbabilisticState(text.to_string()),
+            Some(span),
+        ));
+    }
+
+    Ok(StateIndex::new(
+        text.parse().map_err(|error| MercError::from(error).with_span(span))?,
+    ))
+}
+
 /// Write a labelled transition system in plain text in Aldebaran format to the
 /// given writer, see [read_aut].
 ///
 /// Note that the writer is buffered internally using a `BufWriter`.
 pub fn write_aut(writer: &mut impl Write, lts: &impl LTS) -> Result<(), MercError> {
+    write_aut_with_comments(writer, lts, &[])
+}
+
+/// Writes a labelled transition system in Aldebaran format like [write_aut],
+/// with the given comments directly after the header. Together with
+/// [read_aut_with_comments] this preserves the comments of a file, but not
+/// their positions between the transitions since the order of the
+/// transitions is not preserved either.
+pub fn write_aut_with_comments(writer: &mut impl Write, lts: &impl LTS, comments: &[String]) -> Result<(), MercError> {
     info!("Writing LTS in .aut format...");
 
     let mut writer = BufWriter::new(writer);
@@ -113,17 +224,14 @@ pub fn write_aut(writer: &mut impl Write, lts: &impl LTS) -> Result<(), MercErro
         lts.num_of_states()
     )?;
 
-    let num_of_transitions = lts.num_of_transitions();
-    let progress = TimeProgress::new(
-        move |written: usize| {
-            info!(
-                "Wrote {} transitions {}%...",
-                LargeFormatter(written),
-                written * 100 / num_of_transitions
-            )
-        },
-        1,
-    );
+    for comment in comments {
+        // A comment with multiple lines is written as multiple comments.
+        for line in comment.split('\n') {
+            writeln!(writer, "%{line}")?;
+        }
+    }
+
+    let progress = Progress::new("Writing LTS", "transitions", Some(lts.num_of_transitions()));
     let mut transitions_written = 0usize;
     for state_index in lts.iter_states() {
         for transition in lts.outgoing_transitions(state_index) {
@@ -140,6 +248,7 @@ pub fn write_aut(writer: &mut impl Write, lts: &impl LTS) -> Result<(), MercErro
         }
     }
 
+    progress.finish(transitions_written);
     info!("Finished writing LTS.");
     Ok(())
 }
@@ -164,9 +273,13 @@ fn read_transition(input: &str) -> Option<(&str, &str, &str)> {
     let from = input.get(start_paren + 1..start_comma)?.trim();
     let label = input.get(start_comma + 1..start_second_comma)?.trim();
     let to = input.get(start_second_comma + 1..end_paren)?.trim();
-    // Handle the special case where it has quotes.
-    if label.starts_with('"') && label.ends_with('"') {
-        return Some((from, &label[1..label.len() - 1], to));
+    // Handle the special case where it has quotes, which must be balanced.
+    let label = match label.strip_prefix('"') {
+        Some(quoted) => quoted.strip_suffix('"')?,
+        None => label,
+    };
+    if label.contains('"') {
+        return None;
     }
 
     Some((from, label, to))
@@ -199,6 +312,7 @@ mod tests {
 
     use super::*;
 
+    use merc_utilities::MercErrorKind;
     use merc_utilities::random_test;
     use test_log::test;
 
@@ -229,6 +343,58 @@ mod tests {
         ";
 
         debug_assert!(read_aut(wrong_transition.as_bytes(), vec![]).is_err());
+
+        let wrong_state = "des (0,2,3)\n(0,\"a\",1)\n(x,\"b\",2)\n";
+        let error = read_aut(wrong_state.as_bytes(), vec![])
+            .err()
+            .expect("The third line has an invalid state");
+        assert_eq!(
+            error.kind(),
+            MercErrorKind::Parse {
+                span: Some(Span::line(3))
+            }
+        );
+    }
+
+    #[test]
+    fn test_aut_comments() {
+        let file = "% Generated by hand\ndes (0,2,2)\n\n(0,\"a\",1)\n  % A self loop\n(1,\"b\",1)\n%\n";
+
+        let (lts, comments) = read_aut_with_comments(file.as_bytes(), vec![]).unwrap();
+        assert_eq!(lts.num_of_transitions(), 2);
+        assert_eq!(comments, vec![" Generated by hand", " A self loop", ""]);
+
+        let mut buffer: Vec<u8> = Vec::new();
+        write_aut_with_comments(&mut buffer, &lts, &comments).unwrap();
+
+        let (result_lts, result_comments) = read_aut_with_comments(&buffer[0..], vec![]).unwrap();
+        crate::check_equivalent(&lts, &result_lts);
+        assert_eq!(result_comments, comments);
+    }
+
+    #[test]
+    fn test_probabilistic_aut() {
+        let probabilistic_initial = "des (0 1/2 1,1,2)\n(0,\"a\",1)\n";
+        let error = read_aut(probabilistic_initial.as_bytes(), vec![])
+            .err()
+            .expect("The initial state is probabilistic");
+        assert_eq!(
+            error.kind(),
+            MercErrorKind::Parse {
+                span: Some(Span::line(1))
+            }
+        );
+
+        let probabilistic_transition = "des (0,2,3)\n(0,\"a\",1)\n\n(1,\"b\",1 1/3 2)\n";
+        let error = read_aut(probabilistic_transition.as_bytes(), vec![])
+            .err()
+            .expect("The second transition is probabilistic");
+        assert_eq!(
+            error.kind(),
+            MercErrorKind::Parse {
+                span: Some(Span::line(4))
+            }
+        );
     }
 
     #[test]
@@ -18,12 +18,16 @@ mod inner {
 
     /// This is a stub implementation used when BCG support is not compiled in.
     pub fn read_bcg(_path: &Path, _hidden_labels: Vec<String>) -> Result<LabelledTransitionSystem<String>, MercError> {
-        Err("BCG format support not compiled in, see the 'cadp' feature.".into())
+        Err(MercError::unsupported(
+            "BCG format support not compiled in, see the 'cadp' feature.",
+        ))
     }
 
     /// This is a stub implementation used when BCG support is not compiled in.
     pub fn write_bcg(_lts: &impl LTS, _path: &Path) -> Result<(), MercError> {
-        Err("BCG format support not compiled in, see the 'cadp' feature.".into())
+        Err(MercError::unsupported(
+            "BCG format support not compiled in, see the 'cadp' feature.",
+        ))
     }
 }
 
@@ -43,8 +47,7 @@ mod inner {
     use std::sync::Mutex;
     use std::sync::Once;
 
-    use merc_io::LargeFormatter;
-    use merc_io::TimeProgress;
+    use merc_io::Progress;
 
     use crate::LabelIndex;
     use crate::LtsBuilder;
@@ -69,7 +72,8 @@ mod inner {
     /// platform, and the `CADP` environment variable to be set.
     ///
     /// Note that the C library can only read files from disk; reading from
-    /// in-memory buffers is not supported.
+    /// in-memory buffers is not supported. Labels that are invisible in the
+    /// BCG file, or occur in `hidden_labels`, are mapped to the internal action.
     pub fn read_bcg(path: &Path, hidden_labels: Vec<String>) -> Result<LabelledTransitionSystem<String>, MercError> {
         initialize_bcg()?;
         info!("Reading LTS in BCG format...");
@@ -111,7 +115,7 @@ mod inner {
             let is_visible = unsafe { BCG_OT_LABEL_VISIBLE(bcg_object, i) };
 
             let label = unsafe { CStr::from_ptr(label).to_string_lossy().into_owned() };
-            if is_visible {
+            if is_visible && !hidden_labels.contains(&label) {
                 label_index.insert(i as usize, labels.len()); // Map to new index.
                 labels.push(label.clone());
             } else {
@@ -123,16 +127,7 @@ mod inner {
         let initial_state = unsafe { BCG_OT_INITIAL_STATE(bcg_object) };
 
         let num_of_transitions = unsafe { BCG_OT_NB_EDGES(bcg_object) };
-        let mut progress = TimeProgress::new(
-            move |transitions: usize| {
-                info!(
-                    "Read {} transitions ({}%)...",
-                    LargeFormatter(transitions),
-                    transitions * 100 / num_of_transitions as usize
-                );
-            },
-            1,
-        );
+        let progress = Progress::new("Reading LTS", "transitions", Some(num_of_transitions as usize));
 
         // Read the successors for every state.
         let num_of_states = unsafe { BCG_OT_NB_STATES(bcg_object) };
@@ -155,6 +150,7 @@ mod inner {
             BCG_OT_READ_BCG_END(&mut bcg_object);
         }
 
+        progress.finish(num_of_transitions.get());
         info!("Finished reading LTS.");
         Ok(lts)
     }
@@ -163,7 +159,9 @@ mod inner {
     ///
     /// # Details
     ///
-    /// We require the label to be convertible into a `String`.
+    /// We require the label to be convertible into a `String`. The BCG format
+    /// has no state attributes, so only the transitions and their labels are
+    /// stored.
     pub fn write_bcg<L: LTS>(lts: &L, path: &Path) -> Result<(), MercError>
     where
         String: From<L::Label>,
@@ -197,17 +195,7 @@ mod inner {
             );
         }
 
-        let num_of_transitions = lts.num_of_transitions();
-        let mut progress = TimeProgress::new(
-            move |transitions: usize| {
-                info!(
-                    "Wrote {} transitions ({}%)...",
-                    LargeFormatter(transitions),
-                    transitions * 100 / num_of_transitions
-                );
-            },
-            1,
-        );
+        let progress = Progress::new("Writing LTS", "transitions", Some(lts.num_of_transitions()));
 
         let labels = lts
             .labels()
@@ -236,6 +224,7 @@ mod inner {
             BCG_IO_WRITE_BCG_END();
         }
 
+        progress.finish(number_of_transitions);
         info!("Finished writing LTS.");
         Ok(())
     }
@@ -253,14 +242,16 @@ mod inner {
                 if Path::new(&cadp_path).exists() {
                     info!("Found CADP installation at: {}", cadp_path);
                 } else {
-                    return Err(format!("The CADP environment variable is set to '{}', but this path does not exist; the CADP toolset must be installed to read BCG files.", cadp_path).into());
+                    return Err(MercError::unsupported(format!(
+                        "The CADP environment variable is set to '{}', but this path does not exist; the CADP toolset must be installed to read BCG files.",
+                        cadp_path
+                    )));
                 }
             }
             Err(_) => {
-                return Err(
-                    "The CADP environment variable is not set; the CADP toolset must be installed to read BCG files."
-                        .into(),
-                );
+                return Err(MercError::unsupported(
+                    "The CADP environment variable is not set; the CADP toolset must be installed to read BCG files.",
+                ));
             }
         }
 
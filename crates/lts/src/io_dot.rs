@@ -0,0 +1,73 @@
+#![forbid(unsafe_code)]
+
+use std::io::BufWriter;
+use std::io::Write;
+
+use log::info;
+
+use merc_io::Progress;
+use merc_utilities::MercError;
+
+use crate::LTS;
+
+/// Write a labelled transition system in the Graphviz DOT format to the given
+/// writer, which is intended for visualising (small) LTSs.
+///
+/// Note that the writer is buffered internally using a `BufWriter`.
+pub fn write_dot(writer: &mut impl Write, lts: &impl LTS) -> Result<(), MercError> {
+    info!("Writing LTS in .dot format...");
+
+    let mut writer = BufWriter::new(writer);
+    writeln!(writer, "digraph lts {{")?;
+    writeln!(writer, "  rankdir=LR;")?;
+    writeln!(writer, "  node [shape=circle];")?;
+
+    for state_index in lts.iter_states() {
+        writeln!(writer, "  s{state_index} [label=\"{state_index}\"];")?;
+    }
+
+    let progress = Progress::new("Writing LTS", "transitions", Some(lts.num_of_transitions()));
+    let mut transitions_written = 0usize;
+    for state_index in lts.iter_states() {
+        for transition in lts.outgoing_transitions(state_index) {
+            let label = lts.labels()[transition.label.value()].to_string();
+            writeln!(
+                writer,
+                "  s{} -> s{} [label=\"{}\"];",
+                state_index,
+                transition.to,
+                label.replace('\\', "\\\\").replace('"', "\\\"")
+            )?;
+
+            progress.print(transitions_written);
+            transitions_written += 1;
+        }
+    }
+
+    writeln!(writer, "  init [shape=point, label=\"\"];")?;
+    writeln!(writer, "  init -> s{};", lts.initial_state_index())?;
+    writeln!(writer, "}}")?;
+
+    progress.finish(transitions_written);
+    info!("Finished writing LTS.");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::read_aut;
+
+    use super::*;
+
+    #[test]
+    fn test_writing_dot() {
+        let lts = read_aut("des (0, 1, 2)\n(0, \"a\", 1)\n".as_bytes(), vec![]).unwrap();
+
+        let mut output = Vec::new();
+        write_dot(&mut output, &lts).unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("init -> s0;"));
+        assert!(output.contains("s0 -> s1 [label=\"a\"];"));
+    }
+}
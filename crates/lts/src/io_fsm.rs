@@ -0,0 +1,95 @@
+#![forbid(unsafe_code)]
+
+use std::io::BufWriter;
+use std::io::Write;
+
+use log::info;
+
+use merc_io::Progress;
+use merc_utilities::MercError;
+
+use crate::LTS;
+use crate::StateIndex;
+use crate::TransitionLabel;
+
+/// Write a labelled transition system in the mCRL2 FSM format to the given
+/// writer.
+///
+/// # Details
+///
+/// The FSM format stores state vectors in its first two sections, but since
+/// our LTSs have no state parameters these sections contain one empty line per
+/// state. States are numbered from one and the first state is the initial
+/// state, so the initial state is swapped with the state at index zero. The
+/// internal action is written as `tau`.
+///
+/// Note that the writer is buffered internally using a `BufWriter`.
+pub fn write_fsm(writer: &mut impl Write, lts: &impl LTS) -> Result<(), MercError> {
+    info!("Writing LTS in .fsm format...");
+
+    let mut writer = BufWriter::new(writer);
+
+    // The parameter section is empty, followed by one (empty) state vector per state.
+    writeln!(writer, "---")?;
+    for _ in lts.iter_states() {
+        writeln!(writer)?;
+    }
+    writeln!(writer, "---")?;
+
+    let initial_state = lts.initial_state_index();
+    let renumber = |state: StateIndex| {
+        if state == initial_state {
+            1
+        } else if state.value() == 0 {
+            initial_state.value() + 1
+        } else {
+            state.value() + 1
+        }
+    };
+
+    let progress = Progress::new("Writing LTS", "transitions", Some(lts.num_of_transitions()));
+    let mut transitions_written = 0usize;
+    for state_index in lts.iter_states() {
+        for transition in lts.outgoing_transitions(state_index) {
+            let label = &lts.labels()[transition.label.value()];
+            if label.is_tau_label() {
+                writeln!(writer, "{} {} \"tau\"", renumber(state_index), renumber(transition.to))?;
+            } else {
+                writeln!(
+                    writer,
+                    "{} {} \"{}\"",
+                    renumber(state_index),
+                    renumber(transition.to),
+                    label
+                )?;
+            }
+
+            progress.print(transitions_written);
+            transitions_written += 1;
+        }
+    }
+
+    progress.finish(transitions_written);
+    info!("Finished writing LTS.");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::read_aut;
+
+    use super::*;
+
+    #[test]
+    fn test_writing_fsm() {
+        let lts = read_aut("des (1, 2, 2)\n(1, \"a\", 0)\n(0, \"i\", 1)\n".as_bytes(), vec![]).unwrap();
+
+        let mut output = Vec::new();
+        write_fsm(&mut output, &lts).unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "---\n\n\n---\n2 1 \"tau\"\n1 2 \"a\"\n"
+        );
+    }
+}
@@ -1,9 +1,8 @@
 #![forbid(unsafe_code)]
 
 use std::collections::HashMap;
-use std::io::BufReader;
+use std::io::BufRead;
 use std::io::BufWriter;
-use std::io::Read;
 use std::io::Write;
 
 use log::info;
@@ -15,11 +14,13 @@ use merc_aterm::ATermStreamable;
 use merc_aterm::ATermWrite;
 use merc_aterm::BinaryATermReader;
 use merc_aterm::BinaryATermWriter;
+use merc_aterm::DecompressingReader;
 use merc_aterm::Symbol;
+#[cfg(feature = "zstd")]
+use merc_aterm::compressed_writer;
 use merc_aterm::is_list_term;
 use merc_data::DataSpecification;
-use merc_io::LargeFormatter;
-use merc_io::TimeProgress;
+use merc_io::Progress;
 use merc_utilities::MercError;
 
 use crate::LTS;
@@ -29,16 +30,22 @@ use crate::MultiAction;
 use crate::StateIndex;
 
 /// Loads a labelled transition system from the binary 'lts' format of the mCRL2 toolset.
+///
+/// Input that is zstd compressed, e.g. `.lts.zst` files, is decompressed transparently.
+/// The reader is not buffered again, such that memory mapped input, see
+/// [`merc_unsafety::FileReader`], is read without copying.
 pub fn read_lts(
-    reader: impl Read,
+    reader: impl BufRead,
     hidden_labels: Vec<String>,
 ) -> Result<LabelledTransitionSystem<MultiAction>, MercError> {
     info!("Reading LTS in .lts format...");
 
-    let mut reader = BinaryATermReader::new(BufReader::new(reader))?;
+    let mut reader = BinaryATermReader::new(DecompressingReader::new(reader)?)?;
 
     if reader.read_aterm()? != Some(lts_marker()) {
-        return Err("Stream does not contain a labelled transition system (LTS).".into());
+        return Err(MercError::format(
+            "Stream does not contain a labelled transition system (LTS).",
+        ));
     }
 
     // Read the data specification, parameters, and actions.
@@ -53,21 +60,24 @@ pub fn read_lts(
     let mut initial_state: Option<StateIndex> = None;
     let mut builder = LtsBuilder::new(Vec::new(), hidden_labels);
 
-    let progress = TimeProgress::new(
-        |num_of_transitions| {
-            info!("Read {num_of_transitions} transitions...");
-        },
-        1,
-    );
+    let progress = Progress::new("Reading LTS", "transitions", None);
 
     loop {
         let term = reader.read_aterm()?;
         match term {
             Some(t) => {
                 if t == transition_marker() {
-                    let from: ATermInt = reader.read_aterm()?.ok_or("Missing from state")?.into();
-                    let label = reader.read_aterm()?.ok_or("Missing transition label")?;
-                    let to: ATermInt = reader.read_aterm()?.ok_or("Missing to state")?.into();
+                    let from: ATermInt = reader
+                        .read_aterm()?
+                        .ok_or_else(|| MercError::format("Missing from state"))?
+                        .into();
+                    let label = reader
+                        .read_aterm()?
+                        .ok_or_else(|| MercError::format("Missing transition label"))?;
+                    let to: ATermInt = reader
+                        .read_aterm()?
+                        .ok_or_else(|| MercError::format("Missing to state"))?
+                        .into();
 
                     if let Some(multi_action) = multi_actions.get(&label) {
                         // Multi-action already exists in the cache.
@@ -89,29 +99,42 @@ pub fn read_lts(
 
                     progress.print(builder.num_of_transitions());
                 } else if t == probabilistic_transition_mark() {
-                    return Err("Probabilistic transitions are not supported yet.".into());
+                    return Err(MercError::format("Probabilistic transitions are not supported yet."));
                 } else if is_list_term(&t) {
                     // State labels can be ignored for the reduction algorithm.
                 } else if t == initial_state_marker() {
-                    let length = ATermInt::from(reader.read_aterm()?.ok_or("Missing initial state length")?).value();
+                    let length = ATermInt::from(
+                        reader
+                            .read_aterm()?
+                            .ok_or_else(|| MercError::format("Missing initial state length"))?,
+                    )
+                    .value();
                     if length != 1 {
-                        return Err("Initial state length greater than 1 is not supported.".into());
+                        return Err(MercError::format(
+                            "Initial state length greater than 1 is not supported.",
+                        ));
                     }
 
                     initial_state = Some(StateIndex::new(
-                        ATermInt::from(reader.read_aterm()?.ok_or("Missing initial state index")?).value(),
+                        ATermInt::from(
+                            reader
+                                .read_aterm()?
+                                .ok_or_else(|| MercError::format("Missing initial state index"))?,
+                        )
+                        .value(),
                     ));
                     println!("Initial state: {:?}", initial_state);
                 } else {
-                    return Err(format!("Unexpected term in LTS stream: {}", t).into());
+                    return Err(MercError::format(format!("Unexpected term in LTS stream: {}", t)));
                 }
             }
             None => break, // The default constructed term indicates the end of the stream.
         }
     }
+    progress.finish(builder.num_of_transitions());
     info!("Finished reading LTS.");
 
-    Ok(builder.finish(initial_state.ok_or("Missing initial state")?))
+    Ok(builder.finish(initial_state.ok_or_else(|| MercError::format("Missing initial state"))?))
 }
 
 /// Write a labelled transition system in binary 'lts' format to the given
@@ -151,9 +174,31 @@ where
     L: LTS<Label = MultiAction>,
 {
     info!("Writing LTS in .lts format...");
+    write_lts_stream(BinaryATermWriter::new(BufWriter::new(writer))?, lts)
+}
+
+/// Write a labelled transition system in the zstd compressed binary 'lts'
+/// format to the given writer, see [`write_lts`] for details.
+#[cfg(feature = "zstd")]
+pub fn write_lts_compressed<L>(writer: &mut impl Write, lts: &L) -> Result<(), MercError>
+where
+    L: LTS<Label = MultiAction>,
+{
+    info!("Writing LTS in compressed .lts format...");
+    let mut encoder = compressed_writer(BufWriter::new(writer), merc_aterm::DEFAULT_COMPRESSION_LEVEL)?;
+    write_lts_stream(BinaryATermWriter::new(&mut encoder)?, lts)?;
 
-    let mut writer = BinaryATermWriter::new(BufWriter::new(writer))?;
+    // Finish the zstd frame explicitly, since errors are lost when the buffered writer is dropped.
+    encoder.finish()?.flush()?;
+    Ok(())
+}
 
+/// Writes the contents of the LTS to the given binary aterm stream.
+fn write_lts_stream<W, L>(mut writer: BinaryATermWriter<W>, lts: &L) -> Result<(), MercError>
+where
+    W: Write,
+    L: LTS<Label = MultiAction>,
+{
     writer.write_aterm(&lts_marker())?;
 
     // Write the data specification, parameters, and actions.
@@ -173,17 +218,7 @@ where
     writer.write_aterm(&ATermInt::new(1))?; // Length of initial state is 1.
     writer.write_aterm(&ATermInt::new(*lts.initial_state_index()))?;
 
-    let num_of_transitions = lts.num_of_transitions();
-    let progress = TimeProgress::new(
-        move |written: usize| {
-            info!(
-                "Wrote {} transitions ({}%)...",
-                LargeFormatter(written),
-                written * 100 / num_of_transitions
-            );
-        },
-        1,
-    );
+    let progress = Progress::new("Writing LTS", "transitions", Some(lts.num_of_transitions()));
 
     let mut written = 0;
     for state in lts.iter_states() {
@@ -198,6 +233,8 @@ where
         }
     }
 
+    ATermWrite::flush(&mut writer)?;
+    progress.finish(written);
     info!("Finished writing LTS.");
     Ok(())
 }
@@ -255,4 +292,20 @@ mod tests {
             crate::check_equivalent(&lts, &result_lts);
         })
     }
+
+    #[test]
+    #[cfg(feature = "zstd")]
+    #[cfg_attr(miri, ignore)]
+    fn test_random_compressed_lts_io() {
+        random_test(100, |rng| {
+            let lts = random_lts_monolithic::<MultiAction>(rng, 100, 3, 20);
+
+            let mut buffer: Vec<u8> = Vec::new();
+            write_lts_compressed(&mut buffer, &lts).unwrap();
+
+            let result_lts = read_lts(&buffer[0..], vec![]).unwrap();
+
+            crate::check_equivalent(&lts, &result_lts);
+        })
+    }
 }
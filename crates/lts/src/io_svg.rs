@@ -0,0 +1,63 @@
+#![forbid(unsafe_code)]
+
+use std::io::Write;
+
+use log::info;
+
+use merc_io::NodeShape;
+use merc_io::RenderGraph;
+use merc_utilities::MercError;
+
+use crate::LTS;
+
+/// Write a labelled transition system as an SVG image to the given writer,
+/// using the built-in layered layout of [RenderGraph] instead of Graphviz.
+///
+/// Returns an error when the LTS has more than `max_states` states, since the
+/// layout is only intended for visualising small LTSs.
+pub fn write_svg(writer: &mut impl Write, lts: &impl LTS, max_states: usize) -> Result<(), MercError> {
+    info!("Writing LTS in .svg format...");
+
+    let mut graph = RenderGraph::new();
+    for state_index in lts.iter_states() {
+        graph.add_node(state_index.to_string(), None, NodeShape::Circle);
+    }
+    graph.set_initial(lts.initial_state_index().value());
+
+    for state_index in lts.iter_states() {
+        for transition in lts.outgoing_transitions(state_index) {
+            graph.add_edge(
+                state_index.value(),
+                transition.to.value(),
+                Some(lts.labels()[transition.label].to_string()),
+            );
+        }
+    }
+
+    graph.write_svg(writer, max_states)?;
+    info!("Finished writing LTS.");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use merc_io::DEFAULT_SVG_MAX_NODES;
+
+    use crate::read_aut;
+
+    use super::*;
+
+    #[test]
+    fn test_writing_svg() {
+        let lts = read_aut("des (0, 2, 2)\n(0, \"a\", 1)\n(1, \"b\", 0)\n".as_bytes(), vec![]).unwrap();
+
+        let mut output = Vec::new();
+        write_svg(&mut output, &lts, DEFAULT_SVG_MAX_NODES).unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains(">a</text>"));
+        assert!(output.contains(">b</text>"));
+
+        assert!(write_svg(&mut Vec::new(), &lts, 1).is_err());
+    }
+}
@@ -0,0 +1,275 @@
+#![forbid(unsafe_code)]
+
+use merc_utilities::MercError;
+
+use crate::LTS;
+use crate::LabelIndex;
+use crate::TransitionLabel;
+
+/// The default maximum number of states for which [canonical_form] is computed.
+pub const DEFAULT_ISOMORPHISM_LIMIT: usize = 10_000;
+
+/// A canonical representation of an LTS, such that two LTSs are isomorphic iff
+/// their canonical forms are equal. See [canonical_form].
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CanonicalForm {
+    /// The number of states of the LTS.
+    num_of_states: usize,
+    /// The initial state in the canonical numbering.
+    initial_state: usize,
+    /// The transitions (from, label, to) in the canonical numbering, in sorted order.
+    transitions: Vec<(usize, String, usize)>,
+}
+
+impl CanonicalForm {
+    /// Returns the number of states.
+    pub fn num_of_states(&self) -> usize {
+        self.num_of_states
+    }
+
+    /// Returns the transitions (from, label, to) in the canonical numbering of the states.
+    pub fn transitions(&self) -> &[(usize, String, usize)] {
+        &self.transitions
+    }
+}
+
+/// Computes the canonical form of the given LTS, which fails when it has more
+/// than `max_states` states.
+///
+/// # Details
+///
+/// The states are first coloured by iterative refinement, where the colour of
+/// a state is determined by its previous colour and the labels and colours of
+/// its incoming and outgoing transitions. When states remain that have the
+/// same colour, each of them is in turn given a unique colour and the
+/// refinement is repeated, after which the lexicographically smallest
+/// numbering of the transitions is chosen. The labels are compared by their
+/// name, where all hidden labels are the same, so the result does not depend
+/// on the order of the states and labels.
+///
+/// Since this can take exponential time for LTSs with many symmetries, states
+/// with exactly the same transitions are only individualised once.
+pub fn canonical_form<L: LTS>(lts: &L, max_states: usize) -> Result<CanonicalForm, MercError> {
+    if lts.num_of_states() > max_states {
+        return Err(MercError::unsupported(format!(
+            "Cannot compute the canonical form of an LTS with {} states, which exceeds the limit of {max_states} states",
+            lts.num_of_states()
+        )));
+    }
+
+    // The name of every label, and its rank in the sorted names.
+    let names: Vec<String> = lts
+        .labels()
+        .iter()
+        .enumerate()
+        .map(|(index, label)| {
+            if lts.is_hidden_label(LabelIndex::new(index)) {
+                L::Label::tau_label().to_string()
+            } else {
+                label.to_string()
+            }
+        })
+        .collect();
+    let mut sorted_names = names.clone();
+    sorted_names.sort();
+    sorted_names.dedup();
+    let label_rank: Vec<usize> = names
+        .iter()
+        .map(|name| sorted_names.binary_search(name).expect("Every name is sorted"))
+        .collect();
+
+    let num_of_states = lts.num_of_states();
+    let mut graph = Graph {
+        outgoing: vec![Vec::new(); num_of_states],
+        incoming: vec![Vec::new(); num_of_states],
+        transitions: Vec::new(),
+    };
+
+    for state in lts.iter_states() {
+        for transition in lts.outgoing_transitions(state) {
+            let label = label_rank[transition.label];
+            graph.outgoing[*state].push((label, *transition.to));
+            graph.incoming[*transition.to].push((label, *state));
+            graph.transitions.push((*state, label, *transition.to));
+        }
+    }
+
+    for neighbours in graph.outgoing.iter_mut().chain(graph.incoming.iter_mut()) {
+        neighbours.sort_unstable();
+    }
+
+    // The initial state is distinguished from all other states.
+    let initial = *lts.initial_state_index();
+    let colours = (0..num_of_states).map(|state| usize::from(state != initial)).collect();
+
+    let mut best = None;
+    search(&graph, colours, &mut best);
+    let (colours, transitions) = best.expect("The search always reaches a discrete colouring");
+
+    Ok(CanonicalForm {
+        num_of_states,
+        initial_state: colours[initial],
+        transitions: transitions
+            .into_iter()
+            .map(|(from, label, to)| (from, sorted_names[label].clone(), to))
+            .collect(),
+    })
+}
+
+/// Returns true iff the given LTSs are isomorphic, see [canonical_form].
+pub fn is_isomorphic<L: LTS, R: LTS>(left: &L, right: &R, max_states: usize) -> Result<bool, MercError> {
+    if left.num_of_states() != right.num_of_states() || left.num_of_transitions() != right.num_of_transitions() {
+        return Ok(false);
+    }
+
+    Ok(canonical_form(left, max_states)? == canonical_form(right, max_states)?)
+}
+
+/// The transitions of an LTS, where the labels are given by their rank.
+struct Graph {
+    /// The sorted (label, to) pairs of every state.
+    outgoing: Vec<Vec<(usize, usize)>>,
+    /// The sorted (label, from) pairs of every state.
+    incoming: Vec<Vec<(usize, usize)>>,
+    /// All (from, label, to) transitions.
+    transitions: Vec<(usize, usize, usize)>,
+}
+
+/// A discrete colouring with the corresponding sorted transitions.
+type Leaf = (Vec<usize>, Vec<(usize, usize, usize)>);
+
+/// The colour of a state with the sorted (label, colour) pairs of its outgoing and incoming transitions.
+type Signature = (usize, Vec<(usize, usize)>, Vec<(usize, usize)>);
+
+/// Refines the given colouring, and individualises the states of the first
+/// colour that contains multiple states until the colouring is discrete. Keeps
+/// the leaf with the smallest transitions in `best`.
+fn search(graph: &Graph, mut colours: Vec<usize>, best: &mut Option<Leaf>) {
+    refine(graph, &mut colours);
+
+    let mut sizes = vec![0usize; colours.len()];
+    for colour in &colours {
+        sizes[*colour] += 1;
+    }
+
+    match sizes.iter().position(|size| *size > 1) {
+        None => {
+            let mut transitions: Vec<(usize, usize, usize)> = graph
+                .transitions
+                .iter()
+                .map(|(from, label, to)| (colours[*from], *label, colours[*to]))
+                .collect();
+            transitions.sort_unstable();
+
+            if best.as_ref().is_none_or(|(_, best)| transitions < *best) {
+                *best = Some((colours, transitions));
+            }
+        }
+        Some(cell) => {
+            let mut individualised: Vec<usize> = Vec::new();
+            for state in (0..colours.len()).filter(|state| colours[*state] == cell) {
+                // Swapping two states with the same transitions is an automorphism, so only one of them is tried.
+                if individualised.iter().any(|other| {
+                    graph.outgoing[*other] == graph.outgoing[state] && graph.incoming[*other] == graph.incoming[state]
+                }) {
+                    continue;
+                }
+                individualised.push(state);
+
+                let colours = colours
+                    .iter()
+                    .enumerate()
+                    .map(|(other, colour)| 2 * colour + usize::from(*colour == cell && other != state))
+                    .collect();
+                search(graph, colours, best);
+            }
+        }
+    }
+}
+
+/// Refines the colouring until it is stable, after which the colours are the
+/// ranks of the sorted signatures and therefore independent of the state numbering.
+fn refine(graph: &Graph, colours: &mut Vec<usize>) {
+    let count = |colours: &[usize]| {
+        let mut sorted = colours.to_vec();
+        sorted.sort_unstable();
+        sorted.dedup();
+        sorted.len()
+    };
+
+    let mut num_of_colours = count(colours.as_slice());
+    loop {
+        let signatures: Vec<Signature> = (0..colours.len())
+            .map(|state| {
+                let mut outgoing: Vec<(usize, usize)> = graph.outgoing[state]
+                    .iter()
+                    .map(|(label, to)| (*label, colours[*to]))
+                    .collect();
+                outgoing.sort_unstable();
+
+                let mut incoming: Vec<(usize, usize)> = graph.incoming[state]
+                    .iter()
+                    .map(|(label, from)| (*label, colours[*from]))
+                    .collect();
+                incoming.sort_unstable();
+
+                (colours[state], outgoing, incoming)
+            })
+            .collect();
+
+        let mut sorted: Vec<&Signature> = signatures.iter().collect();
+        sorted.sort_unstable();
+        sorted.dedup();
+
+        *colours = signatures
+            .iter()
+            .map(|signature| sorted.binary_search(&signature).expect("Every signature is sorted"))
+            .collect();
+
+        if sorted.len() == num_of_colours {
+            break;
+        }
+        num_of_colours = sorted.len();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::seq::SliceRandom;
+
+    use merc_utilities::random_test;
+
+    use crate::LabelledTransitionSystem;
+    use crate::StateIndex;
+    use crate::random_lts;
+    use crate::read_aut;
+
+    use super::*;
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_random_isomorphism() {
+        random_test(100, |rng| {
+            let lts = random_lts(rng, 10, 3, 3);
+
+            let mut permutation: Vec<usize> = (0..lts.num_of_states()).collect();
+            permutation.shuffle(rng);
+            let permuted = LabelledTransitionSystem::new_from_permutation(lts.clone(), |state| {
+                StateIndex::new(permutation[*state])
+            });
+
+            assert!(is_isomorphic(&lts, &permuted, DEFAULT_ISOMORPHISM_LIMIT).unwrap());
+        });
+    }
+
+    #[test]
+    fn test_not_isomorphic() {
+        // Both LTSs are strongly bisimilar, but not isomorphic.
+        let left = read_aut("des (0,2,2)\n(0,\"a\",1)\n(1,\"a\",1)\n".as_bytes(), Vec::new()).unwrap();
+        let right = read_aut("des (0,2,2)\n(0,\"a\",0)\n(1,\"a\",1)\n".as_bytes(), Vec::new()).unwrap();
+
+        assert!(!is_isomorphic(&left, &right, DEFAULT_ISOMORPHISM_LIMIT).unwrap());
+        assert!(is_isomorphic(&left, &left, DEFAULT_ISOMORPHISM_LIMIT).unwrap());
+        assert!(canonical_form(&left, 1).is_err(), "The LTS exceeds the limit");
+    }
+}
@@ -258,33 +258,27 @@ impl<Label: TransitionLabel> LabelledTransitionSystem<Label> {
         )
     }
 
-    /// Creates a labelled transition system from another one, given the permutation of state indices
-    ///
+    /// Creates a labelled transition system from another one, given the
+    /// permutation of state indices, such that every state `s` of the given
+    /// LTS becomes state `permutation(s)`.
     pub fn new_from_permutation<P>(lts: Self, permutation: P) -> Self
     where
         P: Fn(StateIndex) -> StateIndex + Copy,
     {
-        let mut states = bytevec![0; lts.num_of_states()];
-
+        let mut inverse = vec![StateIndex::new(0); lts.num_of_states()];
         for state_index in lts.iter_states() {
-            // Keep the transitions the same move the state indices around
-            let new_state_index = permutation(state_index);
-            let state = lts.states.index(*state_index);
-            states.update(*new_state_index, |entry| {
-                *entry = state;
-            });
+            inverse[permutation(state_index).value()] = state_index;
         }
 
-        // Add the sentinel state.
-        states.push(lts.num_of_transitions());
-
-        LabelledTransitionSystem {
-            initial_state: permutation(lts.initial_state),
-            labels: lts.labels,
-            states,
-            transition_labels: lts.transition_labels,
-            transition_to: lts.transition_to,
-        }
+        Self::with_successors(
+            permutation(lts.initial_state),
+            lts.num_of_states(),
+            lts.labels.clone(),
+            |state_index| {
+                lts.outgoing_transitions(inverse[state_index.value()])
+                    .map(move |transition| (transition.label, permutation(transition.to)))
+            },
+        )
     }
 
     /// Consumes the LTS and relabels its transition labels according to the given mapping.
@@ -362,6 +356,7 @@ impl<L: TransitionLabel> LTS for LabelledTransitionSystem<L> {
 
 /// Metrics for a labelled transition system.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LtsMetrics {
     /// The number of states in the LTS.
     pub num_of_states: usize,
@@ -4,24 +4,44 @@ mod incoming_transitions;
 mod io;
 mod io_aut;
 mod io_bcg;
+mod io_dot;
+mod io_fsm;
 mod io_lts;
+mod io_svg;
+mod isomorphism;
 mod labelled_transition_system;
 mod lts;
 mod lts_builder;
 mod lts_builder_fast;
+#[cfg(feature = "serde")]
+mod lts_serde;
+mod lts_variant;
+mod lts_with_indices;
 mod multi_action;
 mod product_lts;
 mod random_lts;
+mod shared_labels;
+mod statistics;
+mod transform;
 
 pub use incoming_transitions::*;
 pub use io::*;
 pub use io_aut::*;
 pub use io_bcg::*;
+pub use io_dot::*;
+pub use io_fsm::*;
 pub use io_lts::*;
+pub use io_svg::*;
+pub use isomorphism::*;
 pub use labelled_transition_system::*;
 pub use lts::*;
 pub use lts_builder::*;
 pub use lts_builder_fast::*;
+pub use lts_variant::*;
+pub use lts_with_indices::*;
 pub use multi_action::*;
 pub use product_lts::*;
 pub use random_lts::*;
+pub use shared_labels::*;
+pub use statistics::*;
+pub use transform::*;
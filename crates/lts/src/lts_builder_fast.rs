@@ -69,7 +69,7 @@ impl<L: TransitionLabel> LtsBuilderFast<L> {
     pub fn add_transition<Q>(&mut self, from: StateIndex, label: &Q, to: StateIndex)
     where
         L: Borrow<Q>,
-        Q: ToOwned<Owned = L> + Eq + Hash,
+        Q: ?Sized + ToOwned<Owned = L> + Eq + Hash,
     {
         let label_index = if let Some(&index) = self.labels_index.get(label) {
             index
@@ -105,6 +105,11 @@ impl<L: TransitionLabel> LtsBuilderFast<L> {
         self.transitions.len()
     }
 
+    /// Returns the labels added to the builder, indexed by their label index.
+    pub fn labels(&self) -> &[L] {
+        &self.labels
+    }
+
     /// Returns the number of states that the builder currently found.
     pub fn num_of_states(&self) -> usize {
         self.num_of_states
@@ -0,0 +1,104 @@
+#![forbid(unsafe_code)]
+
+//! Serde support for [LabelledTransitionSystem], enabled by the `serde` feature.
+//!
+//! An LTS is (de)serialized as its initial state, number of states, labels and
+//! a list of `(from, label, to)` transitions, since its internal compressed
+//! representation is not meaningful outside of this crate.
+
+use serde::Deserialize;
+use serde::Deserializer;
+use serde::Serialize;
+use serde::Serializer;
+use serde::de;
+
+use crate::LTS;
+use crate::LabelIndex;
+use crate::LabelledTransitionSystem;
+use crate::StateIndex;
+use crate::TransitionLabel;
+
+/// The serialized form of a [LabelledTransitionSystem].
+#[derive(Serialize, Deserialize)]
+struct SerializedLts<Label> {
+    initial_state: usize,
+    num_of_states: usize,
+    labels: Vec<Label>,
+    transitions: Vec<(usize, usize, usize)>,
+}
+
+impl<Label: TransitionLabel + Serialize> Serialize for LabelledTransitionSystem<Label> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let transitions = self
+            .iter_states()
+            .flat_map(|from| {
+                self.outgoing_transitions(from)
+                    .map(move |transition| (from.value(), transition.label.value(), transition.to.value()))
+            })
+            .collect();
+
+        SerializedLts {
+            initial_state: self.initial_state_index().value(),
+            num_of_states: self.num_of_states(),
+            labels: self.labels().to_vec(),
+            transitions,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de, Label: TransitionLabel + Deserialize<'de>> Deserialize<'de> for LabelledTransitionSystem<Label> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let lts = SerializedLts::<Label>::deserialize(deserializer)?;
+
+        if lts.initial_state >= lts.num_of_states.max(1) {
+            return Err(de::Error::custom(format!(
+                "Initial state {} out of bounds {}",
+                lts.initial_state, lts.num_of_states
+            )));
+        }
+
+        if let Some((from, label, to)) = lts
+            .transitions
+            .iter()
+            .find(|(from, label, to)| *from.max(to) >= lts.num_of_states || *label >= lts.labels.len())
+        {
+            return Err(de::Error::custom(format!(
+                "Transition ({from}, {label}, {to}) refers to a state or label that does not exist"
+            )));
+        }
+
+        Ok(LabelledTransitionSystem::new(
+            StateIndex::new(lts.initial_state),
+            Some(lts.num_of_states),
+            || {
+                lts.transitions
+                    .iter()
+                    .map(|(from, label, to)| (StateIndex::new(*from), LabelIndex::new(*label), StateIndex::new(*to)))
+            },
+            lts.labels,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use merc_utilities::random_test;
+
+    use crate::check_equivalent;
+    use crate::random_lts;
+
+    use super::*;
+
+    #[test]
+    fn test_random_lts_serde() {
+        random_test(100, |rng| {
+            let lts = random_lts(rng, 10, 3, 3);
+
+            let json = serde_json::to_string(&lts).unwrap();
+            let result: LabelledTransitionSystem<String> = serde_json::from_str(&json).unwrap();
+
+            check_equivalent(&lts, &result);
+        });
+    }
+}
@@ -0,0 +1,162 @@
+#![forbid(unsafe_code)]
+
+use itertools::Either;
+
+use crate::GenericLts;
+use crate::LTS;
+use crate::LabelIndex;
+use crate::LabelledTransitionSystem;
+use crate::MultiAction;
+use crate::StateIndex;
+use crate::Transition;
+use crate::TransitionLabel;
+
+/// An LTS that can be any of the supported LTS representations, which
+/// implements the [LTS] trait by dispatching to the underlying representation.
+///
+/// # Details
+///
+/// All variants expose their labels as strings, where the internal action is
+/// `i`, such that generic algorithms can be applied to any variant directly.
+/// For multi-actions the string representation is canonical, since the actions
+/// are stored in a sorted set, so equal multi-actions yield equal strings.
+pub enum LtsVariant {
+    /// An explicit LTS with string labels, as read from `.aut` and `.bcg` files.
+    Explicit(LabelledTransitionSystem<String>),
+    /// An explicit LTS with multi-action labels, as read from `.lts` files.
+    MultiAction {
+        lts: LabelledTransitionSystem<MultiAction>,
+        /// The string representation of every multi-action label.
+        labels: Vec<String>,
+    },
+}
+
+/// Applies the given expression to the underlying LTS of every variant.
+macro_rules! dispatch {
+    ($variant:expr, $lts:ident => $body:expr) => {
+        match $variant {
+            LtsVariant::Explicit($lts) => $body,
+            LtsVariant::MultiAction { lts: $lts, .. } => $body,
+        }
+    };
+}
+
+impl LtsVariant {
+    /// Creates a variant from an LTS with multi-action labels.
+    pub fn from_multi_action_lts(lts: LabelledTransitionSystem<MultiAction>) -> Self {
+        let labels = lts
+            .labels()
+            .iter()
+            .map(|label| {
+                if label.is_tau_label() {
+                    String::tau_label()
+                } else {
+                    label.to_string()
+                }
+            })
+            .collect();
+
+        LtsVariant::MultiAction { lts, labels }
+    }
+
+    /// Converts the LTS into an explicit LTS with string labels.
+    pub fn into_string_lts(self) -> LabelledTransitionSystem<String> {
+        match self {
+            LtsVariant::Explicit(lts) => lts,
+            LtsVariant::MultiAction { lts, .. } => GenericLts::Lts(lts).into_string_lts(),
+        }
+    }
+}
+
+impl From<GenericLts> for LtsVariant {
+    fn from(lts: GenericLts) -> Self {
+        match lts {
+            GenericLts::Aut(lts) | GenericLts::Bcg(lts) => LtsVariant::Explicit(lts),
+            GenericLts::Lts(lts) => LtsVariant::from_multi_action_lts(lts),
+        }
+    }
+}
+
+impl LTS for LtsVariant {
+    type Label = String;
+
+    fn initial_state_index(&self) -> StateIndex {
+        dispatch!(self, lts => lts.initial_state_index())
+    }
+
+    fn outgoing_transitions(&self, state_index: StateIndex) -> impl Iterator<Item = Transition> + '_ {
+        match self {
+            LtsVariant::Explicit(lts) => Either::Left(lts.outgoing_transitions(state_index)),
+            LtsVariant::MultiAction { lts, .. } => Either::Right(lts.outgoing_transitions(state_index)),
+        }
+    }
+
+    fn iter_states(&self) -> impl Iterator<Item = StateIndex> + '_ {
+        match self {
+            LtsVariant::Explicit(lts) => Either::Left(lts.iter_states()),
+            LtsVariant::MultiAction { lts, .. } => Either::Right(lts.iter_states()),
+        }
+    }
+
+    fn num_of_states(&self) -> usize {
+        dispatch!(self, lts => lts.num_of_states())
+    }
+
+    fn num_of_labels(&self) -> usize {
+        dispatch!(self, lts => lts.num_of_labels())
+    }
+
+    fn num_of_transitions(&self) -> usize {
+        dispatch!(self, lts => lts.num_of_transitions())
+    }
+
+    fn labels(&self) -> &[String] {
+        match self {
+            LtsVariant::Explicit(lts) => lts.labels(),
+            LtsVariant::MultiAction { labels, .. } => labels,
+        }
+    }
+
+    fn is_hidden_label(&self, label_index: LabelIndex) -> bool {
+        dispatch!(self, lts => lts.is_hidden_label(label_index))
+    }
+
+    fn merge_disjoint<L: LTS<Label = String>>(self, other: &L) -> (LabelledTransitionSystem<String>, StateIndex) {
+        self.into_string_lts().merge_disjoint(other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use merc_utilities::random_test;
+
+    use crate::random_lts;
+
+    use super::*;
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_random_lts_variant() {
+        random_test(100, |rng| {
+            let lts = random_lts(rng, 10, 3, 3);
+            let multi_action_lts = GenericLts::Aut(lts.clone()).into_multi_action_lts().unwrap();
+
+            let variant = LtsVariant::from(GenericLts::Lts(multi_action_lts));
+            assert_eq!(variant.num_of_states(), lts.num_of_states());
+            assert_eq!(variant.num_of_transitions(), lts.num_of_transitions());
+
+            // The labels of every transition are preserved.
+            for state_index in lts.iter_states() {
+                let expected: Vec<_> = lts
+                    .outgoing_transitions(state_index)
+                    .map(|transition| (&lts.labels()[transition.label.value()], transition.to))
+                    .collect();
+                let result: Vec<_> = variant
+                    .outgoing_transitions(state_index)
+                    .map(|transition| (&variant.labels()[transition.label.value()], transition.to))
+                    .collect();
+                assert_eq!(expected, result);
+            }
+        });
+    }
+}
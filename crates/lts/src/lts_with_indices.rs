@@ -0,0 +1,110 @@
+#![forbid(unsafe_code)]
+
+use std::sync::OnceLock;
+
+use crate::IncomingTransitions;
+use crate::IncomingTransitionsMetrics;
+use crate::LTS;
+use crate::LabelIndex;
+use crate::LabelledTransitionSystem;
+use crate::StateIndex;
+use crate::Transition;
+
+/// A labelled transition system together with indices that are constructed
+/// lazily on first use, and reused afterwards.
+///
+/// # Details
+///
+/// Several algorithms, such as signature refinement and weak bisimulation,
+/// require the incoming transitions of every state. Constructing these is
+/// linear in the number of transitions, so when multiple passes are performed
+/// over the same LTS it is beneficial to construct them only once.
+pub struct LtsWithIndices<L: LTS> {
+    lts: L,
+    incoming: OnceLock<IncomingTransitions>,
+}
+
+impl<L: LTS> LtsWithIndices<L> {
+    /// Creates a new wrapper around the given LTS, without constructing any indices.
+    pub fn new(lts: L) -> Self {
+        Self {
+            lts,
+            incoming: OnceLock::new(),
+        }
+    }
+
+    /// Returns the underlying LTS.
+    pub fn lts(&self) -> &L {
+        &self.lts
+    }
+
+    /// Consumes the wrapper and returns the underlying LTS.
+    pub fn into_inner(self) -> L {
+        self.lts
+    }
+
+    /// Returns the incoming transitions of the LTS, which are constructed on first use.
+    pub fn incoming_transitions(&self) -> &IncomingTransitions {
+        self.incoming.get_or_init(|| IncomingTransitions::new(&self.lts))
+    }
+
+    /// Returns the memory metrics of the indices that have been constructed so far.
+    pub fn metrics(&self) -> Option<IncomingTransitionsMetrics> {
+        self.incoming.get().map(|incoming| incoming.metrics())
+    }
+}
+
+impl<L: LTS> LTS for LtsWithIndices<L> {
+    type Label = L::Label;
+
+    fn merge_disjoint<T: LTS<Label = Self::Label>>(
+        self,
+        other: &T,
+    ) -> (LabelledTransitionSystem<L::Label>, StateIndex) {
+        self.lts.merge_disjoint(other)
+    }
+
+    delegate::delegate! {
+        to self.lts {
+            fn initial_state_index(&self) -> StateIndex;
+            fn num_of_states(&self) -> usize;
+            fn num_of_labels(&self) -> usize;
+            fn num_of_transitions(&self) -> usize;
+            fn is_hidden_label(&self, label_index: LabelIndex) -> bool;
+            fn labels(&self) -> &[L::Label];
+            fn outgoing_transitions(&self, state_index: StateIndex) -> impl Iterator<Item = Transition>;
+            fn iter_states(&self) -> impl Iterator<Item = StateIndex> + '_;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use merc_utilities::random_test;
+
+    use crate::random_lts;
+
+    use super::*;
+
+    #[test]
+    fn test_random_lts_with_indices() {
+        random_test(100, |rng| {
+            let lts = LtsWithIndices::new(random_lts(rng, 10, 3, 3));
+            assert!(lts.metrics().is_none());
+
+            // The index is only constructed once, and afterwards reused.
+            let incoming = lts.incoming_transitions() as *const IncomingTransitions;
+            assert!(std::ptr::eq(incoming, lts.incoming_transitions()));
+            assert!(lts.metrics().is_some());
+
+            let expected = IncomingTransitions::new(lts.lts());
+            for state_index in lts.iter_states() {
+                assert!(
+                    lts.incoming_transitions()
+                        .incoming_transitions(state_index)
+                        .eq(expected.incoming_transitions(state_index))
+                );
+            }
+        });
+    }
+}
@@ -31,50 +31,89 @@ use merc_utilities::MercError;
 
 use crate::TransitionLabel;
 
-/// Represents a multi-action, i.e., a set of action labels
+/// Represents a multi-action, i.e., a set of action labels, that optionally
+/// occurs at a specific time.
 #[derive(Clone, PartialOrd, Ord, PartialEq, Eq, Hash)]
 pub struct MultiAction {
     actions: VecSet<Action>,
+
+    /// The time tag of the multi-action, as the textual representation of a data expression.
+    time: Option<String>,
 }
 
 impl MultiAction {
     /// Parses a multi-action from a string representation, typically found in the Aldebaran format.
+    ///
+    /// # Details
+    ///
+    /// The multi-action consists of actions separated by `|`, where every action
+    /// can have data arguments, and is optionally followed by a time tag, for
+    /// example `a(1, f(2, 3))|b@3`. The arguments and time are stored as text,
+    /// so separators that occur within brackets, or as part of the `||`
+    /// operator, are not interpreted. The label `tau` represents the internal
+    /// action, which can also have a time tag.
     pub fn from_string(input: &str) -> Result<Self, MercError> {
-        let mut actions = VecSet::new();
+        let (body, time) = match split_top_level(input, '@')?.as_slice() {
+            [body] => (*body, None),
+            [body, time] => {
+                let time = time.trim();
+                if time.is_empty() {
+                    return Err(MercError::parse(
+                        format!("Empty time tag in multi-action {input}."),
+                        None,
+                    ));
+                }
 
-        for part in input.split('|') {
-            let part = part.trim();
-            if part.is_empty() {
-                return Err("Empty action label in multi-action.".into());
+                (*body, Some(time.to_string()))
             }
+            _ => {
+                return Err(MercError::parse(
+                    format!("Multiple time tags in multi-action {input}."),
+                    None,
+                ));
+            }
+        };
 
-            if let Some(open_paren_index) = part.find('(') {
-                if !part.ends_with(')') {
-                    return Err(format!("Malformed action with arguments: {}", part).into());
-                }
+        let mut actions = VecSet::new();
+        if body.trim() != "tau" {
+            for part in split_top_level(body, '|')? {
+                actions.insert(parse_action(part.trim())?);
+            }
+        }
 
-                let undefined_sort = SortExpression::unknown_sort();
+        Ok(MultiAction { actions, time })
+    }
 
-                let label = &part[..open_paren_index].trim();
-                let args_str = &part[open_paren_index + 1..part.len() - 1];
-                let arguments: Vec<(String, String)> = args_str
-                    .split(',')
-                    .map(|s| (s.trim().to_string(), undefined_sort.name().to_string()))
-                    .collect();
-                actions.insert(Action {
-                    label: label.to_string(),
-                    arguments,
-                });
-            } else {
-                let label = part.trim();
-                actions.insert(Action {
-                    label: label.to_string(),
-                    arguments: Vec::new(),
-                });
-            }
+    /// Returns the actions of the multi-action, which is empty for the internal action.
+    pub fn actions(&self) -> impl Iterator<Item = &Action> {
+        self.actions.iter()
+    }
+
+    /// Returns the time tag of the multi-action, if it is timed.
+    pub fn time(&self) -> Option<&str> {
+        self.time.as_deref()
+    }
+
+    /// Returns the multi-action where the arguments and the time are in a
+    /// canonical textual form, see [normalize_expression], such that
+    /// multi-actions that only differ in formatting become equal.
+    pub fn normalize(&self) -> MultiAction {
+        let mut actions = VecSet::new();
+        for action in self.actions.iter() {
+            actions.insert(Action {
+                label: action.label.clone(),
+                arguments: action
+                    .arguments
+                    .iter()
+                    .map(|(argument, sort)| (normalize_expression(argument), sort.clone()))
+                    .collect(),
+            });
         }
 
-        Ok(MultiAction { actions })
+        MultiAction {
+            actions,
+            time: self.time.as_deref().map(normalize_expression),
+        }
     }
 
     /// Converts the MultiAction into its mCRL2 ATerm representation.
@@ -100,7 +139,10 @@ impl MultiAction {
             .collect::<Result<Vec<_>, _>>()?;
 
         let actions_list = ATermList::<MCRL2Action>::from_double_iter(action_terms.into_iter());
-        let time_term: DataExpression = DataVariable::new("@undefined_real").into();
+        let time_term: DataExpression = match &self.time {
+            Some(time) => DataExpression::from_string(time)?,
+            None => DataVariable::new(UNDEFINED_TIME).into(),
+        };
         Ok(MCRL2TimedMultiAction::new(actions_list, time_term.copy()).into())
     }
 
@@ -109,14 +151,13 @@ impl MultiAction {
         if is_mcrl2_timed_multi_action_symbol(&term.get_head_symbol()) {
             let multi_action = MCRL2TimedMultiAction::from(term);
 
-            if is_data_variable(&multi_action.time()) {
-                let variable: DataVariableRef = multi_action.time().into();
-                if variable.name() != "@undefined_real" {
-                    return Err("Timed multi-actions are not supported.".into());
-                }
+            let time = if is_data_variable(&multi_action.time())
+                && DataVariableRef::from(multi_action.time()).name() == UNDEFINED_TIME
+            {
+                None
             } else {
-                return Err("Timed multi-actions are not supported.".into());
-            }
+                Some(multi_action.time().to_string())
+            };
 
             let mut actions = VecSet::new();
             for action in multi_action.actions() {
@@ -132,9 +173,12 @@ impl MultiAction {
                 });
             }
 
-            Ok(MultiAction { actions })
+            Ok(MultiAction { actions, time })
         } else {
-            Err(format!("Expected TimedMultAction symbol, got {}.", term).into())
+            Err(MercError::format(format!(
+                "Expected TimedMultAction symbol, got {}.",
+                term
+            )))
         }
     }
 }
@@ -143,7 +187,7 @@ impl MultiAction {
 mod inner {
     use merc_aterm::ATermStringRef;
     use merc_aterm::Symbol;
-    use merc_data::DataExpression;
+    use merc_data::DataExpressionList;
     use merc_data::DataExpressionRef;
     use merc_macros::merc_ignore;
 
@@ -183,7 +227,7 @@ mod inner {
     impl MCRL2Action {
         /// Creates a new Action with the given label and arguments.
         #[merc_ignore]
-        pub fn new(label: MCRL2ActionLabelRef<'_>, arguments: ATermList<DataExpression>) -> Self {
+        pub fn new(label: MCRL2ActionLabelRef<'_>, arguments: DataExpressionList) -> Self {
             let args: &[ATermRef<'_>] = &[label.into(), arguments.copy()];
             let term = ATerm::with_args(&Symbol::new("Action", 2), args);
             MCRL2Action { term: term.protect() }
@@ -195,7 +239,7 @@ mod inner {
         }
 
         /// Returns the data arguments of the action.
-        pub fn arguments(&self) -> ATermList<DataExpression> {
+        pub fn arguments(&self) -> DataExpressionList {
             self.term.arg(1).into()
         }
     }
@@ -208,7 +252,7 @@ mod inner {
     impl MCRL2ActionLabel {
         /// Constructs a new action label with the given name and arguments.
         #[merc_ignore]
-        pub fn new(name: ATermStringRef<'_>, args: ATermList<DataExpression>) -> Self {
+        pub fn new(name: ATermStringRef<'_>, args: DataExpressionList) -> Self {
             let args: &[ATermRef<'_>] = &[name.into(), args.copy()];
             let term = ATerm::with_args(&Symbol::new("ActId", 2), args);
             MCRL2ActionLabel { term: term.protect() }
@@ -253,6 +297,106 @@ fn is_mcrl2_action_label_symbol(symbol: &SymbolRef<'_>) -> bool {
     symbol.name() == "ActId" && symbol.arity() == 2
 }
 
+/// The variable that mCRL2 uses as the time of untimed multi-actions.
+const UNDEFINED_TIME: &str = "@undefined_real";
+
+/// Returns the textual representation of a data expression without
+/// whitespace, except for a single space between two identifiers or numbers,
+/// for example `f(x, y + 1)` becomes `f(x,y+1)`.
+pub fn normalize_expression(text: &str) -> String {
+    let is_identifier = |c: char| c.is_alphanumeric() || c == '_' || c == '\'';
+
+    let mut result = String::with_capacity(text.len());
+    let mut pending_space = false;
+    for c in text.trim().chars() {
+        if c.is_whitespace() {
+            pending_space = true;
+            continue;
+        }
+
+        if pending_space && result.chars().next_back().is_some_and(is_identifier) && is_identifier(c) {
+            result.push(' ');
+        }
+        pending_space = false;
+        result.push(c);
+    }
+
+    result
+}
+
+/// Parses a single action with optional arguments, for example `a` or `b(1, f(2))`.
+fn parse_action(input: &str) -> Result<Action, MercError> {
+    let (label, arguments) = match input.find('(') {
+        Some(open_paren_index) => {
+            if !input.ends_with(')') {
+                return Err(MercError::parse(
+                    format!("Malformed action with arguments: {input}"),
+                    None,
+                ));
+            }
+
+            let undefined_sort = SortExpression::unknown_sort();
+            let mut arguments = Vec::new();
+            for argument in split_top_level(&input[open_paren_index + 1..input.len() - 1], ',')? {
+                let argument = argument.trim();
+                if argument.is_empty() {
+                    return Err(MercError::parse(format!("Empty argument in action {input}."), None));
+                }
+
+                arguments.push((argument.to_string(), undefined_sort.name().to_string()));
+            }
+
+            (input[..open_paren_index].trim(), arguments)
+        }
+        None => (input, Vec::new()),
+    };
+
+    if label.is_empty() {
+        return Err(MercError::parse("Empty action label in multi-action.", None));
+    }
+
+    Ok(Action {
+        label: label.to_string(),
+        arguments,
+    })
+}
+
+/// Splits the input at every occurrence of the separator that is not enclosed
+/// in brackets. The separator `|` is not split at the `||` operator.
+fn split_top_level(input: &str, separator: char) -> Result<Vec<&str>, MercError> {
+    let mut result = Vec::new();
+    let mut depth: usize = 0;
+    let mut start = 0;
+
+    let mut chars = input.char_indices().peekable();
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => {
+                depth = depth
+                    .checked_sub(1)
+                    .ok_or_else(|| MercError::parse(format!("Unbalanced brackets in {input}."), None))?;
+            }
+            '|' if separator == '|' && chars.peek().is_some_and(|(_, next)| *next == '|') => {
+                // Skip the || operator, which can only occur in data expressions.
+                chars.next();
+            }
+            _ if c == separator && depth == 0 => {
+                result.push(&input[start..index]);
+                start = index + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+
+    if depth != 0 {
+        return Err(MercError::parse(format!("Unbalanced brackets in {input}."), None));
+    }
+
+    result.push(&input[start..]);
+    Ok(result)
+}
+
 /// Represents a single action label, with its (data) arguments
 #[derive(Clone, PartialOrd, Ord, PartialEq, Eq, Hash)]
 pub struct Action {
@@ -265,6 +409,16 @@ impl Action {
     pub fn new(label: String, arguments: Vec<(String, String)>) -> Self {
         Action { label, arguments }
     }
+
+    /// Returns the label of the action.
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    /// Returns the arguments of the action, as pairs of the textual representation of the argument and its sort.
+    pub fn arguments(&self) -> &[(String, String)] {
+        &self.arguments
+    }
 }
 
 impl TransitionLabel for MultiAction {
@@ -273,7 +427,10 @@ impl TransitionLabel for MultiAction {
     }
 
     fn tau_label() -> Self {
-        MultiAction { actions: VecSet::new() }
+        MultiAction {
+            actions: VecSet::new(),
+            time: None,
+        }
     }
 
     fn matches_label(&self, label: &str) -> bool {
@@ -290,6 +447,7 @@ impl TransitionLabel for MultiAction {
                     .to_string(),
                 Vec::new(),
             )),
+            time: None,
         }
     }
 }
@@ -297,10 +455,21 @@ impl TransitionLabel for MultiAction {
 impl fmt::Display for MultiAction {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         if self.actions.is_empty() {
-            write!(f, "τ")
+            // A timed internal action is written such that it can be parsed again.
+            if self.time.is_some() {
+                write!(f, "tau")?;
+            } else {
+                write!(f, "τ")?;
+            }
         } else {
-            write!(f, "{}", self.actions.iter().format("|"))
+            write!(f, "{}", self.actions.iter().format("|"))?;
+        }
+
+        if let Some(time) = &self.time {
+            write!(f, "@{time}")?;
         }
+
+        Ok(())
     }
 }
 
@@ -327,6 +496,7 @@ mod tests {
     use merc_data::SortExpression;
 
     use crate::MultiAction;
+    use crate::normalize_expression;
 
     #[test]
     fn test_multi_action_parse_string() {
@@ -354,4 +524,80 @@ mod tests {
                 .any(|act| act.label == "c" && act.arguments.is_empty())
         );
     }
+
+    #[test]
+    fn test_multi_action_parse_timed() {
+        let action = MultiAction::from_string("a(1,2)|b@3").unwrap();
+
+        assert_eq!(action.time(), Some("3"));
+        assert_eq!(action.actions().count(), 2);
+        assert!(
+            action
+                .actions()
+                .any(|act| act.label() == "a" && act.arguments().len() == 2)
+        );
+        assert!(
+            action
+                .actions()
+                .any(|act| act.label() == "b" && act.arguments().is_empty())
+        );
+
+        let tau = MultiAction::from_string("tau@1/2").unwrap();
+        assert_eq!(tau.actions().count(), 0);
+        assert_eq!(tau.time(), Some("1/2"));
+    }
+
+    #[test]
+    fn test_multi_action_parse_nested_arguments() {
+        let action = MultiAction::from_string("a(f(1, 2), [3, 4]) | b(x || y, {c})").unwrap();
+
+        let arguments: Vec<Vec<&str>> = action
+            .actions()
+            .map(|act| act.arguments().iter().map(|(name, _sort)| name.as_str()).collect())
+            .collect();
+        assert_eq!(arguments, vec![vec!["f(1, 2)", "[3, 4]"], vec!["x || y", "{c}"]]);
+    }
+
+    #[test]
+    fn test_multi_action_display_round_trip() {
+        for input in ["a", "a(1, 2)|b@3", "a(f(1, 2))|b(x || y)", "tau@5"] {
+            let action = MultiAction::from_string(input).unwrap();
+            let result = MultiAction::from_string(&action.to_string()).unwrap();
+            assert_eq!(
+                action, result,
+                "Parsing the output of {input} does not yield the same multi-action"
+            );
+        }
+    }
+
+    #[test]
+    fn test_multi_action_normalize() {
+        let left = MultiAction::from_string("b(x ||  y) | a( f(1,2) , n + 1)@ t ").unwrap();
+        let right = MultiAction::from_string("a(f(1, 2), n+1)|b(x||y)@t").unwrap();
+
+        assert_ne!(left, right);
+        assert_eq!(left.normalize(), right.normalize());
+        assert_eq!(left.normalize().to_string(), "a(f(1,2), n+1)|b(x||y)@t");
+        assert_eq!(normalize_expression("exists x: Nat . x > 1"), "exists x:Nat.x>1");
+    }
+
+    #[test]
+    fn test_multi_action_parse_errors() {
+        for input in ["a(1", "a)", "a|", "|b", "a(1,)", "a@", "a@1@2", "(1)"] {
+            assert!(
+                MultiAction::from_string(input).is_err(),
+                "Expected {input} to be rejected"
+            );
+        }
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_multi_action_mcrl2_aterm_timed() {
+        let action = MultiAction::from_string("a(x, y)|b@t").unwrap();
+        let result = MultiAction::from_mcrl2_aterm(action.to_mcrl2_aterm().unwrap()).unwrap();
+
+        assert_eq!(result.time(), Some("t"));
+        assert_eq!(result.to_string(), action.to_string());
+    }
 }
@@ -0,0 +1,190 @@
+#![forbid(unsafe_code)]
+
+use std::collections::HashMap;
+
+use crate::LTS;
+use crate::LabelIndex;
+use crate::LabelledTransitionSystem;
+use crate::MultiAction;
+use crate::TransitionLabel;
+
+/// Returns the canonical representation of the given label, such that labels
+/// that represent the same multi-action, but are formatted differently, for
+/// example `b|a(1,2)` and `a(1, 2) | b`, become equal. Labels that cannot be
+/// parsed as a multi-action are only trimmed.
+pub fn canonical_label(label: &str) -> String {
+    match MultiAction::from_string(label) {
+        // The internal action is identified by the label itself, so keep it as is.
+        Ok(multi_action) if multi_action.is_tau_label() && multi_action.time().is_none() => label.trim().to_string(),
+        Ok(multi_action) => multi_action.normalize().to_string(),
+        Err(_) => label.trim().to_string(),
+    }
+}
+
+/// A shared index for the labels of two LTSs, where labels with the same
+/// [canonical_label] get the same index.
+///
+/// # Details
+///
+/// The hidden label, at index zero, of both LTSs is mapped to the internal
+/// action at index zero, such that the hidden labels remain hidden.
+pub struct SharedLabels {
+    labels: Vec<String>,
+
+    /// The shared index of every label of the left LTS.
+    left: Vec<LabelIndex>,
+    /// The shared index of every label of the right LTS.
+    right: Vec<LabelIndex>,
+}
+
+impl SharedLabels {
+    /// Creates the shared index for the given labels of the left and right LTSs.
+    pub fn new(left: &[String], right: &[String]) -> Self {
+        let mut labels = vec![String::tau_label()];
+        let mut indices: HashMap<String, LabelIndex> = HashMap::new();
+        indices.insert(String::tau_label(), LabelIndex::new(0));
+
+        let mut index_of = |position: usize, label: &String| {
+            if position == 0 {
+                return LabelIndex::new(0);
+            }
+
+            let label = canonical_label(label);
+            *indices.entry(label.clone()).or_insert_with(|| {
+                labels.push(label);
+                LabelIndex::new(labels.len() - 1)
+            })
+        };
+
+        let left = left.iter().enumerate().map(|(i, label)| index_of(i, label)).collect();
+        let right = right.iter().enumerate().map(|(i, label)| index_of(i, label)).collect();
+
+        SharedLabels { labels, left, right }
+    }
+
+    /// Returns the shared labels, where the label at index zero is the internal action.
+    pub fn labels(&self) -> &[String] {
+        &self.labels
+    }
+
+    /// Returns the shared index of the given label index of the left LTS.
+    pub fn left(&self, label: LabelIndex) -> LabelIndex {
+        self.left[label.value()]
+    }
+
+    /// Returns the shared index of the given label index of the right LTS.
+    pub fn right(&self, label: LabelIndex) -> LabelIndex {
+        self.right[label.value()]
+    }
+}
+
+/// Returns both LTSs where the labels have been replaced by their
+/// [canonical_label] and are indexed by the same [SharedLabels], such that
+/// equal labels also have equal indices in both LTSs.
+///
+/// This is used before comparing two LTSs, since otherwise differences in
+/// formatting between the labels of both inputs result in spurious
+/// inequivalences.
+pub fn canonicalize_lts_pair<L: LTS<Label = String>>(
+    left: &L,
+    right: &L,
+) -> (LabelledTransitionSystem<String>, LabelledTransitionSystem<String>) {
+    let shared = SharedLabels::new(left.labels(), right.labels());
+
+    (
+        relabel_shared(left, shared.labels(), |label| shared.left(label)),
+        relabel_shared(right, shared.labels(), |label| shared.right(label)),
+    )
+}
+
+/// Returns a copy of the given LTS with the given labels, where the label
+/// indices of the transitions are mapped by the given function.
+fn relabel_shared<L: LTS<Label = String>>(
+    lts: &L,
+    labels: &[String],
+    mapping: impl Fn(LabelIndex) -> LabelIndex,
+) -> LabelledTransitionSystem<String> {
+    let mapping = &mapping;
+    LabelledTransitionSystem::new(
+        lts.initial_state_index(),
+        Some(lts.num_of_states()),
+        || {
+            lts.iter_states().flat_map(move |state_index| {
+                lts.outgoing_transitions(state_index)
+                    .map(move |transition| (state_index, mapping(transition.label), transition.to))
+            })
+        },
+        labels.to_vec(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use merc_utilities::random_test;
+
+    use crate::LtsBuilder;
+    use crate::StateIndex;
+    use crate::random_lts;
+
+    use super::*;
+
+    #[test]
+    fn test_canonical_label() {
+        assert_eq!(canonical_label("b | a(1,2)"), canonical_label("a(1, 2)|b"));
+        assert_eq!(canonical_label("a(x || y)@ 3"), "a(x||y)@3");
+        assert_eq!(canonical_label("i"), "i");
+        assert_eq!(canonical_label(" a(1 "), "a(1");
+    }
+
+    #[test]
+    fn test_canonicalize_lts_pair() {
+        let mut left = LtsBuilder::new(Vec::new(), Vec::new());
+        left.add_transition(StateIndex::new(0), "a(1,2)", StateIndex::new(1));
+        left.add_transition(StateIndex::new(1), "b | c", StateIndex::new(0));
+        let left = left.finish(StateIndex::new(0));
+
+        let mut right = LtsBuilder::new(Vec::new(), Vec::new());
+        right.add_transition(StateIndex::new(0), "c|b", StateIndex::new(1));
+        right.add_transition(StateIndex::new(1), "a(1, 2)", StateIndex::new(0));
+        right.add_transition(StateIndex::new(1), "a( 1 ,2 )", StateIndex::new(1));
+        let right = right.finish(StateIndex::new(0));
+
+        let (left, right) = canonicalize_lts_pair(&left, &right);
+        assert_eq!(left.labels(), right.labels());
+        assert_eq!(left.labels().len(), 3, "Expected the labels i, a(1,2) and b|c");
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_random_canonicalize_lts_pair() {
+        random_test(100, |rng| {
+            let left = random_lts(rng, 10, 3, 3);
+            let right = random_lts(rng, 10, 3, 3);
+
+            let (left_result, right_result) = canonicalize_lts_pair(&left, &right);
+            assert_eq!(left_result.labels(), right_result.labels());
+
+            // The transitions, and their labels, are preserved.
+            for (lts, result) in [(&left, &left_result), (&right, &right_result)] {
+                assert_eq!(lts.num_of_states(), result.num_of_states());
+                assert_eq!(lts.num_of_transitions(), result.num_of_transitions());
+
+                for state_index in lts.iter_states() {
+                    let expected: Vec<_> = lts
+                        .outgoing_transitions(state_index)
+                        .map(|transition| (&lts.labels()[transition.label.value()], transition.to))
+                        .collect();
+                    let mut actual: Vec<_> = result
+                        .outgoing_transitions(state_index)
+                        .map(|transition| (&result.labels()[transition.label.value()], transition.to))
+                        .collect();
+                    actual.sort();
+
+                    let mut expected = expected;
+                    expected.sort();
+                    assert_eq!(expected, actual);
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,367 @@
+#![forbid(unsafe_code)]
+
+use std::collections::BTreeMap;
+
+use crate::LTS;
+use crate::LabelIndex;
+use crate::StateIndex;
+
+/// Counts how often every value occurs, for example the out-degrees of the
+/// states of an LTS.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Histogram {
+    counts: BTreeMap<usize, usize>,
+}
+
+impl Histogram {
+    /// Creates a histogram of the given values.
+    pub fn from_values(values: impl IntoIterator<Item = usize>) -> Self {
+        let mut counts = BTreeMap::new();
+        for value in values {
+            *counts.entry(value).or_default() += 1;
+        }
+
+        Self { counts }
+    }
+
+    /// Returns the (value, count) pairs ordered by value, omitting the values that do not occur.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.counts.iter().map(|(value, count)| (*value, *count))
+    }
+
+    /// Returns the total number of values.
+    pub fn num_of_values(&self) -> usize {
+        self.counts.values().sum()
+    }
+
+    /// Returns the smallest value, or `None` when the histogram is empty.
+    pub fn min(&self) -> Option<usize> {
+        self.counts.keys().next().cloned()
+    }
+
+    /// Returns the largest value, or `None` when the histogram is empty.
+    pub fn max(&self) -> Option<usize> {
+        self.counts.keys().next_back().cloned()
+    }
+
+    /// Returns the mean of the values, or zero when the histogram is empty.
+    pub fn mean(&self) -> f64 {
+        let num_of_values = self.num_of_values();
+        if num_of_values == 0 {
+            return 0.0;
+        }
+
+        let sum: usize = self.iter().map(|(value, count)| value * count).sum();
+        sum as f64 / num_of_values as f64
+    }
+}
+
+/// Returns the histogram of the number of outgoing transitions of every state.
+pub fn out_degree_histogram(lts: &impl LTS) -> Histogram {
+    Histogram::from_values(
+        lts.iter_states()
+            .map(|state_index| lts.outgoing_transitions(state_index).count()),
+    )
+}
+
+/// Returns the histogram of the number of incoming transitions of every state.
+pub fn in_degree_histogram(lts: &impl LTS) -> Histogram {
+    let mut in_degrees = vec![0; lts.num_of_states()];
+    for state_index in lts.iter_states() {
+        for transition in lts.outgoing_transitions(state_index) {
+            in_degrees[transition.to] += 1;
+        }
+    }
+
+    Histogram::from_values(in_degrees)
+}
+
+/// Returns the states without outgoing transitions.
+pub fn deadlock_states(lts: &impl LTS) -> Vec<StateIndex> {
+    lts.iter_states()
+        .filter(|state_index| lts.outgoing_transitions(*state_index).next().is_none())
+        .collect()
+}
+
+/// Returns the number of states of every strongly connected component of the
+/// hidden transitions, including the trivial components of a single state.
+pub fn tau_scc_sizes(lts: &impl LTS) -> Vec<usize> {
+    let mut sizes = Vec::new();
+    for_each_tau_scc(lts, |component| sizes.push(component.len()));
+    sizes
+}
+
+/// Returns the livelocks of the LTS, which are the strongly connected
+/// components of the hidden transitions that contain a tau-cycle. These are the
+/// non-trivial components and the single states with a hidden self-loop.
+pub fn livelocks(lts: &impl LTS) -> Vec<Vec<StateIndex>> {
+    let mut livelocks = Vec::new();
+    for_each_tau_scc(lts, |component| {
+        if has_tau_cycle(lts, component) {
+            livelocks.push(component.to_vec());
+        }
+    });
+    livelocks
+}
+
+/// Returns for every state whether it is divergent, indexed by the state index.
+/// A state is divergent when it can perform an infinite sequence of hidden
+/// transitions, i.e., when it can reach a tau-cycle by hidden transitions.
+pub fn divergence_marking(lts: &impl LTS) -> Vec<bool> {
+    let mut divergent = vec![false; lts.num_of_states()];
+
+    // The components are visited in reverse topological order, so the components
+    // reachable by a hidden transition have already been marked.
+    for_each_tau_scc(lts, |component| {
+        let is_divergent = has_tau_cycle(lts, component)
+            || component.iter().any(|state_index| {
+                lts.outgoing_transitions(*state_index)
+                    .any(|transition| lts.is_hidden_label(transition.label) && divergent[transition.to])
+            });
+
+        if is_divergent {
+            for state_index in component {
+                divergent[*state_index] = true;
+            }
+        }
+    });
+
+    divergent
+}
+
+/// Returns the divergent states, see [divergence_marking].
+pub fn divergent_states(lts: &impl LTS) -> Vec<StateIndex> {
+    divergence_marking(lts)
+        .iter()
+        .enumerate()
+        .filter(|(_, divergent)| **divergent)
+        .map(|(state_index, _)| StateIndex::new(state_index))
+        .collect()
+}
+
+/// Returns true iff the given strongly connected component of the hidden
+/// transitions contains a tau-cycle.
+fn has_tau_cycle(lts: &impl LTS, component: &[StateIndex]) -> bool {
+    if let [state_index] = component {
+        lts.outgoing_transitions(*state_index)
+            .any(|transition| lts.is_hidden_label(transition.label) && transition.to == *state_index)
+    } else {
+        true
+    }
+}
+
+/// Calls the given function with the states of every strongly connected
+/// component of the hidden transitions, including the trivial components of a
+/// single state. The components are visited in reverse topological order.
+///
+/// # Details
+///
+/// This is an iterative variant of Tarjan's algorithm, such that deep tau
+/// paths do not overflow the stack.
+pub(crate) fn for_each_tau_scc(lts: &impl LTS, mut f: impl FnMut(&[StateIndex])) {
+    const UNVISITED: usize = usize::MAX;
+
+    let mut index = vec![UNVISITED; lts.num_of_states()];
+    let mut lowlink = vec![0; lts.num_of_states()];
+    let mut on_stack = vec![false; lts.num_of_states()];
+    let mut stack = Vec::new();
+    let mut next_index = 0;
+
+    // The states being visited, with the position of the next outgoing transition to consider.
+    let mut work: Vec<(StateIndex, usize)> = Vec::new();
+
+    for root in lts.iter_states() {
+        if index[root] != UNVISITED {
+            continue;
+        }
+
+        index[root] = next_index;
+        lowlink[root] = next_index;
+        next_index += 1;
+        on_stack[root] = true;
+        stack.push(root);
+        work.push((root, 0));
+
+        while let Some(&(state_index, position)) = work.last() {
+            let next = lts
+                .outgoing_transitions(state_index)
+                .enumerate()
+                .skip(position)
+                .find(|(_, transition)| lts.is_hidden_label(transition.label));
+
+            if let Some((position, transition)) = next {
+                work.last_mut().expect("The work stack is not empty").1 = position + 1;
+
+                let to = transition.to;
+                if index[to] == UNVISITED {
+                    index[to] = next_index;
+                    lowlink[to] = next_index;
+                    next_index += 1;
+                    on_stack[to] = true;
+                    stack.push(to);
+                    work.push((to, 0));
+                } else if on_stack[to] {
+                    lowlink[state_index] = lowlink[state_index].min(index[to]);
+                }
+            } else {
+                work.pop();
+                if let Some(&(parent, _)) = work.last() {
+                    lowlink[parent] = lowlink[parent].min(lowlink[state_index]);
+                }
+
+                if lowlink[state_index] == index[state_index] {
+                    // The state is the root of a component, which consists of the states above it on the stack.
+                    let start = stack
+                        .iter()
+                        .rposition(|member| *member == state_index)
+                        .expect("The root of a component is on the stack");
+                    for member in &stack[start..] {
+                        on_stack[*member] = false;
+                    }
+                    f(&stack[start..]);
+                    stack.truncate(start);
+                }
+            }
+        }
+    }
+}
+
+/// Statistics on the transitions of a single label.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LabelStatistics {
+    /// The number of transitions with this label.
+    pub num_of_transitions: usize,
+
+    /// The largest number of transitions with this label leaving a single
+    /// state, where at most one means that the label is deterministic.
+    pub max_fan_out: usize,
+
+    /// The number of states with more than one outgoing transition with this label.
+    pub nondeterministic_states: usize,
+}
+
+/// Returns the determinism statistics for every label, indexed by the label index.
+pub fn label_statistics(lts: &impl LTS) -> Vec<LabelStatistics> {
+    let mut statistics = vec![LabelStatistics::default(); lts.num_of_labels()];
+
+    // The number of outgoing transitions per label of the current state, and the labels that occur.
+    let mut fan_out = vec![0; lts.num_of_labels()];
+    let mut occurring: Vec<LabelIndex> = Vec::new();
+
+    for state_index in lts.iter_states() {
+        for transition in lts.outgoing_transitions(state_index) {
+            if fan_out[transition.label] == 0 {
+                occurring.push(transition.label);
+            }
+            fan_out[transition.label] += 1;
+        }
+
+        for label in occurring.drain(..) {
+            let label_statistics = &mut statistics[label];
+            label_statistics.num_of_transitions += fan_out[label];
+            label_statistics.max_fan_out = label_statistics.max_fan_out.max(fan_out[label]);
+            if fan_out[label] > 1 {
+                label_statistics.nondeterministic_states += 1;
+            }
+            fan_out[label] = 0;
+        }
+    }
+
+    statistics
+}
+
+#[cfg(test)]
+mod tests {
+    use merc_utilities::random_test;
+
+    use crate::LabelledTransitionSystem;
+    use crate::LtsBuilderFast;
+    use crate::random_lts;
+
+    use super::*;
+
+    /// Returns the LTS 0 -a-> 1, 0 -a-> 2, 1 -i-> 2, 2 -i-> 1 and 2 -b-> 3, where state 3 is a deadlock.
+    fn example() -> LabelledTransitionSystem<String> {
+        let mut builder = LtsBuilderFast::new(Vec::new(), Vec::new());
+        builder.add_transition(StateIndex::new(0), "a", StateIndex::new(1));
+        builder.add_transition(StateIndex::new(0), "a", StateIndex::new(2));
+        builder.add_transition(StateIndex::new(1), "i", StateIndex::new(2));
+        builder.add_transition(StateIndex::new(2), "i", StateIndex::new(1));
+        builder.add_transition(StateIndex::new(2), "b", StateIndex::new(3));
+        builder.finish(StateIndex::new(0), true)
+    }
+
+    #[test]
+    fn test_degree_histograms() {
+        let lts = example();
+
+        let out_degrees = out_degree_histogram(&lts);
+        assert_eq!(out_degrees.iter().collect::<Vec<_>>(), vec![(0, 1), (1, 1), (2, 2)]);
+        assert_eq!(out_degrees.mean(), 1.25);
+
+        let in_degrees = in_degree_histogram(&lts);
+        assert_eq!(in_degrees.iter().collect::<Vec<_>>(), vec![(0, 1), (1, 1), (2, 2)]);
+        assert_eq!(deadlock_states(&lts), vec![StateIndex::new(3)]);
+    }
+
+    #[test]
+    fn test_tau_scc_sizes() {
+        let mut sizes = tau_scc_sizes(&example());
+        sizes.sort();
+        assert_eq!(sizes, vec![1, 1, 2]);
+    }
+
+    #[test]
+    fn test_divergence() {
+        let lts = example();
+        assert_eq!(divergent_states(&lts), vec![StateIndex::new(1), StateIndex::new(2)]);
+
+        let mut livelocks = livelocks(&lts);
+        assert_eq!(livelocks.len(), 1);
+        livelocks[0].sort();
+        assert_eq!(livelocks[0], vec![StateIndex::new(1), StateIndex::new(2)]);
+    }
+
+    #[test]
+    fn test_label_statistics() {
+        let lts = example();
+        let statistics = label_statistics(&lts);
+
+        let a = lts.labels().iter().position(|label| label == "a").unwrap();
+        assert_eq!(
+            statistics[a],
+            LabelStatistics {
+                num_of_transitions: 2,
+                max_fan_out: 2,
+                nondeterministic_states: 1
+            }
+        );
+    }
+
+    #[test]
+    fn test_random_statistics() {
+        random_test(100, |rng| {
+            let lts = random_lts(rng, 10, 3, 3);
+
+            assert_eq!(out_degree_histogram(&lts).num_of_values(), lts.num_of_states());
+            assert_eq!(in_degree_histogram(&lts).num_of_values(), lts.num_of_states());
+            assert_eq!(tau_scc_sizes(&lts).iter().sum::<usize>(), lts.num_of_states());
+
+            // Every state on a livelock is divergent.
+            let divergent = divergence_marking(&lts);
+            assert!(
+                livelocks(&lts)
+                    .iter()
+                    .flatten()
+                    .all(|state_index| divergent[*state_index])
+            );
+            assert_eq!(
+                label_statistics(&lts)
+                    .iter()
+                    .map(|statistics| statistics.num_of_transitions)
+                    .sum::<usize>(),
+                lts.num_of_transitions()
+            );
+        })
+    }
+}
@@ -0,0 +1,364 @@
+#![forbid(unsafe_code)]
+
+use std::collections::VecDeque;
+use std::fmt;
+
+use itertools::Itertools;
+use regex::Regex;
+
+use merc_utilities::MercError;
+use merc_utilities::Span;
+
+use crate::LTS;
+use crate::LabelIndex;
+use crate::LabelledTransitionSystem;
+use crate::LtsBuilderFast;
+use crate::StateIndex;
+use crate::TransitionLabel;
+
+/// Returns the LTS in which every label is replaced by the result of
+/// `mapping`, where the transitions for which it returns `None` are removed.
+///
+/// # Details
+///
+/// The hidden labels of the input are passed to `mapping` as the tau label.
+/// Labels that are mapped to the same label are merged, and the resulting
+/// duplicate transitions are removed. The states are kept as is, even when they
+/// become unreachable because transitions are removed.
+pub fn map_labels<L: LTS>(
+    lts: &L,
+    mapping: impl Fn(&L::Label) -> Option<L::Label>,
+) -> LabelledTransitionSystem<L::Label> {
+    let labels: Vec<Option<L::Label>> = lts
+        .labels()
+        .iter()
+        .enumerate()
+        .map(|(index, label)| {
+            if lts.is_hidden_label(LabelIndex::new(index)) {
+                mapping(&L::Label::tau_label())
+            } else {
+                mapping(label)
+            }
+        })
+        .collect();
+
+    let mut builder = LtsBuilderFast::new(Vec::new(), Vec::new());
+    for state in lts.iter_states() {
+        for transition in lts.outgoing_transitions(state) {
+            if let Some(label) = &labels[transition.label.value()] {
+                builder.add_transition(state, label, transition.to);
+            }
+        }
+    }
+
+    builder.require_num_of_states(lts.num_of_states());
+    builder.finish(lts.initial_state_index(), true)
+}
+
+/// Renames every match of `pattern` in the visible labels by `replacement`,
+/// which can refer to the capture groups of the pattern as `$1`, `$name`,
+/// etc.
+pub fn rename_labels<L: LTS<Label = String>>(
+    lts: &L,
+    pattern: &Regex,
+    replacement: &str,
+) -> LabelledTransitionSystem<String> {
+    map_labels(lts, |label| Some(rename_label(label, pattern, replacement)))
+}
+
+/// Renames the given actions to the internal action.
+pub fn hide_labels<L: LTS>(lts: &L, actions: &[String]) -> LabelledTransitionSystem<L::Label> {
+    map_labels(lts, |label| Some(hide_label(label, actions)))
+}
+
+/// Removes all transitions labelled by one of the given actions.
+pub fn remove_labels<L: LTS>(lts: &L, actions: &[String]) -> LabelledTransitionSystem<L::Label> {
+    map_labels(lts, |label| remove_label(label, actions))
+}
+
+/// Returns the sub-LTS that is reachable from the initial state using only the
+/// transitions labelled by one of the given actions, where the states are
+/// renumbered in breadth-first order.
+///
+/// # Details
+///
+/// The hidden labels of the input are matched as the tau label, so the
+/// internal transitions are only kept when the tau label is given as well.
+pub fn project_labels<L: LTS>(lts: &L, actions: &[String]) -> LabelledTransitionSystem<L::Label> {
+    let labels: Vec<Option<L::Label>> = lts
+        .labels()
+        .iter()
+        .enumerate()
+        .map(|(index, label)| {
+            let label = if lts.is_hidden_label(LabelIndex::new(index)) {
+                L::Label::tau_label()
+            } else {
+                label.clone()
+            };
+
+            if actions.iter().any(|action| label.matches_label(action)) {
+                Some(label)
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    let mut builder = LtsBuilderFast::new(Vec::new(), Vec::new());
+    let mut state_map: Vec<Option<StateIndex>> = vec![None; lts.num_of_states()];
+    let mut queue = VecDeque::new();
+
+    state_map[lts.initial_state_index()] = Some(StateIndex::new(0));
+    queue.push_back(lts.initial_state_index());
+    let mut num_of_states = 1;
+
+    while let Some(state_index) = queue.pop_front() {
+        let from = state_map[state_index].expect("Queued states are numbered");
+
+        for transition in lts.outgoing_transitions(state_index) {
+            if let Some(label) = &labels[transition.label] {
+                let to = *state_map[transition.to].get_or_insert_with(|| {
+                    queue.push_back(transition.to);
+                    num_of_states += 1;
+                    StateIndex::new(num_of_states - 1)
+                });
+
+                builder.add_transition(from, label, to);
+            }
+        }
+    }
+
+    builder.require_num_of_states(num_of_states);
+    builder.finish(StateIndex::new(0), true)
+}
+
+/// A single operation of a transformation script, see [LabelTransformation::parse_script].
+#[derive(Clone, Debug)]
+pub enum LabelTransformation {
+    /// Renames the labels as described in [rename_labels].
+    Rename(Regex, String),
+    /// Hides the given actions as described in [hide_labels].
+    Hide(Vec<String>),
+    /// Removes the transitions with the given actions as described in [remove_labels].
+    Remove(Vec<String>),
+}
+
+impl LabelTransformation {
+    /// Parses a script consisting of one operation per line, where empty lines
+    /// and lines starting with `#` are ignored.
+    ///
+    /// ```text
+    /// # Rename send_1 to s1, etc.
+    /// rename send_(\d+) s$1
+    /// hide internal, tick
+    /// remove error
+    /// ```
+    ///
+    /// The pattern and replacement of `rename` are separated by whitespace, so
+    /// they cannot contain whitespace themselves. The actions of `hide` and
+    /// `remove` are separated by commas.
+    pub fn parse_script(script: &str) -> Result<Vec<LabelTransformation>, MercError> {
+        let mut result = Vec::new();
+
+        for (number, line) in script.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            result.push(Self::parse(line).map_err(|error| error.with_span(Span::line(number + 1)))?);
+        }
+
+        Ok(result)
+    }
+
+    /// Parses a single operation of a script, see [LabelTransformation::parse_script].
+    pub fn parse(operation: &str) -> Result<LabelTransformation, MercError> {
+        let (keyword, rest) = operation
+            .trim()
+            .split_once(char::is_whitespace)
+            .ok_or_else(|| MercError::parse(format!("Operation {operation} has no arguments"), None))?;
+
+        let actions = || -> Vec<String> {
+            rest.split(',')
+                .map(|action| action.trim().to_string())
+                .filter(|action| !action.is_empty())
+                .collect()
+        };
+
+        match keyword {
+            "rename" => {
+                let arguments: Vec<&str> = rest.split_whitespace().collect();
+                if let [pattern, replacement] = arguments[..] {
+                    Ok(LabelTransformation::Rename(
+                        Regex::new(pattern).map_err(|error| MercError::parse(error, None))?,
+                        replacement.to_string(),
+                    ))
+                } else {
+                    Err(MercError::parse(
+                        format!("Expected rename <pattern> <replacement>, but found {operation}"),
+                        None,
+                    ))
+                }
+            }
+            "hide" => Ok(LabelTransformation::Hide(actions())),
+            "remove" => Ok(LabelTransformation::Remove(actions())),
+            _ => Err(MercError::parse(
+                format!("Unknown operation {keyword}, expected rename, hide or remove"),
+                None,
+            )),
+        }
+    }
+}
+
+impl fmt::Display for LabelTransformation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LabelTransformation::Rename(pattern, replacement) => write!(f, "rename {pattern} {replacement}"),
+            LabelTransformation::Hide(actions) => write!(f, "hide {}", actions.iter().format(", ")),
+            LabelTransformation::Remove(actions) => write!(f, "remove {}", actions.iter().format(", ")),
+        }
+    }
+}
+
+/// Applies the given transformations in order, which only traverses the transitions of the LTS once.
+pub fn transform_labels<L: LTS<Label = String>>(
+    lts: &L,
+    transformations: &[LabelTransformation],
+) -> LabelledTransitionSystem<String> {
+    map_labels(lts, |label| {
+        transformations
+            .iter()
+            .try_fold(label.clone(), |label, transformation| match transformation {
+                LabelTransformation::Rename(pattern, replacement) => Some(rename_label(&label, pattern, replacement)),
+                LabelTransformation::Hide(actions) => Some(hide_label(&label, actions)),
+                LabelTransformation::Remove(actions) => remove_label(&label, actions),
+            })
+    })
+}
+
+/// Renames a single label, where the internal action is never renamed.
+fn rename_label(label: &str, pattern: &Regex, replacement: &str) -> String {
+    if label == String::tau_label() {
+        label.to_string()
+    } else {
+        pattern.replace_all(label, replacement).into_owned()
+    }
+}
+
+fn hide_label<L: TransitionLabel>(label: &L, actions: &[String]) -> L {
+    if actions.iter().any(|action| label.matches_label(action)) {
+        L::tau_label()
+    } else {
+        label.clone()
+    }
+}
+
+fn remove_label<L: TransitionLabel>(label: &L, actions: &[String]) -> Option<L> {
+    if actions.iter().any(|action| label.matches_label(action)) {
+        None
+    } else {
+        Some(label.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use merc_utilities::MercErrorKind;
+
+    use super::*;
+
+    /// Returns the LTS 0 -a-> 1, 0 -send_1-> 2, 1 -b-> 2 and 2 -send_2-> 0.
+    fn example() -> LabelledTransitionSystem<String> {
+        let mut builder = LtsBuilderFast::new(Vec::new(), Vec::new());
+        builder.add_transition(StateIndex::new(0), "a", StateIndex::new(1));
+        builder.add_transition(StateIndex::new(0), "send_1", StateIndex::new(2));
+        builder.add_transition(StateIndex::new(1), "b", StateIndex::new(2));
+        builder.add_transition(StateIndex::new(2), "send_2", StateIndex::new(0));
+        builder.finish(StateIndex::new(0), true)
+    }
+
+    /// Returns the labels of the outgoing transitions of the given state.
+    fn outgoing_labels(lts: &LabelledTransitionSystem<String>, state: usize) -> Vec<String> {
+        lts.outgoing_transitions(StateIndex::new(state))
+            .map(|transition| lts.labels()[transition.label.value()].clone())
+            .sorted()
+            .collect()
+    }
+
+    #[test]
+    fn test_rename_labels() {
+        let lts = rename_labels(&example(), &Regex::new(r"send_(\d+)").unwrap(), "s$1");
+
+        assert_eq!(outgoing_labels(&lts, 0), vec!["a", "s1"]);
+        assert_eq!(outgoing_labels(&lts, 2), vec!["s2"]);
+        assert_eq!(lts.num_of_transitions(), 4);
+    }
+
+    #[test]
+    fn test_hide_labels() {
+        let lts = hide_labels(&example(), &["a".to_string(), "b".to_string()]);
+
+        assert_eq!(lts.num_of_labels(), 3);
+        assert!(
+            lts.outgoing_transitions(StateIndex::new(0))
+                .any(|transition| lts.is_hidden_label(transition.label))
+        );
+    }
+
+    #[test]
+    fn test_remove_labels() {
+        let lts = remove_labels(&example(), &["send_1".to_string()]);
+
+        assert_eq!(lts.num_of_states(), 3);
+        assert_eq!(lts.num_of_transitions(), 3);
+        assert_eq!(outgoing_labels(&lts, 0), vec!["a"]);
+    }
+
+    #[test]
+    fn test_project_labels() {
+        // The path 0 -a-> 1 -b-> 2 is kept, but the send transitions are removed.
+        let lts = project_labels(&example(), &["a".to_string(), "b".to_string()]);
+
+        assert_eq!(lts.num_of_states(), 3);
+        assert_eq!(lts.num_of_transitions(), 2);
+        assert_eq!(outgoing_labels(&lts, 0), vec!["a"]);
+
+        // Without a no transition is reachable from the initial state.
+        let lts = project_labels(&example(), &["b".to_string()]);
+        assert_eq!(lts.num_of_states(), 1);
+        assert_eq!(lts.num_of_transitions(), 0);
+    }
+
+    #[test]
+    fn test_transform_script() {
+        let transformations = LabelTransformation::parse_script(
+            "# Merge all send actions.
+            rename send_\\d+ send
+            hide a
+            remove b",
+        )
+        .unwrap();
+        assert_eq!(transformations.len(), 3);
+
+        let lts = transform_labels(&example(), &transformations);
+        assert_eq!(lts.num_of_transitions(), 3);
+        assert_eq!(outgoing_labels(&lts, 0), vec!["i", "send"]);
+        assert_eq!(outgoing_labels(&lts, 1), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_parse_script_errors() {
+        assert!(LabelTransformation::parse_script("rename a").is_err());
+        assert!(LabelTransformation::parse_script("rename ( b").is_err());
+        assert!(LabelTransformation::parse_script("block a").is_err());
+
+        let error = LabelTransformation::parse_script("# comment\nhide a\nblock a").unwrap_err();
+        assert_eq!(
+            error.kind(),
+            MercErrorKind::Parse {
+                span: Some(Span::line(3))
+            }
+        );
+    }
+}
@@ -3,9 +3,11 @@ use proc_macro2::TokenStream;
 use quote::ToTokens;
 use quote::format_ident;
 use quote::quote;
+use quote::quote_spanned;
 use syn::Item;
 use syn::ItemMod;
 use syn::parse_quote;
+use syn::spanned::Spanned;
 
 pub(crate) fn merc_derive_terms_impl(_attributes: TokenStream, input: TokenStream) -> TokenStream {
     // Parse the input tokens into a syntax tree
@@ -20,15 +22,23 @@ pub(crate) fn merc_derive_terms_impl(_attributes: TokenStream, input: TokenStrea
                 Item::Struct(object) => {
                     // If the struct is annotated with term we process it as a term.
                     if let Some(attr) = object.attrs.iter().find(|attr| attr.meta.path().is_ident("merc_term")) {
-                        // The #term(assertion) annotation must contain an assertion
-                        let assertion = match attr.parse_args::<syn::Ident>() {
-                            Ok(assertion) => {
-                                let assertion_msg = format!("{assertion}");
+                        // The #term(assertion, options...) annotation can contain an assertion and options.
+                        let options = match TermOptions::parse(attr) {
+                            Ok(options) => options,
+                            Err(error) => {
+                                added.push(Item::Verbatim(error.to_compile_error()));
+                                continue;
+                            }
+                        };
+
+                        let assertion = match &options.assertion {
+                            Some(assertion) => {
+                                let assertion_msg = path_to_string(assertion);
                                 quote!(
                                     debug_assert!(#assertion(&term), "Term {:?} does not satisfy {}", term, #assertion_msg)
                                 )
                             }
-                            Err(_x) => {
+                            None => {
                                 quote!()
                             }
                         };
@@ -253,6 +263,91 @@ pub(crate) fn merc_derive_terms_impl(_attributes: TokenStream, input: TokenStrea
                         );
 
                         added.push(Item::Verbatim(generated));
+
+                        if options.list {
+                            // Add a <name>List alias for lists of this term.
+                            let name_list = format_ident!("{}List", object.ident);
+                            let doc = format!("A list of [{name}] terms.");
+                            added.push(Item::Verbatim(quote!(
+                                #[doc = #doc]
+                                pub type #name_list #generics = ATermList<#name #generics>;
+                            )));
+                        }
+
+                        if let Some(assertion) = &options.assertion {
+                            let message = format!(
+                                "Term {{}} is not a {name}, since it does not satisfy {}",
+                                path_to_string(assertion)
+                            );
+
+                            // The recognizer is called with the span of the annotation, such that an
+                            // unresolved path or a wrong signature is reported at the annotation
+                            // instead of at the generated code.
+                            let check = quote_spanned!(assertion.span()=> #assertion(term));
+
+                            // Note that TryFrom<ATerm> cannot be implemented, since it conflicts with
+                            // the blanket implementation that is derived from From<ATerm>.
+                            added.push(Item::Verbatim(quote!(
+                                impl #generics #name #generics {
+                                    /// Returns true iff the given term satisfies the recognizer of this type.
+                                    pub fn is<'a, 'b>(term: &'b impl Term<'a, 'b>) -> bool {
+                                        #check
+                                    }
+
+                                    /// Converts the given term, returning an error when it does not satisfy the recognizer.
+                                    pub fn try_from_term(term: ATerm) -> Result<#name #generics, ::merc_utilities::MercError> {
+                                        if Self::is(&term) {
+                                            Ok(term.into())
+                                        } else {
+                                            Err(format!(#message, term).into())
+                                        }
+                                    }
+                                }
+
+                                impl #generics_ref #name_ref #generics_ref {
+                                    /// Converts the given term, returning an error when it does not satisfy the recognizer.
+                                    pub fn try_from_term(term: ATermRef<'a>) -> Result<#name_ref #generics_ref, ::merc_utilities::MercError> {
+                                        if #name::is(&term) {
+                                            Ok(term.into())
+                                        } else {
+                                            Err(format!(#message, term).into())
+                                        }
+                                    }
+                                }
+                            )));
+                        }
+
+                        if options.serde {
+                            // Terms are (de)serialized using their textual representation.
+                            let generics_de = create_generics_with_lifetimes(&object.generics, &["'de"]);
+                            let check = match &options.assertion {
+                                Some(_) => quote!(
+                                    let term = Self::try_from_term(term).map_err(::serde::de::Error::custom)?;
+                                ),
+                                None => quote!(
+                                    let term: Self = term.into();
+                                ),
+                            };
+
+                            added.push(Item::Verbatim(quote!(
+                                #[cfg(feature = "serde")]
+                                impl #generics ::serde::Serialize for #name #generics {
+                                    fn serialize<S: ::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                                        serializer.collect_str(&self.term)
+                                    }
+                                }
+
+                                #[cfg(feature = "serde")]
+                                impl #generics_de ::serde::Deserialize<'de> for #name #generics {
+                                    fn deserialize<D: ::serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                                        let text = <String as ::serde::Deserialize>::deserialize(deserializer)?;
+                                        let term = ATerm::from_string(&text).map_err(::serde::de::Error::custom)?;
+                                        #check
+                                        Ok(term)
+                                    }
+                                }
+                            )));
+                        }
                     }
                 }
                 Item::Impl(implementation) => {
@@ -311,6 +406,57 @@ pub(crate) fn merc_derive_terms_impl(_attributes: TokenStream, input: TokenStrea
     ast.into_token_stream()
 }
 
+/// The arguments of the `#[merc_term(...)]` annotation.
+#[derive(Default)]
+struct TermOptions {
+    /// The recognizer that every term of this type satisfies.
+    assertion: Option<syn::Path>,
+    /// Generate a `<name>List` alias for lists of these terms.
+    list: bool,
+    /// Generate serde implementations, enabled by the `serde` feature of the crate.
+    serde: bool,
+}
+
+impl TermOptions {
+    /// Parses the arguments of the given annotation, where all arguments except
+    /// for `list` and `serde` are considered to be the path of the recognizer.
+    fn parse(attr: &syn::Attribute) -> syn::Result<Self> {
+        let mut options = TermOptions::default();
+
+        if matches!(attr.meta, syn::Meta::Path(_)) {
+            // The annotation has no arguments.
+            return Ok(options);
+        }
+
+        let arguments =
+            attr.parse_args_with(syn::punctuated::Punctuated::<syn::Path, syn::Token![,]>::parse_terminated)?;
+
+        for argument in arguments {
+            if argument.is_ident("list") {
+                options.list = true;
+            } else if argument.is_ident("serde") {
+                options.serde = true;
+            } else if options.assertion.is_some() {
+                return Err(syn::Error::new(
+                    argument.span(),
+                    "The merc_term annotation contains more than one recognizer",
+                ));
+            } else {
+                options.assertion = Some(argument);
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+/// Returns the given path as it is written, without the spaces of the token stream.
+fn path_to_string(path: &syn::Path) -> String {
+    let segments: Vec<String> = path.segments.iter().map(|segment| segment.ident.to_string()).collect();
+    let prefix = if path.leading_colon.is_some() { "::" } else { "" };
+    format!("{prefix}{}", segments.join("::"))
+}
+
 #[cfg(test)]
 mod tests {
     use std::str::FromStr;
@@ -341,4 +487,47 @@ mod tests {
 
         println!("{result}");
     }
+
+    #[test]
+    fn test_macro_options() {
+        let input = "
+            mod anything {
+                #[merc_term(crate::test, list, serde)]
+                struct Test {
+                    term: ATerm,
+                }
+            }
+        ";
+
+        let tokens = TokenStream::from_str(input).unwrap();
+        let result = merc_derive_terms_impl(TokenStream::default(), tokens).to_string();
+
+        assert!(result.contains("type TestList = ATermList < Test >"));
+        assert!(
+            result.contains("fn is < 'a , 'b > (term : & 'b impl Term < 'a , 'b >) -> bool { crate :: test (term) }")
+        );
+        assert!(result.contains("fn try_from_term (term : ATerm)"));
+        assert!(result.contains("fn try_from_term (term : ATermRef < 'a >)"));
+        assert!(result.contains("does not satisfy crate::test"));
+        assert!(result.contains(":: serde :: Serialize for Test"));
+        assert!(result.contains(":: serde :: Deserialize < 'de > for Test"));
+    }
+
+    #[test]
+    fn test_macro_invalid_recognizer() {
+        let input = "
+            mod anything {
+                #[merc_term(is_a, is_b)]
+                struct Test {
+                    term: ATerm,
+                }
+            }
+        ";
+
+        let tokens = TokenStream::from_str(input).unwrap();
+        let result = merc_derive_terms_impl(TokenStream::default(), tokens).to_string();
+
+        assert!(result.contains("compile_error"));
+        assert!(result.contains("more than one recognizer"));
+    }
 }
@@ -0,0 +1,350 @@
+//! An arbitrary-precision rational number type.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::Add;
+use std::ops::Div;
+use std::ops::Mul;
+use std::ops::Neg;
+use std::ops::Sub;
+use std::str::FromStr;
+
+use num::BigInt;
+use num::Integer;
+use num::One;
+use num::Signed;
+use num::Zero;
+
+use merc_utilities::MercError;
+
+/// An arbitrary-precision rational number `numerator / denominator`.
+///
+/// # Details
+///
+/// The representation is always normalised, i.e., the greatest common divisor
+/// of the numerator and denominator is one and the denominator is strictly
+/// positive. As a consequence structural equality coincides with numerical
+/// equality, and the derived [`Hash`] is consistent with [`Eq`].
+///
+/// The textual representation is `n/d`, or just `n` whenever the denominator
+/// is one, and can be parsed back with [`FromStr`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BigRational {
+    numerator: BigInt,
+    denominator: BigInt,
+}
+
+impl BigRational {
+    /// Creates a new rational `numerator / denominator` in normal form.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the denominator is zero.
+    pub fn new(numerator: impl Into<BigInt>, denominator: impl Into<BigInt>) -> Self {
+        Self::try_new(numerator, denominator).expect("The denominator of a rational cannot be zero")
+    }
+
+    /// Creates a new rational `numerator / denominator` in normal form, returns an error when the denominator is zero.
+    pub fn try_new(numerator: impl Into<BigInt>, denominator: impl Into<BigInt>) -> Result<Self, MercError> {
+        let numerator = numerator.into();
+        let denominator = denominator.into();
+
+        if denominator.is_zero() {
+            return Err("The denominator of a rational cannot be zero".into());
+        }
+
+        Ok(Self::normalise(numerator, denominator))
+    }
+
+    /// Returns the rational representing zero.
+    pub fn zero() -> Self {
+        Self {
+            numerator: BigInt::zero(),
+            denominator: BigInt::one(),
+        }
+    }
+
+    /// Returns the rational representing one.
+    pub fn one() -> Self {
+        Self {
+            numerator: BigInt::one(),
+            denominator: BigInt::one(),
+        }
+    }
+
+    /// Returns the numerator of the normalised fraction.
+    pub fn numerator(&self) -> &BigInt {
+        &self.numerator
+    }
+
+    /// Returns the (strictly positive) denominator of the normalised fraction.
+    pub fn denominator(&self) -> &BigInt {
+        &self.denominator
+    }
+
+    /// Returns true iff this rational is zero.
+    pub fn is_zero(&self) -> bool {
+        self.numerator.is_zero()
+    }
+
+    /// Returns true iff this rational is an integer.
+    pub fn is_integer(&self) -> bool {
+        self.denominator.is_one()
+    }
+
+    /// Returns true iff this rational is strictly smaller than zero.
+    pub fn is_negative(&self) -> bool {
+        self.numerator.is_negative()
+    }
+
+    /// Returns the absolute value of this rational.
+    pub fn abs(&self) -> Self {
+        Self {
+            numerator: self.numerator.abs(),
+            denominator: self.denominator.clone(),
+        }
+    }
+
+    /// Returns the multiplicative inverse, or an error when the value is zero.
+    pub fn recip(&self) -> Result<Self, MercError> {
+        Self::try_new(self.denominator.clone(), self.numerator.clone())
+    }
+
+    /// Divides both components by their greatest common divisor and ensures the denominator is positive.
+    fn normalise(mut numerator: BigInt, mut denominator: BigInt) -> Self {
+        debug_assert!(!denominator.is_zero(), "The denominator cannot be zero");
+
+        if numerator.is_zero() {
+            return Self::zero();
+        }
+
+        let gcd = numerator.gcd(&denominator);
+        if !gcd.is_one() {
+            numerator /= &gcd;
+            denominator /= &gcd;
+        }
+
+        if denominator.is_negative() {
+            numerator = -numerator;
+            denominator = -denominator;
+        }
+
+        Self { numerator, denominator }
+    }
+}
+
+impl Default for BigRational {
+    fn default() -> Self {
+        Self::zero()
+    }
+}
+
+impl From<BigInt> for BigRational {
+    fn from(value: BigInt) -> Self {
+        Self {
+            numerator: value,
+            denominator: BigInt::one(),
+        }
+    }
+}
+
+macro_rules! impl_from_primitive {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for BigRational {
+                fn from(value: $t) -> Self {
+                    BigRational::from(BigInt::from(value))
+                }
+            }
+        )*
+    };
+}
+
+impl_from_primitive!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+impl fmt::Display for BigRational {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_integer() {
+            write!(f, "{}", self.numerator)
+        } else {
+            write!(f, "{}/{}", self.numerator, self.denominator)
+        }
+    }
+}
+
+impl FromStr for BigRational {
+    type Err = MercError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once('/') {
+            Some((numerator, denominator)) => {
+                BigRational::try_new(numerator.trim().parse::<BigInt>()?, denominator.trim().parse::<BigInt>()?)
+            }
+            None => Ok(BigRational::from(s.trim().parse::<BigInt>()?)),
+        }
+    }
+}
+
+impl PartialOrd for BigRational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BigRational {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Both denominators are positive, so cross multiplication preserves the order.
+        (&self.numerator * &other.denominator).cmp(&(&other.numerator * &self.denominator))
+    }
+}
+
+impl Neg for BigRational {
+    type Output = BigRational;
+
+    fn neg(self) -> Self::Output {
+        BigRational {
+            numerator: -self.numerator,
+            denominator: self.denominator,
+        }
+    }
+}
+
+impl Neg for &BigRational {
+    type Output = BigRational;
+
+    fn neg(self) -> Self::Output {
+        -self.clone()
+    }
+}
+
+impl Add for &BigRational {
+    type Output = BigRational;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        BigRational::normalise(
+            &self.numerator * &rhs.denominator + &rhs.numerator * &self.denominator,
+            &self.denominator * &rhs.denominator,
+        )
+    }
+}
+
+impl Sub for &BigRational {
+    type Output = BigRational;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        BigRational::normalise(
+            &self.numerator * &rhs.denominator - &rhs.numerator * &self.denominator,
+            &self.denominator * &rhs.denominator,
+        )
+    }
+}
+
+impl Mul for &BigRational {
+    type Output = BigRational;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        BigRational::normalise(&self.numerator * &rhs.numerator, &self.denominator * &rhs.denominator)
+    }
+}
+
+impl Div for &BigRational {
+    type Output = BigRational;
+
+    /// # Panics
+    ///
+    /// Panics when dividing by zero.
+    fn div(self, rhs: Self) -> Self::Output {
+        assert!(!rhs.is_zero(), "Division of a rational by zero");
+        BigRational::normalise(&self.numerator * &rhs.denominator, &self.denominator * &rhs.numerator)
+    }
+}
+
+/// Implements the owned variants of a binary operator in terms of the borrowed one.
+macro_rules! forward_binary_op {
+    ($trait:ident, $method:ident) => {
+        impl $trait for BigRational {
+            type Output = BigRational;
+
+            fn $method(self, rhs: BigRational) -> Self::Output {
+                $trait::$method(&self, &rhs)
+            }
+        }
+
+        impl $trait<&BigRational> for BigRational {
+            type Output = BigRational;
+
+            fn $method(self, rhs: &BigRational) -> Self::Output {
+                $trait::$method(&self, rhs)
+            }
+        }
+
+        impl $trait<BigRational> for &BigRational {
+            type Output = BigRational;
+
+            fn $method(self, rhs: BigRational) -> Self::Output {
+                $trait::$method(self, &rhs)
+            }
+        }
+    };
+}
+
+forward_binary_op!(Add, add);
+forward_binary_op!(Sub, sub);
+forward_binary_op!(Mul, mul);
+forward_binary_op!(Div, div);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand::Rng;
+
+    use merc_utilities::random_test;
+
+    #[test]
+    fn test_normalisation() {
+        assert_eq!(BigRational::new(2, 4), BigRational::new(1, 2));
+        assert_eq!(BigRational::new(-2, -4), BigRational::new(1, 2));
+        assert_eq!(BigRational::new(2, -4), BigRational::new(-1, 2));
+        assert_eq!(BigRational::new(0, -5), BigRational::zero());
+        assert!(BigRational::try_new(1, 0).is_err());
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let half = BigRational::new(1, 2);
+        let third = BigRational::new(1, 3);
+
+        assert_eq!(&half + &third, BigRational::new(5, 6));
+        assert_eq!(&half - &third, BigRational::new(1, 6));
+        assert_eq!(&half * &third, BigRational::new(1, 6));
+        assert_eq!(&half / &third, BigRational::new(3, 2));
+        assert_eq!(-half.clone(), BigRational::new(-1, 2));
+        assert!(third < half);
+    }
+
+    #[test]
+    fn test_display_parse() {
+        assert_eq!(BigRational::new(6, 4).to_string(), "3/2");
+        assert_eq!(BigRational::new(-4, 2).to_string(), "-2");
+        assert_eq!("3/-6".parse::<BigRational>().unwrap(), BigRational::new(-1, 2));
+        assert!("1/0".parse::<BigRational>().is_err());
+        assert!("a/b".parse::<BigRational>().is_err());
+    }
+
+    #[test]
+    fn test_random_rational_roundtrip() {
+        random_test(1000, |rng| {
+            let numerator: i64 = rng.random();
+            let denominator: i64 = rng.random_range(1..i64::MAX);
+
+            let value = BigRational::new(numerator, denominator);
+            let result: BigRational = value.to_string().parse().unwrap();
+            assert_eq!(result, value);
+
+            // Adding and subtracting should be the identity.
+            let other = BigRational::new(rng.random::<i32>(), rng.random_range(1..i32::MAX));
+            assert_eq!(&(&value + &other) - &other, value);
+        });
+    }
+}
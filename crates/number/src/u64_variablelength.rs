@@ -1,11 +1,5 @@
-use std::io::Read;
-use std::io::Write;
-
 use bitstream_io::BitRead;
-use bitstream_io::BitReader;
 use bitstream_io::BitWrite;
-use bitstream_io::BitWriter;
-use bitstream_io::Endianness;
 
 use merc_utilities::MercError;
 
@@ -19,10 +13,7 @@ pub const fn encoding_size<T>() -> usize {
 /// # Details
 ///
 /// Implementation taken from <https://techoverflow.net/2013/01/25/efficiently-encoding-variable-length-integers-in-cc/>
-pub fn write_u64_variablelength<W: Write, E: Endianness>(
-    stream: &mut BitWriter<W, E>,
-    mut value: u64,
-) -> Result<(), MercError> {
+pub fn write_u64_variablelength<W: BitWrite + ?Sized>(stream: &mut W, mut value: u64) -> Result<(), MercError> {
     // While more than 7 bits of data are left, occupy the last output byte
     // and set the next byte flag.
     while value > 0b01111111 {
@@ -37,21 +28,64 @@ pub fn write_u64_variablelength<W: Write, E: Endianness>(
 }
 
 /// Decodes an unsigned variable-length integer using the MSB algorithm.
-pub fn read_u64_variablelength<R: Read, E: Endianness>(stream: &mut BitReader<R, E>) -> Result<u64, MercError> {
+///
+/// # Details
+///
+/// The decoding is checked, i.e., an encoding that does not terminate within
+/// [`encoding_size`] bytes or that does not fit into a u64 results in an error
+/// instead of a silently truncated value.
+pub fn read_u64_variablelength<R: BitRead + ?Sized>(stream: &mut R) -> Result<u64, MercError> {
     let mut value: u64 = 0;
     for i in 0..encoding_size::<u64>() {
         let byte = stream.read::<8, u8>()?;
 
         // Take 7 bits (mask 0x01111111) from byte and shift it before the bits already written to value.
-        value |= ((byte & 0b01111111) as u64) << (7 * i);
+        let bits = (byte & 0b01111111) as u64;
+        let shift = 7 * i;
+        if shift == 63 && bits > 1 {
+            // Only the least significant bit of the last byte fits into a u64.
+            return Err(MercError::format("Variable-length integer does not fit into 64 bits"));
+        }
+        value |= bits << shift;
 
         if byte & 0b10000000 == 0 {
             // If the next-byte flag is not set then we are finished.
-            break;
+            return Ok(value);
         }
     }
 
-    Ok(value)
+    Err(MercError::format(
+        "Variable-length integer exceeds the maximum encoding size",
+    ))
+}
+
+/// Returns the number of bytes used by [`write_u64_variablelength`] to encode the given value.
+pub fn u64_variablelength_size(value: u64) -> usize {
+    // Every byte stores seven bits, but zero still requires a single byte.
+    (64 - value.leading_zeros() as usize).div_ceil(7).max(1)
+}
+
+/// Maps a signed integer to an unsigned integer such that values with a small
+/// magnitude have a small encoding, i.e., 0, -1, 1, -2, ... are mapped to 0,
+/// 1, 2, 3, ...
+pub fn zigzag_encode(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+/// The inverse of [`zigzag_encode`].
+pub fn zigzag_decode(value: u64) -> i64 {
+    ((value >> 1) as i64) ^ -((value & 1) as i64)
+}
+
+/// Encodes a signed variable-length integer by applying the zigzag mapping
+/// followed by [`write_u64_variablelength`].
+pub fn write_i64_variablelength<W: BitWrite + ?Sized>(stream: &mut W, value: i64) -> Result<(), MercError> {
+    write_u64_variablelength(stream, zigzag_encode(value))
+}
+
+/// Decodes a signed variable-length integer written by [`write_i64_variablelength`].
+pub fn read_i64_variablelength<R: BitRead + ?Sized>(stream: &mut R) -> Result<i64, MercError> {
+    Ok(zigzag_decode(read_u64_variablelength(stream)?))
 }
 
 #[cfg(test)]
@@ -59,6 +93,8 @@ mod tests {
     use super::*;
 
     use bitstream_io::BigEndian;
+    use bitstream_io::BitReader;
+    use bitstream_io::BitWriter;
     use rand::Rng;
 
     use merc_utilities::random_test;
@@ -78,4 +114,63 @@ mod tests {
             assert_eq!(result, value);
         });
     }
+
+    #[test]
+    fn test_random_signed_integer_encoding() {
+        random_test(1000, |rng| {
+            // Bias towards small magnitudes, since these are the interesting cases for zigzag.
+            let value: i64 = if rng.random_bool(0.5) {
+                rng.random_range(-1000..1000)
+            } else {
+                rng.random()
+            };
+
+            let mut stream: [u8; encoding_size::<u64>()] = [0; encoding_size::<u64>()];
+            let mut writer = BitWriter::<_, BigEndian>::new(&mut stream[0..]);
+            write_i64_variablelength(&mut writer, value).unwrap();
+
+            let mut reader = BitReader::<_, BigEndian>::new(&stream[0..]);
+            assert_eq!(read_i64_variablelength(&mut reader).unwrap(), value);
+            assert_eq!(zigzag_decode(zigzag_encode(value)), value);
+        });
+    }
+
+    #[test]
+    fn test_integer_encoding_size() {
+        for value in [0, 1, 127, 128, 16383, 16384, u32::MAX as u64, u64::MAX] {
+            let mut stream: Vec<u8> = Vec::new();
+            write_u64_variablelength(&mut BitWriter::<_, BigEndian>::new(&mut stream), value).unwrap();
+
+            assert_eq!(stream.len(), u64_variablelength_size(value));
+        }
+
+        assert_eq!(zigzag_encode(0), 0);
+        assert_eq!(zigzag_encode(-1), 1);
+        assert_eq!(zigzag_encode(1), 2);
+        assert_eq!(zigzag_encode(i64::MIN), u64::MAX);
+    }
+
+    #[test]
+    fn test_random_malformed_integer_encoding() {
+        random_test(1000, |rng| {
+            // Arbitrary byte sequences must never panic, and overlong sequences must be rejected.
+            let stream: Vec<u8> = (0..rng.random_range(0..2 * encoding_size::<u64>()))
+                .map(|_| rng.random())
+                .collect();
+
+            let mut reader = BitReader::<_, BigEndian>::new(&stream[..]);
+            let _ = read_u64_variablelength(&mut reader);
+        });
+
+        // Eleven bytes with the continuation flag set can never be a valid encoding.
+        let stream = [0xffu8; 11];
+        let mut reader = BitReader::<_, BigEndian>::new(&stream[..]);
+        assert!(read_u64_variablelength(&mut reader).is_err());
+
+        // The tenth byte may only contain a single bit.
+        let mut stream = [0xffu8; 10];
+        stream[9] = 0b00000010;
+        let mut reader = BitReader::<_, BigEndian>::new(&stream[..]);
+        assert!(read_u64_variablelength(&mut reader).is_err());
+    }
 }
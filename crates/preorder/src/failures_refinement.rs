@@ -7,16 +7,21 @@
 //! inclusion. All algorithms come in a variant with and without internal steps. It is possible to generate a counter
 //! transition system in case the inclusion is answered by no.
 
+use std::collections::VecDeque;
+
 use log::trace;
 use merc_collections::VecSet;
 use merc_lts::LTS;
 use merc_lts::StateIndex;
+use merc_lts::divergence_marking;
 use merc_reduction::Equivalence;
 use merc_reduction::Partition;
 use merc_reduction::quotient_lts_block;
 use merc_reduction::reduce_lts;
 use merc_reduction::strong_bisim_sigref;
+use merc_utilities::MercError;
 use merc_utilities::Timing;
+use merc_utilities::check_cancelled;
 
 use crate::Antichain;
 use crate::RefinementType;
@@ -30,18 +35,26 @@ pub enum ExplorationStrategy {
 /// This function checks using algorithms in the paper mentioned above
 /// whether transition system l1 is included in transition system l2, in the
 /// sense of trace inclusions, failures inclusion and divergence failures
-/// inclusion.
+/// inclusion. Returns an error when the computation was cancelled.
+///
+/// # Details
+///
+/// The preprocessing step reduces the LTSs modulo strong bisimulation, which
+/// preserves the divergences, since the weaker equivalences that remove
+/// tau-cycles do not preserve the (stable) failures and divergences.
 pub fn is_failures_refinement<L: LTS, const COUNTER_EXAMPLE: bool>(
     impl_lts: L,
     spec_lts: L,
     refinement: RefinementType,
-    _strategy: ExplorationStrategy,
+    strategy: ExplorationStrategy,
     preprocess: bool,
     timing: &mut Timing,
-) -> bool {
-    let reduction = match refinement {
-        RefinementType::Trace => Equivalence::StrongBisim,
-    };
+) -> Result<bool, MercError> {
+    if refinement.simulation_type().is_some() {
+        return Err(MercError::unsupported(format!(
+            "The {refinement:?} preorder is not supported by the antichain algorithm."
+        )));
+    }
 
     // For the preprocessing/quotienting step it makes sense to merge both LTSs
     // together in case that some states are equivalent. So we do this in all branches.
@@ -49,70 +62,149 @@ pub fn is_failures_refinement<L: LTS, const COUNTER_EXAMPLE: bool>(
         if COUNTER_EXAMPLE {
             // If a counter example is to be generated, we only reduce the
             // specification LTS such that the trace remains valid.
-            let reduced_spec = reduce_lts(spec_lts, reduction, timing);
+            let reduced_spec = reduce_lts(spec_lts, Equivalence::StrongBisim, timing)?;
             impl_lts.merge_disjoint(&reduced_spec)
         } else {
             let (merged_lts, initial_spec) = impl_lts.merge_disjoint(&spec_lts);
 
             // Reduce all states in the merged LTS.
-            match reduction {
-                Equivalence::StrongBisim => {
-                    let (preprocess_lts, partition) = strong_bisim_sigref(merged_lts, timing);
+            let (preprocess_lts, partition) = strong_bisim_sigref(merged_lts, timing)?;
 
-                    let initial_spec = partition.block_number(initial_spec);
-                    let reduced_lts = quotient_lts_block::<_, false>(&preprocess_lts, &partition);
+            let initial_spec = partition.block_number(initial_spec);
+            let reduced_lts = quotient_lts_block::<_, false>(&preprocess_lts, &partition);
 
-                    // After partitioning the block becomes the state in the reduced_lts.
-                    (reduced_lts, StateIndex::new(*initial_spec))
-                }
-                _ => unimplemented!(),
-            }
+            // After partitioning the block becomes the state in the reduced_lts.
+            (reduced_lts, StateIndex::new(*initial_spec))
         }
     } else {
         impl_lts.merge_disjoint(&spec_lts)
     };
 
-    let mut working = vec![(merged_lts.initial_state_index(), VecSet::singleton(initial_spec))];
+    let weak = refinement.is_weak();
+    let divergent = if refinement == RefinementType::FailuresDivergence {
+        divergence_marking(&merged_lts)
+    } else {
+        vec![false; merged_lts.num_of_states()]
+    };
+
+    let initial_spec = closure(&merged_lts, VecSet::singleton(initial_spec), weak);
+    let mut working = VecDeque::from([(merged_lts.initial_state_index(), initial_spec.clone())]);
 
     // The antichain data structure is used for storing explored states. However, as opposed to a discovered set it
     // allows for pruning additional pairs based on the `antichain` property.
     let mut antichain = Antichain::new();
+    antichain.insert(merged_lts.initial_state_index(), initial_spec);
 
-    while let Some((impl_state, spec)) = working.pop() {
+    while let Some((impl_state, spec)) = match strategy {
+        ExplorationStrategy::BFS => working.pop_front(),
+        ExplorationStrategy::DFS => working.pop_back(),
+    } {
+        check_cancelled()?;
         trace!("Checking ({:?}, {:?})", impl_state, spec);
         // pop (impl,spec) from working;
 
+        if spec.iter().any(|s| divergent[*s]) {
+            // A divergent specification allows any behaviour, so the successors need not be explored.
+            continue;
+        }
+
+        if divergent[impl_state] {
+            // The implementation diverges, but the specification does not.
+            return Ok(false);
+        }
+
+        if refinement.has_refusals()
+            && (!weak || is_stable(&merged_lts, impl_state))
+            && !refusals_contained_in(&merged_lts, impl_state, &spec, weak)
+        {
+            // The implementation state refuses actions that no (stable) specification state refuses.
+            return Ok(false);
+        }
+
         for impl_transition in merged_lts.outgoing_transitions(impl_state) {
-            // spec' := {s' | exists s in spec. s-e->s'};
-            let mut spec_prime = VecSet::new();
-            for s in &spec {
-                for spec_transition in merged_lts.outgoing_transitions(*s) {
-                    if impl_transition.label == spec_transition.label {
-                        spec_prime.insert(spec_transition.to);
+            let spec_prime = if weak && merged_lts.is_hidden_label(impl_transition.label) {
+                // The specification can mimic a hidden step by not moving, since it is closed under hidden steps.
+                spec.clone()
+            } else {
+                // spec' := {s' | exists s in spec. s-e->s'};
+                let mut spec_prime = VecSet::new();
+                for s in &spec {
+                    for spec_transition in merged_lts.outgoing_transitions(*s) {
+                        if impl_transition.label == spec_transition.label {
+                            spec_prime.insert(spec_transition.to);
+                        }
                     }
                 }
-            }
+
+                closure(&merged_lts, spec_prime, weak)
+            };
 
             trace!("spec' = {:?}", spec_prime);
             if spec_prime.is_empty() {
                 // if spec' = {} then
-                return false; //    return false;
+                return Ok(false); //    return false;
             }
 
             if antichain.insert(impl_transition.to, spec_prime.clone()) {
                 // if antichain_insert(impl,spec') then
-                working.push((impl_transition.to, spec_prime));
+                working.push_back((impl_transition.to, spec_prime));
+            }
+        }
+    }
+
+    Ok(true)
+}
+
+/// Extends the given set of states with all states reachable by hidden
+/// transitions when `weak` is true, and otherwise returns the set unchanged.
+fn closure(lts: &impl LTS, mut states: VecSet<StateIndex>, weak: bool) -> VecSet<StateIndex> {
+    if !weak {
+        return states;
+    }
+
+    let mut stack: Vec<StateIndex> = states.iter().cloned().collect();
+    while let Some(state_index) = stack.pop() {
+        for transition in lts.outgoing_transitions(state_index) {
+            if lts.is_hidden_label(transition.label) && states.insert(transition.to) {
+                stack.push(transition.to);
             }
         }
     }
 
-    true
+    states
+}
+
+/// Returns true iff the given state has no outgoing hidden transitions.
+fn is_stable(lts: &impl LTS, state_index: StateIndex) -> bool {
+    !lts.outgoing_transitions(state_index)
+        .any(|transition| lts.is_hidden_label(transition.label))
+}
+
+/// Returns true iff the refusals of the implementation state are included in
+/// the refusals of one of the specification states, i.e., there is a
+/// specification state that enables a subset of the actions enabled by the
+/// implementation state. When `weak` is true only the stable specification
+/// states are considered.
+fn refusals_contained_in(lts: &impl LTS, impl_state: StateIndex, spec: &VecSet<StateIndex>, weak: bool) -> bool {
+    let mut enabled = VecSet::new();
+    for transition in lts.outgoing_transitions(impl_state) {
+        enabled.insert(transition.label);
+    }
+
+    spec.iter().any(|s| {
+        (!weak || is_stable(lts, *s))
+            && lts
+                .outgoing_transitions(*s)
+                .all(|transition| enabled.contains(&transition.label))
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use merc_io::DumpFiles;
+    use merc_lts::LabelledTransitionSystem;
     use merc_lts::random_lts;
+    use merc_lts::read_aut;
     use merc_lts::write_aut;
     use merc_reduction::Equivalence;
     use merc_reduction::reduce_lts;
@@ -132,7 +224,7 @@ mod tests {
             let spec_lts = random_lts(rng, 10, 20, 5);
 
             let mut timing = Timing::default();
-            let impl_lts = reduce_lts(spec_lts.clone(), Equivalence::StrongBisim, &mut timing);
+            let impl_lts = reduce_lts(spec_lts.clone(), Equivalence::StrongBisim, &mut timing).unwrap();
 
             files.dump("spec.aut", |w| write_aut(w, &spec_lts)).unwrap();
             files.dump("impl.aut", |w| write_aut(w, &impl_lts)).unwrap();
@@ -145,9 +237,78 @@ mod tests {
                     ExplorationStrategy::BFS,
                     false,
                     &mut timing
-                ),
+                )
+                .unwrap(),
                 "Strong bisimulation implies trace refinement."
             );
         });
     }
+
+    #[test]
+    #[cfg_attr(miri, ignore)] // Tests are too slow under miri.
+    fn test_random_refinement() {
+        random_test(100, |rng| {
+            let spec_lts = random_lts(rng, 10, 20, 5);
+
+            let mut timing = Timing::default();
+            let impl_lts = reduce_lts(spec_lts.clone(), Equivalence::StrongBisim, &mut timing).unwrap();
+
+            for refinement in [
+                RefinementType::Trace,
+                RefinementType::WeakTrace,
+                RefinementType::Failures,
+                RefinementType::WeakFailures,
+                RefinementType::FailuresDivergence,
+            ] {
+                for strategy in [ExplorationStrategy::BFS, ExplorationStrategy::DFS] {
+                    assert!(
+                        is_failures_refinement::<_, false>(
+                            impl_lts.clone(),
+                            spec_lts.clone(),
+                            refinement,
+                            strategy,
+                            true,
+                            &mut timing
+                        )
+                        .unwrap(),
+                        "Strong bisimulation implies {refinement:?} refinement."
+                    );
+                }
+            }
+        });
+    }
+
+    #[test]
+    fn test_failures_divergence_refinement() {
+        let divergent = read_aut(b"des (0,1,1)\n(0,i,0)\n" as &[u8], Vec::new()).unwrap();
+        let deadlock = read_aut(b"des (0,0,1)\n" as &[u8], Vec::new()).unwrap();
+        let action = read_aut(b"des (0,1,2)\n(0,a,1)\n" as &[u8], Vec::new()).unwrap();
+
+        let check = |impl_lts: &LabelledTransitionSystem<String>,
+                     spec_lts: &LabelledTransitionSystem<String>,
+                     refinement: RefinementType| {
+            is_failures_refinement::<_, false>(
+                impl_lts.clone(),
+                spec_lts.clone(),
+                refinement,
+                ExplorationStrategy::BFS,
+                false,
+                &mut Timing::default(),
+            )
+            .unwrap()
+        };
+
+        // A divergence has no stable failures, but is not allowed by a specification that does not diverge.
+        assert!(check(&divergent, &deadlock, RefinementType::WeakTrace));
+        assert!(check(&divergent, &deadlock, RefinementType::WeakFailures));
+        assert!(!check(&divergent, &deadlock, RefinementType::FailuresDivergence));
+
+        // A divergent specification allows any behaviour.
+        assert!(!check(&action, &divergent, RefinementType::WeakTrace));
+        assert!(check(&action, &divergent, RefinementType::FailuresDivergence));
+
+        // A deadlock refuses the action that the specification must accept.
+        assert!(check(&deadlock, &action, RefinementType::Trace));
+        assert!(!check(&deadlock, &action, RefinementType::Failures));
+    }
 }
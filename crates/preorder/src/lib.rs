@@ -4,7 +4,9 @@
 mod antichain;
 mod failures_refinement;
 mod preorder;
+mod simulation_refinement;
 
 pub use antichain::*;
 pub use failures_refinement::*;
 pub use preorder::*;
+pub use simulation_refinement::*;
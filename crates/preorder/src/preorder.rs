@@ -1,24 +1,75 @@
 use merc_lts::LTS;
+use merc_reduction::SimulationType;
+use merc_utilities::MercError;
 use merc_utilities::Timing;
 
 use crate::ExplorationStrategy;
 use crate::is_failures_refinement;
+use crate::is_simulation_refinement;
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
 pub enum RefinementType {
+    /// Trace inclusion, where the hidden action is treated as a visible action.
     Trace,
+    /// Trace inclusion, where the hidden actions are not observable.
+    WeakTrace,
+    /// Failures inclusion, where the hidden action is treated as a visible action.
+    Failures,
+    /// Stable failures inclusion, where the hidden actions are not observable.
+    WeakFailures,
+    /// Failures-divergence inclusion, as used by FDR.
+    FailuresDivergence,
+    /// The simulation preorder.
+    Simulation,
+    /// The ready simulation preorder.
+    ReadySimulation,
+    /// The coupled simulation preorder, where the hidden actions are not observable.
+    CoupledSimulation,
 }
 
-pub fn refines<L: LTS>(impl_lts: L, spec_lts: L, preorder: RefinementType, timing: &mut Timing) -> bool {
-    match preorder {
-        RefinementType::Trace => is_failures_refinement::<L, false>(
-            impl_lts,
-            spec_lts,
-            RefinementType::Trace,
-            ExplorationStrategy::BFS,
-            false,
-            timing,
-        ),
+impl RefinementType {
+    /// Returns true iff the hidden actions are not observable in this preorder.
+    pub fn is_weak(&self) -> bool {
+        matches!(
+            self,
+            RefinementType::WeakTrace
+                | RefinementType::WeakFailures
+                | RefinementType::FailuresDivergence
+                | RefinementType::CoupledSimulation
+        )
     }
+
+    /// Returns the simulation preorder that corresponds to this preorder, if any.
+    pub fn simulation_type(&self) -> Option<SimulationType> {
+        match self {
+            RefinementType::Simulation => Some(SimulationType::Simulation),
+            RefinementType::ReadySimulation => Some(SimulationType::ReadySimulation),
+            RefinementType::CoupledSimulation => Some(SimulationType::CoupledSimulation),
+            _ => None,
+        }
+    }
+
+    /// Returns true iff this preorder compares the refusals of states.
+    pub fn has_refusals(&self) -> bool {
+        matches!(
+            self,
+            RefinementType::Failures | RefinementType::WeakFailures | RefinementType::FailuresDivergence
+        )
+    }
+}
+
+/// Returns true iff the implementation LTS refines the specification LTS
+/// modulo the given preorder.
+pub fn refines<L: LTS>(
+    impl_lts: L,
+    spec_lts: L,
+    preorder: RefinementType,
+    timing: &mut Timing,
+) -> Result<bool, MercError> {
+    if let Some(simulation) = preorder.simulation_type() {
+        return is_simulation_refinement(impl_lts, spec_lts, simulation, timing);
+    }
+
+    is_failures_refinement::<L, false>(impl_lts, spec_lts, preorder, ExplorationStrategy::BFS, false, timing)
 }
@@ -0,0 +1,70 @@
+use merc_lts::LTS;
+use merc_reduction::SimulationType;
+use merc_reduction::simulation_preorder;
+use merc_utilities::MercError;
+use merc_utilities::Timing;
+
+/// Returns true iff the initial state of the implementation LTS is simulated by
+/// the initial state of the specification LTS, for the given simulation preorder.
+/// Returns an error when the computation was cancelled.
+pub fn is_simulation_refinement<L: LTS>(
+    impl_lts: L,
+    spec_lts: L,
+    simulation: SimulationType,
+    timing: &mut Timing,
+) -> Result<bool, MercError> {
+    let (merged_lts, initial_spec) = impl_lts.merge_disjoint(&spec_lts);
+    drop(spec_lts); // No longer needed.
+
+    let preorder = simulation_preorder(&merged_lts, simulation, timing)?;
+    Ok(preorder.is_simulated_by(merged_lts.initial_state_index(), initial_spec))
+}
+
+#[cfg(test)]
+mod tests {
+    use merc_lts::random_lts;
+    use merc_reduction::Equivalence;
+    use merc_reduction::SimulationType;
+    use merc_reduction::reduce_lts;
+    use merc_utilities::Timing;
+    use merc_utilities::random_test;
+
+    use crate::is_simulation_refinement;
+
+    #[test]
+    #[cfg_attr(miri, ignore)] // Tests are too slow under miri.
+    fn test_random_simulation_refinement() {
+        random_test(100, |rng| {
+            let spec_lts = random_lts(rng, 10, 3, 3);
+
+            let mut timing = Timing::default();
+            let impl_lts = reduce_lts(spec_lts.clone(), Equivalence::Simulation, &mut timing).unwrap();
+
+            for simulation in [
+                SimulationType::Simulation,
+                SimulationType::ReadySimulation,
+                SimulationType::CoupledSimulation,
+            ] {
+                assert!(
+                    is_simulation_refinement(spec_lts.clone(), spec_lts.clone(), simulation, &mut timing).unwrap(),
+                    "Every LTS refines itself modulo {simulation:?}."
+                );
+            }
+
+            assert!(
+                is_simulation_refinement(
+                    impl_lts.clone(),
+                    spec_lts.clone(),
+                    SimulationType::Simulation,
+                    &mut timing
+                )
+                .unwrap(),
+                "The quotient modulo simulation equivalence is simulated by the original LTS."
+            );
+            assert!(
+                is_simulation_refinement(spec_lts, impl_lts, SimulationType::Simulation, &mut timing).unwrap(),
+                "The original LTS is simulated by its quotient modulo simulation equivalence."
+            );
+        });
+    }
+}
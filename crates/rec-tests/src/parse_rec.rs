@@ -69,7 +69,9 @@ fn parse_rec(contents: &str, path: Option<PathBuf>) -> Result<(RewriteSpecificat
 
     // Use Pest parser (generated automatically from the grammar.pest file)
     let mut parse_result = RecParser::parse(Rule::rec_spec, contents)?;
-    let root = parse_result.next().ok_or("Could not parse REC specification")?;
+    let root = parse_result
+        .next()
+        .ok_or_else(|| MercError::parse("Could not parse REC specification", None))?;
     let parse_node = ParseNode::new(root);
 
     // Parse using the consumed-based implementation
@@ -30,6 +30,8 @@ fn rec_test(rec_files: Vec<&str>, expected_result: &str) {
     // Test Sabre rewriter
     let mut sa = SabreRewriter::new(&spec);
     let mut inner = InnermostRewriter::new(&spec);
+    // A small cache, such that entries are also evicted.
+    let mut cached = InnermostRewriter::new(&spec).with_cache(1000);
 
     let mut expected = expected_result.split('\n');
 
@@ -44,6 +46,13 @@ fn rec_test(rec_files: Vec<&str>, expected_result: &str) {
             "The inner rewrite result doesn't match the expected result",
         );
 
+        let result = cached.rewrite(term);
+        assert_eq!(
+            result,
+            expected_result.clone(),
+            "The cached inner rewrite result doesn't match the expected result",
+        );
+
         let result = sa.rewrite(term);
         assert_eq!(
             result, expected_result,
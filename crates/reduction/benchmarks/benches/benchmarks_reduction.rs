@@ -0,0 +1,73 @@
+use std::env;
+use std::fs;
+use std::fs::File;
+use std::hint::black_box;
+
+use criterion::Criterion;
+use criterion::criterion_group;
+use criterion::criterion_main;
+
+use merc_lts::LabelledTransitionSystem;
+use merc_lts::random_lts;
+use merc_lts::read_aut;
+use merc_reduction::SplittingOrder;
+use merc_reduction::branching_bisim_sigref_with_strategy;
+use merc_reduction::strong_bisim_sigref_with_strategy;
+use merc_utilities::Timing;
+
+/// The splitting orders that are compared.
+const ORDERS: [SplittingOrder; 4] = [
+    SplittingOrder::Lifo,
+    SplittingOrder::Fifo,
+    SplittingOrder::LargestBlockFirst,
+    SplittingOrder::SmallestSignatureFirst,
+];
+
+/// Benchmarks strong and branching bisimulation reduction of the given LTS for every splitting order.
+fn bench_splitting_orders(c: &mut Criterion, name: &str, lts: &LabelledTransitionSystem<String>) {
+    for order in ORDERS {
+        c.bench_function(&format!("strong_bisim_sigref {order:?} {name}"), |bencher| {
+            bencher.iter(|| {
+                let mut timing = Timing::default();
+                black_box(strong_bisim_sigref_with_strategy(lts.clone(), &mut order.strategy(), &mut timing).unwrap());
+            })
+        });
+
+        c.bench_function(&format!("branching_bisim_sigref {order:?} {name}"), |bencher| {
+            bencher.iter(|| {
+                let mut timing = Timing::default();
+                black_box(
+                    branching_bisim_sigref_with_strategy(lts.clone(), &mut order.strategy(), &mut timing).unwrap(),
+                );
+            })
+        });
+    }
+}
+
+/// Compares the splitting orders of signature refinement on a random LTS, and
+/// on the `.aut` files in the directory given by the `MERC_VLTS_BENCHMARKS`
+/// environment variable, for example the VLTS benchmark suite.
+pub fn criterion_benchmark_splitting_orders(c: &mut Criterion) {
+    let mut rng = rand::rng();
+    let lts = random_lts(&mut rng, 10_000, 10, 5);
+    bench_splitting_orders(c, "random 10000", &lts);
+
+    if let Ok(directory) = env::var("MERC_VLTS_BENCHMARKS") {
+        let mut paths: Vec<_> = fs::read_dir(directory)
+            .expect("MERC_VLTS_BENCHMARKS should be a directory")
+            .map(|entry| entry.expect("Failed to read directory entry").path())
+            .filter(|path| path.extension().is_some_and(|extension| extension == "aut"))
+            .collect();
+        paths.sort();
+
+        for path in paths {
+            let lts = read_aut(File::open(&path).unwrap(), Vec::new()).unwrap();
+            let name = path.file_stem().unwrap().to_string_lossy();
+
+            bench_splitting_orders(c, &name, &lts);
+        }
+    }
+}
+
+criterion_group!(benches, criterion_benchmark_splitting_orders);
+criterion_main!(benches);
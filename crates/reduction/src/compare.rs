@@ -1,49 +1,219 @@
 #![forbid(unsafe_code)]
 
+use merc_lts::DEFAULT_ISOMORPHISM_LIMIT;
 use merc_lts::LTS;
+use merc_lts::StateIndex;
+use merc_lts::is_isomorphic;
+use merc_utilities::MercError;
 use merc_utilities::Timing;
 
 use crate::Equivalence;
 use crate::Partition;
+use crate::SimulationType;
 use crate::branching_bisim_sigref;
 use crate::branching_bisim_sigref_naive;
+use crate::preprocessed_state_index;
+use crate::quotient_lts_block;
+use crate::simulation_preorder;
 use crate::strong_bisim_sigref;
 use crate::strong_bisim_sigref_naive;
 use crate::weak_bisim_sigref_naive;
 use crate::weak_bisimulation;
 
 // Compare two LTSs for equivalence using the given algorithm.
-pub fn compare_lts<L: LTS>(equivalence: Equivalence, left: L, right: L, timing: &mut Timing) -> bool {
+pub fn compare_lts<L: LTS>(
+    equivalence: Equivalence,
+    left: L,
+    right: L,
+    timing: &mut Timing,
+) -> Result<bool, MercError> {
+    if equivalence == Equivalence::Isomorphism {
+        // Isomorphism is checked directly, since it cannot be decided on the merged LTS.
+        let mut time_isomorphism = timing.start("isomorphism");
+        let result = is_isomorphic(&left, &right, DEFAULT_ISOMORPHISM_LIMIT);
+        time_isomorphism.finish();
+        return result;
+    }
+
+    let mut time_merge = timing.start("merge lts");
+    let (merged, rhs_initial) = left.merge_disjoint(&right);
+    drop(right); // No longer needed.
+    time_merge.finish();
+
+    compare_merged(equivalence, merged, rhs_initial, timing)
+}
+
+/// Returns the finest supported equivalences that hold between the two LTSs,
+/// or an empty vector when none of them hold. There can be multiple, since
+/// for example ready simulation and branching bisimulation are incomparable.
+///
+/// # Details
+///
+/// Strong bisimulation implies all other equivalences, so the merged LTS is
+/// first reduced modulo strong bisimulation and the remaining equivalences are
+/// checked on this quotient. These are checked from coarse to fine along the
+/// chains of coupled simulation, weak bisimulation and branching bisimulation,
+/// and simulation and ready simulation, where a finer equivalence is only
+/// checked when the coarser one holds.
+pub fn classify_equivalence<L: LTS>(left: L, right: L, timing: &mut Timing) -> Result<Vec<Equivalence>, MercError> {
     let mut time_merge = timing.start("merge lts");
     let (merged, rhs_initial) = left.merge_disjoint(&right);
     drop(right); // No longer needed.
     time_merge.finish();
 
-    // Reduce the merged LTS modulo the given equivalence and return the partition
-    match equivalence {
+    let (merged, partition) = strong_bisim_sigref(merged, timing)?;
+    let lhs_block = partition.block_number(merged.initial_state_index());
+    let rhs_block = partition.block_number(rhs_initial);
+    if lhs_block == rhs_block {
+        return Ok(vec![Equivalence::StrongBisim]);
+    }
+
+    let mut time_quotient = timing.start("quotient");
+    let quotient = quotient_lts_block::<_, false>(&merged, &partition);
+    drop(merged);
+    time_quotient.finish();
+
+    // After quotienting the block becomes the state in the quotient.
+    let rhs_initial = StateIndex::new(*rhs_block);
+
+    let mut finest = Vec::new();
+    for chain in [
+        [
+            Equivalence::CoupledSimulation,
+            Equivalence::WeakBisim,
+            Equivalence::BranchingBisim,
+        ]
+        .as_slice(),
+        [Equivalence::Simulation, Equivalence::ReadySimulation].as_slice(),
+    ] {
+        let mut holds = None;
+        for equivalence in chain {
+            if !compare_merged(*equivalence, quotient.clone(), rhs_initial, timing)? {
+                break;
+            }
+
+            holds = Some(*equivalence);
+        }
+
+        finest.extend(holds);
+    }
+
+    Ok(finest)
+}
+
+/// Returns true iff the initial state of the merged LTS and the given state are
+/// equivalent modulo the given equivalence.
+fn compare_merged<L: LTS>(
+    equivalence: Equivalence,
+    merged: L,
+    rhs_initial: StateIndex,
+    timing: &mut Timing,
+) -> Result<bool, MercError> {
+    // Reduce the merged LTS modulo the given equivalence and return the
+    // partition, the reductions for weak and branching bisimulation renumber
+    // the states so the right initial state must be renumbered accordingly.
+    let equivalent = match equivalence {
+        Equivalence::None => {
+            return Err(MercError::unsupported(
+                "The 'none' equivalence only normalises an LTS and cannot be used for comparison.",
+            ));
+        }
         Equivalence::WeakBisim => {
-            let (lts, partition) = weak_bisimulation(merged, timing);
+            let rhs_initial = preprocessed_state_index(&merged, rhs_initial);
+            let (lts, partition) = weak_bisimulation(merged, timing)?;
             partition.block_number(lts.initial_state_index()) == partition.block_number(rhs_initial)
         }
         Equivalence::WeakBisimSigref => {
-            let (lts, partition) = weak_bisim_sigref_naive(merged, timing);
+            let rhs_initial = preprocessed_state_index(&merged, rhs_initial);
+            let (lts, partition) = weak_bisim_sigref_naive(merged, timing)?;
             partition.block_number(lts.initial_state_index()) == partition.block_number(rhs_initial)
         }
         Equivalence::StrongBisim => {
-            let (lts, partition) = strong_bisim_sigref(merged, timing);
+            let (lts, partition) = strong_bisim_sigref(merged, timing)?;
             partition.block_number(lts.initial_state_index()) == partition.block_number(rhs_initial)
         }
         Equivalence::StrongBisimNaive => {
-            let (lts, partition) = strong_bisim_sigref_naive(merged, timing);
+            let (lts, partition) = strong_bisim_sigref_naive(merged, timing)?;
             partition.block_number(lts.initial_state_index()) == partition.block_number(rhs_initial)
         }
         Equivalence::BranchingBisim => {
-            let (lts, partition) = branching_bisim_sigref(merged, timing);
+            let rhs_initial = preprocessed_state_index(&merged, rhs_initial);
+            let (lts, partition) = branching_bisim_sigref(merged, timing)?;
             partition.block_number(lts.initial_state_index()) == partition.block_number(rhs_initial)
         }
         Equivalence::BranchingBisimNaive => {
-            let (lts, partition) = branching_bisim_sigref_naive(merged, timing);
+            let rhs_initial = preprocessed_state_index(&merged, rhs_initial);
+            let (lts, partition) = branching_bisim_sigref_naive(merged, timing)?;
             partition.block_number(lts.initial_state_index()) == partition.block_number(rhs_initial)
         }
+        Equivalence::Simulation => {
+            let preorder = simulation_preorder(&merged, SimulationType::Simulation, timing)?;
+            let partition = preorder.partition();
+            partition.block_number(merged.initial_state_index()) == partition.block_number(rhs_initial)
+        }
+        Equivalence::ReadySimulation => {
+            let preorder = simulation_preorder(&merged, SimulationType::ReadySimulation, timing)?;
+            let partition = preorder.partition();
+            partition.block_number(merged.initial_state_index()) == partition.block_number(rhs_initial)
+        }
+        Equivalence::CoupledSimulation => {
+            let preorder = simulation_preorder(&merged, SimulationType::CoupledSimulation, timing)?;
+            let partition = preorder.partition();
+            partition.block_number(merged.initial_state_index()) == partition.block_number(rhs_initial)
+        }
+        Equivalence::Isomorphism => {
+            return Err(MercError::unsupported(
+                "Isomorphism cannot be decided on a merged LTS, use compare_lts instead.",
+            ));
+        }
+    };
+
+    Ok(equivalent)
+}
+
+#[cfg(test)]
+mod tests {
+    use merc_lts::random_lts;
+    use merc_lts::read_aut;
+    use merc_utilities::Timing;
+    use merc_utilities::random_test;
+
+    use crate::Equivalence;
+    use crate::classify_equivalence;
+    use crate::compare_lts;
+    use crate::reduce_lts;
+
+    #[test]
+    fn test_classify_equivalence() {
+        let left = read_aut(b"des (0,2,3)\n(0,i,1)\n(1,a,2)\n" as &[u8], Vec::new()).unwrap();
+        let right = read_aut(b"des (0,1,2)\n(0,a,1)\n" as &[u8], Vec::new()).unwrap();
+
+        // The hidden step is inert for branching bisimulation, but observable for (ready) simulation.
+        let result = classify_equivalence(left, right, &mut Timing::new()).unwrap();
+        assert_eq!(result, vec![Equivalence::BranchingBisim]);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_random_classify_equivalence() {
+        random_test(100, |rng| {
+            let lts = random_lts(rng, 10, 3, 3);
+            let mut timing = Timing::new();
+
+            let reduced = reduce_lts(lts.clone(), Equivalence::StrongBisim, &mut timing).unwrap();
+            let result = classify_equivalence(lts, reduced, &mut timing).unwrap();
+            assert_eq!(result, vec![Equivalence::StrongBisim]);
+        })
+    }
+
+    #[test]
+    fn test_compare_isomorphism() {
+        let left = read_aut(b"des (0,2,2)\n(0,a,1)\n(1,a,1)\n" as &[u8], Vec::new()).unwrap();
+        let right = read_aut(b"des (1,2,2)\n(1,a,0)\n(0,a,0)\n" as &[u8], Vec::new()).unwrap();
+        let reduced = reduce_lts(left.clone(), Equivalence::StrongBisim, &mut Timing::new()).unwrap();
+
+        let mut timing = Timing::new();
+        assert!(compare_lts(Equivalence::Isomorphism, left.clone(), right, &mut timing).unwrap());
+        assert!(!compare_lts(Equivalence::Isomorphism, left, reduced, &mut timing).unwrap());
     }
 }
@@ -0,0 +1,300 @@
+#![forbid(unsafe_code)]
+
+use std::collections::VecDeque;
+
+use bitvec::bitvec;
+use bitvec::order::Lsb0;
+use log::info;
+
+use merc_lts::IncomingTransitions;
+use merc_lts::LTS;
+use merc_lts::LabelledTransitionSystem;
+use merc_lts::LtsBuilderFast;
+use merc_lts::StateIndex;
+use merc_lts::TransitionLabel;
+use merc_utilities::MercError;
+use merc_utilities::Timing;
+use merc_utilities::check_cancelled;
+
+use crate::quotient_lts_naive;
+use crate::tau_scc_decomposition;
+
+/// Reduces the given LTS by giving priority to its tau-confluent transitions,
+/// which preserves branching bisimulation (and therefore also weak
+/// bisimulation), but not strong bisimulation.
+///
+/// # Details
+///
+/// First the strongly connected tau components are collapsed, such that the
+/// LTS is free of tau-loops. Then the tau-confluent transitions are computed by
+/// [tau_confluent_transitions] and prioritised by [tau_prioritisation].
+pub fn confluence_reduction<L: LTS>(
+    lts: L,
+    timing: &mut Timing,
+) -> Result<LabelledTransitionSystem<L::Label>, MercError> {
+    let mut time_scc = timing.start("tau scc decomposition");
+    let scc_partition = tau_scc_decomposition(&lts);
+    let tau_loop_free_lts = quotient_lts_naive(&lts, &scc_partition, true);
+    drop(lts);
+    time_scc.finish();
+
+    let mut time_confluence = timing.start("confluence");
+    let confluent = tau_confluent_transitions(&tau_loop_free_lts)?;
+    time_confluence.finish();
+
+    let mut time_prioritisation = timing.start("prioritisation");
+    let result = tau_prioritisation(&tau_loop_free_lts, &confluent);
+    time_prioritisation.finish();
+
+    info!(
+        "Confluence reduction removed {} states and {} transitions",
+        tau_loop_free_lts.num_of_states() - result.num_of_states(),
+        tau_loop_free_lts
+            .num_of_transitions()
+            .saturating_sub(result.num_of_transitions())
+    );
+    Ok(result)
+}
+
+/// Returns for every state the (sorted) targets of its outgoing tau-confluent
+/// transitions, which is the largest set of tau transitions that is confluent.
+///
+/// # Details
+///
+/// A set of tau transitions T is confluent when for every s -tau-> t in T and
+/// every other transition s -a-> u, either a is tau and u = t, or there is a
+/// transition t -a-> v such that u -tau-> v is in T or u = v. Furthermore, when
+/// s -a-> u is itself in T then t -a-> v must be in T as well.
+///
+/// Starting from all tau transitions, the transitions that violate this
+/// condition are removed until a fixed point is reached. Removing a transition
+/// from a state u only affects the transitions of the states with a transition
+/// to u, which are found using the incoming transitions.
+pub fn tau_confluent_transitions(lts: &impl LTS) -> Result<Vec<Vec<StateIndex>>, MercError> {
+    let incoming = IncomingTransitions::new(lts);
+
+    let mut confluent: Vec<Vec<StateIndex>> = lts
+        .iter_states()
+        .map(|state_index| {
+            let mut targets: Vec<StateIndex> = lts
+                .outgoing_transitions(state_index)
+                .filter(|transition| lts.is_hidden_label(transition.label))
+                .map(|transition| transition.to)
+                .collect();
+            targets.sort_unstable();
+            targets.dedup();
+            targets
+        })
+        .collect();
+
+    // The states whose confluent transitions must be checked (again).
+    let mut queue: VecDeque<StateIndex> = lts.iter_states().collect();
+    let mut queued = bitvec![u64, Lsb0; 1; lts.num_of_states()];
+
+    while let Some(state_index) = queue.pop_front() {
+        check_cancelled()?;
+        queued.set(state_index.value(), false);
+
+        let mut removed = false;
+        let mut index = 0;
+        while index < confluent[state_index].len() {
+            let target = confluent[state_index][index];
+            if is_confluent(lts, &confluent, state_index, target) {
+                index += 1;
+            } else {
+                confluent[state_index].remove(index);
+                removed = true;
+            }
+        }
+
+        if removed {
+            for transition in incoming.incoming_transitions(state_index) {
+                if !queued[transition.to.value()] {
+                    queued.set(transition.to.value(), true);
+                    queue.push_back(transition.to);
+                }
+            }
+        }
+    }
+
+    Ok(confluent)
+}
+
+/// Returns the LTS in which every state with an outgoing tau-confluent
+/// transition is merged with the state reached by following such transitions,
+/// where `confluent` is computed by [tau_confluent_transitions].
+///
+/// # Details
+///
+/// Every state with confluent transitions only keeps its first confluent
+/// transition, which is sound because all states connected by confluent
+/// transitions are branching bisimilar. The remaining states only have a single
+/// inert tau transition, and are therefore merged with the last state of the
+/// resulting chain. This requires the LTS to be free of tau-loops, since
+/// otherwise these chains are not finite. Only the states reachable from the
+/// initial state are kept.
+pub fn tau_prioritisation<L: LTS>(lts: &L, confluent: &[Vec<StateIndex>]) -> LabelledTransitionSystem<L::Label> {
+    // Determine the last state of the chain of first confluent transitions for every state.
+    let mut representative: Vec<Option<StateIndex>> = vec![None; lts.num_of_states()];
+    let mut chain = Vec::new();
+    for state_index in lts.iter_states() {
+        let mut current = state_index;
+        while representative[current].is_none() {
+            match confluent[current].first() {
+                Some(&next) => {
+                    chain.push(current);
+                    current = next;
+                }
+                None => representative[current] = Some(current),
+            }
+
+            debug_assert!(
+                chain.len() <= lts.num_of_states(),
+                "The confluent transitions of state {state_index} contain a cycle"
+            );
+        }
+
+        let last = representative[current];
+        for state_index in chain.drain(..) {
+            representative[state_index] = last;
+        }
+    }
+    let representative =
+        |state_index: StateIndex| representative[state_index].expect("Every state has a representative");
+
+    // Keep the outgoing transitions of the representatives that are reachable from the initial state.
+    let mut builder = LtsBuilderFast::new(lts.labels().into(), Vec::new());
+    let tau_label = L::Label::tau_label();
+    let mut state_map: Vec<Option<StateIndex>> = vec![None; lts.num_of_states()];
+    let mut queue = VecDeque::new();
+
+    let initial_state = representative(lts.initial_state_index());
+    state_map[initial_state] = Some(StateIndex::new(0));
+    queue.push_back(initial_state);
+    let mut num_of_states = 1;
+
+    while let Some(state_index) = queue.pop_front() {
+        let from = state_map[state_index].expect("Queued states are numbered");
+
+        for transition in lts.outgoing_transitions(state_index) {
+            let to_state = representative(transition.to);
+            let hidden = lts.is_hidden_label(transition.label);
+            if hidden && to_state == state_index {
+                continue;
+            }
+
+            let to = *state_map[to_state].get_or_insert_with(|| {
+                queue.push_back(to_state);
+                num_of_states += 1;
+                StateIndex::new(num_of_states - 1)
+            });
+
+            let label = if hidden {
+                &tau_label
+            } else {
+                &lts.labels()[transition.label]
+            };
+            builder.add_transition(from, label, to);
+        }
+    }
+
+    builder.require_num_of_states(num_of_states);
+    builder.finish(StateIndex::new(0), true)
+}
+
+/// Returns true iff the tau transition from `state_index` to `target` satisfies
+/// the confluence condition of [tau_confluent_transitions] with respect to the
+/// current set of confluent transitions.
+fn is_confluent(lts: &impl LTS, confluent: &[Vec<StateIndex>], state_index: StateIndex, target: StateIndex) -> bool {
+    lts.outgoing_transitions(state_index).all(|other| {
+        let hidden = lts.is_hidden_label(other.label);
+        if hidden && other.to == target {
+            return true;
+        }
+
+        let other_confluent = hidden && confluent[state_index].binary_search(&other.to).is_ok();
+        lts.outgoing_transitions(target).any(|closing| {
+            closing.label == other.label
+                && (closing.to == other.to || confluent[other.to].binary_search(&closing.to).is_ok())
+                && (!other_confluent || confluent[target].binary_search(&closing.to).is_ok())
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use merc_io::DumpFiles;
+    use merc_lts::LabelIndex;
+    use merc_lts::random_lts;
+    use merc_lts::write_aut;
+    use merc_utilities::random_test;
+    use test_log::test;
+
+    use crate::Equivalence;
+    use crate::compare_lts;
+
+    use super::*;
+
+    #[test]
+    #[cfg_attr(miri, ignore)] // Miri is too slow
+    fn test_random_confluence_reduction() {
+        random_test(100, |rng| {
+            let mut files = DumpFiles::new("test_random_confluence_reduction");
+
+            let lts = random_lts(rng, 10, 3, 3);
+            files.dump("input.aut", |f| write_aut(f, &lts)).unwrap();
+
+            let reduced = confluence_reduction(lts.clone(), &mut Timing::new()).unwrap();
+            files.dump("reduced.aut", |f| write_aut(f, &reduced)).unwrap();
+
+            assert!(reduced.num_of_states() <= lts.num_of_states());
+            assert!(
+                compare_lts(Equivalence::BranchingBisim, lts, reduced, &mut Timing::new()).unwrap(),
+                "The confluence reduction should preserve branching bisimulation"
+            );
+        });
+    }
+
+    #[test]
+    fn test_confluent_diamond() {
+        // The tau and a transitions of state 0 commute, so state 0 is merged with state 1 and state 2 with state 3.
+        let transitions = [(0, 0, 1), (0, 1, 2), (1, 1, 3), (2, 0, 3), (3, 2, 3)]
+            .map(|(from, label, to)| (StateIndex::new(from), LabelIndex::new(label), StateIndex::new(to)));
+
+        let lts = LabelledTransitionSystem::new(
+            StateIndex::new(0),
+            None,
+            || transitions.iter().cloned(),
+            vec!["i".to_string(), "a".to_string(), "b".to_string()],
+        );
+
+        let confluent = tau_confluent_transitions(&lts).unwrap();
+        assert_eq!(confluent[0], vec![StateIndex::new(1)]);
+        assert_eq!(confluent[2], vec![StateIndex::new(3)]);
+
+        let reduced = tau_prioritisation(&lts, &confluent);
+        assert_eq!(reduced.num_of_states(), 2);
+        assert_eq!(reduced.num_of_transitions(), 2);
+    }
+
+    #[test]
+    fn test_non_confluent_choice() {
+        // The tau transition of state 0 disables the a transition, so it is not confluent.
+        let transitions = [(0, 0, 1), (0, 1, 2), (1, 2, 2)]
+            .map(|(from, label, to)| (StateIndex::new(from), LabelIndex::new(label), StateIndex::new(to)));
+
+        let lts = LabelledTransitionSystem::new(
+            StateIndex::new(0),
+            None,
+            || transitions.iter().cloned(),
+            vec!["i".to_string(), "a".to_string(), "b".to_string()],
+        );
+
+        let confluent = tau_confluent_transitions(&lts).unwrap();
+        assert!(confluent[0].is_empty());
+
+        let reduced = tau_prioritisation(&lts, &confluent);
+        assert_eq!(reduced.num_of_states(), 3);
+        assert_eq!(reduced.num_of_transitions(), 3);
+    }
+}
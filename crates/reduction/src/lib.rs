@@ -2,24 +2,34 @@
 
 mod block_partition;
 mod compare;
+mod confluence;
 mod indexed_partition;
+mod normalize;
 mod quotient;
 mod reduce;
+mod registry;
 mod scc_decomposition;
 mod signature_refinement;
 mod signatures;
+mod simulation;
 mod simple_block_partition;
 mod sort_topological;
+mod splitting_strategy;
 mod weak_bisimulation;
 
 pub use block_partition::*;
 pub use compare::*;
+pub use confluence::*;
 pub use indexed_partition::*;
+pub use normalize::*;
 pub use quotient::*;
 pub use reduce::*;
+pub use registry::*;
 pub use scc_decomposition::*;
 pub use signature_refinement::*;
 pub use signatures::*;
+pub use simulation::*;
 pub use simple_block_partition::*;
 pub use sort_topological::*;
+pub use splitting_strategy::*;
 pub use weak_bisimulation::*;
@@ -0,0 +1,126 @@
+#![forbid(unsafe_code)]
+
+use std::collections::VecDeque;
+
+use merc_lts::LTS;
+use merc_lts::LabelIndex;
+use merc_lts::LabelledTransitionSystem;
+use merc_lts::StateIndex;
+use merc_lts::TransitionLabel;
+
+/// Returns a normalised copy of the given LTS, such that the output for the
+/// same input is always identical and can be compared textually.
+///
+/// # Details
+///
+/// The labels are sorted, with the hidden label first. The states are
+/// renumbered in breadth first order starting from the initial state, which
+/// becomes state zero, where successors are visited in the order of their
+/// label and original index. Unreachable states are numbered afterwards in
+/// their original order. Finally, the outgoing transitions of every state are
+/// sorted and duplicate transitions are removed.
+pub fn normalize_lts<L: LTS>(lts: &L) -> LabelledTransitionSystem<L::Label> {
+    // Sort the labels, placing the hidden label first.
+    let mut label_order: Vec<usize> = (0..lts.labels().len()).collect();
+    label_order.sort_by_key(|&index| {
+        let label = &lts.labels()[index];
+        (!label.is_tau_label(), label)
+    });
+
+    let mut label_map = vec![LabelIndex::new(0); lts.labels().len()];
+    for (new_index, &old_index) in label_order.iter().enumerate() {
+        label_map[old_index] = LabelIndex::new(new_index);
+    }
+    let labels = label_order.iter().map(|&index| lts.labels()[index].clone()).collect();
+
+    // Renumber the states in breadth first order, starting with the initial state.
+    let mut state_map: Vec<Option<StateIndex>> = vec![None; lts.num_of_states()];
+    let mut num_of_visited = 0;
+    let mut queue = VecDeque::new();
+
+    for root in std::iter::once(lts.initial_state_index()).chain(lts.iter_states()) {
+        if state_map[root].is_some() {
+            continue;
+        }
+
+        state_map[root] = Some(StateIndex::new(num_of_visited));
+        num_of_visited += 1;
+        queue.push_back(root);
+
+        while let Some(state_index) = queue.pop_front() {
+            let mut successors: Vec<(LabelIndex, StateIndex)> = lts
+                .outgoing_transitions(state_index)
+                .map(|transition| (label_map[transition.label], transition.to))
+                .collect();
+            successors.sort_unstable();
+
+            for (_, to) in successors {
+                if state_map[to].is_none() {
+                    state_map[to] = Some(StateIndex::new(num_of_visited));
+                    num_of_visited += 1;
+                    queue.push_back(to);
+                }
+            }
+        }
+    }
+
+    let state_map: Vec<StateIndex> = state_map
+        .into_iter()
+        .map(|state| state.expect("Every state has been visited"))
+        .collect();
+
+    // Collect the renumbered transitions, sorted per state and without duplicates.
+    let mut transitions = Vec::with_capacity(lts.num_of_transitions());
+    for state_index in lts.iter_states() {
+        let from = state_map[state_index];
+        for transition in lts.outgoing_transitions(state_index) {
+            transitions.push((from, label_map[transition.label], state_map[transition.to]));
+        }
+    }
+    transitions.sort_unstable();
+    transitions.dedup();
+
+    LabelledTransitionSystem::new(
+        StateIndex::new(0),
+        Some(lts.num_of_states()),
+        || transitions.iter().cloned(),
+        labels,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use merc_lts::LTS;
+    use merc_lts::random_lts;
+    use merc_utilities::Timing;
+    use merc_utilities::random_test;
+
+    use crate::Equivalence;
+    use crate::compare_lts;
+
+    use super::*;
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_random_normalize_lts() {
+        random_test(100, |rng| {
+            let lts = random_lts(rng, 10, 3, 3);
+            let mut timing = Timing::new();
+
+            let result = normalize_lts(&lts);
+            assert_eq!(result.initial_state_index(), StateIndex::new(0));
+            assert_eq!(result.num_of_states(), lts.num_of_states());
+
+            // The transitions of every state are sorted and unique.
+            for state_index in result.iter_states() {
+                let transitions: Vec<_> = result
+                    .outgoing_transitions(state_index)
+                    .map(|transition| (transition.label, transition.to))
+                    .collect();
+                assert!(transitions.windows(2).all(|pair| pair[0] < pair[1]));
+            }
+
+            assert!(compare_lts(Equivalence::StrongBisim, lts, result, &mut timing).unwrap());
+        })
+    }
+}
@@ -2,20 +2,26 @@
 
 use merc_lts::LTS;
 use merc_lts::LabelledTransitionSystem;
+use merc_utilities::MercError;
 use merc_utilities::Timing;
 
+use crate::SimulationType;
 use crate::branching_bisim_sigref;
 use crate::branching_bisim_sigref_naive;
+use crate::normalize_lts;
 use crate::quotient_lts_block;
 use crate::quotient_lts_naive;
+use crate::simulation_preorder;
 use crate::strong_bisim_sigref;
 use crate::strong_bisim_sigref_naive;
 use crate::weak_bisim_sigref_naive;
 use crate::weak_bisimulation;
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
 pub enum Equivalence {
+    /// Does not reduce the LTS, but only normalises it, see [normalize_lts].
+    None,
     /// Partition based refinement algorithms.
     WeakBisim,
     /// Various signature based reduction algorithms.
@@ -24,43 +30,118 @@ pub enum Equivalence {
     StrongBisimNaive,
     BranchingBisim,
     BranchingBisimNaive,
+    /// Partition-relation pair based simulation algorithms, see [simulation_preorder].
+    Simulation,
+    ReadySimulation,
+    CoupledSimulation,
+    /// Checks whether two LTSs are isomorphic, see [merc_lts::canonical_form].
+    /// Can only be used for comparison.
+    Isomorphism,
+}
+
+/// A preprocessing step that is applied before the reduction, see [reduce_lts].
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+pub enum Preprocess {
+    /// Gives priority to tau-confluent transitions, see [crate::confluence_reduction].
+    /// Only preserves branching and weak bisimulation, and coupled simulation.
+    Confluence,
+}
+
+impl Equivalence {
+    /// All equivalences, in the order of their declaration.
+    pub const ALL: [Equivalence; 11] = [
+        Equivalence::None,
+        Equivalence::WeakBisim,
+        Equivalence::WeakBisimSigref,
+        Equivalence::StrongBisim,
+        Equivalence::StrongBisimNaive,
+        Equivalence::BranchingBisim,
+        Equivalence::BranchingBisimNaive,
+        Equivalence::Simulation,
+        Equivalence::ReadySimulation,
+        Equivalence::CoupledSimulation,
+        Equivalence::Isomorphism,
+    ];
+
+    /// Returns true iff the given preprocessing step preserves this equivalence.
+    pub fn is_preserved_by(&self, preprocess: Preprocess) -> bool {
+        match preprocess {
+            Preprocess::Confluence => !matches!(
+                self,
+                Equivalence::StrongBisim
+                    | Equivalence::StrongBisimNaive
+                    | Equivalence::Simulation
+                    | Equivalence::ReadySimulation
+                    | Equivalence::Isomorphism
+            ),
+        }
+    }
 }
 
 /// Reduces the given LTS modulo the given equivalence using signature refinement
-pub fn reduce_lts<L: LTS>(lts: L, equivalence: Equivalence, timing: &mut Timing) -> LabelledTransitionSystem<L::Label> {
+pub fn reduce_lts<L: LTS>(
+    lts: L,
+    equivalence: Equivalence,
+    timing: &mut Timing,
+) -> Result<LabelledTransitionSystem<L::Label>, MercError> {
     let (result, mut timer) = match equivalence {
+        Equivalence::None => {
+            let normalize_time = timing.start("normalize");
+            (normalize_lts(&lts), normalize_time)
+        }
         Equivalence::WeakBisim => {
-            let (lts, partition) = weak_bisimulation(lts, timing);
+            let (lts, partition) = weak_bisimulation(lts, timing)?;
             let quotient_time = timing.start("quotient");
             (quotient_lts_naive(&lts, &partition, true), quotient_time)
         }
         Equivalence::WeakBisimSigref => {
-            let (lts, partition) = weak_bisim_sigref_naive(lts, timing);
+            let (lts, partition) = weak_bisim_sigref_naive(lts, timing)?;
             let quotient_time = timing.start("quotient");
             (quotient_lts_naive(&lts, &partition, true), quotient_time)
         }
         Equivalence::StrongBisim => {
-            let (lts, partition) = strong_bisim_sigref(lts, timing);
+            let (lts, partition) = strong_bisim_sigref(lts, timing)?;
             let quotient_time = timing.start("quotient");
             (quotient_lts_block::<_, false>(&lts, &partition), quotient_time)
         }
         Equivalence::StrongBisimNaive => {
-            let (lts, partition) = strong_bisim_sigref_naive(lts, timing);
+            let (lts, partition) = strong_bisim_sigref_naive(lts, timing)?;
             let quotient_time = timing.start("quotient");
             (quotient_lts_naive(&lts, &partition, false), quotient_time)
         }
         Equivalence::BranchingBisim => {
-            let (lts, partition) = branching_bisim_sigref(lts, timing);
+            let (lts, partition) = branching_bisim_sigref(lts, timing)?;
             let quotient_time = timing.start("quotient");
             (quotient_lts_block::<_, true>(&lts, &partition), quotient_time)
         }
         Equivalence::BranchingBisimNaive => {
-            let (lts, partition) = branching_bisim_sigref_naive(lts, timing);
+            let (lts, partition) = branching_bisim_sigref_naive(lts, timing)?;
             let quotient_time = timing.start("quotient");
             (quotient_lts_naive(&lts, &partition, true), quotient_time)
         }
+        Equivalence::Simulation => {
+            let preorder = simulation_preorder(&lts, SimulationType::Simulation, timing)?;
+            let quotient_time = timing.start("quotient");
+            (quotient_lts_naive(&lts, preorder.partition(), false), quotient_time)
+        }
+        Equivalence::ReadySimulation => {
+            let preorder = simulation_preorder(&lts, SimulationType::ReadySimulation, timing)?;
+            let quotient_time = timing.start("quotient");
+            (quotient_lts_naive(&lts, preorder.partition(), false), quotient_time)
+        }
+        Equivalence::CoupledSimulation => {
+            let preorder = simulation_preorder(&lts, SimulationType::CoupledSimulation, timing)?;
+            let quotient_time = timing.start("quotient");
+            (quotient_lts_naive(&lts, preorder.partition(), true), quotient_time)
+        }
+        Equivalence::Isomorphism => {
+            return Err(MercError::unsupported(
+                "Isomorphism can only be used for comparison, not for reduction.",
+            ));
+        }
     };
 
     timer.finish();
-    result
+    Ok(result)
 }
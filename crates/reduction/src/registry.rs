@@ -0,0 +1,193 @@
+#![forbid(unsafe_code)]
+
+use merc_lts::LabelledTransitionSystem;
+use merc_lts::LtsVariant;
+use merc_utilities::MercError;
+use merc_utilities::Timing;
+
+use crate::Equivalence;
+use crate::Preprocess;
+use crate::reduce_lts;
+
+/// A reduction algorithm that can be registered in a [ReductionRegistry], such
+/// that tools can select it by name at runtime.
+///
+/// # Details
+///
+/// This allows crates outside of this repository to provide reductions modulo
+/// new equivalences without adding a variant to [Equivalence]. Every
+/// [Equivalence] is itself a reduction algorithm, see
+/// [ReductionRegistry::with_builtin].
+pub trait ReductionAlgorithm: Send + Sync {
+    /// The unique name by which the algorithm is selected, for example `strong-bisim`.
+    fn name(&self) -> &'static str;
+
+    /// A short description of the algorithm, which is shown in the help of tools.
+    fn description(&self) -> &'static str;
+
+    /// Returns true iff the given preprocessing step preserves the equivalence
+    /// that is reduced modulo. This is conservatively false by default.
+    fn is_preserved_by(&self, _preprocess: Preprocess) -> bool {
+        false
+    }
+
+    /// Reduces the given LTS modulo the equivalence of this algorithm.
+    fn reduce(&self, lts: LtsVariant, timing: &mut Timing) -> Result<LabelledTransitionSystem<String>, MercError>;
+}
+
+impl ReductionAlgorithm for Equivalence {
+    fn name(&self) -> &'static str {
+        match self {
+            Equivalence::None => "none",
+            Equivalence::WeakBisim => "weak-bisim",
+            Equivalence::WeakBisimSigref => "weak-bisim-sigref",
+            Equivalence::StrongBisim => "strong-bisim",
+            Equivalence::StrongBisimNaive => "strong-bisim-naive",
+            Equivalence::BranchingBisim => "branching-bisim",
+            Equivalence::BranchingBisimNaive => "branching-bisim-naive",
+            Equivalence::Simulation => "simulation",
+            Equivalence::ReadySimulation => "ready-simulation",
+            Equivalence::CoupledSimulation => "coupled-simulation",
+            Equivalence::Isomorphism => "isomorphism",
+        }
+    }
+
+    fn description(&self) -> &'static str {
+        match self {
+            Equivalence::None => "Only normalises the LTS",
+            Equivalence::WeakBisim => "Weak bisimulation using partition refinement",
+            Equivalence::WeakBisimSigref => "Weak bisimulation using signature refinement",
+            Equivalence::StrongBisim => "Strong bisimulation using signature refinement",
+            Equivalence::StrongBisimNaive => "Strong bisimulation using naive signature refinement",
+            Equivalence::BranchingBisim => "Branching bisimulation using signature refinement",
+            Equivalence::BranchingBisimNaive => "Branching bisimulation using naive signature refinement",
+            Equivalence::Simulation => "Simulation equivalence",
+            Equivalence::ReadySimulation => "Ready simulation equivalence",
+            Equivalence::CoupledSimulation => "Coupled simulation equivalence",
+            Equivalence::Isomorphism => "Isomorphism, which can only be used for comparison",
+        }
+    }
+
+    fn is_preserved_by(&self, preprocess: Preprocess) -> bool {
+        Equivalence::is_preserved_by(self, preprocess)
+    }
+
+    fn reduce(&self, lts: LtsVariant, timing: &mut Timing) -> Result<LabelledTransitionSystem<String>, MercError> {
+        reduce_lts(lts, *self, timing)
+    }
+}
+
+/// A collection of reduction algorithms that can be selected by their name.
+#[derive(Default)]
+pub struct ReductionRegistry {
+    algorithms: Vec<Box<dyn ReductionAlgorithm>>,
+}
+
+impl ReductionRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a registry that contains every [Equivalence] that can be used for reduction.
+    pub fn with_builtin() -> Self {
+        let mut registry = Self::new();
+        for equivalence in Equivalence::ALL
+            .into_iter()
+            .filter(|equivalence| *equivalence != Equivalence::Isomorphism)
+        {
+            registry
+                .register(equivalence)
+                .expect("The names of the equivalences are unique");
+        }
+
+        registry
+    }
+
+    /// Registers the given algorithm, which fails when an algorithm with the same name is already registered.
+    pub fn register(&mut self, algorithm: impl ReductionAlgorithm + 'static) -> Result<(), MercError> {
+        if self.get(algorithm.name()).is_some() {
+            return Err(MercError::unsupported(format!(
+                "A reduction algorithm named {} is already registered",
+                algorithm.name()
+            )));
+        }
+
+        self.algorithms.push(Box::new(algorithm));
+        Ok(())
+    }
+
+    /// Returns the algorithm with the given name, if it has been registered.
+    pub fn get(&self, name: &str) -> Option<&dyn ReductionAlgorithm> {
+        self.algorithms
+            .iter()
+            .find(|algorithm| algorithm.name() == name)
+            .map(|algorithm| algorithm.as_ref())
+    }
+
+    /// Returns an iterator over all registered algorithms, in the order of registration.
+    pub fn iter(&self) -> impl Iterator<Item = &dyn ReductionAlgorithm> {
+        self.algorithms.iter().map(|algorithm| algorithm.as_ref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use merc_lts::DEFAULT_ISOMORPHISM_LIMIT;
+    use merc_lts::is_isomorphic;
+    use merc_lts::random_lts;
+    use merc_utilities::random_test;
+
+    use super::*;
+
+    /// An algorithm that does not reduce the LTS at all.
+    struct Identity;
+
+    impl ReductionAlgorithm for Identity {
+        fn name(&self) -> &'static str {
+            "identity"
+        }
+
+        fn description(&self) -> &'static str {
+            "Returns the LTS as is"
+        }
+
+        fn reduce(&self, lts: LtsVariant, _timing: &mut Timing) -> Result<LabelledTransitionSystem<String>, MercError> {
+            Ok(lts.into_string_lts())
+        }
+    }
+
+    #[test]
+    fn test_register_reduction_algorithm() {
+        let mut registry = ReductionRegistry::with_builtin();
+        assert_eq!(registry.iter().count(), Equivalence::ALL.len() - 1);
+        assert!(registry.get("isomorphism").is_none());
+
+        registry.register(Identity).unwrap();
+        assert!(registry.get("identity").is_some());
+        assert!(registry.get("unknown").is_none());
+
+        // Names must be unique.
+        assert!(registry.register(Identity).is_err());
+        assert!(registry.register(Equivalence::StrongBisim).is_err());
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_random_registered_reduction() {
+        let registry = ReductionRegistry::with_builtin();
+
+        random_test(100, |rng| {
+            let lts = random_lts(rng, 10, 3, 3);
+
+            let expected = reduce_lts(lts.clone(), Equivalence::StrongBisim, &mut Timing::new()).unwrap();
+            let result = registry
+                .get("strong-bisim")
+                .unwrap()
+                .reduce(LtsVariant::Explicit(lts), &mut Timing::new())
+                .unwrap();
+
+            assert!(is_isomorphic(&result, &expected, DEFAULT_ISOMORPHISM_LIMIT).unwrap());
+        });
+    }
+}
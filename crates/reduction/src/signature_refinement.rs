@@ -1,10 +1,8 @@
 use std::mem::swap;
 
 use bumpalo::Bump;
-use log::debug;
-use log::info;
 use log::trace;
-use merc_io::TimeProgress;
+use merc_io::Progress;
 use merc_lts::IncomingTransitions;
 use merc_lts::LTS;
 use merc_lts::LabelIndex;
@@ -13,15 +11,20 @@ use merc_lts::StateIndex;
 use rustc_hash::FxHashMap;
 use rustc_hash::FxHashSet;
 
+use merc_utilities::MercError;
 use merc_utilities::Timing;
+use merc_utilities::check_cancelled;
 
 use crate::BlockIndex;
 use crate::BlockPartition;
 use crate::BlockPartitionBuilder;
+use crate::DirtyBlock;
 use crate::IndexedPartition;
+use crate::LifoStrategy;
 use crate::Partition;
 use crate::Signature;
 use crate::SignatureBuilder;
+use crate::SplittingStrategy;
 use crate::branching_bisim_signature;
 use crate::branching_bisim_signature_inductive;
 use crate::branching_bisim_signature_sorted;
@@ -32,7 +35,17 @@ use crate::weak_bisim_signature_sorted;
 use crate::weak_bisim_signature_sorted_taus;
 
 /// Computes a strong bisimulation partitioning using signature refinement
-pub fn strong_bisim_sigref<L: LTS>(lts: L, timing: &mut Timing) -> (L, BlockPartition) {
+pub fn strong_bisim_sigref<L: LTS>(lts: L, timing: &mut Timing) -> Result<(L, BlockPartition), MercError> {
+    strong_bisim_sigref_with_strategy(lts, &mut LifoStrategy::default(), timing)
+}
+
+/// Computes a strong bisimulation partitioning using signature refinement,
+/// where the dirty blocks are split in the order of the given strategy.
+pub fn strong_bisim_sigref_with_strategy<L: LTS>(
+    lts: L,
+    strategy: &mut impl SplittingStrategy,
+    timing: &mut Timing,
+) -> Result<(L, BlockPartition), MercError> {
     let mut timepre = timing.start("preprocess");
     let incoming = IncomingTransitions::new(&lts);
     timepre.finish();
@@ -41,32 +54,43 @@ pub fn strong_bisim_sigref<L: LTS>(lts: L, timing: &mut Timing) -> (L, BlockPart
     let partition = signature_refinement::<_, _, false>(
         &lts,
         &incoming,
+        strategy,
         |state_index, partition, _, builder| {
             strong_bisim_signature(state_index, &lts, partition, builder);
         },
         |_, _| None,
-    );
+    )?;
     time.finish();
 
-    (lts, partition)
+    Ok((lts, partition))
 }
 
 /// Computes a strong bisimulation partitioning using signature refinement
-pub fn strong_bisim_sigref_naive<L: LTS>(lts: L, timing: &mut Timing) -> (L, IndexedPartition) {
+pub fn strong_bisim_sigref_naive<L: LTS>(lts: L, timing: &mut Timing) -> Result<(L, IndexedPartition), MercError> {
     let mut time = timing.start("reduction");
     let partition = signature_refinement_naive::<_, _, false>(&lts, |state_index, partition, _, builder| {
         strong_bisim_signature(state_index, &lts, partition, builder);
-    });
+    })?;
 
     time.finish();
-    (lts, partition)
+    Ok((lts, partition))
 }
 
 /// Computes a branching bisimulation partitioning using signature refinement
 pub fn branching_bisim_sigref<L: LTS>(
     lts: L,
     timing: &mut Timing,
-) -> (LabelledTransitionSystem<L::Label>, BlockPartition) {
+) -> Result<(LabelledTransitionSystem<L::Label>, BlockPartition), MercError> {
+    branching_bisim_sigref_with_strategy(lts, &mut LifoStrategy::default(), timing)
+}
+
+/// Computes a branching bisimulation partitioning using signature refinement,
+/// where the dirty blocks are split in the order of the given strategy.
+pub fn branching_bisim_sigref_with_strategy<L: LTS>(
+    lts: L,
+    strategy: &mut impl SplittingStrategy,
+    timing: &mut Timing,
+) -> Result<(LabelledTransitionSystem<L::Label>, BlockPartition), MercError> {
     let mut timepre = timing.start("preprocess");
     let preprocessed_lts = preprocess_branching(lts);
     let incoming = IncomingTransitions::new(&preprocessed_lts);
@@ -80,6 +104,7 @@ pub fn branching_bisim_sigref<L: LTS>(
     let partition = signature_refinement::<_, _, true>(
         &preprocessed_lts,
         &incoming,
+        strategy,
         |state_index, partition, state_to_key, builder| {
             branching_bisim_signature_inductive(state_index, &preprocessed_lts, partition, state_to_key, builder);
 
@@ -119,19 +144,19 @@ pub fn branching_bisim_sigref<L: LTS>(
 
             None
         },
-    );
+    )?;
 
     time.finish();
 
     // Combine the SCC partition with the branching bisimulation partition.
-    (preprocessed_lts, partition)
+    Ok((preprocessed_lts, partition))
 }
 
 /// Computes a branching bisimulation partitioning using signature refinement without dirty blocks.
 pub fn branching_bisim_sigref_naive<L: LTS>(
     lts: L,
     timing: &mut Timing,
-) -> (LabelledTransitionSystem<L::Label>, IndexedPartition) {
+) -> Result<(LabelledTransitionSystem<L::Label>, IndexedPartition), MercError> {
     let mut timepre = timing.start("preprocess");
     let preprocessed_lts = preprocess_branching(lts);
     timepre.finish();
@@ -166,17 +191,17 @@ pub fn branching_bisim_sigref_naive<L: LTS>(
                 );
             }
         },
-    );
+    )?;
     time.finish();
 
-    (preprocessed_lts, partition)
+    Ok((preprocessed_lts, partition))
 }
 
 /// Computes a branching bisimulation partitioning using signature refinement without dirty blocks.
 pub fn weak_bisim_sigref_naive<L: LTS>(
     lts: L,
     timing: &mut Timing,
-) -> (LabelledTransitionSystem<L::Label>, IndexedPartition) {
+) -> Result<(LabelledTransitionSystem<L::Label>, IndexedPartition), MercError> {
     let mut timepre = timing.start("preprocess");
     let preprocessed_lts = preprocess_branching(lts);
     timepre.finish();
@@ -188,10 +213,10 @@ pub fn weak_bisim_sigref_naive<L: LTS>(
         |state_index, partition, state_to_signature, builder| {
             weak_bisim_signature_sorted(state_index, &preprocessed_lts, partition, state_to_signature, builder)
         },
-    );
+    )?;
     time.finish();
 
-    (preprocessed_lts, partition)
+    Ok((preprocessed_lts, partition))
 }
 
 /// General signature refinement algorithm that accepts an arbitrary signature
@@ -202,9 +227,10 @@ pub fn weak_bisim_sigref_naive<L: LTS>(
 fn signature_refinement<F, G, const BRANCHING: bool>(
     lts: &impl LTS,
     incoming: &IncomingTransitions,
+    strategy: &mut impl SplittingStrategy,
     mut signature: F,
     mut renumber: G,
-) -> BlockPartition
+) -> Result<BlockPartition, MercError>
 where
     F: FnMut(StateIndex, &BlockPartition, &[BlockIndex], &mut SignatureBuilder),
     G: FnMut(&[(LabelIndex, BlockIndex)], &Vec<Signature>) -> Option<BlockIndex>,
@@ -226,18 +252,26 @@ where
     // Refine partitions until stable.
     let mut iteration = 0usize;
     let mut states = Vec::new();
+    let mut new_blocks = Vec::new();
+
+    // The size of the signature that formed every block, used by the splitting strategy.
+    let mut signature_sizes = vec![0];
+
+    // The size of the last computed signature of every state.
+    let mut state_signature_sizes = vec![0; lts.num_of_states()];
 
     // Used to keep track of dirty blocks.
-    let mut worklist = vec![BlockIndex::new(0)];
+    strategy.push(DirtyBlock {
+        block: BlockIndex::new(0),
+        size: lts.num_of_states(),
+        signature_size: 0,
+    });
 
-    let progress = TimeProgress::new(
-        |(iteration, blocks)| {
-            info!("Iteration {iteration}, found {blocks} blocks...");
-        },
-        5,
-    );
+    let progress = Progress::new("Signature refinement", "blocks", None).with_interval(5);
+
+    while let Some(block_index) = strategy.pop() {
+        check_cancelled()?;
 
-    while let Some(block_index) = worklist.pop() {
         // Clear the current partition to start the next blocks.
         id.clear();
 
@@ -266,8 +300,12 @@ where
         // This is a workaround for a data race in bumpalo for zero-sized slices.
         let empty_slice: &[(LabelIndex, BlockIndex)] = &[];
 
-        for new_block_index in
-            partition.partition_marked_with(block_index, &mut split_builder, |state_index, partition| {
+        // The new blocks are collected first, since the partitioner borrows the signatures mutably.
+        new_blocks.clear();
+        new_blocks.extend(partition.partition_marked_with(
+            block_index,
+            &mut split_builder,
+            |state_index, partition| {
                 signature(state_index, partition, &state_to_key, &mut builder);
 
                 // Compute the signature of a single state
@@ -290,16 +328,22 @@ where
 
                 // (branching) Keep track of the signature for every block in the next partition.
                 state_to_key[state_index] = index;
+                state_signature_sizes[state_index] = builder.len();
 
                 trace!("State {state_index} signature {builder:?} index {index}");
                 index
-            })
-        {
+            },
+        ));
+
+        for &new_block_index in &new_blocks {
             if block_index != new_block_index {
                 // If this is a new block, mark the incoming states as dirty
                 states.clear();
                 states.extend(partition.iter_block(new_block_index));
 
+                signature_sizes.resize(partition.num_of_blocks(), 0);
+                signature_sizes[new_block_index] = state_signature_sizes[states[0]];
+
                 for &state_index in &states {
                     for transition in incoming.incoming_transitions(state_index) {
                         if BRANCHING {
@@ -311,7 +355,11 @@ where
 
                                 if !partition.block(other_block).has_marked() {
                                     // If block was not already marked then add it to the worklist.
-                                    worklist.push(other_block);
+                                    strategy.push(DirtyBlock {
+                                        block: other_block,
+                                        size: partition.block(other_block).len(),
+                                        signature_size: signature_sizes[other_block],
+                                    });
                                 }
 
                                 partition.mark_element(transition.to);
@@ -322,7 +370,11 @@ where
 
                             if !partition.block(other_block).has_marked() {
                                 // If block was not already marked then add it to the worklist.
-                                worklist.push(other_block);
+                                strategy.push(DirtyBlock {
+                                    block: other_block,
+                                    size: partition.block(other_block).len(),
+                                    signature_size: signature_sizes[other_block],
+                                });
                             }
 
                             partition.mark_element(transition.to);
@@ -336,11 +388,12 @@ where
 
         iteration += 1;
 
-        progress.print((iteration, partition.num_of_blocks()));
+        progress.print(partition.num_of_blocks());
     }
+    progress.finish(partition.num_of_blocks());
 
     trace!("Refinement partition {partition}");
-    partition
+    Ok(partition)
 }
 
 /// General signature refinement algorithm that accepts an arbitrary signature
@@ -348,7 +401,10 @@ where
 /// The signature function is called for each state and should fill the
 /// signature builder with the signature of the state. It consists of the
 /// current partition, the signatures per state for the next partition.
-fn signature_refinement_naive<F, L: LTS, const WEAK: bool>(lts: &L, mut signature: F) -> IndexedPartition
+fn signature_refinement_naive<F, L: LTS, const WEAK: bool>(
+    lts: &L,
+    mut signature: F,
+) -> Result<IndexedPartition, MercError>
 where
     F: FnMut(StateIndex, &IndexedPartition, &Vec<Signature<'_>>, &mut SignatureBuilder),
 {
@@ -369,19 +425,15 @@ where
     let mut old_count = 1;
     let mut iteration = 0;
 
-    let progress = TimeProgress::new(
-        |(iteration, blocks)| {
-            debug!("Iteration {iteration}, found {blocks} blocks...",);
-        },
-        5,
-    );
+    let progress = Progress::new("Naive signature refinement", "blocks", None).with_interval(5);
 
     // This is a workaround for a data race in bumpalo for zero-sized slices.
     let empty_slice: &[(LabelIndex, BlockIndex)] = &[];
 
     while old_count != id.len() {
+        check_cancelled()?;
         old_count = id.len();
-        progress.print((iteration, old_count));
+        progress.print(old_count);
         swap(&mut partition, &mut next_partition);
 
         // Clear the current partition to start the next blocks.
@@ -460,7 +512,7 @@ where
         )),
         "The resulting partition is not a valid partition."
     );
-    partition
+    Ok(partition)
 }
 
 /// Returns true iff the given partition is a strong bisimulation partition
@@ -525,6 +577,8 @@ mod tests {
     use merc_utilities::Timing;
     use merc_utilities::random_test;
 
+    use crate::SplittingOrder;
+
     /// Returns true iff the partitions are equal, runs in O(n^2).
     fn equal_partitions(left: &impl Partition, right: &impl Partition) -> bool {
         // Check that states in the same block, have a single (unique) number in
@@ -575,8 +629,8 @@ mod tests {
             let lts = random_lts(rng, 10, 3, 3);
             let mut timing = Timing::new();
 
-            let (_result_lts, result_partition) = strong_bisim_sigref(lts.clone(), &mut timing);
-            let (_expected_lts, expected_partition) = strong_bisim_sigref_naive(lts, &mut timing);
+            let (_result_lts, result_partition) = strong_bisim_sigref(lts.clone(), &mut timing).unwrap();
+            let (_expected_lts, expected_partition) = strong_bisim_sigref_naive(lts, &mut timing).unwrap();
 
             // There is no preprocessing so this works.
             assert!(equal_partitions(&result_partition, &expected_partition));
@@ -590,14 +644,42 @@ mod tests {
             let lts = random_lts(rng, 10, 3, 3);
             let mut timing = Timing::new();
 
-            let (_result_lts, result_partition) = branching_bisim_sigref(lts.clone(), &mut timing);
-            let (_expected_lts, expected_partition) = branching_bisim_sigref_naive(lts, &mut timing);
+            let (_result_lts, result_partition) = branching_bisim_sigref(lts.clone(), &mut timing).unwrap();
+            let (_expected_lts, expected_partition) = branching_bisim_sigref_naive(lts, &mut timing).unwrap();
 
             // There is no preprocessing so this works.
             assert!(equal_partitions(&result_partition, &expected_partition));
         });
     }
 
+    #[test]
+    #[cfg_attr(miri, ignore)] // Miri is too slow
+    fn test_random_splitting_strategies() {
+        random_test(100, |rng| {
+            let lts = random_lts(rng, 10, 3, 3);
+            let mut timing = Timing::new();
+
+            let (_expected_lts, expected_partition) = strong_bisim_sigref_naive(lts.clone(), &mut timing).unwrap();
+            let (_, expected_branching) = branching_bisim_sigref(lts.clone(), &mut timing).unwrap();
+
+            for order in [
+                SplittingOrder::Lifo,
+                SplittingOrder::Fifo,
+                SplittingOrder::LargestBlockFirst,
+                SplittingOrder::SmallestSignatureFirst,
+            ] {
+                // The splitting order does not influence the resulting partition.
+                let (_, partition) =
+                    strong_bisim_sigref_with_strategy(lts.clone(), &mut order.strategy(), &mut timing).unwrap();
+                assert!(equal_partitions(&partition, &expected_partition), "{order:?}");
+
+                let (_, partition) =
+                    branching_bisim_sigref_with_strategy(lts.clone(), &mut order.strategy(), &mut timing).unwrap();
+                assert!(equal_partitions(&partition, &expected_branching), "{order:?}");
+            }
+        });
+    }
+
     /// Checks that the branching bisimulation partition is a refinement of the strong bisimulation partition.
     fn is_refinement(lts: &impl LTS, strong_partition: &impl Partition, branching_partition: &impl Partition) {
         for state_index in lts.iter_states() {
@@ -622,8 +704,10 @@ mod tests {
             let lts = random_lts(rng, 10, 3, 3);
             let mut timing = Timing::new();
 
-            let (preprocessed_lts, branching_partition) = branching_bisim_sigref_naive(lts, &mut timing);
-            let strong_partition = strong_bisim_sigref_naive(preprocessed_lts.clone(), &mut timing).1;
+            let (preprocessed_lts, branching_partition) = branching_bisim_sigref_naive(lts, &mut timing).unwrap();
+            let strong_partition = strong_bisim_sigref_naive(preprocessed_lts.clone(), &mut timing)
+                .unwrap()
+                .1;
             is_refinement(&preprocessed_lts, &strong_partition, &branching_partition);
         });
     }
@@ -635,8 +719,10 @@ mod tests {
             let lts = random_lts(rng, 10, 3, 3);
             let mut timing = Timing::new();
 
-            let (preprocessed_lts, weak_partition) = weak_bisim_sigref_naive(lts, &mut timing);
-            let strong_partition = strong_bisim_sigref_naive(preprocessed_lts.clone(), &mut timing).1;
+            let (preprocessed_lts, weak_partition) = weak_bisim_sigref_naive(lts, &mut timing).unwrap();
+            let strong_partition = strong_bisim_sigref_naive(preprocessed_lts.clone(), &mut timing)
+                .unwrap()
+                .1;
             is_refinement(&preprocessed_lts, &strong_partition, &weak_partition);
         });
     }
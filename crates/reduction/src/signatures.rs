@@ -11,6 +11,7 @@ use merc_lts::StateIndex;
 use rustc_hash::FxHashSet;
 
 use crate::BlockIndex;
+use crate::IndexedPartition;
 use crate::Partition;
 use crate::quotient_lts_naive;
 
@@ -299,10 +300,27 @@ pub fn weak_bisim_signature_sorted_taus(
 /// Perform the preprocessing necessary for branching bisimulation with the
 /// sorted signature see [branching_bisim_signature_sorted].
 pub fn preprocess_branching<L: LTS>(lts: L) -> LabelledTransitionSystem<L::Label> {
-    let scc_partition = tau_scc_decomposition(&lts);
-    let tau_loop_free_lts = quotient_lts_naive(&lts, &scc_partition, true);
+    let (_, tau_loop_free_lts, topological_permutation) = preprocess_branching_impl(&lts);
     drop(lts);
 
+    LabelledTransitionSystem::new_from_permutation(tau_loop_free_lts, |i| topological_permutation[i])
+}
+
+/// Returns the index of the given state in the LTS returned by
+/// [preprocess_branching], since the preprocessing renumbers the states.
+pub fn preprocessed_state_index(lts: &impl LTS, state_index: StateIndex) -> StateIndex {
+    let (scc_partition, _, topological_permutation) = preprocess_branching_impl(lts);
+    topological_permutation[scc_partition.block_number(state_index).value()]
+}
+
+/// Removes the tau-loops of the given LTS, and returns the SCC partition, the
+/// resulting LTS and the topological order of its tau transitions.
+fn preprocess_branching_impl<L: LTS>(
+    lts: &L,
+) -> (IndexedPartition, LabelledTransitionSystem<L::Label>, Vec<StateIndex>) {
+    let scc_partition = tau_scc_decomposition(lts);
+    let tau_loop_free_lts = quotient_lts_naive(lts, &scc_partition, true);
+
     // Sort the states according to the topological order of the tau transitions.
     let topological_permutation = sort_topological(
         &tau_loop_free_lts,
@@ -311,5 +329,5 @@ pub fn preprocess_branching<L: LTS>(lts: L) -> LabelledTransitionSystem<L::Label
     )
     .expect("After quotienting, the LTS should not contain cycles");
 
-    LabelledTransitionSystem::new_from_permutation(tau_loop_free_lts, |i| topological_permutation[i])
+    (scc_partition, tau_loop_free_lts, topological_permutation)
 }
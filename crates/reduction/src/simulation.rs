@@ -0,0 +1,806 @@
+//! Implements the simulation preorders by partition-relation pair refinement,
+//! following the algorithm by Ranzato and Tapparo.
+#![forbid(unsafe_code)]
+
+use bitvec::bitvec;
+use bitvec::order::Lsb0;
+use bitvec::vec::BitVec;
+use log::trace;
+use merc_io::Progress;
+use merc_lts::LTS;
+use merc_lts::StateIndex;
+use merc_utilities::MercError;
+use merc_utilities::Timing;
+use merc_utilities::check_cancelled;
+use rustc_hash::FxHashMap;
+
+use crate::BlockIndex;
+use crate::IndexedPartition;
+use crate::Partition;
+
+/// Type alias because we use bitvec for marking states and related blocks.
+type BitArray = BitVec<u64, Lsb0>;
+
+/// The simulation preorders that can be computed by [simulation_preorder].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SimulationType {
+    /// Every transition of the simulated state can be mimicked by the simulating state.
+    Simulation,
+    /// Simulation where related states additionally enable the same actions.
+    ReadySimulation,
+    /// Weak simulation where the simulating state can silently reach a state
+    /// that is simulated by the simulated state.
+    CoupledSimulation,
+}
+
+/// A preorder on the states of an LTS represented by a partition-relation
+/// pair. The partition groups the states that simulate each other, and the
+/// relation is a partial order on the blocks of this partition.
+pub struct SimulationPreorder {
+    partition: IndexedPartition,
+
+    /// Block `b` is simulated by block `d` iff `relation[b][d]` holds.
+    relation: Vec<BitArray>,
+}
+
+impl SimulationPreorder {
+    /// Returns the partition of the states into simulation equivalence classes.
+    pub fn partition(&self) -> &IndexedPartition {
+        &self.partition
+    }
+
+    /// Returns true iff the `left` state is simulated by the `right` state.
+    pub fn is_simulated_by(&self, left: StateIndex, right: StateIndex) -> bool {
+        self.relation[self.partition.block_number(left)][self.partition.block_number(right).value()]
+    }
+}
+
+/// Computes the given simulation preorder on the states of the LTS.
+///
+/// # Details
+///
+/// The states are partitioned into blocks together with a relation on these
+/// blocks, which over-approximates the simulation preorder. For every block `B`
+/// and action `a` the algorithm maintains the set `Remove_a(B)` of states with
+/// an `a` step, but without an `a` step into a block above `B`. Such a set is
+/// processed by splitting the partition such that it becomes a union of
+/// blocks, after which these blocks can no longer simulate the blocks with an
+/// `a` step into `B`. Counters for the number of steps into the blocks above
+/// every block are used to update the remove sets, which yields the algorithm
+/// of Ranzato and Tapparo for labelled transition systems.
+///
+/// For coupled simulation the weak transitions are used instead, and
+/// additionally every block `D` above a block `B` must silently reach a block
+/// below `B`. This is enforced by an outer refinement loop.
+pub fn simulation_preorder(
+    lts: &impl LTS,
+    simulation: SimulationType,
+    timing: &mut Timing,
+) -> Result<SimulationPreorder, MercError> {
+    let mut time_pre = timing.start("preprocess");
+    let steps = Steps::new(lts, simulation == SimulationType::CoupledSimulation);
+    time_pre.finish();
+
+    let mut time = timing.start("reduction");
+    let mut pair = PartitionRelationPair::new(&steps, simulation);
+
+    let progress = Progress::new("Simulation preorder", "blocks", None);
+
+    loop {
+        pair.refine(&steps, &progress)?;
+
+        if simulation != SimulationType::CoupledSimulation || !pair.couple(&steps) {
+            break;
+        }
+    }
+
+    trace!("Stable partition-relation pair with {} blocks", pair.blocks.len());
+    let result = pair.finish(lts.num_of_states());
+    time.finish();
+
+    Ok(result)
+}
+
+/// A step of a group, as stored at its target state.
+struct Step {
+    label: usize,
+    from: StateIndex,
+    group: usize,
+}
+
+/// The transitions that have to be mimicked, where the steps with the same
+/// source state and label form a group.
+struct Steps {
+    num_of_states: usize,
+    num_of_labels: usize,
+
+    /// The source state and label of every group.
+    groups: Vec<(StateIndex, usize)>,
+
+    /// The target states of every group.
+    targets: Vec<Vec<StateIndex>>,
+
+    /// The incoming steps of every state.
+    incoming: Vec<Vec<Step>>,
+
+    /// The label of the weak silent steps, when weak transitions are used.
+    silent: Option<usize>,
+}
+
+impl Steps {
+    /// Collects the transitions of the LTS, or the weak transitions when `weak`
+    /// is true. The weak silent steps include the empty step of every state.
+    fn new(lts: &impl LTS, weak: bool) -> Self {
+        let silent = weak.then(|| lts.num_of_labels());
+
+        let closures: Vec<Vec<StateIndex>> = if weak {
+            let mut visited = bitvec![u64, Lsb0; 0; lts.num_of_states()];
+            lts.iter_states()
+                .map(|state_index| {
+                    let mut closure = vec![state_index];
+                    visited.set(*state_index, true);
+
+                    let mut index = 0;
+                    while index < closure.len() {
+                        for transition in lts.outgoing_transitions(closure[index]) {
+                            if lts.is_hidden_label(transition.label) && !visited[*transition.to] {
+                                visited.set(*transition.to, true);
+                                closure.push(transition.to);
+                            }
+                        }
+                        index += 1;
+                    }
+
+                    for state_index in &closure {
+                        visited.set(**state_index, false);
+                    }
+                    closure
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        let mut groups = Vec::new();
+        let mut targets: Vec<Vec<StateIndex>> = Vec::new();
+        let mut incoming: Vec<Vec<Step>> = (0..lts.num_of_states()).map(|_| Vec::new()).collect();
+
+        let mut outgoing = Vec::new();
+        for state_index in lts.iter_states() {
+            outgoing.clear();
+            if let Some(silent) = silent {
+                for silent_index in &closures[*state_index] {
+                    outgoing.push((silent, *silent_index));
+                    for transition in lts.outgoing_transitions(*silent_index) {
+                        if !lts.is_hidden_label(transition.label) {
+                            for to in &closures[transition.to] {
+                                outgoing.push((transition.label.value(), *to));
+                            }
+                        }
+                    }
+                }
+            } else {
+                outgoing.extend(
+                    lts.outgoing_transitions(state_index)
+                        .map(|transition| (transition.label.value(), transition.to)),
+                );
+            }
+
+            outgoing.sort_unstable();
+            outgoing.dedup();
+
+            for (label, to) in &outgoing {
+                if groups.last() != Some(&(state_index, *label)) {
+                    groups.push((state_index, *label));
+                    targets.push(Vec::new());
+                }
+
+                let group = groups.len() - 1;
+                targets[group].push(*to);
+                incoming[*to].push(Step {
+                    label: *label,
+                    from: state_index,
+                    group,
+                });
+            }
+        }
+
+        Self {
+            num_of_states: lts.num_of_states(),
+            num_of_labels: lts.num_of_labels() + usize::from(weak),
+            groups,
+            targets,
+            incoming,
+            silent,
+        }
+    }
+}
+
+/// The partition of the states into blocks, with a relation on these blocks.
+struct PartitionRelationPair {
+    blocks: Vec<Vec<StateIndex>>,
+    block_of: Vec<usize>,
+
+    /// Block `b` is simulated by block `d` iff `relation[b][d]` holds.
+    relation: Vec<BitArray>,
+
+    /// The number of steps of every group into the blocks above a block,
+    /// indexed by the block and then the group.
+    counts: Vec<Vec<u32>>,
+
+    /// The states with a step for a label, but without such a step into the
+    /// blocks above a block, indexed by the block and then the label.
+    remove: Vec<Vec<Vec<StateIndex>>>,
+
+    /// The pairs of a block and a label whose remove set may be non-empty.
+    worklist: Vec<(usize, usize)>,
+
+    /// The position of every state in its block.
+    position: Vec<usize>,
+
+    /// The number of marked states of every block, used when splitting.
+    marked: Vec<usize>,
+
+    /// The block that the marked states of every block are moved to.
+    split_to: Vec<Option<usize>>,
+
+    /// Pruning a whole block of predecessors relies on the transitivity of the
+    /// relation, which is broken by removing pairs for the coupling condition.
+    /// In that case the predecessors are split off first, such that only
+    /// blocks in which every state has a step into the block are pruned.
+    split_predecessors: bool,
+}
+
+impl PartitionRelationPair {
+    /// Creates the initial partition-relation pair, which groups the states by
+    /// their enabled actions. For (coupled) simulation a block is related to
+    /// the blocks that enable more actions, and for ready simulation only to
+    /// itself.
+    fn new(steps: &Steps, simulation: SimulationType) -> Self {
+        let mut enabled = vec![Vec::new(); steps.num_of_states];
+        for (state_index, label) in &steps.groups {
+            enabled[*state_index].push(*label);
+        }
+
+        let mut enabled_sets: Vec<&Vec<usize>> = Vec::new();
+        let mut block_of_set: FxHashMap<&Vec<usize>, usize> = FxHashMap::default();
+        let mut blocks: Vec<Vec<StateIndex>> = Vec::new();
+        let mut block_of = vec![0; steps.num_of_states];
+        let mut position = vec![0; steps.num_of_states];
+
+        for (state_index, labels) in enabled.iter().enumerate() {
+            let block = *block_of_set.entry(labels).or_insert_with(|| {
+                enabled_sets.push(labels);
+                blocks.push(Vec::new());
+                blocks.len() - 1
+            });
+
+            position[state_index] = blocks[block].len();
+            blocks[block].push(StateIndex::new(state_index));
+            block_of[state_index] = block;
+        }
+
+        let relation: Vec<BitArray> = enabled_sets
+            .iter()
+            .map(|labels| {
+                enabled_sets
+                    .iter()
+                    .map(|other| match simulation {
+                        SimulationType::Simulation | SimulationType::CoupledSimulation => {
+                            labels.iter().all(|label| other.binary_search(label).is_ok())
+                        }
+                        SimulationType::ReadySimulation => labels == other,
+                    })
+                    .collect()
+            })
+            .collect();
+
+        // The blocks below every block, used to count the steps into the blocks above a block.
+        let num_of_blocks = blocks.len();
+        let below: Vec<Vec<usize>> = (0..num_of_blocks)
+            .map(|other| (0..num_of_blocks).filter(|block| relation[*block][other]).collect())
+            .collect();
+
+        let mut counts = vec![vec![0; steps.groups.len()]; num_of_blocks];
+        for (group, targets) in steps.targets.iter().enumerate() {
+            for to in targets {
+                for block in &below[block_of[*to]] {
+                    counts[*block][group] += 1;
+                }
+            }
+        }
+
+        let mut pair = Self {
+            blocks,
+            block_of,
+            relation,
+            counts,
+            remove: Vec::new(),
+            worklist: Vec::new(),
+            position,
+            marked: vec![0; num_of_blocks],
+            split_to: vec![None; num_of_blocks],
+            split_predecessors: simulation == SimulationType::CoupledSimulation,
+        };
+
+        for block in 0..pair.blocks.len() {
+            let mut remove = vec![Vec::new(); steps.num_of_labels];
+            for (group, (state_index, label)) in steps.groups.iter().enumerate() {
+                if pair.counts[block][group] == 0 {
+                    remove[*label].push(*state_index);
+                }
+            }
+
+            for (label, states) in remove.iter().enumerate() {
+                if !states.is_empty() {
+                    pair.worklist.push((block, label));
+                }
+            }
+            pair.remove.push(remove);
+        }
+
+        pair
+    }
+
+    /// Processes the remove sets until all of them are empty.
+    fn refine(&mut self, steps: &Steps, progress: &Progress) -> Result<(), MercError> {
+        let mut predecessors = Vec::new();
+        let mut predecessor_blocks = Vec::new();
+        let mut removed = Vec::new();
+
+        while let Some((block, label)) = self.worklist.pop() {
+            check_cancelled()?;
+            progress.print(self.blocks.len());
+
+            let remove = std::mem::take(&mut self.remove[block][label]);
+            if remove.is_empty() {
+                continue;
+            }
+
+            // The predecessors are determined before the block itself is split.
+            predecessors.clear();
+            for state_index in &self.blocks[block] {
+                predecessors.extend(
+                    steps.incoming[*state_index]
+                        .iter()
+                        .filter(|step| step.label == label)
+                        .map(|step| step.from),
+                );
+            }
+
+            self.split(&remove, &mut removed);
+            if self.split_predecessors {
+                predecessors.sort_unstable();
+                predecessors.dedup();
+                self.split(&predecessors, &mut predecessor_blocks);
+                self.blocks_of(&remove, &mut removed);
+            } else {
+                self.blocks_of(&predecessors, &mut predecessor_blocks);
+            }
+
+            // The blocks with a step for this label into the block can no longer be simulated by the removed blocks.
+            for predecessor in &predecessor_blocks {
+                for other in &removed {
+                    if self.relation[*predecessor][*other] {
+                        self.remove_pair(steps, *predecessor, *other);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Removes the pairs of blocks that violate the coupling condition, which
+    /// requires that every block above a block `B` can silently reach a block
+    /// below `B`. Returns true iff a pair was removed.
+    fn couple(&mut self, steps: &Steps) -> bool {
+        let silent = steps.silent.expect("Coupled simulation uses the weak transitions");
+
+        let mut changed = false;
+        let mut reach = Vec::new();
+        let mut reach_blocks = Vec::new();
+        let mut reach_marked = bitvec![u64, Lsb0; 0; steps.num_of_states];
+
+        let mut block = 0;
+        while block < self.blocks.len() {
+            // The states that can silently reach a block below this block.
+            reach.clear();
+            for (other, states) in self.blocks.iter().enumerate() {
+                if self.relation[other][block] {
+                    for state_index in states {
+                        reach.extend(
+                            steps.incoming[*state_index]
+                                .iter()
+                                .filter(|step| step.label == silent)
+                                .map(|step| step.from),
+                        );
+                    }
+                }
+            }
+            reach.sort_unstable();
+            reach.dedup();
+
+            self.split(&reach, &mut reach_blocks);
+
+            for state_index in &reach {
+                reach_marked.set(**state_index, true);
+            }
+
+            for other in 0..self.blocks.len() {
+                if self.relation[block][other] && !reach_marked[*self.blocks[other][0]] {
+                    self.remove_pair(steps, block, other);
+                    changed = true;
+                }
+            }
+
+            for state_index in &reach {
+                reach_marked.set(**state_index, false);
+            }
+
+            block += 1;
+        }
+
+        changed
+    }
+
+    /// Splits every block into the given states and the remaining states,
+    /// where both parts inherit the relation, counters and remove sets of the
+    /// original block. The given states must be distinct, and afterwards
+    /// `blocks` contains the blocks that form the given states.
+    fn split(&mut self, states: &[StateIndex], blocks: &mut Vec<usize>) {
+        blocks.clear();
+        for state_index in states {
+            let block = self.block_of[*state_index];
+            if self.marked[block] == 0 {
+                blocks.push(block);
+            }
+            self.marked[block] += 1;
+        }
+
+        let mut split = Vec::new();
+        for block in blocks.iter_mut() {
+            let marked = std::mem::take(&mut self.marked[*block]);
+            if marked < self.blocks[*block].len() {
+                let new_block = self.copy_block(*block);
+                self.split_to[*block] = Some(new_block);
+                split.push(*block);
+                *block = new_block;
+            }
+        }
+
+        // Move the marked states of the blocks that are split to the new blocks.
+        for state_index in states {
+            let block = self.block_of[*state_index];
+            if let Some(new_block) = self.split_to[block] {
+                let position = self.position[*state_index];
+                self.blocks[block].swap_remove(position);
+                if let Some(moved) = self.blocks[block].get(position) {
+                    self.position[*moved] = position;
+                }
+
+                self.position[*state_index] = self.blocks[new_block].len();
+                self.block_of[*state_index] = new_block;
+                self.blocks[new_block].push(*state_index);
+            }
+        }
+
+        for block in split {
+            self.split_to[block] = None;
+        }
+    }
+
+    /// Stores the blocks that contain the given states in `blocks`.
+    fn blocks_of(&mut self, states: &[StateIndex], blocks: &mut Vec<usize>) {
+        blocks.clear();
+        for state_index in states {
+            let block = self.block_of[*state_index];
+            if self.marked[block] == 0 {
+                self.marked[block] = 1;
+                blocks.push(block);
+            }
+        }
+
+        for block in blocks.iter() {
+            self.marked[*block] = 0;
+        }
+    }
+
+    /// Adds an empty block that inherits the relation, counters and remove
+    /// sets of the given block, and returns its index.
+    fn copy_block(&mut self, block: usize) -> usize {
+        let new_block = self.blocks.len();
+        self.blocks.push(Vec::new());
+        self.marked.push(0);
+        self.split_to.push(None);
+
+        for row in self.relation.iter_mut() {
+            let related = row[block];
+            row.push(related);
+        }
+        let row = self.relation[block].clone();
+        self.relation.push(row);
+
+        let counts = self.counts[block].clone();
+        self.counts.push(counts);
+
+        let remove = self.remove[block].clone();
+        for (label, states) in remove.iter().enumerate() {
+            if !states.is_empty() {
+                self.worklist.push((new_block, label));
+            }
+        }
+        self.remove.push(remove);
+
+        new_block
+    }
+
+    /// Removes the pair where `block` is simulated by `other`, and updates the
+    /// counters and remove sets of `block` accordingly.
+    fn remove_pair(&mut self, steps: &Steps, block: usize, other: usize) {
+        self.relation[block].set(other, false);
+
+        for state_index in &self.blocks[other] {
+            for step in &steps.incoming[*state_index] {
+                let count = &mut self.counts[block][step.group];
+                *count -= 1;
+
+                if *count == 0 {
+                    let remove = &mut self.remove[block][step.label];
+                    if remove.is_empty() {
+                        self.worklist.push((block, step.label));
+                    }
+                    remove.push(step.from);
+                }
+            }
+        }
+    }
+
+    /// Merges the blocks that are related in both directions, and returns the
+    /// resulting preorder.
+    fn finish(self, num_of_states: usize) -> SimulationPreorder {
+        let mut class_of: Vec<Option<usize>> = vec![None; self.blocks.len()];
+        let mut representatives = Vec::new();
+
+        for block in 0..self.blocks.len() {
+            if class_of[block].is_none() {
+                let class = representatives.len();
+                representatives.push(block);
+                for (other, other_class) in class_of.iter_mut().enumerate().skip(block) {
+                    if self.relation[block][other] && self.relation[other][block] {
+                        *other_class = Some(class);
+                    }
+                }
+            }
+        }
+
+        let mut partition = vec![BlockIndex::new(0); num_of_states];
+        for (block, states) in self.blocks.iter().enumerate() {
+            let class = class_of[block].expect("Every block belongs to a class");
+            for state_index in states {
+                partition[*state_index] = BlockIndex::new(class);
+            }
+        }
+
+        let relation = representatives
+            .iter()
+            .map(|block| {
+                representatives
+                    .iter()
+                    .map(|other| self.relation[*block][*other])
+                    .collect()
+            })
+            .collect();
+
+        SimulationPreorder {
+            partition: IndexedPartition::with_partition(partition, representatives.len()),
+            relation,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use merc_lts::LTS;
+    use merc_lts::random_lts;
+    use merc_lts::read_aut;
+    use merc_utilities::Timing;
+    use merc_utilities::random_test;
+
+    use crate::Partition;
+    use crate::strong_bisim_sigref_naive;
+
+    use super::*;
+
+    /// Computes the simulation preorder as the greatest fixpoint on pairs of states.
+    fn naive_simulation_preorder(lts: &impl LTS, simulation: SimulationType) -> Vec<Vec<bool>> {
+        let weak = simulation == SimulationType::CoupledSimulation;
+        let num_of_states = lts.num_of_states();
+
+        // The reflexive and transitive closure of the hidden transitions.
+        let mut silent = vec![vec![false; num_of_states]; num_of_states];
+        for state_index in lts.iter_states() {
+            silent[*state_index][*state_index] = true;
+            for transition in lts.outgoing_transitions(state_index) {
+                if weak && lts.is_hidden_label(transition.label) {
+                    silent[*state_index][*transition.to] = true;
+                }
+            }
+        }
+        for middle in 0..num_of_states {
+            for from in 0..num_of_states {
+                for to in 0..num_of_states {
+                    silent[from][to] |= silent[from][middle] && silent[middle][to];
+                }
+            }
+        }
+
+        let steps: Vec<Vec<(Option<usize>, usize)>> = lts
+            .iter_states()
+            .map(|state_index| {
+                let mut steps = Vec::new();
+                if weak {
+                    for middle in lts.iter_states().filter(|middle| silent[*state_index][**middle]) {
+                        steps.push((None, *middle));
+                        for transition in lts.outgoing_transitions(middle) {
+                            if !lts.is_hidden_label(transition.label) {
+                                for (to, reachable) in silent[*transition.to].iter().enumerate() {
+                                    if *reachable {
+                                        steps.push((Some(transition.label.value()), to));
+                                    }
+                                }
+                            }
+                        }
+                    }
+                } else {
+                    steps.extend(
+                        lts.outgoing_transitions(state_index)
+                            .map(|transition| (Some(transition.label.value()), *transition.to)),
+                    );
+                }
+                steps
+            })
+            .collect();
+
+        let enabled = |state: usize| {
+            let mut labels: Vec<_> = steps[state].iter().map(|(label, _)| *label).collect();
+            labels.sort_unstable();
+            labels.dedup();
+            labels
+        };
+
+        let mut relation: Vec<Vec<bool>> = (0..num_of_states)
+            .map(|left| {
+                (0..num_of_states)
+                    .map(|right| simulation != SimulationType::ReadySimulation || enabled(left) == enabled(right))
+                    .collect()
+            })
+            .collect();
+
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for left in 0..num_of_states {
+                for right in 0..num_of_states {
+                    if !relation[left][right] {
+                        continue;
+                    }
+
+                    let simulated = steps[left].iter().all(|(label, left_to)| {
+                        steps[right]
+                            .iter()
+                            .any(|(other, right_to)| label == other && relation[*left_to][*right_to])
+                    });
+                    let coupled = !weak || (0..num_of_states).any(|to| silent[right][to] && relation[to][left]);
+
+                    if !simulated || !coupled {
+                        relation[left][right] = false;
+                        changed = true;
+                    }
+                }
+            }
+        }
+
+        relation
+    }
+
+    #[test]
+    fn test_simulation_preorder() {
+        // The state 0 is simulated by state 3, but not the other way around.
+        let lts = read_aut(
+            b"des (0,5,7)
+(0,a,1)
+(0,a,2)
+(1,b,6)
+(3,a,4)
+(4,b,6)
+(4,c,5)
+" as &[u8],
+            Vec::new(),
+        )
+        .unwrap();
+
+        let mut timing = Timing::new();
+        let preorder = simulation_preorder(&lts, SimulationType::Simulation, &mut timing).unwrap();
+        assert!(preorder.is_simulated_by(StateIndex::new(0), StateIndex::new(3)));
+        assert!(!preorder.is_simulated_by(StateIndex::new(3), StateIndex::new(0)));
+
+        // The states 0 and 3 enable the same actions, but state 2 is a deadlock.
+        let preorder = simulation_preorder(&lts, SimulationType::ReadySimulation, &mut timing).unwrap();
+        assert!(!preorder.is_simulated_by(StateIndex::new(0), StateIndex::new(3)));
+    }
+
+    #[test]
+    fn test_coupled_simulation_preorder() {
+        // The gradual commitment in state 4 is coupled similar to the immediate choice in state 0.
+        let lts = read_aut(
+            b"des (0,13,10)
+(0,i,1)
+(0,i,2)
+(0,i,3)
+(1,a,9)
+(2,b,9)
+(3,c,9)
+(4,i,5)
+(4,i,6)
+(5,a,9)
+(6,i,7)
+(6,i,8)
+(7,b,9)
+(8,c,9)
+" as &[u8],
+            Vec::new(),
+        )
+        .unwrap();
+
+        let mut timing = Timing::new();
+        let preorder = simulation_preorder(&lts, SimulationType::CoupledSimulation, &mut timing).unwrap();
+        assert!(preorder.is_simulated_by(StateIndex::new(0), StateIndex::new(4)));
+        assert!(preorder.is_simulated_by(StateIndex::new(4), StateIndex::new(0)));
+
+        // The state 0 can commit to a single action, which the state 6 cannot mimic.
+        assert!(!preorder.is_simulated_by(StateIndex::new(0), StateIndex::new(6)));
+
+        // The hidden steps cannot be mimicked by the state 4 when they are treated as visible actions.
+        let preorder = simulation_preorder(&lts, SimulationType::Simulation, &mut timing).unwrap();
+        assert!(!preorder.is_simulated_by(StateIndex::new(0), StateIndex::new(4)));
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_random_simulation_preorder() {
+        random_test(100, |rng| {
+            let lts = random_lts(rng, 10, 3, 3);
+            let mut timing = Timing::new();
+
+            for simulation in [
+                SimulationType::Simulation,
+                SimulationType::ReadySimulation,
+                SimulationType::CoupledSimulation,
+            ] {
+                let preorder = simulation_preorder(&lts, simulation, &mut timing).unwrap();
+                let expected = naive_simulation_preorder(&lts, simulation);
+                for left in lts.iter_states() {
+                    for right in lts.iter_states() {
+                        assert_eq!(
+                            preorder.is_simulated_by(left, right),
+                            expected[*left][*right],
+                            "{simulation:?} differs for the states {left} and {right}"
+                        );
+                    }
+                }
+            }
+
+            // Strong bisimilar states simulate each other.
+            let (lts, bisimulation) = strong_bisim_sigref_naive(lts, &mut timing).unwrap();
+            let preorder = simulation_preorder(&lts, SimulationType::Simulation, &mut timing).unwrap();
+            for left in lts.iter_states() {
+                for right in lts.iter_states() {
+                    if bisimulation.block_number(left) == bisimulation.block_number(right) {
+                        assert!(preorder.is_simulated_by(left, right));
+                    }
+                }
+            }
+        })
+    }
+}
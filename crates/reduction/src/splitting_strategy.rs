@@ -0,0 +1,137 @@
+#![forbid(unsafe_code)]
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::collections::VecDeque;
+
+use crate::BlockIndex;
+
+/// A block that has become dirty during signature refinement, which means that
+/// the signatures of some of its states may have changed.
+#[derive(Clone, Copy, Debug)]
+pub struct DirtyBlock {
+    /// The index of the dirty block.
+    pub block: BlockIndex,
+
+    /// The number of states in the block when it became dirty.
+    pub size: usize,
+
+    /// The size of the signature that formed this block when it was split off,
+    /// or zero for the initial block.
+    pub signature_size: usize,
+}
+
+/// Determines the order in which the dirty blocks are split by signature
+/// refinement. The choice of splitter does not change the resulting partition,
+/// but it can have a large influence on the running time.
+pub trait SplittingStrategy {
+    /// Adds a dirty block to the worklist. A block is only added when it is
+    /// not already in the worklist.
+    fn push(&mut self, block: DirtyBlock);
+
+    /// Removes the next block to split from the worklist.
+    fn pop(&mut self) -> Option<BlockIndex>;
+}
+
+/// Selects one of the predefined splitting strategies, see [SplittingStrategy].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+pub enum SplittingOrder {
+    /// Splits the most recently dirtied block first.
+    #[default]
+    Lifo,
+    /// Splits the blocks in the order in which they became dirty.
+    Fifo,
+    /// Splits the largest dirty block first.
+    LargestBlockFirst,
+    /// Splits the dirty block with the smallest signature first.
+    SmallestSignatureFirst,
+}
+
+impl SplittingOrder {
+    /// Returns the splitting strategy of this order.
+    pub fn strategy(&self) -> Box<dyn SplittingStrategy> {
+        match self {
+            SplittingOrder::Lifo => Box::new(LifoStrategy::default()),
+            SplittingOrder::Fifo => Box::new(FifoStrategy::default()),
+            SplittingOrder::LargestBlockFirst => Box::new(LargestBlockFirstStrategy::default()),
+            SplittingOrder::SmallestSignatureFirst => Box::new(SmallestSignatureFirstStrategy::default()),
+        }
+    }
+}
+
+impl<S: SplittingStrategy + ?Sized> SplittingStrategy for Box<S> {
+    fn push(&mut self, block: DirtyBlock) {
+        (**self).push(block)
+    }
+
+    fn pop(&mut self) -> Option<BlockIndex> {
+        (**self).pop()
+    }
+}
+
+/// Splits the most recently dirtied block first.
+#[derive(Default)]
+pub struct LifoStrategy {
+    worklist: Vec<BlockIndex>,
+}
+
+impl SplittingStrategy for LifoStrategy {
+    fn push(&mut self, block: DirtyBlock) {
+        self.worklist.push(block.block);
+    }
+
+    fn pop(&mut self) -> Option<BlockIndex> {
+        self.worklist.pop()
+    }
+}
+
+/// Splits the blocks in the order in which they became dirty.
+#[derive(Default)]
+pub struct FifoStrategy {
+    worklist: VecDeque<BlockIndex>,
+}
+
+impl SplittingStrategy for FifoStrategy {
+    fn push(&mut self, block: DirtyBlock) {
+        self.worklist.push_back(block.block);
+    }
+
+    fn pop(&mut self) -> Option<BlockIndex> {
+        self.worklist.pop_front()
+    }
+}
+
+/// Splits the largest dirty block first, where the size is determined when the
+/// block became dirty.
+#[derive(Default)]
+pub struct LargestBlockFirstStrategy {
+    worklist: BinaryHeap<(usize, BlockIndex)>,
+}
+
+impl SplittingStrategy for LargestBlockFirstStrategy {
+    fn push(&mut self, block: DirtyBlock) {
+        self.worklist.push((block.size, block.block));
+    }
+
+    fn pop(&mut self) -> Option<BlockIndex> {
+        self.worklist.pop().map(|(_, block)| block)
+    }
+}
+
+/// Splits the dirty block with the smallest signature first, since the
+/// signatures of its states are the cheapest to recompute.
+#[derive(Default)]
+pub struct SmallestSignatureFirstStrategy {
+    worklist: BinaryHeap<Reverse<(usize, BlockIndex)>>,
+}
+
+impl SplittingStrategy for SmallestSignatureFirstStrategy {
+    fn push(&mut self, block: DirtyBlock) {
+        self.worklist.push(Reverse((block.signature_size, block.block)));
+    }
+
+    fn pop(&mut self) -> Option<BlockIndex> {
+        self.worklist.pop().map(|Reverse((_, block))| block)
+    }
+}
@@ -1,7 +1,5 @@
 #![forbid(unsafe_code)]
 
-use log::info;
-
 use merc_aterm::storage::THREAD_TERM_POOL;
 use merc_aterm::storage::ThreadTermPool;
 use merc_data::DataApplication;
@@ -10,7 +8,7 @@ use merc_data::DataExpressionRef;
 
 use crate::RewriteEngine;
 use crate::RewriteSpecification;
-use crate::RewritingStatistics;
+use crate::RewriteStats;
 use crate::Rule;
 use crate::matching::conditions::EMACondition;
 use crate::matching::conditions::extend_conditions;
@@ -22,24 +20,30 @@ use crate::set_automaton::SetAutomaton;
 use crate::utilities::Config;
 use crate::utilities::DataPositionIndexed;
 use crate::utilities::InnermostStack;
+use crate::utilities::NormalFormCache;
 use crate::utilities::TermStack;
 use crate::utilities::TermStackBuilder;
 use merc_utilities::debug_trace;
 
 impl RewriteEngine for InnermostRewriter {
-    fn rewrite(&mut self, t: &DataExpression) -> DataExpression {
-        let mut stats = RewritingStatistics::default();
+    fn rewrite_with_stats(&mut self, t: &DataExpression, stats: Option<&mut RewriteStats>) -> DataExpression {
+        let mut local = RewriteStats::default();
 
         debug_trace!("input: {}", t);
 
         let result = THREAD_TERM_POOL.with_borrow(|tp| {
-            InnermostRewriter::rewrite_aux(tp, &mut self.stack, &mut self.builder, &mut stats, &self.apma, t)
+            InnermostRewriter::rewrite_aux(
+                tp,
+                &mut self.stack,
+                &mut self.builder,
+                &mut local,
+                &self.apma,
+                &mut self.cache,
+                t,
+            )
         });
 
-        info!(
-            "{} rewrites, {} single steps and {} symbol comparisons",
-            stats.recursions, stats.rewrite_steps, stats.symbol_comparisons
-        );
+        local.finish(stats, t, &result);
         result
     }
 }
@@ -53,9 +57,23 @@ impl InnermostRewriter {
             apma,
             stack: InnermostStack::default(),
             builder: TermStackBuilder::new(),
+            cache: None,
         }
     }
 
+    /// Keeps the normal forms of at most `capacity` subterms between calls to
+    /// [RewriteEngine::rewrite], such that subterms that occur in multiple
+    /// input terms are only rewritten once, see [NormalFormCache].
+    pub fn with_cache(mut self, capacity: usize) -> InnermostRewriter {
+        self.cache = Some(NormalFormCache::new(capacity));
+        self
+    }
+
+    /// Returns the normal form cache, if enabled.
+    pub fn cache(&self) -> Option<&NormalFormCache> {
+        self.cache.as_ref()
+    }
+
     /// Function to rewrite a term 't'. The elements of the automaton 'states'
     /// and 'tp' are passed as separate parameters to satisfy the borrow
     /// checker.
@@ -69,13 +87,15 @@ impl InnermostRewriter {
     ///     - Rewrite(index): Updates the configuration to rewrite the top of the term stack
     ///                       and places the result on the given index.
     ///     - Construct(arity, index, result):
+    ///     - Store(term, index): Caches the term at the given index as the normal form of the given term.
     ///
     pub(crate) fn rewrite_aux(
         tp: &ThreadTermPool,
         stack: &mut InnermostStack,
         builder: &mut TermStackBuilder,
-        stats: &mut RewritingStatistics,
+        stats: &mut RewriteStats,
         automaton: &SetAutomaton<AnnouncementInnermost>,
+        cache: &mut Option<NormalFormCache>,
         input_term: &DataExpression,
     ) -> DataExpression {
         stats.recursions += 1;
@@ -100,6 +120,18 @@ impl InnermostRewriter {
                         let mut write_terms = stack.terms.write();
                         let term = write_terms.pop().unwrap().unwrap();
 
+                        if let Some(cache) = cache.as_mut() {
+                            if let Some(normal_form) = cache.get(&term) {
+                                stats.cache_hits += 1;
+                                write_terms[result] = Some(write_terms.protect(&normal_form).into());
+                                continue;
+                            }
+
+                            // Cache the normal form after it has been placed at the result index.
+                            let stored = write_configs.protect(&term);
+                            write_configs.push(Config::Store(stored.into(), result));
+                        }
+
                         let symbol = term.data_function_symbol();
                         let arguments = term.data_arguments();
 
@@ -139,13 +171,13 @@ impl InnermostRewriter {
                         drop(write_terms);
                         drop(write_configs);
 
-                        match InnermostRewriter::find_match(tp, stack, builder, stats, automaton, &term.copy()) {
-                            Some((_announcement, annotation)) => {
+                        match InnermostRewriter::find_match(tp, stack, builder, stats, automaton, cache, &term.copy()) {
+                            Some((announcement, annotation)) => {
                                 debug_trace!(
                                     "rewrite {} => {} using rule {}",
                                     term,
                                     annotation.rhs_stack.evaluate(&term),
-                                    _announcement.rule
+                                    announcement.rule
                                 );
 
                                 // Reacquire the write access and add the matching RHSStack.
@@ -158,7 +190,7 @@ impl InnermostRewriter {
                                     &term.copy(),
                                     index,
                                 );
-                                stats.rewrite_steps += 1;
+                                stats.add_rule_application(&announcement.rule);
                             }
                             None => {
                                 // Add the term on the stack.
@@ -167,6 +199,19 @@ impl InnermostRewriter {
                             }
                         }
                     }
+                    Config::Store(term, index) => {
+                        drop(write_configs);
+
+                        let read_terms = stack.terms.read();
+                        let normal_form = read_terms[index]
+                            .as_ref()
+                            .expect("The normal form should be computed before it is stored");
+
+                        cache
+                            .as_mut()
+                            .expect("Normal forms are only stored when caching is enabled")
+                            .insert(&term, normal_form);
+                    }
                     Config::Term(_, _) => {
                         unreachable!("This case should not happen");
                     }
@@ -192,6 +237,7 @@ impl InnermostRewriter {
                                         Config::Rewrite(result) => index == *result,
                                         Config::Term(_, result) => index == *result,
                                         Config::Return() => true,
+                                        Config::Store(_, _) => false,
                                     }
                                 }),
                                 "The default term at index {index} is not a result of any operation."
@@ -208,8 +254,9 @@ impl InnermostRewriter {
         tp: &ThreadTermPool,
         stack: &mut InnermostStack,
         builder: &mut TermStackBuilder,
-        stats: &mut RewritingStatistics,
+        stats: &mut RewriteStats,
         automaton: &'a SetAutomaton<AnnouncementInnermost>,
+        cache: &mut Option<NormalFormCache>,
         t: &DataExpressionRef<'_>,
     ) -> Option<(&'a MatchAnnouncement, &'a AnnouncementInnermost)> {
         // Start at the initial state
@@ -226,7 +273,9 @@ impl InnermostRewriter {
             if let Some(transition) = automaton.transitions().get(&(state_index, symbol.operation_id())) {
                 for (announcement, annotation) in &transition.announcements {
                     if check_equivalence_classes(t, &annotation.equivalence_classes)
-                        && InnermostRewriter::check_conditions(tp, stack, builder, stats, automaton, annotation, t)
+                        && InnermostRewriter::check_conditions(
+                            tp, stack, builder, stats, automaton, cache, annotation, t,
+                        )
                     {
                         // We found a matching pattern
                         return Some((announcement, annotation));
@@ -247,12 +296,14 @@ impl InnermostRewriter {
     }
 
     /// Checks whether the condition holds for given match announcement.
+    #[allow(clippy::too_many_arguments)]
     fn check_conditions(
         tp: &ThreadTermPool,
         stack: &mut InnermostStack,
         builder: &mut TermStackBuilder,
-        stats: &mut RewritingStatistics,
+        stats: &mut RewriteStats,
         automaton: &SetAutomaton<AnnouncementInnermost>,
+        cache: &mut Option<NormalFormCache>,
         announcement: &AnnouncementInnermost,
         t: &DataExpressionRef<'_>,
     ) -> bool {
@@ -260,8 +311,8 @@ impl InnermostRewriter {
             let rhs: DataExpression = c.rhs_term_stack.evaluate_with(t, builder);
             let lhs: DataExpression = c.lhs_term_stack.evaluate_with(t, builder);
 
-            let rhs_normal = InnermostRewriter::rewrite_aux(tp, stack, builder, stats, automaton, &rhs);
-            let lhs_normal = InnermostRewriter::rewrite_aux(tp, stack, builder, stats, automaton, &lhs);
+            let rhs_normal = InnermostRewriter::rewrite_aux(tp, stack, builder, stats, automaton, cache, &rhs);
+            let lhs_normal = InnermostRewriter::rewrite_aux(tp, stack, builder, stats, automaton, cache, &lhs);
 
             if lhs_normal != rhs_normal && c.equality || lhs_normal == rhs_normal && !c.equality {
                 return false;
@@ -277,6 +328,7 @@ pub struct InnermostRewriter {
     apma: SetAutomaton<AnnouncementInnermost>,
     stack: InnermostStack,
     builder: TermStackBuilder,
+    cache: Option<NormalFormCache>,
 }
 
 pub struct AnnouncementInnermost {
@@ -5,7 +5,10 @@ mod matching;
 mod naive_rewriter;
 mod rewrite_specification;
 mod sabre_rewriter;
+#[cfg(feature = "serde")]
+mod serde_term;
 mod set_automaton;
+mod termination;
 
 pub mod test_utility;
 pub mod utilities;
@@ -15,3 +18,4 @@ pub use naive_rewriter::*;
 pub use rewrite_specification::*;
 pub use sabre_rewriter::*;
 pub use set_automaton::*;
+pub use termination::*;
@@ -1,6 +1,5 @@
 #![forbid(unsafe_code)]
 
-use log::info;
 use merc_data::DataApplication;
 use merc_data::DataExpression;
 use merc_data::DataExpressionRef;
@@ -10,7 +9,7 @@ use crate::AnnouncementInnermost;
 use crate::MatchAnnouncement;
 use crate::RewriteEngine;
 use crate::RewriteSpecification;
-use crate::RewritingStatistics;
+use crate::RewriteStats;
 use crate::set_automaton::SetAutomaton;
 use crate::utilities::DataPositionIndexed;
 
@@ -21,15 +20,12 @@ pub struct NaiveRewriter {
 }
 
 impl RewriteEngine for NaiveRewriter {
-    fn rewrite(&mut self, t: &DataExpression) -> DataExpression {
-        let mut stats = RewritingStatistics::default();
+    fn rewrite_with_stats(&mut self, t: &DataExpression, stats: Option<&mut RewriteStats>) -> DataExpression {
+        let mut local = RewriteStats::default();
 
-        let result = NaiveRewriter::rewrite_aux(&self.apma, t.copy(), &mut stats);
+        let result = NaiveRewriter::rewrite_aux(&self.apma, t.copy(), &mut local);
 
-        info!(
-            "{} rewrites, {} single steps and {} symbol comparisons",
-            stats.recursions, stats.rewrite_steps, stats.symbol_comparisons
-        );
+        local.finish(stats, t, &result);
         result
     }
 }
@@ -37,7 +33,7 @@ impl RewriteEngine for NaiveRewriter {
 impl NaiveRewriter {
     pub fn new(spec: &RewriteSpecification) -> NaiveRewriter {
         NaiveRewriter {
-            apma: SetAutomaton::new(spec, AnnouncementInnermost::new, false),
+            apma: SetAutomaton::new(spec, AnnouncementInnermost::new, true),
         }
     }
 
@@ -46,7 +42,7 @@ impl NaiveRewriter {
     fn rewrite_aux(
         automaton: &SetAutomaton<AnnouncementInnermost>,
         t: DataExpressionRef<'_>,
-        stats: &mut RewritingStatistics,
+        stats: &mut RewriteStats,
     ) -> DataExpression {
         let symbol = t.data_function_symbol();
 
@@ -64,9 +60,10 @@ impl NaiveRewriter {
 
         match NaiveRewriter::find_match(automaton, &nf, stats) {
             None => nf,
-            Some((_announcement, ema)) => {
+            Some((announcement, ema)) => {
                 let result = ema.rhs_stack.evaluate(&nf);
-                debug_trace!("rewrote {} to {} using rule {}", nf, result, _announcement.rule);
+                debug_trace!("rewrote {} to {} using rule {}", nf, result, announcement.rule);
+                stats.add_rule_application(&announcement.rule);
                 NaiveRewriter::rewrite_aux(automaton, result.copy(), stats)
             }
         }
@@ -76,7 +73,7 @@ impl NaiveRewriter {
     fn find_match<'a>(
         automaton: &'a SetAutomaton<AnnouncementInnermost>,
         t: &DataExpression,
-        stats: &mut RewritingStatistics,
+        stats: &mut RewriteStats,
     ) -> Option<(&'a MatchAnnouncement, &'a AnnouncementInnermost)> {
         // Start at the initial state
         let mut state_index = 0;
@@ -140,7 +137,7 @@ impl NaiveRewriter {
         automaton: &SetAutomaton<AnnouncementInnermost>,
         t: &DataExpressionRef<'_>,
         ema: &AnnouncementInnermost,
-        stats: &mut RewritingStatistics,
+        stats: &mut RewriteStats,
     ) -> bool {
         for c in &ema.conditions {
             let rhs = c.lhs_term_stack.evaluate(t);
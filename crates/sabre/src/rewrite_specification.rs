@@ -4,9 +4,12 @@ use std::fmt;
 
 use itertools::Itertools;
 use merc_data::DataExpression;
+use merc_data::DataFunctionSymbol;
+use merc_data::DataSpecification;
 
 /// A rewrite specification is a set of rewrite rules, given by [Rule].
 #[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RewriteSpecification {
     rewrite_rules: Vec<Rule>,
 }
@@ -23,22 +26,55 @@ impl RewriteSpecification {
     }
 }
 
+impl From<&DataSpecification> for RewriteSpecification {
+    /// Converts the equations of the data specification into rewrite rules,
+    /// where the condition `c` of an equation becomes the condition `c == true`.
+    fn from(spec: &DataSpecification) -> Self {
+        let true_symbol: DataExpression = DataFunctionSymbol::new("true").into();
+
+        RewriteSpecification::new(
+            spec.equations()
+                .iter()
+                .map(|equation| Rule {
+                    conditions: equation
+                        .condition
+                        .iter()
+                        .map(|condition| Condition {
+                            lhs: condition.clone(),
+                            rhs: true_symbol.clone(),
+                            equality: true,
+                        })
+                        .collect(),
+                    lhs: equation.lhs.clone(),
+                    rhs: equation.rhs.clone(),
+                })
+                .collect(),
+        )
+    }
+}
+
 /// A condition of a conditional rewrite rule.
 ///
 /// Either `lhs == rhs` or `lhs != rhs` depending on equality being true.
 #[derive(Clone, Debug, Eq, Hash, PartialEq, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Condition {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_term"))]
     pub lhs: DataExpression,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_term"))]
     pub rhs: DataExpression,
     pub equality: bool,
 }
 
 /// A rewrite rule.
 #[derive(Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Rule {
     /// A conjunction of clauses
     pub conditions: Vec<Condition>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_term"))]
     pub lhs: DataExpression,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_term"))]
     pub rhs: DataExpression,
 }
 
@@ -76,3 +112,40 @@ impl fmt::Display for Condition {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use merc_data::to_data_expression;
+    use merc_syntax::DataExpr;
+    use merc_syntax::UntypedDataSpecification;
+
+    use crate::RewriteEngine;
+    use crate::SabreRewriter;
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_rewrite_data_specification() {
+        let spec = UntypedDataSpecification::parse(
+            "sort List = struct nil | cons(head: Nat, tail: List)?is_cons;
+            map append: List # Nat -> List;
+            var l: List;
+                n, m: Nat;
+            eqn append(nil, n) = cons(n, nil);
+                append(cons(m, l), n) = cons(m, append(l, n));",
+        )
+        .unwrap();
+
+        let spec = RewriteSpecification::from(&DataSpecification::from_syntax(&spec).unwrap());
+        let mut rewriter = SabreRewriter::new(&spec);
+
+        let term = |text: &str| to_data_expression(&DataExpr::parse(text).unwrap(), &Default::default()).unwrap();
+        assert_eq!(
+            rewriter.rewrite(&term("append(cons(a, nil), b)")),
+            term("cons(a, cons(b, nil))")
+        );
+        assert_eq!(rewriter.rewrite(&term("head(tail(cons(a, cons(b, nil))))")), term("b"));
+        assert_eq!(rewriter.rewrite(&term("is_cons(nil)")), term("false"));
+    }
+}
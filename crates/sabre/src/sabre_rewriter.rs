@@ -1,14 +1,26 @@
 #![forbid(unsafe_code)]
 
+use std::fmt;
+
+use ahash::HashMap;
 use log::info;
 
+use merc_aterm::Term;
+use merc_aterm::fold_term;
 use merc_aterm::storage::THREAD_TERM_POOL;
 use merc_aterm::storage::ThreadTermPool;
 use merc_data::DataExpression;
 use merc_data::DataExpressionRef;
+use merc_data::DataFunctionSymbol;
+use merc_data::DataFunctionSymbolRef;
+use merc_data::is_data_application;
+use merc_data::is_data_function_symbol;
+use merc_data::is_data_machine_number;
+use merc_data::is_data_variable;
 use merc_utilities::debug_trace;
 
 use crate::RewriteSpecification;
+use crate::Rule;
 use crate::matching::nonlinear::check_equivalence_classes;
 use crate::set_automaton::MatchAnnouncement;
 use crate::set_automaton::SetAutomaton;
@@ -19,29 +31,120 @@ use crate::utilities::SideInfo;
 use crate::utilities::SideInfoType;
 
 /// A shared trait for all the rewriters
+///
+/// # Details
+///
+/// Rewriters store protected terms, which belong to the thread that created
+/// them, so a rewriter cannot be sent to another thread. To rewrite in
+/// parallel every thread must construct its own rewriter, which is what the
+/// parallel state space exploration of `merc_explore` does.
 pub trait RewriteEngine {
     /// Rewrites the given term into normal form.
-    fn rewrite(&mut self, term: &DataExpression) -> DataExpression;
+    fn rewrite(&mut self, term: &DataExpression) -> DataExpression {
+        self.rewrite_with_stats(term, None)
+    }
+
+    /// Rewrites the given term into normal form, and adds the statistics of
+    /// this rewrite to `stats` when given.
+    fn rewrite_with_stats(&mut self, term: &DataExpression, stats: Option<&mut RewriteStats>) -> DataExpression;
 }
 
-#[derive(Default)]
-pub struct RewritingStatistics {
+/// Statistics of one or more calls to a [RewriteEngine].
+#[derive(Clone, Debug, Default)]
+pub struct RewriteStats {
     /// Count the number of rewrite rules applied
     pub rewrite_steps: usize,
     /// Counts the number of times symbols are compared.
     pub symbol_comparisons: usize,
     /// The number of times rewrite is called recursively (to rewrite conditions etc)
     pub recursions: usize,
+    /// The number of subterms whose normal form was found in the cache.
+    pub cache_hits: usize,
+    /// The number of times that every rule has been applied.
+    pub rule_applications: HashMap<Rule, usize>,
+    /// The largest number of function symbols of an input term or its normal
+    /// form, where shared subterms are counted for every occurrence.
+    pub max_term_size: usize,
+}
+
+impl RewriteStats {
+    /// Counts an application of the given rule.
+    pub(crate) fn add_rule_application(&mut self, rule: &Rule) {
+        self.rewrite_steps += 1;
+        match self.rule_applications.get_mut(rule) {
+            Some(count) => *count += 1,
+            None => {
+                self.rule_applications.insert(rule.clone(), 1);
+            }
+        }
+    }
+
+    /// Logs the statistics of a single rewrite, and adds them to `total` when
+    /// given. The term sizes are only computed when they are requested.
+    pub(crate) fn finish(self, total: Option<&mut RewriteStats>, input: &DataExpression, result: &DataExpression) {
+        info!("{self}");
+
+        if let Some(total) = total {
+            total.rewrite_steps += self.rewrite_steps;
+            total.symbol_comparisons += self.symbol_comparisons;
+            total.recursions += self.recursions;
+            total.cache_hits += self.cache_hits;
+            for (rule, count) in self.rule_applications {
+                *total.rule_applications.entry(rule).or_default() += count;
+            }
+
+            total.max_term_size = total.max_term_size.max(term_size(input)).max(term_size(result));
+        }
+    }
+}
+
+impl fmt::Display for RewriteStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} rewrites, {} single steps, {} symbol comparisons and {} cached normal forms",
+            self.recursions, self.rewrite_steps, self.symbol_comparisons, self.cache_hits
+        )
+    }
+}
+
+/// Returns the number of function symbols, variables and numbers in the given
+/// term, which saturates for terms with many shared subterms.
+fn term_size(term: &DataExpression) -> usize {
+    fold_term(term, |t, arguments: &[usize]| {
+        if is_data_function_symbol(t) || is_data_variable(t) || is_data_machine_number(t) {
+            Ok(1)
+        } else if is_data_application(t) {
+            // The head symbol is the first argument, and is counted as well.
+            Ok(arguments
+                .iter()
+                .fold(0, |size, argument| size.saturating_add(*argument)))
+        } else {
+            Ok(0)
+        }
+    })
+    .expect("Computing the term size cannot fail")
 }
 
 /// The Set Automaton based Rewrite Engine implementation.
 pub struct SabreRewriter {
     automaton: SetAutomaton<AnnouncementSabre>,
+    /// The rewrite rules from which the automaton is constructed.
+    rules: Vec<Rule>,
+    /// The function symbols of rewritten terms that do not occur in the rules.
+    symbols: HashMap<DataFunctionSymbol, usize>,
 }
 
 impl RewriteEngine for SabreRewriter {
-    fn rewrite(&mut self, term: &DataExpression) -> DataExpression {
-        self.stack_based_normalise(term)
+    fn rewrite_with_stats(&mut self, term: &DataExpression, stats: Option<&mut RewriteStats>) -> DataExpression {
+        let mut local = RewriteStats::default();
+
+        self.add_symbols(term);
+        let result = THREAD_TERM_POOL
+            .with_borrow(|tp| SabreRewriter::stack_based_normalise_aux(tp, &self.automaton, term, &mut local));
+
+        local.finish(stats, term, &result);
+        result
     }
 }
 
@@ -49,22 +152,57 @@ impl SabreRewriter {
     pub fn new(spec: &RewriteSpecification) -> Self {
         let automaton = SetAutomaton::new(spec, AnnouncementSabre::new, false);
 
-        SabreRewriter { automaton }
+        SabreRewriter {
+            automaton,
+            rules: spec.rewrite_rules().to_vec(),
+            symbols: HashMap::default(),
+        }
     }
 
-    /// Function to rewrite a term. See the module documentation.
-    pub fn stack_based_normalise(&mut self, t: &DataExpression) -> DataExpression {
-        let mut stats = RewritingStatistics::default();
+    /// Adds the function symbols of the given term that are unknown to the set
+    /// automaton, in which case the automaton is constructed again.
+    ///
+    /// # Details
+    ///
+    /// The set automaton does not look for redexes below the arguments of
+    /// symbols without transitions. The terms that occur during rewriting
+    /// consist of subterms of the input term and the symbols of the rules, so
+    /// it suffices to check the input term.
+    fn add_symbols(&mut self, term: &DataExpression) {
+        let mut added = false;
+        fold_term(term, |t, _: &[()]| {
+            // The head symbol of an application is folded first, so its arity is updated afterwards.
+            if is_data_application(t) {
+                let t = DataExpressionRef::from(t.copy());
+                let symbol = t.data_function_symbol();
+                if !self.automaton.contains_symbol(&symbol) {
+                    self.symbols.insert(symbol.protect(), t.data_arguments().len());
+                    added = true;
+                }
+            } else if is_data_function_symbol(t) {
+                let symbol = DataFunctionSymbolRef::from(t.copy());
+                if !self.automaton.contains_symbol(&symbol) {
+                    self.symbols.entry(symbol.protect()).or_insert(0);
+                    added = true;
+                }
+            }
 
-        let result = THREAD_TERM_POOL
-            .with_borrow(|tp| SabreRewriter::stack_based_normalise_aux(tp, &self.automaton, t, &mut stats));
+            Ok(())
+        })
+        .expect("Collecting the function symbols cannot fail");
 
-        info!(
-            "{} rewrites, {} single steps and {} symbol comparisons",
-            stats.recursions, stats.rewrite_steps, stats.symbol_comparisons
-        );
+        if added {
+            info!(
+                "Constructing the set automaton again for {} unknown symbols",
+                self.symbols.len()
+            );
+            self.automaton = SetAutomaton::with_symbols(&self.rules, &self.symbols, AnnouncementSabre::new, false);
+        }
+    }
 
-        result
+    /// Function to rewrite a term. See the module documentation.
+    pub fn stack_based_normalise(&mut self, t: &DataExpression) -> DataExpression {
+        self.rewrite_with_stats(t, None)
     }
 
     /// The _aux function splits the [TermPool] pool and the [SetAutomaton] to make borrow checker happy.
@@ -73,7 +211,7 @@ impl SabreRewriter {
         tp: &ThreadTermPool,
         automaton: &SetAutomaton<AnnouncementSabre>,
         t: &DataExpression,
-        stats: &mut RewritingStatistics,
+        stats: &mut RewriteStats,
     ) -> DataExpression {
         stats.recursions += 1;
 
@@ -232,9 +370,9 @@ impl SabreRewriter {
         annotation: &AnnouncementSabre,
         leaf_index: usize,
         cs: &mut ConfigurationStack<'_>,
-        stats: &mut RewritingStatistics,
+        stats: &mut RewriteStats,
     ) {
-        stats.rewrite_steps += 1;
+        stats.add_rule_application(&announcement.rule);
 
         let read_terms = cs.terms.read();
         let leaf_subterm: &DataExpressionRef<'_> = &read_terms[leaf_index];
@@ -264,7 +402,7 @@ impl SabreRewriter {
         announcement: &MatchAnnouncement,
         annotation: &AnnouncementSabre,
         subterm: &DataExpressionRef<'_>,
-        stats: &mut RewritingStatistics,
+        stats: &mut RewriteStats,
     ) -> bool {
         for c in &annotation.conditions {
             let subterm = subterm.get_data_position(&announcement.position);
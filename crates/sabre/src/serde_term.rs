@@ -0,0 +1,59 @@
+#![forbid(unsafe_code)]
+
+//! Serde support for the data expressions in a [crate::RewriteSpecification], enabled by the `serde` feature.
+//!
+//! A term is serialized as its integer value for integer terms, and as its
+//! head symbol name followed by its arguments otherwise. Note that the textual
+//! aterm format can not be used for this purpose, since it does not distinguish
+//! integers from constants.
+
+use merc_aterm::ATerm;
+use merc_aterm::ATermInt;
+use merc_aterm::ATermRef;
+use merc_aterm::Symbol;
+use merc_aterm::Term;
+use merc_aterm::is_int_term;
+use merc_data::DataExpression;
+use serde::Deserialize;
+use serde::Deserializer;
+use serde::Serialize;
+use serde::Serializer;
+
+/// The serialized form of a term.
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum SerializedTerm {
+    Int(usize),
+    Application(String, Vec<SerializedTerm>),
+}
+
+impl SerializedTerm {
+    fn from_term(term: &ATermRef<'_>) -> SerializedTerm {
+        if is_int_term(term) {
+            SerializedTerm::Int(term.annotation().expect("Integer terms have an annotation"))
+        } else {
+            SerializedTerm::Application(
+                term.get_head_symbol().name().to_string(),
+                term.arguments().map(|arg| SerializedTerm::from_term(&arg)).collect(),
+            )
+        }
+    }
+
+    fn to_term(&self) -> ATerm {
+        match self {
+            SerializedTerm::Int(value) => ATermInt::new(*value).into(),
+            SerializedTerm::Application(name, args) => {
+                let args: Vec<ATerm> = args.iter().map(SerializedTerm::to_term).collect();
+                ATerm::with_iter(&Symbol::new(name.as_str(), args.len()), &args)
+            }
+        }
+    }
+}
+
+pub(crate) fn serialize<S: Serializer>(expression: &DataExpression, serializer: S) -> Result<S::Ok, S::Error> {
+    SerializedTerm::from_term(&expression.get()).serialize(serializer)
+}
+
+pub(crate) fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<DataExpression, D::Error> {
+    Ok(SerializedTerm::deserialize(deserializer)?.to_term().into())
+}
@@ -12,6 +12,7 @@ use merc_aterm::Term;
 use merc_data::DataExpression;
 use merc_data::DataExpressionRef;
 use merc_data::DataFunctionSymbol;
+use merc_data::DataFunctionSymbolRef;
 use merc_data::is_data_application;
 use merc_data::is_data_function_symbol;
 use merc_data::is_data_machine_number;
@@ -87,14 +88,38 @@ impl<M> SetAutomaton<M> {
     /// announcement. This is used to accomondate different types of annotations
     /// for the different rewrite engines.
     pub fn new(spec: &RewriteSpecification, annotate: impl Fn(&Rule) -> M, apma: bool) -> SetAutomaton<M> {
+        Self::from_rules(spec.rewrite_rules(), annotate, apma)
+    }
+
+    /// Creates a new SetAutomaton that matches the left hand sides of the given
+    /// rules, see [SetAutomaton::new]. Rules that are not supported, see
+    /// [is_supported_rule], are ignored.
+    pub fn from_rules(rules: &[Rule], annotate: impl Fn(&Rule) -> M, apma: bool) -> SetAutomaton<M> {
+        Self::with_symbols(rules, &HashMap::default(), annotate, apma)
+    }
+
+    /// Creates a new SetAutomaton that matches the left hand sides of the given
+    /// rules, see [SetAutomaton::from_rules], where the given function symbols
+    /// (with their arities) are added to the symbols of the rules.
+    ///
+    /// # Details
+    ///
+    /// The automaton only has transitions for the function symbols that it
+    /// knows, so a set automaton that is not an APMA cannot look for matches
+    /// below other symbols.
+    pub fn with_symbols(
+        rules: &[Rule],
+        symbols: &HashMap<DataFunctionSymbol, usize>,
+        annotate: impl Fn(&Rule) -> M,
+        apma: bool,
+    ) -> SetAutomaton<M> {
         let start = Instant::now();
 
         // States are labelled s0, s1, s2, etcetera. state_counter keeps track of count.
         let mut state_counter: usize = 1;
 
         // Remove rules that we cannot deal with
-        let supported_rules: Vec<Rule> = spec
-            .rewrite_rules()
+        let supported_rules: Vec<Rule> = rules
             .iter()
             .filter(|rule| is_supported_rule(rule))
             .map(Rule::clone)
@@ -102,7 +127,7 @@ impl<M> SetAutomaton<M> {
 
         // Find the indices of all the function symbols.
         let symbols = {
-            let mut symbols = HashMap::default();
+            let mut symbols = symbols.clone();
 
             for rule in &supported_rules {
                 find_symbols(&rule.lhs.copy(), &mut symbols);
@@ -248,6 +273,12 @@ impl<M> SetAutomaton<M> {
         result
     }
 
+    /// Returns true iff the given function symbol has transitions in this automaton.
+    pub fn contains_symbol(&self, symbol: &DataFunctionSymbolRef<'_>) -> bool {
+        // Every state has a transition for every known symbol, including the initial state.
+        self.transitions.contains_key(&(0, symbol.operation_id()))
+    }
+
     /// Returns the number of states
     pub fn num_of_states(&self) -> usize {
         self.states.len()
@@ -6,9 +6,11 @@
 mod automaton;
 mod display;
 mod match_goal;
+mod pattern_matcher;
 
 pub use automaton::*;
 pub(crate) use match_goal::*;
+pub use pattern_matcher::*;
 
 #[allow(unused)]
 pub use display::*;
@@ -0,0 +1,227 @@
+use ahash::HashMap;
+use ahash::HashMapExt;
+use merc_data::DataExpression;
+use merc_data::DataExpressionRef;
+use merc_data::DataFunctionSymbol;
+use merc_data::DataVariable;
+use merc_data::is_data_variable;
+use merc_utilities::MercError;
+
+use crate::Rule;
+use crate::matching::nonlinear::EquivalenceClass;
+use crate::matching::nonlinear::check_equivalence_classes;
+use crate::matching::nonlinear::derive_equivalence_classes;
+use crate::utilities::DataPosition;
+use crate::utilities::DataPositionIndexed;
+use crate::utilities::create_var_map;
+
+use super::SetAutomaton;
+use super::is_supported_rule;
+
+/// A match of the pattern with the given index, see [PatternMatcher::matches].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PatternMatch {
+    /// The index of the pattern in the patterns of the matcher.
+    pub pattern: usize,
+    /// The subterm of the matched term for every variable of the pattern.
+    pub substitution: Vec<(DataVariable, DataExpression)>,
+}
+
+/// Matches terms against many patterns simultaneously, using a [SetAutomaton]
+/// that only finds matches at the root position.
+///
+/// # Details
+///
+/// This is independent of the rewrite engines, and can be used by any
+/// analysis that needs to find which of a large number of patterns match a
+/// term. The automaton only has to inspect every function symbol of the term
+/// at most once, regardless of the number of patterns.
+///
+/// The automaton only has transitions for the function symbols of the
+/// patterns. Every other function symbol of a term is therefore treated as an
+/// additional constant that does not occur in the patterns.
+pub struct PatternMatcher {
+    automaton: SetAutomaton<PatternAnnotation>,
+    unknown: DataFunctionSymbol,
+}
+
+/// The information of a pattern that is needed to announce its matches.
+struct PatternAnnotation {
+    /// The indices of the patterns, which are all equal.
+    patterns: Vec<usize>,
+    /// The positions that must contain equal subterms for non-linear patterns.
+    equivalence_classes: Vec<EquivalenceClass>,
+    /// The position of every variable in the pattern.
+    variables: Vec<(DataVariable, DataPosition)>,
+}
+
+impl PatternMatcher {
+    /// Creates a matcher for the given patterns, which fails when a pattern is
+    /// a variable or a higher order term.
+    pub fn new(patterns: &[DataExpression]) -> Result<PatternMatcher, MercError> {
+        // Equal patterns are only added to the automaton once.
+        let mut indices: HashMap<DataExpression, Vec<usize>> = HashMap::new();
+        let mut rules = Vec::new();
+        for (index, pattern) in patterns.iter().enumerate() {
+            if is_data_variable(pattern) {
+                return Err(format!("The pattern {pattern} is a variable, which cannot be matched").into());
+            }
+
+            let rule = Rule {
+                conditions: Vec::new(),
+                lhs: pattern.clone(),
+                rhs: pattern.clone(),
+            };
+
+            if !is_supported_rule(&rule) {
+                return Err(format!("The pattern {pattern} is a higher order term, which is not supported").into());
+            }
+
+            let entry = indices.entry(pattern.clone()).or_default();
+            if entry.is_empty() {
+                rules.push(rule);
+            }
+            entry.push(index);
+        }
+
+        // The constant that represents the symbols that do not occur in the patterns, which is never announced.
+        let unknown = DataFunctionSymbol::new("@unknown");
+        let unknown_pattern: DataExpression = unknown.clone().into();
+        indices.entry(unknown_pattern.clone()).or_default();
+        rules.push(Rule {
+            conditions: Vec::new(),
+            lhs: unknown_pattern.clone(),
+            rhs: unknown_pattern,
+        });
+
+        let automaton = SetAutomaton::from_rules(
+            &rules,
+            |rule| {
+                let mut variables: Vec<(DataVariable, DataPosition)> = create_var_map(&rule.lhs).into_iter().collect();
+                variables.sort_unstable_by(|(_, left), (_, right)| left.cmp(right));
+
+                PatternAnnotation {
+                    patterns: indices[&rule.lhs].clone(),
+                    equivalence_classes: derive_equivalence_classes(rule),
+                    variables,
+                }
+            },
+            true,
+        );
+
+        Ok(PatternMatcher { automaton, unknown })
+    }
+
+    /// Returns the number of states of the underlying automaton.
+    pub fn num_of_states(&self) -> usize {
+        self.automaton.num_of_states()
+    }
+
+    /// Returns all patterns that match the given term at the root position, ordered by their index.
+    pub fn matches(&self, term: &DataExpressionRef<'_>) -> Vec<PatternMatch> {
+        let mut result = Vec::new();
+
+        let mut state_index = 0;
+        loop {
+            let state = &self.automaton.states()[state_index];
+            let subterm = term.get_data_position(state.label());
+            let symbol = subterm.data_function_symbol();
+
+            let transitions = self.automaton.transitions();
+            let Some(transition) = transitions
+                .get(&(state_index, symbol.operation_id()))
+                .or_else(|| transitions.get(&(state_index, self.unknown.operation_id())))
+            else {
+                break;
+            };
+
+            for (_, annotation) in &transition.announcements {
+                if check_equivalence_classes(term, &annotation.equivalence_classes) {
+                    let substitution: Vec<(DataVariable, DataExpression)> = annotation
+                        .variables
+                        .iter()
+                        .map(|(variable, position)| (variable.clone(), term.get_data_position(position).protect()))
+                        .collect();
+
+                    for pattern in &annotation.patterns {
+                        result.push(PatternMatch {
+                            pattern: *pattern,
+                            substitution: substitution.clone(),
+                        });
+                    }
+                }
+            }
+
+            // An APMA has at most one destination for every transition.
+            match transition.destinations.first() {
+                Some((_, destination)) => state_index = *destination,
+                None => break,
+            }
+        }
+
+        result.sort_unstable_by_key(|pattern_match| pattern_match.pattern);
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ahash::AHashSet;
+
+    use super::*;
+
+    #[test]
+    fn test_pattern_matcher() {
+        let variables = AHashSet::from([String::from("x"), String::from("y")]);
+        let patterns: Vec<DataExpression> = ["f(x, a)", "f(a, y)", "f(x, x)", "g(x)", "f(x, a)", "f(x, b)"]
+            .iter()
+            .map(|pattern| DataExpression::from_string_untyped(pattern, &variables).unwrap())
+            .collect();
+
+        let matcher = PatternMatcher::new(&patterns).unwrap();
+
+        let term = DataExpression::from_string("f(a, a)").unwrap();
+        let matches = matcher.matches(&term.copy());
+        assert_eq!(
+            matches.iter().map(|m| m.pattern).collect::<Vec<_>>(),
+            vec![0, 1, 2, 4],
+            "All patterns with the same head symbol, except for f(x, b), match"
+        );
+
+        let a = DataExpression::from_string("a").unwrap();
+        for pattern_match in &matches {
+            assert!(pattern_match.substitution.iter().all(|(_, value)| *value == a));
+        }
+
+        let term = DataExpression::from_string("f(b, a)").unwrap();
+        assert_eq!(
+            matcher
+                .matches(&term.copy())
+                .iter()
+                .map(|m| m.pattern)
+                .collect::<Vec<_>>(),
+            vec![0, 4]
+        );
+
+        // Symbols that do not occur in the patterns can still be matched by variables.
+        let term = DataExpression::from_string("f(c, a)").unwrap();
+        assert_eq!(
+            matcher
+                .matches(&term.copy())
+                .iter()
+                .map(|m| m.pattern)
+                .collect::<Vec<_>>(),
+            vec![0, 4]
+        );
+
+        let term = DataExpression::from_string("h(a)").unwrap();
+        assert!(matcher.matches(&term.copy()).is_empty());
+    }
+
+    #[test]
+    fn test_pattern_matcher_variable() {
+        let variables = AHashSet::from([String::from("x")]);
+        let pattern = DataExpression::from_string_untyped("x", &variables).unwrap();
+        assert!(PatternMatcher::new(&[pattern]).is_err());
+    }
+}
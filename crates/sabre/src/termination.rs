@@ -0,0 +1,292 @@
+#![forbid(unsafe_code)]
+
+use std::fmt;
+use std::iter::once;
+
+use ahash::HashMap;
+use ahash::HashSet;
+use itertools::Itertools;
+
+use merc_data::DataExpressionRef;
+use merc_data::DataFunctionSymbol;
+use merc_data::DataFunctionSymbolRef;
+use merc_data::is_data_application;
+use merc_data::is_data_function_symbol;
+
+use crate::RewriteSpecification;
+use crate::Rule;
+use crate::set_automaton::is_supported_rule;
+
+/// The default maximum number of comparisons that [check_termination] uses to orient a single rule.
+pub const DEFAULT_TERMINATION_LIMIT: usize = 100_000;
+
+/// The number of times that [check_termination] searches for a precedence,
+/// where every attempt first orients the rules that failed in the previous one.
+const MAX_ATTEMPTS: usize = 3;
+
+/// The result of [check_termination].
+pub enum Termination {
+    /// All rules are oriented by the lexicographic path ordering with the given
+    /// precedence, so the rewrite system is terminating.
+    Terminating(Precedence),
+    /// The given rules could not be oriented, so the rewrite system is possibly non-terminating.
+    Unknown(Vec<Rule>),
+}
+
+/// A strict partial order on function symbols, which is kept transitively closed.
+#[derive(Clone, Default)]
+pub struct Precedence {
+    /// The function symbols that occur in the order, indexed by their operation id.
+    symbols: HashMap<usize, DataFunctionSymbol>,
+    /// The pairs (f, g) of operation ids such that f > g.
+    greater: HashSet<(usize, usize)>,
+}
+
+impl Precedence {
+    /// Returns true iff f > g in this precedence.
+    pub fn is_greater(&self, f: &DataFunctionSymbolRef<'_>, g: &DataFunctionSymbolRef<'_>) -> bool {
+        self.greater.contains(&(f.operation_id(), g.operation_id()))
+    }
+
+    /// Adds f > g and the pairs that follow by transitivity, which fails when
+    /// it would make the order cyclic. The precedence is then unchanged.
+    fn insert(&mut self, f: &DataFunctionSymbolRef<'_>, g: &DataFunctionSymbolRef<'_>) -> bool {
+        let (f_id, g_id) = (f.operation_id(), g.operation_id());
+        if f_id == g_id || self.greater.contains(&(g_id, f_id)) {
+            return false;
+        }
+
+        if self.greater.contains(&(f_id, g_id)) {
+            return true;
+        }
+
+        self.symbols.entry(f_id).or_insert_with(|| f.protect());
+        self.symbols.entry(g_id).or_insert_with(|| g.protect());
+
+        let above: Vec<usize> = once(f_id)
+            .chain(self.greater.iter().filter(|(_, to)| *to == f_id).map(|(from, _)| *from))
+            .collect();
+        let below: Vec<usize> = once(g_id)
+            .chain(self.greater.iter().filter(|(from, _)| *from == g_id).map(|(_, to)| *to))
+            .collect();
+
+        for from in &above {
+            for to in &below {
+                self.greater.insert((*from, *to));
+            }
+        }
+
+        true
+    }
+}
+
+impl fmt::Display for Precedence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            self.greater
+                .iter()
+                .map(|(from, to)| format!("{} > {}", self.symbols[from], self.symbols[to]))
+                .sorted()
+                .format(", ")
+        )
+    }
+}
+
+/// Checks whether the rewrite rules of the given specification are
+/// terminating, by searching for a precedence such that the lexicographic path
+/// ordering (LPO) orients every rule. At most `limit` comparisons are used to
+/// orient a single rule.
+///
+/// # Details
+///
+/// This is a heuristic, since the precedence is extended greedily while the
+/// rules are oriented one by one, and the LPO is not able to show termination
+/// of every terminating rewrite system. A result of [Termination::Unknown]
+/// therefore only means that the rewrite system is possibly non-terminating,
+/// for example when a rule duplicates its left-hand side.
+///
+/// For a conditional rule the left-hand side must also be greater than both
+/// sides of every condition, since the rewriters normalise the conditions.
+/// Higher order rules are never oriented.
+pub fn check_termination(spec: &RewriteSpecification, limit: usize) -> Termination {
+    let mut rules: Vec<&Rule> = spec.rewrite_rules().iter().collect();
+    let mut best: Option<Vec<Rule>> = None;
+
+    for _ in 0..MAX_ATTEMPTS {
+        let mut precedence = Precedence::default();
+        let (oriented, unoriented): (Vec<&Rule>, Vec<&Rule>) = rules
+            .iter()
+            .copied()
+            .partition(|rule| orient_rule(rule, &mut precedence, limit));
+
+        if unoriented.is_empty() {
+            return Termination::Terminating(precedence);
+        }
+
+        if best.as_ref().is_none_or(|best| unoriented.len() < best.len()) {
+            best = Some(unoriented.iter().map(|rule| (*rule).clone()).collect());
+        }
+
+        // The greedy choices for the other rules may have prevented these rules from being oriented.
+        rules = unoriented.into_iter().chain(oriented).collect();
+    }
+
+    Termination::Unknown(best.expect("At least one attempt has been made"))
+}
+
+/// Extends the precedence such that the LPO orients the given rule, which
+/// fails when no such extension is found. The precedence is then unchanged.
+fn orient_rule(rule: &Rule, precedence: &mut Precedence, limit: usize) -> bool {
+    if !is_supported_rule(rule) {
+        return false;
+    }
+
+    let lhs = rule.lhs.copy();
+    let mut search = LpoSearch { steps: 0, limit };
+    let mut extended = precedence.clone();
+
+    let oriented = search.greater(&lhs, &rule.rhs.copy(), &mut extended)
+        && rule.conditions.iter().all(|condition| {
+            search.greater(&lhs, &condition.lhs.copy(), &mut extended)
+                && search.greater(&lhs, &condition.rhs.copy(), &mut extended)
+        });
+
+    if oriented {
+        *precedence = extended;
+    }
+
+    oriented
+}
+
+/// The state of the search for a precedence that orients a single rule.
+struct LpoSearch {
+    /// The number of comparisons so far.
+    steps: usize,
+    /// The maximum number of comparisons, after which every comparison fails.
+    limit: usize,
+}
+
+impl LpoSearch {
+    /// Returns true iff s > t in the LPO for an extension of the given
+    /// precedence, in which case the precedence is extended. Otherwise, the
+    /// precedence is unchanged.
+    fn greater(&mut self, s: &DataExpressionRef<'_>, t: &DataExpressionRef<'_>, precedence: &mut Precedence) -> bool {
+        self.steps += 1;
+        if self.steps > self.limit || !is_function_term(s) {
+            return false;
+        }
+
+        // Some argument of s is equal to or greater than t, which also covers the case that t is a variable of s.
+        for argument in s.data_arguments() {
+            if argument == *t {
+                return true;
+            }
+
+            if self.greater(&argument, t, precedence) {
+                return true;
+            }
+        }
+
+        if !is_function_term(t) {
+            return false;
+        }
+
+        let f = s.data_function_symbol();
+        let g = t.data_function_symbol();
+        let mut extended = precedence.clone();
+
+        let result = if f.operation_id() == g.operation_id() {
+            self.lexicographic(s, t, &mut extended)
+        } else {
+            extended.insert(&f, &g)
+                && t.data_arguments()
+                    .all(|argument| self.greater(s, &argument, &mut extended))
+        };
+
+        if result {
+            *precedence = extended;
+        }
+
+        result
+    }
+
+    /// Returns true iff the arguments of s are lexicographically greater than
+    /// the arguments of t, where s and t have the same head symbol, and s is
+    /// greater than every argument of t after the first argument that differs.
+    fn lexicographic(
+        &mut self,
+        s: &DataExpressionRef<'_>,
+        t: &DataExpressionRef<'_>,
+        precedence: &mut Precedence,
+    ) -> bool {
+        if s.data_arguments().len() != t.data_arguments().len() {
+            return false;
+        }
+
+        let mut arguments = s.data_arguments().zip(t.data_arguments());
+        while let Some((left, right)) = arguments.next() {
+            if left != right {
+                return self.greater(&left, &right, precedence)
+                    && arguments.all(|(_, right)| self.greater(s, &right, precedence));
+            }
+        }
+
+        false
+    }
+}
+
+/// Returns true iff the term is a function symbol applied to zero or more arguments.
+fn is_function_term(term: &DataExpressionRef<'_>) -> bool {
+    is_data_application(term) || is_data_function_symbol(term)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utility::create_rewrite_rule;
+
+    use super::*;
+
+    #[test]
+    fn test_terminating_rewrite_system() {
+        let spec = RewriteSpecification::new(vec![
+            create_rewrite_rule("plus(x, 0)", "x", &["x"]).unwrap(),
+            create_rewrite_rule("plus(x, S(y))", "S(plus(x, y))", &["x", "y"]).unwrap(),
+            create_rewrite_rule("times(x, 0)", "0", &["x"]).unwrap(),
+            create_rewrite_rule("times(x, S(y))", "plus(times(x, y), x)", &["x", "y"]).unwrap(),
+            create_rewrite_rule("ack(0, y)", "S(y)", &["y"]).unwrap(),
+            create_rewrite_rule("ack(S(x), 0)", "ack(x, S(0))", &["x"]).unwrap(),
+            create_rewrite_rule("ack(S(x), S(y))", "ack(x, ack(S(x), y))", &["x", "y"]).unwrap(),
+        ]);
+
+        match check_termination(&spec, DEFAULT_TERMINATION_LIMIT) {
+            Termination::Terminating(precedence) => {
+                let precedence = precedence.to_string();
+                assert!(precedence.contains("times > plus"), "{precedence}");
+                assert!(precedence.contains("plus > S"), "{precedence}");
+            }
+            Termination::Unknown(rules) => panic!("The rules {rules:?} should be oriented"),
+        }
+    }
+
+    #[test]
+    fn test_non_terminating_rewrite_system() {
+        let commutativity = create_rewrite_rule("f(x, y)", "f(y, x)", &["x", "y"]).unwrap();
+        let cycle = create_rewrite_rule("g(a)", "h(a)", &[]).unwrap();
+        let spec = RewriteSpecification::new(vec![
+            commutativity.clone(),
+            cycle.clone(),
+            create_rewrite_rule("h(a)", "g(a)", &[]).unwrap(),
+            create_rewrite_rule("k(x)", "x", &["x"]).unwrap(),
+        ]);
+
+        match check_termination(&spec, DEFAULT_TERMINATION_LIMIT) {
+            Termination::Terminating(precedence) => panic!("Found the precedence {precedence}"),
+            Termination::Unknown(rules) => {
+                assert_eq!(rules.len(), 2);
+                assert!(rules.contains(&commutativity));
+            }
+        }
+    }
+}
@@ -4,6 +4,9 @@ use ahash::AHashSet;
 use merc_aterm::ATerm;
 use merc_data::to_untyped_data_expression;
 use merc_utilities::MercError;
+use rand::Rng;
+use rand::seq::IndexedRandom;
+use rand::seq::IteratorRandom;
 
 use crate::Rule;
 
@@ -22,3 +25,107 @@ pub fn create_rewrite_rule(lhs: &str, rhs: &str, variables: &[&str]) -> Result<R
         rhs: to_untyped_data_expression(rhs, Some(&vars)),
     })
 }
+
+/// A randomly generated rewrite specification, see [random_rewrite_specification].
+pub struct RandomRewriteSpecification {
+    /// The generated rewrite rules.
+    pub rules: Vec<Rule>,
+    /// The function symbols, with their arity, that can be used to construct terms.
+    pub symbols: Vec<(String, usize)>,
+    /// The constants that can be used to construct terms.
+    pub constants: Vec<String>,
+}
+
+/// Creates a random rewrite specification with the given number of defined
+/// function symbols, which is terminating and confluent such that every
+/// rewrite engine must yield the same normal form.
+///
+/// The symbols are split into constructors, which have no rules, and the
+/// defined symbols `f0, f1, ...`. Every rule for `fi` has a distinct
+/// constructor pattern as first argument and fresh variables elsewhere, so the
+/// rules are left-linear and non-overlapping. The right-hand side only uses
+/// constructors, variables of the left-hand side and defined symbols `fj` with
+/// `j < i`, which ensures termination.
+pub fn random_rewrite_specification(
+    rng: &mut impl Rng,
+    num_of_defined: usize,
+) -> Result<RandomRewriteSpecification, MercError> {
+    let constructors: Vec<(String, usize)> = vec![
+        ("a".to_string(), 0),
+        ("b".to_string(), 0),
+        ("s".to_string(), 1),
+        ("p".to_string(), 2),
+    ];
+
+    let mut rules = Vec::new();
+    let mut symbols = constructors.clone();
+    for i in 0..num_of_defined {
+        let name = format!("f{i}");
+        let arity = rng.random_range(1..=2);
+
+        let num_of_rules = rng.random_range(1..=constructors.len());
+        for (constructor, constructor_arity) in constructors.iter().choose_multiple(rng, num_of_rules) {
+            let mut variables = Vec::new();
+            let pattern_arguments = (0..*constructor_arity)
+                .map(|_| fresh_variable(&mut variables))
+                .collect();
+
+            let mut arguments = vec![application(constructor, pattern_arguments)];
+            for _ in 1..arity {
+                arguments.push(fresh_variable(&mut variables));
+            }
+
+            let lhs = application(&name, arguments);
+            let rhs = random_rhs(rng, 3, &variables, &symbols);
+
+            let variables: Vec<&str> = variables.iter().map(String::as_str).collect();
+            rules.push(create_rewrite_rule(&lhs, &rhs, &variables)?);
+        }
+
+        // Only later defined symbols can use this symbol in their right-hand side.
+        symbols.push((name, arity));
+    }
+
+    let (constants, symbols) = symbols.into_iter().partition::<Vec<_>, _>(|(_, arity)| *arity == 0);
+    Ok(RandomRewriteSpecification {
+        rules,
+        symbols,
+        constants: constants.into_iter().map(|(name, _)| name).collect(),
+    })
+}
+
+/// Returns a new variable that does not occur in the given variables, and adds it to them.
+fn fresh_variable(variables: &mut Vec<String>) -> String {
+    let variable = format!("x{}", variables.len());
+    variables.push(variable.clone());
+    variable
+}
+
+/// Returns the textual representation of the application of name to the given arguments.
+fn application(name: &str, arguments: Vec<String>) -> String {
+    if arguments.is_empty() {
+        name.to_string()
+    } else {
+        format!("{name}({})", arguments.join(", "))
+    }
+}
+
+/// Creates a random right-hand side of at most the given depth, consisting of
+/// the given variables and symbols.
+fn random_rhs(rng: &mut impl Rng, depth: usize, variables: &[String], symbols: &[(String, usize)]) -> String {
+    if depth == 0 || rng.random_bool(0.4) {
+        let constants = symbols.iter().filter(|(_, arity)| *arity == 0).map(|(name, _)| name);
+        variables
+            .iter()
+            .chain(constants)
+            .choose(rng)
+            .expect("There is at least one constant")
+            .clone()
+    } else {
+        let (name, arity) = symbols.choose(rng).expect("There is at least one symbol");
+        let arguments = (0..*arity)
+            .map(|_| random_rhs(rng, depth - 1, variables, symbols))
+            .collect();
+        application(name, arguments)
+    }
+}
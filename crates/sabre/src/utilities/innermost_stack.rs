@@ -61,7 +61,7 @@ impl InnermostStack {
                 Config::Rewrite(_) => {
                     unreachable!("This case should not happen");
                 }
-                Config::Return() => {
+                Config::Return() | Config::Store(_, _) => {
                     unreachable!("This case should not happen");
                 }
             }
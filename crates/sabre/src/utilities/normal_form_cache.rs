@@ -0,0 +1,131 @@
+#![forbid(unsafe_code)]
+
+use merc_aterm::Protected;
+use merc_aterm::TermMap;
+use merc_data::DataExpression;
+use merc_data::DataExpressionRef;
+
+/// A cache of the normal forms of terms that is kept between rewrites, such
+/// that shared subterms are only rewritten once.
+///
+/// # Details
+///
+/// The cache contains at most `capacity` terms. When it is full, the least
+/// recently used quarter of the entries is evicted at once, which avoids
+/// maintaining an ordered list of all entries on every lookup. The cached terms
+/// are kept alive by the cache until they are evicted.
+pub struct NormalFormCache {
+    /// Maps every term to its normal form and the time of its last use.
+    entries: Protected<TermMap<(DataExpressionRef<'static>, usize)>>,
+    capacity: usize,
+    /// Incremented on every use to determine the least recently used entries.
+    clock: usize,
+    hits: usize,
+}
+
+impl NormalFormCache {
+    /// Creates an empty cache that contains at most `capacity` terms.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: Protected::new(TermMap::new()),
+            capacity,
+            clock: 0,
+            hits: 0,
+        }
+    }
+
+    /// Returns the normal form of the given term, if it is cached.
+    pub fn get(&mut self, term: &DataExpressionRef<'_>) -> Option<DataExpression> {
+        self.clock += 1;
+
+        let mut write = self.entries.write();
+        let (normal_form, last_used) = write.get_mut(term)?;
+        *last_used = self.clock;
+        self.hits += 1;
+        Some(normal_form.protect())
+    }
+
+    /// Caches the normal form of the given term, evicting the least recently used entries when the cache is full.
+    pub fn insert(&mut self, term: &DataExpressionRef<'_>, normal_form: &DataExpressionRef<'_>) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        self.clock += 1;
+        if self.entries.read().len() >= self.capacity {
+            self.evict();
+        }
+
+        let mut write = self.entries.write();
+        let normal_form = write.protect(normal_form);
+        write.insert(term, (normal_form.into(), self.clock));
+    }
+
+    /// Returns the number of cached terms.
+    pub fn len(&self) -> usize {
+        self.entries.read().len()
+    }
+
+    /// Returns true iff the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of times that a normal form was found in the cache.
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+
+    /// Removes all entries from the cache.
+    pub fn clear(&mut self) {
+        self.entries.write().clear();
+    }
+
+    /// Evicts the least recently used entries until three quarters of the capacity remain.
+    fn evict(&mut self) {
+        let mut write = self.entries.write();
+
+        let mut last_used: Vec<usize> = write.iter().map(|(_, (_, last_used))| *last_used).collect();
+        let num_of_kept = (self.capacity - self.capacity / 4).min(last_used.len() - 1);
+        let index = last_used.len() - num_of_kept - 1;
+        let (_, threshold, _) = last_used.select_nth_unstable(index);
+
+        // The times of use are unique, so exactly the oldest entries are removed.
+        let threshold = *threshold;
+        write.retain(|_, (_, last_used)| *last_used > threshold);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normal_form_cache_eviction() {
+        let mut cache = NormalFormCache::new(8);
+        let terms: Vec<DataExpression> = (0..16)
+            .map(|i| DataExpression::from_string(&format!("f(a{i})")).unwrap())
+            .collect();
+        let normal_form = DataExpression::from_string("a").unwrap();
+
+        for term in &terms[0..8] {
+            cache.insert(&term.copy(), &normal_form.copy());
+        }
+
+        // Use the first term, which makes it the most recently used one.
+        assert!(cache.get(&terms[0].copy()).is_some());
+
+        for term in &terms[8..10] {
+            cache.insert(&term.copy(), &normal_form.copy());
+        }
+
+        assert!(cache.len() <= 8);
+        assert!(cache.get(&terms[0].copy()).is_some(), "Recently used terms are kept");
+        assert!(
+            cache.get(&terms[1].copy()).is_none(),
+            "The least recently used term is evicted"
+        );
+        assert!(cache.get(&terms[9].copy()).is_some());
+        assert_eq!(cache.hits(), 3);
+    }
+}
@@ -53,33 +53,40 @@ pub enum Config<'a> {
     Term(DataExpressionRef<'a>, usize),
     /// Yields the given index as returned term.
     Return(),
+    /// Caches the term at the given index as the normal form of the given term, see [crate::utilities::NormalFormCache].
+    Store(DataExpressionRef<'a>, usize),
 }
 
 impl Markable for Config<'_> {
     fn mark(&self, marker: &mut Marker<'_>) {
-        if let Config::Construct(t, _, _) = self {
-            t.mark(marker);
+        match self {
+            Config::Construct(t, _, _) => t.mark(marker),
+            Config::Store(t, _) => t.mark(marker),
+            _ => {}
         }
     }
 
     fn contains_term(&self, term: &ATermRef<'_>) -> bool {
-        if let Config::Construct(t, _, _) = self {
-            t.contains_term(term)
-        } else {
-            false
+        match self {
+            Config::Construct(t, _, _) => t.contains_term(term),
+            Config::Store(t, _) => t.contains_term(term),
+            _ => false,
         }
     }
 
     fn contains_symbol(&self, symbol: &SymbolRef<'_>) -> bool {
-        if let Config::Construct(t, _, _) = self {
-            t.contains_symbol(symbol)
-        } else {
-            false
+        match self {
+            Config::Construct(t, _, _) => t.contains_symbol(symbol),
+            Config::Store(t, _) => t.contains_symbol(symbol),
+            _ => false,
         }
     }
 
     fn len(&self) -> usize {
-        if let Config::Construct(_, _, _) = self { 1 } else { 0 }
+        match self {
+            Config::Construct(_, _, _) | Config::Store(_, _) => 1,
+            _ => 0,
+        }
     }
 }
 
@@ -106,6 +113,7 @@ impl fmt::Display for Config<'_> {
                 write!(f, "Term({term}, {result})")
             }
             Config::Return() => write!(f, "Return()"),
+            Config::Store(term, result) => write!(f, "Store({term}, {result})"),
         }
     }
 }
@@ -207,7 +215,7 @@ impl TermStack {
                     Config::Rewrite(_) => {
                         unreachable!("This case should not happen");
                     }
-                    Config::Return() => {
+                    Config::Return() | Config::Store(_, _) => {
                         unreachable!("This case should not happen");
                     }
                 }
@@ -261,6 +269,7 @@ impl Clone for TermStack {
                     write.push(Config::Term(t.copy(), *y));
                 }
                 Config::Return() => write.push(Config::Return()),
+                Config::Store(t, y) => write.push(Config::Store(t.copy(), *y)),
             }
         }
         drop(write);
@@ -0,0 +1,112 @@
+use merc_aterm::ATerm;
+use merc_aterm::random_term;
+use merc_data::DataExpression;
+use merc_data::to_untyped_data_expression;
+use merc_sabre::InnermostRewriter;
+use merc_sabre::NaiveRewriter;
+use merc_sabre::RewriteEngine;
+use merc_sabre::RewriteSpecification;
+use merc_sabre::RewriteStats;
+use merc_sabre::Rule;
+use merc_sabre::SabreRewriter;
+use merc_sabre::test_utility::create_rewrite_rule;
+use merc_sabre::test_utility::random_rewrite_specification;
+use merc_utilities::random_test_with_corpus;
+
+/// Rewrites the term with every rewrite engine, and returns a description of
+/// the normal forms when they are not all equal.
+fn find_discrepancy(rules: &[Rule], term: &DataExpression) -> Option<String> {
+    let spec = RewriteSpecification::new(rules.to_vec());
+
+    let naive = NaiveRewriter::new(&spec).rewrite(term);
+    let innermost = InnermostRewriter::new(&spec).rewrite(term);
+    let sabre = SabreRewriter::new(&spec).rewrite(term);
+
+    if naive != innermost || naive != sabre {
+        Some(format!("naive: {naive}\ninnermost: {innermost}\nsabre: {sabre}"))
+    } else {
+        None
+    }
+}
+
+/// Shrinks a discrepancy to a minimal failing case, by removing rules and
+/// replacing the term by one of its arguments as long as the rewrite engines
+/// still disagree.
+fn shrink(mut rules: Vec<Rule>, mut term: DataExpression) -> (Vec<Rule>, DataExpression) {
+    loop {
+        let removable = (0..rules.len()).find(|&index| {
+            let mut smaller = rules.clone();
+            smaller.remove(index);
+            find_discrepancy(&smaller, &term).is_some()
+        });
+
+        if let Some(index) = removable {
+            rules.remove(index);
+            continue;
+        }
+
+        let argument = term
+            .data_arguments()
+            .map(|argument| argument.protect())
+            .find(|argument| find_discrepancy(&rules, argument).is_some());
+
+        match argument {
+            Some(argument) => term = argument,
+            None => return (rules, term),
+        }
+    }
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_random_rewriters_agree() {
+    random_test_with_corpus(
+        concat!(env!("CARGO_MANIFEST_DIR"), "/tests/seeds/differential_test.txt"),
+        100,
+        |rng| {
+            let spec = random_rewrite_specification(rng, 4).unwrap();
+
+            for _ in 0..10 {
+                let term = to_untyped_data_expression(random_term(rng, &spec.symbols, &spec.constants, 10), None);
+
+                if find_discrepancy(&spec.rules, &term).is_some() {
+                    let (rules, term) = shrink(spec.rules.clone(), term);
+                    let discrepancy = find_discrepancy(&rules, &term).expect("Shrinking preserves the discrepancy");
+
+                    panic!(
+                        "The rewrite engines disagree on {term} for the rules\n{}\n{discrepancy}",
+                        RewriteSpecification::new(rules)
+                    );
+                }
+            }
+        },
+    );
+}
+
+#[test]
+fn test_rewrite_stats() {
+    let rules = vec![
+        create_rewrite_rule("f(x)", "g(x)", &["x"]).unwrap(),
+        create_rewrite_rule("g(a)", "b", &[]).unwrap(),
+    ];
+    let spec = RewriteSpecification::new(rules.clone());
+    let term = to_untyped_data_expression(ATerm::from_string("f(f(a))").unwrap(), None);
+
+    let rewriters: Vec<Box<dyn RewriteEngine>> = vec![
+        Box::new(NaiveRewriter::new(&spec)),
+        Box::new(InnermostRewriter::new(&spec)),
+        Box::new(SabreRewriter::new(&spec)),
+    ];
+
+    for mut rewriter in rewriters {
+        let mut stats = RewriteStats::default();
+        rewriter.rewrite_with_stats(&term, Some(&mut stats));
+        rewriter.rewrite_with_stats(&term, Some(&mut stats));
+
+        // Every rewrite of f(f(a)) to g(b) applies the first rule twice and the second rule once.
+        assert_eq!(stats.rewrite_steps, 6);
+        assert_eq!(stats.rule_applications[&rules[0]], 4);
+        assert_eq!(stats.rule_applications[&rules[1]], 2);
+        assert_eq!(stats.max_term_size, 3);
+    }
+}
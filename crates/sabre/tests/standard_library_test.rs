@@ -0,0 +1,191 @@
+use std::collections::BTreeSet;
+
+use rand::Rng;
+use test_case::test_case;
+
+use merc_data::DataApplication;
+use merc_data::DataExpression;
+use merc_data::DataFunctionSymbol;
+use merc_data::bool_expression;
+use merc_data::int_expression;
+use merc_data::nat_expression;
+use merc_data::pos_expression;
+use merc_data::standard_data_specification;
+use merc_data::to_data_expression;
+use merc_sabre::InnermostRewriter;
+use merc_sabre::RewriteEngine;
+use merc_sabre::RewriteSpecification;
+use merc_syntax::DataExpr;
+use merc_utilities::random_test;
+
+/// Returns the application of the function symbol with the given name.
+fn apply(name: &str, arguments: &[DataExpression]) -> DataExpression {
+    DataApplication::with_args(&DataFunctionSymbol::new(name), arguments).into()
+}
+
+/// Parses the given expression, which should not contain numerals.
+fn parse(text: &str) -> DataExpression {
+    to_data_expression(&DataExpr::parse(text).unwrap(), &Default::default()).unwrap()
+}
+
+/// The innermost rewriter for the standard data specification. The sabre
+/// rewriter is not used, since its set automaton for these rules has thousands
+/// of states and takes minutes to construct.
+struct Rewriter {
+    innermost: InnermostRewriter,
+}
+
+impl Rewriter {
+    fn new() -> Self {
+        let spec = RewriteSpecification::from(&standard_data_specification());
+        Self {
+            innermost: InnermostRewriter::new(&spec),
+        }
+    }
+
+    /// Returns the normal form of the term.
+    fn normal_form(&mut self, term: &DataExpression) -> DataExpression {
+        self.innermost.rewrite(term)
+    }
+}
+
+// The expected normal forms are those computed by mCRL2's rewriter.
+#[test_case("!(true && false) => false", bool_expression(false) ; "booleans")]
+#[test_case("[true, false] ++ [true]", parse("[true, false, true]") ; "concatenation")]
+#[test_case("[true] <| false", parse("[true, false]") ; "snoc")]
+#[test_case("rhead([false, true]) && head([true, false])", bool_expression(true) ; "head")]
+#[test_case("rtail([true, false, false])", parse("[true, false]") ; "rtail")]
+#[test_case("#([true, false, true])", nat_expression(3) ; "list size")]
+#[test_case("false in [true, true]", bool_expression(false) ; "list membership")]
+#[test_case("{true, false} == {false, true, false}", bool_expression(true) ; "set equality")]
+#[test_case("#({true} + {false})", nat_expression(2) ; "set union")]
+#[test_case("{true, false} - {true}", parse("{false}") ; "set difference")]
+#[test_case("{true} < {false, true}", bool_expression(true) ; "strict subset")]
+fn test_standard_data_specification(text: &str, expected: DataExpression) {
+    let mut rewriter = Rewriter::new();
+
+    assert_eq!(rewriter.normal_form(&parse(text)), rewriter.normal_form(&expected));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_random_arithmetic() {
+    let mut rewriter = Rewriter::new();
+
+    random_test(100, |rng| {
+        let (a, b) = (rng.random_range(1..1000u64), rng.random_range(1..1000u64));
+        let (p, q) = (pos_expression(a), pos_expression(b));
+
+        assert_eq!(
+            rewriter.normal_form(&apply("+", &[p.clone(), q.clone()])),
+            pos_expression(a + b)
+        );
+        assert_eq!(
+            rewriter.normal_form(&apply("*", &[p.clone(), q.clone()])),
+            pos_expression(a * b)
+        );
+        assert_eq!(
+            rewriter.normal_form(&apply("-", &[p.clone(), q.clone()])),
+            int_expression(a as i64 - b as i64)
+        );
+        assert_eq!(
+            rewriter.normal_form(&apply("<", &[p.clone(), q.clone()])),
+            bool_expression(a < b)
+        );
+        assert_eq!(
+            rewriter.normal_form(&apply("<=", &[p.clone(), q])),
+            bool_expression(a <= b)
+        );
+        assert_eq!(rewriter.normal_form(&apply("pred", &[p])), nat_expression(a - 1));
+
+        let (m, n) = (a - 1, rng.random_range(0..1000u64));
+        let (x, y) = (nat_expression(m), nat_expression(n));
+        assert_eq!(
+            rewriter.normal_form(&apply("+", &[x.clone(), y.clone()])),
+            nat_expression(m + n)
+        );
+        assert_eq!(
+            rewriter.normal_form(&apply("*", &[x.clone(), y.clone()])),
+            nat_expression(m * n)
+        );
+        assert_eq!(
+            rewriter.normal_form(&apply("-", &[x.clone(), y.clone()])),
+            int_expression(m as i64 - n as i64)
+        );
+        assert_eq!(rewriter.normal_form(&apply("max", &[x, y])), nat_expression(m.max(n)));
+
+        let (i, j) = (rng.random_range(-500..500i64), rng.random_range(-500..500i64));
+        let (x, y) = (int_expression(i), int_expression(j));
+        assert_eq!(
+            rewriter.normal_form(&apply("+", &[x.clone(), y.clone()])),
+            int_expression(i + j)
+        );
+        assert_eq!(
+            rewriter.normal_form(&apply("-", &[x.clone(), y.clone()])),
+            int_expression(i - j)
+        );
+        assert_eq!(
+            rewriter.normal_form(&apply("*", &[x.clone(), y.clone()])),
+            int_expression(i * j)
+        );
+        assert_eq!(
+            rewriter.normal_form(&apply("<", &[x.clone(), y.clone()])),
+            bool_expression(i < j)
+        );
+        assert_eq!(
+            rewriter.normal_form(&apply("==", &[x.clone(), y])),
+            bool_expression(i == j)
+        );
+        assert_eq!(
+            rewriter.normal_form(&apply("abs", &[x])),
+            nat_expression(i.unsigned_abs())
+        );
+    });
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_random_sets() {
+    let mut rewriter = Rewriter::new();
+
+    random_test(100, |rng| {
+        let mut random_set = || -> (BTreeSet<u64>, DataExpression) {
+            let elements: Vec<u64> = (0..rng.random_range(0..6)).map(|_| rng.random_range(0..10)).collect();
+            let term = elements.iter().fold(parse("{}"), |set, element| {
+                apply("@fset_insert", &[nat_expression(*element), set])
+            });
+            (elements.into_iter().collect(), term)
+        };
+
+        // The normal form of a set is the sorted list of its elements.
+        let expected = |set: BTreeSet<u64>| {
+            set.iter().rev().fold(parse("{}"), |result, element| {
+                apply("@fset_cons", &[nat_expression(*element), result])
+            })
+        };
+
+        let (left, s) = random_set();
+        let (right, t) = random_set();
+        assert_eq!(rewriter.normal_form(&s), expected(left.clone()));
+        assert_eq!(
+            rewriter.normal_form(&apply("+", &[s.clone(), t.clone()])),
+            expected(&left | &right)
+        );
+        assert_eq!(
+            rewriter.normal_form(&apply("*", &[s.clone(), t.clone()])),
+            expected(&left & &right)
+        );
+        assert_eq!(
+            rewriter.normal_form(&apply("-", &[s.clone(), t.clone()])),
+            expected(&left - &right)
+        );
+        assert_eq!(
+            rewriter.normal_form(&apply("<=", &[s.clone(), t])),
+            bool_expression(left.is_subset(&right))
+        );
+        assert_eq!(
+            rewriter.normal_form(&apply("#", &[s])),
+            nat_expression(left.len() as u64)
+        );
+    });
+}
@@ -0,0 +1,89 @@
+#![forbid(unsafe_code)]
+
+use std::fmt;
+use std::time::Duration;
+
+#[cfg(feature = "merc_metrics")]
+use crate::sync::AtomicUsize;
+#[cfg(feature = "merc_metrics")]
+use crate::sync::Ordering;
+
+/// Metrics about the contention of a [crate::BfSharedMutex], accumulated over all its instances.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BfSharedMutexMetrics {
+    /// The number of times shared (read) access was acquired.
+    pub shared_acquisitions: usize,
+    /// The number of times exclusive (write) access was acquired.
+    pub exclusive_acquisitions: usize,
+    /// The total time that readers waited because their forbidden flag was set.
+    pub forbidden_wait: Duration,
+    /// The total time that writers waited for readers to leave their busy section.
+    pub busy_wait: Duration,
+}
+
+impl fmt::Display for BfSharedMutexMetrics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Acquired {} shared and {} exclusive locks",
+            self.shared_acquisitions, self.exclusive_acquisitions
+        )?;
+        write!(
+            f,
+            "Readers waited {:.3}s on forbidden flags, writers waited {:.3}s on busy flags",
+            self.forbidden_wait.as_secs_f64(),
+            self.busy_wait.as_secs_f64()
+        )
+    }
+}
+
+/// The counters of a single instance of the shared mutex, only updated by the owning thread.
+#[cfg(feature = "merc_metrics")]
+#[derive(Default)]
+pub(crate) struct ControlMetrics {
+    shared_acquisitions: AtomicUsize,
+    exclusive_acquisitions: AtomicUsize,
+    forbidden_wait_nanos: AtomicUsize,
+    busy_wait_nanos: AtomicUsize,
+}
+
+#[cfg(feature = "merc_metrics")]
+impl ControlMetrics {
+    pub(crate) fn record_shared(&self) {
+        self.shared_acquisitions.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_exclusive(&self) {
+        self.exclusive_acquisitions.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_forbidden_wait(&self, duration: Duration) {
+        self.forbidden_wait_nanos
+            .fetch_add(duration.as_nanos() as usize, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_busy_wait(&self, duration: Duration) {
+        self.busy_wait_nanos
+            .fetch_add(duration.as_nanos() as usize, Ordering::Relaxed);
+    }
+
+    /// Adds the counters of this instance to the given metrics.
+    pub(crate) fn add_to(&self, metrics: &mut BfSharedMutexMetrics) {
+        metrics.shared_acquisitions += self.shared_acquisitions.load(Ordering::Relaxed);
+        metrics.exclusive_acquisitions += self.exclusive_acquisitions.load(Ordering::Relaxed);
+        metrics.forbidden_wait += Duration::from_nanos(self.forbidden_wait_nanos.load(Ordering::Relaxed) as u64);
+        metrics.busy_wait += Duration::from_nanos(self.busy_wait_nanos.load(Ordering::Relaxed) as u64);
+    }
+
+    /// Adds the counters of the given instance to this one, used to retain the counters of dropped instances.
+    pub(crate) fn merge(&self, other: &ControlMetrics) {
+        self.shared_acquisitions
+            .fetch_add(other.shared_acquisitions.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.exclusive_acquisitions
+            .fetch_add(other.exclusive_acquisitions.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.forbidden_wait_nanos
+            .fetch_add(other.forbidden_wait_nanos.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.busy_wait_nanos
+            .fetch_add(other.busy_wait_nanos.load(Ordering::Relaxed), Ordering::Relaxed);
+    }
+}
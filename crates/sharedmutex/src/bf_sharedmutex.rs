@@ -5,14 +5,26 @@ use std::error::Error;
 use std::fmt::Debug;
 use std::ops::Deref;
 use std::ops::DerefMut;
-use std::sync::Arc;
-use std::sync::Mutex;
-use std::sync::MutexGuard;
-use std::sync::atomic::AtomicBool;
-use std::sync::atomic::Ordering;
+use std::sync::PoisonError;
 
 use crossbeam_utils::CachePadded;
 
+#[cfg(feature = "merc_metrics")]
+use merc_utilities::Instant;
+
+use crate::BfSharedMutexMetrics;
+#[cfg(feature = "merc_metrics")]
+use crate::bf_metrics::ControlMetrics;
+
+use crate::lock_order::LockOrderGuard;
+use crate::lock_order::lock_removed;
+use crate::sync::Arc;
+use crate::sync::AtomicBool;
+use crate::sync::Mutex;
+use crate::sync::MutexGuard;
+use crate::sync::Ordering;
+use crate::sync::spin_loop;
+
 /// A shared mutex (readers-writer lock) implementation based on the so-called
 /// busy-forbidden protocol.
 ///
@@ -44,6 +56,10 @@ unsafe impl<T> Send for BfSharedMutex<T> {}
 struct SharedMutexControl {
     busy: AtomicBool,
     forbidden: AtomicBool,
+
+    /// The contention counters of this instance.
+    #[cfg(feature = "merc_metrics")]
+    metrics: ControlMetrics,
 }
 
 /// The shared data between all instances of the shared mutex.
@@ -53,6 +69,14 @@ struct SharedData<T> {
 
     /// The list of all the shared mutex instances.
     other: Mutex<Vec<Option<Arc<CachePadded<SharedMutexControl>>>>>,
+
+    /// Set when a thread panicked while holding exclusive access, such that the
+    /// object might be left in an inconsistent state.
+    poisoned: AtomicBool,
+
+    /// The contention counters of the instances that have been dropped.
+    #[cfg(feature = "merc_metrics")]
+    retired: ControlMetrics,
 }
 
 impl<T> BfSharedMutex<T> {
@@ -65,6 +89,9 @@ impl<T> BfSharedMutex<T> {
             shared: Arc::new(CachePadded::new(SharedData {
                 object: UnsafeCell::new(object),
                 other: Mutex::new(vec![Some(control.clone())]),
+                poisoned: AtomicBool::new(false),
+                #[cfg(feature = "merc_metrics")]
+                retired: ControlMetrics::default(),
             })),
             index: 0,
         }
@@ -76,7 +103,7 @@ impl<T> Clone for BfSharedMutex<T> {
         // Register a new instance in the other list.
         let control = Arc::new(CachePadded::new(SharedMutexControl::default()));
 
-        let mut other = self.shared.other.lock().expect("Failed to lock mutex");
+        let mut other = self.shared.other.lock().unwrap_or_else(PoisonError::into_inner);
         other.push(Some(control.clone()));
 
         Self {
@@ -89,10 +116,18 @@ impl<T> Clone for BfSharedMutex<T> {
 
 impl<T> Drop for BfSharedMutex<T> {
     fn drop(&mut self) {
-        let mut other = self.shared.other.lock().expect("Failed to lock mutex");
+        let mut other = self.shared.other.lock().unwrap_or_else(PoisonError::into_inner);
 
         // Remove ourselves from the table.
         other[self.index] = None;
+
+        #[cfg(feature = "merc_metrics")]
+        self.shared.retired.merge(&self.control.metrics);
+
+        if Arc::strong_count(&self.shared) == 1 {
+            // This is the last instance, so the lock no longer exists.
+            lock_removed(self.lock_id());
+        }
     }
 }
 
@@ -101,6 +136,7 @@ impl<T> Drop for BfSharedMutex<T> {
 pub struct BfSharedMutexWriteGuard<'a, T> {
     mutex: &'a BfSharedMutex<T>,
     guard: MutexGuard<'a, Vec<Option<Arc<CachePadded<SharedMutexControl>>>>>,
+    _order: LockOrderGuard,
 }
 
 /// Allow dereferencing the underlying object.
@@ -122,9 +158,13 @@ impl<T> DerefMut for BfSharedMutexWriteGuard<'_, T> {
 
 impl<T> Drop for BfSharedMutexWriteGuard<'_, T> {
     fn drop(&mut self) {
+        if std::thread::panicking() {
+            self.mutex.shared.poisoned.store(true, Ordering::SeqCst);
+        }
+
         // Allow other threads to acquire access to the shared mutex.
         for control in self.guard.iter().flatten() {
-            control.forbidden.store(false, std::sync::atomic::Ordering::SeqCst);
+            control.forbidden.store(false, Ordering::SeqCst);
         }
 
         // The mutex guard is then dropped here.
@@ -133,6 +173,7 @@ impl<T> Drop for BfSharedMutexWriteGuard<'_, T> {
 
 pub struct BfSharedMutexReadGuard<'a, T> {
     mutex: &'a BfSharedMutex<T>,
+    _order: LockOrderGuard,
 }
 
 /// Allow dereferences the underlying object.
@@ -156,6 +197,45 @@ impl<T> Drop for BfSharedMutexReadGuard<'_, T> {
     }
 }
 
+/// The guard object for upgradeable read access to the underlying object.
+///
+/// # Details
+///
+/// Holding this guard excludes writers and other upgradeable readers, but
+/// regular readers can still acquire shared access. The guard can be upgraded
+/// to exclusive access without releasing the lock in between.
+#[must_use = "Dropping the guard unlocks the shared mutex immediately"]
+pub struct BfSharedMutexUpgradableReadGuard<'a, T> {
+    mutex: &'a BfSharedMutex<T>,
+    guard: MutexGuard<'a, Vec<Option<Arc<CachePadded<SharedMutexControl>>>>>,
+    order: LockOrderGuard,
+}
+
+/// Allow dereferences the underlying object.
+impl<T> Deref for BfSharedMutexUpgradableReadGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        // No writer can exist while we hold the mutex of the other table, so only immutable access is provided.
+        unsafe { &*self.mutex.shared.object.get() }
+    }
+}
+
+impl<'a, T> BfSharedMutexUpgradableReadGuard<'a, T> {
+    /// Upgrades the guard to exclusive access, without allowing a writer in between.
+    pub fn upgrade(self) -> BfSharedMutexWriteGuard<'a, T> {
+        let BfSharedMutexUpgradableReadGuard { mutex, guard, order } = self;
+        mutex.forbid_others(&guard);
+
+        // We now have exclusive access to the object according to the protocol
+        BfSharedMutexWriteGuard {
+            mutex,
+            guard,
+            _order: order,
+        }
+    }
+}
+
 impl<T> BfSharedMutex<T> {
     /// Provides read access to the underlying object, allowing multiple immutable references to it.
     #[inline]
@@ -165,8 +245,14 @@ impl<T> BfSharedMutex<T> {
             "Cannot acquire read access again inside a reader section"
         );
 
+        #[cfg(feature = "merc_metrics")]
+        let mut wait_start = None;
+
         self.control.busy.store(true, Ordering::SeqCst);
         while self.control.forbidden.load(Ordering::SeqCst) {
+            #[cfg(feature = "merc_metrics")]
+            wait_start.get_or_insert_with(Instant::now);
+
             self.control.busy.store(false, Ordering::SeqCst);
 
             // Wait for the mutex of the writer.
@@ -175,8 +261,19 @@ impl<T> BfSharedMutex<T> {
             self.control.busy.store(true, Ordering::SeqCst);
         }
 
+        #[cfg(feature = "merc_metrics")]
+        {
+            if let Some(start) = wait_start {
+                self.control.metrics.record_forbidden_wait(start.elapsed());
+            }
+            self.control.metrics.record_shared();
+        }
+
         // We now have immutable access to the object due to the protocol.
-        Ok(BfSharedMutexReadGuard { mutex: self })
+        Ok(BfSharedMutexReadGuard {
+            mutex: self,
+            _order: LockOrderGuard::new(self.lock_id()),
+        })
     }
 
     /// Creates a new `BfSharedMutexReadGuard` without checking if the lock is held.
@@ -190,7 +287,10 @@ impl<T> BfSharedMutex<T> {
     /// with `mem::forget`.
     #[inline]
     pub unsafe fn create_read_guard_unchecked(&self) -> BfSharedMutexReadGuard<'_, T> {
-        BfSharedMutexReadGuard { mutex: self }
+        BfSharedMutexReadGuard {
+            mutex: self,
+            _order: LockOrderGuard::assume_acquired(self.lock_id()),
+        }
     }
 
     /// Returns a raw pointer to the underlying data.
@@ -216,40 +316,110 @@ impl<T> BfSharedMutex<T> {
         let other = self.shared.other.lock()?;
 
         debug_assert!(
-            !self.control.busy.load(std::sync::atomic::Ordering::SeqCst),
+            !self.control.busy.load(Ordering::SeqCst),
             "Can only exclusive lock outside of a shared lock, no upgrading!"
         );
         debug_assert!(
-            !self.control.forbidden.load(std::sync::atomic::Ordering::SeqCst),
+            !self.control.forbidden.load(Ordering::SeqCst),
             "Can not acquire exclusive lock inside of exclusive section"
         );
 
+        self.forbid_others(&other);
+
+        // We now have exclusive access to the object according to the protocol
+        Ok(BfSharedMutexWriteGuard {
+            mutex: self,
+            guard: other,
+            _order: LockOrderGuard::new(self.lock_id()),
+        })
+    }
+
+    /// Provides upgradeable read access to the underlying object, which can
+    /// later be upgraded to write access without releasing the lock.
+    ///
+    /// # Details
+    ///
+    /// This follows the protocol extension of the recursive variant in the
+    /// paper, where the upgrading thread holds the mutex of the writers during
+    /// its read section. Other readers can continue, but writers and other
+    /// upgradeable readers must wait until the guard is dropped.
+    #[inline]
+    pub fn upgradable_read<'a>(&'a self) -> Result<BfSharedMutexUpgradableReadGuard<'a, T>, Box<dyn Error + 'a>> {
+        debug_assert!(
+            !self.control.busy.load(Ordering::SeqCst),
+            "Can only acquire upgradeable access outside of a shared lock"
+        );
+
+        let other = self.shared.other.lock()?;
+
+        #[cfg(feature = "merc_metrics")]
+        self.control.metrics.record_shared();
+
+        // No writer can be active while we hold the mutex, so no instance is forbidden.
+        Ok(BfSharedMutexUpgradableReadGuard {
+            mutex: self,
+            guard: other,
+            order: LockOrderGuard::new(self.lock_id()),
+        })
+    }
+
+    /// Sets the forbidden flag of all instances and waits for the other instances to leave their busy section.
+    fn forbid_others(&self, other: &[Option<Arc<CachePadded<SharedMutexControl>>>]) {
         // Make all instances wait due to forbidden access.
         for control in other.iter().flatten() {
             debug_assert!(
-                !control.forbidden.load(std::sync::atomic::Ordering::SeqCst),
+                !control.forbidden.load(Ordering::SeqCst),
                 "Other instance is already forbidden, this cannot happen"
             );
 
-            control.forbidden.store(true, std::sync::atomic::Ordering::SeqCst);
+            control.forbidden.store(true, Ordering::SeqCst);
         }
 
+        #[cfg(feature = "merc_metrics")]
+        let wait_start = Instant::now();
+
         // Wait for the instances to exit their busy status.
         for (index, option) in other.iter().enumerate() {
             if index != self.index {
                 if let Some(object) = option {
-                    while object.busy.load(std::sync::atomic::Ordering::SeqCst) {
-                        std::hint::spin_loop();
+                    while object.busy.load(Ordering::SeqCst) {
+                        spin_loop();
                     }
                 }
             }
         }
 
-        // We now have exclusive access to the object according to the protocol
-        Ok(BfSharedMutexWriteGuard {
-            mutex: self,
-            guard: other,
-        })
+        #[cfg(feature = "merc_metrics")]
+        {
+            self.control.metrics.record_busy_wait(wait_start.elapsed());
+            self.control.metrics.record_exclusive();
+        }
+    }
+
+    /// Returns the contention metrics accumulated over all instances of this
+    /// shared mutex, which are only available when the `merc_metrics` feature
+    /// is enabled. Must not be called while holding exclusive access.
+    pub fn metrics(&self) -> Option<BfSharedMutexMetrics> {
+        #[cfg(feature = "merc_metrics")]
+        {
+            let other = self.shared.other.lock().unwrap_or_else(PoisonError::into_inner);
+
+            let mut metrics = BfSharedMutexMetrics::default();
+            self.shared.retired.add_to(&mut metrics);
+            for control in other.iter().flatten() {
+                control.metrics.add_to(&mut metrics);
+            }
+
+            Some(metrics)
+        }
+
+        #[cfg(not(feature = "merc_metrics"))]
+        None
+    }
+
+    /// Returns an identifier of the lock that is shared by all instances.
+    fn lock_id(&self) -> usize {
+        &**self.shared as *const SharedData<T> as usize
     }
 
     /// Check if the shared mutex is locked shared, meaning no other thread has a read lock.
@@ -262,6 +432,13 @@ impl<T> BfSharedMutex<T> {
         self.control.forbidden.load(Ordering::Relaxed)
     }
 
+    /// Returns true iff a thread panicked while holding exclusive access, in
+    /// which case the object might be in an inconsistent state. After this the
+    /// `write` operation fails, as for a poisoned [std::sync::Mutex].
+    pub fn is_poisoned(&self) -> bool {
+        self.shared.poisoned.load(Ordering::Relaxed)
+    }
+
     /// Obtain mutable access to the object without locking, is safe because we have mutable access.
     pub fn get_mut(&mut self) -> &mut T {
         unsafe { &mut *self.shared.object.get() }
@@ -274,12 +451,16 @@ impl<T: Debug> Debug for BfSharedMutex<T> {
             .entry(&"busy", &self.control.busy.load(Ordering::SeqCst))
             .entry(&"forbidden", &self.control.forbidden.load(Ordering::SeqCst))
             .entry(&"index", &self.index)
-            .entry(&"len(other)", &self.shared.other.lock().unwrap().len())
+            .entry(
+                &"len(other)",
+                &self.shared.other.lock().unwrap_or_else(PoisonError::into_inner).len(),
+            )
             .finish()?;
 
         writeln!(f)?;
         writeln!(f, "other values: [")?;
-        for control in self.shared.other.lock().unwrap().iter().flatten() {
+        let other = self.shared.other.lock().unwrap_or_else(PoisonError::into_inner);
+        for control in other.iter().flatten() {
             f.debug_map()
                 .entry(&"busy", &control.busy.load(Ordering::SeqCst))
                 .entry(&"forbidden", &control.forbidden.load(Ordering::SeqCst))
@@ -351,6 +532,53 @@ mod tests {
         assert_eq!(*shared_number.write().unwrap(), num_threads * num_iterations * 5 + 5);
     }
 
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_random_shared_mutex_upgradable() {
+        let shared_number = BfSharedMutex::new(0);
+        let num_iterations = 500;
+        let num_threads = 20;
+
+        random_test_threads(
+            num_iterations,
+            num_threads,
+            || shared_number.clone(),
+            |rng, number| {
+                if rng.random_bool(0.5) {
+                    // No writer can modify the number between reading and upgrading.
+                    let guard = number.upgradable_read().unwrap();
+                    let value = *guard;
+
+                    let mut guard = guard.upgrade();
+                    assert_eq!(*guard, value);
+                    *guard += 1;
+                } else {
+                    black_box(*number.read().unwrap());
+                }
+            },
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "merc_metrics")]
+    fn test_shared_mutex_metrics() {
+        let shared_number = BfSharedMutex::new(0);
+
+        {
+            let other = shared_number.clone();
+            *other.write().unwrap() += 1;
+            drop(other.read().unwrap());
+        }
+
+        drop(shared_number.read().unwrap());
+        *shared_number.upgradable_read().unwrap().upgrade() += 1;
+
+        // The counters of dropped instances are retained.
+        let metrics = shared_number.metrics().unwrap();
+        assert_eq!(metrics.shared_acquisitions, 3);
+        assert_eq!(metrics.exclusive_acquisitions, 2);
+    }
+
     #[test]
     #[cfg_attr(miri, ignore)]
     fn test_random_shared() {
@@ -379,3 +607,72 @@ mod tests {
         );
     }
 }
+
+#[cfg(all(test, loom))]
+mod loom_tests {
+    use crate::BfSharedMutex;
+    use crate::sync::Arc;
+    use crate::sync::AtomicBool;
+    use crate::sync::Ordering;
+
+    #[test]
+    fn test_loom_shared_mutex_exclusive() {
+        loom::model(|| {
+            let shared_number = BfSharedMutex::new(0);
+
+            // Indicates that a writer is active, which must never be observed by another reader or writer.
+            let writing = Arc::new(AtomicBool::new(false));
+
+            let threads: Vec<_> = (0..2)
+                .map(|_| {
+                    let number = shared_number.clone();
+                    let writing = writing.clone();
+
+                    loom::thread::spawn(move || {
+                        {
+                            let guard = number.read().unwrap();
+                            assert!(!writing.load(Ordering::SeqCst));
+                            assert!(*guard <= 2);
+                        }
+
+                        let mut guard = number.write().unwrap();
+                        assert!(!writing.swap(true, Ordering::SeqCst));
+                        *guard += 1;
+                        writing.store(false, Ordering::SeqCst);
+                    })
+                })
+                .collect();
+
+            for thread in threads {
+                thread.join().unwrap();
+            }
+
+            assert_eq!(*shared_number.read().unwrap(), 2);
+        });
+    }
+
+    #[test]
+    fn test_loom_shared_mutex_upgradable() {
+        loom::model(|| {
+            let shared_number = BfSharedMutex::new(0);
+
+            let number = shared_number.clone();
+            let reader = loom::thread::spawn(move || {
+                let value = *number.read().unwrap();
+                assert!(value <= 1);
+            });
+
+            {
+                let guard = shared_number.upgradable_read().unwrap();
+                let value = *guard;
+
+                let mut guard = guard.upgrade();
+                assert_eq!(*guard, value);
+                *guard += 1;
+            }
+
+            reader.join().unwrap();
+            assert_eq!(*shared_number.read().unwrap(), 1);
+        });
+    }
+}
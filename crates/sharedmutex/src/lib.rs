@@ -1,9 +1,14 @@
 #![doc = include_str!("../README.md")]
 
+mod bf_metrics;
 mod bf_sharedmutex;
 mod bf_vec;
+mod lock_order;
 mod recursive_lock;
 
+pub mod sync;
+
+pub use bf_metrics::*;
 pub use bf_sharedmutex::*;
 pub use bf_vec::*;
 pub use recursive_lock::*;
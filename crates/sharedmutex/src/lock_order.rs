@@ -0,0 +1,174 @@
+//! Tracks the order in which locks are acquired to detect potential deadlocks.
+//!
+//! When the `merc_debug` feature is enabled every acquisition of a shared mutex
+//! adds an edge from each lock held by the current thread to the acquired lock
+//! in a global lock dependency graph. Whenever an acquisition introduces a
+//! cycle in this graph the locks have been acquired in inconsistent orders,
+//! which can deadlock, and we panic with the stack traces of both acquisitions.
+//! Shared and exclusive acquisitions are treated the same, so the analysis is
+//! conservative. Without the feature all functions are no-ops.
+
+#[cfg(feature = "merc_debug")]
+mod tracker {
+    use std::backtrace::Backtrace;
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+    use std::collections::HashSet;
+    use std::sync::Arc;
+    use std::sync::LazyLock;
+    use std::sync::Mutex;
+
+    /// The lock dependency graph, where every edge stores the stack trace of the acquisition that introduced it.
+    static GRAPH: LazyLock<Mutex<HashMap<usize, HashMap<usize, Arc<Backtrace>>>>> =
+        LazyLock::new(|| Mutex::new(HashMap::new()));
+
+    thread_local! {
+        /// The locks that are currently held by this thread, in acquisition order.
+        static HELD: RefCell<Vec<usize>> = const { RefCell::new(Vec::new()) };
+    }
+
+    pub fn acquire(lock: usize) {
+        let held = HELD.with_borrow(|held| held.clone());
+
+        if !held.is_empty() {
+            let mut graph = GRAPH.lock().expect("Lock poisoned!");
+
+            for &from in held.iter().filter(|&&from| from != lock) {
+                if graph.get(&from).is_some_and(|edges| edges.contains_key(&lock)) {
+                    // This order has been observed before, and was checked at that point.
+                    continue;
+                }
+
+                if let Some(trace) = find_path(&graph, lock, from) {
+                    panic!(
+                        "Potential deadlock: lock {lock:#x} acquired while holding lock {from:#x}, but the reverse order was observed before.\n\nCurrent acquisition:\n{}\n\nPrevious acquisition:\n{trace}",
+                        Backtrace::force_capture()
+                    );
+                }
+
+                graph
+                    .entry(from)
+                    .or_default()
+                    .insert(lock, Arc::new(Backtrace::force_capture()));
+            }
+        }
+
+        HELD.with_borrow_mut(|held| held.push(lock));
+    }
+
+    pub fn release(lock: usize) {
+        HELD.with_borrow_mut(|held| {
+            // Locks are not necessarily released in the reverse acquisition order.
+            if let Some(position) = held.iter().rposition(|&held| held == lock) {
+                held.remove(position);
+            }
+        });
+    }
+
+    pub fn remove(lock: usize) {
+        let mut graph = GRAPH.lock().expect("Lock poisoned!");
+        graph.remove(&lock);
+        for edges in graph.values_mut() {
+            edges.remove(&lock);
+        }
+    }
+
+    /// Returns the stack trace of the first edge on a path from `from` to `to`, if such a path exists.
+    fn find_path(
+        graph: &HashMap<usize, HashMap<usize, Arc<Backtrace>>>,
+        from: usize,
+        to: usize,
+    ) -> Option<Arc<Backtrace>> {
+        let mut visited = HashSet::new();
+        let mut stack: Vec<(usize, Option<Arc<Backtrace>>)> = vec![(from, None)];
+
+        while let Some((lock, trace)) = stack.pop() {
+            if lock == to {
+                return trace;
+            }
+
+            if visited.insert(lock) {
+                for (&next, next_trace) in graph.get(&lock).into_iter().flatten() {
+                    stack.push((next, Some(trace.clone().unwrap_or_else(|| next_trace.clone()))));
+                }
+            }
+        }
+
+        None
+    }
+}
+
+/// Records that the current thread holds a lock for as long as this object is alive.
+pub(crate) struct LockOrderGuard {
+    #[cfg(feature = "merc_debug")]
+    lock: usize,
+}
+
+impl LockOrderGuard {
+    /// Records that the current thread acquires the given lock, and panics when this violates an earlier observed lock order.
+    #[inline]
+    pub(crate) fn new(_lock: usize) -> Self {
+        #[cfg(feature = "merc_debug")]
+        tracker::acquire(_lock);
+
+        Self {
+            #[cfg(feature = "merc_debug")]
+            lock: _lock,
+        }
+    }
+
+    /// Creates a guard for a lock whose acquisition has already been recorded, for example when its guard was forgotten.
+    #[inline]
+    pub(crate) fn assume_acquired(_lock: usize) -> Self {
+        Self {
+            #[cfg(feature = "merc_debug")]
+            lock: _lock,
+        }
+    }
+}
+
+impl Drop for LockOrderGuard {
+    #[inline]
+    fn drop(&mut self) {
+        #[cfg(feature = "merc_debug")]
+        tracker::release(self.lock);
+    }
+}
+
+/// Removes the given lock from the dependency graph, since its identifier can be reused afterwards.
+#[inline]
+pub(crate) fn lock_removed(_lock: usize) {
+    #[cfg(feature = "merc_debug")]
+    tracker::remove(_lock);
+}
+
+#[cfg(all(test, feature = "merc_debug"))]
+mod tests {
+    use crate::BfSharedMutex;
+
+    #[test]
+    fn test_consistent_lock_order() {
+        let first = BfSharedMutex::new(0);
+        let second = BfSharedMutex::new(0);
+
+        for _ in 0..2 {
+            let _first = first.write().unwrap();
+            let _second = second.read().unwrap();
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Potential deadlock")]
+    fn test_lock_order_inversion() {
+        let first = BfSharedMutex::new(0);
+        let second = BfSharedMutex::new(0);
+
+        {
+            let _first = first.write().unwrap();
+            let _second = second.write().unwrap();
+        }
+
+        let _second = second.write().unwrap();
+        let _first = first.write().unwrap();
+    }
+}
@@ -5,18 +5,37 @@ use std::error::Error;
 use std::mem;
 use std::ops::Deref;
 use std::ops::DerefMut;
+use std::thread;
+use std::thread::ThreadId;
 
 use crate::BfSharedMutex;
+use crate::BfSharedMutexMetrics;
 use crate::BfSharedMutexReadGuard;
+use crate::BfSharedMutexUpgradableReadGuard;
 use crate::BfSharedMutexWriteGuard;
 
-/// An extension of the [BfSharedMutex] that allows recursive read locking without deadlocks.
+/// An extension of the [BfSharedMutex] that allows recursive read and write locking without deadlocks.
+///
+/// # Details
+///
+/// A read lock can be acquired inside a read or write section, and a write lock
+/// can be acquired inside a write section. Acquiring a write lock inside a read
+/// section is not possible, since that would require upgrading the read lock.
+///
+/// Similar to [std::sync::Mutex], the lock is poisoned when a thread panics
+/// while holding a write lock, after which acquiring the lock fails.
 pub struct RecursiveLock<T> {
     inner: BfSharedMutex<T>,
 
-    /// The number of times the current thread has read locked the mutex.
+    /// The number of times the current thread has locked the mutex.
     recursive_depth: Cell<usize>,
 
+    /// The number of times the current thread has write locked the mutex.
+    write_depth: Cell<usize>,
+
+    /// The thread that holds the write lock, used to check that the recursive write locks are acquired by the same thread.
+    owner: Cell<Option<ThreadId>>,
+
     /// The number of calls to the write() method.
     write_calls: Cell<usize>,
 
@@ -27,12 +46,7 @@ pub struct RecursiveLock<T> {
 impl<T> RecursiveLock<T> {
     /// Creates a new `RecursiveLock` with the given data.
     pub fn new(data: T) -> Self {
-        RecursiveLock {
-            inner: BfSharedMutex::new(data),
-            recursive_depth: Cell::new(0),
-            write_calls: Cell::new(0),
-            read_recursive_calls: Cell::new(0),
-        }
+        Self::from_mutex(BfSharedMutex::new(data))
     }
 
     /// Creates a new `RecursiveLock` from an existing `BfSharedMutex`.
@@ -40,6 +54,8 @@ impl<T> RecursiveLock<T> {
         RecursiveLock {
             inner: mutex,
             recursive_depth: Cell::new(0),
+            write_depth: Cell::new(0),
+            owner: Cell::new(None),
             write_calls: Cell::new(0),
             read_recursive_calls: Cell::new(0),
         }
@@ -50,20 +66,51 @@ impl<T> RecursiveLock<T> {
             pub fn data_ptr(&self) -> *const T;
             pub fn is_locked(&self) -> bool;
             pub fn is_locked_exclusive(&self) -> bool;
+            pub fn is_poisoned(&self) -> bool;
+            pub fn metrics(&self) -> Option<BfSharedMutexMetrics>;
         }
     }
 
-    /// Acquires a write lock on the mutex.
+    /// Acquires a write lock on the mutex, allowing for recursive write locking.
+    ///
+    /// Only the innermost write guard may be used to mutate the object.
     pub fn write(&self) -> Result<RecursiveLockWriteGuard<'_, T>, Box<dyn Error + '_>> {
+        self.write_calls.set(self.write_calls.get() + 1);
+
+        if self.write_depth.get() > 0 {
+            // We are already holding the write lock, so we just increment the depth.
+            debug_assert_eq!(
+                self.owner.get(),
+                Some(thread::current().id()),
+                "The write lock is held by another thread"
+            );
+            self.write_depth.set(self.write_depth.get() + 1);
+            self.recursive_depth.set(self.recursive_depth.get() + 1);
+            return Ok(RecursiveLockWriteGuard {
+                mutex: self,
+                guard: None,
+                depth: self.write_depth.get(),
+            });
+        }
+
         debug_assert!(
             self.recursive_depth.get() == 0,
             "Cannot call write() inside a read section"
         );
-        self.write_calls.set(self.write_calls.get() + 1);
-        self.recursive_depth.set(1);
-        Ok(RecursiveLockWriteGuard {
+        let guard = self.inner.write()?;
+        Ok(self.enter_write(guard))
+    }
+
+    /// Acquires an upgradeable read lock on the mutex, which can later be upgraded to a write lock.
+    pub fn upgradable_read(&self) -> Result<RecursiveLockUpgradableReadGuard<'_, T>, Box<dyn Error + '_>> {
+        debug_assert!(
+            self.recursive_depth.get() == 0,
+            "Cannot call upgradable_read() inside a read section"
+        );
+        self.check_poisoned()?;
+        Ok(RecursiveLockUpgradableReadGuard {
             mutex: self,
-            guard: self.inner.write()?,
+            guard: self.inner.upgradable_read()?,
         })
     }
 
@@ -73,6 +120,7 @@ impl<T> RecursiveLock<T> {
             self.recursive_depth.get() == 0,
             "Cannot call read() inside a read section"
         );
+        self.check_poisoned()?;
         self.inner.read()
     }
 
@@ -80,18 +128,24 @@ impl<T> RecursiveLock<T> {
     pub fn read_recursive<'a>(&'a self) -> Result<RecursiveLockReadGuard<'a, T>, Box<dyn Error + 'a>> {
         self.read_recursive_calls.set(self.read_recursive_calls.get() + 1);
         if self.recursive_depth.get() == 0 {
-            // If we are not already holding a read lock, we acquire one.
+            // If we are not already holding a lock, we acquire a read lock.
             // Acquire the read guard, but forget it to prevent it from being dropped.
+            self.check_poisoned()?;
             self.recursive_depth.set(1);
             mem::forget(self.inner.read());
             Ok(RecursiveLockReadGuard { mutex: self })
         } else {
-            // If we are already holding a read lock, we just increment the depth.
+            // If we are already holding a read or write lock, we just increment the depth.
             self.recursive_depth.set(self.recursive_depth.get() + 1);
             Ok(RecursiveLockReadGuard { mutex: self })
         }
     }
 
+    /// Returns true iff the current thread holds a read or write lock through this instance.
+    pub fn is_held(&self) -> bool {
+        self.recursive_depth.get() > 0
+    }
+
     /// Returns the number of times `write()` has been called.
     pub fn write_call_count(&self) -> usize {
         self.write_calls.get()
@@ -101,6 +155,27 @@ impl<T> RecursiveLock<T> {
     pub fn read_recursive_call_count(&self) -> usize {
         self.read_recursive_calls.get()
     }
+
+    /// Returns the outermost write guard for the given exclusive access.
+    fn enter_write<'a>(&'a self, guard: BfSharedMutexWriteGuard<'a, T>) -> RecursiveLockWriteGuard<'a, T> {
+        self.owner.set(Some(thread::current().id()));
+        self.write_depth.set(1);
+        self.recursive_depth.set(1);
+        RecursiveLockWriteGuard {
+            mutex: self,
+            guard: Some(guard),
+            depth: 1,
+        }
+    }
+
+    /// Returns an error when the lock is poisoned.
+    fn check_poisoned(&self) -> Result<(), Box<dyn Error + '_>> {
+        if self.inner.is_poisoned() {
+            return Err("The lock is poisoned, since a thread panicked while holding the write lock".into());
+        }
+
+        Ok(())
+    }
 }
 
 #[must_use = "Dropping the guard unlocks the recursive lock immediately"]
@@ -131,10 +206,39 @@ impl<T> Drop for RecursiveLockReadGuard<'_, T> {
     }
 }
 
+#[must_use = "Dropping the guard unlocks the recursive lock immediately"]
+pub struct RecursiveLockUpgradableReadGuard<'a, T> {
+    mutex: &'a RecursiveLock<T>,
+    guard: BfSharedMutexUpgradableReadGuard<'a, T>,
+}
+
+impl<'a, T> RecursiveLockUpgradableReadGuard<'a, T> {
+    /// Upgrades the guard to a write lock, without allowing another writer in between.
+    pub fn upgrade(self) -> RecursiveLockWriteGuard<'a, T> {
+        let mutex = self.mutex;
+        mutex.write_calls.set(mutex.write_calls.get() + 1);
+        mutex.enter_write(self.guard.upgrade())
+    }
+}
+
+/// Allow dereferences the underlying object.
+impl<T> Deref for RecursiveLockUpgradableReadGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        self.guard.deref()
+    }
+}
+
 #[must_use = "Dropping the guard unlocks the recursive lock immediately"]
 pub struct RecursiveLockWriteGuard<'a, T> {
     mutex: &'a RecursiveLock<T>,
-    guard: BfSharedMutexWriteGuard<'a, T>,
+
+    /// The exclusive access, which is only owned by the outermost guard.
+    guard: Option<BfSharedMutexWriteGuard<'a, T>>,
+
+    /// The write depth of this guard, where the outermost guard has depth one.
+    depth: usize,
 }
 
 /// Allow dereferences the underlying object.
@@ -142,22 +246,40 @@ impl<T> Deref for RecursiveLockWriteGuard<'_, T> {
     type Target = T;
 
     fn deref(&self) -> &Self::Target {
-        // There can only be shared guards, which only provide immutable access to the object.
-        self.guard.deref()
+        // The current thread has exclusive access, which is shared between the nested guards.
+        unsafe { &*self.mutex.inner.data_ptr() }
     }
 }
 
 /// Allow dereferences the underlying object.
 impl<T> DerefMut for RecursiveLockWriteGuard<'_, T> {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        // There can only be shared guards, which only provide immutable access to the object.
-        self.guard.deref_mut()
+        debug_assert_eq!(
+            self.depth,
+            self.mutex.write_depth.get(),
+            "Only the innermost write guard can provide mutable access"
+        );
+
+        // The current thread has exclusive access, and only the innermost guard is used for mutable access.
+        unsafe { &mut *self.mutex.inner.data_ptr() }
     }
 }
 
 impl<T> Drop for RecursiveLockWriteGuard<'_, T> {
     fn drop(&mut self) {
+        debug_assert_eq!(
+            self.depth,
+            self.mutex.write_depth.get(),
+            "Recursive write guards must be dropped in reverse order"
+        );
+
+        self.mutex.write_depth.set(self.mutex.write_depth.get() - 1);
         self.mutex.recursive_depth.set(self.mutex.recursive_depth.get() - 1);
+        if self.guard.is_some() {
+            self.mutex.owner.set(None);
+        }
+
+        // The exclusive access of the outermost guard is released when the guard field is dropped.
     }
 }
 
@@ -261,6 +383,23 @@ mod tests {
         assert_eq!(lock.read_recursive_call_count(), 4);
     }
 
+    #[test]
+    fn test_upgradable_read() {
+        let lock = RecursiveLock::new(42);
+
+        let guard = lock.upgradable_read().unwrap();
+        assert_eq!(*guard, 42);
+        assert_eq!(lock.write_call_count(), 0);
+
+        let mut guard = guard.upgrade();
+        *guard += 1;
+        assert_eq!(lock.write_call_count(), 1);
+        drop(guard);
+
+        assert_eq!(lock.recursive_depth.get(), 0);
+        assert_eq!(*lock.read_recursive().unwrap(), 43);
+    }
+
     #[test]
     fn test_both_counters() {
         let lock = RecursiveLock::new(42);
@@ -298,4 +437,42 @@ mod tests {
             assert_eq!(lock.read_recursive_call_count(), 3);
         }
     }
+
+    #[test]
+    fn test_nested_recursive_writes() {
+        let lock = RecursiveLock::new(42);
+
+        let mut guard1 = lock.write().unwrap();
+        *guard1 += 1;
+        {
+            let mut guard2 = lock.write().unwrap();
+            *guard2 += 1;
+
+            // A read lock can be acquired inside a write section.
+            assert_eq!(*lock.read_recursive().unwrap(), 44);
+            assert_eq!(lock.recursive_depth.get(), 2);
+        }
+        assert_eq!(*guard1, 44);
+        drop(guard1);
+
+        assert!(!lock.is_held());
+        assert_eq!(lock.write_call_count(), 2);
+        assert_eq!(*lock.read_recursive().unwrap(), 44);
+    }
+
+    #[test]
+    fn test_poisoned_write() {
+        let lock = RecursiveLock::new(42);
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            let _guard = lock.write().unwrap();
+            panic!("Panic while holding the write lock");
+        }));
+        assert!(result.is_err());
+
+        assert!(lock.is_poisoned());
+        assert!(!lock.is_held());
+        assert!(lock.write().is_err());
+        assert!(lock.read_recursive().is_err());
+    }
 }
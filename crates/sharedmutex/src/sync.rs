@@ -0,0 +1,42 @@
+//! Synchronisation primitives that are replaced by their [loom](https://docs.rs/loom)
+//! counterparts when compiled with `RUSTFLAGS="--cfg loom"`, such that the
+//! model checker can explore all interleavings of the code using them.
+
+#[cfg(not(loom))]
+pub use std::sync::Arc;
+#[cfg(not(loom))]
+pub use std::sync::Mutex;
+#[cfg(not(loom))]
+pub use std::sync::MutexGuard;
+#[cfg(not(loom))]
+pub use std::sync::atomic::AtomicBool;
+#[cfg(not(loom))]
+pub use std::sync::atomic::AtomicUsize;
+#[cfg(not(loom))]
+pub use std::sync::atomic::Ordering;
+
+#[cfg(loom)]
+pub use loom::sync::Arc;
+#[cfg(loom)]
+pub use loom::sync::Mutex;
+#[cfg(loom)]
+pub use loom::sync::MutexGuard;
+#[cfg(loom)]
+pub use loom::sync::atomic::AtomicBool;
+#[cfg(loom)]
+pub use loom::sync::atomic::AtomicUsize;
+#[cfg(loom)]
+pub use loom::sync::atomic::Ordering;
+
+/// Signals that the current thread is busy waiting.
+///
+/// Under loom this yields to the other threads instead, since the model
+/// checker would otherwise explore an unbounded number of spin iterations.
+#[inline]
+pub fn spin_loop() {
+    #[cfg(not(loom))]
+    std::hint::spin_loop();
+
+    #[cfg(loom)]
+    loom::thread::yield_now();
+}
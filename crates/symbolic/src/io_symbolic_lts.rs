@@ -2,13 +2,13 @@ use std::io::Read;
 
 use log::info;
 use merc_aterm::ATerm;
-use merc_aterm::ATermList;
 use merc_aterm::ATermRead;
 use merc_aterm::ATermStreamable;
 use merc_aterm::BinaryATermReader;
 use merc_aterm::Symbol;
 use merc_data::DataSpecification;
 use merc_data::DataVariable;
+use merc_data::DataVariableList;
 use merc_io::BitStreamRead;
 use merc_ldd::BinaryLddReader;
 use merc_ldd::Storage;
@@ -54,11 +54,14 @@ pub fn read_symbolic_lts<R: Read>(storage: &mut Storage, reader: R) -> Result<Sy
     let mut stream = BinaryLddReader::new(aterm_stream)?;
 
     if ATermRead::read_aterm(&mut stream)? != Some(symbolic_labelled_transition_system_mark()) {
-        return Err("Expected symbolic labelled transition system stream".into());
+        return Err(MercError::format("Expected symbolic labelled transition system stream"));
     }
 
     let data_spec = DataSpecification::read(&mut stream)?;
-    let process_parameters: ATermList<DataVariable> = stream.read_aterm()?.ok_or("Expected process parameters")?.into();
+    let process_parameters: DataVariableList = stream
+        .read_aterm()?
+        .ok_or_else(|| MercError::format("Expected process parameters"))?
+        .into();
     let process_parameters: Vec<DataVariable> = process_parameters.to_vec();
 
     let initial_state = stream.read_ldd(storage)?;
@@ -87,13 +90,23 @@ pub fn read_symbolic_lts<R: Read>(storage: &mut Storage, reader: R) -> Result<Sy
         let num_of_reads = stream.read_integer()?;
         let mut read_parameters: Vec<DataVariable> = Vec::with_capacity(num_of_reads as usize);
         for _ in 0..num_of_reads {
-            read_parameters.push(stream.read_aterm()?.ok_or("Unexpected end of stream")?.into());
+            read_parameters.push(
+                stream
+                    .read_aterm()?
+                    .ok_or_else(|| MercError::format("Unexpected end of stream"))?
+                    .into(),
+            );
         }
 
         let num_of_writes = stream.read_integer()?;
         let mut write_parameters: Vec<DataVariable> = Vec::with_capacity(num_of_writes as usize);
         for _ in 0..num_of_writes {
-            write_parameters.push(stream.read_aterm()?.ok_or("Unexpected end of stream")?.into());
+            write_parameters.push(
+                stream
+                    .read_aterm()?
+                    .ok_or_else(|| MercError::format("Unexpected end of stream"))?
+                    .into(),
+            );
         }
 
         let relation = stream.read_ldd(storage)?;
@@ -1,77 +1,248 @@
+use std::collections::HashMap;
+use std::ops::Range;
+
 use oxidd::BooleanFunction;
+use oxidd::BooleanFunctionQuant;
 use oxidd::ManagerRef;
 use oxidd::bdd::BDDFunction;
 use oxidd::bdd::BDDManagerRef;
 
 use merc_ldd::DataRef;
+use merc_ldd::Ldd;
 use merc_ldd::LddRef;
 use merc_ldd::Storage;
+use merc_ldd::Value;
 use merc_ldd::height;
 use merc_utilities::MercError;
 
-pub fn ldd_to_bdd_simple(
+/// The encoding of the values of an LDD level by BDD variables.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+pub enum VariableEncoding {
+    /// A value is encoded by its binary representation, with the most
+    /// significant bit first, so a level with values up to n uses log2(n + 1)
+    /// variables.
+    #[default]
+    Binary,
+
+    /// A value v is encoded by the v-th variable of the level being true and
+    /// all other variables being false, so a level with values up to n uses
+    /// n + 1 variables.
+    OneHot,
+}
+
+/// Describes how the vectors of an LDD are encoded by the variables of a BDD,
+/// where the variables of every level are placed consecutively starting at
+/// variable zero.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BddEncoding {
+    encoding: VariableEncoding,
+
+    /// The first variable of every level, followed by the total number of variables.
+    offsets: Vec<u32>,
+}
+
+impl BddEncoding {
+    /// Creates an encoding where every level can represent the values up to,
+    /// and including, the given highest value of that level.
+    pub fn new(encoding: VariableEncoding, highest: &[Value]) -> Self {
+        let mut offsets = Vec::with_capacity(highest.len() + 1);
+        let mut offset = 0;
+        offsets.push(offset);
+
+        for value in highest {
+            offset += match encoding {
+                VariableEncoding::Binary => u32::BITS - value.leading_zeros(),
+                VariableEncoding::OneHot => value + 1,
+            };
+            offsets.push(offset);
+        }
+
+        Self { encoding, offsets }
+    }
+
+    /// Creates an encoding that can represent all the vectors in the given LDD.
+    pub fn from_ldd(storage: &mut Storage, encoding: VariableEncoding, ldd: &LddRef<'_>) -> Self {
+        Self::new(encoding, &compute_highest(storage, ldd))
+    }
+
+    /// Returns the kind of encoding that is used for the values.
+    pub fn encoding(&self) -> VariableEncoding {
+        self.encoding
+    }
+
+    /// Returns the number of levels, i.e., the length of the encoded vectors.
+    pub fn num_of_levels(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    /// Returns the number of BDD variables required for this encoding.
+    pub fn num_of_variables(&self) -> u32 {
+        *self.offsets.last().expect("There is always at least one offset")
+    }
+
+    /// Returns the BDD variables that encode the values of the given level.
+    pub fn variables(&self, level: usize) -> Range<u32> {
+        self.offsets[level]..self.offsets[level + 1]
+    }
+
+    /// Returns the highest value that can be represented at the given level.
+    pub fn highest(&self, level: usize) -> Value {
+        let width = self.variables(level).len() as u32;
+        match self.encoding {
+            VariableEncoding::Binary => ((1u64 << width) - 1) as Value,
+            VariableEncoding::OneHot => width.saturating_sub(1),
+        }
+    }
+
+    /// Returns the cube over the variables of the given level that encodes the given value.
+    fn encode(&self, manager_ref: &BDDManagerRef, level: usize, value: Value) -> Result<BDDFunction, MercError> {
+        if value > self.highest(level) {
+            return Err(format!("The value {value} cannot be represented at level {level} of the encoding").into());
+        }
+
+        let variables = self.variables(level);
+        let width = variables.len() as u32;
+
+        manager_ref.with_manager_shared(|manager| {
+            let mut cube = BDDFunction::t(manager);
+            for (i, variable) in variables.enumerate() {
+                let positive = match self.encoding {
+                    VariableEncoding::Binary => value & (1 << (width - i as u32 - 1)) != 0,
+                    VariableEncoding::OneHot => i as Value == value,
+                };
+
+                let literal = BDDFunction::var(manager, variable)?;
+                let literal = if positive { literal } else { literal.not()? };
+                cube = cube.and(&literal)?;
+            }
+
+            Ok(cube)
+        })
+    }
+}
+
+/// Converts an LDD representing a set of vectors into a BDD representing the
+/// same set, where the values are encoded by the given encoding.
+///
+/// # Details
+///
+/// The variables of the encoding *must* already exist in the given BDD
+/// manager, see [crate::create_variables]. Fails when the LDD contains a value
+/// that cannot be represented by the encoding.
+pub fn ldd_to_bdd(
     storage: &mut Storage,
     manager_ref: &BDDManagerRef,
     ldd: &LddRef<'_>,
+    encoding: &BddEncoding,
 ) -> Result<BDDFunction, MercError> {
-    let highest = compute_highest(storage, ldd);
-    let bits = compute_bits(&highest);
-    let bits_dd = merc_ldd::singleton(storage, &bits);
+    debug_assert!(
+        ldd == storage.empty_set() || height(storage, ldd) == encoding.num_of_levels(),
+        "The encoding must have a level for every level of the LDD"
+    );
 
-    ldd_to_bdd(storage, manager_ref, ldd, &bits_dd, 0)
+    #[allow(clippy::mutable_key_type)]
+    let mut cache: HashMap<Ldd, BDDFunction> = HashMap::new();
+    ldd_to_bdd_rec(storage, manager_ref, ldd, encoding, 0, &mut cache)
 }
 
-/// Converts an LDD representing a set of vectors into a BDD representing the
-/// same set by bitblasting the vector elements.
+/// Converts a BDD over the variables of the given encoding into an LDD
+/// representing the same set of vectors, which is the inverse of [ldd_to_bdd].
 ///
 /// # Details
 ///
-/// The bits should be a singleton LDD containing the result of
-/// [`compute_bits`]. The conversion works recursively by processing the LDD
-/// node by node, and introducing bits number of BDD variables for each layer in
-/// the LDD. Note that `first_variable` indicates the level of the first
-/// variable, and the next bits are placed at consecutive BDD layers. These
-/// variables *must* already exist in the given BDD manager.
-pub fn ldd_to_bdd(
+/// For the one-hot encoding the assignments in which not exactly one variable
+/// of a level is true do not represent a value, and are therefore ignored.
+pub fn bdd_to_ldd(
+    storage: &mut Storage,
+    manager_ref: &BDDManagerRef,
+    bdd: &BDDFunction,
+    encoding: &BddEncoding,
+) -> Result<Ldd, MercError> {
+    // The cubes that encode every value of every level.
+    let mut cubes = Vec::with_capacity(encoding.num_of_levels());
+    for level in 0..encoding.num_of_levels() {
+        cubes.push(
+            (0..=encoding.highest(level))
+                .map(|value| encoding.encode(manager_ref, level, value))
+                .collect::<Result<Vec<_>, _>>()?,
+        );
+    }
+
+    let mut cache: HashMap<(usize, BDDFunction), Ldd> = HashMap::new();
+    bdd_to_ldd_rec(storage, bdd, &cubes, 0, &mut cache)
+}
+
+/// The recursive implementation of [ldd_to_bdd], where the given LDD is at the given level.
+#[allow(clippy::mutable_key_type)]
+fn ldd_to_bdd_rec(
     storage: &mut Storage,
     manager_ref: &BDDManagerRef,
     ldd: &LddRef<'_>,
-    bits: &LddRef<'_>,
-    first_variable: u32,
+    encoding: &BddEncoding,
+    level: usize,
+    cache: &mut HashMap<Ldd, BDDFunction>,
 ) -> Result<BDDFunction, MercError> {
-    // Base cases
-    if **storage.empty_set() == *ldd {
+    if ldd == storage.empty_set() {
         return Ok(manager_ref.with_manager_shared(|manager| BDDFunction::f(manager)));
     }
-    if **storage.empty_vector() == *ldd {
+    if ldd == storage.empty_vector() {
         return Ok(manager_ref.with_manager_shared(|manager| BDDFunction::t(manager)));
     }
 
-    // TODO: Implement caching
+    // Since all vectors have the same length, every node occurs at a single level.
+    if let Some(result) = cache.get(ldd) {
+        return Ok(result.clone());
+    }
+
     let DataRef(value, down, right) = storage.get_ref(ldd);
-    let DataRef(bits_value, bits_down, _bits_right) = storage.get_ref(bits); // Is singleton so right is ignored.
-
-    let right = ldd_to_bdd(storage, manager_ref, &right, bits, first_variable)?;
-    let mut down = ldd_to_bdd(storage, manager_ref, &down, &bits_down, first_variable + bits_value)?;
-
-    // Encode current value
-    for i in 0..bits_value {
-        // encode with high bit first
-        let bit = bits_value - i - 1;
-        if value & (1 << i) != 0 {
-            // bit is 1
-            down = manager_ref.with_manager_shared(|manager| {
-                BDDFunction::var(manager, first_variable + bit)?.ite(&BDDFunction::f(manager), &down)
-            })?;
-        } else {
-            // bit is 0
-            down = manager_ref.with_manager_shared(|manager| {
-                BDDFunction::var(manager, first_variable + bit)?.ite(&down, &BDDFunction::f(manager))
-            })?;
+    let down_result = ldd_to_bdd_rec(storage, manager_ref, &down, encoding, level + 1, cache)?;
+    let right_result = ldd_to_bdd_rec(storage, manager_ref, &right, encoding, level, cache)?;
+
+    let cube = encoding.encode(manager_ref, level, value)?;
+    let result = cube.and(&down_result)?.or(&right_result)?;
+
+    cache.insert(storage.protect(ldd), result.clone());
+    Ok(result)
+}
+
+/// The recursive implementation of [bdd_to_ldd], where the variables of the
+/// levels before the given level no longer occur in the given BDD.
+fn bdd_to_ldd_rec(
+    storage: &mut Storage,
+    bdd: &BDDFunction,
+    cubes: &[Vec<BDDFunction>],
+    level: usize,
+    cache: &mut HashMap<(usize, BDDFunction), Ldd>,
+) -> Result<Ldd, MercError> {
+    if !bdd.satisfiable() {
+        return Ok(storage.empty_set().clone());
+    }
+    if level == cubes.len() {
+        return Ok(storage.empty_vector().clone());
+    }
+
+    let key = (level, bdd.clone());
+    if let Some(result) = cache.get(&key) {
+        return Ok(result.clone());
+    }
+
+    // Determine the remaining vectors for every value of this level.
+    let mut children = Vec::new();
+    for (value, cube) in cubes[level].iter().enumerate() {
+        let down = bdd_to_ldd_rec(storage, &bdd.restrict(cube)?, cubes, level + 1, cache)?;
+        if down != *storage.empty_set() {
+            children.push((value as Value, down));
         }
     }
 
-    Ok(down.or(&right)?)
+    let mut result = storage.empty_set().clone();
+    for (value, down) in children.iter().rev() {
+        result = storage.insert(*value, down, &result);
+    }
+
+    cache.insert(key, result.clone());
+    Ok(result)
 }
 
 /// Computes the highest value for every layer in the LDD
@@ -94,17 +265,11 @@ fn compute_highest_rec(storage: &mut Storage, result: &mut [u32], set: &LddRef<'
     result[depth] = result[depth].max(value);
 }
 
-/// Computes the number of bits required to represent the highest value at each layer.
-fn compute_bits(highest: &[u32]) -> Vec<u32> {
-    highest.iter().map(|&h| u32::BITS - h.leading_zeros()).collect()
-}
-
 #[cfg(test)]
 mod tests {
     use merc_ldd::fmt_node;
     use merc_ldd::from_iter;
     use merc_ldd::random_vector_set;
-    use merc_ldd::singleton;
     use merc_utilities::random_test;
 
     use crate::create_variables;
@@ -134,19 +299,21 @@ mod tests {
                 }
             }
 
-            let bits = compute_bits(&highest);
-            println!("Bits: {:?}", bits);
+            let encoding = BddEncoding::new(VariableEncoding::Binary, &highest);
+            println!("Encoding: {:?}", encoding);
 
-            for (i, b) in bits.iter().enumerate() {
-                let expected_bits = if highest[i] == 0 {
-                    0
-                } else {
-                    u32::BITS - highest[i].leading_zeros()
-                };
-                assert_eq!(
-                    *b, expected_bits,
-                    "The number of bits for depth {} is {}, but expected {}",
-                    i, b, expected_bits
+            for (i, h) in highest.iter().enumerate() {
+                assert!(
+                    encoding.highest(i) >= *h,
+                    "The encoding of depth {} can represent up to {}, but expected {}",
+                    i,
+                    encoding.highest(i),
+                    h
+                );
+                assert!(
+                    *h == 0 || encoding.highest(i) < 2 * h,
+                    "The encoding of depth {} uses more bits than necessary",
+                    i
                 );
             }
         })
@@ -154,25 +321,41 @@ mod tests {
 
     #[test]
     #[cfg_attr(miri, ignore)] // Oxidd does not work with miri
-    fn test_random_ldd_to_bdd() {
-        random_test(100, |rng| {
-            let set = random_vector_set(rng, 4, 3, 5);
+    fn test_random_ldd_to_bdd_round_trip() {
+        for encoding in [VariableEncoding::Binary, VariableEncoding::OneHot] {
+            random_test(100, |rng| {
+                let set = random_vector_set(rng, 16, 4, 5);
 
-            let mut storage = Storage::new();
-            let ldd = from_iter(&mut storage, set.iter());
-            println!("LDD: {}", fmt_node(&storage, &ldd));
+                let mut storage = Storage::new();
+                let ldd = from_iter(&mut storage, set.iter());
+                println!("LDD: {}", fmt_node(&storage, &ldd));
 
-            let highest = compute_highest(&mut storage, &ldd);
-            let bits = compute_bits(&highest);
-            let bits_dd = singleton(&mut storage, &bits);
+                let encoding = BddEncoding::from_ldd(&mut storage, encoding, &ldd);
+                let manager_ref = oxidd::bdd::new_manager(2048, 1024, 1);
+                let _variables = create_variables(&manager_ref, encoding.num_of_variables()).unwrap();
+
+                let bdd = ldd_to_bdd(&mut storage, &manager_ref, &ldd, &encoding).unwrap();
+                let result = bdd_to_ldd(&mut storage, &manager_ref, &bdd, &encoding).unwrap();
+
+                assert_eq!(
+                    result, ldd,
+                    "Converting {:?} to a BDD and back does not yield the same set",
+                    encoding
+                );
+            });
+        }
+    }
 
-            let manager_ref = oxidd::bdd::new_manager(2048, 1024, 1);
+    #[test]
+    #[cfg_attr(miri, ignore)] // Oxidd does not work with miri
+    fn test_ldd_to_bdd_unrepresentable() {
+        let mut storage = Storage::new();
+        let ldd = merc_ldd::singleton(&mut storage, &[4, 1]);
 
-            let total_bits: u32 = bits.iter().sum();
-            println!("Total bits: {}", total_bits);
-            let _variables = create_variables(&manager_ref, total_bits).unwrap();
+        let encoding = BddEncoding::new(VariableEncoding::Binary, &[3, 1]);
+        let manager_ref = oxidd::bdd::new_manager(2048, 1024, 1);
+        let _variables = create_variables(&manager_ref, encoding.num_of_variables()).unwrap();
 
-            let _bdd = ldd_to_bdd(&mut storage, &manager_ref, &ldd, &bits_dd, 0).unwrap();
-        });
+        assert!(ldd_to_bdd(&mut storage, &manager_ref, &ldd, &encoding).is_err());
     }
 }
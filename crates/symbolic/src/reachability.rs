@@ -50,6 +50,9 @@ pub fn reachability(storage: &mut Storage, lts: &impl SymbolicLTS) -> Result<usi
 
         todo = minus(storage, &todo1, &states);
         states = union(storage, &states, &todo);
+        drop(todo1);
+        storage.maybe_garbage_collect();
+
         progress.print(iteration);
         iteration += 1;
     }
@@ -43,6 +43,11 @@ impl SymbolicLts {
     pub fn data_specification(&self) -> &DataSpecification {
         &self.data_specification
     }
+
+    /// Returns the summand groups of the LTS.
+    pub fn summand_groups(&self) -> &[SummandGroup] {
+        &self.summand_groups
+    }
 }
 
 impl SymbolicLTS for SymbolicLts {
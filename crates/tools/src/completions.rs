@@ -0,0 +1,112 @@
+//! Generation of shell completions and man pages for the command line tools.
+
+use std::fs;
+use std::fs::File;
+use std::io;
+use std::io::BufWriter;
+use std::path::Path;
+use std::path::PathBuf;
+
+use clap::Args;
+use clap::Command;
+use clap::CommandFactory;
+use clap::ValueEnum;
+use clap_complete::Shell;
+use clap_mangen::Man;
+use log::info;
+
+use merc_utilities::MercError;
+
+/// The arguments of the hidden `generate-completions` subcommand, which can be
+/// added to the subcommands of a tool as `GenerateCompletions(GenerateCompletionsArgs)`.
+#[derive(Args, Debug)]
+#[command(hide = true, about = "Generates shell completions and man pages for this tool")]
+pub struct GenerateCompletionsArgs {
+    #[arg(long, value_enum, help = "Print the completions for the given shell")]
+    shell: Option<Shell>,
+
+    #[arg(
+        long,
+        help = "Write the completions for all shells, or only the given shell, and the man pages to this directory"
+    )]
+    output: Option<PathBuf>,
+}
+
+/// Generates the shell completions and man pages of the tool with the command
+/// line interface `C` as specified by the given arguments.
+pub fn generate_completions<C: CommandFactory>(args: &GenerateCompletionsArgs) -> Result<(), MercError> {
+    generate(C::command(), args)
+}
+
+/// Generates the shell completions and man pages for the given command.
+fn generate(mut command: Command, args: &GenerateCompletionsArgs) -> Result<(), MercError> {
+    let name = command.get_name().to_string();
+
+    match (&args.output, args.shell) {
+        (None, Some(shell)) => {
+            clap_complete::generate(shell, &mut command, name, &mut io::stdout());
+        }
+        (Some(output), shell) => {
+            fs::create_dir_all(output)?;
+
+            let shells = match shell {
+                Some(shell) => vec![shell],
+                None => Shell::value_variants().to_vec(),
+            };
+
+            for shell in shells {
+                let path = clap_complete::generate_to(shell, &mut command, &name, output)?;
+                info!("Generated {shell} completions in {}", path.display());
+            }
+
+            write_man_pages(&command, &name, output)?;
+        }
+        (None, None) => {
+            return Err("Either --shell or --output must be given".into());
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes the man page of the given command, and of all its visible
+/// subcommands, to the given directory.
+fn write_man_pages(command: &Command, name: &str, output: &Path) -> Result<(), MercError> {
+    let path = output.join(format!("{name}.1"));
+    let mut writer = BufWriter::new(File::create(&path)?);
+    Man::new(command.clone().name(name.to_string())).render(&mut writer)?;
+    info!("Generated the man page {}", path.display());
+
+    for subcommand in command.get_subcommands().filter(|subcommand| !subcommand.is_hide_set()) {
+        write_man_pages(subcommand, &format!("{name}-{}", subcommand.get_name()), output)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Arg;
+
+    use super::*;
+
+    #[test]
+    fn test_generate_completions() {
+        let command = Command::new("merc-test")
+            .arg(Arg::new("timings").long("timings"))
+            .subcommand(Command::new("reduce").arg(Arg::new("filename")))
+            .subcommand(Command::new("generate-completions").hide(true));
+
+        let output = tempfile::tempdir().unwrap();
+        let args = GenerateCompletionsArgs {
+            shell: Some(Shell::Bash),
+            output: Some(output.path().to_path_buf()),
+        };
+        generate(command, &args).unwrap();
+
+        assert!(output.path().join("merc-test.bash").is_file());
+        assert!(output.path().join("merc-test.1").is_file());
+        assert!(output.path().join("merc-test-reduce.1").is_file());
+        assert!(!output.path().join("merc-test-generate-completions.1").exists());
+    }
+}
@@ -0,0 +1,309 @@
+//! Support for a `merc.toml` configuration file whose values act as defaults
+//! for the command line arguments of the tools.
+
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+
+use clap::Command;
+use clap::Parser;
+use clap::error::ErrorKind;
+use toml::Table;
+use toml::Value;
+
+use merc_utilities::MercError;
+
+/// The name of the configuration file.
+pub const CONFIG_FILE_NAME: &str = "merc.toml";
+
+/// The environment variable that, when set, specifies the path of the
+/// configuration file instead.
+pub const CONFIG_VARIABLE: &str = "MERC_CONFIG";
+
+/// Parses the command line arguments of a tool, where the values in the
+/// configuration file, see [Config::find], act as defaults for the arguments.
+///
+/// # Details
+///
+/// The values at the top level of the configuration file apply to all tools
+/// that have an argument with that name, and the values in the table of a tool
+/// (or one of its subcommands) only apply to that tool. For example:
+///
+/// ```toml
+/// timings = true
+///
+/// [merc-lts]
+/// output-format = "json"
+///
+/// [merc-lts.reduce]
+/// preprocess = "confluence"
+/// ```
+///
+/// The arguments are identified by their long name, and arguments given on the
+/// command line always take precedence over the configuration file.
+pub fn parse_with_config<P: Parser>() -> P {
+    let mut command = P::command();
+
+    match Config::find() {
+        Ok(Some(config)) => match config.apply(command.clone()) {
+            Ok(result) => command = result,
+            Err(err) => command.error(ErrorKind::InvalidValue, err).exit(),
+        },
+        Ok(None) => {}
+        Err(err) => command.error(ErrorKind::Io, err).exit(),
+    }
+
+    let matches = command.get_matches_mut();
+    P::from_arg_matches(&matches).unwrap_or_else(|err| err.format(&mut command).exit())
+}
+
+/// The contents of a configuration file.
+#[derive(Debug, Default)]
+pub struct Config {
+    /// The path of the configuration file, used in error messages.
+    path: PathBuf,
+    table: Table,
+}
+
+impl Config {
+    /// Finds and reads the configuration file, which is the file given by
+    /// [CONFIG_VARIABLE], or otherwise the first [CONFIG_FILE_NAME] that exists
+    /// in the current directory or the `merc` directory of the user
+    /// configuration directory.
+    pub fn find() -> Result<Option<Config>, MercError> {
+        if let Some(path) = std::env::var_os(CONFIG_VARIABLE) {
+            return Ok(Some(Config::read(Path::new(&path))?));
+        }
+
+        for directory in [Some(PathBuf::from(".")), user_config_directory()]
+            .into_iter()
+            .flatten()
+        {
+            let path = directory.join(CONFIG_FILE_NAME);
+            if path.is_file() {
+                return Ok(Some(Config::read(&path)?));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Reads the configuration file at the given path.
+    pub fn read(path: &Path) -> Result<Config, MercError> {
+        let text = fs::read_to_string(path).map_err(|err| format!("Cannot read {}: {err}", path.display()))?;
+        Config::parse(path, &text)
+    }
+
+    /// Parses the given contents of the configuration file at the given path.
+    pub fn parse(path: &Path, text: &str) -> Result<Config, MercError> {
+        let table = text
+            .parse::<Table>()
+            .map_err(|err| format!("Cannot parse {}: {err}", path.display()))?;
+
+        Ok(Config {
+            path: path.to_path_buf(),
+            table,
+        })
+    }
+
+    /// Returns the command where the values of the configuration file have
+    /// been set as the defaults of its arguments.
+    pub fn apply(&self, command: Command) -> Result<Command, MercError> {
+        let mut tables = vec![&self.table];
+        let name = command.get_name().to_string();
+
+        match self.table.get(&name) {
+            Some(Value::Table(table)) => {
+                tables.push(table);
+                self.apply_tables(command, &mut tables, &name)
+            }
+            Some(_) => Err(format!("The entry {name} in {} must be a table", self.path.display()).into()),
+            None => self.apply_tables(command, &mut tables, &name),
+        }
+    }
+
+    /// Sets the values of the given tables as the defaults of the arguments of
+    /// the command, where later tables take precedence. Only the last table is
+    /// required to consist of arguments of this command, since the earlier
+    /// tables also apply to other (sub)commands.
+    fn apply_tables<'a>(
+        &'a self,
+        mut command: Command,
+        tables: &mut Vec<&'a Table>,
+        path: &str,
+    ) -> Result<Command, MercError> {
+        let specific = tables.len() > 1;
+
+        // Subcommands are configured in the nested table with their name.
+        let subcommands: Vec<String> = command
+            .get_subcommands()
+            .map(|sub| sub.get_name().to_string())
+            .collect();
+
+        if specific {
+            let table = tables.last().expect("There is at least one table");
+            for (key, value) in table.iter() {
+                let is_argument = command
+                    .get_arguments()
+                    .any(|arg| !arg.is_positional() && arg.get_long() == Some(key.as_str()));
+                let is_subcommand = value.is_table() && subcommands.contains(key);
+
+                if !is_argument && !is_subcommand {
+                    return Err(format!("Unknown option {key} for {path} in {}", self.path.display()).into());
+                }
+            }
+        }
+
+        let arguments: Vec<(String, String)> = command
+            .get_arguments()
+            .filter(|arg| !arg.is_positional())
+            .filter_map(|arg| Some((arg.get_id().to_string(), arg.get_long()?.to_string())))
+            .collect();
+
+        for (id, long) in arguments {
+            let Some(value) = tables
+                .iter()
+                .rev()
+                .find_map(|table| table.get(&long).filter(|value| !value.is_table()))
+            else {
+                continue;
+            };
+
+            let values = to_strings(value)
+                .map_err(|err| format!("Invalid value for {long} of {path} in {}: {err}", self.path.display()))?;
+            command = command.mut_arg(id, |arg| arg.default_values(values));
+        }
+
+        for name in subcommands {
+            let subcommand = command
+                .find_subcommand(&name)
+                .expect("The subcommand was found before")
+                .clone();
+
+            let subcommand = match tables
+                .last()
+                .copied()
+                .and_then(|table| table.get(&name))
+                .filter(|_| specific)
+            {
+                Some(Value::Table(table)) => {
+                    tables.push(table);
+                    let result = self.apply_tables(subcommand, tables, &format!("{path}.{name}"));
+                    tables.pop();
+                    result?
+                }
+                _ => {
+                    // Only the general values apply to this subcommand.
+                    let mut general = vec![&self.table];
+                    self.apply_tables(subcommand, &mut general, &format!("{path}.{name}"))?
+                }
+            };
+
+            command = command.mut_subcommand(&name, |_| subcommand);
+        }
+
+        Ok(command)
+    }
+}
+
+/// Returns the textual representation of the given value as command line
+/// values, where an array results in one value per element.
+fn to_strings(value: &Value) -> Result<Vec<String>, MercError> {
+    match value {
+        Value::String(value) => Ok(vec![value.clone()]),
+        Value::Integer(value) => Ok(vec![value.to_string()]),
+        Value::Float(value) => Ok(vec![value.to_string()]),
+        Value::Boolean(value) => Ok(vec![value.to_string()]),
+        Value::Datetime(value) => Ok(vec![value.to_string()]),
+        Value::Array(values) => {
+            let mut result = Vec::new();
+            for value in values {
+                if value.is_array() {
+                    return Err("nested arrays are not supported".into());
+                }
+                result.extend(to_strings(value)?);
+            }
+            Ok(result)
+        }
+        Value::Table(_) => Err("expected a value instead of a table".into()),
+    }
+}
+
+/// Returns the `merc` directory in the configuration directory of the user,
+/// following the XDG base directory specification on Unix.
+fn user_config_directory() -> Option<PathBuf> {
+    let base = if cfg!(windows) {
+        std::env::var_os("APPDATA").map(PathBuf::from)
+    } else {
+        std::env::var_os("XDG_CONFIG_HOME")
+            .filter(|path| !path.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+    };
+
+    base.map(|base| base.join("merc"))
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Arg;
+    use clap::ArgAction;
+
+    use super::*;
+
+    fn command() -> Command {
+        Command::new("merc-test")
+            .arg(
+                Arg::new("timings")
+                    .long("timings")
+                    .global(true)
+                    .action(ArgAction::SetTrue),
+            )
+            .arg(Arg::new("threads").long("threads").default_value("1"))
+            .subcommand(Command::new("reduce").arg(Arg::new("preprocess").long("preprocess")))
+    }
+
+    #[test]
+    fn test_config_defaults() {
+        let config = Config::parse(
+            Path::new("merc.toml"),
+            "
+            timings = true
+            unrelated = 5
+
+            [merc-test]
+            threads = 4
+
+            [merc-test.reduce]
+            preprocess = 'tau-scc'
+            ",
+        )
+        .unwrap();
+
+        let mut command = config.apply(command()).unwrap();
+        let matches = command.try_get_matches_from_mut(["merc-test", "reduce"]).unwrap();
+        assert!(matches.get_flag("timings"));
+        assert_eq!(matches.get_one::<String>("threads").unwrap(), "4");
+
+        let (_, reduce) = matches.subcommand().unwrap();
+        assert_eq!(reduce.get_one::<String>("preprocess").unwrap(), "tau-scc");
+
+        // The command line takes precedence over the configuration file.
+        let matches = command
+            .try_get_matches_from_mut(["merc-test", "--threads", "8", "reduce", "--preprocess", "none"])
+            .unwrap();
+        assert_eq!(matches.get_one::<String>("threads").unwrap(), "8");
+
+        let (_, reduce) = matches.subcommand().unwrap();
+        assert_eq!(reduce.get_one::<String>("preprocess").unwrap(), "none");
+    }
+
+    #[test]
+    fn test_config_unknown_option() {
+        let config = Config::parse(Path::new("merc.toml"), "[merc-test]\nthread = 4\n").unwrap();
+        assert!(config.apply(command()).is_err());
+
+        let config = Config::parse(Path::new("merc.toml"), "[merc-test.reduce]\nthreads = 4\n").unwrap();
+        assert!(config.apply(command()).is_err());
+    }
+}
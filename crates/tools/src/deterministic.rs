@@ -0,0 +1,21 @@
+use clap::Args;
+
+/// The flag for the tools that use multi-threaded algorithms, which forces a
+/// canonical numbering of their results such that they do not depend on the
+/// scheduling of the threads.
+#[derive(Args, Clone, Copy, Debug)]
+pub struct DeterministicFlag {
+    #[arg(
+        long,
+        global = true,
+        default_value_t = false,
+        help = "Number the results of multi-threaded algorithms canonically, such that they are reproducible"
+    )]
+    deterministic: bool,
+}
+
+impl From<DeterministicFlag> for bool {
+    fn from(val: DeterministicFlag) -> Self {
+        val.deterministic
+    }
+}
@@ -0,0 +1,119 @@
+//! Golden file tests for the command line tools, which run a tool as a
+//! subprocess and compare its normalized output against a stored golden file.
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use serde_json::Value;
+
+use merc_utilities::MercError;
+
+/// The placeholder in the arguments that is replaced by the path of a temporary
+/// output file, for example `{output}.pg` for commands that write a parity game.
+pub const OUTPUT_PLACEHOLDER: &str = "{output}";
+
+/// The environment variable that, when set, rewrites the golden files instead
+/// of comparing them.
+pub const BLESS_VARIABLE: &str = "MERC_BLESS";
+
+/// Runs the given binary with the arguments in the given directory, and
+/// compares its normalized output to the golden file.
+///
+/// # Details
+///
+/// Every occurrence of [OUTPUT_PLACEHOLDER] in the arguments is replaced by the
+/// path of a temporary file, whose normalized contents are appended to the
+/// output of the tool. When the golden file does not exist yet, or
+/// [BLESS_VARIABLE] is set, the golden file is written instead.
+pub fn check_golden(binary: &Path, directory: &Path, args: &[&str], golden_path: &Path) -> Result<(), MercError> {
+    let name = golden_path
+        .file_stem()
+        .ok_or("The golden file must have a file name")?
+        .to_string_lossy();
+    let output_prefix = std::env::temp_dir().join(format!("merc-golden-{}-{name}", std::process::id()));
+    let output_prefix = output_prefix.to_string_lossy();
+
+    let arguments: Vec<String> = args
+        .iter()
+        .map(|arg| arg.replace(OUTPUT_PLACEHOLDER, &output_prefix))
+        .collect();
+
+    let output = Command::new(binary).args(&arguments).current_dir(directory).output()?;
+    if !output.status.success() {
+        return Err(format!(
+            "Running {} {} failed with {}:\n{}",
+            binary.display(),
+            arguments.join(" "),
+            output.status,
+            String::from_utf8_lossy(&output.stderr)
+        )
+        .into());
+    }
+
+    let mut result = normalize_output(&String::from_utf8(output.stdout)?);
+    for (arg, path) in args.iter().zip(&arguments) {
+        if arg.contains(OUTPUT_PLACEHOLDER) {
+            // The original argument is used as header, since the temporary path differs between runs.
+            result.push_str(&format!("--- {arg}\n"));
+            result.push_str(&normalize_output(&fs::read_to_string(path)?));
+            fs::remove_file(path)?;
+        }
+    }
+
+    if !golden_path.exists() || std::env::var_os(BLESS_VARIABLE).is_some() {
+        if let Some(parent) = golden_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(golden_path, &result)?;
+        return Ok(());
+    }
+
+    let expected = normalize_output(&fs::read_to_string(golden_path)?);
+    if result != expected {
+        return Err(format!(
+            "The output of {} {} does not match the golden file {}, set {BLESS_VARIABLE}=1 to update it.\nExpected:\n{expected}\nActual:\n{result}",
+            binary.display(),
+            args.join(" "),
+            golden_path.display()
+        )
+        .into());
+    }
+
+    Ok(())
+}
+
+/// Normalizes the output of a tool such that it can be compared between runs
+/// and platforms. Line endings are unified and trailing whitespace is removed.
+/// For JSON output the timings, which differ between runs, are replaced by null.
+pub fn normalize_output(output: &str) -> String {
+    if let Ok(mut value) = serde_json::from_str::<Value>(output) {
+        if let Some(Value::Object(timings)) = value.get_mut("timings") {
+            for timing in timings.values_mut() {
+                *timing = Value::Null;
+            }
+        }
+
+        return format!(
+            "{}\n",
+            serde_json::to_string_pretty(&value).expect("A JSON value can always be serialized")
+        );
+    }
+
+    output.lines().map(|line| format!("{}\n", line.trim_end())).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_output() {
+        assert_eq!(normalize_output("W0: 0, 1 \r\nW1: 2\r\n"), "W0: 0, 1\nW1: 2\n");
+
+        let json = r#"{"tool": "merc-lts", "results": {"states": 4}, "timings": {"read_aut": {"total": 0.25}}}"#;
+        let normalized: Value = serde_json::from_str(&normalize_output(json)).unwrap();
+        assert_eq!(normalized["timings"]["read_aut"], Value::Null);
+        assert_eq!(normalized["results"]["states"], 4);
+    }
+}
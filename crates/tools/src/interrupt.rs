@@ -0,0 +1,31 @@
+//! Handling of Ctrl-C for the command line tools.
+
+use std::process;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+
+use log::warn;
+
+use merc_utilities::CancellationToken;
+use merc_utilities::MercError;
+
+/// The exit code used when the process is terminated by a second interrupt.
+const INTERRUPTED_EXIT_CODE: i32 = 130;
+
+/// Installs a Ctrl-C handler that cancels the [`CancellationToken::global`]
+/// token, such that the running algorithm stops at its next safe point.
+/// Interrupting a second time terminates the process immediately.
+pub fn install_interrupt_handler() -> Result<(), MercError> {
+    static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+    ctrlc::set_handler(|| {
+        if INTERRUPTED.swap(true, Ordering::Relaxed) {
+            process::exit(INTERRUPTED_EXIT_CODE);
+        }
+
+        warn!("Interrupted, stopping at the next safe point. Press Ctrl-C again to terminate immediately.");
+        CancellationToken::global().cancel();
+    })?;
+
+    Ok(())
+}
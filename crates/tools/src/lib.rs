@@ -1,7 +1,21 @@
+pub mod completions;
+pub mod config;
 pub mod console;
+pub mod deterministic;
+pub mod golden;
+pub mod interrupt;
+pub mod progress;
+pub mod report;
 pub mod verbosity;
 pub mod version;
 
+pub use completions::*;
+pub use config::*;
 pub use console::*;
+pub use deterministic::*;
+pub use golden::*;
+pub use interrupt::*;
+pub use progress::*;
+pub use report::*;
 pub use verbosity::*;
 pub use version::*;
@@ -0,0 +1,98 @@
+use std::sync::Mutex;
+
+use clap::Args;
+use clap::ValueEnum;
+use indicatif::ProgressBar;
+use indicatif::ProgressStyle;
+
+use merc_io::LogProgressSink;
+use merc_io::ProgressSink;
+use merc_io::ProgressUpdate;
+use merc_io::SilentProgressSink;
+use merc_io::set_progress_sink;
+use merc_utilities::MercError;
+
+#[derive(Args, Debug)]
+pub struct ProgressFlag {
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        default_value_t = ProgressMode::Log,
+        help = "Set how the progress of long running procedures is shown"
+    )]
+    progress: ProgressMode,
+}
+
+impl ProgressFlag {
+    /// Installs the progress sink corresponding to the flag, must be called
+    /// before any progress has been reported.
+    pub fn install(&self) -> Result<(), MercError> {
+        match self.progress {
+            ProgressMode::Log => set_progress_sink(Box::new(LogProgressSink)),
+            ProgressMode::Bar => set_progress_sink(Box::new(BarProgressSink::default())),
+            ProgressMode::None => set_progress_sink(Box::new(SilentProgressSink)),
+        }
+    }
+}
+
+/// The ways in which progress can be shown.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum ProgressMode {
+    /// Print the progress as log messages.
+    Log,
+    /// Show a progress bar on the terminal.
+    Bar,
+    /// Do not show any progress.
+    None,
+}
+
+/// Shows the progress of the current procedure as a progress bar on the terminal.
+#[derive(Default)]
+pub struct BarProgressSink {
+    /// The task of the current procedure and its progress bar.
+    bar: Mutex<Option<(String, ProgressBar)>>,
+}
+
+impl ProgressSink for BarProgressSink {
+    fn update(&self, update: &ProgressUpdate<'_>) {
+        let mut current = self.bar.lock().expect("The lock should not be poisoned");
+
+        if current.as_ref().is_none_or(|(task, _)| task != update.task) {
+            // A new procedure has started, so the bar of the previous one is removed.
+            if let Some((_, bar)) = current.take() {
+                bar.finish_and_clear();
+            }
+
+            *current = Some((update.task.to_string(), new_bar(update)));
+        }
+
+        if let Some((_, bar)) = current.as_ref() {
+            bar.set_position(update.current as u64);
+        }
+    }
+
+    fn finish(&self, update: &ProgressUpdate<'_>) {
+        let mut current = self.bar.lock().expect("The lock should not be poisoned");
+        if current.as_ref().is_some_and(|(task, _)| task == update.task) {
+            if let Some((_, bar)) = current.take() {
+                bar.finish_and_clear();
+            }
+        }
+    }
+}
+
+/// Creates a progress bar for the given procedure, which is a spinner when the total amount of work is unknown.
+fn new_bar(update: &ProgressUpdate<'_>) -> ProgressBar {
+    let (bar, template) = match update.total {
+        Some(total) => (
+            ProgressBar::new(total as u64),
+            "{msg} [{wide_bar}] {human_pos}/{human_len} ({eta})",
+        ),
+        None => (ProgressBar::new_spinner(), "{spinner} {msg} {human_pos}"),
+    };
+
+    bar.set_style(ProgressStyle::with_template(template).expect("The progress bar template should be valid"));
+    bar.set_message(format!("{} {}", update.task, update.unit));
+    bar
+}
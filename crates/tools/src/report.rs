@@ -0,0 +1,127 @@
+//! Structured reporting of the results of the command line tools.
+
+use std::fmt::Display;
+use std::io::Write;
+use std::io::stdout;
+
+use clap::Args;
+use serde_json::Map;
+use serde_json::Value;
+use serde_json::json;
+
+use merc_utilities::MercError;
+use merc_utilities::Timing;
+
+/// The format in which a tool prints its results to stdout.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Human readable text.
+    #[default]
+    Text,
+    /// A single JSON object.
+    Json,
+}
+
+#[derive(Args, Clone, Copy, Debug)]
+pub struct OutputFormatFlag {
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        default_value_t = OutputFormat::Text,
+        help = "Set the format of the results printed to stdout"
+    )]
+    output_format: OutputFormat,
+}
+
+impl OutputFormatFlag {
+    /// Returns the selected output format.
+    pub fn format(&self) -> OutputFormat {
+        self.output_format
+    }
+}
+
+/// Collects the results of a single tool invocation, such that all tools
+/// print them consistently in the selected [`OutputFormat`].
+///
+/// # Details
+///
+/// In the text format the human readable lines passed to [`Report::text`] are
+/// printed immediately, and the structured results are ignored. In the JSON
+/// format only the structured results are printed, as a single object that
+/// also contains the tool name and the aggregated timings, when the report is
+/// finished.
+pub struct Report {
+    tool: &'static str,
+    format: OutputFormat,
+    results: Map<String, Value>,
+}
+
+impl Report {
+    /// Creates a new empty report for the given tool.
+    pub fn new(tool: &'static str, format: OutputFormat) -> Self {
+        Self {
+            tool,
+            format,
+            results: Map::new(),
+        }
+    }
+
+    /// Returns true iff the results are printed as JSON.
+    pub fn is_json(&self) -> bool {
+        self.format == OutputFormat::Json
+    }
+
+    /// Prints the given human readable line, only for the text format.
+    pub fn text(&self, line: impl Display) {
+        if !self.is_json() {
+            println!("{line}");
+        }
+    }
+
+    /// Records a structured result under the given key, overwriting a previous result with the same key.
+    pub fn set(&mut self, key: &str, value: impl Into<Value>) {
+        self.results.insert(key.to_string(), value.into());
+    }
+
+    /// Prints the report. For the text format the timings are only printed when `print_timings` is set.
+    pub fn finish(self, timing: &Timing, print_timings: bool) -> Result<(), MercError> {
+        match self.format {
+            OutputFormat::Text => {
+                if print_timings {
+                    timing.print();
+                }
+            }
+            OutputFormat::Json => {
+                let timings: Map<String, Value> = timing
+                    .aggregate_results()
+                    .into_iter()
+                    .map(|ag| {
+                        (
+                            ag.name,
+                            json!({
+                                "total": ag.total,
+                                "min": ag.min,
+                                "max": ag.max,
+                                "avg": ag.avg,
+                                "count": ag.count,
+                                "peak_bytes": ag.peak_bytes,
+                            }),
+                        )
+                    })
+                    .collect();
+
+                let mut object = Map::new();
+                object.insert("tool".to_string(), self.tool.into());
+                object.insert("results".to_string(), Value::Object(self.results));
+                object.insert("timings".to_string(), Value::Object(timings));
+
+                let mut out = stdout().lock();
+                serde_json::to_writer_pretty(&mut out, &Value::Object(object))?;
+                writeln!(out)?;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -24,6 +24,9 @@ pub struct AllocCounter {
     max_size_of_allocations: AtomicUsize,
 }
 
+/// A snapshot of the metrics of an [AllocCounter].
+#[derive(Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AllocMetrics {
     number_of_allocations: usize,
     size_of_allocations: usize,
@@ -35,6 +38,38 @@ pub struct AllocMetrics {
     max_size_of_allocations: usize,
 }
 
+impl AllocMetrics {
+    /// Returns the number of live allocations.
+    pub fn current_allocations(&self) -> usize {
+        self.number_of_allocations
+    }
+
+    /// Returns the number of bytes currently allocated.
+    pub fn current_bytes(&self) -> usize {
+        self.size_of_allocations
+    }
+
+    /// Returns the number of allocations performed since the start.
+    pub fn total_allocations(&self) -> usize {
+        self.total_number_of_allocations
+    }
+
+    /// Returns the number of bytes allocated since the start.
+    pub fn total_bytes(&self) -> usize {
+        self.total_size_of_allocations
+    }
+
+    /// Returns the peak number of live allocations.
+    pub fn peak_allocations(&self) -> usize {
+        self.max_number_of_allocations
+    }
+
+    /// Returns the peak number of bytes allocated at the same time.
+    pub fn peak_bytes(&self) -> usize {
+        self.max_size_of_allocations
+    }
+}
+
 impl fmt::Display for AllocMetrics {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         writeln!(
@@ -98,6 +133,25 @@ impl AllocCounter {
         self.size_of_allocations.store(0, Ordering::Relaxed);
     }
 
+    /// Starts measuring the peak allocated size from the current size onwards.
+    /// Returns the previous peak, which must be passed to [AllocCounter::finish_peak_measurement].
+    ///
+    /// # Details
+    ///
+    /// Measurements can be nested as long as they are finished in the reverse
+    /// order in which they were started, in which case the peak of the
+    /// enclosing measurement is restored when the inner one finishes.
+    pub fn start_peak_measurement(&self) -> usize {
+        let current = self.size_of_allocations.load(Ordering::Relaxed);
+        self.max_size_of_allocations.swap(current, Ordering::Relaxed)
+    }
+
+    /// Finishes the measurement started by [AllocCounter::start_peak_measurement]
+    /// and returns the peak allocated size in bytes since its start.
+    pub fn finish_peak_measurement(&self, previous_peak: usize) -> usize {
+        self.max_size_of_allocations.fetch_max(previous_peak, Ordering::Relaxed)
+    }
+
     fn alloc(&self, layout: Layout) -> *mut u8 {
         let ret = unsafe { System.alloc(layout) };
 
@@ -243,6 +297,26 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_nested_peak_measurement() {
+        let counter = AllocCounter::new();
+        let small = Layout::from_size_align(32, 8).unwrap();
+        let large = Layout::from_size_align(128, 8).unwrap();
+
+        let outer = counter.start_peak_measurement();
+        let ptr = counter.alloc(large);
+        counter.dealloc(ptr, large);
+
+        let inner = counter.start_peak_measurement();
+        let ptr = counter.alloc(small);
+        counter.dealloc(ptr, small);
+        assert_eq!(counter.finish_peak_measurement(inner), 32);
+
+        // The peak of the outer measurement is restored after the inner one.
+        assert_eq!(counter.finish_peak_measurement(outer), 128);
+        assert_eq!(counter.get_metrics().peak_bytes(), 128);
+    }
+
     #[test]
     fn test_reset_functionality() {
         let counter = AllocCounter::new();
@@ -1,6 +1,10 @@
-#[cfg(feature = "merc_metrics")]
 use log::info;
 
+use merc_io::BytesFormatter;
+
+#[cfg(feature = "merc_metrics")]
+use crate::AllocMetrics;
+
 #[cfg(feature = "merc_metrics")]
 #[global_allocator]
 static GLOBAL_ALLOCATOR: crate::AllocCounter = crate::AllocCounter::new();
@@ -16,11 +20,65 @@ static GLOBAL_ALLOCATOR: tikv_jemallocator::Jemalloc = tikv_jemallocator::Jemall
 #[global_allocator]
 static GLOBAL_ALLOCATOR: mimalloc::MiMalloc = mimalloc::MiMalloc;
 
-/// Prints information from the [AllocCounter].
-#[cfg(feature = "merc_metrics")]
+/// Prints information from the [AllocCounter](crate::AllocCounter) and the peak resident set size.
 pub fn print_allocator_metrics() {
+    #[cfg(feature = "merc_metrics")]
     info!("{}", GLOBAL_ALLOCATOR.get_metrics());
+
+    if let Some(bytes) = peak_resident_set_size() {
+        info!("Peak resident set size: {}", BytesFormatter(bytes));
+    }
+}
+
+/// Returns the current metrics of the global allocator, which are only
+/// available when the `merc_metrics` feature is enabled.
+#[cfg(feature = "merc_metrics")]
+pub fn allocator_metrics() -> Option<AllocMetrics> {
+    Some(GLOBAL_ALLOCATOR.get_metrics())
 }
 
+/// Returns the current metrics of the global allocator, which are only
+/// available when the `merc_metrics` feature is enabled.
 #[cfg(not(feature = "merc_metrics"))]
-pub fn print_allocator_metrics() {}
+pub fn allocator_metrics() -> Option<crate::AllocMetrics> {
+    None
+}
+
+/// Registers a [merc_utilities::MemoryProbe] such that every
+/// [merc_utilities::Timer] records the peak allocated memory during its
+/// lifetime. Does nothing when the `merc_metrics` feature is disabled.
+pub fn register_timing_memory_probe() {
+    #[cfg(feature = "merc_metrics")]
+    merc_utilities::set_memory_probe(merc_utilities::MemoryProbe {
+        start: || GLOBAL_ALLOCATOR.start_peak_measurement(),
+        finish: |token| GLOBAL_ALLOCATOR.finish_peak_measurement(token),
+    });
+}
+
+/// Returns the peak resident set size of this process in bytes, as reported
+/// by the operating system. Only supported on Linux.
+pub fn peak_resident_set_size() -> Option<usize> {
+    if cfg!(target_os = "linux") {
+        let status = std::fs::read_to_string("/proc/self/status").ok()?;
+        let line = status.lines().find(|line| line.starts_with("VmHWM:"))?;
+
+        // The line has the form `VmHWM:     1234 kB`.
+        let kilobytes: usize = line.split_whitespace().nth(1)?.parse().ok()?;
+        Some(kilobytes * 1024)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg_attr(miri, ignore)] // Miri does not support reading files with isolation
+    fn test_peak_resident_set_size() {
+        if cfg!(target_os = "linux") {
+            assert!(peak_resident_set_size().is_some_and(|bytes| bytes > 0));
+        }
+    }
+}
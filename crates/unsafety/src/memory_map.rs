@@ -0,0 +1,168 @@
+//! Memory-mapped reading of (large) binary input files.
+
+use std::fs::File;
+use std::io;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::Read;
+use std::path::Path;
+
+use log::debug;
+
+use merc_io::BitStreamReader;
+
+/// A [`Read`] implementation that reads the contents of a file through a
+/// memory map, which avoids the system call and copying overhead of the
+/// regular buffered readers for large files.
+#[cfg(any(unix, windows))]
+pub struct MmapReader {
+    map: memmap2::Mmap,
+    position: usize,
+}
+
+#[cfg(any(unix, windows))]
+impl MmapReader {
+    /// Maps the given file into memory.
+    ///
+    /// # Details
+    ///
+    /// The file must not be modified (or truncated) by another process while
+    /// it is mapped, since that is undefined behaviour. This is the same
+    /// assumption that all tools make about their input files.
+    pub fn new(file: &File) -> io::Result<Self> {
+        // SAFETY: See the documentation above, the input files are not modified while reading.
+        let map = unsafe { memmap2::MmapOptions::new().map(file)? };
+
+        #[cfg(unix)]
+        let _ = map.advise(memmap2::Advice::Sequential);
+
+        Ok(Self { map, position: 0 })
+    }
+
+    /// Returns the number of bytes that have not yet been read.
+    pub fn remaining(&self) -> usize {
+        self.map.len() - self.position
+    }
+}
+
+#[cfg(any(unix, windows))]
+impl Read for MmapReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let remaining = &self.map[self.position..];
+        let length = remaining.len().min(buf.len());
+
+        buf[..length].copy_from_slice(&remaining[..length]);
+        self.position += length;
+        Ok(length)
+    }
+}
+
+#[cfg(any(unix, windows))]
+impl BufRead for MmapReader {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        Ok(&self.map[self.position..])
+    }
+
+    fn consume(&mut self, amount: usize) {
+        self.position = (self.position + amount).min(self.map.len());
+    }
+}
+
+/// A bit stream reader that directly reads from a memory-mapped file.
+#[cfg(any(unix, windows))]
+pub type MmapBitStreamReader = BitStreamReader<MmapReader>;
+
+/// A reader for input files that uses a memory map whenever that is supported
+/// by the platform, and falls back to a regular buffered reader otherwise.
+pub enum FileReader {
+    #[cfg(any(unix, windows))]
+    Mapped(MmapReader),
+    Buffered(BufReader<File>),
+}
+
+impl FileReader {
+    /// Opens the file at the given path for reading.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = File::open(path.as_ref())?;
+
+        #[cfg(any(unix, windows))]
+        match MmapReader::new(&file) {
+            Ok(reader) => return Ok(FileReader::Mapped(reader)),
+            Err(error) => {
+                // For example pipes and other special files cannot be mapped.
+                debug!(
+                    "Failed to memory map {}: {error}, falling back to buffered reading",
+                    path.as_ref().display()
+                );
+            }
+        }
+
+        Ok(FileReader::Buffered(BufReader::new(file)))
+    }
+
+    /// Returns true iff the file is read through a memory map.
+    pub fn is_mapped(&self) -> bool {
+        match self {
+            #[cfg(any(unix, windows))]
+            FileReader::Mapped(_) => true,
+            FileReader::Buffered(_) => false,
+        }
+    }
+}
+
+impl Read for FileReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            #[cfg(any(unix, windows))]
+            FileReader::Mapped(reader) => reader.read(buf),
+            FileReader::Buffered(reader) => reader.read(buf),
+        }
+    }
+}
+
+impl BufRead for FileReader {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        match self {
+            #[cfg(any(unix, windows))]
+            FileReader::Mapped(reader) => reader.fill_buf(),
+            FileReader::Buffered(reader) => reader.fill_buf(),
+        }
+    }
+
+    fn consume(&mut self, amount: usize) {
+        match self {
+            #[cfg(any(unix, windows))]
+            FileReader::Mapped(reader) => reader.consume(amount),
+            FileReader::Buffered(reader) => reader.consume(amount),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use merc_io::BitStreamRead;
+    use merc_io::BitStreamWrite;
+    use merc_io::BitStreamWriter;
+
+    use super::*;
+
+    #[test]
+    #[cfg_attr(miri, ignore)] // Miri does not support memory maps
+    fn test_file_reader() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        {
+            let mut writer = BitStreamWriter::new(file.as_file_mut());
+            writer.write_string("hello").unwrap();
+            writer.write_integer(123456789).unwrap();
+            writer.flush().unwrap();
+        }
+        file.flush().unwrap();
+
+        let reader = FileReader::open(file.path()).unwrap();
+        let mut stream = BitStreamReader::new(reader);
+        assert_eq!(stream.read_string().unwrap(), "hello");
+        assert_eq!(stream.read_integer().unwrap(), 123456789);
+    }
+}
@@ -158,6 +158,9 @@ where
 {
     index: DashSet<Entry<T>, S>,
 
+    /// A copy of the hasher used by the index, since `DashSet` does not expose it.
+    hasher: S,
+
     allocator: A,
 }
 
@@ -176,16 +179,20 @@ where
 {
     /// Creates an empty StablePointerSet with the default hasher and global allocator.
     pub fn new() -> Self {
+        let hasher = RandomState::new();
         Self {
-            index: DashSet::default(),
+            index: DashSet::with_hasher(hasher.clone()),
+            hasher,
             allocator: Global,
         }
     }
 
     /// Creates an empty StablePointerSet with the specified capacity, default hasher, and global allocator.
     pub fn with_capacity(capacity: usize) -> Self {
+        let hasher = RandomState::new();
         Self {
-            index: DashSet::with_capacity_and_hasher(capacity, RandomState::new()),
+            index: DashSet::with_capacity_and_hasher(capacity, hasher.clone()),
+            hasher,
             allocator: Global,
         }
     }
@@ -199,7 +206,8 @@ where
     /// Creates an empty StablePointerSet with the specified hasher and global allocator.
     pub fn with_hasher(hasher: S) -> Self {
         Self {
-            index: DashSet::with_hasher(hasher),
+            index: DashSet::with_hasher(hasher.clone()),
+            hasher,
             allocator: Global,
         }
     }
@@ -207,7 +215,8 @@ where
     /// Creates an empty StablePointerSet with the specified capacity, hasher, and global allocator.
     pub fn with_capacity_and_hasher(capacity: usize, hasher: S) -> Self {
         Self {
-            index: DashSet::with_capacity_and_hasher(capacity, hasher),
+            index: DashSet::with_capacity_and_hasher(capacity, hasher.clone()),
+            hasher,
             allocator: Global,
         }
     }
@@ -224,8 +233,10 @@ where
     where
         S: Default,
     {
+        let hasher = S::default();
         Self {
-            index: DashSet::with_hasher(S::default()),
+            index: DashSet::with_hasher(hasher.clone()),
+            hasher,
             allocator,
         }
     }
@@ -235,8 +246,10 @@ where
     where
         S: Default,
     {
+        let hasher = S::default();
         Self {
-            index: DashSet::with_capacity_and_hasher(capacity, S::default()),
+            index: DashSet::with_capacity_and_hasher(capacity, hasher.clone()),
+            hasher,
             allocator,
         }
     }
@@ -244,7 +257,8 @@ where
     /// Creates an empty StablePointerSet with the specified hasher and allocator.
     pub fn with_hasher_in(hasher: S, allocator: A) -> Self {
         Self {
-            index: DashSet::with_hasher(hasher),
+            index: DashSet::with_hasher(hasher.clone()),
+            hasher,
             allocator,
         }
     }
@@ -252,7 +266,8 @@ where
     /// Creates an empty StablePointerSet with the specified capacity, hasher, and allocator.
     pub fn with_capacity_and_hasher_in(capacity: usize, hasher: S, allocator: A) -> Self {
         Self {
-            index: DashSet::with_capacity_and_hasher(capacity, hasher),
+            index: DashSet::with_capacity_and_hasher(capacity, hasher.clone()),
+            hasher,
             allocator,
         }
     }
@@ -272,6 +287,11 @@ where
         self.index.capacity()
     }
 
+    /// Returns the hasher that is used to hash the elements of the set.
+    pub fn hasher(&self) -> &S {
+        &self.hasher
+    }
+
     /// Inserts an element into the set using an equivalent value.
     ///
     /// This version takes a reference to an equivalent value and creates the value to insert
@@ -0,0 +1,88 @@
+use std::error::Error;
+use std::fmt;
+use std::sync::Arc;
+use std::sync::LazyLock;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+
+/// A token that can be shared between threads to request that a long-running
+/// computation stops at the next safe point.
+///
+/// # Details
+///
+/// Algorithms poll the token with [`CancellationToken::check`], typically once
+/// per iteration of their main loop, and return the [`Cancelled`] error when a
+/// cancellation has been requested. Cloning the token shares the underlying
+/// flag. The [`CancellationToken::global`] token is cancelled by the Ctrl-C
+/// handler of the tools.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+/// The process wide token, see [`CancellationToken::global`].
+static GLOBAL_TOKEN: LazyLock<CancellationToken> = LazyLock::new(CancellationToken::new);
+
+impl CancellationToken {
+    /// Creates a new token that has not been cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the process wide cancellation token.
+    pub fn global() -> &'static CancellationToken {
+        &GLOBAL_TOKEN
+    }
+
+    /// Requests the cancellation of all computations that poll this token.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns true iff cancellation has been requested.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Returns the [`Cancelled`] error iff cancellation has been requested.
+    pub fn check(&self) -> Result<(), Cancelled> {
+        if self.is_cancelled() { Err(Cancelled) } else { Ok(()) }
+    }
+}
+
+/// Polls the [`CancellationToken::global`] token, see [`CancellationToken::check`].
+pub fn check_cancelled() -> Result<(), Cancelled> {
+    CancellationToken::global().check()
+}
+
+/// The error returned by computations that have been cancelled.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cancelled;
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "The computation was cancelled")
+    }
+}
+
+impl Error for Cancelled {}
+
+#[cfg(test)]
+mod tests {
+    use crate::MercError;
+
+    use super::*;
+
+    #[test]
+    fn test_cancellation_token() {
+        let token = CancellationToken::new();
+        let shared = token.clone();
+        assert!(token.check().is_ok());
+
+        shared.cancel();
+        assert!(token.is_cancelled());
+
+        let error: MercError = token.check().unwrap_err().into();
+        assert!(error.downcast_ref::<Cancelled>().is_some());
+    }
+}
@@ -2,16 +2,153 @@ use core::error::Error;
 use core::fmt::Debug;
 use core::fmt::Display;
 
-/// The Merc error type. This has a blanket [`From`] impl for any type that implements Rust's [`Error`],
-/// meaning it can be used as a "catch all" error. Captures a backtrace that can be printed from this object.
+use crate::Cancelled;
+
+/// A position in a textual input, used to report parse errors.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Span {
+    /// The line number, starting at one.
+    pub line: usize,
+    /// The column number, starting at one, or zero when unknown.
+    pub column: usize,
+}
+
+impl Span {
+    /// Creates a span for the given line where the column is unknown.
+    pub fn line(line: usize) -> Self {
+        Self { line, column: 0 }
+    }
+}
+
+impl Display for Span {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if self.column == 0 {
+            write!(f, "line {}", self.line)
+        } else {
+            write!(f, "line {}, column {}", self.line, self.column)
+        }
+    }
+}
+
+/// Classifies a [`MercError`], such that callers can react to the kind of
+/// failure without inspecting the message.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MercErrorKind {
+    /// Reading or writing a file or stream failed.
+    Io,
+    /// The textual input could not be parsed, optionally at the given position.
+    Parse { span: Option<Span> },
+    /// The (binary) input does not adhere to the expected format.
+    Format,
+    /// The requested operation or input is not supported.
+    Unsupported,
+    /// The computation has been cancelled, see [`crate::CancellationToken`].
+    Cancelled,
+    /// Any other error, for example a violated precondition.
+    Internal,
+}
+
+impl Display for MercErrorKind {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            MercErrorKind::Io => write!(f, "I/O error"),
+            MercErrorKind::Parse { span: Some(span) } => write!(f, "parse error at {span}"),
+            MercErrorKind::Parse { span: None } => write!(f, "parse error"),
+            MercErrorKind::Format => write!(f, "format error"),
+            MercErrorKind::Unsupported => write!(f, "unsupported"),
+            MercErrorKind::Cancelled => write!(f, "cancelled"),
+            MercErrorKind::Internal => write!(f, "internal error"),
+        }
+    }
+}
+
+/// The Merc error type, which consists of a [`MercErrorKind`], the underlying
+/// error and optionally the error that caused it. Captures a backtrace that
+/// can be printed from this object.
+///
+/// There is a blanket [`From`] impl for any type that implements Rust's
+/// [`Error`], which classifies [`std::io::Error`], [`Cancelled`] and the
+/// number parse errors by their kind. Strings are converted into
+/// [`MercErrorKind::Internal`] errors; this conversion is kept for a
+/// transition period, new code should use the constructors instead.
 pub struct MercError {
     inner: Box<InnerMercError>,
 }
 
+type BoxedError = Box<dyn Error + Send + Sync + 'static>;
+
 impl MercError {
-    /// Attempts to downcast the internal error to the given type.
+    /// Creates an error of the given kind.
+    #[cold]
+    pub fn new(kind: MercErrorKind, error: impl Into<BoxedError>) -> Self {
+        MercError {
+            inner: Box::new(InnerMercError {
+                kind,
+                error: error.into(),
+                source: None,
+                backtrace: std::backtrace::Backtrace::capture(),
+            }),
+        }
+    }
+
+    /// Creates a [`MercErrorKind::Parse`] error at the given position.
+    pub fn parse(error: impl Into<BoxedError>, span: Option<Span>) -> Self {
+        Self::new(MercErrorKind::Parse { span }, error)
+    }
+
+    /// Creates a [`MercErrorKind::Format`] error.
+    pub fn format(error: impl Into<BoxedError>) -> Self {
+        Self::new(MercErrorKind::Format, error)
+    }
+
+    /// Creates a [`MercErrorKind::Unsupported`] error.
+    pub fn unsupported(error: impl Into<BoxedError>) -> Self {
+        Self::new(MercErrorKind::Unsupported, error)
+    }
+
+    /// Creates a [`MercErrorKind::Internal`] error.
+    pub fn internal(error: impl Into<BoxedError>) -> Self {
+        Self::new(MercErrorKind::Internal, error)
+    }
+
+    /// Wraps this error into a new error with the given message and the same
+    /// kind, where this error becomes the source.
+    #[cold]
+    pub fn context(self, message: impl Into<BoxedError>) -> Self {
+        let mut error = Self::new(self.inner.kind, message);
+        error.inner.source = Some(self);
+        error
+    }
+
+    /// Sets the position of a [`MercErrorKind::Parse`] error, for example to
+    /// add the line number to a number parse error. Errors of other kinds are
+    /// returned unchanged.
+    pub fn with_span(mut self, span: Span) -> Self {
+        if let MercErrorKind::Parse { span: position } = &mut self.inner.kind {
+            *position = Some(span);
+        }
+        self
+    }
+
+    /// Returns the kind of this error.
+    pub fn kind(&self) -> MercErrorKind {
+        self.inner.kind
+    }
+
+    /// Returns the error that caused this error, if any.
+    pub fn source(&self) -> Option<&MercError> {
+        self.inner.source.as_ref()
+    }
+
+    /// Returns an iterator over this error and the errors that caused it.
+    pub fn chain(&self) -> impl Iterator<Item = &MercError> {
+        std::iter::successors(Some(self), |error| error.source())
+    }
+
+    /// Attempts to downcast the underlying error, or one of the errors that
+    /// caused it, to the given type.
     pub fn downcast_ref<E: Error + 'static>(&self) -> Option<&E> {
-        self.inner.error.downcast_ref::<E>()
+        self.chain().find_map(|error| error.inner.error.downcast_ref::<E>())
     }
 }
 
@@ -20,8 +157,12 @@ impl MercError {
 /// introduce an extra indirection, but error handling is a "cold path". We
 /// don't need to optimize it to that degree.
 struct InnerMercError {
+    /// The classification of the error
+    kind: MercErrorKind,
     /// The underlying error
-    error: Box<dyn Error + Send + Sync + 'static>,
+    error: BoxedError,
+    /// The error that caused this error
+    source: Option<MercError>,
     /// A backtrace captured at creation
     backtrace: std::backtrace::Backtrace,
 }
@@ -29,29 +170,46 @@ struct InnerMercError {
 // NOTE: writing the impl this way gives us From<&str>
 impl<E> From<E> for MercError
 where
-    Box<dyn Error + Send + Sync + 'static>: From<E>,
+    BoxedError: From<E>,
 {
     #[cold]
     fn from(error: E) -> Self {
-        MercError {
-            inner: Box::new(InnerMercError {
-                error: error.into(),
-                backtrace: std::backtrace::Backtrace::capture(),
-            }),
-        }
+        let error: BoxedError = error.into();
+        let kind = if error.is::<std::io::Error>() {
+            MercErrorKind::Io
+        } else if error.is::<Cancelled>() {
+            MercErrorKind::Cancelled
+        } else if error.is::<std::num::ParseIntError>() || error.is::<std::num::ParseFloatError>() {
+            MercErrorKind::Parse { span: None }
+        } else {
+            MercErrorKind::Internal
+        };
+
+        MercError::new(kind, error)
     }
 }
 
 impl Display for MercError {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         writeln!(f, "{}", self.inner.error)?;
+        if let MercErrorKind::Parse { span: Some(span) } = self.inner.kind {
+            writeln!(f, "  at {span}")?;
+        }
+
+        for source in self.chain().skip(1) {
+            writeln!(f, "caused by: {}", source.inner.error)?;
+        }
         Ok(())
     }
 }
 
 impl Debug for MercError {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        writeln!(f, "{:?}", self.inner.error)?;
+        writeln!(f, "{}: {:?}", self.inner.kind, self.inner.error)?;
+        for source in self.chain().skip(1) {
+            writeln!(f, "caused by {}: {:?}", source.inner.kind, source.inner.error)?;
+        }
+
         {
             let backtrace = &self.inner.backtrace;
             if let std::backtrace::BacktraceStatus::Captured = backtrace.status() {
@@ -62,3 +220,43 @@ impl Debug for MercError {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_kinds() {
+        let error: MercError = "something went wrong".into();
+        assert_eq!(error.kind(), MercErrorKind::Internal);
+
+        let error: MercError = std::io::Error::other("disk full").into();
+        assert_eq!(error.kind(), MercErrorKind::Io);
+
+        let error: MercError = Cancelled.into();
+        assert_eq!(error.kind(), MercErrorKind::Cancelled);
+
+        let error: MercError = "x".parse::<usize>().unwrap_err().into();
+        assert!(matches!(error.kind(), MercErrorKind::Parse { span: None }));
+    }
+
+    #[test]
+    fn test_error_context() {
+        let error = MercError::parse("unexpected token", Some(Span::line(3))).context("Failed to read model.aut");
+
+        assert_eq!(
+            error.kind(),
+            MercErrorKind::Parse {
+                span: Some(Span::line(3))
+            }
+        );
+        assert_eq!(error.chain().count(), 2);
+        assert_eq!(
+            error.to_string(),
+            "Failed to read model.aut\n  at line 3\ncaused by: unexpected token\n"
+        );
+
+        let error = MercError::from(Cancelled).context("Stopped the exploration");
+        assert!(error.downcast_ref::<Cancelled>().is_some());
+    }
+}
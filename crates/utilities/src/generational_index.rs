@@ -150,8 +150,9 @@ where
                 return false;
             }
 
-            debug_assert_eq!(
-                self.generation, other.generation,
+            // Equal indices of different generations indicate a use-after-free.
+            debug_assert!(
+                self.index != other.index || self.generation == other.generation,
                 "Comparing indices of different generations"
             );
         }
@@ -168,8 +169,8 @@ where
 {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
         #[cfg(debug_assertions)]
-        debug_assert_eq!(
-            self.generation, other.generation,
+        debug_assert!(
+            self.index != other.index || self.generation == other.generation,
             "Comparing indices of different generations"
         );
 
@@ -183,8 +184,8 @@ where
 {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
         #[cfg(debug_assertions)]
-        debug_assert_eq!(
-            self.generation, other.generation,
+        debug_assert!(
+            self.index != other.index || self.generation == other.generation,
             "Comparing indices of different generations"
         );
         self.index.cmp(&other.index)
@@ -226,6 +227,211 @@ impl fmt::Display for GenerationalIndex<usize> {
     }
 }
 
+/// A handle to an entry of a [HierarchicalAllocator]. Unlike
+/// [GenerationalIndex] the generation is always stored, such that handles to
+/// removed entries can be detected in release builds as well.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct HierarchicalHandle {
+    index: usize,
+    generation: usize,
+}
+
+impl fmt::Display for HierarchicalHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.index)
+    }
+}
+
+/// An allocator that stores entries with an optional parent, and returns
+/// generational handles to them.
+///
+/// # Details
+///
+/// Removing an entry also removes all its descendants, which allows freeing a
+/// group of related objects at once. The positions of removed entries are
+/// reused by later insertions, but handles to removed entries remain invalid
+/// since the generation of the position is incremented.
+#[derive(Clone, Debug)]
+pub struct HierarchicalAllocator<T> {
+    slots: Vec<Slot<T>>,
+    free: Vec<usize>,
+    len: usize,
+}
+
+#[derive(Clone, Debug)]
+struct Slot<T> {
+    value: Option<T>,
+    generation: usize,
+    parent: Option<usize>,
+    children: Vec<usize>,
+}
+
+impl<T> Default for HierarchicalAllocator<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> HierarchicalAllocator<T> {
+    /// Creates a new empty allocator.
+    pub fn new() -> Self {
+        Self {
+            slots: Vec::new(),
+            free: Vec::new(),
+            len: 0,
+        }
+    }
+
+    /// Returns the number of live entries.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true iff there are no live entries.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Inserts a new entry without a parent.
+    pub fn insert(&mut self, value: T) -> HierarchicalHandle {
+        self.allocate(value, None)
+    }
+
+    /// Inserts a new entry as a child of the given parent, returns None if the parent is no longer valid.
+    pub fn insert_child(&mut self, parent: HierarchicalHandle, value: T) -> Option<HierarchicalHandle> {
+        if !self.contains(parent) {
+            return None;
+        }
+
+        let handle = self.allocate(value, Some(parent.index));
+        self.slots[parent.index].children.push(handle.index);
+        Some(handle)
+    }
+
+    /// Returns true iff the given handle refers to a live entry.
+    pub fn contains(&self, handle: HierarchicalHandle) -> bool {
+        self.slots
+            .get(handle.index)
+            .is_some_and(|slot| slot.generation == handle.generation && slot.value.is_some())
+    }
+
+    /// Returns a reference to the entry of the given handle, if it is still valid.
+    pub fn get(&self, handle: HierarchicalHandle) -> Option<&T> {
+        if self.contains(handle) {
+            self.slots[handle.index].value.as_ref()
+        } else {
+            None
+        }
+    }
+
+    /// Returns a mutable reference to the entry of the given handle, if it is still valid.
+    pub fn get_mut(&mut self, handle: HierarchicalHandle) -> Option<&mut T> {
+        if self.contains(handle) {
+            self.slots[handle.index].value.as_mut()
+        } else {
+            None
+        }
+    }
+
+    /// Returns the parent of the given entry, if it has one.
+    pub fn parent(&self, handle: HierarchicalHandle) -> Option<HierarchicalHandle> {
+        if !self.contains(handle) {
+            return None;
+        }
+
+        self.slots[handle.index].parent.map(|index| self.handle(index))
+    }
+
+    /// Returns the children of the given entry, which is empty for invalid handles.
+    pub fn children(&self, handle: HierarchicalHandle) -> impl Iterator<Item = HierarchicalHandle> + '_ {
+        let children = if self.contains(handle) {
+            &self.slots[handle.index].children[..]
+        } else {
+            &[]
+        };
+
+        children.iter().map(|index| self.handle(*index))
+    }
+
+    /// Removes the given entry and all its descendants, and returns the value
+    /// of the entry itself. Returns None if the handle is no longer valid.
+    pub fn remove(&mut self, handle: HierarchicalHandle) -> Option<T> {
+        if !self.contains(handle) {
+            return None;
+        }
+
+        // Detach the entry from its parent.
+        if let Some(parent) = self.slots[handle.index].parent {
+            self.slots[parent].children.retain(|child| *child != handle.index);
+        }
+
+        let value = self.free_slot(handle.index);
+
+        // Remove all descendants.
+        let mut stack = std::mem::take(&mut self.slots[handle.index].children);
+        while let Some(index) = stack.pop() {
+            stack.append(&mut self.slots[index].children);
+            self.free_slot(index);
+        }
+
+        value
+    }
+
+    /// Returns an iterator over all live entries, in the order of their positions.
+    pub fn iter(&self) -> impl Iterator<Item = (HierarchicalHandle, &T)> + '_ {
+        self.slots.iter().enumerate().filter_map(|(index, slot)| {
+            slot.value.as_ref().map(|value| {
+                (
+                    HierarchicalHandle {
+                        index,
+                        generation: slot.generation,
+                    },
+                    value,
+                )
+            })
+        })
+    }
+
+    /// Returns the handle for the live entry at the given position.
+    fn handle(&self, index: usize) -> HierarchicalHandle {
+        HierarchicalHandle {
+            index,
+            generation: self.slots[index].generation,
+        }
+    }
+
+    /// Stores the value in a free position, or a new one.
+    fn allocate(&mut self, value: T, parent: Option<usize>) -> HierarchicalHandle {
+        self.len += 1;
+        let slot = Slot {
+            value: Some(value),
+            generation: 0,
+            parent,
+            children: Vec::new(),
+        };
+
+        if let Some(index) = self.free.pop() {
+            let generation = self.slots[index].generation;
+            self.slots[index] = Slot { generation, ..slot };
+            self.handle(index)
+        } else {
+            self.slots.push(slot);
+            self.handle(self.slots.len() - 1)
+        }
+    }
+
+    /// Clears the given position and makes it available for reuse.
+    fn free_slot(&mut self, index: usize) -> Option<T> {
+        let slot = &mut self.slots[index];
+        slot.generation = slot.generation.wrapping_add(1);
+        slot.parent = None;
+
+        self.len -= 1;
+        self.free.push(index);
+        slot.value.take()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     #[cfg(debug_assertions)]
@@ -248,4 +454,35 @@ mod tests {
         // This panics since idx1 and idx2 are from different generations
         assert_eq!(idx1, idx2);
     }
+
+    #[test]
+    fn test_hierarchical_allocator() {
+        let mut allocator = super::HierarchicalAllocator::new();
+        let root = allocator.insert("root");
+        let child = allocator.insert_child(root, "child").unwrap();
+        let grandchild = allocator.insert_child(child, "grandchild").unwrap();
+        let other = allocator.insert("other");
+
+        assert_eq!(allocator.len(), 4);
+        assert_eq!(allocator.parent(grandchild), Some(child));
+        assert!(allocator.children(root).eq([child]));
+
+        // Removing the child also removes the grandchild, but not the root.
+        assert_eq!(allocator.remove(child), Some("child"));
+        assert_eq!(allocator.len(), 2);
+        assert_eq!(allocator.get(grandchild), None);
+        assert_eq!(allocator.children(root).count(), 0);
+        assert!(allocator.insert_child(child, "orphan").is_none());
+
+        // Reused positions do not make old handles valid again.
+        let reused = allocator.insert("reused");
+        assert_eq!(allocator.get(child), None);
+        assert_eq!(allocator.get(grandchild), None);
+        assert_eq!(allocator.get(reused), Some(&"reused"));
+
+        let live: Vec<_> = allocator.iter().map(|(_, value)| *value).collect();
+        assert_eq!(live.len(), 3);
+        assert!(live.contains(&"root") && live.contains(&"other") && live.contains(&"reused"));
+        assert_eq!(allocator.get(other), Some(&"other"));
+    }
 }
@@ -4,10 +4,12 @@
 #[macro_use]
 mod cast_macro;
 
+mod cancellation;
 mod debug_trace;
 mod error;
 mod generational_index;
 mod helper;
+mod logging;
 mod no_hasher;
 mod permutation;
 mod pest_display_pair;
@@ -16,9 +18,11 @@ mod tagged_index;
 mod test_logger;
 mod timing;
 
+pub use cancellation::*;
 pub use error::*;
 pub use generational_index::*;
 pub use helper::*;
+pub use logging::*;
 pub use no_hasher::*;
 pub use permutation::*;
 pub use pest_display_pair::*;
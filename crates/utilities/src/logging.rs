@@ -0,0 +1,55 @@
+use log::LevelFilter;
+use tracing_subscriber::EnvFilter;
+
+use crate::MercError;
+
+/// Initialises the logger of a tool with the given default level.
+///
+/// # Details
+///
+/// Messages are printed to stderr by a [tracing](https://docs.rs/tracing)
+/// subscriber, and messages of the `log` macros used throughout the toolset
+/// are forwarded to it. Every [crate::Timer] enters a span while it is running,
+/// such that messages are printed with the phase, for example
+/// `reduce/preprocess`, in which they were emitted.
+///
+/// The `RUST_LOG` environment variable is parsed in the same format as before,
+/// so `RUST_LOG=merc_lts=debug,merc_aterm=trace` sets the levels per module and
+/// takes precedence over the given level for those modules.
+pub fn init_logger(level: LevelFilter) {
+    if try_init_logger(level).is_err() {
+        log::warn!("A logger has already been initialised");
+    }
+}
+
+/// Same as [init_logger], but returns an error when a logger has already been initialised.
+pub fn try_init_logger(level: LevelFilter) -> Result<(), MercError> {
+    if cfg!(feature = "merc_miri") {
+        return Ok(());
+    }
+
+    // The directives of RUST_LOG follow the given level, such that they take precedence.
+    let directives = match std::env::var(EnvFilter::DEFAULT_ENV) {
+        Ok(directives) if !directives.is_empty() => format!("{},{directives}", level.as_str()),
+        _ => level.as_str().to_string(),
+    };
+    let filter = EnvFilter::builder().parse_lossy(directives);
+
+    tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr)
+        .try_init()
+        .map_err(|err| err.to_string().into())
+}
+
+/// Constructs a logger for tests. This logger will not print anything to the
+/// console, but will instead write to the output captured by the test harness.
+pub fn test_logger() {
+    if cfg!(not(feature = "merc_miri")) {
+        // Ignore double initialisations in tests since tests are ran in parallel.
+        let _ = tracing_subscriber::fmt()
+            .with_env_filter(EnvFilter::from_default_env())
+            .with_test_writer()
+            .try_init();
+    }
+}
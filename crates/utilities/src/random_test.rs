@@ -1,3 +1,11 @@
+use std::fs;
+use std::fs::OpenOptions;
+use std::io;
+use std::io::Write;
+use std::panic;
+use std::panic::AssertUnwindSafe;
+use std::path::Path;
+
 use rand::RngCore;
 use rand::SeedableRng;
 use rand::rngs::StdRng;
@@ -45,6 +53,82 @@ where
     }
 }
 
+/// Runs the random test for every seed in the given corpus, followed by
+/// [random_test]. When the test fails for the random seed, that seed is
+/// appended to the corpus, so committing the corpus makes the failure
+/// reproducible in every later run, both locally and in CI.
+///
+/// # Details
+///
+/// The corpus is a text file with one seed per line, where empty lines and
+/// lines starting with `#` are ignored. A missing corpus is considered empty.
+pub fn random_test_with_corpus<F>(corpus: impl AsRef<Path>, iterations: usize, mut test_function: F)
+where
+    F: FnMut(&mut StdRng),
+{
+    test_logger();
+
+    let corpus = corpus.as_ref();
+    for seed in read_seed_corpus(corpus) {
+        println!("seed: {seed} (from {})", corpus.display());
+        let mut rng = StdRng::seed_from_u64(seed);
+        for _ in 0..iterations {
+            test_function(&mut rng);
+        }
+    }
+
+    let seed = match std::env::var("MERC_SEED") {
+        Ok(seed_str) => seed_str.parse::<u64>().expect("MERC_SEED must be a valid u64"),
+        Err(_) => rand::random(),
+    };
+    println!("random seed: {seed} (use MERC_SEED=<seed> to set fixed seed)");
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        for _ in 0..iterations {
+            test_function(&mut rng);
+        }
+    }));
+
+    if let Err(payload) = result {
+        match append_seed(corpus, seed) {
+            Ok(()) => println!("Added the failing seed {seed} to {}", corpus.display()),
+            Err(error) => println!("Could not add the failing seed {seed} to {}: {error}", corpus.display()),
+        }
+
+        panic::resume_unwind(payload);
+    }
+}
+
+/// Returns the seeds of the given corpus, see [random_test_with_corpus].
+fn read_seed_corpus(corpus: &Path) -> Vec<u64> {
+    let contents = match fs::read_to_string(corpus) {
+        Ok(contents) => contents,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Vec::new(),
+        Err(error) => panic!("Cannot read seed corpus {}: {error}", corpus.display()),
+    };
+
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            line.parse::<u64>()
+                .unwrap_or_else(|_| panic!("Invalid seed \"{line}\" in corpus {}", corpus.display()))
+        })
+        .collect()
+}
+
+/// Appends the seed to the given corpus, creating it when necessary.
+fn append_seed(corpus: &Path, seed: u64) -> io::Result<()> {
+    if let Some(parent) = corpus.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut file = OpenOptions::new().create(true).append(true).open(corpus)?;
+    writeln!(file, "{seed}")
+}
+
 pub fn random_test_threads<C, F, G>(iterations: usize, num_threads: usize, init_function: G, test_function: F)
 where
     C: Send + 'static,
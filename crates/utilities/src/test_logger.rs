@@ -1,10 +1,4 @@
-/// Constructs a logger for tests. This logger will not print anything to the console, but will instead write to a buffer.
-pub fn test_logger() {
-    if cfg!(not(feature = "merc_miri")) {
-        // Ignore double initialisations in tests since tests are ran in parallel.
-        let _ = env_logger::builder().is_test(true).try_init();
-    }
-}
+use crate::test_logger;
 
 pub fn test_threads<C, F, G>(num_threads: usize, init_function: G, test_function: F)
 where
@@ -3,98 +3,224 @@ use std::collections::HashMap;
 use std::io;
 use std::io::Write;
 use std::rc::Rc;
-use std::time::Instant;
+use std::sync::OnceLock;
 
 use log::info;
 use log::warn;
+use serde::Serialize;
+use tracing::span::EnteredSpan;
+
+/// The clock used for all time measurements. On WebAssembly the standard
+/// library does not provide a clock, so the one from the browser is used instead.
+#[cfg(not(target_family = "wasm"))]
+pub use std::time::Instant;
+#[cfg(target_family = "wasm")]
+pub use web_time::Instant;
+
+/// The separator between the names of nested scopes in the path of a timer.
+const SCOPE_SEPARATOR: char = '/';
+
+/// Hooks to measure the peak memory usage while a timer is running, see [`set_memory_probe`].
+#[derive(Clone, Copy)]
+pub struct MemoryProbe {
+    /// Starts a measurement and returns a token that must be passed to `finish`.
+    pub start: fn() -> usize,
+    /// Finishes the measurement for the given token, and returns the peak number of allocated bytes since its start.
+    pub finish: fn(usize) -> usize,
+}
+
+/// The probe used by all timers, if any.
+static MEMORY_PROBE: OnceLock<MemoryProbe> = OnceLock::new();
+
+/// Sets the probe that is used by all timers to measure the peak memory usage
+/// during their lifetime. Can only be set once, subsequent calls are ignored.
+///
+/// # Details
+///
+/// The measurements of nested timers are assumed to finish in the reverse
+/// order in which they are started, as is the case for (nested) scopes.
+pub fn set_memory_probe(probe: MemoryProbe) {
+    let _ = MEMORY_PROBE.set(probe);
+}
+
+/// A single finished measurement.
+struct Measurement {
+    name: String,
+    time: f32,
+    peak_bytes: Option<usize>,
+}
 
 /// A timing object to measure the time of different parts of the program. This
 /// is useful for debugging and profiling.
+///
+/// # Details
+///
+/// Timers can be grouped into (nested) scopes using [`Timing::scope`], which
+/// returns a timing object that shares the results with its parent, but
+/// prefixes the names of its timers with the scope name. The full name of a
+/// timer is the path of its scopes separated by `/`, for example
+/// `reduce/preprocess`. Timers with the same path are aggregated when the
+/// results are printed or exported.
 #[derive(Default)]
 pub struct Timing {
-    results: Rc<RefCell<Vec<(String, f32)>>>,
+    results: Rc<RefCell<Vec<Measurement>>>,
+
+    /// The path of this scope, empty for the outermost scope.
+    scope: String,
 }
 
 /// A timer object that measures the time between its creation and the call to
 /// `finish()`. Finish should be called explicitly before the timer is dropped,
 /// otherwise we get zero values since the timer object is unused and can be
 /// immediately dropped.
+///
+/// While the timer is running it enters a `phase` span with the path of the
+/// timer, such that log messages show the phase in which they were emitted.
 pub struct Timer {
     name: String,
     start: Instant,
-    results: Rc<RefCell<Vec<(String, f32)>>>,
+    results: Rc<RefCell<Vec<Measurement>>>,
     registered: bool,
+
+    /// The token of the memory measurement, if a probe has been set.
+    memory_token: Option<usize>,
+
+    /// The span of the phase, which is exited when the timer finishes.
+    span: Option<EnteredSpan>,
 }
 
-/// Aggregated timing summary for a named timer.
-struct Aggregate {
-    name: String,
+/// The summary of a timer in the output of [`Timing::write_json`].
+#[derive(Serialize)]
+struct JsonSummary<'a> {
+    name: &'a str,
+    count: usize,
+    total: f32,
     min: f32,
     max: f32,
-    total: f32,
     avg: f32,
-    count: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    peak_bytes: Option<usize>,
+}
+
+/// Aggregated timing summary for a named timer, all times are in seconds.
+pub struct Aggregate {
+    pub name: String,
+    pub min: f32,
+    pub max: f32,
+    pub total: f32,
+    pub avg: f32,
+    pub count: usize,
+
+    /// The peak number of allocated bytes over all measurements, if a [`MemoryProbe`] has been set.
+    pub peak_bytes: Option<usize>,
+}
+
+impl Aggregate {
+    /// Returns the number of scopes that this timer is nested in.
+    pub fn depth(&self) -> usize {
+        self.name.matches(SCOPE_SEPARATOR).count()
+    }
+
+    /// Returns the name of the timer without the enclosing scopes.
+    pub fn short_name(&self) -> &str {
+        self.name.rsplit(SCOPE_SEPARATOR).next().unwrap_or(&self.name)
+    }
 }
 
 impl Timing {
     /// Creates a new timing object to track timers.
     pub fn new() -> Self {
-        Self {
-            results: Rc::new(RefCell::new(Vec::new())),
+        Self::default()
+    }
+
+    /// Returns a timing object for the nested scope with the given name, the
+    /// results of its timers are shared with this timing object.
+    pub fn scope(&self, name: &str) -> Timing {
+        Timing {
+            results: self.results.clone(),
+            scope: self.path(name),
         }
     }
 
     /// Starts a new timer with the given name.
     pub fn start(&self, name: &str) -> Timer {
+        let name = self.path(name);
+        let span = tracing::info_span!("phase", path = %name).entered();
+
         Timer {
-            name: name.to_string(),
+            name,
             start: Instant::now(),
             results: self.results.clone(),
             registered: false,
+            memory_token: MEMORY_PROBE.get().map(|probe| (probe.start)()),
+            span: Some(span),
         }
     }
 
-    /// Aggregate results by name and compute (min, max, avg, count, total) for each.
-    fn aggregate_results(&self) -> Vec<Aggregate> {
+    /// Aggregate results by name and compute (min, max, avg, count, total) for
+    /// each. The results are sorted such that nested scopes directly follow
+    /// their parent.
+    pub fn aggregate_results(&self) -> Vec<Aggregate> {
         let mut map: HashMap<String, Aggregate> = HashMap::new();
-        for (name, time) in self.results.borrow().iter() {
-            map.entry(name.clone())
+        for measurement in self.results.borrow().iter() {
+            let time = measurement.time;
+            map.entry(measurement.name.clone())
                 .and_modify(|ag| {
                     ag.count += 1;
-                    ag.total += *time;
-                    ag.min = ag.min.min(*time);
-                    ag.max = ag.max.max(*time);
+                    ag.total += time;
+                    ag.min = ag.min.min(time);
+                    ag.max = ag.max.max(time);
+                    ag.peak_bytes = ag.peak_bytes.max(measurement.peak_bytes);
                 })
                 .or_insert(Aggregate {
-                    name: name.clone(),
-                    min: *time,
-                    max: *time,
-                    total: *time,
+                    name: measurement.name.clone(),
+                    min: time,
+                    max: time,
+                    total: time,
                     avg: 0.0,
                     count: 1,
+                    peak_bytes: measurement.peak_bytes,
                 });
         }
 
-        // Compute the averages and sort by name.
-        let mut out: Vec<Aggregate> = map.into_values().map(|mut ag| {
-                ag.avg = if ag.count > 0 { ag.total / (ag.count as f32) } else { 0.0 };
+        // Compute the averages and sort by path.
+        let mut out: Vec<Aggregate> = map
+            .into_values()
+            .map(|mut ag| {
+                ag.avg = if ag.count > 0 {
+                    ag.total / (ag.count as f32)
+                } else {
+                    0.0
+                };
                 ag
             })
             .collect();
 
-        out.sort_by(|a, b| a.name.cmp(&b.name));
+        out.sort_by(|a, b| a.name.split(SCOPE_SEPARATOR).cmp(b.name.split(SCOPE_SEPARATOR)));
         out
     }
 
-    /// Prints all the finished timers aggregated by name (total first; omit metrics when n == 1).
+    /// Prints all the finished timers aggregated by name (total first; omit
+    /// metrics when n == 1). Nested scopes are indented.
     pub fn print(&self) {
         for ag in self.aggregate_results() {
+            let indent = "  ".repeat(ag.depth());
+            let peak = match ag.peak_bytes {
+                Some(bytes) => format!(", peak memory: {:.2} MB", bytes as f64 / 1_000_000.0),
+                None => String::new(),
+            };
+
             if ag.count == 1 {
-                eprintln!("Time {}: {:.3}s", ag.name, ag.total);
+                eprintln!("{indent}Time {}: {:.3}s{peak}", ag.short_name(), ag.total);
             } else {
                 eprintln!(
-                    "Time {}: {:.3}s, min: {:.3}s, max: {:.3}s, avg: {:.3}s, n: {}",
-                    ag.name, ag.total, ag.min, ag.max, ag.avg, ag.count
+                    "{indent}Time {}: {:.3}s, min: {:.3}s, max: {:.3}s, avg: {:.3}s, n: {}{peak}",
+                    ag.short_name(),
+                    ag.total,
+                    ag.min,
+                    ag.max,
+                    ag.avg,
+                    ag.count
                 );
             }
         }
@@ -117,6 +243,61 @@ impl Timing {
         }
         Ok(())
     }
+
+    /// Writes the aggregated timers as a JSON array of objects to the given writer, all times are in seconds.
+    pub fn write_json(&self, writer: &mut impl Write) -> io::Result<()> {
+        let results = self.aggregate_results();
+        let summaries: Vec<JsonSummary> = results
+            .iter()
+            .map(|ag| JsonSummary {
+                name: &ag.name,
+                count: ag.count,
+                total: ag.total,
+                min: ag.min,
+                max: ag.max,
+                avg: ag.avg,
+                peak_bytes: ag.peak_bytes,
+            })
+            .collect();
+
+        serde_json::to_writer_pretty(&mut *writer, &summaries)?;
+        writeln!(writer)
+    }
+
+    /// Writes the aggregated timers as CSV, with a header line, to the given writer, all times are in seconds.
+    pub fn write_csv(&self, writer: &mut impl Write) -> io::Result<()> {
+        writeln!(writer, "name,count,total,min,max,avg,peak_bytes")?;
+
+        for ag in self.aggregate_results() {
+            if ag.name.contains([',', '"', '\n']) {
+                write!(writer, "\"{}\"", ag.name.replace('"', "\"\""))?;
+            } else {
+                write!(writer, "{}", ag.name)?;
+            }
+
+            write!(
+                writer,
+                ",{},{:.6},{:.6},{:.6},{:.6},",
+                ag.count, ag.total, ag.min, ag.max, ag.avg
+            )?;
+
+            if let Some(bytes) = ag.peak_bytes {
+                write!(writer, "{bytes}")?;
+            }
+            writeln!(writer)?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the full path of a timer or scope with the given name in this scope.
+    fn path(&self, name: &str) -> String {
+        if self.scope.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}{SCOPE_SEPARATOR}{name}", self.scope)
+        }
+    }
 }
 
 impl Timer {
@@ -125,9 +306,19 @@ impl Timer {
         let time = self.start.elapsed().as_secs_f64();
         info!("Time {}: {:.3}s", self.name, time);
 
+        let peak_bytes = self
+            .memory_token
+            .take()
+            .and_then(|token| MEMORY_PROBE.get().map(|probe| (probe.finish)(token)));
+
         // Register the result.
-        self.results.borrow_mut().push((self.name.clone(), time as f32));
-        self.registered = true
+        self.results.borrow_mut().push(Measurement {
+            name: self.name.clone(),
+            time: time as f32,
+            peak_bytes,
+        });
+        self.registered = true;
+        self.span = None;
     }
 }
 
@@ -135,6 +326,50 @@ impl Drop for Timer {
     fn drop(&mut self) {
         if !self.registered {
             warn!("Timer {} was dropped before 'finish()'", self.name);
+
+            // Finish the memory measurement such that the enclosing measurements remain correct.
+            if let (Some(token), Some(probe)) = (self.memory_token.take(), MEMORY_PROBE.get()) {
+                (probe.finish)(token);
+            }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nested_scopes() {
+        let timing = Timing::new();
+
+        let reduce = timing.scope("reduce");
+        for _ in 0..3 {
+            reduce.start("preprocess").finish();
+        }
+        reduce.scope("refine").start("split").finish();
+        timing.start("reduce").finish();
+        timing.start("reduce-total").finish();
+
+        let results = timing.aggregate_results();
+        let names: Vec<&str> = results.iter().map(|ag| ag.name.as_str()).collect();
+        assert_eq!(
+            names,
+            ["reduce", "reduce/preprocess", "reduce/refine/split", "reduce-total"]
+        );
+        assert_eq!(results[1].count, 3);
+        assert_eq!(results[2].depth(), 2);
+        assert_eq!(results[2].short_name(), "split");
+
+        let mut csv = Vec::new();
+        timing.write_csv(&mut csv).unwrap();
+        assert_eq!(String::from_utf8(csv).unwrap().lines().count(), 5);
+
+        let mut json = Vec::new();
+        timing.write_json(&mut json).unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&json).unwrap();
+        assert_eq!(json[2]["name"], "reduce/refine/split");
+        assert_eq!(json[2]["count"], 1);
+        assert!(json[2].get("peak_bytes").is_none());
+    }
+}
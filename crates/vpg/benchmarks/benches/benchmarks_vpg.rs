@@ -0,0 +1,107 @@
+use std::env;
+use std::fs;
+use std::fs::File;
+use std::hint::black_box;
+
+use criterion::Criterion;
+use criterion::criterion_group;
+use criterion::criterion_main;
+use rand::Rng;
+
+use merc_vpg::ParityGame;
+use merc_vpg::Player;
+use merc_vpg::Priority;
+use merc_vpg::VertexIndex;
+use merc_vpg::random_parity_game;
+use merc_vpg::read_pg;
+use merc_vpg::solve_zielonka;
+
+/// Returns the owners, priorities and unordered edges of a random parity game.
+fn random_edges(
+    rng: &mut impl Rng,
+    num_of_vertices: usize,
+    num_of_edges: usize,
+) -> (Vec<Player>, Vec<Priority>, Vec<(VertexIndex, VertexIndex)>) {
+    let owner = (0..num_of_vertices)
+        .map(|_| Player::from_index(rng.random_range(0..2)))
+        .collect();
+    let priority = (0..num_of_vertices)
+        .map(|_| Priority::new(rng.random_range(0..10)))
+        .collect();
+    let edges = (0..num_of_edges)
+        .map(|_| {
+            (
+                VertexIndex::new(rng.random_range(0..num_of_vertices)),
+                VertexIndex::new(rng.random_range(0..num_of_vertices)),
+            )
+        })
+        .collect();
+
+    (owner, priority, edges)
+}
+
+pub fn criterion_benchmark_construction(c: &mut Criterion) {
+    let mut rng = rand::rng();
+    let (owner, priority, edges) = random_edges(&mut rng, 100_000, 1_000_000);
+
+    c.bench_function("from_edges 1000000", |bencher| {
+        bencher.iter(|| {
+            black_box(ParityGame::from_edges(
+                VertexIndex::new(0),
+                owner.clone(),
+                priority.clone(),
+                true,
+                || edges.iter().cloned(),
+            ));
+        })
+    });
+
+    c.bench_function("from_edges_parallel 1000000", |bencher| {
+        bencher.iter(|| {
+            black_box(ParityGame::from_edges_parallel(
+                VertexIndex::new(0),
+                owner.clone(),
+                priority.clone(),
+                true,
+                edges.clone(),
+            ));
+        })
+    });
+}
+
+/// Solves random parity games, and the `.pg` files in the directory given by
+/// the `MERC_PG_BENCHMARKS` environment variable, for example the parity games
+/// of Keiren's benchmark set.
+pub fn criterion_benchmark_zielonka(c: &mut Criterion) {
+    let mut rng = rand::rng();
+    let game = random_parity_game(&mut rng, true, 100_000, 10, 5);
+
+    c.bench_function("zielonka random 100000", |bencher| {
+        bencher.iter(|| {
+            black_box(solve_zielonka(&game).unwrap());
+        })
+    });
+
+    if let Ok(directory) = env::var("MERC_PG_BENCHMARKS") {
+        let mut paths: Vec<_> = fs::read_dir(directory)
+            .expect("MERC_PG_BENCHMARKS should be a directory")
+            .map(|entry| entry.expect("Failed to read directory entry").path())
+            .filter(|path| path.extension().is_some_and(|extension| extension == "pg"))
+            .collect();
+        paths.sort();
+
+        for path in paths {
+            let game = read_pg(File::open(&path).unwrap()).unwrap();
+            let name = path.file_stem().unwrap().to_string_lossy();
+
+            c.bench_function(&format!("zielonka {name}"), |bencher| {
+                bencher.iter(|| {
+                    black_box(solve_zielonka(&game).unwrap());
+                })
+            });
+        }
+    }
+}
+
+criterion_group!(benches, criterion_benchmark_construction, criterion_benchmark_zielonka);
+criterion_main!(benches);
@@ -0,0 +1,610 @@
+//! Authors: Maurice Laveaux and Sjef van Loo
+
+use std::rc::Rc;
+
+use oxidd::BooleanFunction;
+use oxidd::ManagerRef;
+use oxidd::bdd::BDDFunction;
+use oxidd::bdd::BDDManagerRef;
+use oxidd::util::OptBool;
+use splr::Certificate;
+use splr::Config;
+use splr::SatSolverIF;
+use splr::SolveIF;
+use splr::Solver;
+use splr::SolverError;
+
+use merc_symbolic::minus;
+use merc_utilities::MercError;
+
+/// The backend used to reason about sets of configurations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+pub enum ConfigBackendType {
+    /// Represent configuration sets by BDDs.
+    Bdd,
+    /// Represent configuration sets by propositional formulas and decide them with a SAT solver.
+    Sat,
+}
+
+/// Abstracts the operations on sets of configurations that are needed by the
+/// product-based solvers.
+///
+/// # Details
+///
+/// A configuration is an assignment to the features, which are numbered from
+/// zero to [ConfigBackend::num_of_variables]. BDDs can become very large for
+/// feature models with hundreds of features, while the satisfiability queries
+/// on them are typically easy for a SAT solver.
+pub trait ConfigBackend {
+    /// The representation of a set of configurations.
+    type Config: Clone;
+
+    /// The iterator returned by [ConfigBackend::configurations].
+    type Configurations: Iterator<Item = Result<Vec<bool>, MercError>>;
+
+    /// Returns the number of features.
+    fn num_of_variables(&self) -> usize;
+
+    /// Returns the set of all configurations.
+    fn top(&self) -> Result<Self::Config, MercError>;
+
+    /// Returns the empty set of configurations.
+    fn bottom(&self) -> Result<Self::Config, MercError>;
+
+    /// Returns the configurations in which the given feature is enabled.
+    fn variable(&self, index: usize) -> Result<Self::Config, MercError>;
+
+    /// Returns the intersection of both sets.
+    fn and(&self, lhs: &Self::Config, rhs: &Self::Config) -> Result<Self::Config, MercError>;
+
+    /// Returns the union of both sets.
+    fn or(&self, lhs: &Self::Config, rhs: &Self::Config) -> Result<Self::Config, MercError>;
+
+    /// Returns the complement of the set.
+    fn not(&self, config: &Self::Config) -> Result<Self::Config, MercError>;
+
+    /// Returns the configurations in `lhs` that are not in `rhs`.
+    fn minus(&self, lhs: &Self::Config, rhs: &Self::Config) -> Result<Self::Config, MercError> {
+        self.and(lhs, &self.not(rhs)?)
+    }
+
+    /// Returns the configurations in `rhs` or not in `lhs`.
+    fn imp(&self, lhs: &Self::Config, rhs: &Self::Config) -> Result<Self::Config, MercError> {
+        self.or(&self.not(lhs)?, rhs)
+    }
+
+    /// Returns the configurations in `then_branch` that are in `condition`,
+    /// together with the configurations in `else_branch` that are not.
+    fn ite(
+        &self,
+        condition: &Self::Config,
+        then_branch: &Self::Config,
+        else_branch: &Self::Config,
+    ) -> Result<Self::Config, MercError> {
+        self.or(&self.and(condition, then_branch)?, &self.minus(else_branch, condition)?)
+    }
+
+    /// Returns true iff the set contains at least one configuration.
+    fn satisfiable(&self, config: &Self::Config) -> Result<bool, MercError>;
+
+    /// Returns an arbitrary configuration in the set, or `None` when it is empty.
+    fn pick(&self, config: &Self::Config) -> Result<Option<Vec<bool>>, MercError>;
+
+    /// Returns true iff the given configuration, which assigns a value to every feature, is in the set.
+    fn evaluate(&self, config: &Self::Config, values: &[bool]) -> bool;
+
+    /// Returns an iterator over all configurations in the set, where every
+    /// configuration assigns a value to every feature.
+    fn configurations(&self, config: &Self::Config) -> Result<Self::Configurations, MercError>;
+
+    /// Returns the set that only contains the given configuration.
+    fn cube(&self, values: &[bool]) -> Result<Self::Config, MercError> {
+        let mut result = self.top()?;
+        for (index, value) in values.iter().enumerate() {
+            let variable = self.variable(index)?;
+            let literal = if *value { variable } else { self.not(&variable)? };
+            result = self.and(&result, &literal)?;
+        }
+
+        Ok(result)
+    }
+}
+
+/// Represents configuration sets by BDDs in the given manager.
+pub struct BddBackend<'a> {
+    manager_ref: &'a BDDManagerRef,
+    variables: &'a [BDDFunction],
+}
+
+impl<'a> BddBackend<'a> {
+    /// Creates a backend for the given feature variables.
+    pub fn new(manager_ref: &'a BDDManagerRef, variables: &'a [BDDFunction]) -> Self {
+        Self { manager_ref, variables }
+    }
+}
+
+impl ConfigBackend for BddBackend<'_> {
+    type Config = BDDFunction;
+    type Configurations = BddConfigurations;
+
+    fn num_of_variables(&self) -> usize {
+        self.variables.len()
+    }
+
+    fn top(&self) -> Result<BDDFunction, MercError> {
+        Ok(self.manager_ref.with_manager_shared(|manager| BDDFunction::t(manager)))
+    }
+
+    fn bottom(&self) -> Result<BDDFunction, MercError> {
+        Ok(self.manager_ref.with_manager_shared(|manager| BDDFunction::f(manager)))
+    }
+
+    fn variable(&self, index: usize) -> Result<BDDFunction, MercError> {
+        Ok(self.variables[index].clone())
+    }
+
+    fn and(&self, lhs: &BDDFunction, rhs: &BDDFunction) -> Result<BDDFunction, MercError> {
+        Ok(lhs.and(rhs)?)
+    }
+
+    fn or(&self, lhs: &BDDFunction, rhs: &BDDFunction) -> Result<BDDFunction, MercError> {
+        Ok(lhs.or(rhs)?)
+    }
+
+    fn not(&self, config: &BDDFunction) -> Result<BDDFunction, MercError> {
+        Ok(config.not()?)
+    }
+
+    fn minus(&self, lhs: &BDDFunction, rhs: &BDDFunction) -> Result<BDDFunction, MercError> {
+        Ok(minus(lhs, rhs)?)
+    }
+
+    fn imp(&self, lhs: &BDDFunction, rhs: &BDDFunction) -> Result<BDDFunction, MercError> {
+        Ok(lhs.imp(rhs)?)
+    }
+
+    fn ite(
+        &self,
+        condition: &BDDFunction,
+        then_branch: &BDDFunction,
+        else_branch: &BDDFunction,
+    ) -> Result<BDDFunction, MercError> {
+        Ok(condition.ite(then_branch, else_branch)?)
+    }
+
+    fn satisfiable(&self, config: &BDDFunction) -> Result<bool, MercError> {
+        Ok(config.satisfiable())
+    }
+
+    fn pick(&self, config: &BDDFunction) -> Result<Option<Vec<bool>>, MercError> {
+        Ok(pick_configuration(config, self.variables.len()))
+    }
+
+    fn evaluate(&self, config: &BDDFunction, values: &[bool]) -> bool {
+        debug_assert_eq!(values.len(), self.variables.len(), "Every feature should have a value");
+        config.eval(self.variables.iter().zip(values.iter().copied()))
+    }
+
+    fn configurations(&self, config: &BDDFunction) -> Result<BddConfigurations, MercError> {
+        Ok(BddConfigurations {
+            remaining: config.clone(),
+            variables: self.variables.to_vec(),
+        })
+    }
+}
+
+/// Returns an arbitrary configuration in the given BDD, where the variables
+/// that do not matter are chosen to be disabled.
+fn pick_configuration(config: &BDDFunction, num_of_variables: usize) -> Option<Vec<bool>> {
+    config.pick_cube(|_, _, _| false).map(|cube| {
+        (0..num_of_variables)
+            .map(|index| cube.get(index) == Some(&OptBool::True))
+            .collect()
+    })
+}
+
+/// Enumerates the configurations of a BDD, see [BddBackend::configurations].
+pub struct BddConfigurations {
+    /// The configurations that have not been returned yet.
+    remaining: BDDFunction,
+    variables: Vec<BDDFunction>,
+}
+
+impl BddConfigurations {
+    /// Returns the next configuration, and removes it from the remaining configurations.
+    fn next_configuration(&mut self) -> Result<Option<Vec<bool>>, MercError> {
+        let Some(values) = pick_configuration(&self.remaining, self.variables.len()) else {
+            return Ok(None);
+        };
+
+        let mut cube = self.remaining.clone();
+        for (variable, value) in self.variables.iter().zip(&values) {
+            let literal = if *value { variable.clone() } else { variable.not()? };
+            cube = cube.and(&literal)?;
+        }
+
+        self.remaining = minus(&self.remaining, &cube)?;
+
+        Ok(Some(values))
+    }
+}
+
+impl Iterator for BddConfigurations {
+    type Item = Result<Vec<bool>, MercError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_configuration().transpose()
+    }
+}
+
+/// A propositional formula over the features, with structural sharing.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Formula {
+    True,
+    False,
+    /// The feature with the given index.
+    Variable(usize),
+    Not(Rc<Formula>),
+    And(Rc<Formula>, Rc<Formula>),
+    Or(Rc<Formula>, Rc<Formula>),
+}
+
+impl Formula {
+    /// Returns the truth value of the formula when the features have the given values.
+    pub fn evaluate(&self, values: &[bool]) -> bool {
+        match self {
+            Formula::True => true,
+            Formula::False => false,
+            Formula::Variable(index) => values[*index],
+            Formula::Not(inner) => !inner.evaluate(values),
+            Formula::And(lhs, rhs) => lhs.evaluate(values) && rhs.evaluate(values),
+            Formula::Or(lhs, rhs) => lhs.evaluate(values) || rhs.evaluate(values),
+        }
+    }
+}
+
+/// Represents configuration sets by propositional formulas, whose
+/// satisfiability is decided by the splr SAT solver.
+///
+/// # Details
+///
+/// The operations only build formulas, where constants are simplified away.
+/// The satisfiability checks use the Tseitin encoding of the formula into
+/// conjunctive normal form, so every query solves a new SAT problem. The
+/// configurations of a set are instead enumerated by a single incremental
+/// solver, which excludes every returned configuration by a blocking clause.
+pub struct SatBackend {
+    num_of_variables: usize,
+}
+
+impl SatBackend {
+    /// Creates a backend for the given number of features.
+    pub fn new(num_of_variables: usize) -> Self {
+        Self { num_of_variables }
+    }
+
+    /// Returns the clauses of the Tseitin encoding of the formula, or `None` when it is unsatisfiable.
+    ///
+    /// The features are the variables 1 up to num_of_variables, the remaining ones are auxiliary.
+    fn encode(&self, formula: &Formula) -> Option<Vec<Vec<i32>>> {
+        let mut clauses = Vec::new();
+        match formula {
+            Formula::True => {}
+            Formula::False => return None,
+            _ => {
+                let mut next_variable = self.num_of_variables as i32 + 1;
+                let root = tseitin(formula, &mut clauses, &mut next_variable);
+                clauses.push(vec![root]);
+            }
+        }
+
+        Some(clauses)
+    }
+
+    /// Returns a satisfying assignment of the formula, or `None` when it is unsatisfiable.
+    fn solve(&self, formula: &Formula) -> Result<Option<Vec<bool>>, MercError> {
+        if *formula == Formula::True {
+            return Ok(Some(vec![false; self.num_of_variables]));
+        }
+
+        let Some(clauses) = self.encode(formula) else {
+            return Ok(None);
+        };
+
+        match Certificate::try_from(clauses) {
+            Ok(Certificate::SAT(model)) => Ok(Some(model_values(&model, self.num_of_variables))),
+            Ok(Certificate::UNSAT) => Ok(None),
+            Err(err) => Err(format!("The SAT solver failed: {err:?}").into()),
+        }
+    }
+}
+
+impl ConfigBackend for SatBackend {
+    type Config = Rc<Formula>;
+    type Configurations = SatConfigurations;
+
+    fn num_of_variables(&self) -> usize {
+        self.num_of_variables
+    }
+
+    fn top(&self) -> Result<Rc<Formula>, MercError> {
+        Ok(Rc::new(Formula::True))
+    }
+
+    fn bottom(&self) -> Result<Rc<Formula>, MercError> {
+        Ok(Rc::new(Formula::False))
+    }
+
+    fn variable(&self, index: usize) -> Result<Rc<Formula>, MercError> {
+        debug_assert!(index < self.num_of_variables, "Variable {index} out of bounds");
+        Ok(Rc::new(Formula::Variable(index)))
+    }
+
+    fn and(&self, lhs: &Rc<Formula>, rhs: &Rc<Formula>) -> Result<Rc<Formula>, MercError> {
+        Ok(match (lhs.as_ref(), rhs.as_ref()) {
+            (Formula::False, _) | (_, Formula::False) => Rc::new(Formula::False),
+            (Formula::True, _) => rhs.clone(),
+            (_, Formula::True) => lhs.clone(),
+            _ => Rc::new(Formula::And(lhs.clone(), rhs.clone())),
+        })
+    }
+
+    fn or(&self, lhs: &Rc<Formula>, rhs: &Rc<Formula>) -> Result<Rc<Formula>, MercError> {
+        Ok(match (lhs.as_ref(), rhs.as_ref()) {
+            (Formula::True, _) | (_, Formula::True) => Rc::new(Formula::True),
+            (Formula::False, _) => rhs.clone(),
+            (_, Formula::False) => lhs.clone(),
+            _ => Rc::new(Formula::Or(lhs.clone(), rhs.clone())),
+        })
+    }
+
+    fn not(&self, config: &Rc<Formula>) -> Result<Rc<Formula>, MercError> {
+        Ok(match config.as_ref() {
+            Formula::True => Rc::new(Formula::False),
+            Formula::False => Rc::new(Formula::True),
+            Formula::Not(inner) => inner.clone(),
+            _ => Rc::new(Formula::Not(config.clone())),
+        })
+    }
+
+    fn satisfiable(&self, config: &Rc<Formula>) -> Result<bool, MercError> {
+        Ok(self.solve(config)?.is_some())
+    }
+
+    fn pick(&self, config: &Rc<Formula>) -> Result<Option<Vec<bool>>, MercError> {
+        self.solve(config)
+    }
+
+    fn evaluate(&self, config: &Rc<Formula>, values: &[bool]) -> bool {
+        debug_assert_eq!(values.len(), self.num_of_variables, "Every feature should have a value");
+        config.evaluate(values)
+    }
+
+    fn configurations(&self, config: &Rc<Formula>) -> Result<SatConfigurations, MercError> {
+        let Some(mut clauses) = self.encode(config) else {
+            return Ok(SatConfigurations {
+                solver: None,
+                num_of_variables: self.num_of_variables,
+            });
+        };
+
+        // Every feature occurs in a (trivial) clause, such that the solver assigns a value to every feature.
+        for variable in 1..=self.num_of_variables.max(1) as i32 {
+            clauses.push(vec![variable, -variable]);
+        }
+
+        let solver = match Solver::try_from((Config::default(), clauses.as_slice())) {
+            Ok(solver) => Some(solver),
+            Err(Ok(Certificate::UNSAT)) => None,
+            Err(err) => return Err(format!("The SAT solver failed: {err:?}").into()),
+        };
+
+        Ok(SatConfigurations {
+            solver,
+            num_of_variables: self.num_of_variables,
+        })
+    }
+}
+
+/// Enumerates the configurations of a formula, see [SatBackend::configurations].
+pub struct SatConfigurations {
+    /// The incremental solver, which is `None` when all configurations have been returned.
+    solver: Option<Solver>,
+    num_of_variables: usize,
+}
+
+impl SatConfigurations {
+    /// Returns the next configuration, and adds a blocking clause that excludes it from the solutions.
+    fn next_configuration(&mut self) -> Result<Option<Vec<bool>>, MercError> {
+        let Some(solver) = self.solver.as_mut() else {
+            return Ok(None);
+        };
+
+        let values = match solver.solve() {
+            Ok(Certificate::SAT(model)) => model_values(&model, self.num_of_variables),
+            Ok(Certificate::UNSAT) => {
+                self.solver = None;
+                return Ok(None);
+            }
+            Err(err) => {
+                self.solver = None;
+                return Err(format!("The SAT solver failed: {err:?}").into());
+            }
+        };
+
+        // The auxiliary variables are determined by the features, so blocking the features suffices.
+        let blocking: Vec<i32> = values
+            .iter()
+            .enumerate()
+            .map(|(index, value)| if *value { -(index as i32 + 1) } else { index as i32 + 1 })
+            .collect();
+
+        let exhausted = if blocking.is_empty() {
+            // Without features there is only a single configuration.
+            true
+        } else {
+            match solver.add_clause(blocking) {
+                Ok(_) => {
+                    solver.reset();
+                    false
+                }
+                Err(SolverError::Inconsistent | SolverError::EmptyClause) => true,
+                Err(err) => return Err(format!("The SAT solver failed: {err:?}").into()),
+            }
+        };
+
+        if exhausted {
+            self.solver = None;
+        }
+
+        Ok(Some(values))
+    }
+}
+
+impl Iterator for SatConfigurations {
+    type Item = Result<Vec<bool>, MercError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_configuration().transpose()
+    }
+}
+
+/// Returns the values of the features in a model of the SAT solver.
+fn model_values(model: &[i32], num_of_variables: usize) -> Vec<bool> {
+    let mut result = vec![false; num_of_variables];
+    for literal in model {
+        let index = literal.unsigned_abs() as usize - 1;
+        if index < num_of_variables {
+            result[index] = *literal > 0;
+        }
+    }
+
+    result
+}
+
+/// Adds the Tseitin encoding of the formula to the clauses and returns the literal that represents it.
+///
+/// The formula should not contain the constants, which are removed by the operations of [SatBackend].
+fn tseitin(formula: &Formula, clauses: &mut Vec<Vec<i32>>, next_variable: &mut i32) -> i32 {
+    match formula {
+        Formula::Variable(index) => *index as i32 + 1,
+        Formula::Not(inner) => -tseitin(inner, clauses, next_variable),
+        Formula::And(lhs, rhs) | Formula::Or(lhs, rhs) => {
+            let lhs = tseitin(lhs, clauses, next_variable);
+            let rhs = tseitin(rhs, clauses, next_variable);
+            let result = *next_variable;
+            *next_variable += 1;
+
+            if matches!(formula, Formula::And(_, _)) {
+                clauses.push(vec![-result, lhs]);
+                clauses.push(vec![-result, rhs]);
+                clauses.push(vec![result, -lhs, -rhs]);
+            } else {
+                clauses.push(vec![result, -lhs]);
+                clauses.push(vec![result, -rhs]);
+                clauses.push(vec![-result, lhs, rhs]);
+            }
+
+            result
+        }
+        Formula::True | Formula::False => unreachable!("Constants are simplified away by the SatBackend"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use merc_symbolic::create_variables;
+    use merc_symbolic::random_bitvectors;
+    use merc_utilities::random_test;
+
+    use super::*;
+
+    /// Returns the set of configurations given by the bitvectors, where every bitvector is a cube.
+    fn from_bitvectors<B: ConfigBackend>(backend: &B, vectors: &[Vec<OptBool>]) -> Result<B::Config, MercError> {
+        let mut result = backend.bottom()?;
+        for bits in vectors {
+            let mut cube = backend.top()?;
+            for (index, bit) in bits.iter().enumerate() {
+                let literal = match bit {
+                    OptBool::True => backend.variable(index)?,
+                    OptBool::False => backend.not(&backend.variable(index)?)?,
+                    OptBool::None => continue,
+                };
+                cube = backend.and(&cube, &literal)?;
+            }
+            result = backend.or(&result, &cube)?;
+        }
+
+        Ok(result)
+    }
+
+    /// Returns all configurations in the set, in sorted order.
+    fn sorted_configurations<B: ConfigBackend>(backend: &B, config: &B::Config) -> Result<Vec<Vec<bool>>, MercError> {
+        let mut result = backend.configurations(config)?.collect::<Result<Vec<_>, _>>()?;
+        result.sort();
+        Ok(result)
+    }
+
+    /// Checks that both backends agree on the boolean combinations of the given sets of configurations.
+    fn check_backends(
+        bdd_backend: &BddBackend,
+        sat_backend: &SatBackend,
+        lhs: &[Vec<OptBool>],
+        rhs: &[Vec<OptBool>],
+    ) -> Result<(), MercError> {
+        let (bdd_lhs, bdd_rhs) = (from_bitvectors(bdd_backend, lhs)?, from_bitvectors(bdd_backend, rhs)?);
+        let (sat_lhs, sat_rhs) = (from_bitvectors(sat_backend, lhs)?, from_bitvectors(sat_backend, rhs)?);
+
+        let results = [
+            (
+                bdd_backend.and(&bdd_lhs, &bdd_rhs)?,
+                sat_backend.and(&sat_lhs, &sat_rhs)?,
+            ),
+            (bdd_backend.or(&bdd_lhs, &bdd_rhs)?, sat_backend.or(&sat_lhs, &sat_rhs)?),
+            (bdd_backend.not(&bdd_lhs)?, sat_backend.not(&sat_lhs)?),
+            (
+                bdd_backend.minus(&bdd_lhs, &bdd_rhs)?,
+                sat_backend.minus(&sat_lhs, &sat_rhs)?,
+            ),
+            (
+                bdd_backend.ite(&bdd_lhs, &bdd_rhs, &bdd_backend.variable(0)?)?,
+                sat_backend.ite(&sat_lhs, &sat_rhs, &sat_backend.variable(0)?)?,
+            ),
+        ];
+
+        for (bdd, formula) in &results {
+            assert_eq!(bdd_backend.satisfiable(bdd)?, sat_backend.satisfiable(formula)?);
+
+            // The configuration picked by the SAT solver must be in the BDD.
+            if let Some(values) = sat_backend.pick(formula)? {
+                assert!(bdd_backend.evaluate(bdd, &values));
+            }
+
+            // Both backends enumerate the same configurations, which are all in the set.
+            let configurations = sorted_configurations(sat_backend, formula)?;
+            assert_eq!(configurations, sorted_configurations(bdd_backend, bdd)?);
+            for values in &configurations {
+                assert!(sat_backend.evaluate(formula, values));
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)] // Oxidd does not work with miri
+    fn test_random_config_backends() {
+        random_test(100, |rng| {
+            let manager_ref = oxidd::bdd::new_manager(2048, 1024, 1);
+            let variables = create_variables(&manager_ref, 6).unwrap();
+
+            let lhs = random_bitvectors(rng, 6, 4);
+            let rhs = random_bitvectors(rng, 6, 4);
+
+            let bdd_backend = BddBackend::new(&manager_ref, &variables);
+            let sat_backend = SatBackend::new(variables.len());
+            check_backends(&bdd_backend, &sat_backend, &lhs, &rhs).unwrap();
+        })
+    }
+}
@@ -2,12 +2,14 @@
 
 use std::collections::HashMap;
 use std::fmt;
-use std::io::BufRead;
-use std::io::BufReader;
 use std::io::Read;
 
+use ahash::AHashSet;
 use log::debug;
+use log::trace;
+use merc_collections::IndexedSet;
 use merc_lts::LabelIndex;
+use merc_lts::LtsBuilderFast;
 use merc_lts::StateIndex;
 use merc_lts::Transition;
 use oxidd::BooleanFunction;
@@ -15,7 +17,16 @@ use oxidd::Manager;
 use oxidd::ManagerRef;
 use oxidd::bdd::BDDFunction;
 use oxidd::bdd::BDDManagerRef;
-
+use streaming_iterator::StreamingIterator;
+
+use merc_aterm::Term;
+use merc_data::DataExpression;
+use merc_data::DataFunctionSymbol;
+use merc_data::Simplifier;
+use merc_data::is_data_function_symbol;
+use merc_data::to_data_expression;
+use merc_io::LineIterator;
+use merc_io::MAX_LINE_LENGTH;
 use merc_lts::LTS;
 use merc_lts::LabelledTransitionSystem;
 use merc_lts::read_aut;
@@ -23,6 +34,9 @@ use merc_syntax::DataExpr;
 use merc_syntax::MultiAction;
 use merc_utilities::MercError;
 
+use crate::BddBackend;
+use crate::ConfigBackend;
+
 /// Reads a .aut file as feature transition system by using the associated feature diagram.
 ///
 /// # Details
@@ -43,10 +57,13 @@ pub fn read_fts(
 
         debug!("Parsed action: {}", action);
         if action.actions.len() > 1 {
-            return Err(MercError::from(format!(
-                "Cannot read feature transition system: action \"{}\" has multiple actions",
-                label
-            )));
+            return Err(MercError::parse(
+                format!(
+                    "Cannot read feature transition system: action \"{}\" has multiple actions",
+                    label
+                ),
+                None,
+            ));
         }
 
         if let Some(action) = action.actions.first() {
@@ -64,43 +81,136 @@ pub fn read_fts(
     Ok(FeatureTransitionSystem::new(aut, feature_labels, features))
 }
 
-/// Converts the given data expression into a BDD function.
+/// The function symbols of feature expressions that are not feature names.
+const FEATURE_KEYWORDS: [&str; 9] = ["!", "&&", "||", "=>", "true", "false", "tt", "ff", "node"];
+
+/// Parses a feature expression, such as `a && !(b || c) => d`, into a BDD
+/// function over the given features.
+///
+/// # Details
+///
+/// Besides the feature names, the boolean operators `!`, `&&`, `||` and `=>`,
+/// parentheses and the constants `true` and `false`, the expression may use the
+/// encoding `node(var, expr, expr) | tt | ff` of feature transition systems.
+pub fn parse_feature_expression(
+    manager_ref: &BDDManagerRef,
+    features: &HashMap<String, BDDFunction>,
+    text: &str,
+) -> Result<BDDFunction, MercError> {
+    data_expr_to_bdd(manager_ref, features, &DataExpr::parse(text)?)
+}
+
+/// Parses a feature expression, see [parse_feature_expression], into a set of
+/// configurations of the given backend, where the features are given by their
+/// variable index.
+pub fn parse_feature_expression_backend<B: ConfigBackend>(
+    backend: &B,
+    features: &HashMap<String, usize>,
+    text: &str,
+) -> Result<B::Config, MercError> {
+    data_expr_to_config(
+        backend,
+        &|name: &str| features.get(name).and_then(|index| backend.variable(*index).ok()),
+        &DataExpr::parse(text)?,
+    )
+}
+
+/// Returns the names of the features that occur in the given feature
+/// expression, in the order of their first occurrence.
+pub fn feature_names(text: &str) -> Result<Vec<String>, MercError> {
+    let expr = to_data_expression(&DataExpr::parse(text)?, &AHashSet::new())?;
+
+    let mut result: Vec<String> = Vec::new();
+    for term in expr.iter() {
+        if is_data_function_symbol(&term) {
+            let name = DataFunctionSymbol::from(term.protect()).name().to_string();
+            if !FEATURE_KEYWORDS.contains(&name.as_str()) && !result.contains(&name) {
+                result.push(name);
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+/// Converts the given data expression into a BDD function, after simplifying it with [Simplifier].
 ///
-/// The input should be a data expression of the shape: expr = node(var, expr, expr) | tt | ff.
+/// The input should be a data expression of the shape: expr = node(var, expr, expr) | tt | ff, where the feature
+/// names, the boolean operators `!`, `&&`, `||` and `=>` and the constants `true` and `false` may also be used.
 fn data_expr_to_bdd(
     manager_ref: &BDDManagerRef,
     variables: &HashMap<String, BDDFunction>,
     expr: &DataExpr,
 ) -> Result<BDDFunction, MercError> {
-    match expr {
-        DataExpr::Application { function, arguments } => {
-            match function.as_ref() {
-                // A node must be of the shape 'node(var, true_branch, false_branch)'
-                DataExpr::Id(name) => {
-                    if name == "node" {
-                        let variable = format!("{}", arguments[0]);
-                        let then_branch = data_expr_to_bdd(manager_ref, variables, &arguments[1])?;
-                        let else_branch = data_expr_to_bdd(manager_ref, variables, &arguments[2])?;
-                        Ok(variables
-                            .get(&variable)
-                            .ok_or(format!("Variable \"{}\" not found in feature diagram", variable))?
-                            .ite(&then_branch, &else_branch)?)
-                    } else {
-                        unimplemented!("Conversion of data expression to BDD not implemented for this function");
-                    }
-                }
-                _ => unimplemented!("Conversion of data expression to BDD not implemented for this function"),
-            }
+    // The variables of the backend are not used, since the features are looked up by name.
+    data_expr_to_config(
+        &BddBackend::new(manager_ref, &[]),
+        &|name: &str| variables.get(name).cloned(),
+        expr,
+    )
+}
+
+/// Converts the given data expression into a set of configurations of the
+/// backend, where `variable` returns the configurations of a feature by its name.
+/// See [data_expr_to_bdd] for the shape of the expression.
+fn data_expr_to_config<B: ConfigBackend>(
+    backend: &B,
+    variable: &impl Fn(&str) -> Option<B::Config>,
+    expr: &DataExpr,
+) -> Result<B::Config, MercError> {
+    let expr = Simplifier::default().simplify(&to_data_expression(expr, &AHashSet::new())?);
+    data_expression_to_config(backend, variable, &expr)
+}
+
+/// Converts the given simplified data expression into a set of configurations, see [data_expr_to_config].
+fn data_expression_to_config<B: ConfigBackend>(
+    backend: &B,
+    variable: &impl Fn(&str) -> Option<B::Config>,
+    expr: &DataExpression,
+) -> Result<B::Config, MercError> {
+    let name = expr.data_function_symbol().name().to_string();
+    let arguments: Vec<DataExpression> = expr.data_arguments().map(|argument| argument.protect()).collect();
+
+    match (name.as_str(), arguments.as_slice()) {
+        // A node must be of the shape 'node(var, true_branch, false_branch)'
+        ("node", [feature, then_branch, else_branch]) => {
+            let feature = format!("{}", feature);
+            let then_branch = data_expression_to_config(backend, variable, then_branch)?;
+            let else_branch = data_expression_to_config(backend, variable, else_branch)?;
+            let condition = variable(&feature).ok_or_else(|| {
+                MercError::parse(format!("Variable \"{}\" not found in feature diagram", feature), None)
+            })?;
+            backend.ite(&condition, &then_branch, &else_branch)
         }
-        DataExpr::Id(name) => {
-            // Deal with the base cases.
-            match name.as_str() {
-                "tt" => Ok(manager_ref.with_manager_shared(|manager| BDDFunction::t(manager))),
-                "ff" => Ok(manager_ref.with_manager_shared(|manager| BDDFunction::f(manager))),
-                _ => unimplemented!("Cannot convert data expression \"{expr}\" to BDD"),
-            }
+        ("!", [operand]) => backend.not(&data_expression_to_config(backend, variable, operand)?),
+        ("&&", [lhs, rhs]) => {
+            let lhs = data_expression_to_config(backend, variable, lhs)?;
+            let rhs = data_expression_to_config(backend, variable, rhs)?;
+            backend.and(&lhs, &rhs)
         }
-        _ => unimplemented!("Cannot convert data expression \"{expr}\" to BDD"),
+        ("||", [lhs, rhs]) => {
+            let lhs = data_expression_to_config(backend, variable, lhs)?;
+            let rhs = data_expression_to_config(backend, variable, rhs)?;
+            backend.or(&lhs, &rhs)
+        }
+        ("=>", [lhs, rhs]) => {
+            let lhs = data_expression_to_config(backend, variable, lhs)?;
+            let rhs = data_expression_to_config(backend, variable, rhs)?;
+            backend.imp(&lhs, &rhs)
+        }
+        // Deal with the base cases.
+        ("tt" | "true", []) => backend.top(),
+        ("ff" | "false", []) => backend.bottom(),
+        (feature, []) => variable(feature).ok_or_else(|| {
+            MercError::parse(
+                format!("Cannot convert data expression \"{expr}\" to BDD, expected a feature expression"),
+                None,
+            )
+        }),
+        _ => Err(MercError::parse(
+            format!("Cannot convert data expression \"{expr}\" to BDD, expected a feature expression"),
+            None,
+        )),
     }
 }
 
@@ -131,9 +241,12 @@ impl FeatureDiagram {
             )
         });
 
-        let input = BufReader::new(input);
-        let mut line_iter = input.lines();
-        let first_line = line_iter.next().ok_or("Expected variable names line")??;
+        let mut lines = LineIterator::new(input).with_max_line_length(MAX_LINE_LENGTH);
+        lines.advance();
+        let Some(first_line) = lines.get() else {
+            return Err(missing_line(&mut lines, "Expected variable names line"));
+        };
+        lines.check_length()?;
 
         let variable_names: Vec<String> = first_line.split(',').map(|s| s.trim().to_string()).collect();
         let variables = manager_ref.with_manager_exclusive(|manager| -> Result<Vec<BDDFunction>, MercError> {
@@ -146,8 +259,15 @@ impl FeatureDiagram {
 
         let variables = HashMap::from_iter(variable_names.into_iter().zip(variables));
 
-        let second_line = line_iter.next().ok_or("Expected initial configuration line")??;
-        let initial_configuration = data_expr_to_bdd(manager_ref, &variables, &DataExpr::parse(&second_line)?)?;
+        lines.advance();
+        let Some(second_line) = lines.get() else {
+            return Err(missing_line(&mut lines, "Expected initial configuration line"));
+        };
+        lines.check_length()?;
+
+        let initial_configuration = DataExpr::parse(second_line)
+            .and_then(|expression| data_expr_to_bdd(manager_ref, &variables, &expression))
+            .map_err(|error| error.with_span(lines.span()))?;
 
         Ok(Self {
             features: variables,
@@ -166,6 +286,14 @@ impl FeatureDiagram {
     }
 }
 
+/// Returns the error that ended the input, or otherwise a parse error with the given message.
+fn missing_line(lines: &mut LineIterator<impl Read>, message: &'static str) -> MercError {
+    match lines.take_error() {
+        Some(error) => error.into(),
+        None => MercError::parse(message, None),
+    }
+}
+
 impl fmt::Debug for FeatureDiagram {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "variables = {:?}", self.features.keys())
@@ -208,6 +336,168 @@ impl FeatureTransitionSystem {
     pub fn features(&self) -> &HashMap<String, BDDFunction> {
         &self.features
     }
+
+    /// Computes the synchronous parallel composition of this and the other
+    /// feature transition system.
+    ///
+    /// # Details
+    ///
+    /// A transition with a synchronized action is taken by both systems at once,
+    /// and is enabled for the conjunction of both feature expressions. Such
+    /// transitions are omitted when the conjunction is unsatisfiable. All other
+    /// transitions are interleaved and keep their feature expression. If
+    /// `synchronized_actions` is `None`, then all common action names (except
+    /// tau) are synchronized, as in [merc_lts::product_lts]. Both systems must
+    /// be defined over the same features.
+    pub fn parallel_composition(
+        &self,
+        manager_ref: &BDDManagerRef,
+        other: &FeatureTransitionSystem,
+        synchronized_actions: Option<Vec<String>>,
+    ) -> Result<FeatureTransitionSystem, MercError> {
+        if self.features.len() != other.features.len()
+            || self
+                .features
+                .iter()
+                .any(|(name, feature)| other.features.get(name) != Some(feature))
+        {
+            return Err(MercError::unsupported(
+                "Cannot compose feature transition systems that are defined over different features",
+            ));
+        }
+
+        let left_actions = feature_actions(self)?;
+        let right_actions = feature_actions(other)?;
+
+        let synchronized_actions = synchronized_actions.unwrap_or_else(|| {
+            // Tau has no action name, so it is never synchronized.
+            left_actions
+                .iter()
+                .filter_map(|(action, _)| action.clone())
+                .filter(|action| right_actions.iter().any(|(other, _)| other.as_ref() == Some(action)))
+                .collect()
+        });
+        let is_synchronized = |action: &Option<String>| {
+            action
+                .as_ref()
+                .is_some_and(|action| synchronized_actions.contains(action))
+        };
+
+        // The feature expression of every label in the composition.
+        let mut feature_labels: HashMap<String, BDDFunction> = HashMap::new();
+
+        // For the composition we do not know the number of states and transitions in advance.
+        let mut lts_builder = LtsBuilderFast::new(Vec::new(), Vec::new());
+
+        let mut discovered_states: IndexedSet<(StateIndex, StateIndex)> = IndexedSet::new();
+        let mut working = vec![(self.initial_state_index(), other.initial_state_index())];
+        let (_, _) = discovered_states.insert((self.initial_state_index(), other.initial_state_index()));
+
+        while let Some((left_state, right_state)) = working.pop() {
+            let (product_index, inserted) = discovered_states.insert((left_state, right_state));
+            debug_assert!(!inserted, "The product state must have already been added");
+
+            trace!("Considering ({left_state}, {right_state})");
+
+            let mut successors = Vec::new();
+            for left_transition in self.outgoing_transitions(left_state) {
+                let (action, left_expression) = &left_actions[*left_transition.label];
+
+                if is_synchronized(action) {
+                    for right_transition in other.outgoing_transitions(right_state) {
+                        let (right_action, right_expression) = &right_actions[*right_transition.label];
+
+                        if action == right_action {
+                            let feature = self
+                                .feature_label(left_transition.label)
+                                .and(other.feature_label(right_transition.label))?;
+
+                            if feature.satisfiable() {
+                                successors.push((
+                                    synchronized_label(
+                                        action.as_deref().unwrap_or_default(),
+                                        left_expression,
+                                        right_expression,
+                                    ),
+                                    feature,
+                                    (left_transition.to, right_transition.to),
+                                ));
+                            }
+                        }
+                    }
+                } else {
+                    successors.push((
+                        self.labels()[*left_transition.label].clone(),
+                        self.feature_label(left_transition.label).clone(),
+                        (left_transition.to, right_state),
+                    ));
+                }
+            }
+
+            for right_transition in other.outgoing_transitions(right_state) {
+                if !is_synchronized(&right_actions[*right_transition.label].0) {
+                    successors.push((
+                        other.labels()[*right_transition.label].clone(),
+                        other.feature_label(right_transition.label).clone(),
+                        (left_state, right_transition.to),
+                    ));
+                }
+            }
+
+            for (label, feature, (left_to, right_to)) in successors {
+                let (to_index, inserted) = discovered_states.insert((left_to, right_to));
+                lts_builder.add_transition(StateIndex::new(*product_index), &label, StateIndex::new(*to_index));
+                feature_labels.insert(label, feature);
+
+                if inserted {
+                    trace!("Adding ({left_to}, {right_to})");
+                    working.push((left_to, right_to));
+                }
+            }
+        }
+
+        if lts_builder.num_of_states() == 0 {
+            // The composition has no transitions, but an LTS requires at least one state (the initial state).
+            lts_builder.require_num_of_states(1);
+        }
+
+        let lts = lts_builder.finish(StateIndex::new(0), true);
+
+        // Labels without transitions, such as tau, are always enabled.
+        let true_bdd = manager_ref.with_manager_shared(|manager| BDDFunction::t(manager));
+        let feature_labels = lts
+            .labels()
+            .iter()
+            .map(|label| feature_labels.get(label).cloned().unwrap_or_else(|| true_bdd.clone()))
+            .collect();
+
+        Ok(FeatureTransitionSystem::new(lts, feature_labels, self.features.clone()))
+    }
+}
+
+/// Returns the action name, which is `None` for tau, and the textual feature
+/// expression, if any, of every label of the given feature transition system.
+fn feature_actions(fts: &FeatureTransitionSystem) -> Result<Vec<(Option<String>, Option<String>)>, MercError> {
+    fts.labels()
+        .iter()
+        .map(|label| {
+            let action = MultiAction::parse(label)?;
+            Ok(action.actions.first().map_or((None, None), |action| {
+                (Some(action.id.clone()), action.args.first().map(|arg| arg.to_string()))
+            }))
+        })
+        .collect()
+}
+
+/// Returns the label of a synchronized transition for the given action, whose
+/// feature expression is the conjunction of both feature expressions. This
+/// keeps the composition readable by [read_fts].
+fn synchronized_label(action: &str, left: &Option<String>, right: &Option<String>) -> String {
+    match (left, right) {
+        (Some(left), Some(right)) => format!("{action}(({left}) && ({right}))"),
+        (Some(expression), None) | (None, Some(expression)) => format!("{action}({expression})"),
+        (None, None) => action.to_string(),
+    }
 }
 
 impl LTS for FeatureTransitionSystem {
@@ -255,4 +545,73 @@ mod tests {
         )
         .unwrap();
     }
+
+    #[merc_test]
+    #[cfg_attr(miri, ignore)] // Oxidd does not support miri (specifically the crossbeam-epoch dependency)
+    fn test_parallel_composition() {
+        let manager_ref = oxidd::bdd::new_manager(2048, 1024, 1);
+
+        let feature_diagram = FeatureDiagram::from_reader(&manager_ref, "a, b\ntt".as_bytes()).unwrap();
+        let features = feature_diagram.features();
+
+        let left = read_fts(
+            &manager_ref,
+            "des (0,2,2)\n(0,\"go(node(a, tt, ff))\",1)\n(1,\"left\",1)\n".as_bytes(),
+            features.clone(),
+        )
+        .unwrap();
+        let right = read_fts(
+            &manager_ref,
+            "des (0,2,2)\n(0,\"go(node(b, tt, ff))\",1)\n(1,\"stop\",0)\n".as_bytes(),
+            features.clone(),
+        )
+        .unwrap();
+
+        let composition = left.parallel_composition(&manager_ref, &right, None).unwrap();
+
+        // Both systems synchronise on go, after which left and stop are interleaved.
+        assert_eq!(composition.num_of_states(), 3);
+        assert_eq!(composition.num_of_transitions(), 4);
+
+        let go = composition
+            .labels()
+            .iter()
+            .position(|label| label.starts_with("go"))
+            .expect("The composition should contain the synchronized go action");
+        assert!(
+            composition.feature_label(LabelIndex::new(go)) == &features["a"].and(&features["b"]).unwrap(),
+            "The synchronized action should be enabled for the conjunction of both features"
+        );
+
+        // The label of the synchronized action can be read back as a feature transition system.
+        assert!(
+            parse_feature_expression(&manager_ref, features, "(node(a, tt, ff)) && (node(b, tt, ff))").unwrap()
+                == features["a"].and(&features["b"]).unwrap()
+        );
+    }
+
+    #[merc_test]
+    #[cfg_attr(miri, ignore)] // Oxidd does not support miri (specifically the crossbeam-epoch dependency)
+    fn test_read_simplified_feature_diagram() {
+        let manager_ref = oxidd::bdd::new_manager(2048, 1024, 1);
+
+        let feature_diagram = FeatureDiagram::from_reader(
+            &manager_ref,
+            "a, b\nnode(a, tt, ff) && (node(b, tt, ff) || 1 < 2)".as_bytes(),
+        )
+        .unwrap();
+        assert!(feature_diagram.configuration() == &feature_diagram.features()["a"]);
+    }
+
+    #[merc_test]
+    #[cfg_attr(miri, ignore)] // Oxidd does not support miri (specifically the crossbeam-epoch dependency)
+    fn test_read_malformed_feature_diagram() {
+        for input in ["a, b\nfoo(a)", "a, b\nnode(a, tt)", "a, b\nnode(c, tt, ff)"] {
+            let manager_ref = oxidd::bdd::new_manager(2048, 1024, 1);
+            assert!(
+                FeatureDiagram::from_reader(&manager_ref, input.as_bytes()).is_err(),
+                "Expected {input} to be rejected"
+            );
+        }
+    }
 }
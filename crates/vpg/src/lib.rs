@@ -1,24 +1,48 @@
 #![doc = include_str!("../README.md")]
 #![forbid(unsafe_code)]
 
+#[cfg(feature = "variability")]
+mod config_backend;
+#[cfg(feature = "variability")]
 mod feature_transition_system;
+mod mean_payoff;
 mod modal_equation_system;
 mod parity_games;
+#[cfg(feature = "variability")]
 mod project;
 mod reachability;
 mod repeat;
+mod solution;
+#[cfg(feature = "variability")]
 mod submap;
+#[cfg(feature = "variability")]
 mod translate;
+mod translate_lts;
+#[cfg(feature = "variability")]
 mod variability_zielonka;
+#[cfg(feature = "variability")]
+mod verify_solution;
 mod zielonka;
 
+#[cfg(feature = "variability")]
+pub use config_backend::*;
+#[cfg(feature = "variability")]
 pub use feature_transition_system::*;
+pub use mean_payoff::*;
 pub use modal_equation_system::*;
 pub use parity_games::*;
+#[cfg(feature = "variability")]
 pub use project::*;
 pub use reachability::*;
 pub use repeat::*;
+pub use solution::*;
+#[cfg(feature = "variability")]
 pub use submap::*;
+#[cfg(feature = "variability")]
 pub use translate::*;
+pub use translate_lts::*;
+#[cfg(feature = "variability")]
 pub use variability_zielonka::*;
+#[cfg(feature = "variability")]
+pub use verify_solution::*;
 pub use zielonka::*;
@@ -0,0 +1,208 @@
+//! Authors: Maurice Laveaux and Sjef van Loo
+//!
+//! Implements value iteration based solvers for the quantitative objectives
+//! of a [`crate::WeightedParityGame`].
+
+use std::collections::VecDeque;
+
+use bitvec::bitvec;
+use bitvec::order::Lsb0;
+use log::debug;
+
+use merc_utilities::MercError;
+use merc_utilities::check_cancelled;
+
+use crate::PG;
+use crate::Player;
+use crate::Predecessors;
+use crate::Weight;
+use crate::WeightedParityGame;
+
+/// Solves the energy game, where [Player::Even] must keep the sum of the
+/// weights, starting from an initial credit, non-negative forever.
+///
+/// Returns for every vertex the minimal initial credit that [Player::Even]
+/// needs, or `None` when no finite credit suffices, in which case
+/// [Player::Odd] wins the vertex.
+///
+/// # Details
+///
+/// This is the progress measure lifting algorithm by Brim et al., which
+/// repeatedly lifts the credit of a vertex to the minimum (for
+/// [Player::Even]) or maximum (for [Player::Odd]) over its outgoing edges (v,
+/// w, u) of `max(0, credit(u) - w)`. A finite minimal credit never exceeds
+/// `(n - 1) * W`, where `n` is the number of vertices and `W` the largest
+/// absolute weight, so larger credits are replaced by top.
+///
+/// Returns an error when the computation was cancelled, see [`merc_utilities::CancellationToken`].
+pub fn solve_energy(game: &WeightedParityGame) -> Result<Vec<Option<Weight>>, MercError> {
+    debug_assert!(game.is_total(), "Energy solver requires a total game");
+
+    let num_of_vertices = game.num_of_vertices();
+    let top = (num_of_vertices.saturating_sub(1) as Weight) * game.max_absolute_weight() + 1;
+    let predecessors = Predecessors::new(game.parity_game());
+
+    let mut credit: Vec<Weight> = vec![0; num_of_vertices];
+    let mut queue: VecDeque<_> = game.iter_vertices().collect();
+    let mut queued = bitvec![usize, Lsb0; 1; num_of_vertices];
+    let mut num_of_lifts = 0;
+
+    while let Some(v) = queue.pop_front() {
+        check_cancelled()?;
+        queued.set(*v, false);
+
+        let costs = game.outgoing_weighted_edges(v).map(|(u, weight)| {
+            if credit[*u] == top {
+                top
+            } else {
+                (credit[*u] - weight).clamp(0, top)
+            }
+        });
+
+        let lifted = if game.owner(v) == Player::Even {
+            costs.min()
+        } else {
+            costs.max()
+        }
+        .expect("Every vertex has an outgoing edge");
+
+        // The lifting is monotone, so the credit can only increase.
+        if lifted > credit[*v] {
+            credit[*v] = lifted;
+            num_of_lifts += 1;
+
+            for u in predecessors.predecessors(v) {
+                if !queued[*u] {
+                    queued.set(*u, true);
+                    queue.push_back(u);
+                }
+            }
+        }
+    }
+
+    debug!("Performed {num_of_lifts} lifts");
+    Ok(credit
+        .into_iter()
+        .map(|credit| if credit == top { None } else { Some(credit) })
+        .collect())
+}
+
+/// Approximates the mean-payoff value of every vertex, which is the limit
+/// average of the weights that [Player::Even] can guarantee while
+/// [Player::Odd] tries to minimise it, using the given number of iterations.
+///
+/// # Details
+///
+/// This is the value iteration by Zwick and Paterson, which computes the
+/// optimal total weight of the plays of length `k` for every vertex. The
+/// returned values are these totals divided by `k`, which differ at most `2 *
+/// n * W / k` from the actual values, where `n` is the number of vertices and
+/// `W` the largest absolute weight. Since the actual values are rationals with
+/// a denominator of at most `n`, they are determined uniquely by the
+/// approximation after [mean_payoff_iterations] iterations.
+///
+/// Returns an error when the number of iterations is zero, or when the
+/// computation was cancelled, see [`merc_utilities::CancellationToken`].
+pub fn solve_mean_payoff(game: &WeightedParityGame, iterations: usize) -> Result<Vec<f64>, MercError> {
+    debug_assert!(game.is_total(), "Mean-payoff solver requires a total game");
+    if iterations == 0 {
+        return Err("The number of iterations must be positive".into());
+    }
+
+    let mut values: Vec<Weight> = vec![0; game.num_of_vertices()];
+    let mut next_values: Vec<Weight> = vec![0; game.num_of_vertices()];
+
+    for _ in 0..iterations {
+        check_cancelled()?;
+
+        for v in game.iter_vertices() {
+            let totals = game.outgoing_weighted_edges(v).map(|(u, weight)| weight + values[*u]);
+
+            next_values[*v] = if game.owner(v) == Player::Even {
+                totals.max()
+            } else {
+                totals.min()
+            }
+            .expect("Every vertex has an outgoing edge");
+        }
+
+        std::mem::swap(&mut values, &mut next_values);
+    }
+
+    Ok(values
+        .into_iter()
+        .map(|value| value as f64 / iterations as f64)
+        .collect())
+}
+
+/// Returns the number of iterations `4 * n^3 * W` after which
+/// [solve_mean_payoff] determines the exact mean-payoff values, where `n` is
+/// the number of vertices and `W` the largest absolute weight.
+pub fn mean_payoff_iterations(game: &WeightedParityGame) -> usize {
+    let num_of_vertices = game.num_of_vertices();
+    (4 * num_of_vertices.pow(3) * game.max_absolute_weight() as usize).max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use merc_utilities::random_test;
+
+    use crate::Priority;
+    use crate::VertexIndex;
+    use crate::random_weighted_parity_game;
+
+    use super::*;
+
+    /// Returns the game where v0 (even) chooses between a loop with weight -1
+    /// and moving to v1 with weight -2, v1 (odd) only has a loop with weight 1,
+    /// and v2 (odd) chooses between a loop with weight -1 and moving to v1.
+    fn example() -> WeightedParityGame {
+        WeightedParityGame::from_edges(
+            VertexIndex::new(0),
+            vec![Player::Even, Player::Odd, Player::Odd],
+            vec![Priority::new(0); 3],
+            || {
+                [(0, -1, 0), (0, -2, 1), (1, 1, 1), (2, -1, 2), (2, 0, 1)]
+                    .into_iter()
+                    .map(|(from, weight, to)| (VertexIndex::new(from), weight, VertexIndex::new(to)))
+            },
+        )
+    }
+
+    #[test]
+    fn test_solve_energy() {
+        assert_eq!(solve_energy(&example()).unwrap(), vec![Some(2), Some(0), None]);
+    }
+
+    #[test]
+    fn test_solve_mean_payoff() {
+        let game = example();
+        let values = solve_mean_payoff(&game, mean_payoff_iterations(&game)).unwrap();
+
+        for (value, expected) in values.into_iter().zip([1.0, 1.0, -1.0]) {
+            assert!((value - expected).abs() < 0.1, "Expected {expected}, but got {value}");
+        }
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)] // Very slow under Miri
+    fn test_random_energy_mean_payoff() {
+        random_test(100, |rng| {
+            let game = random_weighted_parity_game(rng, 10, 5, 3, 4);
+            let credits = solve_energy(&game).unwrap();
+            let values = solve_mean_payoff(&game, mean_payoff_iterations(&game)).unwrap();
+
+            // Even wins the energy game iff the mean-payoff value is non-negative, and negative values are at most -1/n.
+            let threshold = -1.0 / (2.0 * game.num_of_vertices() as f64);
+            for v in game.iter_vertices() {
+                assert_eq!(
+                    credits[*v].is_some(),
+                    values[*v] > threshold,
+                    "Vertex {v} has credit {:?}, but mean-payoff value {}",
+                    credits[*v],
+                    values[*v]
+                );
+            }
+        })
+    }
+}
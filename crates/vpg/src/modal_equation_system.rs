@@ -53,7 +53,8 @@ impl From<Equation> for StateFrm {
 }
 
 impl ModalEquationSystem {
-    /// Converts a plain state formula into a fixpoint equation system.
+    /// Converts a plain state formula into a fixpoint equation system, which is
+    /// empty when the formula contains no fixpoints.
     pub fn new(formula: &StateFrm) -> Self {
         let mut equations = Vec::new();
 
@@ -68,11 +69,6 @@ impl ModalEquationSystem {
             "Duplicate variable names found in fixpoint equation system"
         );
 
-        debug_assert!(
-            !equations.is_empty(),
-            "At least one fixpoint equation expected in the equation system"
-        );
-
         ModalEquationSystem { equations }
     }
 
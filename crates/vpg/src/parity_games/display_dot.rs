@@ -1,9 +1,11 @@
 use std::fmt;
 
+#[cfg(feature = "variability")]
 use merc_symbolic::FormatConfigSet;
 
 use crate::PG;
 use crate::Player;
+#[cfg(feature = "variability")]
 use crate::VariabilityParityGame;
 
 /// Display implementation output a parity game in Graphviz DOT format.
@@ -41,10 +43,12 @@ impl<'a, G: PG> fmt::Display for PgDot<'a, G> {
 }
 
 /// Display implementation output a variability parity game in Graphviz DOT format.
+#[cfg(feature = "variability")]
 pub struct VpgDot<'a> {
     pub game: &'a VariabilityParityGame,
 }
 
+#[cfg(feature = "variability")]
 impl<'a> VpgDot<'a> {
     /// Creates a new PgDot Display for the given parity game.
     pub fn new(game: &'a VariabilityParityGame) -> Self {
@@ -52,6 +56,7 @@ impl<'a> VpgDot<'a> {
     }
 }
 
+#[cfg(feature = "variability")]
 impl<'a> fmt::Display for VpgDot<'a> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write_dot_header(f)?;
@@ -0,0 +1,85 @@
+use std::io::Write;
+
+use merc_io::NodeShape;
+use merc_io::RenderGraph;
+#[cfg(feature = "variability")]
+use merc_symbolic::FormatConfigSet;
+use merc_utilities::MercError;
+
+use crate::PG;
+use crate::Player;
+#[cfg(feature = "variability")]
+use crate::VariabilityParityGame;
+
+/// Writes the parity game as an SVG image using the built-in layout of
+/// [RenderGraph], where the vertices of [Player::Even] are diamonds and those of
+/// [Player::Odd] are squares, similar to [crate::PgDot].
+///
+/// Returns an error when the game has more than `max_vertices` vertices.
+pub fn write_pg_svg<G: PG>(writer: &mut impl Write, game: &G, max_vertices: usize) -> Result<(), MercError> {
+    let mut graph = render_vertices(game);
+    for v in game.iter_vertices() {
+        for to in game.outgoing_edges(v) {
+            graph.add_edge(*v, *to, None);
+        }
+    }
+
+    graph.write_svg(writer, max_vertices)
+}
+
+/// Writes the variability parity game as an SVG image like [write_pg_svg],
+/// where the edges are labelled by their configurations.
+#[cfg(feature = "variability")]
+pub fn write_vpg_svg(
+    writer: &mut impl Write,
+    game: &VariabilityParityGame,
+    max_vertices: usize,
+) -> Result<(), MercError> {
+    let mut graph = render_vertices(game);
+    for v in game.iter_vertices() {
+        for edge in game.outgoing_conf_edges(v) {
+            graph.add_edge(*v, *edge.to(), Some(FormatConfigSet(edge.configuration()).to_string()));
+        }
+    }
+
+    graph.write_svg(writer, max_vertices)
+}
+
+/// Returns the graph with a node for every vertex of the game, in the same order.
+fn render_vertices<G: PG>(game: &G) -> RenderGraph {
+    let mut graph = RenderGraph::new();
+    for v in game.iter_vertices() {
+        let shape = match game.owner(v) {
+            Player::Even => NodeShape::Diamond,
+            Player::Odd => NodeShape::Square,
+        };
+
+        graph.add_node(game.priority(v).to_string(), Some(format!("v{v}")), shape);
+    }
+
+    graph.set_initial(*game.initial_vertex());
+    graph
+}
+
+#[cfg(test)]
+mod tests {
+    use merc_io::DEFAULT_SVG_MAX_NODES;
+    use merc_utilities::random_test;
+
+    use crate::random_parity_game;
+
+    use super::*;
+
+    #[test]
+    fn test_write_pg_svg() {
+        random_test(10, |rng| {
+            let game = random_parity_game(rng, true, 10, 3, 3);
+
+            let mut output = Vec::new();
+            write_pg_svg(&mut output, &game, DEFAULT_SVG_MAX_NODES).unwrap();
+
+            let output = String::from_utf8(output).unwrap();
+            assert!(output.contains(">v9</text>"));
+        })
+    }
+}
@@ -10,7 +10,9 @@ use streaming_iterator::StreamingIterator;
 use thiserror::Error;
 
 use merc_io::LineIterator;
-use merc_io::TimeProgress;
+use merc_io::MAX_LINE_LENGTH;
+use merc_io::Progress;
+use merc_io::capacity_hint;
 use merc_utilities::MercError;
 
 use crate::PG;
@@ -39,55 +41,86 @@ pub enum IOError {
 pub fn read_pg(reader: impl Read) -> Result<ParityGame, MercError> {
     info!("Reading parity game in .pg format...");
 
-    let mut lines = LineIterator::new(reader);
+    let mut lines = LineIterator::new(reader).with_max_line_length(MAX_LINE_LENGTH);
     lines.advance();
     let header = lines
         .get()
-        .ok_or(IOError::InvalidHeader("The first line should be the header"))?;
+        .ok_or_else(|| MercError::parse(IOError::InvalidHeader("The first line should be the header"), None))?;
+    lines.check_length()?;
 
     // Read the header
     let header_regex = Regex::new(r#"parity\s+([0-9]+)\s*;"#).expect("Regex compilation should not fail");
 
     let (_, [num_of_vertices_txt]) = header_regex
         .captures(header)
-        .ok_or(IOError::InvalidHeader("does not match parity <num_of_vertices>;"))?
+        .ok_or_else(|| {
+            MercError::parse(
+                IOError::InvalidHeader("does not match parity <num_of_vertices>;"),
+                Some(lines.span()),
+            )
+        })?
         .extract();
 
-    let num_of_vertices: usize = num_of_vertices_txt.parse()?;
-    let progress = TimeProgress::new(
-        |(amount, total): (usize, usize)| info!("Read {} vertices ({}%)...", amount, amount * 100 / total),
-        1,
-    );
+    let num_of_vertices: usize = num_of_vertices_txt
+        .parse()
+        .map_err(|error| MercError::from(error).with_span(lines.span()))?;
+    let progress = Progress::new("Reading parity game", "vertices", Some(num_of_vertices));
 
     // Collect that data into the parity game structure
-    let mut owner: Vec<Player> = vec![Player::Even; num_of_vertices];
-    let mut priority: Vec<Priority> = vec![Priority::new(0); num_of_vertices];
+    let mut owner: Vec<Player> = Vec::with_capacity(capacity_hint(num_of_vertices));
+    let mut priority: Vec<Priority> = Vec::with_capacity(capacity_hint(num_of_vertices));
 
-    let mut vertices: Vec<usize> = Vec::with_capacity(num_of_vertices + 1);
-    let mut transitions_to: Vec<VertexIndex> = Vec::with_capacity(num_of_vertices);
+    let mut vertices: Vec<usize> = Vec::with_capacity(capacity_hint(num_of_vertices + 1));
+    let mut transitions_to: Vec<VertexIndex> = Vec::with_capacity(capacity_hint(num_of_vertices));
 
     let mut vertex_count = 0;
-    while let Some(line) = lines.next() {
+    loop {
+        lines.advance();
+        let Some(line) = lines.get() else {
+            break;
+        };
+        let span = lines.span();
+        lines.check_length()?;
+
+        let line_error = |error: IOError| MercError::parse(error, Some(span));
+        let parse_error = |error: std::num::ParseIntError| MercError::from(error).with_span(span);
+
         // Parse the line: <index> <priority> <owner> <outgoing_vertex>, <outgoing_vertex>, ...;
         let mut parts = line.split_whitespace();
 
         let index: usize = parts
             .next()
-            .ok_or(IOError::InvalidLine("Expected at least <index> ...;"))?
-            .parse()?;
+            .ok_or_else(|| line_error(IOError::InvalidLine("Expected at least <index> ...;")))?
+            .parse()
+            .map_err(parse_error)?;
         let vertex_priority: usize = parts
             .next()
-            .ok_or(IOError::InvalidLine("Expected at least <index> <priority> ...;"))?
-            .parse()?;
+            .ok_or_else(|| line_error(IOError::InvalidLine("Expected at least <index> <priority> ...;")))?
+            .parse()
+            .map_err(parse_error)?;
         let vertex_owner: u8 = parts
             .next()
-            .ok_or(IOError::InvalidLine(
-                "Expected at least <index> <priority> <owner> ...;",
-            ))?
-            .parse()?;
+            .ok_or_else(|| {
+                line_error(IOError::InvalidLine(
+                    "Expected at least <index> <priority> <owner> ...;",
+                ))
+            })?
+            .parse()
+            .map_err(parse_error)?;
+
+        if index != vertex_count || index >= num_of_vertices {
+            return Err(MercError::parse(
+                format!("Expected vertex {vertex_count}, but found {index}; the vertices must be listed in order"),
+                Some(span),
+            ));
+        }
 
-        owner[index] = Player::from_index(vertex_owner);
-        priority[index] = Priority::new(vertex_priority);
+        if vertex_owner > 1 {
+            return Err(line_error(IOError::InvalidLine("The owner must be either 0 or 1")));
+        }
+
+        owner.push(Player::from_index(vertex_owner));
+        priority.push(Priority::new(vertex_priority));
 
         // Store the offset for the vertex
         vertices.push(transitions_to.len());
@@ -100,13 +133,32 @@ pub fn read_pg(reader: impl Read) -> Result<ParityGame, MercError> {
                 .filter(|s| !s.trim().is_empty())
                 .map(|s| s.trim().parse())
             {
-                let successor = successor?;
+                let successor = successor.map_err(parse_error)?;
+                if successor >= num_of_vertices {
+                    return Err(MercError::parse(
+                        format!("Successor {successor} exceeds the number of vertices {num_of_vertices}"),
+                        Some(span),
+                    ));
+                }
                 transitions_to.push(VertexIndex::new(successor));
             }
         }
 
-        progress.print((vertex_count + 1, num_of_vertices));
         vertex_count += 1;
+        progress.print(vertex_count);
+    }
+    progress.finish(vertex_count);
+
+    // A read error ends the lines early, which should not be reported as missing vertices.
+    if let Some(error) = lines.take_error() {
+        return Err(error.into());
+    }
+
+    if vertex_count != num_of_vertices {
+        return Err(MercError::parse(
+            format!("The header states {num_of_vertices} vertices, but {vertex_count} vertices are listed"),
+            None,
+        ));
     }
 
     // Add the sentinel state.
@@ -125,10 +177,7 @@ pub fn read_pg(reader: impl Read) -> Result<ParityGame, MercError> {
 pub fn write_pg(mut writer: impl Write, game: &ParityGame) -> Result<(), MercError> {
     info!("Writing parity game to .pg format...");
 
-    let progress = TimeProgress::new(
-        |(index, total): (usize, usize)| info!("Wrote {} vertices ({}%)...", index, index * 100 / total),
-        1,
-    );
+    let progress = Progress::new("Writing parity game", "vertices", Some(game.num_of_vertices()));
 
     writeln!(writer, "parity {};", game.num_of_vertices())?;
     for v in game.iter_vertices() {
@@ -138,8 +187,9 @@ pub fn write_pg(mut writer: impl Write, game: &ParityGame) -> Result<(), MercErr
         write!(writer, "{} {} {} ", v.value(), prio.value(), owner)?;
         write!(writer, "{}", game.outgoing_edges(v).map(|to| to.value()).format(", "))?;
         writeln!(writer, ";")?;
-        progress.print((v.value() + 1, game.num_of_vertices()));
+        progress.print(v.value() + 1);
     }
+    progress.finish(game.num_of_vertices());
 
     Ok(())
 }
@@ -155,4 +205,17 @@ mod tests {
         assert_eq!(parity_game.num_of_vertices(), 3002);
         assert_eq!(parity_game.num_of_edges(), 3968);
     }
+
+    #[test]
+    fn test_read_pg_malformed() {
+        for input in [
+            "parity 2;\n0 1 2 1;\n1 0 0 0;",
+            "parity 2;\n1 1 0 0;\n0 0 0 1;",
+            "parity 2;\n0 1 0 5;\n1 0 0 0;",
+            "parity 3;\n0 1 0 1;\n1 0 0 0;",
+            "parity 1;\n0 1 0 0;\n1 0 0 0;",
+        ] {
+            assert!(read_pg(input.as_bytes()).is_err(), "Expected {input} to be rejected");
+        }
+    }
 }
@@ -1,8 +1,10 @@
 //! Authors: Maurice Laveaux and Sjef van Loo
 
+use std::collections::HashMap;
 use std::io::BufWriter;
 use std::io::Read;
 use std::io::Write;
+use std::rc::Rc;
 
 use itertools::Itertools;
 use log::info;
@@ -15,18 +17,26 @@ use regex::Regex;
 use streaming_iterator::StreamingIterator;
 
 use merc_io::LineIterator;
-use merc_io::TimeProgress;
+use merc_io::MAX_LINE_LENGTH;
+use merc_io::Progress;
+use merc_io::capacity_hint;
 use merc_symbolic::FormatConfigSet;
-use merc_symbolic::minus;
 use merc_utilities::MercError;
+use merc_utilities::Span;
 
+use crate::BddBackend;
+use crate::ConfigBackend;
+use crate::Formula;
 use crate::IOError;
 use crate::PG;
 use crate::ParityGame;
 use crate::Player;
 use crate::Priority;
+use crate::SatBackend;
 use crate::VariabilityParityGame;
 use crate::VertexIndex;
+use crate::feature_names;
+use crate::parse_feature_expression_backend;
 
 /// Reads a variability parity game in an extended PGSolver `.vpg` format from the given reader.
 /// Note that the reader is buffered internally using a `BufReader`.
@@ -38,7 +48,12 @@ use crate::VertexIndex;
 /// parity <num_of_vertices>;
 /// `<index> <priority> <owner> <outgoing_vertex>,<outgoing_vertex>,...;`
 /// Each outgoing edge is represented as `<to>|<configuration_set>`. For the
-/// format of the configuration set see [parse_configuration_set]
+/// format of the configuration set see [parse_configuration_set].
+///
+/// Alternatively, the configurations in the header and on the edges can be
+/// feature expressions such as `confs a && !(b || c);`, as used by FTS tools,
+/// see [parse_feature_expression]. In that case the variables are the
+/// features in the order in which they occur in the header.
 pub fn read_vpg(manager: &BDDManagerRef, reader: impl Read) -> Result<VariabilityParityGame, MercError> {
     info!("Reading variability parity game in .vpg format...");
 
@@ -50,99 +65,218 @@ pub fn read_vpg(manager: &BDDManagerRef, reader: impl Read) -> Result<Variabilit
         )
     });
 
-    let mut lines = LineIterator::new(reader);
+    let mut lines = LineIterator::new(reader).with_max_line_length(MAX_LINE_LENGTH);
+    let header = read_configuration_header(&mut lines)?;
+    let variables = manager.with_manager_exclusive(|manager| -> Result<Vec<BDDFunction>, MercError> {
+        let variables = match &header.features {
+            Some(names) => manager.add_named_vars(names.iter()).map_err(|e| format!("{}", e))?,
+            None => manager.add_vars(header.num_of_variables as u32),
+        };
+
+        Ok(variables
+            .map(|i| BDDFunction::var(manager, i))
+            .collect::<Result<Vec<_>, _>>()?)
+    })?;
+
+    read_vpg_backend(&BddBackend::new(manager, &variables), &header, lines)
+}
+
+/// Reads a variability parity game in the `.vpg` format, see [read_vpg], where
+/// the configurations are represented by propositional formulas. Returns the
+/// [SatBackend] for these formulas together with the game.
+///
+/// # Details
+///
+/// The configurations are converted directly into formulas, so no BDDs are
+/// constructed. This is useful for feature models with many features.
+pub fn read_vpg_sat(reader: impl Read) -> Result<(SatBackend, VariabilityParityGame<Rc<Formula>>), MercError> {
+    info!("Reading variability parity game in .vpg format...");
+
+    let mut lines = LineIterator::new(reader).with_max_line_length(MAX_LINE_LENGTH);
+    let header = read_configuration_header(&mut lines)?;
+    let backend = SatBackend::new(header.num_of_variables);
+    let game = read_vpg_backend(&backend, &header, lines)?;
+    Ok((backend, game))
+}
+
+/// Reads the first line of a `.vpg` file, which is the confs header.
+fn read_configuration_header(lines: &mut LineIterator<impl Read>) -> Result<ConfigurationHeader, MercError> {
     lines.advance();
-    let header = lines
-        .get()
-        .ok_or(IOError::InvalidHeader("The first line should be the confs header"))?;
+    let header = lines.get().ok_or_else(|| {
+        MercError::parse(
+            IOError::InvalidHeader("The first line should be the confs header"),
+            None,
+        )
+    })?;
+    lines.check_length()?;
 
     // Read the confs <configurations> line
-    let confs_regex = Regex::new(r#"confs\s+([+-01]*)\s*;"#).expect("Regex compilation should not fail");
+    let confs_regex = Regex::new(r#"confs\s+([^;]*?)\s*;"#).expect("Regex compilation should not fail");
     let (_, [configurations_txt]) = confs_regex
         .captures(header)
-        .ok_or(IOError::InvalidHeader("header does not match confs <configurations>;"))?
+        .ok_or_else(|| {
+            MercError::parse(
+                IOError::InvalidHeader("header does not match confs <configurations>;"),
+                Some(lines.span()),
+            )
+        })?
         .extract();
-    let (variables, configurations) = parse_configuration(manager, configurations_txt)?;
+
+    parse_configuration(configurations_txt).map_err(|error| error.with_span(lines.span()))
+}
+
+/// Reads the remainder of a `.vpg` file after the confs header, where the
+/// configurations are represented by the given backend.
+fn read_vpg_backend<B: ConfigBackend>(
+    backend: &B,
+    header: &ConfigurationHeader,
+    mut lines: LineIterator<impl Read>,
+) -> Result<VariabilityParityGame<B::Config>, MercError> {
+    // The features are numbered in the order of the variables.
+    let features: Option<HashMap<String, usize>> = header.features.as_ref().map(|names| {
+        names
+            .iter()
+            .cloned()
+            .enumerate()
+            .map(|(index, name)| (name, index))
+            .collect()
+    });
+    let parse_config = |text: &str| match &features {
+        Some(features) => parse_feature_expression_backend(backend, features, text),
+        None => parse_configuration_set_backend(backend, text),
+    };
+
+    let configurations = parse_config(&header.configuration).map_err(|error| error.with_span(Span::line(1)))?;
+    let variables = (0..header.num_of_variables)
+        .map(|index| backend.variable(index))
+        .collect::<Result<Vec<_>, _>>()?;
 
     // Read the parity header
     let header_regex = Regex::new(r#"parity\s+([0-9]+)\s*;"#).expect("Regex compilation should not fail");
-    let header = lines
-        .next()
-        .ok_or(IOError::InvalidHeader("The second line should be the parity header"))?;
+    lines.advance();
+    let header = lines.get().ok_or_else(|| {
+        MercError::parse(
+            IOError::InvalidHeader("The second line should be the parity header"),
+            None,
+        )
+    })?;
+    lines.check_length()?;
 
     let (_, [num_of_vertices_txt]) = header_regex
         .captures(header)
-        .ok_or(IOError::InvalidHeader(
-            "header does not match parity <num_of_vertices>;",
-        ))?
+        .ok_or_else(|| {
+            MercError::parse(
+                IOError::InvalidHeader("header does not match parity <num_of_vertices>;"),
+                Some(lines.span()),
+            )
+        })?
         .extract();
 
-    let num_of_vertices: usize = num_of_vertices_txt.parse()?;
+    let num_of_vertices: usize = num_of_vertices_txt
+        .parse()
+        .map_err(|error| MercError::from(error).with_span(lines.span()))?;
 
     // Collect that data into the parity game structure
-    let mut owner: Vec<Player> = vec![Player::Even; num_of_vertices];
-    let mut priority: Vec<Priority> = vec![Priority::new(0); num_of_vertices];
+    let mut owner: Vec<Player> = Vec::with_capacity(capacity_hint(num_of_vertices));
+    let mut priority: Vec<Priority> = Vec::with_capacity(capacity_hint(num_of_vertices));
 
-    let mut vertices: Vec<usize> = Vec::with_capacity(num_of_vertices + 1);
-    let mut edges_to: Vec<VertexIndex> = Vec::with_capacity(num_of_vertices);
-    let mut edges_configuration: Vec<BDDFunction> = Vec::with_capacity(num_of_vertices);
+    let mut vertices: Vec<usize> = Vec::with_capacity(capacity_hint(num_of_vertices + 1));
+    let mut edges_to: Vec<VertexIndex> = Vec::with_capacity(capacity_hint(num_of_vertices));
+    let mut edges_configuration: Vec<B::Config> = Vec::with_capacity(capacity_hint(num_of_vertices));
 
     // Print progress messages
-    let progress = TimeProgress::new(
-        |(amount, total): (usize, usize)| info!("Read {} vertices ({}%)...", amount, amount * 100 / total),
-        1,
-    );
+    let progress = Progress::new("Reading variability parity game", "vertices", Some(num_of_vertices));
     let mut vertex_count = 0;
-    while let Some(line) = lines.next() {
+    loop {
+        lines.advance();
+        let Some(line) = lines.get() else {
+            break;
+        };
+        let span = lines.span();
+        lines.check_length()?;
+
+        let line_error = |error: IOError| MercError::parse(error, Some(span));
+        let parse_error = |error: std::num::ParseIntError| MercError::from(error).with_span(span);
+
         // Parse the line: <index> <priority> <owner> <outgoing_vertex>, <outgoing_vertex>, ...;
-        let mut parts = line.split_whitespace();
-
-        let index: usize = parts
-            .next()
-            .ok_or(IOError::InvalidLine("Expected at least <index> ...;"))?
-            .parse()?;
-        let vertex_priority: usize = parts
-            .next()
-            .ok_or(IOError::InvalidLine("Expected at least <index> <priority> ...;"))?
-            .parse()?;
-        let vertex_owner = Player::from_index(
-            parts
-                .next()
-                .ok_or(IOError::InvalidLine(
-                    "Expected at least <index> <priority> <owner> ...;",
-                ))?
-                .parse()?,
-        );
+        let (index, rest) = split_word(line);
+        let (vertex_priority, rest) = split_word(rest);
+        let (vertex_owner, successors) = split_word(rest);
+
+        if index.is_empty() {
+            return Err(line_error(IOError::InvalidLine("Expected at least <index> ...;")));
+        }
+        if vertex_priority.is_empty() {
+            return Err(line_error(IOError::InvalidLine(
+                "Expected at least <index> <priority> ...;",
+            )));
+        }
+        if vertex_owner.is_empty() {
+            return Err(line_error(IOError::InvalidLine(
+                "Expected at least <index> <priority> <owner> ...;",
+            )));
+        }
+
+        let index: usize = index.parse().map_err(parse_error)?;
+        let vertex_priority: usize = vertex_priority.parse().map_err(parse_error)?;
+        let vertex_owner: u8 = vertex_owner.parse().map_err(parse_error)?;
+
+        if index != vertex_count || index >= num_of_vertices {
+            return Err(MercError::parse(
+                format!("Expected vertex {vertex_count}, but found {index}; the vertices must be listed in order"),
+                Some(span),
+            ));
+        }
 
-        owner[index] = vertex_owner;
-        priority[index] = Priority::new(vertex_priority);
+        if vertex_owner > 1 {
+            return Err(line_error(IOError::InvalidLine("The owner must be either 0 or 1")));
+        }
+
+        owner.push(Player::from_index(vertex_owner));
+        priority.push(Priority::new(vertex_priority));
 
         // Store the offset for the vertex
         vertices.push(edges_configuration.len());
 
-        for successors in parts {
-            // Parse successors (remaining parts, removing trailing semicolon)
-            for successor in successors
-                .trim_end_matches(';')
-                .split(',')
-                .filter(|s| !s.trim().is_empty())
-            {
-                let parts: Vec<&str> = successor.trim().split('|').collect();
-                let successor_index: usize = parts[0].trim().parse()?;
-                edges_to.push(VertexIndex::new(successor_index));
-
-                if parts.len() > 1 {
-                    let config = parse_configuration_set(manager, &variables, parts[1].trim())?;
-                    edges_configuration.push(config);
-                } else {
-                    // No configuration specified, use true (all configurations)
-                    edges_configuration.push(manager.with_manager_shared(|m| BDDFunction::t(m)));
-                }
+        // Parse successors, removing the trailing semicolon
+        for successor in split_successors(successors.trim().trim_end_matches(';')) {
+            let (to, config) = match successor.split_once('|') {
+                Some((to, config)) => (to, Some(config.trim())),
+                None => (successor, None),
+            };
+
+            let successor_index: usize = to.trim().parse().map_err(parse_error)?;
+            if successor_index >= num_of_vertices {
+                return Err(MercError::parse(
+                    format!("Successor {successor_index} exceeds the number of vertices {num_of_vertices}"),
+                    Some(span),
+                ));
+            }
+            edges_to.push(VertexIndex::new(successor_index));
+
+            if let Some(config) = config {
+                edges_configuration.push(parse_config(config).map_err(|error| error.with_span(span))?);
+            } else {
+                // No configuration specified, use true (all configurations)
+                edges_configuration.push(backend.top()?);
             }
         }
 
-        progress.print((vertex_count + 1, num_of_vertices));
         vertex_count += 1;
+        progress.print(vertex_count);
+    }
+    progress.finish(vertex_count);
+
+    // A read error ends the lines early, which should not be reported as missing vertices.
+    if let Some(error) = lines.take_error() {
+        return Err(error.into());
+    }
+
+    if vertex_count != num_of_vertices {
+        return Err(MercError::parse(
+            format!("The header states {num_of_vertices} vertices, but {vertex_count} vertices are listed"),
+            None,
+        ));
     }
 
     // Add the sentinel state.
@@ -156,22 +290,66 @@ pub fn read_vpg(manager: &BDDManagerRef, reader: impl Read) -> Result<Variabilit
     ))
 }
 
-/// Parses a configuration set from a string representation into a BDD function, but also creates the necessary variables.
-/// based on the length of the configurations.
-fn parse_configuration(manager: &BDDManagerRef, config: &str) -> Result<(Vec<BDDFunction>, BDDFunction), MercError> {
+/// The configurations of the `confs` header of a `.vpg` file.
+struct ConfigurationHeader {
+    /// The text of the configurations.
+    configuration: String,
+
+    /// The names of the features in the order of their variables, when the configurations are feature expressions.
+    features: Option<Vec<String>>,
+
+    num_of_variables: usize,
+}
+
+/// Determines the variables of the configurations in the confs header, which
+/// are based on the length of the configurations, or on the features when the
+/// configuration is a feature expression.
+fn parse_configuration(config: &str) -> Result<ConfigurationHeader, MercError> {
+    if !config.is_empty() && !config.chars().all(|c| matches!(c, '+' | '-' | '0' | '1')) {
+        let names = feature_names(config)?;
+        return Ok(ConfigurationHeader {
+            configuration: config.to_string(),
+            num_of_variables: names.len(),
+            features: Some(names),
+        });
+    }
+
     if let Some(first_part) = config.split('+').next() {
-        let variables = manager.with_manager_exclusive(|manager| {
-            manager
-                .add_vars(first_part.len() as u32)
-                .map(|i| BDDFunction::var(manager, i))
-                .collect::<Result<Vec<_>, _>>()
-        })?;
-
-        let configuration = parse_configuration_set(manager, &variables, config)?;
-        return Ok((variables.to_vec(), configuration));
+        return Ok(ConfigurationHeader {
+            configuration: config.to_string(),
+            features: None,
+            num_of_variables: first_part.len(),
+        });
     };
 
-    Err(MercError::from(IOError::InvalidHeader("Empty configuration string")))
+    Err(MercError::parse(
+        IOError::InvalidHeader("Empty configuration string"),
+        None,
+    ))
+}
+
+/// Splits off the first whitespace separated word of the text, and returns it together with the remainder.
+fn split_word(text: &str) -> (&str, &str) {
+    let text = text.trim_start();
+    match text.find(char::is_whitespace) {
+        Some(index) => text.split_at(index),
+        None => (text, ""),
+    }
+}
+
+/// Splits the successors at the commas that are not nested in the parentheses of a feature expression.
+fn split_successors(text: &str) -> impl Iterator<Item = &str> {
+    let mut depth = 0usize;
+    text.split(move |c: char| {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+        c == ',' && depth == 0
+    })
+    .map(str::trim)
+    .filter(|successor| !successor.is_empty())
 }
 
 /// Parses a configuration from a string representation into a BDD function.
@@ -190,31 +368,44 @@ pub fn parse_configuration_set(
     variables: &[BDDFunction],
     config: &str,
 ) -> Result<BDDFunction, MercError> {
-    manager_ref.with_manager_shared(|manager| -> Result<BDDFunction, MercError> {
-        let mut result = BDDFunction::f(manager);
-
-        for part in config.split('+') {
-            let mut conjunction = BDDFunction::t(manager);
-
-            for (i, c) in part.chars().enumerate() {
-                let var = &variables[i];
-                match c {
-                    '1' => conjunction = conjunction.and(var)?,
-                    '0' => conjunction = minus(&conjunction, var)?,
-                    '-' => {} // don't care
-                    _ => {
-                        return Err(MercError::from(IOError::InvalidHeader(
-                            "Invalid character in configuration",
-                        )));
-                    }
+    parse_configuration_set_backend(&BddBackend::new(manager_ref, variables), config)
+}
+
+/// Parses a configuration, see [parse_configuration_set], into a set of configurations of the given backend.
+pub fn parse_configuration_set_backend<B: ConfigBackend>(backend: &B, config: &str) -> Result<B::Config, MercError> {
+    let mut result = backend.bottom()?;
+
+    for part in config.split('+') {
+        let mut conjunction = backend.top()?;
+
+        if part.len() != backend.num_of_variables() {
+            return Err(MercError::parse(
+                format!(
+                    "Configuration {part} should consist of {} characters, one for every variable",
+                    backend.num_of_variables()
+                ),
+                None,
+            ));
+        }
+
+        for (i, c) in part.chars().enumerate() {
+            match c {
+                '1' => conjunction = backend.and(&conjunction, &backend.variable(i)?)?,
+                '0' => conjunction = backend.minus(&conjunction, &backend.variable(i)?)?,
+                '-' => {} // don't care
+                _ => {
+                    return Err(MercError::parse(
+                        IOError::InvalidHeader("Invalid character in configuration"),
+                        None,
+                    ));
                 }
             }
-
-            result = result.or(&conjunction)?;
         }
 
-        Ok(result)
-    })
+        result = backend.or(&result, &conjunction)?;
+    }
+
+    Ok(result)
 }
 
 /// Writes the given parity game to the given writer in .vpg format.
@@ -226,10 +417,7 @@ pub fn write_vpg(writer: &mut impl Write, game: &VariabilityParityGame) -> Resul
     writeln!(writer, "confs {};", FormatConfigSet(game.configuration()))?;
     writeln!(writer, "parity {};", game.num_of_vertices())?;
 
-    let progress = TimeProgress::new(
-        |(index, total): (usize, usize)| info!("Wrote {} vertices ({}%)...", index, index * 100 / total),
-        1,
-    );
+    let progress = Progress::new("Writing variability parity game", "vertices", Some(game.num_of_vertices()));
     for v in game.iter_vertices() {
         let prio = game.priority(v);
         let owner = game.owner(v).to_index();
@@ -244,8 +432,9 @@ pub fn write_vpg(writer: &mut impl Write, game: &VariabilityParityGame) -> Resul
         )?;
 
         writeln!(writer, ";")?;
-        progress.print((v.value(), game.num_of_vertices()));
+        progress.print(v.value() + 1);
     }
+    progress.finish(game.num_of_vertices());
 
     Ok(())
 }
@@ -268,4 +457,60 @@ mod tests {
         assert_eq!(parity_game.num_of_vertices(), 3002);
         assert_eq!(parity_game.num_of_edges(), 4409);
     }
+
+    #[test]
+    #[cfg_attr(miri, ignore)] // Oxidd does not work with miri
+    fn test_read_vpg_feature_expressions() {
+        let manager = oxidd::bdd::new_manager(2048, 1024, 1);
+
+        let parity_game = read_vpg(
+            &manager,
+            "confs a && !b;\nparity 2;\n0 0 0 1|a || b, 0;\n1 1 1 1|(a => b) && !b;".as_bytes(),
+        )
+        .unwrap();
+
+        assert_eq!(parity_game.num_of_vertices(), 2);
+        assert_eq!(parity_game.num_of_edges(), 3);
+
+        let features = HashMap::from_iter(
+            ["a".to_string(), "b".to_string()]
+                .into_iter()
+                .zip(parity_game.variables().iter().cloned()),
+        );
+        let expected = parse_feature_expression(&manager, &features, "!(b || !a)").unwrap();
+        assert!(parity_game.configuration() == &expected);
+    }
+
+    #[test]
+    fn test_read_vpg_sat() {
+        let (backend, parity_game) =
+            read_vpg_sat("confs a && !b;\nparity 2;\n0 0 0 1|a || b, 0;\n1 1 1 1|(a => b) && !b;".as_bytes()).unwrap();
+
+        assert_eq!(parity_game.num_of_vertices(), 2);
+        assert_eq!(parity_game.num_of_edges(), 3);
+
+        let configurations: Vec<_> = backend
+            .configurations(parity_game.configuration())
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(configurations, vec![vec![true, false]]);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)] // Oxidd does not work with miri
+    fn test_read_vpg_malformed() {
+        for input in [
+            "confs 1-;\nparity 1;\n0 0 0 0|1;",
+            "confs 1-;\nparity 1;\n0 0 2 0;",
+            "confs 1-;\nparity 1;\n0 0 0 1|1-;",
+            "confs a;\nparity 1;\n0 0 0 0|c;",
+        ] {
+            let manager = oxidd::bdd::new_manager(2048, 1024, 1);
+            assert!(
+                read_vpg(&manager, input.as_bytes()).is_err(),
+                "Expected {input} to be rejected"
+            );
+        }
+    }
 }
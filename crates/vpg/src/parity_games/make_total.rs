@@ -1,11 +1,8 @@
 use merc_utilities::MercError;
-use oxidd::BooleanFunction;
-use oxidd::ManagerRef;
-use oxidd::bdd::BDDFunction;
 use oxidd::bdd::BDDManagerRef;
 
-use merc_symbolic::minus;
-
+use crate::BddBackend;
+use crate::ConfigBackend;
 use crate::PG;
 use crate::Player;
 use crate::Priority;
@@ -17,8 +14,17 @@ pub fn make_vpg_total(
     manager_ref: &BDDManagerRef,
     vpg: &VariabilityParityGame,
 ) -> Result<VariabilityParityGame, MercError> {
+    make_vpg_total_backend(&BddBackend::new(manager_ref, vpg.variables()), vpg)
+}
+
+/// Makes the given variability parity game total by adding edges to true/false
+/// nodes as needed, where the configurations are represented by the given backend.
+pub fn make_vpg_total_backend<B: ConfigBackend>(
+    backend: &B,
+    vpg: &VariabilityParityGame<B::Config>,
+) -> Result<VariabilityParityGame<B::Config>, MercError> {
     // The universe for totality is the game's overall configuration, not global true.
-    let universe = manager_ref.with_manager_shared(|manager| BDDFunction::t(manager));
+    let universe = backend.top()?;
 
     // For a total game we need to potentially add new edges to true/false nodes.
     let mut edges = Vec::new();
@@ -40,18 +46,18 @@ pub fn make_vpg_total(
     edges.push((false_node, universe.clone(), false_node)); // Self-loop on false node
 
     for vertex in vpg.iter_vertices() {
-        let mut all_outgoing = manager_ref.with_manager_shared(|manager| BDDFunction::f(manager));
+        let mut all_outgoing = backend.bottom()?;
         for edge in vpg.outgoing_conf_edges(vertex) {
             // Add a new edge with a random configuration.
             edges.push((vertex, edge.configuration().clone(), edge.to()));
 
             // Keep track of the overall outgoing configuration.
-            all_outgoing = all_outgoing.or(edge.configuration())?;
+            all_outgoing = backend.or(&all_outgoing, edge.configuration())?;
         }
 
         // Missing configurations are those in the universe not covered by any outgoing edge.
-        let missing = minus(&universe, &all_outgoing)?;
-        if missing.satisfiable() {
+        let missing = backend.minus(&universe, &all_outgoing)?;
+        if backend.satisfiable(&missing)? {
             if owners[*vertex] == Player::Even {
                 // Even player: add edge to true node for the remaining configurations.
                 edges.push((vertex, universe.clone(), true_node));
@@ -62,13 +68,12 @@ pub fn make_vpg_total(
         }
     }
 
-    Ok(VariabilityParityGame::from_edges(
-        manager_ref,
+    Ok(VariabilityParityGame::from_config_edges(
         vpg.initial_vertex(),
         owners,
         priorities,
         vpg.configuration().clone(),
         vpg.variables().clone(),
-        || edges.iter().cloned(),
+        edges,
     ))
 }
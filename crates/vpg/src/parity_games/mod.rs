@@ -7,25 +7,37 @@
 #![forbid(unsafe_code)]
 
 mod display_dot;
+mod display_svg;
 mod io;
 mod io_pg;
+#[cfg(feature = "variability")]
 mod io_vpg;
+#[cfg(feature = "variability")]
 mod make_total;
 mod parity_game;
 mod player;
 mod predecessors;
 mod random_game;
+#[cfg(feature = "variability")]
 mod variability_parity_game;
+#[cfg(feature = "variability")]
 mod variability_predecessors;
+mod weighted_parity_game;
 
 pub use display_dot::*;
+pub use display_svg::*;
 pub use io::*;
 pub use io_pg::*;
+#[cfg(feature = "variability")]
 pub use io_vpg::*;
+#[cfg(feature = "variability")]
 pub use make_total::*;
 pub use parity_game::*;
 pub use player::*;
 pub use predecessors::*;
 pub use random_game::*;
+#[cfg(feature = "variability")]
 pub use variability_parity_game::*;
+#[cfg(feature = "variability")]
 pub use variability_predecessors::*;
+pub use weighted_parity_game::*;
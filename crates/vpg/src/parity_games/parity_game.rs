@@ -2,6 +2,7 @@
 use std::fmt;
 
 use itertools::Itertools;
+use rayon::prelude::*;
 
 use merc_utilities::TagIndex;
 
@@ -181,6 +182,80 @@ impl ParityGame {
         }
     }
 
+    /// Constructs a new parity game from a vector of edges, in the same way as
+    /// [ParityGame::from_edges], but where the edges are sorted into the
+    /// compressed sparse row arrays in parallel.
+    ///
+    /// # Details
+    ///
+    /// The edges do not have to be ordered by their source vertex. The successors
+    /// of every vertex keep their relative order, so the result is equal to the
+    /// one of [ParityGame::from_edges].
+    pub fn from_edges_parallel(
+        initial_vertex: VertexIndex,
+        owner: Vec<Player>,
+        mut priority: Vec<Priority>,
+        make_total: bool,
+        mut edges: Vec<(VertexIndex, VertexIndex)>,
+    ) -> Self {
+        let num_of_vertices = owner.len();
+        debug_assert_eq!(
+            priority.len(),
+            num_of_vertices,
+            "Owner and priority vectors should have the same length"
+        );
+        debug_assert!(
+            initial_vertex.value() < num_of_vertices,
+            "Initial vertex index {} out of bounds {num_of_vertices}",
+            initial_vertex.value()
+        );
+        debug_assert!(
+            edges
+                .par_iter()
+                .all(|(from, to)| **from < num_of_vertices && **to < num_of_vertices),
+            "Vertex index out of bounds, num_of_vertices {num_of_vertices}"
+        );
+
+        // Count the number of transitions for every state, including the sentinel state.
+        let mut vertices = vec![0; num_of_vertices + 1];
+        for (from, _) in &edges {
+            vertices[**from] += 1;
+        }
+
+        // If make_total is true, add self-loops for vertices that have no outgoing edges
+        if make_total {
+            for vertex_idx in 0..num_of_vertices {
+                if vertices[vertex_idx] == 0 {
+                    edges.push((VertexIndex::new(vertex_idx), VertexIndex::new(vertex_idx)));
+                    vertices[vertex_idx] = 1;
+
+                    // Change the priority of the vertex such that the self-loop is winning for the opponent.
+                    priority[vertex_idx] = Priority::new(owner[vertex_idx].opponent().to_index());
+                }
+            }
+        }
+
+        // The sort is stable, so the successors of every vertex keep their order.
+        edges.par_sort_by_key(|(from, _)| *from);
+
+        // Sets the offset for every state into the edge arrays, the sentinel state obtains the number of edges.
+        vertices.iter_mut().fold(0, |count, start| {
+            let result = count + *start;
+            *start = count;
+            result
+        });
+
+        let edges_to = edges.into_par_iter().map(|(_, to)| to).collect();
+
+        Self {
+            initial_vertex,
+            owner,
+            priority,
+            vertices,
+            edges_to,
+        }
+    }
+
     /// Returns true iff the parity game is total, checks all vertices have at least one outgoing edge.
     pub fn is_total(&self) -> bool {
         for v in self.iter_vertices() {
@@ -298,10 +373,14 @@ pub trait PG {
 
 #[cfg(test)]
 mod tests {
+    use rand::Rng;
+
     use merc_utilities::random_test;
 
     use crate::random_parity_game;
 
+    use super::*;
+
     #[test]
     fn test_random_parity_game_make_total() {
         random_test(100, |rng| {
@@ -309,4 +388,35 @@ mod tests {
             assert!(game.is_total());
         });
     }
+
+    #[test]
+    fn test_random_parity_game_from_edges_parallel() {
+        random_test(100, |rng| {
+            let num_of_vertices = 50;
+            let owner: Vec<Player> = (0..num_of_vertices)
+                .map(|_| Player::from_index(rng.random_range(0..2)))
+                .collect();
+            let priority: Vec<Priority> = (0..num_of_vertices)
+                .map(|_| Priority::new(rng.random_range(0..10)))
+                .collect();
+            let edges: Vec<(VertexIndex, VertexIndex)> = (0..rng.random_range(0..200))
+                .map(|_| {
+                    (
+                        VertexIndex::new(rng.random_range(0..num_of_vertices)),
+                        VertexIndex::new(rng.random_range(0..num_of_vertices)),
+                    )
+                })
+                .collect();
+
+            let initial_vertex = VertexIndex::new(0);
+            let expected = ParityGame::from_edges(initial_vertex, owner.clone(), priority.clone(), true, || {
+                edges.iter().cloned()
+            });
+            let game = ParityGame::from_edges_parallel(initial_vertex, owner, priority, true, edges);
+
+            assert_eq!(game.vertices(), expected.vertices());
+            assert_eq!(game.edges_to(), expected.edges_to());
+            assert_eq!(game.priorities(), expected.priorities());
+        });
+    }
 }
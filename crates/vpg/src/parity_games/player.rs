@@ -5,6 +5,7 @@ use crate::Priority;
 
 /// The two players in a parity game.
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Player {
     Even,
     Odd,
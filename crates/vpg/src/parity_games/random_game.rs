@@ -1,17 +1,26 @@
+#[cfg(feature = "variability")]
 use oxidd::bdd::BDDFunction;
+#[cfg(feature = "variability")]
 use oxidd::bdd::BDDManagerRef;
 use rand::Rng;
 
+#[cfg(feature = "variability")]
 use merc_symbolic::create_variables;
+#[cfg(feature = "variability")]
 use merc_symbolic::random_bdd;
+#[cfg(feature = "variability")]
 use merc_utilities::MercError;
 
 use crate::PG;
 use crate::ParityGame;
 use crate::Player;
 use crate::Priority;
+#[cfg(feature = "variability")]
 use crate::VariabilityParityGame;
 use crate::VertexIndex;
+use crate::Weight;
+use crate::WeightedParityGame;
+#[cfg(feature = "variability")]
 use crate::make_vpg_total;
 
 /// Creates a random parity game with the given number of vertices, priorities, and outdegree.
@@ -56,7 +65,27 @@ pub fn random_parity_game(
     })
 }
 
+/// Creates a random total parity game with the given number of vertices,
+/// priorities, and outdegree, where every edge has a random weight in the range
+/// [-max_weight, max_weight].
+pub fn random_weighted_parity_game(
+    rng: &mut impl Rng,
+    num_of_vertices: usize,
+    num_of_priorities: usize,
+    outdegree: usize,
+    max_weight: Weight,
+) -> WeightedParityGame {
+    let pg = random_parity_game(rng, true, num_of_vertices, num_of_priorities, outdegree);
+
+    let weights: Vec<Weight> = (0..pg.num_of_edges())
+        .map(|_| rng.random_range(-max_weight..=max_weight))
+        .collect();
+
+    WeightedParityGame::new(pg, weights)
+}
+
 /// Creates a random parity game with the given number of vertices, priorities, and outdegree.
+#[cfg(feature = "variability")]
 pub fn random_variability_parity_game(
     manager_ref: &BDDManagerRef,
     rng: &mut impl Rng,
@@ -95,7 +124,9 @@ mod tests {
 
     use crate::PG;
     use crate::random_parity_game;
+    #[cfg(feature = "variability")]
     use crate::random_variability_parity_game;
+    use crate::random_weighted_parity_game;
 
     #[test]
     fn test_random_parity_game() {
@@ -106,6 +137,16 @@ mod tests {
     }
 
     #[test]
+    fn test_random_weighted_parity_game() {
+        random_test(100, |rng| {
+            let game = random_weighted_parity_game(rng, 10, 5, 3, 4);
+            assert!(game.is_total());
+            assert!(game.max_absolute_weight() <= 4);
+        })
+    }
+
+    #[test]
+    #[cfg(feature = "variability")]
     #[cfg_attr(miri, ignore)] // Oxidd does not work with miri
     fn test_random_variability_parity_game() {
         random_test(100, |rng| {
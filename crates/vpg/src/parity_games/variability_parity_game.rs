@@ -9,9 +9,10 @@ use oxidd::bdd::BDDFunction;
 use oxidd::bdd::BDDManagerRef;
 
 use merc_symbolic::FormatConfigSet;
-use merc_symbolic::minus;
 use merc_utilities::MercError;
 
+use crate::BddBackend;
+use crate::ConfigBackend;
 use crate::PG;
 use crate::ParityGame;
 use crate::Player;
@@ -26,47 +27,46 @@ use crate::VertexIndex;
 ///
 /// This is also a max-priority parity game. There is also a configuration set associated
 /// with the variability parity game, representing the overall configurations.
-pub struct VariabilityParityGame {
+///
+/// The configurations can also be represented by the sets of another
+/// [crate::ConfigBackend], for example the formulas of [crate::SatBackend]
+/// that are read by [crate::read_vpg_sat].
+pub struct VariabilityParityGame<C = BDDFunction> {
     /// The underlying normal parity game.
     game: ParityGame,
 
     /// The overall configurations for the variability parity game.
-    configuration: BDDFunction,
+    configuration: C,
 
     /// The variables used in the configuration BDD.
-    variables: Vec<BDDFunction>,
+    variables: Vec<C>,
 
     /// Every edge has an associated BDD function representing the configurations
     /// in which the edge is enabled.
-    edges_configuration: Vec<BDDFunction>,
+    edges_configuration: Vec<C>,
 }
 
 /// Represents an edge in the parity game along with its configuration BDD.
-pub struct Edge<'a> {
+pub struct Edge<'a, C = BDDFunction> {
     to: VertexIndex,
-    configuration: &'a BDDFunction,
+    configuration: &'a C,
 }
 
-impl<'a> Edge<'a> {
+impl<'a, C> Edge<'a, C> {
     /// Returns the target vertex of the edge.
     pub fn to(&self) -> VertexIndex {
         self.to
     }
 
     /// Returns the configuration BDD associated with the edge.
-    pub fn configuration(&self) -> &BDDFunction {
+    pub fn configuration(&self) -> &'a C {
         self.configuration
     }
 }
 
-impl VariabilityParityGame {
+impl<C: Clone> VariabilityParityGame<C> {
     /// Construct a new variability parity game from an iterator over edges.
-    pub fn new(
-        parity_game: ParityGame,
-        configuration: BDDFunction,
-        variables: Vec<BDDFunction>,
-        edges_configuration: Vec<BDDFunction>,
-    ) -> Self {
+    pub fn new(parity_game: ParityGame, configuration: C, variables: Vec<C>, edges_configuration: Vec<C>) -> Self {
         // Check that the sizes are consistent
         debug_assert_eq!(
             edges_configuration.len(),
@@ -82,6 +82,86 @@ impl VariabilityParityGame {
         }
     }
 
+    /// Constructs a new variability parity game from the given edges, which
+    /// are tuples of the form (from, configuration, to).
+    ///
+    /// The vertices are given by their owner and priority.
+    pub fn from_config_edges(
+        initial_vertex: VertexIndex,
+        owner: Vec<Player>,
+        priority: Vec<Priority>,
+        configuration: C,
+        variables: Vec<C>,
+        mut edges: Vec<(VertexIndex, C, VertexIndex)>,
+    ) -> Self {
+        // The parity game places the outgoing edges of every vertex in the given order.
+        edges.sort_by_key(|(from, _, _)| *from);
+        let game = ParityGame::from_edges(initial_vertex, owner, priority, false, || {
+            edges.iter().map(|(from, _, to)| (*from, *to))
+        });
+
+        let edges_configuration = edges.into_iter().map(|(_, configuration, _)| configuration).collect();
+        Self::new(game, configuration, variables, edges_configuration)
+    }
+
+    /// Returns an iterator over the outgoing edges of the given vertex.
+    pub fn outgoing_conf_edges(&self, state_index: VertexIndex) -> impl Iterator<Item = Edge<'_, C>> + '_ {
+        let start = self.game.vertices()[*state_index];
+        let end = self.game.vertices()[*state_index + 1];
+        self.edges_configuration[start..end]
+            .iter()
+            .zip(self.game.edges_to()[start..end].iter())
+            .map(|(configuration, &to)| Edge { to, configuration })
+    }
+
+    /// Returns true iff the parity game is total, checks all vertices have at
+    /// least one outgoing edge, where the configurations are represented by the given backend.
+    pub fn is_total_backend<B: ConfigBackend<Config = C>>(&self, backend: &B) -> Result<bool, MercError> {
+        // Check that every vertex has at least one outgoing edge.
+        for v in self.iter_vertices() {
+            if self.outgoing_edges(v).next().is_none() {
+                return Ok(false);
+            }
+        }
+
+        // Check that the configurations of the outgoing edges cover the overall configuration.
+        for v in self.iter_vertices() {
+            // Compute the disjunction of all outgoing edge configurations.
+            let covered = self
+                .outgoing_conf_edges(v)
+                .try_fold(backend.bottom()?, |acc, edge| backend.or(&acc, edge.configuration()))?;
+
+            // If there are configurations not covered by the outgoing edges, the game is not total.
+            if backend.satisfiable(&backend.minus(&self.configuration, &covered)?)? {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Returns the overall configuration BDD of the variability parity game.
+    pub fn configuration(&self) -> &C {
+        &self.configuration
+    }
+
+    /// Returns the variables used in the configuration BDD.
+    pub fn variables(&self) -> &Vec<C> {
+        &self.variables
+    }
+
+    /// Returns the owners of the vertices in the variability parity game.
+    pub(crate) fn owners(&self) -> &Vec<Player> {
+        self.game.owners()
+    }
+
+    /// Returns the priorities of the vertices in the variability parity game.
+    pub(crate) fn priorities(&self) -> &Vec<Priority> {
+        self.game.priorities()
+    }
+}
+
+impl VariabilityParityGame {
     /// Constructs a new variability parity game from an iterator over edges.
     ///
     /// The vertices are given by their owner and priority.
@@ -174,64 +254,13 @@ impl VariabilityParityGame {
         }
     }
 
-    /// Returns an iterator over the outgoing edges of the given vertex.
-    pub fn outgoing_conf_edges(&self, state_index: VertexIndex) -> impl Iterator<Item = Edge<'_>> + '_ {
-        let start = self.game.vertices()[*state_index];
-        let end = self.game.vertices()[*state_index + 1];
-        self.edges_configuration[start..end]
-            .iter()
-            .zip(self.game.edges_to()[start..end].iter())
-            .map(|(configuration, &to)| Edge { to, configuration })
-    }
-
     /// Returns true iff the parity game is total, checks all vertices have at least one outgoing edge.
     pub fn is_total(&self, manager_ref: &BDDManagerRef) -> Result<bool, MercError> {
-        // Check that every vertex has at least one outgoing edge.
-        for v in self.iter_vertices() {
-            if self.outgoing_edges(v).next().is_none() {
-                return Ok(false);
-            }
-        }
-
-        // Check that the configurations of the outgoing edges cover the overall configuration.
-        for v in self.iter_vertices() {
-            // Compute the disjunction of all outgoing edge configurations.
-            let covered = self.outgoing_conf_edges(v).try_fold(
-                manager_ref.with_manager_shared(|manager| BooleanFunction::f(manager)),
-                |acc: BDDFunction, edge| acc.or(edge.configuration()),
-            )?;
-
-            // If there are configurations not covered by the outgoing edges, the game is not total.
-            if minus(&self.configuration, &covered)?.satisfiable() {
-                return Ok(false);
-            }
-        }
-
-        Ok(true)
-    }
-
-    /// Returns the overall configuration BDD of the variability parity game.
-    pub fn configuration(&self) -> &BDDFunction {
-        &self.configuration
-    }
-
-    /// Returns the variables used in the configuration BDD.
-    pub fn variables(&self) -> &Vec<BDDFunction> {
-        &self.variables
-    }
-
-    /// Returns the owners of the vertices in the variability parity game.
-    pub(crate) fn owners(&self) -> &Vec<Player> {
-        self.game.owners()
-    }
-
-    /// Returns the priorities of the vertices in the variability parity game.
-    pub(crate) fn priorities(&self) -> &Vec<Priority> {
-        self.game.priorities()
+        self.is_total_backend(&BddBackend::new(manager_ref, &self.variables))
     }
 }
 
-impl PG for VariabilityParityGame {
+impl<C> PG for VariabilityParityGame<C> {
     delegate! {
         to self.game {
             fn initial_vertex(&self) -> VertexIndex;
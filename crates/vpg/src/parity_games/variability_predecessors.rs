@@ -1,28 +1,49 @@
 //! Authors: Maurice Laveaux and Sjef van Loo
 
+use std::cell::OnceCell;
+
 use oxidd::BooleanFunction;
+use oxidd::Function;
 use oxidd::ManagerRef;
 use oxidd::bdd::BDDFunction;
 use oxidd::bdd::BDDManagerRef;
+use oxidd_core::util::EdgeDropGuard;
 
 use merc_collections::ByteCompressedVec;
 use merc_collections::bytevec;
+use merc_utilities::MercError;
 
 use crate::PG;
+use crate::Submap;
 use crate::VariabilityParityGame;
 use crate::VertexIndex;
 
 /// Stores the incoming transitions for a given variability parity game.
+///
+/// # Details
+///
+/// The edges are stored by their target vertex. The outgoing edges of every
+/// vertex refer to the same edges by their position, such that a
+/// [RestrictedPredecessors] view can share its information between both
+/// directions.
 pub struct VariabilityPredecessors {
     edges_from: ByteCompressedVec<VertexIndex>,
+    edges_to: ByteCompressedVec<VertexIndex>,
     edges_configuration: Vec<BDDFunction>,
     vertex_to_predecessors: ByteCompressedVec<usize>,
+
+    /// The positions of the outgoing edges of every vertex, with the offsets given by `vertex_to_successors`.
+    successor_edges: ByteCompressedVec<usize>,
+    vertex_to_successors: ByteCompressedVec<usize>,
 }
 
 impl VariabilityPredecessors {
     /// Creates the predecessors structure for the given parity game.
     pub fn new(manager_ref: &BDDManagerRef, game: &VariabilityParityGame) -> Self {
         let mut edges_from = bytevec![VertexIndex::new(0); game.num_of_edges()];
+        let mut edges_to = bytevec![VertexIndex::new(0); game.num_of_edges()];
+        let mut successor_edges = bytevec![0; game.num_of_edges()];
+        let mut vertex_to_successors = ByteCompressedVec::new();
         let mut edges_configuration =
             manager_ref.with_manager_shared(|manager| vec![BDDFunction::f(manager); game.num_of_edges()]);
         let mut state2incoming = bytevec![0; game.num_of_vertices()];
@@ -41,16 +62,22 @@ impl VariabilityPredecessors {
             new_offset
         });
 
-        // Place the transitions
+        // Place the transitions, and record their positions for the outgoing edges
+        let mut num_of_successors = 0;
         for state_index in game.iter_vertices() {
+            vertex_to_successors.push(num_of_successors);
             for edge in game.outgoing_conf_edges(state_index) {
                 state2incoming.update(*edge.to(), |start| {
                     edges_from.set(*start, state_index);
+                    edges_to.set(*start, edge.to());
                     edges_configuration[*start] = edge.configuration().clone();
+                    successor_edges.set(num_of_successors, *start);
                     *start += 1;
                 });
+                num_of_successors += 1;
             }
         }
+        vertex_to_successors.push(num_of_successors);
 
         state2incoming.fold(0, |previous, start| {
             let result = *start;
@@ -63,8 +90,11 @@ impl VariabilityPredecessors {
 
         Self {
             edges_from,
+            edges_to,
             edges_configuration,
             vertex_to_predecessors: state2incoming,
+            successor_edges,
+            vertex_to_successors,
         }
     }
 
@@ -74,4 +104,94 @@ impl VariabilityPredecessors {
         let end = self.vertex_to_predecessors.index(state_index.value() + 1);
         (start..end).map(move |i| (self.edges_from.index(i), &self.edges_configuration[i]))
     }
+
+    /// Returns a view on the predecessors restricted to the subgame given by `gamma`.
+    pub fn restrict<'a>(&'a self, gamma: &'a Submap) -> RestrictedPredecessors<'a> {
+        RestrictedPredecessors {
+            predecessors: self,
+            gamma,
+            edges_configuration: vec![OnceCell::new(); self.edges_from.len()],
+        }
+    }
+}
+
+/// A view on the [VariabilityPredecessors] restricted to the subgame given by
+/// `gamma`, in which the configuration of every edge (v, w) is gamma(v) ∧
+/// theta(v, w) ∧ gamma(w).
+///
+/// # Details
+///
+/// The restricted configurations are computed on demand and at most once for
+/// every edge, so a view can be shared by the attractor computations on the
+/// same subgame. The configurations of the edges that leave the subgame are
+/// empty.
+pub struct RestrictedPredecessors<'a> {
+    predecessors: &'a VariabilityPredecessors,
+    gamma: &'a Submap,
+    edges_configuration: Vec<OnceCell<BDDFunction>>,
+}
+
+impl<'a> RestrictedPredecessors<'a> {
+    /// Returns the subgame to which this view is restricted.
+    pub fn gamma(&self) -> &'a Submap {
+        self.gamma
+    }
+
+    /// Returns an iterator over the incoming transitions for the given state
+    /// with their restricted configuration.
+    pub fn predecessors<'b, 'id>(
+        &'b self,
+        manager: &'b <BDDFunction as Function>::Manager<'id>,
+        state_index: VertexIndex,
+    ) -> impl Iterator<Item = Result<(VertexIndex, &'b BDDFunction), MercError>> + 'b {
+        let start = self.predecessors.vertex_to_predecessors.index(state_index.value());
+        let end = self.predecessors.vertex_to_predecessors.index(state_index.value() + 1);
+        (start..end).map(move |i| Ok((self.predecessors.edges_from.index(i), self.configuration(manager, i)?)))
+    }
+
+    /// Returns an iterator over the outgoing transitions for the given state
+    /// with their restricted configuration.
+    pub fn successors<'b, 'id>(
+        &'b self,
+        manager: &'b <BDDFunction as Function>::Manager<'id>,
+        state_index: VertexIndex,
+    ) -> impl Iterator<Item = Result<(VertexIndex, &'b BDDFunction), MercError>> + 'b {
+        let start = self.predecessors.vertex_to_successors.index(state_index.value());
+        let end = self.predecessors.vertex_to_successors.index(state_index.value() + 1);
+        (start..end).map(move |j| {
+            let i = self.predecessors.successor_edges.index(j);
+            Ok((self.predecessors.edges_to.index(i), self.configuration(manager, i)?))
+        })
+    }
+
+    /// Returns the restricted configuration of the edge at the given position, computing it when necessary.
+    fn configuration<'id>(
+        &self,
+        manager: &<BDDFunction as Function>::Manager<'id>,
+        i: usize,
+    ) -> Result<&BDDFunction, MercError> {
+        if let Some(configuration) = self.edges_configuration[i].get() {
+            return Ok(configuration);
+        }
+
+        let from = self.predecessors.edges_from.index(i);
+        let to = self.predecessors.edges_to.index(i);
+        let configuration = BDDFunction::from_edge(
+            manager,
+            BDDFunction::and_edge(
+                manager,
+                &EdgeDropGuard::new(
+                    manager,
+                    BDDFunction::and_edge(
+                        manager,
+                        self.gamma[from].as_edge(manager),
+                        self.predecessors.edges_configuration[i].as_edge(manager),
+                    )?,
+                ),
+                self.gamma[to].as_edge(manager),
+            )?,
+        );
+
+        Ok(self.edges_configuration[i].get_or_init(|| configuration))
+    }
 }
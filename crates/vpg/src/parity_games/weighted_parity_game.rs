@@ -0,0 +1,173 @@
+//! Authors: Maurice Laveaux and Sjef van Loo
+
+use std::fmt;
+
+use delegate::delegate;
+use itertools::Itertools;
+
+use crate::PG;
+use crate::ParityGame;
+use crate::Player;
+use crate::Priority;
+use crate::VertexIndex;
+
+/// The type of the integer weights on the edges of a [WeightedParityGame].
+pub type Weight = i64;
+
+/// A weighted parity game is an extension of a parity game where each edge is
+/// associated with an integer weight, which can be used for quantitative
+/// objectives such as mean-payoff and energy objectives.
+///
+/// # Details
+///
+/// The priorities are kept such that the same game can also be solved as a
+/// parity game, but the quantitative solvers, see for example
+/// [crate::solve_energy], only consider the weights. Player [Player::Even]
+/// is the player that wants to maximise the weights.
+pub struct WeightedParityGame {
+    /// The underlying normal parity game.
+    game: ParityGame,
+
+    /// Every edge has an associated weight.
+    weights: Vec<Weight>,
+}
+
+impl WeightedParityGame {
+    /// Constructs a new weighted parity game, where the weights are given in
+    /// the same order as the edges of the parity game.
+    pub fn new(parity_game: ParityGame, weights: Vec<Weight>) -> Self {
+        debug_assert_eq!(
+            weights.len(),
+            parity_game.num_of_edges(),
+            "There should be a weight for every edge"
+        );
+
+        Self {
+            game: parity_game,
+            weights,
+        }
+    }
+
+    /// Constructs a new weighted parity game from an iterator over edges.
+    ///
+    /// The vertices are given by their owner and priority. The `edges` iterator
+    /// should yield tuples of the form (from, weight, to).
+    pub fn from_edges<F, I>(
+        initial_vertex: VertexIndex,
+        owner: Vec<Player>,
+        priority: Vec<Priority>,
+        mut edges: F,
+    ) -> Self
+    where
+        F: FnMut() -> I,
+        I: Iterator<Item = (VertexIndex, Weight, VertexIndex)>,
+    {
+        let game = ParityGame::from_edges(initial_vertex, owner, priority, false, || {
+            edges().map(|(from, _, to)| (from, to))
+        });
+
+        // The edges are placed in the order of the iterator for every vertex, so the weights can be placed in the same way.
+        let mut offsets = game.vertices().clone();
+        let mut weights = vec![0; game.num_of_edges()];
+        for (from, weight, _) in edges() {
+            let start = &mut offsets[*from];
+            weights[*start] = weight;
+            *start += 1;
+        }
+
+        Self { game, weights }
+    }
+
+    /// Returns an iterator over the outgoing edges of the given vertex together with their weights.
+    pub fn outgoing_weighted_edges(
+        &self,
+        state_index: VertexIndex,
+    ) -> impl Iterator<Item = (VertexIndex, Weight)> + '_ {
+        let start = self.game.vertices()[*state_index];
+        let end = self.game.vertices()[*state_index + 1];
+        self.game.edges_to()[start..end]
+            .iter()
+            .cloned()
+            .zip(self.weights[start..end].iter().cloned())
+    }
+
+    /// Returns the largest absolute weight of all edges, or zero when there are no edges.
+    pub fn max_absolute_weight(&self) -> Weight {
+        self.weights.iter().map(|weight| weight.abs()).max().unwrap_or(0)
+    }
+
+    /// Returns true iff the parity game is total, checks all vertices have at least one outgoing edge.
+    pub fn is_total(&self) -> bool {
+        self.game.is_total()
+    }
+
+    /// Returns the underlying parity game.
+    pub fn parity_game(&self) -> &ParityGame {
+        &self.game
+    }
+}
+
+impl PG for WeightedParityGame {
+    delegate! {
+        to self.game {
+            fn initial_vertex(&self) -> VertexIndex;
+            fn num_of_vertices(&self) -> usize;
+            fn num_of_edges(&self) -> usize;
+            fn iter_vertices(&self) -> impl Iterator<Item = VertexIndex> + '_;
+            fn owner(&self, vertex: VertexIndex) -> Player;
+            fn priority(&self, vertex: VertexIndex) -> Priority;
+            fn outgoing_edges(&self, state_index: VertexIndex) -> impl Iterator<Item = VertexIndex> + '_;
+        }
+    }
+}
+
+impl fmt::Display for WeightedParityGame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Weighted Parity Game:")?;
+
+        for v in self.iter_vertices() {
+            writeln!(
+                f,
+                "v{}: {:?}, priority={}, edges=[{}]",
+                v,
+                self.owner(v).to_index(),
+                self.priority(v),
+                self.outgoing_weighted_edges(v)
+                    .map(|(to, weight)| format!("(v{to}, {weight})"))
+                    .format(", ")
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_weighted_parity_game_from_edges() {
+        let game = WeightedParityGame::from_edges(
+            VertexIndex::new(0),
+            vec![Player::Even, Player::Odd],
+            vec![Priority::new(0), Priority::new(1)],
+            || {
+                [(1, -2, 0), (0, 3, 1), (1, 4, 1), (0, -1, 0)]
+                    .into_iter()
+                    .map(|(from, weight, to)| (VertexIndex::new(from), weight, VertexIndex::new(to)))
+            },
+        );
+
+        assert!(game.is_total());
+        assert_eq!(game.max_absolute_weight(), 4);
+        assert_eq!(
+            game.outgoing_weighted_edges(VertexIndex::new(0)).collect::<Vec<_>>(),
+            vec![(VertexIndex::new(1), 3), (VertexIndex::new(0), -1)]
+        );
+        assert_eq!(
+            game.outgoing_weighted_edges(VertexIndex::new(1)).collect::<Vec<_>>(),
+            vec![(VertexIndex::new(0), -2), (VertexIndex::new(1), 4)]
+        );
+    }
+}
@@ -1,11 +1,13 @@
 use merc_utilities::Timing;
 use oxidd::BooleanFunction;
 use oxidd::bdd::BDDFunction;
+use oxidd::bdd::BDDManagerRef;
 use oxidd::util::OptBool;
 
 use merc_symbolic::CubeIterAll;
 use merc_utilities::MercError;
 
+use crate::ConfigBackend;
 use crate::PG;
 use crate::ParityGame;
 use crate::VariabilityParityGame;
@@ -36,6 +38,45 @@ pub fn project_variability_parity_game(
     ))
 }
 
+/// Projects a variability parity game onto a partial configuration, given as a
+/// set of configurations in which some features are fixed, for example `1-0`
+/// as parsed by [crate::parse_configuration_set].
+///
+/// # Details
+///
+/// The result is a variability parity game whose configurations are restricted
+/// to the given set, and where the edges that are not enabled for any of these
+/// configurations are removed. The features that are not fixed remain symbolic,
+/// so the resulting game represents a family of products instead of a single one.
+pub fn project_variability_parity_game_partial(
+    manager_ref: &BDDManagerRef,
+    vpg: &VariabilityParityGame,
+    partial_configuration: &BDDFunction,
+) -> Result<VariabilityParityGame, MercError> {
+    let configuration = vpg.configuration().and(partial_configuration)?;
+
+    let mut edges = Vec::new();
+    for v in vpg.iter_vertices() {
+        for edge in vpg.outgoing_conf_edges(v) {
+            // Only keep the edges that are enabled for some configuration in the projection.
+            let edge_configuration = edge.configuration().and(&configuration)?;
+            if edge_configuration.satisfiable() {
+                edges.push((v, edge_configuration, edge.to()));
+            }
+        }
+    }
+
+    Ok(VariabilityParityGame::from_edges(
+        manager_ref,
+        vpg.initial_vertex(),
+        vpg.owners().clone(),
+        vpg.priorities().clone(),
+        configuration,
+        vpg.variables().clone(),
+        || edges.iter().cloned(),
+    ))
+}
+
 /// Projects all configurations of a variability parity game into standard parity games.
 pub fn project_variability_parity_games_iter<'a>(
     vpg: &'a VariabilityParityGame,
@@ -51,3 +92,145 @@ pub fn project_variability_parity_games_iter<'a>(
         Ok(((cube, bdd, pg), timing))
     })
 }
+
+/// Projects all configurations of a variability parity game into standard
+/// parity games, where the configurations are enumerated and the edges are
+/// selected using the given configuration backend.
+///
+/// # Details
+///
+/// The configurations are enumerated by [ConfigBackend::configurations], and
+/// the configuration of every edge is evaluated under the enumerated
+/// configuration, so no set operations are needed per edge.
+pub fn project_variability_parity_games_backend<'a, B: ConfigBackend>(
+    vpg: &'a VariabilityParityGame<B::Config>,
+    backend: &'a B,
+    timing: &'a Timing,
+) -> impl Iterator<Item = Result<((Vec<OptBool>, ParityGame), &'a Timing), MercError>> + 'a {
+    BackendProjectionIter {
+        vpg,
+        backend,
+        timing,
+        configurations: None,
+        done: false,
+    }
+}
+
+/// The iterator returned by [project_variability_parity_games_backend].
+struct BackendProjectionIter<'a, B: ConfigBackend> {
+    vpg: &'a VariabilityParityGame<B::Config>,
+    backend: &'a B,
+    timing: &'a Timing,
+
+    /// The enumeration of the configurations, which is started on the first use.
+    configurations: Option<B::Configurations>,
+
+    /// Whether all configurations have been projected, or an error occurred.
+    done: bool,
+}
+
+impl<B: ConfigBackend> BackendProjectionIter<'_, B> {
+    /// Returns the projection on the next configuration, or `None` when all configurations have been projected.
+    fn next_projection(&mut self) -> Result<Option<(Vec<OptBool>, ParityGame)>, MercError> {
+        if self.configurations.is_none() {
+            self.configurations = Some(self.backend.configurations(self.vpg.configuration())?);
+        }
+
+        let configurations = self
+            .configurations
+            .as_mut()
+            .expect("The configurations are initialised above");
+        let Some(values) = configurations.next().transpose()? else {
+            return Ok(None);
+        };
+
+        let mut time_proj = self.timing.start("project");
+        let mut edges = Vec::new();
+        for v in self.vpg.iter_vertices() {
+            for edge in self.vpg.outgoing_conf_edges(v) {
+                // Check if the edge is enabled by the product, if so, include it.
+                if self.backend.evaluate(edge.configuration(), &values) {
+                    edges.push((v, edge.to()));
+                }
+            }
+        }
+
+        let pg = ParityGame::from_edges(
+            self.vpg.initial_vertex(),
+            self.vpg.owners().clone(),
+            self.vpg.priorities().clone(),
+            true, // It can be that after removing edges the result is not a total parity game.
+            || edges.iter().cloned(),
+        );
+        time_proj.finish();
+
+        let cube = values
+            .into_iter()
+            .map(|value| if value { OptBool::True } else { OptBool::False })
+            .collect();
+        Ok(Some((cube, pg)))
+    }
+}
+
+impl<'a, B: ConfigBackend> Iterator for BackendProjectionIter<'a, B> {
+    type Item = Result<((Vec<OptBool>, ParityGame), &'a Timing), MercError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        match self.next_projection() {
+            Ok(Some(result)) => Some(Ok((result, self.timing))),
+            Ok(None) => {
+                self.done = true;
+                None
+            }
+            Err(err) => {
+                self.done = true;
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use merc_macros::merc_test;
+    use merc_utilities::random_test;
+
+    use crate::parse_configuration_set;
+    use crate::random_variability_parity_game;
+    use crate::solve_zielonka;
+
+    use super::*;
+
+    #[merc_test]
+    #[cfg_attr(miri, ignore)] // Oxidd does not work with miri
+    fn test_random_project_partial_configuration() {
+        random_test(100, |rng| {
+            let manager_ref = oxidd::bdd::new_manager(2048, 1024, 1);
+            let vpg = random_variability_parity_game(&manager_ref, rng, true, 20, 3, 3, 3).unwrap();
+
+            let partial = parse_configuration_set(&manager_ref, vpg.variables(), "1-0").unwrap();
+            let projection = project_variability_parity_game_partial(&manager_ref, &vpg, &partial).unwrap();
+
+            // Every product of the partial projection is a product of the original game with the same solution.
+            let mut num_of_products = 0;
+            for result in CubeIterAll::new(projection.variables(), projection.configuration()) {
+                let (cube, bdd) = result.unwrap();
+                assert_eq!(cube[0], OptBool::True);
+                assert_eq!(cube[2], OptBool::False);
+
+                let expected = project_variability_parity_game(&vpg, &bdd).unwrap();
+                let actual = project_variability_parity_game(&projection, &bdd).unwrap();
+                assert_eq!(solve_zielonka(&actual).unwrap(), solve_zielonka(&expected).unwrap());
+                num_of_products += 1;
+            }
+
+            let expected_products =
+                CubeIterAll::new(vpg.variables(), &vpg.configuration().and(&partial).unwrap()).count();
+            assert_eq!(num_of_products, expected_products);
+        })
+    }
+}
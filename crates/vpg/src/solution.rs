@@ -0,0 +1,176 @@
+#![forbid(unsafe_code)]
+
+#[cfg(feature = "variability")]
+use merc_symbolic::CubeIterAll;
+#[cfg(feature = "variability")]
+use merc_symbolic::FormatConfig;
+#[cfg(feature = "variability")]
+use merc_utilities::MercError;
+#[cfg(feature = "variability")]
+use oxidd::BooleanFunction;
+#[cfg(feature = "variability")]
+use oxidd::ManagerRef;
+#[cfg(feature = "variability")]
+use oxidd::bdd::BDDFunction;
+#[cfg(feature = "variability")]
+use oxidd::bdd::BDDManagerRef;
+
+use crate::Player;
+use crate::Set;
+#[cfg(feature = "variability")]
+use crate::Submap;
+#[cfg(feature = "variability")]
+use crate::VertexIndex;
+#[cfg(feature = "variability")]
+use crate::parse_configuration_set;
+
+/// The solution of a parity game, given by the vertices won by each player.
+///
+/// # Details
+///
+/// This is a plain representation of the winning sets computed by
+/// [crate::solve_zielonka], which can be (de)serialized when the `serde`
+/// feature is enabled.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ParityGameSolution {
+    /// The vertices won by [Player::Even] and [Player::Odd] respectively, in increasing order.
+    pub winning: [Vec<usize>; 2],
+}
+
+impl ParityGameSolution {
+    /// Converts the winning sets into a solution.
+    pub fn from_sets(sets: &[Set; 2]) -> Self {
+        Self {
+            winning: [sets[0].iter_ones().collect(), sets[1].iter_ones().collect()],
+        }
+    }
+
+    /// Returns the player that wins the given vertex, if the vertex is part of the solution.
+    pub fn winner(&self, vertex: usize) -> Option<Player> {
+        if self.winning[0].binary_search(&vertex).is_ok() {
+            Some(Player::Even)
+        } else if self.winning[1].binary_search(&vertex).is_ok() {
+            Some(Player::Odd)
+        } else {
+            None
+        }
+    }
+}
+
+/// The vertices won by a player for a single product, i.e., feature configuration.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ProductSolution {
+    /// The product, formatted as a configuration of the features.
+    pub product: String,
+    /// The vertices won for this product.
+    pub vertices: Vec<usize>,
+}
+
+/// The solution of a variability parity game, given per product by the vertices won by each player.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VariabilityParityGameSolution {
+    /// The products and vertices won by [Player::Even] and [Player::Odd] respectively.
+    pub winning: [Vec<ProductSolution>; 2],
+}
+
+#[cfg(feature = "variability")]
+impl VariabilityParityGameSolution {
+    /// Converts the winning submaps of both players, as computed by
+    /// [crate::solve_variability_zielonka], into a solution for every product
+    /// of the given configuration over the given variables.
+    pub fn from_submaps(
+        submaps: &[Submap; 2],
+        variables: &Vec<BDDFunction>,
+        configuration: &BDDFunction,
+    ) -> Result<Self, MercError> {
+        let mut winning = [Vec::new(), Vec::new()];
+        for (submap, products) in submaps.iter().zip(winning.iter_mut()) {
+            for entry in CubeIterAll::new(variables, configuration) {
+                let (product, product_function) = entry?;
+
+                products.push(ProductSolution {
+                    product: FormatConfig(&product).to_string(),
+                    vertices: submap
+                        .vertices_for(&product_function)?
+                        .into_iter()
+                        .map(|v| *v)
+                        .collect(),
+                });
+            }
+        }
+
+        Ok(Self { winning })
+    }
+
+    /// Converts the solution back into the winning submaps of both players,
+    /// for a game with the given variables and number of vertices. This is
+    /// the inverse of [Self::from_submaps] on the configurations of the game.
+    pub fn to_submaps(
+        &self,
+        manager_ref: &BDDManagerRef,
+        variables: &[BDDFunction],
+        num_of_vertices: usize,
+    ) -> Result<[Submap; 2], MercError> {
+        let false_bdd = manager_ref.with_manager_shared(|manager| BDDFunction::f(manager));
+        let mut submaps = [
+            Submap::new(manager_ref, false_bdd.clone(), num_of_vertices),
+            Submap::new(manager_ref, false_bdd, num_of_vertices),
+        ];
+
+        for (products, submap) in self.winning.iter().zip(submaps.iter_mut()) {
+            for solution in products {
+                let product = parse_configuration_set(manager_ref, variables, &solution.product)?;
+
+                for v in &solution.vertices {
+                    if *v >= num_of_vertices {
+                        return Err(MercError::format(format!(
+                            "Vertex {v} of product {} does not exist in a game with {num_of_vertices} vertices",
+                            solution.product
+                        )));
+                    }
+
+                    let v = VertexIndex::new(*v);
+                    let configurations = submap[v].or(&product)?;
+                    manager_ref.with_manager_shared(|manager| submap.set(manager, v, configurations));
+                }
+            }
+        }
+
+        Ok(submaps)
+    }
+
+    /// Returns the player that wins the given vertex for the given product, if
+    /// the product and vertex are part of the solution.
+    pub fn winner(&self, product: &str, vertex: usize) -> Option<Player> {
+        for (index, products) in self.winning.iter().enumerate() {
+            if products
+                .iter()
+                .any(|solution| solution.product == product && solution.vertices.binary_search(&vertex).is_ok())
+            {
+                return Some(Player::from_index(index as u8));
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bitvec::bitvec;
+    use bitvec::order::Lsb0;
+
+    use super::*;
+
+    #[test]
+    fn test_parity_game_solution() {
+        let solution = ParityGameSolution::from_sets(&[bitvec![usize, Lsb0; 1, 0, 1], bitvec![usize, Lsb0; 0, 1, 0]]);
+
+        assert_eq!(solution.winning, [vec![0, 2], vec![1]]);
+        assert_eq!(solution.winner(1), Some(Player::Odd));
+        assert_eq!(solution.winner(3), None);
+    }
+}
@@ -14,7 +14,13 @@ use merc_utilities::MercError;
 
 use crate::VertexIndex;
 
-/// A mapping from vertices to configurations.
+/// A mapping from vertices to sets of configurations, which represents a set
+/// of vertices for every configuration. The solvers of variability parity games
+/// return the winning sets of both players as submaps.
+///
+/// The set operations are applied pointwise, for example the union of two
+/// submaps maps every vertex to the union of both sets of configurations. The
+/// vertex set of a single configuration can be obtained by [Submap::vertices_for].
 ///
 /// # Details
 ///
@@ -164,6 +170,31 @@ impl Submap {
         Ok(self)
     }
 
+    /// Computes the intersection between this submap and another submap.
+    pub fn and(mut self, manager_ref: &BDDManagerRef, other: &Submap) -> Result<Submap, MercError> {
+        manager_ref.with_manager_shared(|manager| -> Result<(), MercError> {
+            let f_edge = EdgeDropGuard::new(manager, BDDFunction::f_edge(manager));
+
+            for (i, func) in self.mapping.iter_mut().enumerate() {
+                let func_edge = func.as_edge(manager);
+
+                let was_satisfiable = *func_edge != *f_edge;
+                let new_func = BDDFunction::and_edge(manager, func_edge, other.mapping[i].as_edge(manager))?;
+                let is_satisfiable = new_func != *f_edge;
+
+                *func = BDDFunction::from_edge(manager, new_func);
+
+                if was_satisfiable && !is_satisfiable {
+                    self.non_empty_count -= 1;
+                }
+            }
+
+            Ok(())
+        })?;
+
+        Ok(self)
+    }
+
     /// Computes the intersection between this submap and another function.
     pub fn and_function(
         mut self,
@@ -223,6 +254,69 @@ impl Submap {
         Ok(self)
     }
 
+    /// Returns true iff every vertex is mapped to a subset of the configurations it is mapped to by the other submap.
+    pub fn is_subset(&self, manager_ref: &BDDManagerRef, other: &Submap) -> Result<bool, MercError> {
+        manager_ref.with_manager_shared(|manager| -> Result<bool, MercError> {
+            let f_edge = EdgeDropGuard::new(manager, BDDFunction::f_edge(manager));
+
+            for (i, func) in self.mapping.iter().enumerate() {
+                let difference = EdgeDropGuard::new(
+                    manager,
+                    minus_edge(manager, func.as_edge(manager), other.mapping[i].as_edge(manager))?,
+                );
+
+                if *difference != *f_edge {
+                    return Ok(false);
+                }
+            }
+
+            Ok(true)
+        })
+    }
+
+    /// Returns true iff no vertex shares a configuration with the other submap.
+    pub fn is_disjoint(&self, manager_ref: &BDDManagerRef, other: &Submap) -> Result<bool, MercError> {
+        manager_ref.with_manager_shared(|manager| -> Result<bool, MercError> {
+            let f_edge = EdgeDropGuard::new(manager, BDDFunction::f_edge(manager));
+
+            for (i, func) in self.mapping.iter().enumerate() {
+                let intersection = EdgeDropGuard::new(
+                    manager,
+                    BDDFunction::and_edge(manager, func.as_edge(manager), other.mapping[i].as_edge(manager))?,
+                );
+
+                if *intersection != *f_edge {
+                    return Ok(false);
+                }
+            }
+
+            Ok(true)
+        })
+    }
+
+    /// Returns the union of the configurations of all vertices.
+    pub fn configurations(&self) -> Result<BDDFunction, MercError> {
+        let mut result = self.false_bdd.clone();
+        for func in &self.mapping {
+            result = result.or(func)?;
+        }
+
+        Ok(result)
+    }
+
+    /// Returns the vertices whose configurations intersect the given configuration, which
+    /// is the set of vertices for that configuration when it is a single product.
+    pub fn vertices_for(&self, configuration: &BDDFunction) -> Result<Vec<VertexIndex>, MercError> {
+        let mut vertices = Vec::new();
+        for (v, func) in self.iter() {
+            if func.and(configuration)?.satisfiable() {
+                vertices.push(v);
+            }
+        }
+
+        Ok(vertices)
+    }
+
     /// Returns an iterator over all entries.
     pub fn iter(&self) -> impl Iterator<Item = (VertexIndex, &BDDFunction)> {
         self.mapping
@@ -230,6 +324,16 @@ impl Submap {
             .enumerate()
             .map(|(i, func)| (VertexIndex::new(i), func))
     }
+
+    /// Returns an iterator over the entries whose configuration is satisfiable.
+    pub fn iter_non_empty<'a, 'id: 'a>(
+        &'a self,
+        manager: &'a <BDDFunction as Function>::Manager<'id>,
+    ) -> impl Iterator<Item = (VertexIndex, &'a BDDFunction)> + 'a {
+        let f_edge = self.false_bdd.as_edge(manager);
+
+        self.iter().filter(move |(_, func)| func.as_edge(manager) != f_edge)
+    }
 }
 
 impl Index<VertexIndex> for Submap {
@@ -285,4 +389,44 @@ mod tests {
 
         assert_eq!(submap.non_empty_count, 1);
     }
+
+    #[merc_test]
+    #[cfg_attr(miri, ignore)] // Oxidd does not work with miri
+    fn test_submap_algebra() {
+        let manager_ref = oxidd::bdd::new_manager(2048, 1024, 1);
+        let vars: Vec<BDDFunction> = manager_ref
+            .with_manager_exclusive(|manager| {
+                AllocResult::from_iter(manager.add_vars(2).map(|i| BDDFunction::var(manager, i)))
+            })
+            .expect("Could not create variables");
+
+        let false_bdd = manager_ref.with_manager_shared(|manager| BDDFunction::f(manager));
+        let mut left = Submap::new(&manager_ref, false_bdd.clone(), 2);
+        let mut right = Submap::new(&manager_ref, false_bdd.clone(), 2);
+
+        // left = { 0 -> x0, 1 -> x1 } and right = { 0 -> x0 \/ x1 }
+        manager_ref.with_manager_shared(|manager| {
+            left.set(manager, VertexIndex::new(0), vars[0].clone());
+            left.set(manager, VertexIndex::new(1), vars[1].clone());
+            right.set(manager, VertexIndex::new(0), vars[0].or(&vars[1]).unwrap());
+        });
+
+        assert!(!left.is_subset(&manager_ref, &right).unwrap());
+        assert!(!left.is_disjoint(&manager_ref, &right).unwrap());
+        assert!(left.configurations().unwrap() == vars[0].or(&vars[1]).unwrap());
+
+        let intersection = left.clone().and(&manager_ref, &right).unwrap();
+        assert_eq!(intersection.number_of_non_empty(), 1);
+        assert!(intersection.is_subset(&manager_ref, &left).unwrap());
+        assert!(intersection.is_subset(&manager_ref, &right).unwrap());
+
+        let difference = left.clone().minus(&manager_ref, &right).unwrap();
+        assert!(difference.is_disjoint(&manager_ref, &right).unwrap());
+        let union = difference.or(&manager_ref, &intersection).unwrap();
+        assert!(union.configurations().unwrap() == left.configurations().unwrap());
+
+        // Only vertex 1 contains a configuration in which x1 holds and x0 does not.
+        let product = vars[1].and(&vars[0].not().unwrap()).unwrap();
+        assert_eq!(left.vertices_for(&product).unwrap(), vec![VertexIndex::new(1)]);
+    }
 }
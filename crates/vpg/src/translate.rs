@@ -1,3 +1,4 @@
+use itertools::Itertools;
 use log::debug;
 use log::info;
 use log::trace;
@@ -10,18 +11,16 @@ use merc_collections::IndexedSet;
 use merc_io::TimeProgress;
 use merc_lts::LTS;
 use merc_lts::StateIndex;
-use merc_syntax::ActFrm;
-use merc_syntax::ActFrmBinaryOp;
 use merc_syntax::Action;
 use merc_syntax::FixedPointOperator;
 use merc_syntax::ModalityOperator;
 use merc_syntax::MultiAction;
-use merc_syntax::RegFrm;
 use merc_syntax::StateFrm;
 use merc_syntax::StateFrmOp;
 use merc_utilities::MercError;
 
 use crate::FeatureTransitionSystem;
+use crate::Formula;
 use crate::ModalEquationSystem;
 use crate::Player;
 use crate::Priority;
@@ -29,6 +28,7 @@ use crate::VariabilityParityGame;
 use crate::VertexIndex;
 use crate::compute_reachable;
 use crate::make_vpg_total;
+use crate::match_regular_formula;
 
 /// Translates a feature transition system into a variability parity game.
 pub fn translate(
@@ -58,8 +58,14 @@ pub fn translate(
 
     algorithm.translate(fts.initial_state_index(), 0)?;
 
-    // Convert the feature diagram (with names) to a VPG
-    let variables: Vec<BDDFunction> = fts.features().values().cloned().collect();
+    // Convert the feature diagram (with names) to a VPG, the features are sorted
+    // by name such that the order of the variables does not depend on the hash map.
+    let variables: Vec<BDDFunction> = fts
+        .features()
+        .iter()
+        .sorted_by(|(left, _), (right, _)| left.cmp(right))
+        .map(|(_, variable)| variable.clone())
+        .collect();
 
     let result = VariabilityParityGame::from_edges(
         manager_ref,
@@ -91,13 +97,6 @@ pub fn translate(
     Ok(total_result)
 }
 
-/// Is used to distinguish between StateFrm and Equation vertices in the vertex map.
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
-enum Formula<'a> {
-    StateFrm(&'a StateFrm),
-    Equation(usize),
-}
-
 /// Local struct to keep track of the translation state
 ///
 /// Implements the translation from (s, Ψ) pairs to VPG vertices and edges.
@@ -175,7 +174,7 @@ impl<'a> Translation<'a> {
             self.progress.print(self.vertices.len());
             match formula {
                 Formula::StateFrm(f) => {
-                    self.translate_vertex(s, f, vertex_index);
+                    self.translate_vertex(s, f, vertex_index)?;
                 }
                 Formula::Equation(i) => {
                     self.translate_equation(s, i, vertex_index);
@@ -194,7 +193,12 @@ impl<'a> Translation<'a> {
     /// The `vertex_map` is used to keep track of already translated vertices.
     ///
     /// This function is recursively called for subformulas.
-    pub fn translate_vertex(&mut self, s: StateIndex, formula: &'a StateFrm, vertex_index: VertexIndex) {
+    pub fn translate_vertex(
+        &mut self,
+        s: StateIndex,
+        formula: &'a StateFrm,
+        vertex_index: VertexIndex,
+    ) -> Result<(), MercError> {
         match formula {
             StateFrm::True => {
                 // (s, true) → odd, 0
@@ -225,7 +229,9 @@ impl<'a> Translation<'a> {
                         self.edges.push((vertex_index, self.true_bdd.clone(), s_psi_2));
                     }
                     _ => {
-                        unimplemented!("Cannot translate binary operator in {}", formula);
+                        return Err(MercError::unsupported(format!(
+                            "Cannot translate binary operator in {formula}"
+                        )));
                     }
                 }
             }
@@ -254,7 +260,7 @@ impl<'a> Translation<'a> {
 
                             trace!("Matching action {} against formula {}", action, formula);
 
-                            if match_regular_formula(formula, action) {
+                            if match_regular_formula(formula, action)? {
                                 let s_prime_psi = self.queue_vertex(transition.to, Formula::StateFrm(expr));
 
                                 self.edges.push((
@@ -272,7 +278,7 @@ impl<'a> Translation<'a> {
                         for transition in self.fts.outgoing_transitions(s) {
                             let action = &self.parsed_labels[*transition.label];
 
-                            if match_regular_formula(formula, action) {
+                            if match_regular_formula(formula, action)? {
                                 let s_prime_psi = self.queue_vertex(transition.to, Formula::StateFrm(expr));
 
                                 self.edges.push((
@@ -286,9 +292,11 @@ impl<'a> Translation<'a> {
                 }
             }
             _ => {
-                unimplemented!("Cannot translate formula {}", formula);
+                return Err(MercError::unsupported(format!("Cannot translate formula {formula}")));
             }
         }
+
+        Ok(())
     }
 
     /// Applies the translation to the given (s, equation) vertex.
@@ -345,37 +353,6 @@ fn strip_feature_configuration_from_multi_action(multi_action: &MultiAction) ->
     }
 }
 
-/// Returns true iff the given action matches the regular formula.
-fn match_regular_formula(formula: &RegFrm, action: &MultiAction) -> bool {
-    match formula {
-        RegFrm::Action(action_formula) => match_action_formula(action_formula, action),
-        RegFrm::Choice { lhs, rhs } => match_regular_formula(lhs, action) || match_regular_formula(rhs, action),
-        _ => {
-            unimplemented!("Cannot translate regular formula {}", formula);
-        }
-    }
-}
-
-/// Returns true iff the given action matches the action formula.
-fn match_action_formula(formula: &ActFrm, action: &MultiAction) -> bool {
-    match formula {
-        ActFrm::True => true,
-        ActFrm::False => false,
-        ActFrm::MultAct(expected_action) => expected_action == action,
-        ActFrm::Binary { op, lhs, rhs } => match op {
-            ActFrmBinaryOp::Union => match_action_formula(lhs, action) || match_action_formula(rhs, action),
-            ActFrmBinaryOp::Intersect => match_action_formula(lhs, action) && match_action_formula(rhs, action),
-            _ => {
-                unimplemented!("Cannot translate binary operator {}", formula);
-            }
-        },
-        ActFrm::Negation(expr) => !match_action_formula(expr, action),
-        _ => {
-            unimplemented!("Cannot translate action formula {}", formula);
-        }
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use merc_macros::merc_test;
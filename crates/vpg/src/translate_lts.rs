@@ -0,0 +1,474 @@
+#![forbid(unsafe_code)]
+
+use std::collections::HashMap;
+
+use bitvec::bitvec;
+use bitvec::order::Lsb0;
+use log::debug;
+use log::info;
+
+use merc_collections::IndexedSet;
+use merc_io::TimeProgress;
+use merc_lts::LTS;
+use merc_lts::LabelIndex;
+use merc_lts::LabelledTransitionSystem;
+use merc_lts::LtsBuilderFast;
+use merc_lts::StateIndex;
+use merc_lts::TransitionLabel;
+use merc_syntax::ActFrm;
+use merc_syntax::ActFrmBinaryOp;
+use merc_syntax::FixedPointOperator;
+use merc_syntax::ModalityOperator;
+use merc_syntax::MultiAction;
+use merc_syntax::RegFrm;
+use merc_syntax::StateFrm;
+use merc_syntax::StateFrmOp;
+use merc_syntax::StateVarDecl;
+use merc_utilities::MercError;
+
+use crate::ModalEquationSystem;
+use crate::PG;
+use crate::ParityGame;
+use crate::Player;
+use crate::Priority;
+use crate::VertexIndex;
+use crate::solve_zielonka;
+use crate::solve_zielonka_strategy;
+
+/// Translates a labelled transition system and a modal mu-calculus formula
+/// into a parity game, where [Player::Even] wins the initial vertex iff the
+/// formula holds in the initial state of the LTS.
+///
+/// # Details
+///
+/// This is the variant of [crate::translate] without variability, which means
+/// that the labels are parsed as plain multi-actions, including their
+/// arguments, and the hidden labels are the empty multi-action `tau`. Only the
+/// fixpoints, modalities, conjunctions and disjunctions are supported.
+pub fn translate_lts<L: LTS>(lts: &L, formula: &StateFrm) -> Result<ParityGame, MercError> {
+    let parsed_labels = parse_labels(lts)?;
+    let equation_system = ModalEquationSystem::new(formula);
+    debug!("{}", equation_system);
+
+    let (game, _translation) = translate_lts_impl(lts, &parsed_labels, &equation_system, formula)?;
+    Ok(game)
+}
+
+/// Returns true iff the given formula holds in the initial state of the LTS,
+/// which is decided by solving the parity game obtained by [translate_lts].
+pub fn model_check_lts<L: LTS>(lts: &L, formula: &StateFrm) -> Result<bool, MercError> {
+    let game = translate_lts(lts, formula)?;
+    info!("Parity game has {} vertices", game.num_of_vertices());
+
+    let solution = solve_zielonka(&game)?;
+    Ok(solution[Player::Even.to_index()][0])
+}
+
+/// The result of [model_check_lts_with_evidence].
+pub struct Evidence<Label: TransitionLabel> {
+    /// True iff the formula holds in the initial state of the LTS.
+    pub holds: bool,
+
+    /// The sub-LTS that explains the outcome, which is a witness when the
+    /// formula holds and a counterexample otherwise. The states keep the
+    /// numbering of the original LTS, and unused states have no transitions.
+    pub lts: LabelledTransitionSystem<Label>,
+
+    /// The modal subformulas that hold (for a witness) or are violated (for a
+    /// counterexample) in the states of the evidence.
+    pub annotations: Vec<(StateIndex, String)>,
+}
+
+/// Checks the given formula like [model_check_lts], but additionally extracts
+/// the evidence for the outcome from the winning strategy of the parity game.
+///
+/// # Details
+///
+/// Starting from the initial vertex, the vertices owned by the winner only
+/// follow their winning strategy, whereas all moves of the opponent are
+/// followed. Every modality edge that is visited in this way corresponds to a
+/// transition of the LTS, which together form the evidence.
+pub fn model_check_lts_with_evidence<L: LTS>(lts: &L, formula: &StateFrm) -> Result<Evidence<L::Label>, MercError> {
+    let parsed_labels = parse_labels(lts)?;
+    let equation_system = ModalEquationSystem::new(formula);
+    debug!("{}", equation_system);
+
+    let (game, translation) = translate_lts_impl(lts, &parsed_labels, &equation_system, formula)?;
+    info!("Parity game has {} vertices", game.num_of_vertices());
+
+    let (solution, strategy) = solve_zielonka_strategy(&game)?;
+    let holds = solution[Player::Even.to_index()][0];
+    let winner = if holds { Player::Even } else { Player::Odd };
+
+    let mut builder = LtsBuilderFast::new(lts.labels().into(), Vec::new());
+    let tau_label = L::Label::tau_label();
+    let mut annotations = Vec::new();
+
+    let mut visited = bitvec![usize, Lsb0; 0; game.num_of_vertices()];
+    let mut stack = vec![VertexIndex::new(0)];
+    visited.set(0, true);
+
+    while let Some(v) = stack.pop() {
+        let (s, formula) = &translation.pairs[v];
+        if let Formula::StateFrm(formula) = formula {
+            if matches!(formula, StateFrm::Modality { .. }) {
+                annotations.push((*s, formula.to_string()));
+            }
+        }
+
+        let successors: Vec<VertexIndex> = if game.owner(v) == winner {
+            vec![strategy[v]]
+        } else {
+            game.outgoing_edges(v).collect()
+        };
+
+        for w in successors {
+            if let Some(&label) = translation.edge_labels.get(&(v, w)) {
+                let label = if lts.is_hidden_label(label) {
+                    &tau_label
+                } else {
+                    &lts.labels()[label]
+                };
+                builder.add_transition(*s, label, translation.pairs[w].0);
+            }
+
+            if !visited[*w] {
+                visited.set(*w, true);
+                stack.push(w);
+            }
+        }
+    }
+
+    builder.require_num_of_states(lts.num_of_states());
+    annotations.sort();
+    Ok(Evidence {
+        holds,
+        lts: builder.finish(lts.initial_state_index(), true),
+        annotations,
+    })
+}
+
+/// Parses the labels of the LTS as multi-actions, where the hidden labels
+/// become the empty multi-action.
+fn parse_labels<L: LTS>(lts: &L) -> Result<Vec<MultiAction>, MercError> {
+    lts.labels()
+        .iter()
+        .enumerate()
+        .map(|(index, label)| {
+            if lts.is_hidden_label(LabelIndex::new(index)) {
+                Ok(MultiAction { actions: Vec::new() })
+            } else {
+                MultiAction::parse(&label.to_string())
+            }
+        })
+        .collect()
+}
+
+/// Performs the translation of [translate_lts], but also returns the
+/// translation itself to relate the vertices back to the LTS.
+fn translate_lts_impl<'a, L: LTS>(
+    lts: &'a L,
+    parsed_labels: &'a [MultiAction],
+    equation_system: &'a ModalEquationSystem,
+    formula: &'a StateFrm,
+) -> Result<(ParityGame, LtsTranslation<'a, L>), MercError> {
+    let mut algorithm = LtsTranslation::new(lts, parsed_labels, equation_system);
+    algorithm.translate(lts.initial_state_index(), formula)?;
+
+    let (owners, priorities) = algorithm.vertices.iter().cloned().unzip();
+    let game = ParityGame::from_edges(VertexIndex::new(0), owners, priorities, true, || {
+        algorithm.edges.iter().cloned()
+    });
+    Ok((game, algorithm))
+}
+
+/// Is used to distinguish between StateFrm and Equation vertices in the vertex map.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub(crate) enum Formula<'a> {
+    StateFrm(&'a StateFrm),
+    Equation(usize),
+}
+
+/// Local struct to keep track of the translation state, see the `Translation`
+/// of [crate::translate] for the variability variant.
+struct LtsTranslation<'a, L: LTS> {
+    vertex_map: IndexedSet<(StateIndex, Formula<'a>)>,
+    vertices: Vec<(Player, Priority)>,
+    edges: Vec<(VertexIndex, VertexIndex)>,
+
+    /// The (state, formula) pair of every vertex.
+    pairs: Vec<(StateIndex, Formula<'a>)>,
+
+    /// The label of the first transition that induced a modality edge.
+    edge_labels: HashMap<(VertexIndex, VertexIndex), LabelIndex>,
+
+    // Used for the depth first search.
+    queue: Vec<(StateIndex, Formula<'a>, VertexIndex)>,
+
+    /// The parsed labels of the LTS.
+    parsed_labels: &'a [MultiAction],
+
+    /// The labelled transition system being translated.
+    lts: &'a L,
+
+    /// A reference to the modal equation system being translated.
+    equation_system: &'a ModalEquationSystem,
+
+    /// Use to print progress information.
+    progress: TimeProgress<usize>,
+}
+
+impl<'a, L: LTS> LtsTranslation<'a, L> {
+    /// Creates a new translation instance.
+    fn new(lts: &'a L, parsed_labels: &'a [MultiAction], equation_system: &'a ModalEquationSystem) -> Self {
+        let progress: TimeProgress<usize> = TimeProgress::new(
+            |num_of_vertices: usize| {
+                info!("Translated {} vertices...", num_of_vertices);
+            },
+            1,
+        );
+
+        Self {
+            vertex_map: IndexedSet::new(),
+            vertices: Vec::new(),
+            edges: Vec::new(),
+            pairs: Vec::new(),
+            edge_labels: HashMap::new(),
+            queue: Vec::new(),
+            parsed_labels,
+            lts,
+            equation_system,
+            progress,
+        }
+    }
+
+    /// Perform the actual translation, where the initial vertex is (initial_state, formula).
+    fn translate(&mut self, initial_state: StateIndex, formula: &'a StateFrm) -> Result<(), MercError> {
+        let initial_vertex = self.queue_vertex(initial_state, Formula::StateFrm(formula));
+        debug_assert_eq!(initial_vertex, 0, "The initial vertex should be vertex 0");
+
+        while let Some((s, formula, vertex_index)) = self.queue.pop() {
+            self.progress.print(self.vertices.len());
+            match formula {
+                Formula::StateFrm(f) => self.translate_vertex(s, f, vertex_index)?,
+                Formula::Equation(i) => self.translate_equation(s, i, vertex_index),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Translate a single vertex (s, Ψ) into the parity game vertex and its
+    /// outgoing edges, where vertices without outgoing edges are lost by their
+    /// owner.
+    fn translate_vertex(
+        &mut self,
+        s: StateIndex,
+        formula: &'a StateFrm,
+        vertex_index: VertexIndex,
+    ) -> Result<(), MercError> {
+        match formula {
+            StateFrm::True => {
+                // (s, true) → odd, 0
+                self.vertices[vertex_index] = (Player::Odd, Priority::new(0));
+            }
+            StateFrm::False => {
+                // (s, false) → even, 0
+                self.vertices[vertex_index] = (Player::Even, Priority::new(0));
+            }
+            StateFrm::Binary { op, lhs, rhs } => {
+                let owner = match op {
+                    // (s, Ψ_1 ∧ Ψ_2) → odd, (s, Ψ_1) and (s, Ψ_2), 0
+                    StateFrmOp::Conjunction => Player::Odd,
+                    // (s, Ψ_1 ∨ Ψ_2) → even, (s, Ψ_1) and (s, Ψ_2), 0
+                    StateFrmOp::Disjunction => Player::Even,
+                    _ => {
+                        return Err(MercError::unsupported(format!(
+                            "Cannot translate binary operator in {formula}"
+                        )));
+                    }
+                };
+
+                self.vertices[vertex_index] = (owner, Priority::new(0));
+                let s_psi_1 = self.queue_vertex(s, Formula::StateFrm(lhs));
+                let s_psi_2 = self.queue_vertex(s, Formula::StateFrm(rhs));
+                self.edges.push((vertex_index, s_psi_1));
+                self.edges.push((vertex_index, s_psi_2));
+            }
+            StateFrm::Id(identifier, _)
+            | StateFrm::FixedPoint {
+                variable: StateVarDecl { identifier, .. },
+                ..
+            } => {
+                // A fixpoint is replaced by its variable, see the RHS of the modal equation system.
+                let (i, _equation) = self
+                    .equation_system
+                    .find_equation_by_identifier(identifier)
+                    .ok_or_else(|| format!("Variable {identifier} is not bound by a fixpoint"))?;
+
+                self.vertices[vertex_index] = (Player::Odd, Priority::new(0)); // The priority and owner do not matter here
+                let equation_vertex = self.queue_vertex(s, Formula::Equation(i));
+                self.edges.push((vertex_index, equation_vertex));
+            }
+            StateFrm::Modality {
+                operator,
+                formula,
+                expr,
+            } => {
+                // (s, [a] Ψ) → odd and (s, <a> Ψ) → even, (s', Ψ) for all s' with s -a-> s', 0
+                let owner = match operator {
+                    ModalityOperator::Box => Player::Odd,
+                    ModalityOperator::Diamond => Player::Even,
+                };
+                self.vertices[vertex_index] = (owner, Priority::new(0));
+
+                for transition in self.lts.outgoing_transitions(s) {
+                    if match_regular_formula(formula, &self.parsed_labels[transition.label])? {
+                        let s_prime_psi = self.queue_vertex(transition.to, Formula::StateFrm(expr));
+                        self.edges.push((vertex_index, s_prime_psi));
+                        self.edge_labels
+                            .entry((vertex_index, s_prime_psi))
+                            .or_insert(transition.label);
+                    }
+                }
+            }
+            _ => {
+                return Err(MercError::unsupported(format!("Cannot translate formula {formula}")));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Applies the translation to the given (s, equation) vertex.
+    fn translate_equation(&mut self, s: StateIndex, equation_index: usize, vertex_index: VertexIndex) {
+        let equation = self.equation_system.equation(equation_index);
+        let alternation_depth = self.equation_system.alternation_depth(equation_index);
+        self.vertices[vertex_index] = match equation.operator() {
+            // (s, μ X. Ψ) → odd, (s, Ψ[x := μ X. Ψ]), 2 * floor(AD(Ψ)/2) + 1
+            FixedPointOperator::Least => (Player::Odd, Priority::new(2 * (alternation_depth / 2) + 1)),
+            // (s, ν X. Ψ) → even, (s, Ψ[x := ν X. Ψ]), 2 * floor(AD(Ψ)/2)
+            FixedPointOperator::Greatest => (Player::Even, Priority::new(2 * (alternation_depth / 2))),
+        };
+
+        let s_psi = self.queue_vertex(s, Formula::StateFrm(equation.body()));
+        self.edges.push((vertex_index, s_psi));
+    }
+
+    /// Queues a new pair to be translated, returning its vertex index.
+    fn queue_vertex(&mut self, s: StateIndex, formula: Formula<'a>) -> VertexIndex {
+        let (index, inserted) = self.vertex_map.insert((s, formula.clone()));
+        let vertex_index = VertexIndex::new(*index);
+
+        if inserted {
+            // New vertex, assign placeholder values
+            self.vertices.resize(*vertex_index + 1, (Player::Odd, Priority::new(0)));
+            self.pairs.push((s, formula.clone()));
+            self.queue.push((s, formula, vertex_index));
+        }
+
+        vertex_index
+    }
+}
+
+/// Returns true iff the given action matches the regular formula, or an error
+/// when the regular formula is not supported.
+pub(crate) fn match_regular_formula(formula: &RegFrm, action: &MultiAction) -> Result<bool, MercError> {
+    match formula {
+        RegFrm::Action(action_formula) => match_action_formula(action_formula, action),
+        RegFrm::Choice { lhs, rhs } => Ok(match_regular_formula(lhs, action)? || match_regular_formula(rhs, action)?),
+        _ => Err(MercError::unsupported(format!(
+            "Cannot translate regular formula {formula}"
+        ))),
+    }
+}
+
+/// Returns true iff the given action matches the action formula, or an error
+/// when the action formula is not supported.
+pub(crate) fn match_action_formula(formula: &ActFrm, action: &MultiAction) -> Result<bool, MercError> {
+    match formula {
+        ActFrm::True => Ok(true),
+        ActFrm::False => Ok(false),
+        ActFrm::MultAct(expected_action) => Ok(expected_action == action),
+        ActFrm::Binary { op, lhs, rhs } => match op {
+            ActFrmBinaryOp::Union => Ok(match_action_formula(lhs, action)? || match_action_formula(rhs, action)?),
+            ActFrmBinaryOp::Intersect => Ok(match_action_formula(lhs, action)? && match_action_formula(rhs, action)?),
+            _ => Err(MercError::unsupported(format!(
+                "Cannot translate binary operator in {formula}"
+            ))),
+        },
+        ActFrm::Negation(expr) => Ok(!match_action_formula(expr, action)?),
+        _ => Err(MercError::unsupported(format!(
+            "Cannot translate action formula {formula}"
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use merc_syntax::UntypedStateFrmSpec;
+
+    use super::*;
+
+    /// Returns the LTS 0 -a-> 1, 1 -b-> 0 and 1 -tau-> 2, where state 2 is a deadlock.
+    fn example() -> LabelledTransitionSystem<String> {
+        let mut builder = LtsBuilderFast::new(Vec::new(), Vec::new());
+        builder.add_transition(StateIndex::new(0), "a", StateIndex::new(1));
+        builder.add_transition(StateIndex::new(1), "b", StateIndex::new(0));
+        builder.add_transition(StateIndex::new(1), "i", StateIndex::new(2));
+        builder.finish(StateIndex::new(0), true)
+    }
+
+    fn holds(formula: &str) -> bool {
+        let formula = UntypedStateFrmSpec::parse(formula).unwrap();
+        model_check_lts(&example(), &formula.formula).unwrap()
+    }
+
+    #[test]
+    fn test_model_check_lts() {
+        assert!(holds("<a>true"));
+        assert!(!holds("<b>true"));
+        assert!(holds("[b]false"));
+        assert!(holds("<a><tau>[true]false"));
+
+        // A deadlock is reachable, but not inevitable.
+        assert!(holds("mu X. [true]false || <true>X"));
+        assert!(!holds("mu X. <true>true && [true]X"));
+
+        // There is an infinite path alternating a and b.
+        assert!(holds("nu X. <a><b>X"));
+        assert!(!holds("nu X. <a><tau>X"));
+    }
+
+    #[test]
+    fn test_model_check_lts_unsupported() {
+        let formula = UntypedStateFrmSpec::parse("!<a>true").unwrap();
+        assert!(model_check_lts(&example(), &formula.formula).is_err());
+
+        // Regular formulas other than actions and choices are rejected.
+        let formula = UntypedStateFrmSpec::parse("<a*>true").unwrap();
+        let error = model_check_lts(&example(), &formula.formula).unwrap_err();
+        assert!(error.to_string().contains("Cannot translate regular formula"));
+    }
+
+    #[test]
+    fn test_model_check_lts_with_evidence() {
+        // The witness for a reachable deadlock is the path 0 -a-> 1 -tau-> 2.
+        let formula = UntypedStateFrmSpec::parse("mu X. [true]false || <true>X").unwrap();
+        let evidence = model_check_lts_with_evidence(&example(), &formula.formula).unwrap();
+        assert!(evidence.holds);
+        assert_eq!(evidence.lts.num_of_states(), 3);
+        assert_eq!(evidence.lts.num_of_transitions(), 2);
+
+        // The counterexample for [a][b]false is the path 0 -a-> 1 -b-> 0.
+        let formula = UntypedStateFrmSpec::parse("[a][b]false").unwrap();
+        let evidence = model_check_lts_with_evidence(&example(), &formula.formula).unwrap();
+        assert!(!evidence.holds);
+        assert_eq!(evidence.lts.num_of_transitions(), 2);
+        assert!(
+            evidence
+                .annotations
+                .contains(&(StateIndex::new(0), "[a][b]false".to_string()))
+        );
+    }
+}
@@ -28,21 +28,28 @@ use oxidd_core::util::EdgeDropGuard;
 use merc_symbolic::minus;
 use merc_symbolic::minus_edge;
 use merc_symbolic::FormatConfigSet;
+use merc_utilities::check_cancelled;
 use merc_utilities::MercError;
 use merc_utilities::Timing;
 
 use crate::combine;
 use crate::compute_reachable;
+use crate::project_variability_parity_games_backend;
 use crate::project_variability_parity_games_iter;
 use crate::solve_zielonka;
+use crate::verify_variability_solution;
 use crate::x_and_not_x;
+use crate::ConfigBackend;
+use crate::ParityGame;
 use crate::Player;
 use crate::Priority;
 use crate::Repeat;
+use crate::RestrictedPredecessors;
 use crate::Set;
 use crate::Submap;
 use crate::VariabilityParityGame;
 use crate::VariabilityPredecessors;
+use crate::VerificationMethod;
 use crate::VertexIndex;
 use crate::PG;
 
@@ -70,7 +77,8 @@ pub fn solve_variability_zielonka(
         "Zielonka solver requires a total parity game"
     );
 
-    let mut zielonka = VariabilityZielonkaSolver::new(manager_ref, game, alternative_solving);
+    let predecessors = VariabilityPredecessors::new(manager_ref, game);
+    let mut zielonka = VariabilityZielonkaSolver::new(manager_ref, game, &predecessors, alternative_solving);
 
     // Determine the initial set of vertices V
     let V = Submap::new(
@@ -108,7 +116,12 @@ pub fn solve_variability_zielonka(
         (W0, W1)
     };
 
-    Ok([W0, W1])
+    let solution = [W0, W1];
+    if cfg!(debug_assertions) {
+        verify_variability_solution(manager_ref, game, &solution, VerificationMethod::Symbolic, &Timing::new())?;
+    }
+
+    Ok(solution)
 }
 
 /// Solves the given variability parity game using the product-based Zielonka algorithm.
@@ -117,36 +130,56 @@ pub fn solve_variability_product_zielonka<'a>(
     timing: &'a Timing,
 ) -> impl Iterator<Item = Result<(Vec<OptBool>, BDDFunction, [Set; 2]), MercError>> + 'a {
     project_variability_parity_games_iter(vpg, timing).map(|result| {
-        match result {
-            Ok(((cube, bdd, pg), timing)) => {
-                let mut reachable_time = timing.start("reachable");
-                let (reachable_pg, projection) = compute_reachable(&pg);
-                reachable_time.finish();
-
-                debug!("Solving projection on {}...", FormatConfig(&cube));
-
-                let pg_solution = solve_zielonka(&reachable_pg);
-                let mut new_solution = [
-                    bitvec![usize, Lsb0; 0; vpg.num_of_vertices()],
-                    bitvec![usize, Lsb0; 0; vpg.num_of_vertices()],
-                ];
-                for v in pg.iter_vertices() {
-                    if let Some(proj_v) = projection[*v] {
-                        // Vertex is reachable in the projection, set its solution
-                        if pg_solution[0][proj_v] {
-                            new_solution[0].set(*v, true);
-                        }
-                        if pg_solution[1][proj_v] {
-                            new_solution[1].set(*v, true);
-                        }
-                    }
-                }
+        let ((cube, bdd, pg), timing) = result?;
 
-                Ok((cube, bdd, new_solution))
+        debug!("Solving projection on {}...", FormatConfig(&cube));
+        let solution = solve_projection(vpg, &pg, timing)?;
+        Ok((cube, bdd, solution))
+    })
+}
+
+/// Solves the given variability parity game using the product-based Zielonka
+/// algorithm, where the products are enumerated and projected using the given
+/// configuration backend.
+pub fn solve_variability_product_zielonka_backend<'a, B: ConfigBackend>(
+    vpg: &'a VariabilityParityGame<B::Config>,
+    backend: &'a B,
+    timing: &'a Timing,
+) -> impl Iterator<Item = Result<(Vec<OptBool>, [Set; 2]), MercError>> + 'a {
+    project_variability_parity_games_backend(vpg, backend, timing).map(|result| {
+        let ((cube, pg), timing) = result?;
+
+        debug!("Solving projection on {}...", FormatConfig(&cube));
+        let solution = solve_projection(vpg, &pg, timing)?;
+        Ok((cube, solution))
+    })
+}
+
+/// Solves the projection of the variability parity game on a single product,
+/// and returns the solution in terms of the vertices of the variability parity game.
+fn solve_projection(vpg: &impl PG, pg: &ParityGame, timing: &Timing) -> Result<[Set; 2], MercError> {
+    let mut reachable_time = timing.start("reachable");
+    let (reachable_pg, projection) = compute_reachable(pg);
+    reachable_time.finish();
+
+    let pg_solution = solve_zielonka(&reachable_pg)?;
+    let mut new_solution = [
+        bitvec![usize, Lsb0; 0; vpg.num_of_vertices()],
+        bitvec![usize, Lsb0; 0; vpg.num_of_vertices()],
+    ];
+    for v in pg.iter_vertices() {
+        if let Some(proj_v) = projection[*v] {
+            // Vertex is reachable in the projection, set its solution
+            if pg_solution[0][proj_v] {
+                new_solution[0].set(*v, true);
+            }
+            if pg_solution[1][proj_v] {
+                new_solution[1].set(*v, true);
             }
-            Err(result) => Err(result),
         }
-    })
+    }
+
+    Ok(new_solution)
 }
 
 /// Verifies that the solution obtained from the variability product-based Zielonka solver
@@ -204,8 +237,8 @@ struct VariabilityZielonkaSolver<'a> {
     /// Keep track of the vertices in the temp_queue above in the attractor computation.
     temp_vertices: BitVec<usize, Lsb0>,
 
-    /// Stores the predecessors of the game.
-    predecessors: VariabilityPredecessors,
+    /// Stores the predecessors of the game, which are restricted to the subgame in every recursive call.
+    predecessors: &'a VariabilityPredecessors,
 
     /// Temporary storage for vertices per priority.
     priority_vertices: Vec<Vec<VertexIndex>>,
@@ -222,7 +255,12 @@ struct VariabilityZielonkaSolver<'a> {
 
 impl<'a> VariabilityZielonkaSolver<'a> {
     /// Creates a new VariabilityZielonkaSolver for the given game.
-    pub fn new(manager_ref: &'a BDDManagerRef, game: &'a VariabilityParityGame, alternative_solving: bool) -> Self {
+    pub fn new(
+        manager_ref: &'a BDDManagerRef,
+        game: &'a VariabilityParityGame,
+        predecessors: &'a VariabilityPredecessors,
+        alternative_solving: bool,
+    ) -> Self {
         // Keep track of the vertices for each priority
         let mut priority_vertices = Vec::new();
 
@@ -244,7 +282,7 @@ impl<'a> VariabilityZielonkaSolver<'a> {
             manager_ref,
             temp_queue: Vec::new(),
             temp_vertices: BitVec::repeat(false, game.num_of_vertices()),
-            predecessors: VariabilityPredecessors::new(manager_ref, game),
+            predecessors,
             priority_vertices,
             recursive_calls: 0,
             alternative_solving,
@@ -255,6 +293,7 @@ impl<'a> VariabilityZielonkaSolver<'a> {
 
     /// Solves the variability parity game for the given set of vertices V.
     fn solve_recursive(&mut self, gamma: Submap, depth: usize) -> Result<(Submap, Submap), MercError> {
+        check_cancelled()?;
         self.recursive_calls += 1;
 
         // For debugging mostly
@@ -296,7 +335,10 @@ impl<'a> VariabilityZielonkaSolver<'a> {
 
         trace!("{indent}Vertices in gamma: {:?}", gamma);
         trace!("{indent}Vertices in mu: {:?}", mu);
-        let alpha = self.attractor(x, &gamma, mu)?;
+
+        // Both attractors are computed within gamma, so they share the restricted predecessors.
+        let predecessors = self.predecessors.restrict(&gamma);
+        let alpha = self.attractor(x, &predecessors, mu)?;
         trace!("{indent}Vertices in alpha: {:?}", alpha);
 
         // 9. (omega'_0, omega'_1) := solve(\gamma \ \alpha)
@@ -319,7 +361,7 @@ impl<'a> VariabilityZielonkaSolver<'a> {
             Ok(combine(omega1_x, omega1_not_x, x))
         } else {
             // 14. \beta := attr_notalpha(\omega'_notx)
-            let beta = self.attractor(not_x, &gamma, omega1_not_x)?;
+            let beta = self.attractor(not_x, &predecessors, omega1_not_x)?;
             // 15. (omega''_0, omega''_1) := solve(gamma \ beta)
             debug!(
                 "{indent}solve_rec(gamma \\ beta), |beta| = {}",
@@ -344,6 +386,7 @@ impl<'a> VariabilityZielonkaSolver<'a> {
 
     /// Left-optimised Zielonka solver that has improved theoretical complexity, but might be slower in practice.
     fn zielonka_family_optimised(&mut self, gamma: Submap, depth: usize) -> Result<(Submap, Submap), MercError> {
+        check_cancelled()?;
         self.recursive_calls += 1;
         let indent = Repeat::new(" ", depth);
         let gamma_copy = gamma.clone();
@@ -389,7 +432,10 @@ impl<'a> VariabilityZielonkaSolver<'a> {
         // 9. alpha := attr_x(\mu).
         trace!("{indent}gamma: {:?}", gamma);
         trace!("{indent}C: {}", FormatConfigSet(&C));
-        let alpha = self.attractor(x, &gamma, mu)?;
+
+        // Both attractors are computed within gamma, so they share the restricted predecessors.
+        let predecessors = self.predecessors.restrict(&gamma);
+        let alpha = self.attractor(x, &predecessors, mu)?;
         trace!("{indent}alpha: {:?}", alpha);
 
         // 10. (omega'_0, omega'_1) := solve(gamma \ alpha)
@@ -451,7 +497,7 @@ impl<'a> VariabilityZielonkaSolver<'a> {
                 .clone()
                 .minus_function(self.manager_ref, &C1_restricted)?;
             trace!("{indent}omega'_notx_restricted: {:?}", omega1_not_x_restricted1);
-            let alpha1 = self.attractor(not_x, &gamma, omega1_not_x_restricted1)?;
+            let alpha1 = self.attractor(not_x, &predecessors, omega1_not_x_restricted1)?;
             trace!("{indent}alpha': {:?}", alpha1);
 
             // Solve on (gamma | C') \ alpha'
@@ -491,7 +537,13 @@ impl<'a> VariabilityZielonkaSolver<'a> {
     ///          (v ∈ V¯x ∧ (∀w ∈ V : v c −→ γ w ⇒ c ∈ α(w)) ⇒ c ∈ α(v)) }
     ///
     /// The relation to the implementation is not entirely straightforward. The player `x` is called alpha here, and A is the beta set.
-    fn attractor(&mut self, alpha: Player, gamma: &Submap, mut A: Submap) -> Result<Submap, MercError> {
+    /// The subgame gamma is given by the restricted `predecessors`, whose edge configurations already include gamma.
+    fn attractor(
+        &mut self,
+        alpha: Player,
+        predecessors: &RestrictedPredecessors<'_>,
+        mut A: Submap,
+    ) -> Result<Submap, MercError> {
         // 2. Queue Q := {v \in V | A(v) != \emptyset }
         debug_assert!(
             self.temp_queue.is_empty(),
@@ -515,20 +567,20 @@ impl<'a> VariabilityZielonkaSolver<'a> {
                 let f_edge = EdgeDropGuard::new(manager, BDDFunction::f_edge(manager));
 
                 while let Some(w) = self.temp_queue.pop() {
+                    check_cancelled()?;
                     self.temp_vertices.set(*w, false);
 
                     // For every v \in Ew do
-                    for (v, edge_guard) in self.predecessors.predecessors(w) {
+                    for predecessor in predecessors.predecessors(manager, w) {
+                        // The configuration of the edge is gamma(v) \intersect \theta(v, w) \intersect gamma(w), and A(w) is included in gamma(w).
+                        let (v, edge_guard) = predecessor?;
+                        if *edge_guard.as_edge(manager) == *f_edge {
+                            continue;
+                        }
+
                         let mut a = EdgeDropGuard::new(
                             manager,
-                            BDDFunction::and_edge(
-                                manager,
-                                &EdgeDropGuard::new(
-                                    manager,
-                                    BDDFunction::and_edge(manager, gamma[v].as_edge(manager), A[w].as_edge(manager))?,
-                                ),
-                                edge_guard.as_edge(manager),
-                            )?,
+                            BDDFunction::and_edge(manager, edge_guard.as_edge(manager), A[w].as_edge(manager))?,
                         );
 
                         if *a != *f_edge {
@@ -538,36 +590,14 @@ impl<'a> VariabilityZielonkaSolver<'a> {
                                 // This assignment has already been computed above.
                             } else {
                                 // 10. a := gamma(v)
-                                a = EdgeDropGuard::new(manager, gamma[v].clone().into_edge(manager));
+                                a = EdgeDropGuard::new(manager, predecessors.gamma()[v].clone().into_edge(manager));
                                 // 11. for w' \in vE such that gamma(v) && theta(v, w') && \gamma(w') != \emptyset do
-                                for edge_w1 in self.game.outgoing_conf_edges(v) {
-                                    let tmp = EdgeDropGuard::new(
-                                        manager,
-                                        BDDFunction::and_edge(
-                                            manager,
-                                            &EdgeDropGuard::new(
-                                                manager,
-                                                BDDFunction::and_edge(
-                                                    manager,
-                                                    gamma[v].as_edge(manager),
-                                                    edge_w1.configuration().as_edge(manager),
-                                                )?,
-                                            ),
-                                            gamma[edge_w1.to()].as_edge(manager),
-                                        )?,
-                                    );
-
-                                    if *tmp != *f_edge {
-                                        // 12. a := a && ((C \ (theta(v, w') && \gamma(w'))) \cup A(w'))
-                                        let tmp = EdgeDropGuard::new(
-                                            manager,
-                                            BDDFunction::and_edge(
-                                                manager,
-                                                edge_w1.configuration().as_edge(manager),
-                                                gamma[edge_w1.to()].as_edge(manager),
-                                            )?,
-                                        );
+                                for successor in predecessors.successors(manager, v) {
+                                    let (w1, tmp) = successor?;
 
+                                    if *tmp.as_edge(manager) != *f_edge {
+                                        // 12. a := a && ((C \ (theta(v, w') && \gamma(w'))) \cup A(w'))
+                                        // Since a is included in gamma(v), intersecting with gamma(v) as well does not change the result.
                                         a = EdgeDropGuard::new(
                                             manager,
                                             BDDFunction::and_edge(
@@ -586,10 +616,10 @@ impl<'a> VariabilityZielonkaSolver<'a> {
                                                                 } else {
                                                                     self.game.configuration().as_edge(manager)
                                                                 },
-                                                                &tmp,
+                                                                tmp.as_edge(manager),
                                                             )?,
                                                         ),
-                                                        A[edge_w1.to()].as_edge(manager),
+                                                        A[w1].as_edge(manager),
                                                     )?,
                                                 ),
                                             )?,
@@ -602,9 +632,7 @@ impl<'a> VariabilityZielonkaSolver<'a> {
                             if *EdgeDropGuard::new(manager, minus_edge(manager, &a, A[v].as_edge(manager))?) != *f_edge
                             {
                                 // 16. A(v) := A(v) \cup a
-                                let was_empty = *A[v].as_edge(manager) == *f_edge;
                                 let update = BDDFunction::or_edge(manager, A[v].as_edge(manager), &a)?;
-                                let is_empty = update == *f_edge;
 
                                 A.set(manager, v, BDDFunction::from_edge(manager, update));
 
@@ -672,10 +700,15 @@ impl<'a> VariabilityZielonkaSolver<'a> {
 
 #[cfg(test)]
 mod tests {
+    use std::rc::Rc;
+
     use merc_io::DumpFiles;
     use merc_macros::merc_test;
+    use merc_symbolic::FormatConfig;
+    use merc_utilities::MercError;
     use merc_utilities::Timing;
     use oxidd::bdd::BDDFunction;
+    use oxidd::bdd::BDDManagerRef;
     use oxidd::util::AllocResult;
     use oxidd::BooleanFunction;
     use oxidd::Manager;
@@ -686,11 +719,17 @@ mod tests {
     use crate::project_variability_parity_games_iter;
     use crate::random_variability_parity_game;
     use crate::solve_variability_product_zielonka;
+    use crate::solve_variability_product_zielonka_backend;
     use crate::solve_variability_zielonka;
     use crate::solve_zielonka;
     use crate::verify_variability_product_zielonka_solution;
     use crate::write_vpg;
+    use crate::BddBackend;
+    use crate::ConfigBackend;
+    use crate::Formula;
+    use crate::SatBackend;
     use crate::Submap;
+    use crate::VariabilityParityGame;
     use crate::VertexIndex;
     use crate::ZielonkaVariant;
     use crate::PG;
@@ -731,4 +770,71 @@ mod tests {
             debug_assert_eq!(solution[1], solution_expected[1]);
         })
     }
+
+    /// Returns the game where every configuration is given by a formula,
+    /// which is the disjunction of the configurations in the BDD.
+    fn to_formulas(
+        manager_ref: &BDDManagerRef,
+        vpg: &VariabilityParityGame,
+        backend: &SatBackend,
+    ) -> Result<VariabilityParityGame<Rc<Formula>>, MercError> {
+        let bdd_backend = BddBackend::new(manager_ref, vpg.variables());
+        let to_formula = |bdd: &BDDFunction| -> Result<Rc<Formula>, MercError> {
+            let mut result = backend.bottom()?;
+            for values in bdd_backend.configurations(bdd)? {
+                result = backend.or(&result, &backend.cube(&values?)?)?;
+            }
+
+            Ok(result)
+        };
+
+        let mut edges = Vec::new();
+        for v in vpg.iter_vertices() {
+            for edge in vpg.outgoing_conf_edges(v) {
+                edges.push((v, to_formula(edge.configuration())?, edge.to()));
+            }
+        }
+
+        Ok(VariabilityParityGame::from_config_edges(
+            vpg.initial_vertex(),
+            vpg.owners().clone(),
+            vpg.priorities().clone(),
+            to_formula(vpg.configuration())?,
+            (0..backend.num_of_variables())
+                .map(|index| backend.variable(index))
+                .collect::<Result<_, _>>()?,
+            edges,
+        ))
+    }
+
+    #[merc_test]
+    #[cfg_attr(miri, ignore)] // Oxidd does not work with miri
+    fn test_random_variability_parity_game_solve_sat_backend() {
+        random_test(100, |rng| {
+            let mut files = DumpFiles::new("test_random_variability_parity_game_solve_sat_backend");
+
+            let manager_ref = oxidd::bdd::new_manager(2048, 1024, 1);
+            let vpg = random_variability_parity_game(&manager_ref, rng, true, 20, 3, 3, 3).unwrap();
+
+            files.dump("input.vpg", |w| write_vpg(w, &vpg)).unwrap();
+
+            let timing = Timing::new();
+            let mut expected: Vec<_> = solve_variability_product_zielonka(&vpg, &timing)
+                .map(|result| result.map(|(cube, _bdd, solution)| (FormatConfig(&cube).to_string(), solution)))
+                .collect::<Result<_, _>>()
+                .unwrap();
+
+            let backend = SatBackend::new(vpg.variables().len());
+            let formulas = to_formulas(&manager_ref, &vpg, &backend).unwrap();
+            let mut solution: Vec<_> = solve_variability_product_zielonka_backend(&formulas, &backend, &timing)
+                .map(|result| result.map(|(cube, solution)| (FormatConfig(&cube).to_string(), solution)))
+                .collect::<Result<_, _>>()
+                .unwrap();
+
+            // The SAT solver enumerates the products in a different order.
+            expected.sort_by(|lhs, rhs| lhs.0.cmp(&rhs.0));
+            solution.sort_by(|lhs, rhs| lhs.0.cmp(&rhs.0));
+            assert_eq!(solution, expected);
+        })
+    }
 }
@@ -0,0 +1,187 @@
+#![forbid(unsafe_code)]
+
+use log::info;
+use oxidd::BooleanFunction;
+use oxidd::ManagerRef;
+use oxidd::bdd::BDDFunction;
+use oxidd::bdd::BDDManagerRef;
+
+use merc_symbolic::FormatConfig;
+use merc_symbolic::FormatConfigSet;
+use merc_symbolic::minus;
+use merc_utilities::MercError;
+use merc_utilities::Timing;
+use merc_utilities::check_cancelled;
+
+use crate::PG;
+use crate::Player;
+use crate::Submap;
+use crate::VariabilityParityGame;
+use crate::solve_variability_product_zielonka;
+
+/// Selects how [verify_variability_solution] checks a solution.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+pub enum VerificationMethod {
+    /// Checks for all configurations at once that the winning sets are closed.
+    #[default]
+    Symbolic,
+    /// Additionally solves every product of the game and compares the winners.
+    Product,
+}
+
+/// Verifies that the given winning submaps of [Player::Even] and
+/// [Player::Odd] are a correct solution of the given total variability parity
+/// game, independently of the solver that computed them. Returns an error that
+/// describes the first violation that is found.
+///
+/// # Details
+///
+/// For every configuration the winning sets must partition the vertices, and
+/// the winning set of every player x must be closed: a vertex owned by x that
+/// is won by x must have a successor won by x, and all successors of a vertex
+/// owned by the opponent that is won by x must be won by x as well. These
+/// conditions are checked symbolically for all configurations at once.
+///
+/// Closed winning sets can still be won by the wrong player, so the
+/// [VerificationMethod::Product] method additionally solves every product
+/// using the explicit Zielonka solver and compares the winners of the
+/// vertices that are reachable in that product.
+pub fn verify_variability_solution(
+    manager_ref: &BDDManagerRef,
+    game: &VariabilityParityGame,
+    solution: &[Submap; 2],
+    method: VerificationMethod,
+    timing: &Timing,
+) -> Result<(), MercError> {
+    info!("Verifying the solution of the variability parity game...");
+    let configuration = game.configuration();
+    let false_bdd = manager_ref.with_manager_shared(|manager| BDDFunction::f(manager));
+
+    for v in game.iter_vertices() {
+        check_cancelled()?;
+
+        let won = [solution[0][v].and(configuration)?, solution[1][v].and(configuration)?];
+
+        // The winning sets partition the vertices for every configuration.
+        let missing = minus(configuration, &won[0].or(&won[1])?)?;
+        if missing.satisfiable() {
+            return Err(MercError::internal(format!(
+                "Vertex {v} is won by neither player for the configurations {}",
+                FormatConfigSet(&missing)
+            )));
+        }
+
+        let both = won[0].and(&won[1])?;
+        if both.satisfiable() {
+            return Err(MercError::internal(format!(
+                "Vertex {v} is won by both players for the configurations {}",
+                FormatConfigSet(&both)
+            )));
+        }
+
+        for (index, won_x) in won.iter().enumerate() {
+            let x = Player::from_index(index as u8);
+            let winning = &solution[index];
+
+            if game.owner(v) == x {
+                // The player can move to a vertex that it wins.
+                let mut stay = false_bdd.clone();
+                for edge in game.outgoing_conf_edges(v) {
+                    stay = stay.or(&edge.configuration().and(&winning[edge.to()])?)?;
+                }
+
+                let stuck = minus(won_x, &stay)?;
+                if stuck.satisfiable() {
+                    return Err(MercError::internal(format!(
+                        "Vertex {v} is won by {x}, which owns it, but has no successor won by {x} for the configurations {}",
+                        FormatConfigSet(&stuck)
+                    )));
+                }
+            } else {
+                // The opponent cannot move to a vertex that it wins.
+                for edge in game.outgoing_conf_edges(v) {
+                    let escape = minus(&won_x.and(edge.configuration())?, &winning[edge.to()])?;
+                    if escape.satisfiable() {
+                        return Err(MercError::internal(format!(
+                            "Vertex {v} is won by {x}, but its opponent can move to {} which is not won by {x} for the configurations {}",
+                            edge.to(),
+                            FormatConfigSet(&escape)
+                        )));
+                    }
+                }
+            }
+        }
+    }
+
+    if method == VerificationMethod::Product {
+        for result in solve_variability_product_zielonka(game, timing) {
+            let (bits, cube, product_solution) = result?;
+
+            for v in game.iter_vertices() {
+                for (index, winning) in solution.iter().enumerate() {
+                    // Only the vertices that are reachable in the product have a winner.
+                    let expected = product_solution[index][*v];
+                    let reachable = expected || product_solution[1 - index][*v];
+                    if reachable && winning[v].and(&cube)?.satisfiable() != expected {
+                        return Err(MercError::internal(format!(
+                            "Vertex {v} is {}won by {} in product {}, but the solution states otherwise",
+                            if expected { "" } else { "not " },
+                            Player::from_index(index as u8),
+                            FormatConfig(&bits)
+                        )));
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use merc_macros::merc_test;
+    use merc_utilities::Timing;
+    use merc_utilities::random_test;
+    use oxidd::BooleanFunction;
+
+    use crate::VerificationMethod;
+    use crate::ZielonkaVariant;
+    use crate::random_variability_parity_game;
+    use crate::solve_variability_zielonka;
+    use crate::verify_variability_solution;
+
+    #[merc_test]
+    #[cfg_attr(miri, ignore)] // Oxidd does not work with miri
+    fn test_random_verify_variability_solution() {
+        random_test(100, |rng| {
+            let manager_ref = oxidd::bdd::new_manager(2048, 1024, 1);
+            let vpg = random_variability_parity_game(&manager_ref, rng, true, 20, 3, 3, 3).unwrap();
+
+            let [w0, w1] = solve_variability_zielonka(&manager_ref, &vpg, ZielonkaVariant::Family, false).unwrap();
+            verify_variability_solution(
+                &manager_ref,
+                &vpg,
+                &[w0.clone(), w1.clone()],
+                VerificationMethod::Product,
+                &Timing::new(),
+            )
+            .unwrap();
+
+            if vpg.configuration().satisfiable() {
+                // Swapping the winning sets of both players does not yield a solution.
+                assert!(
+                    verify_variability_solution(
+                        &manager_ref,
+                        &vpg,
+                        &[w1, w0],
+                        VerificationMethod::Product,
+                        &Timing::new()
+                    )
+                    .is_err()
+                );
+            }
+        })
+    }
+}
@@ -14,6 +14,8 @@ use bitvec::vec::BitVec;
 use itertools::Itertools;
 use log::debug;
 use log::trace;
+use merc_utilities::MercError;
+use merc_utilities::check_cancelled;
 
 use crate::PG;
 use crate::ParityGame;
@@ -27,7 +29,22 @@ use crate::VertexIndex;
 pub type Set = BitVec<usize, Lsb0>;
 
 /// Solves the given parity game using the Zielonka algorithm.
-pub fn solve_zielonka(game: &ParityGame) -> [Set; 2] {
+///
+/// Returns an error when the computation was cancelled, see [`merc_utilities::CancellationToken`].
+pub fn solve_zielonka(game: &ParityGame) -> Result<[Set; 2], MercError> {
+    let (solution, _strategy) = solve_zielonka_strategy(game)?;
+    Ok(solution)
+}
+
+/// Solves the given parity game using the Zielonka algorithm, and also returns
+/// a positional winning strategy.
+///
+/// # Details
+///
+/// For every vertex that is won by its owner, the strategy is the successor
+/// that the owner should choose to win. For the other vertices the strategy
+/// is meaningless, since every choice of the owner is losing.
+pub fn solve_zielonka_strategy(game: &ParityGame) -> Result<([Set; 2], Vec<VertexIndex>), MercError> {
     debug_assert!(game.is_total(), "Zielonka solver requires a total parity game");
 
     let mut V = bitvec![usize, Lsb0; 0; game.num_of_vertices()];
@@ -36,14 +53,15 @@ pub fn solve_zielonka(game: &ParityGame) -> [Set; 2] {
 
     let mut zielonka = ZielonkaSolver::new(game);
 
-    let (W0, W1) = zielonka.zielonka_rec(V, 0);
+    let (W0, W1) = zielonka.zielonka_rec(V, 0)?;
 
     // Check that the result is a valid partition
     debug!("Performed {} recursive calls", zielonka.recursive_calls);
     if cfg!(debug_assertions) {
         zielonka.check_partition(&W0, &W1, &full_V);
+        zielonka.check_strategy(&W0, &W1);
     }
-    [W0, W1]
+    Ok(([W0, W1], zielonka.strategy))
 }
 
 struct ZielonkaSolver<'a> {
@@ -58,6 +76,9 @@ struct ZielonkaSolver<'a> {
     /// Temporary storage for vertices per priority.
     priority_vertices: Vec<Vec<VertexIndex>>,
 
+    /// The successor chosen for every vertex, which is winning when the vertex is won by its owner.
+    strategy: Vec<VertexIndex>,
+
     /// Keeps track of the total number of recursive calls.
     recursive_calls: usize,
 }
@@ -83,18 +104,20 @@ impl ZielonkaSolver<'_> {
             predecessors: Predecessors::new(game),
             priority_vertices,
             temp_queue: Vec::new(),
+            strategy: game.iter_vertices().collect(),
             recursive_calls: 0,
         }
     }
 
     /// Recursively solves the parity game for the given set of vertices V.
-    fn zielonka_rec(&mut self, V: Set, depth: usize) -> (Set, Set) {
+    fn zielonka_rec(&mut self, V: Set, depth: usize) -> Result<(Set, Set), MercError> {
+        check_cancelled()?;
         self.recursive_calls += 1;
         let full_V = V.clone(); // Used for debugging
         let indent = Repeat::new(" ", depth);
 
         if !V.any() {
-            return (V.clone(), V);
+            return Ok((V.clone(), V));
         }
 
         let (highest_prio, lowest_prio) = self.get_highest_lowest_prio(&V);
@@ -120,34 +143,47 @@ impl ZielonkaSolver<'_> {
         );
         trace!("{}Vertices in U: {}", indent, DisplaySet(&U));
 
-        let A = self.attractor(alpha, &V, U);
+        let A = self.attractor(alpha, &V, U.clone())?;
 
         trace!("{}Vertices in A: {}", indent, DisplaySet(&A));
         debug!("{}zielonka(V \\ A) |A| = {}", indent, A.count_ones());
-        let (W1_0, W1_1) = self.zielonka_rec(V.clone().bitand(!A.clone()), depth + 1);
+        let (W1_0, W1_1) = self.zielonka_rec(V.clone().bitand(!A.clone()), depth + 1)?;
 
         let (mut W1_alpha, W1_not_alpha) = x_and_not_x(W1_0, W1_1, alpha);
 
         if !W1_not_alpha.any() {
+            // Alpha wins everything, so from the vertices with the highest priority it can choose any successor in V.
+            for v in U.iter_ones().map(VertexIndex::new) {
+                if self.game.owner(v) == alpha {
+                    self.strategy[v] = self
+                        .game
+                        .outgoing_edges(v)
+                        .find(|w| V[**w])
+                        .expect("Every vertex has a successor in the subgame");
+                }
+            }
+
             W1_alpha |= A;
-            combine(W1_alpha, W1_not_alpha, alpha)
+            Ok(combine(W1_alpha, W1_not_alpha, alpha))
         } else {
-            let B = self.attractor(not_alpha, &V, W1_not_alpha);
+            let B = self.attractor(not_alpha, &V, W1_not_alpha)?;
 
             trace!("{}Vertices in B: {}", indent, DisplaySet(&A));
             debug!("{}zielonka(V \\ B)", indent);
-            let (W2_0, W2_1) = self.zielonka_rec(V.bitand(!B.clone()), depth + 1);
+            let (W2_0, W2_1) = self.zielonka_rec(V.bitand(!B.clone()), depth + 1)?;
 
             let (W2_alpha, mut W2_not_alpha) = x_and_not_x(W2_0, W2_1, alpha);
 
             W2_not_alpha |= B;
             self.check_partition(&W2_alpha, &W2_not_alpha, &full_V);
-            combine(W2_alpha, W2_not_alpha, alpha)
+            Ok(combine(W2_alpha, W2_not_alpha, alpha))
         }
     }
 
-    /// Computes the attractor for `alpha` to the set `U` within the vertices `V`.
-    fn attractor(&mut self, alpha: Player, V: &Set, mut A: Set) -> Set {
+    /// Computes the attractor for `alpha` to the set `U` within the vertices `V`,
+    /// where the strategy of the attracted vertices owned by `alpha` is set to
+    /// move towards `U`.
+    fn attractor(&mut self, alpha: Player, V: &Set, mut A: Set) -> Result<Set, MercError> {
         // 2. Q = {v \in A}
         self.temp_queue.clear();
         for v in A.iter_ones() {
@@ -157,6 +193,8 @@ impl ZielonkaSolver<'_> {
         // 4. While Q is not empty do
         // 5. w := Q.pop()
         while let Some(w) = self.temp_queue.pop() {
+            check_cancelled()?;
+
             // For every u \in Ew do
             for v in self.predecessors.predecessors(w) {
                 if V[*v] {
@@ -171,12 +209,16 @@ impl ZielonkaSolver<'_> {
                     if attracted && !A[*v] {
                         A.set(*v, true);
                         self.temp_queue.push(v);
+
+                        if self.game.owner(v) == alpha {
+                            self.strategy[v] = w;
+                        }
                     }
                 }
             }
         }
 
-        A
+        Ok(A)
     }
 
     /// Returns the highest and lowest priority in the given set of vertices V.
@@ -193,6 +235,24 @@ impl ZielonkaSolver<'_> {
         (Priority::new(highest), Priority::new(lowest))
     }
 
+    /// Checks that the strategy of every vertex won by its owner stays within its winning set.
+    fn check_strategy(&self, W0: &Set, W1: &Set) {
+        for v in self.game.iter_vertices() {
+            let winning = if self.game.owner(v) == Player::Even { W0 } else { W1 };
+            if winning[*v] {
+                let w = self.strategy[v];
+                assert!(
+                    self.game.outgoing_edges(v).any(|u| u == w),
+                    "The strategy of vertex {v} is not a successor"
+                );
+                assert!(
+                    winning[*w],
+                    "The strategy of vertex {v} leaves the winning set of its owner"
+                );
+            }
+        }
+    }
+
     /// Checks that the given solutions are a valid partition of the vertices in V
     fn check_partition(&self, W0: &Set, W1: &Set, V: &Set) {
         let intersection = W0.clone() & W1;
@@ -243,8 +303,12 @@ impl fmt::Display for DisplaySet<'_> {
 mod tests {
     use merc_utilities::random_test;
 
+    use crate::PG;
+    use crate::ParityGame;
+    use crate::VertexIndex;
     use crate::random_parity_game;
     use crate::solve_zielonka;
+    use crate::solve_zielonka_strategy;
 
     #[test]
     #[cfg_attr(miri, ignore)] // Very slow under Miri
@@ -253,7 +317,38 @@ mod tests {
             let pg = random_parity_game(rng, true, 100, 5, 3);
             println!("{:?}", pg);
 
-            solve_zielonka(&pg);
+            solve_zielonka(&pg).unwrap();
+        })
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)] // Very slow under Miri
+    fn test_random_parity_game_strategy() {
+        random_test(100, |rng| {
+            let pg = random_parity_game(rng, true, 100, 5, 3);
+            let (solution, strategy) = solve_zielonka_strategy(&pg).unwrap();
+            let strategy = &strategy;
+
+            // Fixing the strategy for the vertices won by their owner should not change the solution.
+            let won_by_owner = |v: VertexIndex| solution[pg.owner(v).to_index()][*v];
+            let edges: Vec<_> = pg
+                .iter_vertices()
+                .flat_map(|v| {
+                    pg.outgoing_edges(v)
+                        .filter(move |w| !won_by_owner(v) || *w == strategy[v])
+                        .map(move |w| (v, w))
+                })
+                .collect();
+
+            let restricted = ParityGame::from_edges(
+                pg.initial_vertex(),
+                pg.owners().clone(),
+                pg.iter_vertices().map(|v| pg.priority(v)).collect(),
+                false,
+                || edges.iter().cloned(),
+            );
+
+            assert_eq!(solve_zielonka(&restricted).unwrap(), solution);
         })
     }
 }
@@ -0,0 +1,94 @@
+use std::error::Error;
+use std::fs::create_dir_all;
+use std::fs::metadata;
+use std::fs::remove_file;
+use std::path::Path;
+
+use duct::cmd;
+use which::which;
+
+/// The directory in which the VLTS models are stored, which is the default
+/// directory of the VLTS benchmarks in the `merc_benchmarks` crate.
+const VLTS_DIRECTORY: &str = "target/benchmarks/vlts";
+
+/// The location of the VLTS benchmark suite.
+const VLTS_URL: &str = "https://cadp.inria.fr/resources/vlts";
+
+/// The VLTS models that are used for benchmarking, ordered by their number of states.
+const VLTS_MODELS: [&str; 8] = [
+    "vasy_0_1",
+    "cwi_1_2",
+    "vasy_1_4",
+    "vasy_5_9",
+    "vasy_8_24",
+    "vasy_10_56",
+    "cwi_3_14",
+    "vasy_25_25",
+];
+
+/// The REC specifications that are used for benchmarking, see `REC_BENCHMARKS` of the `merc_benchmarks` crate.
+const REC_DIRECTORY: &str = "examples/REC/rec";
+
+///
+/// Checks that the inputs of the benchmark suite are present, and downloads the
+/// missing VLTS models when `download` is true.
+///
+/// The VLTS models are distributed in the BCG format, which are converted to
+/// the `.aut` format by `bcg_io` of the CADP toolset.
+///
+pub fn benchmark_inputs(download: bool) -> Result<(), Box<dyn Error>> {
+    if !Path::new(REC_DIRECTORY).is_dir() {
+        return Err(format!("The REC benchmarks are missing, expected them in {REC_DIRECTORY}").into());
+    }
+    println!("REC benchmarks: {REC_DIRECTORY}");
+
+    create_dir_all(VLTS_DIRECTORY)?;
+
+    let mut missing = Vec::new();
+    for model in VLTS_MODELS {
+        let aut = Path::new(VLTS_DIRECTORY).join(format!("{model}.aut"));
+        if metadata(&aut).is_ok_and(|metadata| metadata.len() > 0) {
+            println!("VLTS model {model}: ok");
+            continue;
+        }
+
+        if !download {
+            println!("VLTS model {model}: missing");
+            missing.push(model);
+            continue;
+        }
+
+        println!("VLTS model {model}: downloading");
+        let bcg = Path::new(VLTS_DIRECTORY).join(format!("{model}.bcg"));
+        cmd!(
+            "curl",
+            "--fail",
+            "--location",
+            "--output",
+            &bcg,
+            format!("{VLTS_URL}/{model}.bcg")
+        )
+        .run()?;
+
+        let bcg_io = which("bcg_io").map_err(|_| {
+            format!(
+                "Converting {} requires bcg_io of the CADP toolset, or convert it to {} manually",
+                bcg.display(),
+                aut.display()
+            )
+        })?;
+        cmd!(bcg_io, &bcg, &aut).run()?;
+        remove_file(&bcg)?;
+    }
+
+    if !missing.is_empty() {
+        return Err(format!(
+            "The VLTS models {} are missing, run with --download to obtain them",
+            missing.join(", ")
+        )
+        .into());
+    }
+
+    println!("ok.");
+    Ok(())
+}
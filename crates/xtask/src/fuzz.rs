@@ -0,0 +1,60 @@
+use std::error::Error;
+use std::fs::copy;
+use std::fs::create_dir_all;
+use std::path::PathBuf;
+
+use duct::cmd;
+use glob::glob;
+
+/// The fuzz targets of the `fuzz` crate, with the examples that are used as the initial corpus.
+const TARGETS: [(&str, &[&str]); 5] = [
+    ("read_aut", &["examples/**/*.aut"]),
+    ("read_pg", &["examples/**/*.pg"]),
+    ("read_vpg", &["examples/**/*.vpg"]),
+    ("read_aterm", &["examples/**/*.lts", "examples/**/*.sym"]),
+    ("read_feature_diagram", &["examples/**/*.fd"]),
+];
+
+///
+/// Seeds the corpus of the given fuzz target with the examples and runs it
+/// using cargo-fuzz, passing the given arguments to it.
+///
+/// This requires cargo-fuzz and the nightly toolchain.
+///
+pub fn fuzz(target: String, arguments: Vec<String>) -> Result<(), Box<dyn Error>> {
+    let (_, patterns) = TARGETS.iter().find(|(name, _)| *name == target).ok_or_else(|| {
+        format!(
+            "Unknown fuzz target {target}, expected one of {}",
+            TARGETS.map(|(name, _)| name).join(", ")
+        )
+    })?;
+
+    let corpus: PathBuf = ["fuzz", "corpus", &target].iter().collect();
+    create_dir_all(&corpus)?;
+
+    for pattern in patterns.iter() {
+        for path in glob(pattern)? {
+            let path = path?;
+            if let Some(file_name) = path.file_name() {
+                copy(&path, corpus.join(file_name))?;
+            }
+        }
+    }
+
+    println!("=== fuzzing {target} ===");
+    let mut fuzz_arguments = vec![
+        "+nightly".to_string(),
+        "fuzz".to_string(),
+        "run".to_string(),
+        "--fuzz-dir".to_string(),
+        "fuzz".to_string(),
+        target,
+        corpus.to_string_lossy().to_string(),
+    ];
+    fuzz_arguments.extend(arguments);
+
+    cmd("cargo", fuzz_arguments).run()?;
+    println!("ok.");
+
+    Ok(())
+}
@@ -0,0 +1,28 @@
+//!
+//! Generates the C headers for the crates that expose a C interface.
+//!
+
+use std::error::Error;
+
+use duct::cmd;
+use which::which;
+
+/// The crates with a C interface, and the location of their generated header relative to the crate.
+const FFI_CRATES: [(&str, &str); 1] = [("crates/lts-ffi", "include/merc_lts_ffi.h")];
+
+/// Generates the headers for all crates in [FFI_CRATES] using the `cbindgen` command line tool.
+pub fn generate_headers() -> Result<(), Box<dyn Error>> {
+    if which("cbindgen").is_err() {
+        return Err("cbindgen is not installed, it can be installed using `cargo install cbindgen`.".into());
+    }
+
+    for (directory, header) in FFI_CRATES {
+        println!("=== Generating {directory}/{header} ===");
+
+        cmd!("cbindgen", "--config", "cbindgen.toml", "--output", header)
+            .dir(directory)
+            .run()?;
+    }
+
+    Ok(())
+}
@@ -11,8 +11,11 @@ use clap::Parser;
 use clap::Subcommand;
 use std::path::PathBuf;
 
+mod benchmark_inputs;
 mod coverage;
 mod discover_tests;
+mod fuzz;
+mod generate_headers;
 mod package;
 mod publish;
 mod sanitizer;
@@ -27,6 +30,11 @@ struct Cli {
 
 #[derive(Subcommand)]
 enum Commands {
+    /// Checks that the inputs of the benchmark suite are present, optionally downloading the missing ones.
+    BenchmarkInputs {
+        #[clap(long)]
+        download: bool,
+    },
     /// Generates a code coverage report using grcov.
     Coverage {
         #[clap(trailing_var_arg = true)]
@@ -44,6 +52,14 @@ enum Commands {
     },
     /// Discovers tests from the examples folder, and prints them as a `#[test_case]` annotation.
     DiscoverTests,
+    /// Runs the given fuzz target, after seeding its corpus with the examples.
+    Fuzz {
+        target: String,
+        #[clap(trailing_var_arg = true)]
+        args: Vec<String>,
+    },
+    /// Generates the C headers of the FFI crates using cbindgen.
+    GenerateHeaders,
     /// Builds and packages the binaries for release.
     Package,
     /// Publishes the crates to crates.io.
@@ -57,10 +73,13 @@ fn main() -> Result<ExitCode, Box<dyn Error>> {
     let cli = Cli::parse();
 
     match cli.command {
+        Commands::BenchmarkInputs { download } => benchmark_inputs::benchmark_inputs(download)?,
         Commands::Coverage { args } => coverage::coverage(args)?,
         Commands::AddressSanitizer { args } => sanitizer::address_sanitizer(args)?,
         Commands::ThreadSanitizer { args } => sanitizer::thread_sanitizer(args)?,
         Commands::DiscoverTests => discover_tests::discover_tests()?,
+        Commands::Fuzz { target, args } => fuzz::fuzz(target, args)?,
+        Commands::GenerateHeaders => generate_headers::generate_headers()?,
         Commands::Package => package::package()?,
         Commands::Publish => publish::publish_crates(),
         Commands::TestTools { directory } => tool_testing::test_tools(directory.as_path())?,
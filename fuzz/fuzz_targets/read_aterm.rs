@@ -0,0 +1,15 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use merc_aterm::ATermRead;
+use merc_aterm::BinaryATermReader;
+
+fuzz_target!(|data: &[u8]| {
+    // Malformed input must result in an error, and never in a panic.
+    let Ok(mut reader) = BinaryATermReader::new(data) else {
+        return;
+    };
+
+    while let Ok(Some(_term)) = reader.read_aterm() {}
+});
@@ -0,0 +1,10 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use merc_lts::read_aut;
+
+fuzz_target!(|data: &[u8]| {
+    // Malformed input must result in an error, and never in a panic.
+    let _ = read_aut(data, Vec::new());
+});
@@ -0,0 +1,13 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use merc_vpg::FeatureDiagram;
+
+fuzz_target!(|data: &[u8]| {
+    // The feature diagram defines the variables, so it requires a fresh manager.
+    let manager_ref = oxidd::bdd::new_manager(2048, 1024, 1);
+
+    // Malformed input must result in an error, and never in a panic.
+    let _ = FeatureDiagram::from_reader(&manager_ref, data);
+});
@@ -0,0 +1,13 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use merc_vpg::read_vpg;
+
+fuzz_target!(|data: &[u8]| {
+    // Every input defines its own variables, so it requires a fresh manager.
+    let manager_ref = oxidd::bdd::new_manager(2048, 1024, 1);
+
+    // Malformed input must result in an error, and never in a panic.
+    let _ = read_vpg(&manager_ref, data);
+});
@@ -0,0 +1,248 @@
+use std::io::stdout;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::Subcommand;
+
+use merc_aterm::storage::print_term_pool_metrics;
+use merc_explore::LinearProcess;
+use merc_explore::PartialOrderReduction;
+use merc_explore::Property;
+use merc_explore::SummandDependencies;
+use merc_explore::check;
+use merc_explore::explore;
+use merc_explore::explore_parallel;
+use merc_explore::explore_reduced;
+use merc_explore::explore_with_checkpoint;
+use merc_linearise::linearise;
+use merc_lts::GenericLts;
+use merc_lts::LTS;
+use merc_lts::LtsFormat;
+use merc_lts::guess_lts_format_from_extension;
+use merc_lts::write_aut;
+use merc_lts::write_explicit_lts;
+use merc_rec_tests::load_rec_from_file;
+use merc_sabre::SabreRewriter;
+use merc_syntax::UntypedProcessSpecification;
+use merc_tools::DeterministicFlag;
+use merc_tools::ProgressFlag;
+use merc_tools::Version;
+use merc_tools::VersionFlag;
+use merc_tools::parse_with_config;
+use merc_tools::verbosity::VerbosityFlag;
+use merc_unsafety::print_allocator_metrics;
+use merc_utilities::MercError;
+use merc_utilities::Timing;
+use merc_utilities::init_logger;
+
+#[derive(clap::Parser, Debug)]
+#[command(
+    about = "Generates and checks the state space of a linear process using term rewriting",
+    arg_required_else_help = true
+)]
+struct Cli {
+    #[command(flatten)]
+    version: VersionFlag,
+
+    #[command(flatten)]
+    verbosity: VerbosityFlag,
+
+    #[command(flatten)]
+    progress: ProgressFlag,
+
+    #[command(flatten)]
+    deterministic: DeterministicFlag,
+
+    #[command(subcommand)]
+    commands: Option<Commands>,
+
+    #[arg(long, global = true)]
+    timings: bool,
+}
+
+#[derive(Debug, Subcommand)]
+enum Commands {
+    Generate(GenerateArgs),
+    Check(CheckArgs),
+}
+
+#[derive(clap::Args, Debug)]
+#[command(about = "Generates the state space of a linear process")]
+struct GenerateArgs {
+    /// The rewrite specification in the REC format that defines the data.
+    specification: PathBuf,
+
+    /// The linear process of which the state space is generated, or an mCRL2 specification with the `.mcrl2` extension.
+    process: PathBuf,
+
+    /// The output LTS, which is printed in the AUT format when omitted.
+    output: Option<PathBuf>,
+
+    #[arg(long, help = "Explicitly specify the LTS output file format")]
+    output_filetype: Option<LtsFormat>,
+
+    #[arg(long, default_value_t = 1, help = "The number of threads used for exploration")]
+    threads: usize,
+
+    #[arg(
+        long,
+        conflicts_with = "threads",
+        help = "Only expand a subset of the summands in every state, which preserves the given behaviour"
+    )]
+    partial_order: Option<PartialOrderReduction>,
+
+    #[arg(
+        long,
+        conflicts_with_all = ["threads", "partial_order"],
+        help = "Periodically saves the progress to the given file, and continues from it when it already exists"
+    )]
+    checkpoint: Option<PathBuf>,
+
+    #[arg(
+        long,
+        default_value_t = 100_000,
+        requires = "checkpoint",
+        help = "The number of explored states between two checkpoints"
+    )]
+    checkpoint_interval: usize,
+}
+
+#[derive(clap::Args, Debug)]
+#[command(about = "Checks whether a deadlock or an action is reachable, and prints a shortest trace towards it")]
+struct CheckArgs {
+    /// The rewrite specification in the REC format that defines the data.
+    specification: PathBuf,
+
+    /// The linear process of which the state space is explored, or an mCRL2 specification with the `.mcrl2` extension.
+    process: PathBuf,
+
+    #[arg(
+        long,
+        conflicts_with = "action",
+        required_unless_present = "action",
+        help = "Check whether a deadlock is reachable"
+    )]
+    deadlock: bool,
+
+    #[arg(
+        long,
+        help = "Check whether the given action, or an action with this name, is reachable"
+    )]
+    action: Option<String>,
+}
+
+fn main() -> Result<ExitCode, MercError> {
+    let cli: Cli = parse_with_config();
+
+    init_logger(cli.verbosity.log_level_filter());
+    cli.progress.install()?;
+
+    if cli.version.into() {
+        eprintln!("{}", Version);
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    let mut timing = Timing::new();
+    let deterministic = cli.deterministic.into();
+
+    if let Some(command) = cli.commands {
+        match command {
+            Commands::Generate(args) => handle_generate(args, deterministic, &mut timing)?,
+            Commands::Check(args) => handle_check(args, &mut timing)?,
+        }
+    }
+
+    if cli.timings {
+        timing.print();
+    }
+
+    print_allocator_metrics();
+    print_term_pool_metrics();
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Generates the state space and writes it to the output. In deterministic mode
+/// the states are numbered in breadth-first order, also when exploring with
+/// multiple threads.
+fn handle_generate(args: GenerateArgs, deterministic: bool, timing: &mut Timing) -> Result<(), MercError> {
+    let mut parse_time = timing.start("parse");
+    let (spec, _) = load_rec_from_file(args.specification.clone())?;
+    let process = load_process(&args.process)?;
+    parse_time.finish();
+
+    let mut explore_time = timing.start("explore");
+    let lts = if args.threads > 1 {
+        // Every worker requires its own rewriter, since terms cannot be shared between threads.
+        explore_parallel(&process, args.threads, deterministic, || {
+            let (spec, _) = load_rec_from_file(args.specification.clone())?;
+            Ok(SabreRewriter::new(&spec.to_rewrite_spec()))
+        })?
+    } else if let Some(reduction) = args.partial_order {
+        let mut rewriter = SabreRewriter::new(&spec.to_rewrite_spec());
+        explore_reduced(&process, &mut rewriter, &SummandDependencies::new(&process), reduction)?
+    } else if let Some(checkpoint) = &args.checkpoint {
+        let mut rewriter = SabreRewriter::new(&spec.to_rewrite_spec());
+        explore_with_checkpoint(&process, &mut rewriter, checkpoint, args.checkpoint_interval)?
+    } else {
+        let mut rewriter = SabreRewriter::new(&spec.to_rewrite_spec());
+        explore(&process, &mut rewriter)?
+    };
+    explore_time.finish();
+
+    eprintln!(
+        "Generated {} states and {} transitions",
+        lts.num_of_states(),
+        lts.num_of_transitions()
+    );
+
+    if let Some(output) = &args.output {
+        let format = guess_lts_format_from_extension(output, args.output_filetype).ok_or("Unknown LTS file format.")?;
+        write_explicit_lts(output, format, GenericLts::Aut(lts), timing)?;
+    } else {
+        write_aut(&mut stdout(), &lts)?;
+    }
+
+    Ok(())
+}
+
+/// Explores the state space until the property is found, and prints the trace towards it.
+fn handle_check(args: CheckArgs, timing: &mut Timing) -> Result<(), MercError> {
+    let mut parse_time = timing.start("parse");
+    let (spec, _) = load_rec_from_file(args.specification)?;
+    let process = load_process(&args.process)?;
+    parse_time.finish();
+
+    let property = match args.action {
+        Some(action) => Property::Action(action),
+        None => Property::Deadlock,
+    };
+
+    let mut check_time = timing.start("check");
+    let mut rewriter = SabreRewriter::new(&spec.to_rewrite_spec());
+    let result = check(&process, &mut rewriter, &property)?;
+    check_time.finish();
+
+    match result {
+        Some(trace) => {
+            eprintln!("Found {property} after {} steps:", trace.labels.len());
+            println!("{trace}");
+        }
+        None => {
+            eprintln!("No {property} is reachable");
+        }
+    }
+
+    Ok(())
+}
+
+/// Loads a linear process, where mCRL2 specifications are linearised first.
+fn load_process(path: &Path) -> Result<LinearProcess, MercError> {
+    let text = std::fs::read_to_string(path)?;
+
+    if path.extension().is_some_and(|extension| extension == "mcrl2") {
+        linearise(&UntypedProcessSpecification::parse(&text)?)
+    } else {
+        LinearProcess::from_string(&text)
+    }
+}
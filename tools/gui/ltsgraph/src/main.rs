@@ -29,7 +29,6 @@ use wgpu::TextureFormat;
 use wgpu::TextureUsages;
 
 use merc_io::LargeFormatter;
-use merc_lts::apply_lts;
 use merc_lts::guess_lts_format_from_extension;
 use merc_lts::read_explicit_lts;
 use merc_lts::LabelledTransitionSystem;
@@ -40,10 +39,12 @@ use merc_ltsgraph_lib::GraphLayout;
 use merc_ltsgraph_lib::SkiaRenderer;
 use merc_ltsgraph_lib::Viewer;
 use merc_tools::console;
+use merc_tools::parse_with_config;
 use merc_tools::verbosity::VerbosityFlag;
 use merc_tools::Version;
 use merc_utilities::MercError;
 use merc_utilities::Timing;
+use merc_utilities::init_logger;
 
 use merc_ltsgraph::init_wgpu;
 use merc_ltsgraph::show_error_dialog;
@@ -135,12 +136,9 @@ async fn main() -> Result<ExitCode, MercError> {
     // Attach the standard output to the command line.
     let _console = console::init()?;
 
-    let cli = Cli::parse();
+    let cli: Cli = parse_with_config();
 
-    env_logger::Builder::new()
-        .filter_level(cli.verbosity.log_level_filter())
-        .parse_default_env()
-        .init();
+    init_logger(cli.verbosity.log_level_filter());
 
     if cli.version {
         eprintln!("{}", Version);
@@ -495,8 +493,7 @@ async fn main() -> Result<ExitCode, MercError> {
             match read_explicit_lts(path, format, vec![], &mut timing) {
                 Ok(lts) => {
                     // Ensure that the labels are strings, such that they can displayed.
-                    let lts: Arc<LabelledTransitionSystem<String>> =
-                        apply_lts!(lts, (), |lts, _| { Arc::new(lts.relabel(|label| label.to_string())) });
+                    let lts: Arc<LabelledTransitionSystem<String>> = Arc::new(lts.into_string_lts());
 
                     info!(
                         "Loaded lts with {} states and {} transitions",
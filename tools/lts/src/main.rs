@@ -1,33 +1,71 @@
 use std::fs::File;
+use std::io::stdin;
 use std::io::stdout;
 use std::path::Path;
 use std::path::PathBuf;
 use std::process::ExitCode;
 
-use clap::Parser;
 use clap::Subcommand;
+use clap::ValueEnum;
+use clap::builder::PossibleValue;
+use clap::builder::PossibleValuesParser;
 use log::info;
+use serde_json::json;
 
+use merc_io::DEFAULT_SVG_MAX_NODES;
 use merc_io::LargeFormatter;
 use merc_lts::GenericLts;
+use merc_lts::Histogram;
 use merc_lts::LTS;
+use merc_lts::LabelTransformation;
 use merc_lts::LtsFormat;
-use merc_lts::apply_lts;
-use merc_lts::apply_lts_pair;
+use merc_lts::LtsVariant;
+use merc_lts::canonicalize_lts_pair;
+use merc_lts::deadlock_states;
+use merc_lts::divergent_states;
 use merc_lts::guess_lts_format_from_extension;
+use merc_lts::in_degree_histogram;
+use merc_lts::label_statistics;
+use merc_lts::livelocks;
+use merc_lts::out_degree_histogram;
+use merc_lts::project_labels;
 use merc_lts::read_explicit_lts;
+use merc_lts::tau_scc_sizes;
+use merc_lts::transform_labels;
 use merc_lts::write_aut;
-use merc_lts::write_bcg;
+use merc_lts::write_dot;
+use merc_lts::write_explicit_lts;
+use merc_lts::write_fsm;
+use merc_lts::write_svg;
 use merc_preorder::RefinementType;
 use merc_preorder::refines;
 use merc_reduction::Equivalence;
-use merc_reduction::reduce_lts;
+use merc_reduction::Preprocess;
+use merc_reduction::ReductionRegistry;
+use merc_reduction::classify_equivalence;
+use merc_reduction::confluence_reduction;
+use merc_syntax::UntypedStateFrmSpec;
+use merc_tools::GenerateCompletionsArgs;
+use merc_tools::OutputFormatFlag;
+use merc_tools::ProgressFlag;
+use merc_tools::Report;
 use merc_tools::Version;
 use merc_tools::VersionFlag;
+use merc_tools::generate_completions;
+use merc_tools::install_interrupt_handler;
+use merc_tools::parse_with_config;
 use merc_tools::verbosity::VerbosityFlag;
 use merc_unsafety::print_allocator_metrics;
+use merc_unsafety::register_timing_memory_probe;
 use merc_utilities::MercError;
 use merc_utilities::Timing;
+use merc_utilities::init_logger;
+use merc_vpg::model_check_lts;
+use merc_vpg::model_check_lts_with_evidence;
+
+mod simulate;
+
+use simulate::*;
 
 #[derive(clap::Parser, Debug)]
 #[command(
@@ -41,6 +79,12 @@ struct Cli {
     #[command(flatten)]
     verbosity: VerbosityFlag,
 
+    #[command(flatten)]
+    progress: ProgressFlag,
+
+    #[command(flatten)]
+    output_format: OutputFormatFlag,
+
     #[command(subcommand)]
     commands: Option<Commands>,
 
@@ -56,6 +100,11 @@ enum Commands {
     Compare(CompareArgs),
     Refines(RefinesArgs),
     Convert(ConvertArgs),
+    Transform(TransformArgs),
+    Check(CheckArgs),
+    Simulate(SimulateArgs),
+    Project(ProjectArgs),
+    GenerateCompletions(GenerateCompletionsArgs),
 }
 
 #[derive(clap::Args, Debug)]
@@ -63,12 +112,20 @@ enum Commands {
 struct InfoArgs {
     filename: String,
     filetype: Option<LtsFormat>,
+
+    #[arg(
+        long,
+        help = "Print detailed statistics, such as degree histograms, tau-SCCs, divergent states and the determinism of labels"
+    )]
+    detailed: bool,
 }
 
 #[derive(clap::Args, Debug)]
 #[command(about = "Reduces the given LTS modulo an equivalent relation")]
 struct ReduceArgs {
-    equivalence: Equivalence,
+    /// The equivalence, or another registered reduction algorithm, to reduce modulo
+    #[arg(value_parser = reduction_algorithms())]
+    equivalence: String,
 
     /// Specify the input LTS.
     filename: PathBuf,
@@ -85,6 +142,9 @@ struct ReduceArgs {
         value_delimiter = ','
     )]
     tau: Option<Vec<String>>,
+
+    #[arg(long, help = "Preprocess the LTS before the reduction")]
+    preprocess: Option<Preprocess>,
 }
 
 #[derive(clap::Args, Debug)]
@@ -108,10 +168,18 @@ struct CompareArgs {
         value_delimiter = ','
     )]
     tau: Option<Vec<String>>,
+
+    #[arg(
+        long,
+        help = "Also report the finest supported equivalences that hold between both LTSs"
+    )]
+    classify: bool,
 }
 
 #[derive(clap::Args, Debug)]
-#[command(about = "Converts an LTS from one format to another")]
+#[command(
+    about = "Converts an LTS from one format to another, where the formats are derived from the file extensions (aut, lts, bcg, fsm, dot or svg)"
+)]
 struct ConvertArgs {
     #[arg(long, help = "Explicitly specify the LTS input file format")]
     input_filetype: Option<LtsFormat>,
@@ -132,6 +200,123 @@ struct ConvertArgs {
         value_delimiter = ','
     )]
     tau: Option<Vec<String>>,
+
+    #[arg(long, default_value_t = DEFAULT_SVG_MAX_NODES, help = "The maximum number of states that is rendered as svg")]
+    svg_max_states: usize,
+}
+
+#[derive(clap::Args, Debug)]
+#[command(about = "Renames, hides and removes the labels of an LTS by means of a script of operations")]
+struct TransformArgs {
+    #[arg(long, help = "Explicitly specify the LTS input file format")]
+    input_filetype: Option<LtsFormat>,
+
+    /// Specify the input LTS.
+    filename: PathBuf,
+
+    #[arg(long, help = "Explicitly specify the LTS output file format")]
+    output_filetype: Option<LtsFormat>,
+
+    /// Specify the output LTS, which is printed in the AUT format when omitted.
+    output: Option<PathBuf>,
+
+    #[arg(
+        long,
+        help = "File containing the operations, one per line: rename <pattern> <replacement>, hide <actions> or remove <actions>"
+    )]
+    script: Option<PathBuf>,
+
+    #[arg(
+        short = 'e',
+        long = "operation",
+        help = "An operation that is applied after the operations of the script, can be given multiple times"
+    )]
+    operations: Vec<String>,
+
+    #[arg(
+        short,
+        long,
+        help = "List of actions that should be considered tau actions",
+        value_delimiter = ','
+    )]
+    tau: Option<Vec<String>>,
+}
+
+#[derive(clap::Args, Debug)]
+#[command(about = "Restricts the given LTS to the part that is reachable using only the given actions")]
+struct ProjectArgs {
+    #[arg(long, help = "Explicitly specify the LTS input file format")]
+    input_filetype: Option<LtsFormat>,
+
+    /// Specify the input LTS.
+    filename: PathBuf,
+
+    #[arg(long, help = "Explicitly specify the LTS output file format")]
+    output_filetype: Option<LtsFormat>,
+
+    /// Specify the output LTS, which is printed in the AUT format when omitted.
+    output: Option<PathBuf>,
+
+    #[arg(
+        long,
+        required = true,
+        help = "List of actions whose transitions are kept, use i for the internal action",
+        value_delimiter = ','
+    )]
+    labels: Vec<String>,
+
+    #[arg(
+        short,
+        long,
+        help = "List of actions that should be considered tau actions",
+        value_delimiter = ','
+    )]
+    tau: Option<Vec<String>>,
+}
+
+#[derive(clap::Args, Debug)]
+#[command(about = "Interactively walks the transitions of the given LTS, starting in the initial state")]
+struct SimulateArgs {
+    /// Specify the input LTS.
+    filename: PathBuf,
+
+    #[arg(long, help = "Explicitly specify the LTS file format")]
+    filetype: Option<LtsFormat>,
+
+    #[arg(
+        short,
+        long,
+        help = "List of actions that should be considered tau actions",
+        value_delimiter = ','
+    )]
+    tau: Option<Vec<String>>,
+}
+
+#[derive(clap::Args, Debug)]
+#[command(about = "Checks whether the given modal mu-calculus formula holds in the initial state of the LTS")]
+struct CheckArgs {
+    /// Specify the input LTS.
+    filename: PathBuf,
+
+    /// Specify the file containing the state formula, typically with the `.mcf` extension.
+    formula: PathBuf,
+
+    #[arg(long, help = "Explicitly specify the LTS file format")]
+    filetype: Option<LtsFormat>,
+
+    #[arg(
+        long,
+        help = "Write a witness or counterexample to the given .aut file and print the annotated states"
+    )]
+    evidence: Option<PathBuf>,
+
+    #[arg(
+        short,
+        long,
+        help = "List of actions that should be considered tau actions",
+        value_delimiter = ','
+    )]
+    tau: Option<Vec<String>>,
 }
 
 #[derive(clap::Args, Debug)]
@@ -153,112 +338,273 @@ struct RefinesArgs {
 }
 
 fn main() -> Result<ExitCode, MercError> {
-    let cli = Cli::parse();
+    let cli: Cli = parse_with_config();
 
-    env_logger::Builder::new()
-        .filter_level(cli.verbosity.log_level_filter())
-        .parse_default_env()
-        .init();
+    init_logger(cli.verbosity.log_level_filter());
+    cli.progress.install()?;
 
     if cli.version.into() {
         eprintln!("{}", Version);
         return Ok(ExitCode::SUCCESS);
     }
 
+    register_timing_memory_probe();
+
+    install_interrupt_handler()?;
+
     let mut timing = Timing::new();
+    let mut report = Report::new("merc-lts", cli.output_format.format());
 
     if let Some(command) = &cli.commands {
         match command {
             Commands::Info(args) => {
-                handle_info(args, &mut timing)?;
+                handle_info(args, &mut report, &mut timing)?;
             }
             Commands::Reduce(args) => {
-                handle_reduce(args, &mut timing)?;
+                handle_reduce(args, &mut report, &mut timing)?;
             }
             Commands::Compare(args) => {
-                handle_compare(args, &mut timing)?;
+                handle_compare(args, &mut report, &mut timing)?;
             }
             Commands::Refines(args) => {
-                handle_refinement(args, &mut timing)?;
+                handle_refinement(args, &mut report, &mut timing)?;
             }
             Commands::Convert(args) => {
-                handle_convert(args, &mut timing)?;
+                handle_convert(args, &mut report, &mut timing)?;
+            }
+            Commands::Transform(args) => {
+                handle_transform(args, &mut report, &mut timing)?;
+            }
+            Commands::Check(args) => {
+                handle_check(args, &mut report, &mut timing)?;
+            }
+            Commands::Simulate(args) => {
+                handle_simulate(args, &report, &mut timing)?;
+            }
+            Commands::Project(args) => {
+                handle_project(args, &mut report, &mut timing)?;
+            }
+            Commands::GenerateCompletions(args) => {
+                generate_completions::<Cli>(args)?;
+                return Ok(ExitCode::SUCCESS);
             }
         }
     }
 
-    if cli.timings {
-        timing.print();
-    }
+    report.finish(&timing, cli.timings)?;
 
     print_allocator_metrics();
     Ok(ExitCode::SUCCESS)
 }
 
 /// Display information about the given LTS.
-fn handle_info(args: &InfoArgs, timing: &mut Timing) -> Result<(), MercError> {
+fn handle_info(args: &InfoArgs, report: &mut Report, timing: &mut Timing) -> Result<(), MercError> {
     let path = Path::new(&args.filename);
 
     let format = guess_lts_format_from_extension(path, args.filetype).ok_or("Unknown LTS file format.")?;
-    let lts = read_explicit_lts(path, format, Vec::new(), timing)?;
-    println!(
+    let lts = LtsVariant::from(read_explicit_lts(path, format, Vec::new(), timing)?);
+    report.text(format!(
         "LTS has {} states and {} transitions.",
         LargeFormatter(lts.num_of_states()),
         LargeFormatter(lts.num_of_transitions())
-    );
+    ));
+    report.set("states", lts.num_of_states());
+    report.set("transitions", lts.num_of_transitions());
 
-    apply_lts!(lts, (), |lts, _| {
-        println!("Labels:");
-        for label in lts.labels() {
-            println!("  {}", label);
-        }
-    });
+    report.text("Labels:");
+    for label in lts.labels() {
+        report.text(format_args!("  {}", label));
+    }
+    report.set("labels", lts.labels().to_vec());
+
+    if args.detailed {
+        print_detailed_info(&lts, report, timing);
+    }
 
     Ok(())
 }
 
+/// Prints the detailed statistics of the given LTS for the `--detailed` option of `info`.
+fn print_detailed_info(lts: &impl LTS, report: &mut Report, timing: &mut Timing) {
+    let mut statistics_time = timing.start("statistics");
+    let out_degrees = out_degree_histogram(lts);
+    let in_degrees = in_degree_histogram(lts);
+    let num_of_deadlocks = deadlock_states(lts).len();
+    let scc_sizes = Histogram::from_values(tau_scc_sizes(lts));
+    let num_of_divergent = divergent_states(lts).len();
+    let livelocks = livelocks(lts);
+    let labels = label_statistics(lts);
+    statistics_time.finish();
+
+    for (name, histogram) in [("Out-degree", &out_degrees), ("In-degree", &in_degrees)] {
+        report.text(format!(
+            "{name}: min {}, max {}, mean {:.2}",
+            histogram.min().unwrap_or(0),
+            histogram.max().unwrap_or(0),
+            histogram.mean()
+        ));
+        for (degree, count) in histogram.iter() {
+            report.text(format!("  {degree}: {}", LargeFormatter(count)));
+        }
+    }
+
+    report.text(format!("Deadlock states: {}", LargeFormatter(num_of_deadlocks)));
+
+    // The branching factor only considers the states that have outgoing transitions.
+    let branching_factor = if num_of_deadlocks < lts.num_of_states() {
+        lts.num_of_transitions() as f64 / (lts.num_of_states() - num_of_deadlocks) as f64
+    } else {
+        0.0
+    };
+    report.text(format!("Branching factor: {branching_factor:.2}"));
+
+    let num_of_nontrivial_sccs: usize = scc_sizes
+        .iter()
+        .filter(|(size, _)| *size > 1)
+        .map(|(_, count)| count)
+        .sum();
+    report.text(format!(
+        "Tau-SCCs: {} of which {} non-trivial, largest has {} states",
+        LargeFormatter(scc_sizes.num_of_values()),
+        LargeFormatter(num_of_nontrivial_sccs),
+        LargeFormatter(scc_sizes.max().unwrap_or(0))
+    ));
+
+    report.text(format!(
+        "Divergent states: {}, livelocks: {} with {} states",
+        LargeFormatter(num_of_divergent),
+        LargeFormatter(livelocks.len()),
+        LargeFormatter(livelocks.iter().map(|livelock| livelock.len()).sum::<usize>())
+    ));
+
+    report.text("Label determinism:");
+    for (label, statistics) in lts.labels().iter().zip(&labels) {
+        report.text(format!(
+            "  {label}: {} transitions, max fan-out {}, {} nondeterministic states",
+            LargeFormatter(statistics.num_of_transitions),
+            statistics.max_fan_out,
+            LargeFormatter(statistics.nondeterministic_states)
+        ));
+    }
+
+    let histogram_json = |histogram: &Histogram| {
+        serde_json::Value::from_iter(histogram.iter().map(|(value, count)| json!([value, count])))
+    };
+    report.set("out_degree_histogram", histogram_json(&out_degrees));
+    report.set("in_degree_histogram", histogram_json(&in_degrees));
+    report.set("tau_scc_size_histogram", histogram_json(&scc_sizes));
+    report.set("deadlock_states", num_of_deadlocks);
+    report.set("divergent_states", num_of_divergent);
+    report.set("livelocks", livelocks.len());
+    report.set("branching_factor", branching_factor);
+    report.set(
+        "label_statistics",
+        serde_json::Value::from_iter(lts.labels().iter().zip(&labels).map(|(label, statistics)| {
+            json!({
+                "label": label.to_string(),
+                "transitions": statistics.num_of_transitions,
+                "max_fan_out": statistics.max_fan_out,
+                "nondeterministic_states": statistics.nondeterministic_states,
+            })
+        })),
+    );
+}
+
 /// Reduce the given LTS into another LTS modulo any of the supported equivalences.
-fn handle_reduce(args: &ReduceArgs, timing: &mut Timing) -> Result<(), MercError> {
+fn handle_reduce(args: &ReduceArgs, report: &mut Report, timing: &mut Timing) -> Result<(), MercError> {
     let path = Path::new(&args.filename);
     let format = guess_lts_format_from_extension(path, args.filetype).ok_or("Unknown LTS file format.")?;
 
-    let lts = read_explicit_lts(path, format, args.tau.clone().unwrap_or_default(), timing)?;
+    if report.is_json() && args.output.is_none() {
+        return Err("An output path must be specified when using the JSON output format.".into());
+    }
+
+    let registry = reduction_registry();
+    let algorithm = registry
+        .get(&args.equivalence)
+        .ok_or_else(|| format!("Unknown reduction algorithm {}.", args.equivalence))?;
+
+    if let Some(preprocess) = args.preprocess {
+        if !algorithm.is_preserved_by(preprocess) {
+            return Err(format!(
+                "Preprocessing with {preprocess:?} does not preserve {}.",
+                algorithm.name()
+            )
+            .into());
+        }
+    }
+
+    let lts = LtsVariant::from(read_explicit_lts(
+        path,
+        format,
+        args.tau.clone().unwrap_or_default(),
+        timing,
+    )?);
     info!(
         "LTS has {} states and {} transitions.",
         LargeFormatter(lts.num_of_states()),
         LargeFormatter(lts.num_of_transitions())
     );
-
-    apply_lts!(lts, timing, |lts, timing| -> Result<(), MercError> {
-        let reduced_lts = reduce_lts(lts, args.equivalence, timing);
-
-        info!(
-            "Reduced LTS has {} states and {} transitions.",
-            LargeFormatter(reduced_lts.num_of_states()),
-            LargeFormatter(reduced_lts.num_of_transitions())
-        );
-
-        if let Some(file) = &args.output {
-            let mut writer = File::create(file)?;
-            write_aut(&mut writer, &reduced_lts)?;
-        } else {
-            write_aut(&mut stdout(), &reduced_lts)?;
+    report.set("states", lts.num_of_states());
+    report.set("transitions", lts.num_of_transitions());
+
+    let reduced_lts = match args.preprocess {
+        Some(Preprocess::Confluence) => {
+            let preprocessed_lts = confluence_reduction(lts, &mut timing.scope("confluence"))?;
+            info!(
+                "Confluence reduced LTS has {} states and {} transitions.",
+                LargeFormatter(preprocessed_lts.num_of_states()),
+                LargeFormatter(preprocessed_lts.num_of_transitions())
+            );
+            report.set("preprocessed_states", preprocessed_lts.num_of_states());
+            report.set("preprocessed_transitions", preprocessed_lts.num_of_transitions());
+
+            algorithm.reduce(LtsVariant::Explicit(preprocessed_lts), &mut timing.scope("reduce"))?
         }
+        None => algorithm.reduce(lts, &mut timing.scope("reduce"))?,
+    };
+    info!(
+        "Reduced LTS has {} states and {} transitions.",
+        LargeFormatter(reduced_lts.num_of_states()),
+        LargeFormatter(reduced_lts.num_of_transitions())
+    );
 
-        Ok(())
-    })?;
+    if let Some(file) = &args.output {
+        let mut writer = File::create(file)?;
+        write_aut(&mut writer, &reduced_lts)?;
+    } else {
+        write_aut(&mut stdout(), &reduced_lts)?;
+    }
 
+    report.set("reduced_states", reduced_lts.num_of_states());
+    report.set("reduced_transitions", reduced_lts.num_of_transitions());
     Ok(())
 }
 
+/// Returns the reduction algorithms that can be selected by the `reduce`
+/// subcommand. Crates that provide additional algorithms can be added as
+/// optional dependencies behind a feature, and registered here.
+fn reduction_registry() -> ReductionRegistry {
+    ReductionRegistry::with_builtin()
+}
+
+/// Returns the names of the algorithms in the [reduction_registry] as possible values for clap.
+fn reduction_algorithms() -> PossibleValuesParser {
+    PossibleValuesParser::new(
+        reduction_registry()
+            .iter()
+            .map(|algorithm| PossibleValue::new(algorithm.name()).help(algorithm.description())),
+    )
+}
+
 /// Handles the refinement checking between two LTSs.
-fn handle_refinement(args: &RefinesArgs, timing: &mut Timing) -> Result<(), MercError> {
+fn handle_refinement(args: &RefinesArgs, report: &mut Report, timing: &mut Timing) -> Result<(), MercError> {
     let impl_path = Path::new(&args.implementation_filename);
     let spec_path = Path::new(&args.specification_filename);
     let format = guess_lts_format_from_extension(impl_path, args.filetype).ok_or("Unknown LTS file format.")?;
 
-    let impl_lts = read_explicit_lts(impl_path, format, Vec::new(), timing)?;
-    let spec_lts = read_explicit_lts(spec_path, format, Vec::new(), timing)?;
+    let impl_lts = LtsVariant::from(read_explicit_lts(impl_path, format, Vec::new(), timing)?);
+    let spec_lts = LtsVariant::from(read_explicit_lts(spec_path, format, Vec::new(), timing)?);
 
     info!(
         "Implementation LTS has {} states and {} transitions.",
@@ -271,37 +617,128 @@ fn handle_refinement(args: &RefinesArgs, timing: &mut Timing) -> Result<(), Merc
         LargeFormatter(spec_lts.num_of_transitions())
     );
 
-    let refines = apply_lts_pair!(impl_lts, spec_lts, timing, |left, right, timing| {
-        refines(left, right, args.refinement, timing)
-    });
+    let mut time_labels = timing.start("canonicalize_labels");
+    let (impl_lts, spec_lts) = canonicalize_lts_pair(&impl_lts, &spec_lts);
+    time_labels.finish();
+
+    let refines = refines(impl_lts, spec_lts, args.refinement, &mut timing.scope("refines"))?;
+
+    report.text(refines);
+    report.set("refines", refines);
+
+    Ok(())
+}
+
+/// Checks a modal mu-calculus formula on the given LTS by solving the corresponding parity game.
+fn handle_check(args: &CheckArgs, report: &mut Report, timing: &mut Timing) -> Result<(), MercError> {
+    let format = guess_lts_format_from_extension(&args.filename, args.filetype).ok_or("Unknown LTS file format.")?;
+
+    let lts = LtsVariant::from(read_explicit_lts(
+        &args.filename,
+        format,
+        args.tau.clone().unwrap_or_default(),
+        timing,
+    )?);
+    info!(
+        "LTS has {} states and {} transitions.",
+        LargeFormatter(lts.num_of_states()),
+        LargeFormatter(lts.num_of_transitions())
+    );
 
-    if refines {
-        println!("true");
+    let formula = UntypedStateFrmSpec::parse(&std::fs::read_to_string(&args.formula)?)?;
+
+    let mut check_time = timing.start("check");
+    let holds = if let Some(file) = &args.evidence {
+        let evidence = model_check_lts_with_evidence(&lts, &formula.formula)?;
+        write_aut(&mut File::create(file)?, &evidence.lts)?;
+
+        let verb = if evidence.holds { "satisfies" } else { "violates" };
+        for (state, subformula) in &evidence.annotations {
+            report.text(format!("state {state} {verb} {subformula}"));
+        }
+        evidence.holds
     } else {
-        println!("false");
+        model_check_lts(&lts, &formula.formula)?
+    };
+    check_time.finish();
+
+    report.text(holds);
+    report.set("holds", holds);
+
+    Ok(())
+}
+
+/// Restricts the given LTS to the transitions with the given labels, and keeps the reachable part.
+fn handle_project(args: &ProjectArgs, report: &mut Report, timing: &mut Timing) -> Result<(), MercError> {
+    let format =
+        guess_lts_format_from_extension(&args.filename, args.input_filetype).ok_or("Unknown LTS file format.")?;
+
+    if report.is_json() && args.output.is_none() {
+        return Err("An output path must be specified when using the JSON output format.".into());
+    }
+
+    let input_lts = read_explicit_lts(&args.filename, format, args.tau.clone().unwrap_or_default(), timing)?;
+    report.set("states", input_lts.num_of_states());
+    report.set("transitions", input_lts.num_of_transitions());
+
+    let mut project_time = timing.start("project");
+    let lts = project_labels(&input_lts.into_string_lts(), &args.labels);
+    project_time.finish();
+
+    info!(
+        "Projected LTS has {} states and {} transitions.",
+        LargeFormatter(lts.num_of_states()),
+        LargeFormatter(lts.num_of_transitions())
+    );
+    report.set("projected_states", lts.num_of_states());
+    report.set("projected_transitions", lts.num_of_transitions());
+
+    if let Some(output) = &args.output {
+        let output_format =
+            guess_lts_format_from_extension(output, args.output_filetype).ok_or("Unknown LTS file format.")?;
+        write_explicit_lts(output, output_format, GenericLts::Aut(lts), timing)?;
+    } else {
+        write_aut(&mut stdout(), &lts)?;
     }
 
     Ok(())
 }
 
+/// Interactively simulates the given LTS using the commands read from stdin.
+fn handle_simulate(args: &SimulateArgs, report: &Report, timing: &mut Timing) -> Result<(), MercError> {
+    if report.is_json() {
+        return Err("The simulation is interactive and does not support the JSON output format.".into());
+    }
+
+    let format = guess_lts_format_from_extension(&args.filename, args.filetype).ok_or("Unknown LTS file format.")?;
+    let lts = LtsVariant::from(read_explicit_lts(
+        &args.filename,
+        format,
+        args.tau.clone().unwrap_or_default(),
+        timing,
+    )?);
+
+    simulate(&lts, stdin().lock(), &mut stdout())
+}
+
 /// Compares two LTSs for equivalence modulo any of the available equivalences.
-fn handle_compare(args: &CompareArgs, timing: &mut Timing) -> Result<(), MercError> {
+fn handle_compare(args: &CompareArgs, report: &mut Report, timing: &mut Timing) -> Result<(), MercError> {
     let format =
         guess_lts_format_from_extension(&args.left_filename, args.filetype).ok_or("Unknown LTS file format.")?;
 
     info!("Assuming format {:?} for both LTSs.", format);
-    let left_lts = read_explicit_lts(
+    let left_lts = LtsVariant::from(read_explicit_lts(
         &args.left_filename,
         format,
         args.tau.clone().unwrap_or_default(),
         timing,
-    )?;
-    let right_lts = read_explicit_lts(
+    )?);
+    let right_lts = LtsVariant::from(read_explicit_lts(
         &args.right_filename,
         format,
         args.tau.clone().unwrap_or_default(),
         timing,
-    )?;
+    )?);
 
     info!(
         "Left LTS has {} states and {} transitions.",
@@ -314,24 +751,45 @@ fn handle_compare(args: &CompareArgs, timing: &mut Timing) -> Result<(), MercErr
         LargeFormatter(right_lts.num_of_transitions())
     );
 
-    let equivalent = apply_lts_pair!(left_lts, right_lts, timing, |left, right, timing| {
-        merc_reduction::compare_lts(args.equivalence, left, right, timing)
-    });
-
-    if equivalent {
-        println!("true");
-    } else {
-        println!("false");
+    let mut time_labels = timing.start("canonicalize_labels");
+    let (left_lts, right_lts) = canonicalize_lts_pair(&left_lts, &right_lts);
+    time_labels.finish();
+
+    if args.classify {
+        let finest = classify_equivalence(left_lts.clone(), right_lts.clone(), &mut timing.scope("classify"))?;
+        let names: Vec<String> = finest
+            .iter()
+            .filter_map(|equivalence| equivalence.to_possible_value())
+            .map(|value| value.get_name().to_string())
+            .collect();
+        if names.is_empty() {
+            report.text("None of the supported equivalences hold.");
+        } else {
+            report.text(format!("Finest equivalences that hold: {}", names.join(", ")));
+        }
+        report.set("classification", names);
     }
 
+    let equivalent = merc_reduction::compare_lts(args.equivalence, left_lts, right_lts, &mut timing.scope("compare"))?;
+
+    report.text(equivalent);
+    report.set("equivalent", equivalent);
+
     Ok(())
 }
 
 /// Converts an LTS from one format to another, does not do any reduction, see [handle_reduce] for that.
-fn handle_convert(args: &ConvertArgs, timing: &mut Timing) -> Result<(), MercError> {
+fn handle_convert(args: &ConvertArgs, report: &mut Report, timing: &mut Timing) -> Result<(), MercError> {
     let format =
         guess_lts_format_from_extension(&args.filename, args.input_filetype).ok_or("Unknown LTS file format.")?;
+
+    if report.is_json() && args.output.is_none() {
+        return Err("An output path must be specified when using the JSON output format.".into());
+    }
+
     let input_lts = read_explicit_lts(&args.filename, format, args.tau.clone().unwrap_or_default(), timing)?;
+    report.set("states", input_lts.num_of_states());
+    report.set("transitions", input_lts.num_of_transitions());
 
     let output_format = if let Some(output) = &args.output {
         guess_lts_format_from_extension(output, args.output_filetype).ok_or("Unknown LTS file format.")?
@@ -341,53 +799,75 @@ fn handle_convert(args: &ConvertArgs, timing: &mut Timing) -> Result<(), MercErr
         return Err("Either output path or output file format must be specified.".into());
     };
 
-    match input_lts {
-        GenericLts::Aut(lts) => match output_format {
-            LtsFormat::Bcg => {
-                if let Some(path) = &args.output {
-                    write_bcg(&lts, path)?;
-                } else {
-                    return Err("Output path must be specified when writing BCG files.".into());
-                }
-            }
-            LtsFormat::Aut => {
-                return Err("Conversion from AUT to AUT is not useful.".into());
-            }
-            _ => {
-                return Err(format!("Conversion to {output_format:?}LTS format is not yet implemented.").into());
-            }
-        },
-        GenericLts::Lts(lts) => match output_format {
-            LtsFormat::Aut => {
-                if let Some(path) = &args.output {
-                    write_aut(&mut File::create(path)?, &lts.relabel(|label| label.to_string()))?;
-                } else {
-                    write_aut(&mut stdout(), &lts.relabel(|label| label.to_string()))?;
-                }
-            }
-            LtsFormat::Bcg => {
-                if let Some(path) = &args.output {
-                    write_bcg(&lts.relabel(|label| label.to_string()), path)?;
-                } else {
-                    return Err("Output path must be specified when writing BCG files.".into());
-                }
-            }
-            LtsFormat::Lts => {
-                return Err("Conversion from LTS to LTS is not useful.".into());
-            }
-        },
-        GenericLts::Bcg(lts) => match output_format {
-            LtsFormat::Aut => {
-                if let Some(path) = &args.output {
-                    write_aut(&mut File::create(path)?, &lts)?;
-                } else {
-                    write_aut(&mut stdout(), &lts)?;
-                }
-            }
-            _ => {
-                return Err(format!("Conversion to {output_format:?}LTS format is not yet implemented.").into());
+    if let Some(path) = &args.output {
+        if output_format == LtsFormat::Svg {
+            // Written directly, since write_explicit_lts uses the default maximum number of states.
+            write_svg(
+                &mut File::create(path)?,
+                &input_lts.into_string_lts(),
+                args.svg_max_states,
+            )?;
+        } else {
+            write_explicit_lts(path, output_format, input_lts, timing)?;
+        }
+    } else {
+        match output_format {
+            LtsFormat::Aut => write_aut(&mut stdout(), &input_lts.into_string_lts())?,
+            LtsFormat::Fsm => write_fsm(&mut stdout(), &input_lts.into_string_lts())?,
+            LtsFormat::Dot => write_dot(&mut stdout(), &input_lts.into_string_lts())?,
+            LtsFormat::Svg => write_svg(&mut stdout(), &input_lts.into_string_lts(), args.svg_max_states)?,
+            LtsFormat::Lts | LtsFormat::Bcg => {
+                return Err(format!("An output path must be specified when writing {output_format:?} files.").into());
             }
-        },
+        }
+    }
+
+    Ok(())
+}
+
+/// Applies the label transformations of the script and the command line to the given LTS.
+fn handle_transform(args: &TransformArgs, report: &mut Report, timing: &mut Timing) -> Result<(), MercError> {
+    let format =
+        guess_lts_format_from_extension(&args.filename, args.input_filetype).ok_or("Unknown LTS file format.")?;
+
+    if report.is_json() && args.output.is_none() {
+        return Err("An output path must be specified when using the JSON output format.".into());
+    }
+
+    let mut transformations = if let Some(script) = &args.script {
+        LabelTransformation::parse_script(&std::fs::read_to_string(script)?)?
+    } else {
+        Vec::new()
+    };
+    for operation in &args.operations {
+        transformations.push(LabelTransformation::parse(operation)?);
+    }
+
+    if transformations.is_empty() {
+        return Err("At least one operation must be given using --script or --operation.".into());
+    }
+
+    let input_lts = read_explicit_lts(&args.filename, format, args.tau.clone().unwrap_or_default(), timing)?;
+    report.set("states", input_lts.num_of_states());
+    report.set("transitions", input_lts.num_of_transitions());
+
+    let mut transform_time = timing.start("transform");
+    let lts = transform_labels(&input_lts.into_string_lts(), &transformations);
+    transform_time.finish();
+
+    info!(
+        "Transformed LTS has {} states and {} transitions.",
+        LargeFormatter(lts.num_of_states()),
+        LargeFormatter(lts.num_of_transitions())
+    );
+    report.set("transformed_transitions", lts.num_of_transitions());
+
+    if let Some(output) = &args.output {
+        let output_format =
+            guess_lts_format_from_extension(output, args.output_filetype).ok_or("Unknown LTS file format.")?;
+        write_explicit_lts(output, output_format, GenericLts::Aut(lts), timing)?;
+    } else {
+        write_aut(&mut stdout(), &lts)?;
     }
 
     Ok(())
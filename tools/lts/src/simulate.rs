@@ -0,0 +1,131 @@
+//! A line based interactive simulator that walks the transitions of an LTS.
+
+use std::fs::File;
+use std::io::BufRead;
+use std::io::Write;
+
+use merc_lts::IncomingTransitions;
+use merc_lts::LTS;
+use merc_lts::LabelIndex;
+use merc_lts::StateIndex;
+use merc_lts::Transition;
+use merc_utilities::MercError;
+
+const HELP: &str = "Commands:
+  <number>       take the transition with the given number
+  back, b        undo the last transition
+  reset, r       return to the initial state
+  incoming, i    show the incoming transitions of the current state
+  trace, t       show the current trace
+  save <file>    write the labels of the current trace to the given file, one per line
+  help, h        show this help
+  quit, q        stop the simulation";
+
+/// Interactively simulates the given LTS, reading the commands from `input`
+/// and printing the states and their outgoing transitions to `output`. The
+/// simulation stops at the `quit` command or at the end of the input.
+pub fn simulate<L: LTS>(lts: &L, input: impl BufRead, output: &mut impl Write) -> Result<(), MercError> {
+    let incoming = IncomingTransitions::new(lts);
+
+    // The taken transitions, where the current state is the target of the last one.
+    let mut trace: Vec<(LabelIndex, StateIndex)> = Vec::new();
+    let current = |trace: &[(LabelIndex, StateIndex)]| {
+        trace
+            .last()
+            .map_or(lts.initial_state_index(), |(_, state_index)| *state_index)
+    };
+
+    writeln!(output, "{HELP}")?;
+    let mut choices = print_state(lts, current(&trace), output)?;
+
+    for line in input.lines() {
+        let line = line?;
+        let mut words = line.split_whitespace();
+        let Some(command) = words.next() else {
+            continue;
+        };
+
+        match command {
+            "back" | "b" => {
+                if trace.pop().is_none() {
+                    writeln!(output, "Already in the initial state")?;
+                }
+            }
+            "reset" | "r" => trace.clear(),
+            "incoming" | "i" => {
+                for transition in incoming.incoming_transitions(current(&trace)) {
+                    writeln!(output, "  {} -{}->", transition.to, lts.labels()[transition.label])?;
+                }
+                continue;
+            }
+            "trace" | "t" => {
+                write!(output, "{}", lts.initial_state_index())?;
+                for (label, state_index) in &trace {
+                    write!(output, " -{}-> {}", lts.labels()[*label], state_index)?;
+                }
+                writeln!(output)?;
+                continue;
+            }
+            "save" => {
+                let Some(path) = words.next() else {
+                    writeln!(output, "Expected a file name")?;
+                    continue;
+                };
+
+                let mut file = File::create(path)?;
+                for (label, _) in &trace {
+                    writeln!(file, "{}", lts.labels()[*label])?;
+                }
+                writeln!(output, "Saved a trace of {} transitions to {path}", trace.len())?;
+                continue;
+            }
+            "help" | "h" => {
+                writeln!(output, "{HELP}")?;
+                continue;
+            }
+            "quit" | "q" => break,
+            _ => match command.parse::<usize>() {
+                Ok(choice) if choice < choices.len() => {
+                    let transition = &choices[choice];
+                    trace.push((transition.label, transition.to));
+                }
+                _ => {
+                    writeln!(
+                        output,
+                        "Unknown command or transition '{command}', type 'help' for the commands"
+                    )?;
+                    continue;
+                }
+            },
+        }
+
+        choices = print_state(lts, current(&trace), output)?;
+    }
+
+    Ok(())
+}
+
+/// Prints the given state with its numbered outgoing transitions, which are returned.
+fn print_state<L: LTS>(
+    lts: &L,
+    state_index: StateIndex,
+    output: &mut impl Write,
+) -> Result<Vec<Transition>, MercError> {
+    let transitions: Vec<Transition> = lts.outgoing_transitions(state_index).collect();
+
+    writeln!(output, "State {state_index}:")?;
+    if transitions.is_empty() {
+        writeln!(output, "  deadlock")?;
+    }
+
+    for (index, transition) in transitions.iter().enumerate() {
+        writeln!(
+            output,
+            "  {index}: -{}-> {}",
+            lts.labels()[transition.label],
+            transition.to
+        )?;
+    }
+
+    Ok(transitions)
+}
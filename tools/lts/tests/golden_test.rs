@@ -0,0 +1,32 @@
+use std::path::Path;
+
+use merc_tools::check_golden;
+use test_case::test_case;
+
+/// Runs merc-lts from the repository root and compares the output to the golden file in `tests/golden`.
+fn golden_test(args: &[&str], golden: &str) {
+    let manifest = Path::new(env!("CARGO_MANIFEST_DIR"));
+    check_golden(
+        Path::new(env!("CARGO_BIN_EXE_merc-lts")),
+        &manifest.join("../.."),
+        args,
+        &manifest.join("tests/golden").join(golden),
+    )
+    .unwrap();
+}
+
+#[cfg_attr(miri, ignore)]
+#[test_case(&["info", "examples/lts/abp.aut"], "info_abp.txt" ; "info abp")]
+#[test_case(&["info", "examples/lts/abp.lts"], "info_abp_lts.txt" ; "info abp lts")]
+#[test_case(&["info", "--detailed", "examples/lts/abp.aut"], "info_detailed_abp.txt" ; "info detailed abp")]
+#[test_case(&["--output-format", "json", "info", "examples/lts/abp.aut"], "info_abp.json" ; "info json abp")]
+#[test_case(&["reduce", "strong-bisim", "examples/lts/abp.aut"], "reduce_strong_abp.aut" ; "reduce strong abp")]
+#[test_case(&["reduce", "branching-bisim", "examples/lts/abp.aut"], "reduce_branching_abp.aut" ; "reduce branching abp")]
+#[test_case(&["reduce", "branching-bisim", "examples/lts/selfloops.aut"], "reduce_branching_selfloops.aut" ; "reduce branching selfloops")]
+#[test_case(&["compare", "branching-bisim", "examples/lts/abp.aut", "examples/lts/abp.aut"], "compare_branching_abp.txt" ; "compare branching abp")]
+#[test_case(&["refines", "trace", "examples/lts/abp.aut", "examples/lts/abp.aut"], "refines_trace_abp.txt" ; "refines trace abp")]
+#[test_case(&["convert", "--output-filetype", "fsm", "examples/lts/abp.aut"], "convert_abp.fsm" ; "convert abp fsm")]
+#[test_case(&["convert", "examples/lts/abp.aut", "{output}.dot"], "convert_abp.dot" ; "convert abp dot")]
+fn test_merc_lts(args: &[&str], golden: &str) {
+    golden_test(args, golden);
+}
@@ -1,17 +1,22 @@
 use std::process::ExitCode;
 
-use clap::Parser;
 use clap::Subcommand;
 use log::info;
 
 use mcrl2::Pbes;
 use mcrl2::set_reporting_level;
 use mcrl2::verbosity_to_log_level_t;
+use merc_tools::GenerateCompletionsArgs;
+use merc_tools::OutputFormatFlag;
+use merc_tools::Report;
 use merc_tools::VerbosityFlag;
 use merc_tools::Version;
 use merc_tools::VersionFlag;
+use merc_tools::generate_completions;
+use merc_tools::parse_with_config;
 use merc_utilities::MercError;
 use merc_utilities::Timing;
+use merc_utilities::init_logger;
 
 use crate::permutation::Permutation;
 use crate::symmetry::SymmetryAlgorithm;
@@ -38,6 +43,9 @@ struct Cli {
     #[command(flatten)]
     verbosity: VerbosityFlag,
 
+    #[command(flatten)]
+    output_format: OutputFormatFlag,
+
     #[arg(long, global = true)]
     timings: bool,
 
@@ -48,6 +56,7 @@ struct Cli {
 #[derive(Debug, Subcommand)]
 enum Commands {
     Symmetry(SymmetryArgs),
+    GenerateCompletions(GenerateCompletionsArgs),
 }
 
 /// Arguments for solving a parity game
@@ -70,12 +79,9 @@ struct SymmetryArgs {
 }
 
 fn main() -> Result<ExitCode, MercError> {
-    let cli = Cli::parse();
+    let cli: Cli = parse_with_config();
 
-    env_logger::Builder::new()
-        .filter_level(cli.verbosity.log_level_filter())
-        .parse_default_env()
-        .init();
+    init_logger(cli.verbosity.log_level_filter());
 
     // Enable logging on the mCRL2 side
     set_reporting_level(verbosity_to_log_level_t(cli.verbosity.verbosity()));
@@ -86,37 +92,45 @@ fn main() -> Result<ExitCode, MercError> {
     }
 
     let timing = Timing::new();
-
-    if let Some(Commands::Symmetry(args)) = cli.commands {
-        let format = args.format.unwrap_or(PbesFormat::Pbes);
-
-        let pbes = match format {
-            PbesFormat::Pbes => Pbes::from_file(&args.filename)?,
-            PbesFormat::Text => Pbes::from_text_file(&args.filename)?,
-        };
-
-        let algorithm = SymmetryAlgorithm::new(&pbes, false)?;
-        if let Some(permutation) = &args.permutation {
-            let pi = Permutation::from_input(permutation)?;
-            if algorithm.check_symmetry(&pi) {
-                println!("true");
+    let mut report = Report::new("merc-pbes", cli.output_format.format());
+
+    match cli.commands {
+        Some(Commands::Symmetry(args)) => {
+            let format = args.format.unwrap_or(PbesFormat::Pbes);
+
+            let pbes = match format {
+                PbesFormat::Pbes => Pbes::from_file(&args.filename)?,
+                PbesFormat::Text => Pbes::from_text_file(&args.filename)?,
+            };
+
+            let algorithm = SymmetryAlgorithm::new(&pbes, false)?;
+            if let Some(permutation) = &args.permutation {
+                let pi = Permutation::from_input(permutation)?;
+                let symmetric = algorithm.check_symmetry(&pi);
+                report.text(symmetric);
+                report.set("symmetric", symmetric);
             } else {
-                println!("false");
-            }
-        } else {
-            for candidate in algorithm.candidates(args.partition_data_sorts) {
-                info!("Found candidate: {}", candidate);
-
-                if algorithm.check_symmetry(&candidate) {
-                    info!("Found symmetry: {}", candidate);
+                let mut symmetries = Vec::new();
+                for candidate in algorithm.candidates(args.partition_data_sorts) {
+                    info!("Found candidate: {}", candidate);
+
+                    if algorithm.check_symmetry(&candidate) {
+                        info!("Found symmetry: {}", candidate);
+                        symmetries.push(candidate.to_string());
+                    }
                 }
+
+                report.set("symmetries", symmetries);
             }
         }
+        Some(Commands::GenerateCompletions(args)) => {
+            generate_completions::<Cli>(&args)?;
+            return Ok(ExitCode::SUCCESS);
+        }
+        None => {}
     }
 
-    if cli.timings {
-        timing.print();
-    }
+    report.finish(&timing, cli.timings)?;
 
     Ok(ExitCode::SUCCESS)
 }
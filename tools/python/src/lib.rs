@@ -0,0 +1,46 @@
+//! Python bindings for the LTS reduction and term rewriting functionality of
+//! MERC, built using [PyO3](https://pyo3.rs). See the README for usage.
+
+use pyo3::exceptions::PyRuntimeError;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use merc_utilities::MercError;
+
+mod lts;
+mod rewrite;
+mod term;
+
+pub use lts::*;
+pub use rewrite::*;
+pub use term::*;
+
+/// Converts a [MercError] into a Python exception.
+pub(crate) fn to_py_err(error: MercError) -> PyErr {
+    PyRuntimeError::new_err(error.to_string().trim_end().to_string())
+}
+
+/// Parses the given value using the clap value names, which are the same names as used by the command line tools.
+pub(crate) fn parse_value<T: clap::ValueEnum>(value: &str, kind: &str) -> PyResult<T> {
+    T::from_str(value, true).map_err(|_| {
+        let options: Vec<String> = T::value_variants()
+            .iter()
+            .filter_map(|variant| variant.to_possible_value())
+            .map(|value| value.get_name().to_string())
+            .collect();
+
+        PyValueError::new_err(format!(
+            "Unknown {kind} '{value}', expected one of: {}",
+            options.join(", ")
+        ))
+    })
+}
+
+/// The `merc` Python module.
+#[pymodule]
+fn merc(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyLts>()?;
+    m.add_class::<PyRewriteSystem>()?;
+    m.add_class::<PyTerm>()?;
+    Ok(())
+}
@@ -0,0 +1,170 @@
+use std::path::PathBuf;
+
+use pyo3::prelude::*;
+
+use merc_lts::GenericLts;
+use merc_lts::LTS;
+use merc_lts::LabelledTransitionSystem;
+use merc_lts::LtsFormat;
+use merc_lts::canonicalize_lts_pair;
+use merc_lts::guess_lts_format_from_extension;
+use merc_lts::read_aut;
+use merc_lts::read_explicit_lts;
+use merc_lts::write_aut;
+use merc_lts::write_explicit_lts;
+use merc_preorder::RefinementType;
+use merc_preorder::refines;
+use merc_reduction::Equivalence;
+use merc_reduction::compare_lts;
+use merc_reduction::reduce_lts;
+use merc_utilities::MercError;
+use merc_utilities::Timing;
+
+use crate::parse_value;
+use crate::to_py_err;
+
+/// A labelled transition system, where the labels are represented by strings.
+///
+/// # Details
+///
+/// All potentially long running operations, such as reading, reducing and
+/// comparing, release the GIL so that other Python threads can continue.
+#[pyclass(name = "Lts", module = "merc", frozen)]
+pub struct PyLts {
+    lts: LabelledTransitionSystem<String>,
+}
+
+#[pymethods]
+impl PyLts {
+    /// Reads an LTS from the given file. The format is derived from the
+    /// extension unless `format` is given, and the labels in `tau` are
+    /// considered to be internal actions.
+    #[staticmethod]
+    #[pyo3(signature = (path, format = None, tau = None))]
+    fn read(py: Python<'_>, path: PathBuf, format: Option<&str>, tau: Option<Vec<String>>) -> PyResult<PyLts> {
+        let format = format
+            .map(|format| parse_value::<LtsFormat>(format, "LTS format"))
+            .transpose()?;
+
+        py.detach(|| -> Result<PyLts, MercError> {
+            let format = guess_lts_format_from_extension(&path, format).ok_or("Unknown LTS file format.")?;
+            let lts = match read_explicit_lts(&path, format, tau.unwrap_or_default(), &mut Timing::new())? {
+                GenericLts::Aut(lts) | GenericLts::Bcg(lts) => lts,
+                GenericLts::Lts(lts) => lts.relabel(|label| label.to_string()),
+            };
+
+            Ok(PyLts { lts })
+        })
+        .map_err(to_py_err)
+    }
+
+    /// Parses an LTS in the Aldebaran (.aut) format from the given text.
+    #[staticmethod]
+    #[pyo3(signature = (text, tau = None))]
+    fn from_aut(text: &str, tau: Option<Vec<String>>) -> PyResult<PyLts> {
+        let lts = read_aut(text.as_bytes(), tau.unwrap_or_default()).map_err(to_py_err)?;
+        Ok(PyLts { lts })
+    }
+
+    /// Writes the LTS to the given file, the format is derived from the extension unless `format` is given.
+    #[pyo3(signature = (path, format = None))]
+    fn write(&self, py: Python<'_>, path: PathBuf, format: Option<&str>) -> PyResult<()> {
+        let format = format
+            .map(|format| parse_value::<LtsFormat>(format, "LTS format"))
+            .transpose()?;
+
+        py.detach(|| -> Result<(), MercError> {
+            let format = guess_lts_format_from_extension(&path, format).ok_or("Unknown LTS file format.")?;
+            write_explicit_lts(&path, format, GenericLts::Aut(self.lts.clone()), &mut Timing::new())
+        })
+        .map_err(to_py_err)
+    }
+
+    /// Returns the LTS in the Aldebaran (.aut) format.
+    fn to_aut(&self) -> PyResult<String> {
+        let mut output = Vec::new();
+        write_aut(&mut output, &self.lts).map_err(to_py_err)?;
+        Ok(String::from_utf8_lossy(&output).into_owned())
+    }
+
+    /// Returns the LTS reduced modulo the given equivalence, for example `strong-bisim` or `branching-bisim`.
+    fn reduce(&self, py: Python<'_>, equivalence: &str) -> PyResult<PyLts> {
+        let equivalence = parse_value::<Equivalence>(equivalence, "equivalence")?;
+
+        py.detach(|| reduce_lts(self.lts.clone(), equivalence, &mut Timing::new()))
+            .map(|lts| PyLts { lts })
+            .map_err(to_py_err)
+    }
+
+    /// Returns true iff this LTS is equivalent to the other LTS modulo the given equivalence.
+    fn compare(&self, py: Python<'_>, other: &PyLts, equivalence: &str) -> PyResult<bool> {
+        let equivalence = parse_value::<Equivalence>(equivalence, "equivalence")?;
+
+        py.detach(|| {
+            let (left, right) = canonicalize_lts_pair(&self.lts, &other.lts);
+            compare_lts(equivalence, left, right, &mut Timing::new())
+        })
+        .map_err(to_py_err)
+    }
+
+    /// Returns true iff this (implementation) LTS refines the given specification LTS modulo the given preorder.
+    #[pyo3(signature = (specification, refinement = "trace"))]
+    fn refines(&self, py: Python<'_>, specification: &PyLts, refinement: &str) -> PyResult<bool> {
+        let refinement = parse_value::<RefinementType>(refinement, "refinement")?;
+
+        py.detach(|| {
+            let (implementation, specification) = canonicalize_lts_pair(&self.lts, &specification.lts);
+            refines(implementation, specification, refinement, &mut Timing::new())
+        })
+        .map_err(to_py_err)
+    }
+
+    /// The index of the initial state.
+    #[getter]
+    fn initial_state(&self) -> usize {
+        self.lts.initial_state_index().value()
+    }
+
+    /// The number of states.
+    #[getter]
+    fn num_of_states(&self) -> usize {
+        self.lts.num_of_states()
+    }
+
+    /// The number of transitions.
+    #[getter]
+    fn num_of_transitions(&self) -> usize {
+        self.lts.num_of_transitions()
+    }
+
+    /// The labels of the LTS, where the first label is the internal action.
+    #[getter]
+    fn labels(&self) -> Vec<String> {
+        self.lts.labels().to_vec()
+    }
+
+    /// Returns all transitions as `(from, label, to)` tuples.
+    fn transitions(&self) -> Vec<(usize, String, usize)> {
+        self.lts
+            .iter_states()
+            .flat_map(|from| {
+                self.lts.outgoing_transitions(from).map(move |transition| {
+                    (
+                        from.value(),
+                        self.lts.labels()[transition.label.value()].clone(),
+                        transition.to.value(),
+                    )
+                })
+            })
+            .collect()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Lts(states={}, transitions={}, labels={})",
+            self.lts.num_of_states(),
+            self.lts.num_of_transitions(),
+            self.lts.num_of_labels()
+        )
+    }
+}
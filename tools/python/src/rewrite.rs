@@ -0,0 +1,121 @@
+use std::fs;
+use std::path::PathBuf;
+
+use pyo3::prelude::*;
+
+use merc_aterm::ATerm;
+use merc_data::DataExpression;
+use merc_data::to_untyped_data_expression;
+use merc_rec_tests::load_rec_from_strings;
+use merc_sabre::InnermostRewriter;
+use merc_sabre::NaiveRewriter;
+use merc_sabre::RewriteEngine;
+use merc_sabre::SabreRewriter;
+use merc_utilities::MercError;
+
+use crate::PyTerm;
+use crate::to_py_err;
+
+/// Selects the rewriter to use, the same options as the `merc-rewrite` tool.
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+enum Rewriter {
+    Naive,
+    Innermost,
+    Sabre,
+}
+
+/// A rewrite system given by a specification in the REC format.
+///
+/// # Details
+///
+/// Rewriting releases the GIL. Since terms are stored in a thread local term
+/// pool the specification is (re)constructed within every call, so it is more
+/// efficient to rewrite many terms at once.
+#[pyclass(name = "RewriteSystem", module = "merc", frozen)]
+pub struct PyRewriteSystem {
+    specification: String,
+}
+
+#[pymethods]
+impl PyRewriteSystem {
+    /// Creates a rewrite system from the given REC specification.
+    #[new]
+    fn new(specification: String) -> PyResult<PyRewriteSystem> {
+        // Parse the specification once to report errors early.
+        load_rec_from_strings(&[&specification]).map_err(to_py_err)?;
+        Ok(PyRewriteSystem { specification })
+    }
+
+    /// Reads a rewrite system from the given REC file.
+    #[staticmethod]
+    fn read(path: PathBuf) -> PyResult<PyRewriteSystem> {
+        PyRewriteSystem::new(fs::read_to_string(path)?)
+    }
+
+    /// Rewrites the given terms to normal form using the given rewriter, and
+    /// returns the normal forms in the same order.
+    #[pyo3(signature = (terms, rewriter = "sabre"))]
+    fn rewrite(&self, py: Python<'_>, terms: Vec<String>, rewriter: &str) -> PyResult<Vec<String>> {
+        let rewriter = crate::parse_value::<Rewriter>(rewriter, "rewriter")?;
+
+        py.detach(|| -> Result<Vec<String>, MercError> {
+            let terms = terms
+                .iter()
+                .map(|term| ATerm::from_string(term))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            Ok(self
+                .rewrite_terms(rewriter, Some(terms))?
+                .iter()
+                .map(|result| result.to_string())
+                .collect())
+        })
+        .map_err(to_py_err)
+    }
+
+    /// Rewrites the given term to normal form using the given rewriter.
+    #[pyo3(signature = (term, rewriter = "sabre"))]
+    fn rewrite_term(&self, term: &PyTerm, rewriter: &str) -> PyResult<PyTerm> {
+        let rewriter = crate::parse_value::<Rewriter>(rewriter, "rewriter")?;
+
+        let mut results = self
+            .rewrite_terms(rewriter, Some(vec![term.term().clone()]))
+            .map_err(to_py_err)?;
+        Ok(PyTerm::new(results.remove(0).into()))
+    }
+
+    /// Rewrites the terms given in the `EVAL` section of the specification to normal form.
+    #[pyo3(signature = (rewriter = "sabre"))]
+    fn normal_forms(&self, py: Python<'_>, rewriter: &str) -> PyResult<Vec<String>> {
+        let rewriter = crate::parse_value::<Rewriter>(rewriter, "rewriter")?;
+
+        py.detach(|| -> Result<Vec<String>, MercError> {
+            Ok(self
+                .rewrite_terms(rewriter, None)?
+                .iter()
+                .map(|result| result.to_string())
+                .collect())
+        })
+        .map_err(to_py_err)
+    }
+}
+
+impl PyRewriteSystem {
+    /// Rewrites the given terms, or the terms of the specification when none are given.
+    fn rewrite_terms(&self, rewriter: Rewriter, terms: Option<Vec<ATerm>>) -> Result<Vec<DataExpression>, MercError> {
+        let (syntax_spec, syntax_terms) = load_rec_from_strings(&[&self.specification])?;
+        let spec = syntax_spec.to_rewrite_spec();
+
+        let mut engine: Box<dyn RewriteEngine> = match rewriter {
+            Rewriter::Naive => Box::new(NaiveRewriter::new(&spec)),
+            Rewriter::Innermost => Box::new(InnermostRewriter::new(&spec)),
+            Rewriter::Sabre => Box::new(SabreRewriter::new(&spec)),
+        };
+
+        Ok(terms
+            .unwrap_or(syntax_terms)
+            .into_iter()
+            .map(|term| engine.rewrite(&to_untyped_data_expression(term, None)))
+            .collect())
+    }
+}
@@ -0,0 +1,79 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::Hash;
+use std::hash::Hasher;
+
+use pyo3::prelude::*;
+
+use merc_aterm::ATerm;
+use merc_aterm::Symb;
+use merc_aterm::Term;
+
+use crate::to_py_err;
+
+/// A maximally shared first-order term.
+///
+/// # Details
+///
+/// Terms are stored in a thread local term pool, and can therefore only be
+/// used on the Python thread that created them.
+#[pyclass(name = "Term", module = "merc", unsendable)]
+pub struct PyTerm {
+    term: ATerm,
+}
+
+impl PyTerm {
+    /// Wraps the given term.
+    pub fn new(term: ATerm) -> Self {
+        Self { term }
+    }
+
+    /// Returns the underlying term.
+    pub fn term(&self) -> &ATerm {
+        &self.term
+    }
+}
+
+#[pymethods]
+impl PyTerm {
+    /// Parses a term from the given text, for example `f(a, g(b))`.
+    #[new]
+    fn parse(text: &str) -> PyResult<PyTerm> {
+        Ok(PyTerm::new(ATerm::from_string(text).map_err(to_py_err)?))
+    }
+
+    /// The name of the head symbol.
+    #[getter]
+    fn head(&self) -> String {
+        self.term.get_head_symbol().name().to_string()
+    }
+
+    /// The number of arguments.
+    #[getter]
+    fn arity(&self) -> usize {
+        self.term.get_head_symbol().arity()
+    }
+
+    /// The arguments of the term.
+    #[getter]
+    fn arguments(&self) -> Vec<PyTerm> {
+        self.term.arguments().map(|arg| PyTerm::new(arg.protect())).collect()
+    }
+
+    fn __str__(&self) -> String {
+        self.term.to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Term('{}')", self.term)
+    }
+
+    fn __eq__(&self, other: &PyTerm) -> bool {
+        self.term == other.term
+    }
+
+    fn __hash__(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.term.hash(&mut hasher);
+        hasher.finish()
+    }
+}
@@ -0,0 +1,37 @@
+import merc
+
+ABP = """des (0, 4, 4)
+(0, "a", 1)
+(1, "i", 2)
+(2, "b", 3)
+(3, "i", 0)
+"""
+
+
+def test_lts_reduce():
+    lts = merc.Lts.from_aut(ABP)
+    assert lts.num_of_states == 4
+    assert lts.num_of_transitions == 4
+
+    reduced = lts.reduce("branching-bisim")
+    assert reduced.num_of_states == 2
+    assert lts.compare(reduced, "branching-bisim")
+    assert not lts.compare(reduced, "strong-bisim")
+    assert lts.refines(reduced, "trace")
+
+
+def test_unknown_equivalence():
+    lts = merc.Lts.from_aut(ABP)
+
+    try:
+        lts.reduce("unknown")
+        assert False
+    except ValueError:
+        pass
+
+
+def test_term():
+    term = merc.Term("f(a, g(b))")
+    assert term.head == "f"
+    assert term.arity == 2
+    assert term.arguments[1] == merc.Term("g(b)")
@@ -2,13 +2,19 @@ use std::fmt::Debug;
 use std::time::Instant;
 
 use clap::ValueEnum;
+use itertools::Itertools;
+use log::warn;
 
 use merc_data::to_untyped_data_expression;
 use merc_rec_tests::load_rec_from_file;
+use merc_sabre::DEFAULT_TERMINATION_LIMIT;
 use merc_sabre::InnermostRewriter;
 use merc_sabre::NaiveRewriter;
 use merc_sabre::RewriteEngine;
+use merc_sabre::RewriteStats;
 use merc_sabre::SabreRewriter;
+use merc_sabre::Termination;
+use merc_sabre::check_termination;
 use merc_utilities::MercError;
 
 /// Selects the rewriter to use.
@@ -19,11 +25,25 @@ pub enum Rewriter {
     Sabre,
 }
 
-/// Rewrites the given REC specification.
-pub fn rewrite_rec(rewriter: Rewriter, filename_specification: &str, output: bool) -> Result<(), MercError> {
+/// Rewrites the given REC specification. The innermost rewriter caches the
+/// normal forms of at most `cache` subterms between the terms when given. The
+/// statistics of rewriting all terms are added to `stats` when given.
+pub fn rewrite_rec(
+    rewriter: Rewriter,
+    filename_specification: &str,
+    output: bool,
+    cache: Option<usize>,
+    mut stats: Option<&mut RewriteStats>,
+) -> Result<(), MercError> {
     let (syntax_spec, syntax_terms) = load_rec_from_file(filename_specification.into())?;
 
     let spec = syntax_spec.to_rewrite_spec();
+    if let Termination::Unknown(rules) = check_termination(&spec, DEFAULT_TERMINATION_LIMIT) {
+        warn!(
+            "The specification is possibly non-terminating, since no lexicographic path ordering orients the rules:\n{}",
+            rules.iter().format("\n")
+        );
+    }
 
     match rewriter {
         Rewriter::Naive => {
@@ -32,7 +52,7 @@ pub fn rewrite_rec(rewriter: Rewriter, filename_specification: &str, output: boo
             let now = Instant::now();
             for term in &syntax_terms {
                 let term = to_untyped_data_expression(term.clone(), None);
-                let result = inner.rewrite(&term);
+                let result = inner.rewrite_with_stats(&term, stats.as_deref_mut());
                 if output {
                     println!("{}", result)
                 }
@@ -41,16 +61,22 @@ pub fn rewrite_rec(rewriter: Rewriter, filename_specification: &str, output: boo
         }
         Rewriter::Innermost => {
             let mut inner = InnermostRewriter::new(&spec);
+            if let Some(capacity) = cache {
+                inner = inner.with_cache(capacity);
+            }
 
             let now = Instant::now();
             for term in &syntax_terms {
                 let term = to_untyped_data_expression(term.clone(), None);
-                let result = inner.rewrite(&term);
+                let result = inner.rewrite_with_stats(&term, stats.as_deref_mut());
                 if output {
                     println!("{}", result)
                 }
             }
             println!("Innermost rewrite took {} ms", now.elapsed().as_millis());
+            if let Some(cache) = inner.cache() {
+                println!("Found {} normal forms in the cache", cache.hits());
+            }
         }
         Rewriter::Sabre => {
             let mut sa = SabreRewriter::new(&spec);
@@ -58,7 +84,7 @@ pub fn rewrite_rec(rewriter: Rewriter, filename_specification: &str, output: boo
             let now = Instant::now();
             for term in &syntax_terms {
                 let term = to_untyped_data_expression(term.clone(), None);
-                let result = sa.rewrite(&term);
+                let result = sa.rewrite_with_stats(&term, stats.as_deref_mut());
                 if output {
                     println!("{}", result)
                 }
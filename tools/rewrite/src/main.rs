@@ -2,18 +2,22 @@ use std::fs::File;
 use std::io::Write;
 use std::process::ExitCode;
 
-use clap::Parser;
 use clap::Subcommand;
 
+use merc_aterm::storage::print_term_pool_metrics;
 use merc_rec_tests::load_rec_from_file;
+use merc_tools::ProgressFlag;
 use merc_tools::VerbosityFlag;
 use merc_tools::Version;
 use merc_tools::VersionFlag;
+use merc_tools::parse_with_config;
 use merc_unsafety::print_allocator_metrics;
 use merc_utilities::MercError;
+use merc_utilities::init_logger;
 
 use merc_rewrite::Rewriter;
 use merc_rewrite::rewrite_rec;
+use merc_sabre::RewriteStats;
 
 mod trs_format;
 
@@ -28,8 +32,14 @@ struct Cli {
     #[command(flatten)]
     verbosity: VerbosityFlag,
 
+    #[command(flatten)]
+    progress: ProgressFlag,
+
     #[command(subcommand)]
     commands: Option<Commands>,
+
+    #[arg(long, global = true)]
+    timings: bool,
 }
 
 #[derive(Debug, Subcommand)]
@@ -51,6 +61,13 @@ struct RewriteArgs {
 
     #[arg(long = "output", default_value_t = false, help = "Print the rewritten term(s)")]
     output: bool,
+
+    #[arg(
+        long,
+        value_name = "CAPACITY",
+        help = "Cache the normal forms of at most CAPACITY subterms between the terms, only used by the innermost rewriter"
+    )]
+    cache: Option<usize>,
 }
 
 #[derive(clap::Args, Debug)]
@@ -63,12 +80,10 @@ struct ConvertArgs {
 }
 
 fn main() -> Result<ExitCode, MercError> {
-    let cli = Cli::parse();
+    let cli: Cli = parse_with_config();
 
-    env_logger::Builder::new()
-        .filter_level(cli.verbosity.log_level_filter())
-        .parse_default_env()
-        .init();
+    init_logger(cli.verbosity.log_level_filter());
+    cli.progress.install()?;
 
     if cli.version.into() {
         eprintln!("{}", Version);
@@ -80,7 +95,18 @@ fn main() -> Result<ExitCode, MercError> {
             Commands::Rewrite(args) => {
                 if args.specification.ends_with(".rec") {
                     assert!(args.terms.is_none());
-                    rewrite_rec(args.rewriter, &args.specification, args.output)?;
+                    let mut stats = RewriteStats::default();
+                    rewrite_rec(
+                        args.rewriter,
+                        &args.specification,
+                        args.output,
+                        args.cache,
+                        cli.timings.then_some(&mut stats),
+                    )?;
+
+                    if cli.timings {
+                        print_stats(&stats);
+                    }
                 }
             }
             Commands::Convert(args) => {
@@ -97,5 +123,18 @@ fn main() -> Result<ExitCode, MercError> {
     }
 
     print_allocator_metrics();
+    print_term_pool_metrics();
     Ok(ExitCode::SUCCESS)
 }
+
+/// Prints the statistics of rewriting, with the rules ordered by the number of times they were applied.
+fn print_stats(stats: &RewriteStats) {
+    eprintln!("{stats}");
+    eprintln!("The largest term contains {} symbols", stats.max_term_size);
+
+    let mut rules: Vec<_> = stats.rule_applications.iter().collect();
+    rules.sort_unstable_by(|(left_rule, left), (right_rule, right)| right.cmp(left).then(left_rule.cmp(right_rule)));
+    for (rule, count) in rules {
+        eprintln!("{count:>10}  {rule}");
+    }
+}
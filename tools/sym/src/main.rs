@@ -1,24 +1,37 @@
 use std::fs::File;
+use std::io::BufWriter;
+use std::io::Write;
 use std::path::PathBuf;
 use std::process::ExitCode;
 
-use clap::Parser;
 use clap::Subcommand;
+use itertools::Itertools;
 
 use merc_io::LargeFormatter;
+use merc_ldd::DEFAULT_GARBAGE_COLLECTION_THRESHOLD;
 use merc_ldd::Storage;
+use merc_ldd::node_count;
+use merc_ldd::nodes_per_level;
+use merc_ldd::print_dot;
 use merc_symbolic::SymFormat;
 use merc_symbolic::SymbolicLTS;
 use merc_symbolic::guess_format_from_extension;
 use merc_symbolic::reachability;
 use merc_symbolic::read_sylvan;
 use merc_symbolic::read_symbolic_lts;
+use merc_tools::GenerateCompletionsArgs;
+use merc_tools::ProgressFlag;
 use merc_tools::Version;
 use merc_tools::VersionFlag;
+use merc_tools::generate_completions;
+use merc_tools::parse_with_config;
 use merc_tools::verbosity::VerbosityFlag;
+use merc_unsafety::FileReader;
 use merc_unsafety::print_allocator_metrics;
+use merc_unsafety::register_timing_memory_probe;
 use merc_utilities::MercError;
 use merc_utilities::Timing;
+use merc_utilities::init_logger;
 
 #[derive(clap::Parser, Debug)]
 #[command(
@@ -32,6 +45,9 @@ struct Cli {
     #[command(flatten)]
     verbosity: VerbosityFlag,
 
+    #[command(flatten)]
+    progress: ProgressFlag,
+
     #[command(subcommand)]
     commands: Option<Commands>,
 
@@ -44,12 +60,29 @@ struct Cli {
 enum Commands {
     Info(InfoArgs),
     Explore(ExploreArgs),
+    GenerateCompletions(GenerateCompletionsArgs),
 }
 
 #[derive(clap::Args, Debug)]
 #[command(about = "Prints information related to the given symbolic LTS")]
 struct InfoArgs {
     filename: PathBuf,
+
+    #[arg(
+        long,
+        help = "Print the number of LDD nodes per level and the size of the relation of every summand group"
+    )]
+    detailed: bool,
+
+    #[arg(long, help = "Write the LDD of the states in the dot format to the given file")]
+    dot: Option<PathBuf>,
+
+    #[arg(
+        long,
+        requires = "dot",
+        help = "Write the LDD of the relation of the given summand group instead of the states"
+    )]
+    dot_group: Option<usize>,
 }
 
 #[derive(clap::Args, Debug)]
@@ -58,27 +91,45 @@ struct ExploreArgs {
     filename: PathBuf,
 
     format: Option<SymFormat>,
+
+    #[arg(
+        long,
+        default_value_t = DEFAULT_GARBAGE_COLLECTION_THRESHOLD,
+        help = "The minimum number of LDD nodes that are created between two garbage collections"
+    )]
+    gc_threshold: usize,
+
+    #[arg(
+        long,
+        default_value_t = 1.0,
+        help = "The number of LDD nodes that are created until the next garbage collection relative to the number of remaining nodes"
+    )]
+    gc_growth: f64,
 }
 
 fn main() -> Result<ExitCode, MercError> {
-    let cli = Cli::parse();
+    let cli: Cli = parse_with_config();
 
-    env_logger::Builder::new()
-        .filter_level(cli.verbosity.log_level_filter())
-        .parse_default_env()
-        .init();
+    init_logger(cli.verbosity.log_level_filter());
+    cli.progress.install()?;
 
     if cli.version.into() {
         eprintln!("{}", Version);
         return Ok(ExitCode::SUCCESS);
     }
 
+    register_timing_memory_probe();
+
     let mut timing = Timing::new();
 
     if let Some(command) = cli.commands {
         match command {
             Commands::Info(args) => handle_info(args, &mut timing)?,
             Commands::Explore(args) => handle_explore(args, &mut timing)?,
+            Commands::GenerateCompletions(args) => {
+                generate_completions::<Cli>(&args)?;
+                return Ok(ExitCode::SUCCESS);
+            }
         }
     }
 
@@ -95,7 +146,7 @@ fn handle_info(args: InfoArgs, timing: &mut Timing) -> Result<(), MercError> {
     let mut storage = Storage::new();
 
     let mut time_read = timing.start("read_symbolic_lts");
-    let lts = read_symbolic_lts(&mut storage, File::open(&args.filename)?)?;
+    let lts = read_symbolic_lts(&mut storage, FileReader::open(&args.filename)?)?;
     time_read.finish();
 
     println!("Symbolic LTS information:");
@@ -104,17 +155,64 @@ fn handle_info(args: InfoArgs, timing: &mut Timing) -> Result<(), MercError> {
         LargeFormatter(merc_ldd::len(&mut storage, lts.states()))
     );
     println!("  Number of summand groups: {}", lts.transition_groups().len());
+    println!(
+        "  Number of LDD nodes: {} (states: {})",
+        LargeFormatter(storage.num_of_nodes()),
+        LargeFormatter(node_count(&storage, lts.states()))
+    );
+
+    if args.detailed {
+        println!(
+            "  LDD nodes per level: {}",
+            nodes_per_level(&storage, lts.states())
+                .iter()
+                .enumerate()
+                .format_with(", ", |(level, count), f| f(&format_args!(
+                    "{level}: {}",
+                    LargeFormatter(*count)
+                )))
+        );
+
+        println!("  Summand groups:");
+        for (index, group) in lts.summand_groups().iter().enumerate() {
+            println!(
+                "    {index}: {} read and {} write parameters, {} transitions, {} LDD nodes",
+                group.read_parameters().len(),
+                group.write_parameters().len(),
+                LargeFormatter(merc_ldd::len(&mut storage, group.relation())),
+                LargeFormatter(node_count(&storage, group.relation()))
+            );
+        }
+    }
+
+    if let Some(path) = &args.dot {
+        let ldd = match args.dot_group {
+            Some(index) => lts
+                .summand_groups()
+                .get(index)
+                .ok_or_else(|| format!("There is no summand group {index}"))?
+                .relation(),
+            None => lts.states(),
+        };
+
+        let mut writer = BufWriter::new(File::create(path)?);
+        print_dot(&storage, &mut writer, ldd)?;
+        writer.flush()?;
+    }
 
+    storage.print_metrics();
     Ok(())
 }
 
 /// Explores the given symbolic LTS.
 fn handle_explore(args: ExploreArgs, _timing: &mut Timing) -> Result<(), MercError> {
     let mut storage = Storage::new();
+    storage.set_garbage_collection_threshold(args.gc_threshold);
+    storage.set_garbage_collection_growth(args.gc_growth);
 
     let format = guess_format_from_extension(&args.filename, args.format).ok_or("Cannot determine input format")?;
 
-    let mut file = File::open(&args.filename)?;
+    let mut file = FileReader::open(&args.filename)?;
     let timing = Timing::new();
 
     match format {
@@ -132,5 +230,6 @@ fn handle_explore(args: ExploreArgs, _timing: &mut Timing) -> Result<(), MercErr
         }
     }
 
+    storage.print_metrics();
     Ok(())
 }
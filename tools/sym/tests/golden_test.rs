@@ -0,0 +1,24 @@
+use std::path::Path;
+
+use merc_tools::check_golden;
+use test_case::test_case;
+
+/// Runs merc-sym from the repository root and compares the output to the golden file in `tests/golden`.
+fn golden_test(args: &[&str], golden: &str) {
+    let manifest = Path::new(env!("CARGO_MANIFEST_DIR"));
+    check_golden(
+        Path::new(env!("CARGO_BIN_EXE_merc-sym")),
+        &manifest.join("../.."),
+        args,
+        &manifest.join("tests/golden").join(golden),
+    )
+    .unwrap();
+}
+
+#[cfg_attr(miri, ignore)]
+#[test_case(&["info", "examples/lts/WMS.sym"], "info_wms.txt" ; "info wms")]
+#[test_case(&["explore", "examples/ldd/anderson.4.ldd"], "explore_anderson_4.txt" ; "explore anderson 4")]
+#[test_case(&["explore", "examples/ldd/bakery.4.ldd"], "explore_bakery_4.txt" ; "explore bakery 4")]
+fn test_merc_sym(args: &[&str], golden: &str) {
+    golden_test(args, golden);
+}
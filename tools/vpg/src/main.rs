@@ -1,46 +1,70 @@
+use std::ffi::OsStr;
 use std::fs::File;
 use std::fs::read_to_string;
 use std::io::Write;
 use std::path::Path;
 use std::process::ExitCode;
 
-use clap::Parser;
 use clap::Subcommand;
 use duct::cmd;
 use itertools::Itertools;
 use log::debug;
 use log::info;
 use merc_vpg::make_vpg_total;
+use merc_vpg::make_vpg_total_backend;
 use merc_vpg::verify_variability_product_zielonka_solution;
 use oxidd::BooleanFunction;
+use oxidd::util::OptBool;
 
-use merc_symbolic::CubeIterAll;
+use merc_io::DEFAULT_SVG_MAX_NODES;
 use merc_symbolic::FormatConfig;
 use merc_syntax::UntypedStateFrmSpec;
+use merc_tools::GenerateCompletionsArgs;
+use merc_tools::OutputFormatFlag;
+use merc_tools::ProgressFlag;
+use merc_tools::Report;
 use merc_tools::VerbosityFlag;
 use merc_tools::Version;
 use merc_tools::VersionFlag;
+use merc_tools::generate_completions;
+use merc_tools::install_interrupt_handler;
+use merc_tools::parse_with_config;
 use merc_unsafety::print_allocator_metrics;
+use merc_unsafety::register_timing_memory_probe;
 use merc_utilities::MercError;
 use merc_utilities::Timing;
+use merc_utilities::init_logger;
+use merc_vpg::BddBackend;
+use merc_vpg::ConfigBackendType;
 use merc_vpg::FeatureDiagram;
+use merc_vpg::PG;
 use merc_vpg::ParityGameFormat;
 use merc_vpg::PgDot;
 use merc_vpg::Player;
+use merc_vpg::ProductSolution;
+use merc_vpg::Set;
+use merc_vpg::VariabilityParityGameSolution;
+use merc_vpg::VerificationMethod;
 use merc_vpg::VpgDot;
 use merc_vpg::ZielonkaVariant;
 use merc_vpg::compute_reachable;
 use merc_vpg::guess_format_from_extension;
+use merc_vpg::parse_configuration_set;
+use merc_vpg::project_variability_parity_game_partial;
 use merc_vpg::project_variability_parity_games_iter;
 use merc_vpg::read_fts;
 use merc_vpg::read_pg;
 use merc_vpg::read_vpg;
-use merc_vpg::solve_variability_product_zielonka;
+use merc_vpg::read_vpg_sat;
+use merc_vpg::solve_variability_product_zielonka_backend;
 use merc_vpg::solve_variability_zielonka;
 use merc_vpg::solve_zielonka;
 use merc_vpg::translate;
+use merc_vpg::verify_variability_solution;
 use merc_vpg::write_pg;
+use merc_vpg::write_pg_svg;
 use merc_vpg::write_vpg;
+use merc_vpg::write_vpg_svg;
 
 /// Default node capacity for the Oxidd decision diagram manager.
 const DEFAULT_OXIDD_NODE_CAPACITY: usize = 2024;
@@ -57,6 +81,12 @@ struct Cli {
     #[command(flatten)]
     verbosity: VerbosityFlag,
 
+    #[command(flatten)]
+    progress: ProgressFlag,
+
+    #[command(flatten)]
+    output_format: OutputFormatFlag,
+
     #[arg(long, global = true)]
     timings: bool,
 
@@ -76,10 +106,12 @@ struct Cli {
 #[derive(Debug, Subcommand)]
 enum Commands {
     Solve(SolveArgs),
+    Verify(VerifyArgs),
     Reachable(ReachableArgs),
     Project(ProjectArgs),
     Translate(TranslateArgs),
     Display(DisplayArgs),
+    GenerateCompletions(GenerateCompletionsArgs),
 }
 
 /// Arguments for solving a parity game
@@ -95,6 +127,10 @@ struct SolveArgs {
     #[arg(long)]
     solve_variant: Option<ZielonkaVariant>,
 
+    /// The backend used to reason about configurations, the SAT backend is only supported by the product-based variant.
+    #[arg(long)]
+    backend: Option<ConfigBackendType>,
+
     /// Whether to output the solution for every single vertex, not just in the initial vertex.
     #[arg(long, default_value_t = false)]
     full_solution: bool,
@@ -102,6 +138,23 @@ struct SolveArgs {
     /// Whether to verify the solution after computing it
     #[arg(long, default_value_t = false)]
     verify_solution: bool,
+
+    /// Writes the solution for every vertex of a variability parity game to the given JSON file
+    #[arg(long)]
+    solution: Option<String>,
+}
+
+/// Arguments for verifying the solution of a variability parity game
+#[derive(clap::Args, Debug)]
+struct VerifyArgs {
+    filename: String,
+
+    /// The solution to verify, as written by `solve --solution`
+    solution: String,
+
+    /// The method used to verify the solution, defaults to symbolic
+    #[arg(long)]
+    method: Option<VerificationMethod>,
 }
 
 /// Arguments for computing the reachable part of a parity game
@@ -126,6 +179,11 @@ struct ProjectArgs {
     #[arg(long, short, default_value_t = false)]
     reachable: bool,
 
+    /// Projects onto the given partial configuration, for example `1-0`, and writes
+    /// the resulting variability parity game instead of every product.
+    #[arg(long)]
+    partial: Option<String>,
+
     #[arg(long, short)]
     format: Option<ParityGameFormat>,
 }
@@ -144,6 +202,10 @@ struct TranslateArgs {
 
     /// The variability parity game output filename
     output: String,
+
+    /// Feature transition systems that are composed in parallel with the first one, synchronizing on common actions
+    #[arg(long)]
+    compose: Vec<String>,
 }
 
 /// Arguments for displaying a (variability) parity game
@@ -151,42 +213,53 @@ struct TranslateArgs {
 struct DisplayArgs {
     filename: String,
 
-    /// The .dot file output filename
+    /// The output filename, which is an SVG image when it has the .svg extension and a Graphviz .dot file otherwise
     output: String,
 
     /// The parity game file format
     #[arg(long, short)]
     format: Option<ParityGameFormat>,
+
+    /// The maximum number of vertices that is rendered as SVG
+    #[arg(long, default_value_t = DEFAULT_SVG_MAX_NODES)]
+    max_vertices: usize,
 }
 
 fn main() -> Result<ExitCode, MercError> {
-    let cli = Cli::parse();
+    let cli: Cli = parse_with_config();
 
     let mut timing = Timing::new();
 
-    env_logger::Builder::new()
-        .filter_level(cli.verbosity.log_level_filter())
-        .parse_default_env()
-        .init();
+    init_logger(cli.verbosity.log_level_filter());
+    cli.progress.install()?;
 
     if cli.version.into() {
         eprintln!("{}", Version);
         return Ok(ExitCode::SUCCESS);
     }
 
+    register_timing_memory_probe();
+
+    install_interrupt_handler()?;
+
+    let mut report = Report::new("merc-vpg", cli.output_format.format());
+
     if let Some(command) = &cli.commands {
         match command {
-            Commands::Solve(args) => handle_solve(&cli, args, &mut timing)?,
-            Commands::Reachable(args) => handle_reachable(&cli, args, &mut timing)?,
-            Commands::Project(args) => handle_project(&cli, args, &mut timing)?,
+            Commands::Solve(args) => handle_solve(&cli, args, &mut report, &mut timing)?,
+            Commands::Verify(args) => handle_verify(&cli, args, &mut report, &mut timing)?,
+            Commands::Reachable(args) => handle_reachable(&cli, args, &mut report, &mut timing)?,
+            Commands::Project(args) => handle_project(&cli, args, &mut report, &mut timing)?,
             Commands::Translate(args) => handle_translate(&cli, args)?,
             Commands::Display(args) => handle_display(&cli, args, &mut timing)?,
+            Commands::GenerateCompletions(args) => {
+                generate_completions::<Cli>(args)?;
+                return Ok(ExitCode::SUCCESS);
+            }
         }
     }
 
-    if cli.timings {
-        timing.print();
-    }
+    report.finish(&timing, cli.timings)?;
 
     print_allocator_metrics();
     if cfg!(feature = "merc_metrics") {
@@ -200,7 +273,7 @@ fn main() -> Result<ExitCode, MercError> {
 /// Reads either a standard parity game (PG) or a variability parity game (VPG)
 /// based on the provided format or filename extension, then solves it using
 /// Zielonka's algorithm.
-fn handle_solve(cli: &Cli, args: &SolveArgs, timing: &mut Timing) -> Result<(), MercError> {
+fn handle_solve(cli: &Cli, args: &SolveArgs, report: &mut Report, timing: &mut Timing) -> Result<(), MercError> {
     let path = Path::new(&args.filename);
     let mut file = File::open(path)?;
     let format = guess_format_from_extension(path, args.format).ok_or("Unknown parity game file format.")?;
@@ -212,21 +285,49 @@ fn handle_solve(cli: &Cli, args: &SolveArgs, timing: &mut Timing) -> Result<(),
         time_read.finish();
 
         let mut time_solve = timing.start("solve_zielonka");
-        let solution = solve_zielonka(&game);
+        let solution = solve_zielonka(&game)?;
         if args.full_solution {
             for (index, player_set) in solution.iter().enumerate() {
-                println!("W{index}: {}", player_set.iter_ones().format(", "));
+                report.text(format_args!("W{index}: {}", player_set.iter_ones().format(", ")));
+                report.set(&format!("W{index}"), player_set.iter_ones().collect::<Vec<_>>());
             }
-        } else if solution[0][0] {
-            println!("{}", Player::Even.solution())
         } else {
-            println!("{}", Player::Odd.solution())
+            let winner = if solution[0][0] { Player::Even } else { Player::Odd };
+            report.text(winner.solution());
+            report.set("winner", winner.to_string());
         }
         time_solve.finish();
     } else {
         let solve_variant = args
             .solve_variant
             .ok_or("For variability parity game solving a solving strategy should be selected")?;
+        let backend = args.backend.unwrap_or(ConfigBackendType::Bdd);
+        if backend == ConfigBackendType::Sat && solve_variant != ZielonkaVariant::Product {
+            return Err("The SAT backend is only supported by the product-based solving strategy".into());
+        }
+
+        if backend == ConfigBackendType::Sat {
+            // The configurations are read as formulas, so no BDDs are constructed.
+            let mut time_read = timing.start("read_vpg");
+            let (backend, game) = read_vpg_sat(&mut file)?;
+            time_read.finish();
+
+            let game = if !game.is_total_backend(&backend)? {
+                info!("Making the VPG total...");
+                make_vpg_total_backend(&backend, &game)?
+            } else {
+                game
+            };
+
+            let mut time_solve = timing.start("solve_variability_zielonka");
+            report_product_solutions(
+                args,
+                report,
+                solve_variability_product_zielonka_backend(&game, &backend, timing),
+            )?;
+            time_solve.finish();
+            return Ok(());
+        }
 
         // Read and solve a variability parity game.
         let manager_ref = oxidd::bdd::new_manager(
@@ -248,48 +349,38 @@ fn handle_solve(cli: &Cli, args: &SolveArgs, timing: &mut Timing) -> Result<(),
 
         let mut time_solve = timing.start("solve_variability_zielonka");
         if solve_variant == ZielonkaVariant::Product {
-            // Since we want to print W0, W1 separately, we need to store the results temporarily.
-            let mut results = [Vec::new(), Vec::new()];
-            for result in solve_variability_product_zielonka(&game, timing) {
-                let (cube, _bdd, solution) = result?;
-
-                for (index, w) in solution.iter().enumerate() {
-                    results[index].push((cube.clone(), w.clone()));
-                }
-            }
-
-            for (index, w) in results.iter().enumerate() {
-                println!("W{index}: ");
-
-                for (cube, vertices) in w {
-                    println!(
-                        "For product {} the following vertices are in: {}",
-                        FormatConfig(cube),
-                        vertices
-                            .iter_ones()
-                            .filter(|v| if args.full_solution { true } else { *v == 0 })
-                            .format(", ")
-                    );
-                }
-            }
+            let backend = BddBackend::new(&manager_ref, game.variables());
+            report_product_solutions(
+                args,
+                report,
+                solve_variability_product_zielonka_backend(&game, &backend, timing),
+            )?;
         } else {
             let solutions = solve_variability_zielonka(&manager_ref, &game, solve_variant, false)?;
-            for (index, w) in solutions.iter().enumerate() {
-                println!("W{index}: ");
+            let mut solution =
+                VariabilityParityGameSolution::from_submaps(&solutions, game.variables(), game.configuration())?;
+            if let Some(output) = &args.solution {
+                // Written before the solution is restricted to the initial vertex.
+                serde_json::to_writer_pretty(File::create(output)?, &solution)?;
+            }
+
+            for (index, products) in solution.winning.iter_mut().enumerate() {
+                report.text(format_args!("W{index}: "));
 
-                for entry in CubeIterAll::new(game.variables(), game.configuration()) {
-                    let (config, config_function) = entry?;
+                for product in products.iter_mut() {
+                    if !args.full_solution {
+                        // Only report the initial vertex.
+                        product.vertices.retain(|v| *v == 0);
+                    }
 
-                    println!(
+                    report.text(format_args!(
                         "For product {} the following vertices are in: {}",
-                        FormatConfig(&config),
-                        w.iter() // Do not use iter_vertices because the first one is the initial vertex only
-                            .take(if args.full_solution { usize::MAX } else { 1 }) // Take only first if we don't want full solution
-                            .filter(|(_v, config)| { config.and(&config_function).unwrap().satisfiable() })
-                            .map(|(v, _)| v)
-                            .format(", ")
-                    );
+                        product.product,
+                        product.vertices.iter().format(", ")
+                    ));
                 }
+
+                report.set(&format!("W{index}"), serde_json::to_value(&*products)?);
             }
 
             if args.verify_solution {
@@ -302,11 +393,91 @@ fn handle_solve(cli: &Cli, args: &SolveArgs, timing: &mut Timing) -> Result<(),
     Ok(())
 }
 
+/// Reports the solutions of the product-based solver for every product.
+fn report_product_solutions(
+    args: &SolveArgs,
+    report: &mut Report,
+    solutions: impl Iterator<Item = Result<(Vec<OptBool>, [Set; 2]), MercError>>,
+) -> Result<(), MercError> {
+    // Since we want to print W0, W1 separately, we need to store the results temporarily.
+    let mut results = [Vec::new(), Vec::new()];
+    for solution in solutions {
+        let (cube, solution) = solution?;
+        for (index, w) in solution.iter().enumerate() {
+            results[index].push((cube.clone(), w.clone()));
+        }
+    }
+
+    for (index, w) in results.iter().enumerate() {
+        report.text(format_args!("W{index}: "));
+
+        let mut products = Vec::new();
+        for (cube, vertices) in w {
+            let vertices: Vec<_> = vertices
+                .iter_ones()
+                .filter(|v| if args.full_solution { true } else { *v == 0 })
+                .collect();
+
+            report.text(format_args!(
+                "For product {} the following vertices are in: {}",
+                FormatConfig(cube),
+                vertices.iter().format(", ")
+            ));
+            products.push(ProductSolution {
+                product: FormatConfig(cube).to_string(),
+                vertices,
+            });
+        }
+
+        report.set(&format!("W{index}"), serde_json::to_value(products)?);
+    }
+
+    Ok(())
+}
+
+/// Handle the `verify` subcommand.
+///
+/// Reads a variability parity game and a solution written by `solve
+/// --solution`, and checks that the solution is correct for every
+/// configuration of the game.
+fn handle_verify(cli: &Cli, args: &VerifyArgs, report: &mut Report, timing: &mut Timing) -> Result<(), MercError> {
+    let manager_ref = oxidd::bdd::new_manager(
+        cli.oxidd_node_capacity,
+        cli.oxidd_cache_capacity.unwrap_or(cli.oxidd_node_capacity),
+        cli.oxidd_workers,
+    );
+
+    let mut time_read = timing.start("read_vpg");
+    let game = read_vpg(&manager_ref, &mut File::open(&args.filename)?)?;
+    let solution: VariabilityParityGameSolution = serde_json::from_str(&read_to_string(&args.solution)?)?;
+    time_read.finish();
+
+    // The solver makes the game total, so the solution is for the total game.
+    let game = if !game.is_total(&manager_ref)? {
+        info!("Making the VPG total...");
+        make_vpg_total(&manager_ref, &game)?
+    } else {
+        game
+    };
+
+    let submaps = solution.to_submaps(&manager_ref, game.variables(), game.num_of_vertices())?;
+    verify_variability_solution(&manager_ref, &game, &submaps, args.method.unwrap_or_default(), timing)?;
+
+    report.text("The solution is correct");
+    report.set("correct", true);
+    Ok(())
+}
+
 /// Handle the `reachable` subcommand.
 ///
 /// Reads a PG or VPG, computes its reachable part, and writes it to `output`.
 /// Also logs the vertex index mapping to aid inspection.
-fn handle_reachable(cli: &Cli, args: &ReachableArgs, timing: &mut Timing) -> Result<(), MercError> {
+fn handle_reachable(
+    cli: &Cli,
+    args: &ReachableArgs,
+    report: &mut Report,
+    timing: &mut Timing,
+) -> Result<(), MercError> {
     let path = Path::new(&args.filename);
     let mut file = File::open(path)?;
 
@@ -322,6 +493,9 @@ fn handle_reachable(cli: &Cli, args: &ReachableArgs, timing: &mut Timing) -> Res
             let (reachable_game, mapping) = compute_reachable(&game);
             time_reachable.finish();
 
+            report.set("vertices", game.num_of_vertices());
+            report.set("reachable_vertices", reachable_game.num_of_vertices());
+
             for (old_index, new_index) in mapping.iter().enumerate() {
                 debug!("{} -> {:?}", old_index, new_index);
             }
@@ -344,6 +518,9 @@ fn handle_reachable(cli: &Cli, args: &ReachableArgs, timing: &mut Timing) -> Res
             let (reachable_game, mapping) = compute_reachable(&game);
             time_reachable.finish();
 
+            report.set("vertices", game.num_of_vertices());
+            report.set("reachable_vertices", reachable_game.num_of_vertices());
+
             for (old_index, new_index) in mapping.iter().enumerate() {
                 debug!("{} -> {:?}", old_index, new_index);
             }
@@ -358,7 +535,7 @@ fn handle_reachable(cli: &Cli, args: &ReachableArgs, timing: &mut Timing) -> Res
 }
 
 /// Compute all the projects of a variability parity game and write them to output.
-fn handle_project(cli: &Cli, args: &ProjectArgs, timing: &mut Timing) -> Result<(), MercError> {
+fn handle_project(cli: &Cli, args: &ProjectArgs, report: &mut Report, timing: &mut Timing) -> Result<(), MercError> {
     let path = Path::new(&args.filename);
     let mut file = File::open(path)?;
     let format = guess_format_from_extension(path, args.format).ok_or("Unknown parity game file format.")?;
@@ -382,6 +559,19 @@ fn handle_project(cli: &Cli, args: &ProjectArgs, timing: &mut Timing) -> Result<
 
     let output_path = Path::new(&args.output);
 
+    if let Some(partial) = &args.partial {
+        let partial_configuration = parse_configuration_set(&manager_ref, vpg.variables(), partial)?;
+
+        let mut time_project = timing.start("project");
+        let projection = project_variability_parity_game_partial(&manager_ref, &vpg, &partial_configuration)?;
+        time_project.finish();
+
+        let mut output_file = File::create(output_path)?;
+        write_vpg(&mut output_file, &projection)?;
+        return Ok(());
+    }
+
+    let mut num_of_projections = 0;
     for result in project_variability_parity_games_iter(&vpg, timing) {
         let ((cube, _bdd, pg), _) = result?;
 
@@ -405,8 +595,11 @@ fn handle_project(cli: &Cli, args: &ProjectArgs, timing: &mut Timing) -> Result<
         } else {
             write_pg(&mut output_file, &pg)?;
         }
+
+        num_of_projections += 1;
     }
 
+    report.set("projections", num_of_projections);
     Ok(())
 }
 
@@ -430,14 +623,23 @@ fn handle_translate(cli: &Cli, args: &TranslateArgs) -> Result<(), MercError> {
     })?;
     let feature_diagram = FeatureDiagram::from_reader(&manager_ref, &mut feature_diagram_file)?;
 
-    // Read FTS
-    let mut fts_file = File::open(&args.fts_filename).map_err(|e| {
-        MercError::from(format!(
-            "Could not open feature transition system file '{}': {}",
-            &args.fts_filename, e
-        ))
-    })?;
-    let fts = read_fts(&manager_ref, &mut fts_file, feature_diagram.features().clone())?;
+    // Read the FTS and compose it with the other components
+    let mut fts = None;
+    for fts_filename in std::iter::once(&args.fts_filename).chain(args.compose.iter()) {
+        let mut fts_file = File::open(fts_filename).map_err(|e| {
+            MercError::from(format!(
+                "Could not open feature transition system file '{}': {}",
+                fts_filename, e
+            ))
+        })?;
+        let component = read_fts(&manager_ref, &mut fts_file, feature_diagram.features().clone())?;
+
+        fts = Some(match fts {
+            None => component,
+            Some(fts) => fts.parallel_composition(&manager_ref, &component, None)?,
+        });
+    }
+    let fts = fts.expect("At least one feature transition system is read");
 
     // Read and validate formula (no actions/data specs supported here)
     let formula_spec = UntypedStateFrmSpec::parse(&read_to_string(&args.formula_filename).map_err(|e| {
@@ -468,12 +670,15 @@ fn handle_translate(cli: &Cli, args: &TranslateArgs) -> Result<(), MercError> {
 
 /// Handle the `display` subcommand.
 ///
-/// Reads a PG or VPG and writes a Graphviz `.dot` representation to `output`.
-/// If the `dot` tool is available, also generates a PDF (`output.pdf`).
+/// Reads a PG or VPG and writes an SVG image to `output` when it has the `.svg`
+/// extension, using the built-in layout. Otherwise, writes a Graphviz `.dot`
+/// representation to `output` and, if the `dot` tool is available, also
+/// generates a PDF (`output.pdf`).
 fn handle_display(cli: &Cli, args: &DisplayArgs, timing: &mut Timing) -> Result<(), MercError> {
     let path = Path::new(&args.filename);
     let mut file = File::open(path)?;
     let format = guess_format_from_extension(path, args.format).ok_or("Unknown parity game file format.")?;
+    let svg = Path::new(&args.output).extension() == Some(OsStr::new("svg"));
 
     if format == ParityGameFormat::PG {
         // Read and display a standard parity game.
@@ -482,7 +687,11 @@ fn handle_display(cli: &Cli, args: &DisplayArgs, timing: &mut Timing) -> Result<
         time_read.finish();
 
         let mut output_file = File::create(&args.output)?;
-        write!(&mut output_file, "{}", PgDot::new(&game))?;
+        if svg {
+            write_pg_svg(&mut output_file, &game, args.max_vertices)?;
+        } else {
+            write!(&mut output_file, "{}", PgDot::new(&game))?;
+        }
     } else {
         // Read and display a variability parity game.
         let manager_ref = oxidd::bdd::new_manager(
@@ -496,7 +705,15 @@ fn handle_display(cli: &Cli, args: &DisplayArgs, timing: &mut Timing) -> Result<
         time_read.finish();
 
         let mut output_file = File::create(&args.output)?;
-        write!(&mut output_file, "{}", VpgDot::new(&game))?;
+        if svg {
+            write_vpg_svg(&mut output_file, &game, args.max_vertices)?;
+        } else {
+            write!(&mut output_file, "{}", VpgDot::new(&game))?;
+        }
+    }
+
+    if svg {
+        return Ok(());
     }
 
     if let Ok(dot_path) = which::which("dot") {
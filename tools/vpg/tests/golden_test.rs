@@ -0,0 +1,29 @@
+use std::path::Path;
+
+use merc_tools::check_golden;
+use test_case::test_case;
+
+/// Runs merc-vpg from the repository root and compares the output to the golden file in `tests/golden`.
+fn golden_test(args: &[&str], golden: &str) {
+    let manifest = Path::new(env!("CARGO_MANIFEST_DIR"));
+    check_golden(
+        Path::new(env!("CARGO_BIN_EXE_merc-vpg")),
+        &manifest.join("../.."),
+        args,
+        &manifest.join("tests/golden").join(golden),
+    )
+    .unwrap();
+}
+
+#[cfg_attr(miri, ignore)]
+#[test_case(&["solve", "examples/vpg/example.pg"], "solve_example_pg.txt" ; "solve pg")]
+#[test_case(&["solve", "--full-solution", "examples/vpg/example.pg"], "solve_full_example_pg.txt" ; "solve full pg")]
+#[test_case(&["--output-format", "json", "solve", "examples/vpg/example.pg"], "solve_example_pg.json" ; "solve json pg")]
+#[test_case(&["solve", "--solve-variant", "product", "examples/vpg/example.vpg"], "solve_product_example_vpg.txt" ; "solve product vpg")]
+#[test_case(&["solve", "--solve-variant", "family", "examples/vpg/example.vpg"], "solve_family_example_vpg.txt" ; "solve family vpg")]
+#[test_case(&["solve", "--solve-variant", "family-optimised-left", "--full-solution", "examples/vpg/example.vpg"], "solve_family_left_full_example_vpg.txt" ; "solve family left full vpg")]
+#[test_case(&["reachable", "examples/vpg/example.pg", "{output}.pg"], "reachable_example_pg.txt" ; "reachable pg")]
+#[test_case(&["translate", "examples/vpg/running_example_fts.fd", "examples/vpg/running_example_fts.aut", "examples/vpg/running_example.mcf", "{output}.vpg"], "translate_running_example.txt" ; "translate running example")]
+fn test_merc_vpg(args: &[&str], golden: &str) {
+    golden_test(args, golden);
+}
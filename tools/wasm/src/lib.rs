@@ -0,0 +1,81 @@
+//! WebAssembly bindings for the explicit LTS reduction and parity game solving
+//! algorithms, built using [wasm-bindgen](https://rustwasm.github.io/wasm-bindgen/).
+//!
+//! All functions take and return the textual file formats, so that they can
+//! easily be used from JavaScript without a file system.
+
+#![forbid(unsafe_code)]
+
+use clap::ValueEnum;
+use wasm_bindgen::prelude::*;
+
+use merc_lts::LTS;
+use merc_lts::LabelledTransitionSystem;
+use merc_reduction::Equivalence;
+use merc_reduction::reduce_lts;
+use merc_utilities::MercError;
+use merc_utilities::Timing;
+use merc_vpg::ParityGameSolution;
+use merc_vpg::read_pg;
+use merc_vpg::solve_zielonka;
+
+/// A labelled transition system with string labels.
+#[wasm_bindgen]
+pub struct Lts {
+    lts: LabelledTransitionSystem<String>,
+}
+
+#[wasm_bindgen]
+impl Lts {
+    /// The number of states.
+    #[wasm_bindgen(getter)]
+    pub fn num_of_states(&self) -> usize {
+        self.lts.num_of_states()
+    }
+
+    /// The number of transitions.
+    #[wasm_bindgen(getter)]
+    pub fn num_of_transitions(&self) -> usize {
+        self.lts.num_of_transitions()
+    }
+}
+
+/// Parses an LTS in the Aldebaran (.aut) format, where the given labels are considered to be internal actions.
+#[wasm_bindgen]
+pub fn read_aut(text: &str, tau: Vec<String>) -> Result<Lts, JsError> {
+    let lts = merc_lts::read_aut(text.as_bytes(), tau).map_err(to_js_error)?;
+    Ok(Lts { lts })
+}
+
+/// Reduces the given LTS modulo the given equivalence, using the same names as the `merc-lts` tool, e.g., `branching-bisim`.
+#[wasm_bindgen]
+pub fn reduce(lts: &Lts, equivalence: &str) -> Result<Lts, JsError> {
+    let equivalence = Equivalence::from_str(equivalence, true).map_err(|error| JsError::new(&error))?;
+
+    let lts = reduce_lts(lts.lts.clone(), equivalence, &mut Timing::new()).map_err(to_js_error)?;
+    Ok(Lts { lts })
+}
+
+/// Returns the given LTS in the Aldebaran (.aut) format.
+#[wasm_bindgen]
+pub fn write_aut(lts: &Lts) -> Result<String, JsError> {
+    let mut output = Vec::new();
+    merc_lts::write_aut(&mut output, &lts.lts).map_err(to_js_error)?;
+    Ok(String::from_utf8_lossy(&output).into_owned())
+}
+
+/// Solves the parity game given in the PGSolver (.pg) format, and returns the
+/// vertices won by player even.
+#[wasm_bindgen]
+pub fn solve_parity_game(text: &str) -> Result<Vec<usize>, JsError> {
+    let game = read_pg(text.as_bytes()).map_err(to_js_error)?;
+    let solution = ParityGameSolution::from_sets(&solve_zielonka(&game).map_err(to_js_error)?);
+
+    let [even, _odd] = solution.winning;
+    Ok(even)
+}
+
+/// Converts a [MercError] into a JavaScript error.
+fn to_js_error(error: MercError) -> JsError {
+    JsError::new(error.to_string().trim_end())
+}